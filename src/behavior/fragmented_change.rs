@@ -0,0 +1,191 @@
+use crate::messages::data_frag::DataFrag;
+use crate::messages::fragment_number::FragmentNumber_t;
+use crate::structure::change_kind::ChangeKind_t;
+use crate::structure::instance_handle::InstanceHandle_t;
+use bytes::{Bytes, BytesMut};
+use std::collections::BTreeSet;
+
+/// Change received from a remote Writer as a series of DataFrag Submessages,
+/// which is not complete yet.
+pub struct FragmentedChange {
+    data_size: u32,
+    fragment_size: u16,
+    value: BytesMut,
+    received_fragments: BTreeSet<FragmentNumber_t>,
+    kind: ChangeKind_t,
+    instance_handle: InstanceHandle_t,
+}
+
+impl FragmentedChange {
+    pub fn new(data_size: u32, fragment_size: u16) -> Self {
+        FragmentedChange {
+            data_size,
+            fragment_size,
+            value: BytesMut::zeroed(data_size as usize),
+            received_fragments: BTreeSet::new(),
+            kind: ChangeKind_t::ALIVE,
+            instance_handle: InstanceHandle_t::HANDLE_NIL,
+        }
+    }
+
+    pub fn fragments_count(&self) -> u32 {
+        DataFrag::fragments_count(self.data_size, self.fragment_size)
+    }
+
+    /// Copies the fragments carried by the DataFrag Submessage. Fragments
+    /// which does not match the size of the change are ignored. The inline
+    /// QoS, usually sent with the first fragment only, gives the kind and
+    /// the instance of the change.
+    pub fn insert(&mut self, data_frag: &DataFrag) {
+        if data_frag.data_size != self.data_size || data_frag.fragment_size != self.fragment_size {
+            return;
+        }
+        if data_frag.inline_qos.is_some() {
+            self.kind = data_frag.change_kind();
+            self.instance_handle = data_frag.key_hash();
+        }
+
+        let fragment_size = usize::from(self.fragment_size);
+        let payload = &data_frag.serialized_payload.value;
+        let first_fragment = u32::from(data_frag.fragment_starting_num);
+        let fragments = first_fragment
+            ..first_fragment.saturating_add(u32::from(data_frag.fragments_in_submessage));
+
+        for (index, fragment) in fragments.enumerate() {
+            if fragment == 0 || fragment > self.fragments_count() {
+                break;
+            }
+            let start = (fragment as usize - 1) * fragment_size;
+            let end = std::cmp::min(start + fragment_size, self.value.len());
+            let payload_start = index * fragment_size;
+            let payload_end = payload_start + (end - start);
+            if payload_end > payload.len() {
+                break;
+            }
+
+            self.value[start..end].copy_from_slice(&payload[payload_start..payload_end]);
+            self.received_fragments
+                .insert(FragmentNumber_t::from(fragment));
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.received_fragments.len() as u32 == self.fragments_count()
+    }
+
+    /// Fragments up to last_fragment_num which were not received yet.
+    pub fn missing_fragments(
+        &self,
+        last_fragment_num: FragmentNumber_t,
+    ) -> impl Iterator<Item = FragmentNumber_t> + '_ {
+        let last_fragment_num = std::cmp::min(u32::from(last_fragment_num), self.fragments_count());
+        (1..=last_fragment_num)
+            .map(FragmentNumber_t::from)
+            .filter(move |fragment| !self.received_fragments.contains(fragment))
+    }
+
+    pub fn kind(&self) -> ChangeKind_t {
+        self.kind
+    }
+
+    pub fn instance_handle(&self) -> InstanceHandle_t {
+        self.instance_handle
+    }
+
+    pub fn into_value(self) -> Bytes {
+        self.value.freeze()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::submessage_elements::parameter::Parameter;
+    use crate::messages::submessage_elements::parameter_list::ParameterList;
+    use crate::messages::submessage_elements::serialized_payload::SerializedPayload;
+    use crate::structure::entity_id::EntityId_t;
+    use crate::structure::parameter_id::ParameterId;
+    use crate::structure::sequence_number::SequenceNumber_t;
+
+    fn data_frag(fragment_starting_num: u32, payload: &'static [u8]) -> DataFrag {
+        DataFrag {
+            reader_id: EntityId_t::ENTITYID_UNKNOWN,
+            writer_id: EntityId_t::ENTITYID_UNKNOWN,
+            writer_sn: SequenceNumber_t::from(1),
+            fragment_starting_num: FragmentNumber_t::from(fragment_starting_num),
+            fragments_in_submessage: (payload.len() as u16).div_ceil(4),
+            data_size: 10,
+            fragment_size: 4,
+            inline_qos: None,
+            serialized_payload: SerializedPayload::from(Bytes::from_static(payload)),
+        }
+    }
+
+    #[test]
+    fn fragments_received_out_of_order_are_assembled() {
+        let mut fragmented_change = FragmentedChange::new(10, 4);
+
+        fragmented_change.insert(&data_frag(3, b"89"));
+        assert!(!fragmented_change.is_complete());
+        fragmented_change.insert(&data_frag(1, b"01234567"));
+
+        assert!(fragmented_change.is_complete());
+        assert_eq!(
+            Bytes::from_static(b"0123456789"),
+            fragmented_change.into_value()
+        );
+    }
+
+    #[test]
+    fn kind_and_instance_are_read_from_inline_qos() {
+        let mut fragmented_change = FragmentedChange::new(10, 4);
+        let mut first = data_frag(1, b"0123");
+        first.inline_qos = Some(ParameterList {
+            parameters: vec![Parameter::new(ParameterId::PID_KEY_HASH, vec![0x0F; 16])],
+        });
+
+        fragmented_change.insert(&first);
+        fragmented_change.insert(&data_frag(2, b"456789"));
+
+        assert_eq!(ChangeKind_t::ALIVE, fragmented_change.kind());
+        assert_eq!(
+            InstanceHandle_t {
+                entity_key: [0x0F; 16]
+            },
+            fragmented_change.instance_handle()
+        );
+    }
+
+    #[test]
+    fn missing_fragments_are_limited_by_last_fragment_num() {
+        let mut fragmented_change = FragmentedChange::new(10, 4);
+        fragmented_change.insert(&data_frag(2, b"4567"));
+
+        assert_eq!(
+            vec![FragmentNumber_t::from(1)],
+            fragmented_change
+                .missing_fragments(FragmentNumber_t::from(2))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![FragmentNumber_t::from(1), FragmentNumber_t::from(3)],
+            fragmented_change
+                .missing_fragments(FragmentNumber_t::from(7))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn fragment_with_different_data_size_is_ignored() {
+        let mut fragmented_change = FragmentedChange::new(12, 4);
+
+        fragmented_change.insert(&data_frag(1, b"0123"));
+
+        assert_eq!(
+            3,
+            fragmented_change
+                .missing_fragments(FragmentNumber_t::from(3))
+                .count()
+        );
+    }
+}
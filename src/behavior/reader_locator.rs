@@ -1,5 +1,3 @@
-use crate::behavior::change_for_reader::ChangeForReader;
-use crate::structure::cache_change::CacheChange;
 use crate::structure::locator::Locator_t;
 use crate::structure::sequence_number::SequenceNumber_t;
 
@@ -9,11 +7,11 @@ use crate::structure::sequence_number::SequenceNumber_t;
 pub struct ReaderLocator {
     /// A list of changes in the writer’s HistoryCache that
     /// were requested by remote Readers at this ReaderLocator
-    requested_changes: Vec<CacheChange>,
+    requested_changes: Vec<SequenceNumber_t>,
 
     /// A list of changes in the writer’s HistoryCache that
     /// have not been sent yet to this ReaderLocator
-    unsent_changes: Vec<CacheChange>,
+    unsent_changes: Vec<SequenceNumber_t>,
 
     /// Unicast or multicast locator through which the readers
     /// represented by this ReaderLocator can be reached
@@ -25,27 +23,106 @@ pub struct ReaderLocator {
 }
 
 impl ReaderLocator {
-    pub fn new(_locator: Locator_t, _expects_inline_qos: bool) -> Self {
-        unimplemented!();
+    pub fn new(locator: Locator_t, expects_inline_qos: bool) -> Self {
+        ReaderLocator {
+            requested_changes: vec![],
+            unsent_changes: vec![],
+            locator,
+            expects_inline_qos,
+        }
     }
 
-    pub fn next_requested_change() -> ChangeForReader {
-        unimplemented!();
+    pub fn locator(&self) -> Locator_t {
+        self.locator
     }
 
-    pub fn next_unset_change() -> ChangeForReader {
-        unimplemented!();
+    pub fn expects_inline_qos(&self) -> bool {
+        self.expects_inline_qos
     }
 
-    pub fn requested_changes() -> Vec<CacheChange> {
-        unimplemented!();
+    /// Removes and returns the lowest requested change.
+    pub fn next_requested_change(&mut self) -> Option<SequenceNumber_t> {
+        next_change(&mut self.requested_changes)
     }
 
-    pub fn requested_changes_set(_req_seq_num_set: &[SequenceNumber_t]) {
-        unimplemented!();
+    /// Removes and returns the lowest unsent change.
+    pub fn next_unsent_change(&mut self) -> Option<SequenceNumber_t> {
+        next_change(&mut self.unsent_changes)
     }
 
-    pub fn unset_changes() -> Vec<CacheChange> {
-        unimplemented!();
+    pub fn requested_changes(&self) -> &[SequenceNumber_t] {
+        &self.requested_changes
+    }
+
+    /// Adds the changes to the requested ones.
+    pub fn requested_changes_set(&mut self, req_seq_num_set: &[SequenceNumber_t]) {
+        add_changes(&mut self.requested_changes, req_seq_num_set)
+    }
+
+    pub fn unsent_changes(&self) -> &[SequenceNumber_t] {
+        &self.unsent_changes
+    }
+
+    /// Adds the changes to the unsent ones, e.g. the new changes of the
+    /// writer's HistoryCache.
+    pub fn unsent_changes_set(&mut self, seq_num_set: &[SequenceNumber_t]) {
+        add_changes(&mut self.unsent_changes, seq_num_set)
+    }
+}
+
+fn next_change(changes: &mut Vec<SequenceNumber_t>) -> Option<SequenceNumber_t> {
+    if changes.is_empty() {
+        None
+    } else {
+        Some(changes.remove(0))
+    }
+}
+
+/// Adds the sequence numbers keeping the changes sorted and unique.
+fn add_changes(changes: &mut Vec<SequenceNumber_t>, seq_num_set: &[SequenceNumber_t]) {
+    for seq_num in seq_num_set {
+        if let Err(index) = changes.binary_search(seq_num) {
+            changes.insert(index, *seq_num);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unsent_changes_are_returned_in_order_once() {
+        let mut reader_locator = ReaderLocator::new(Locator_t::default(), false);
+        reader_locator.unsent_changes_set(&[SequenceNumber_t::from(3), SequenceNumber_t::from(1)]);
+        reader_locator.unsent_changes_set(&[SequenceNumber_t::from(3), SequenceNumber_t::from(2)]);
+
+        assert_eq!(
+            Some(SequenceNumber_t::from(1)),
+            reader_locator.next_unsent_change()
+        );
+        assert_eq!(
+            Some(SequenceNumber_t::from(2)),
+            reader_locator.next_unsent_change()
+        );
+        assert_eq!(
+            Some(SequenceNumber_t::from(3)),
+            reader_locator.next_unsent_change()
+        );
+        assert_eq!(None, reader_locator.next_unsent_change());
+    }
+
+    #[test]
+    fn requested_changes_are_kept_apart_from_unsent() {
+        let mut reader_locator = ReaderLocator::new(Locator_t::default(), true);
+        reader_locator.requested_changes_set(&[SequenceNumber_t::from(5)]);
+
+        assert!(reader_locator.expects_inline_qos());
+        assert!(reader_locator.unsent_changes().is_empty());
+        assert_eq!(
+            Some(SequenceNumber_t::from(5)),
+            reader_locator.next_requested_change()
+        );
+        assert_eq!(None, reader_locator.next_requested_change());
     }
 }
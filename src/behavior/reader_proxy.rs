@@ -1,6 +1,17 @@
+use crate::behavior::change_for_reader::ChangeForReader;
+use crate::behavior::change_for_reader_status_kind::ChangeForReaderStatusKind;
+use crate::messages::fragment_number::FragmentNumber_t;
 use crate::structure::cache_change::CacheChange;
+use crate::structure::change_kind::ChangeKind_t;
+use crate::structure::count::Count_t;
 use crate::structure::guid::GUID_t;
+use crate::structure::locator::LocatorList_t;
 use crate::structure::sequence_number::SequenceNumber_t;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Decides whether a change is relevant to the remote Reader, e.g. by
+/// evaluating the content filter of the Reader.
+pub type ChangeFilter = Box<dyn Fn(&CacheChange) -> bool + Send>;
 
 /// The RTPS ReaderProxy class represents the information an RTPS StatefulWriter
 /// maintains on each matched RTPS.
@@ -9,6 +20,16 @@ pub struct ReaderProxy {
     /// ReaderProxy.
     pub remote_reader_guid: GUID_t,
 
+    /// List of unicast locators (transport, address, port combinations) that
+    /// can be used to send messages to the matched RTPS Reader. The list may
+    /// be empty.
+    pub unicast_locator_list: LocatorList_t,
+
+    /// List of multicast locators (transport, address, port combinations)
+    /// that can be used to send messages to the matched RTPS Reader. The list
+    /// may be empty.
+    pub multicast_locator_list: LocatorList_t,
+
     /// Specifies whether the remote matched RTPS Reader expects in-line QoS to
     /// be sent along with any data
     expects_inline_qos: bool,
@@ -17,18 +38,100 @@ pub struct ReaderProxy {
     is_active: bool,
 
     highest_seq_num_sent: SequenceNumber_t,
+
+    /// All changes up to this sequence number were acknowledged by the remote
+    /// Reader.
+    highest_seq_num_acked: SequenceNumber_t,
+
+    /// Changes that were requested by the remote Reader using AckNack
+    /// Submessages and were not re-sent yet.
+    requested_changes: BTreeSet<SequenceNumber_t>,
+
+    /// Count of the last AckNack Submessage received from the remote Reader.
+    /// Used to discard duplicated AckNack Submessages.
+    last_ack_nack_count: Option<Count_t>,
+
+    /// Fragments of changes that were requested by the remote Reader using
+    /// NackFrag Submessages and were not re-sent yet.
+    requested_fragments: BTreeMap<SequenceNumber_t, BTreeSet<FragmentNumber_t>>,
+
+    /// Count of the last NackFrag Submessage received from the remote Reader.
+    /// Used to discard duplicated NackFrag Submessages.
+    last_nack_frag_count: Option<Count_t>,
+
+    /// The filter applied on behalf of the remote Reader; the changes it
+    /// rejects are not relevant and sent as Gap.
+    content_filter: Option<ChangeFilter>,
+
+    /// Whether the remote Reader is reliable. A best-effort Reader does not
+    /// acknowledge the changes.
+    is_reliable: bool,
 }
 
 impl ReaderProxy {
-    pub fn new(remote_reader_guid: GUID_t, expects_inline_qos: bool) -> ReaderProxy {
+    pub fn new(
+        remote_reader_guid: GUID_t,
+        unicast_locator_list: LocatorList_t,
+        multicast_locator_list: LocatorList_t,
+        expects_inline_qos: bool,
+    ) -> ReaderProxy {
         ReaderProxy {
             remote_reader_guid,
+            unicast_locator_list,
+            multicast_locator_list,
             expects_inline_qos,
             is_active: true,
             highest_seq_num_sent: SequenceNumber_t::from(std::i64::MIN),
+            highest_seq_num_acked: SequenceNumber_t::from(0),
+            requested_changes: BTreeSet::new(),
+            last_ack_nack_count: None,
+            requested_fragments: BTreeMap::new(),
+            last_nack_frag_count: None,
+            content_filter: None,
+            is_reliable: true,
         }
     }
 
+    pub fn set_content_filter(&mut self, content_filter: Option<ChangeFilter>) {
+        self.content_filter = content_filter
+    }
+
+    pub fn set_reliable(&mut self, is_reliable: bool) {
+        self.is_reliable = is_reliable
+    }
+
+    pub fn is_reliable(&self) -> bool {
+        self.is_reliable
+    }
+
+    /// The status and relevance of the change for the remote Reader. The
+    /// changes which are not ALIVE carry only the key and are relevant to
+    /// any content filter.
+    pub fn change_for_reader(&self, change: &CacheChange) -> ChangeForReader {
+        let seq_num = change.sequence_number;
+        let status = if self.is_acked(seq_num) {
+            ChangeForReaderStatusKind::ACKNOWLEDGED
+        } else if self.requested_changes.contains(&seq_num) {
+            ChangeForReaderStatusKind::REQUESTED
+        } else if seq_num > self.highest_seq_num_sent {
+            ChangeForReaderStatusKind::UNSENT
+        } else {
+            ChangeForReaderStatusKind::UNACKNOWLEDGED
+        };
+        ChangeForReader {
+            status,
+            is_relevant: change.kind != ChangeKind_t::ALIVE
+                || self
+                    .content_filter
+                    .as_ref()
+                    .is_none_or(|content_filter| content_filter(change)),
+        }
+    }
+
+    pub fn expects_inline_qos(&self) -> bool {
+        self.expects_inline_qos
+    }
+
     pub fn next_unsent_change<'a>(
         &'a mut self,
         changes: &'a [CacheChange],
@@ -50,12 +153,77 @@ impl ReaderProxy {
             .iter()
             .filter(move |change| change.sequence_number > highest_seq_num_sent)
     }
+
+    /// Marks all changes up to committed_seq_num as acknowledged by the remote
+    /// Reader.
+    pub fn acked_changes_set(&mut self, committed_seq_num: SequenceNumber_t) {
+        if committed_seq_num > self.highest_seq_num_acked {
+            self.highest_seq_num_acked = committed_seq_num;
+        }
+    }
+
+    pub fn is_acked(&self, seq_num: SequenceNumber_t) -> bool {
+        seq_num <= self.highest_seq_num_acked
+    }
+
+    pub fn is_sent(&self, seq_num: SequenceNumber_t) -> bool {
+        seq_num <= self.highest_seq_num_sent
+    }
+
+    /// Marks the changes as requested by the remote Reader. Returns false if
+    /// an AckNack Submessage with the same or a newer count was already
+    /// processed.
+    pub fn requested_changes_set(
+        &mut self,
+        req_seq_num_set: impl Iterator<Item = SequenceNumber_t>,
+        count: Count_t,
+    ) -> bool {
+        if matches!(self.last_ack_nack_count, Some(last) if last >= count) {
+            return false;
+        }
+        self.last_ack_nack_count = Some(count);
+
+        self.requested_changes.extend(req_seq_num_set);
+        true
+    }
+
+    /// Removes and returns all changes requested by the remote Reader.
+    pub fn take_requested_changes(&mut self) -> BTreeSet<SequenceNumber_t> {
+        std::mem::take(&mut self.requested_changes)
+    }
+
+    /// Marks the fragments of the change identified by seq_num as requested
+    /// by the remote Reader. Returns false if a NackFrag Submessage with the
+    /// same or a newer count was already processed.
+    pub fn requested_fragments_set(
+        &mut self,
+        seq_num: SequenceNumber_t,
+        fragments: impl Iterator<Item = FragmentNumber_t>,
+        count: Count_t,
+    ) -> bool {
+        if matches!(self.last_nack_frag_count, Some(last) if last >= count) {
+            return false;
+        }
+        self.last_nack_frag_count = Some(count);
+
+        self.requested_fragments
+            .entry(seq_num)
+            .or_default()
+            .extend(fragments);
+        true
+    }
+
+    /// Removes and returns all fragments requested by the remote Reader.
+    pub fn take_requested_fragments(
+        &mut self,
+    ) -> BTreeMap<SequenceNumber_t, BTreeSet<FragmentNumber_t>> {
+        std::mem::take(&mut self.requested_fragments)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::structure::change_kind::ChangeKind_t;
     use crate::structure::data::Data;
     use crate::structure::instance_handle::InstanceHandle_t;
 
@@ -65,13 +233,14 @@ mod tests {
             writer_guid: GUID_t::GUID_UNKNOWN,
             instance_handle: InstanceHandle_t::default(),
             sequence_number: SequenceNumber_t::from(sequence_number),
-            data_value: Data {},
+            data_value: Data::default(),
+            source_timestamp: None,
         }
     }
 
     #[test]
     fn unsent_changes_returns_not_consumed_changes_by_default() {
-        let reader_proxy = ReaderProxy::new(GUID_t::GUID_UNKNOWN, true);
+        let reader_proxy = ReaderProxy::new(GUID_t::GUID_UNKNOWN, vec![], vec![], true);
 
         let changes = vec![
             default_cache_change(0),
@@ -88,7 +257,7 @@ mod tests {
 
     #[test]
     fn next_unsent_change_returns_cache_change_with_smallest_sequence_number() {
-        let mut reader_proxy = ReaderProxy::new(GUID_t::GUID_UNKNOWN, true);
+        let mut reader_proxy = ReaderProxy::new(GUID_t::GUID_UNKNOWN, vec![], vec![], true);
 
         let changes = vec![
             default_cache_change(6),
@@ -104,7 +273,7 @@ mod tests {
 
     #[test]
     fn next_unsent_change_once_requested_does_not_belong_to_unsent_changes() {
-        let mut reader_proxy = ReaderProxy::new(GUID_t::GUID_UNKNOWN, true);
+        let mut reader_proxy = ReaderProxy::new(GUID_t::GUID_UNKNOWN, vec![], vec![], true);
 
         let changes = vec![
             default_cache_change(6),
@@ -125,9 +294,121 @@ mod tests {
             .any(|change| change == &changes[1]));
     }
 
+    #[test]
+    fn change_for_reader_is_relevant_when_passing_filter() {
+        let mut reader_proxy = ReaderProxy::new(GUID_t::GUID_UNKNOWN, vec![], vec![], false);
+        let changes = vec![default_cache_change(1), default_cache_change(2)];
+        reader_proxy.next_unsent_change(&changes);
+        assert_eq!(
+            ChangeForReader {
+                status: ChangeForReaderStatusKind::UNACKNOWLEDGED,
+                is_relevant: true,
+            },
+            reader_proxy.change_for_reader(&changes[0])
+        );
+
+        reader_proxy.set_content_filter(Some(Box::new(|change: &CacheChange| {
+            change.sequence_number > SequenceNumber_t::from(1)
+        })));
+        reader_proxy.acked_changes_set(SequenceNumber_t::from(1));
+
+        assert_eq!(
+            ChangeForReader {
+                status: ChangeForReaderStatusKind::ACKNOWLEDGED,
+                is_relevant: false,
+            },
+            reader_proxy.change_for_reader(&changes[0])
+        );
+        assert_eq!(
+            ChangeForReader {
+                status: ChangeForReaderStatusKind::UNSENT,
+                is_relevant: true,
+            },
+            reader_proxy.change_for_reader(&changes[1])
+        );
+    }
+
+    #[test]
+    fn requested_fragments_are_merged_until_taken() {
+        let mut reader_proxy = ReaderProxy::new(GUID_t::GUID_UNKNOWN, vec![], vec![], false);
+        let fragments = |numbers: &[u32]| {
+            numbers
+                .iter()
+                .map(|number| FragmentNumber_t::from(*number))
+                .collect::<Vec<_>>()
+        };
+
+        assert!(reader_proxy.requested_fragments_set(
+            SequenceNumber_t::from(1),
+            fragments(&[1, 3]).into_iter(),
+            Count_t::from(1)
+        ));
+        assert!(reader_proxy.requested_fragments_set(
+            SequenceNumber_t::from(1),
+            fragments(&[2, 3]).into_iter(),
+            Count_t::from(2)
+        ));
+
+        let requested = reader_proxy.take_requested_fragments();
+        assert_eq!(1, requested.len());
+        assert_eq!(
+            fragments(&[1, 2, 3]),
+            requested[&SequenceNumber_t::from(1)]
+                .iter()
+                .cloned()
+                .collect::<Vec<_>>()
+        );
+        assert!(reader_proxy.take_requested_fragments().is_empty());
+    }
+
+    #[test]
+    fn duplicated_nack_frag_is_ignored() {
+        let mut reader_proxy = ReaderProxy::new(GUID_t::GUID_UNKNOWN, vec![], vec![], false);
+
+        assert!(reader_proxy.requested_fragments_set(
+            SequenceNumber_t::from(1),
+            vec![FragmentNumber_t::from(1)].into_iter(),
+            Count_t::from(5)
+        ));
+        assert!(!reader_proxy.requested_fragments_set(
+            SequenceNumber_t::from(1),
+            vec![FragmentNumber_t::from(2)].into_iter(),
+            Count_t::from(5)
+        ));
+
+        let requested = reader_proxy.take_requested_fragments();
+        assert_eq!(1, requested[&SequenceNumber_t::from(1)].len());
+    }
+
+    #[test]
+    fn requested_changes_are_taken_once_and_duplicates_ignored() {
+        let mut reader_proxy = ReaderProxy::new(GUID_t::GUID_UNKNOWN, vec![], vec![], false);
+        let seq_nums = |numbers: &[i64]| {
+            numbers
+                .iter()
+                .map(|number| SequenceNumber_t::from(*number))
+                .collect::<Vec<_>>()
+        };
+
+        assert!(reader_proxy.requested_changes_set(seq_nums(&[2, 4]).into_iter(), Count_t::from(1)));
+        assert!(!reader_proxy.requested_changes_set(seq_nums(&[5]).into_iter(), Count_t::from(1)));
+        reader_proxy.acked_changes_set(SequenceNumber_t::from(1));
+
+        assert!(reader_proxy.is_acked(SequenceNumber_t::from(1)));
+        assert!(!reader_proxy.is_acked(SequenceNumber_t::from(2)));
+        assert_eq!(
+            seq_nums(&[2, 4]),
+            reader_proxy
+                .take_requested_changes()
+                .into_iter()
+                .collect::<Vec<_>>()
+        );
+        assert!(reader_proxy.take_requested_changes().is_empty());
+    }
+
     #[test]
     fn unsent_changes_returns_only_changes_that_were_not_sent() {
-        let mut reader_proxy = ReaderProxy::new(GUID_t::GUID_UNKNOWN, true);
+        let mut reader_proxy = ReaderProxy::new(GUID_t::GUID_UNKNOWN, vec![], vec![], true);
 
         let mut changes = vec![
             default_cache_change(6),
@@ -0,0 +1,577 @@
+use crate::behavior::reader::{Reader, ReaderAttributes};
+use crate::behavior::writer_proxy::WriterProxy;
+use crate::messages::ack_nack::AckNack;
+use crate::messages::data::Data as DataSubmessage;
+use crate::messages::data_frag::DataFrag;
+use crate::messages::fragment_number_set::FragmentNumberSet_t;
+use crate::messages::gap::Gap;
+use crate::messages::heartbeat::Heartbeat;
+use crate::messages::heartbeat_frag::HeartbeatFrag;
+use crate::messages::nack_frag::NackFrag;
+use crate::messages::submessage::EntitySubmessage;
+use crate::messages::submessage_flag::SubmessageFlag;
+use crate::structure::cache_change::CacheChange;
+use crate::structure::count::Count_t;
+use crate::structure::data::Data;
+use crate::structure::endpoint::{Endpoint, EndpointAttributes};
+use crate::structure::entity::{Entity, EntityAttributes};
+use crate::structure::guid::GUID_t;
+use crate::structure::reliability_kind::ReliabilityKind_t;
+use crate::structure::sequence_number::SequenceNumber_t;
+use crate::structure::sequence_number_set::SequenceNumberSet_t;
+use crate::structure::time::Time_t;
+
+/// Flags of all Submessages created by the reader; they are always encoded in
+/// little endian
+const LITTLE_ENDIAN_FLAG: u8 = 0x01;
+
+/// Specialization of RTPS Reader used for the Stateful Reference
+/// Implementation. The RTPS StatefulReader keeps state on each matched RTPS
+/// Writer.
+pub struct StatefulReader {
+    /// Used to maintain state on the remote Writers matched up with the
+    /// Reader.
+    matched_writers: Vec<WriterProxy>,
+    entity: EntityAttributes,
+    endpoint: EndpointAttributes,
+    reader: ReaderAttributes,
+
+    /// Counter of the AckNack Submessages sent by this reader.
+    ack_nack_count: Count_t,
+
+    /// Counter of the NackFrag Submessages sent by this reader.
+    nack_frag_count: Count_t,
+
+    /// Submessages created in response to the received Submessages, which
+    /// were not sent yet.
+    pending_submessages: Vec<(GUID_t, EntitySubmessage)>,
+
+    /// The number of changes of the matched Writers that are not available
+    /// anymore and were never received, since the last take_lost_changes.
+    lost_changes: usize,
+
+    /// The maximum size of the serialized data of the changes received as
+    /// DataFrag Submessages; bigger changes are dropped.
+    max_sample_size: u32,
+}
+
+impl Entity for StatefulReader {
+    fn as_entity(&self) -> &EntityAttributes {
+        &self.entity
+    }
+}
+
+impl Endpoint for StatefulReader {
+    fn as_endpoint(&self) -> &EndpointAttributes {
+        &self.endpoint
+    }
+}
+
+impl Reader for StatefulReader {
+    fn as_reader(&self) -> &ReaderAttributes {
+        &self.reader
+    }
+
+    fn as_reader_mut(&mut self) -> &mut ReaderAttributes {
+        &mut self.reader
+    }
+}
+
+impl StatefulReader {
+    /// The default max_sample_size, see set_max_sample_size.
+    pub const DEFAULT_MAX_SAMPLE_SIZE: u32 = 16 * 1024 * 1024;
+
+    pub fn new(
+        entity: EntityAttributes,
+        endpoint: EndpointAttributes,
+        reader: ReaderAttributes,
+    ) -> Self {
+        StatefulReader {
+            matched_writers: vec![],
+            entity,
+            endpoint,
+            reader,
+            ack_nack_count: Count_t::from(0),
+            nack_frag_count: Count_t::from(0),
+            pending_submessages: vec![],
+            lost_changes: 0,
+            max_sample_size: StatefulReader::DEFAULT_MAX_SAMPLE_SIZE,
+        }
+    }
+
+    /// Limits the size of the changes received as DataFrag Submessages.
+    /// The size announced by a DataFrag is allocated as soon as its first
+    /// fragment arrives.
+    pub fn set_max_sample_size(&mut self, max_sample_size: u32) {
+        self.max_sample_size = max_sample_size
+    }
+
+    pub fn matched_writer_add(&mut self, a_writer_proxy: WriterProxy) {
+        self.matched_writers.push(a_writer_proxy)
+    }
+
+    pub fn matched_writer_remove(&mut self, a_writer_guid: GUID_t) {
+        self.matched_writers
+            .retain(|proxy| proxy.remote_writer_guid != a_writer_guid)
+    }
+
+    pub fn matched_writer_lookup(&self, a_writer_guid: GUID_t) -> Option<&WriterProxy> {
+        self.matched_writers
+            .iter()
+            .find(|proxy| proxy.remote_writer_guid == a_writer_guid)
+    }
+
+    fn is_reliable(&self) -> bool {
+        self.endpoint.reliability_level == ReliabilityKind_t::RELIABLE
+    }
+
+    /// Handles a Data Submessage sent by the matched Writer identified by
+    /// writer_guid, with the source_timestamp of the Message. Returns true if
+    /// a new change was added to the reader_cache.
+    pub fn process_data(
+        &mut self,
+        writer_guid: GUID_t,
+        data: &DataSubmessage,
+        source_timestamp: Option<Time_t>,
+    ) -> bool {
+        let value = data
+            .serialized_payload
+            .as_ref()
+            .map(|serialized_payload| serialized_payload.value.clone())
+            .unwrap_or_default();
+
+        self.change_received(CacheChange {
+            kind: data.change_kind(),
+            writer_guid,
+            instance_handle: data.key_hash(),
+            sequence_number: data.writer_sn,
+            data_value: Data::from(value),
+            source_timestamp,
+        })
+    }
+
+    /// Handles a DataFrag Submessage sent by the matched Writer identified by
+    /// writer_guid, see process_data. Returns true if the last missing
+    /// fragment was received and the change was added to the reader_cache.
+    pub fn process_data_frag(
+        &mut self,
+        writer_guid: GUID_t,
+        data_frag: &DataFrag,
+        source_timestamp: Option<Time_t>,
+    ) -> bool {
+        let max_sample_size = self.max_sample_size;
+        let fragmented_change = match self.writer_proxy_mut(writer_guid) {
+            Some(writer_proxy) => writer_proxy.data_frag_received(data_frag, max_sample_size),
+            None => return false,
+        };
+
+        match fragmented_change {
+            Some(fragmented_change) => self.change_received(CacheChange {
+                kind: fragmented_change.kind(),
+                writer_guid,
+                instance_handle: fragmented_change.instance_handle(),
+                sequence_number: data_frag.writer_sn,
+                data_value: Data::from(fragmented_change.into_value()),
+                source_timestamp,
+            }),
+            None => false,
+        }
+    }
+
+    /// Handles a Gap Submessage, marking the changes as irrelevant.
+    pub fn process_gap(&mut self, writer_guid: GUID_t, gap: &Gap) {
+        if let Some(writer_proxy) = self.writer_proxy_mut(writer_guid) {
+            let gap_end = gap.gap_list.base();
+            if gap.gap_start <= writer_proxy.available_changes_max() + SequenceNumber_t::from(1) {
+                writer_proxy.lost_changes_update(gap_end);
+            } else {
+                for seq_num in i64::from(gap.gap_start)..i64::from(gap_end) {
+                    writer_proxy.irrelevant_change_set(SequenceNumber_t::from(seq_num));
+                }
+            }
+            for seq_num in gap.gap_list.iter() {
+                writer_proxy.irrelevant_change_set(seq_num);
+            }
+        }
+    }
+
+    /// Handles a Heartbeat Submessage. The reliable reader responds with an
+    /// AckNack Submessage unless the Heartbeat is final and no changes are
+    /// missing.
+    pub fn process_heartbeat(
+        &mut self,
+        writer_guid: GUID_t,
+        heartbeat: &Heartbeat,
+        flags: SubmessageFlag,
+    ) {
+        if !self.is_reliable() {
+            return;
+        }
+        let reader_id = self.entity.guid.entity_id;
+
+        let writer_proxy = match self.writer_proxy_mut(writer_guid) {
+            Some(writer_proxy) => writer_proxy,
+            None => return,
+        };
+        if !writer_proxy.heartbeat_count_update(heartbeat.count) {
+            return;
+        }
+        writer_proxy.missing_changes_update(heartbeat.last_sn);
+        let lost_changes = writer_proxy.lost_changes_update(heartbeat.first_sn);
+
+        let mut reader_sn_state = SequenceNumberSet_t::new(
+            writer_proxy.available_changes_max() + SequenceNumber_t::from(1),
+        );
+        for seq_num in writer_proxy.missing_changes() {
+            if !reader_sn_state.insert(seq_num) {
+                break;
+            }
+        }
+        self.lost_changes += lost_changes;
+        let no_missing_changes = reader_sn_state.iter().next().is_none();
+        if flags.is_flag_set(Heartbeat::FINAL_FLAG) && no_missing_changes {
+            return;
+        }
+
+        self.ack_nack_count = Count_t::from(i32::from(self.ack_nack_count) + 1);
+        let mut flags = SubmessageFlag {
+            flags: LITTLE_ENDIAN_FLAG,
+        };
+        if no_missing_changes {
+            flags.set_flag(AckNack::FINAL_FLAG);
+        }
+
+        self.pending_submessages.push((
+            writer_guid,
+            EntitySubmessage::AckNack(
+                AckNack {
+                    reader_id,
+                    writer_id: writer_guid.entity_id,
+                    reader_sn_state,
+                    count: self.ack_nack_count,
+                },
+                flags,
+            ),
+        ));
+    }
+
+    /// Handles a HeartbeatFrag Submessage. The reliable reader responds with
+    /// a NackFrag Submessage if some of the announced fragments are missing.
+    pub fn process_heartbeat_frag(&mut self, writer_guid: GUID_t, heartbeat_frag: &HeartbeatFrag) {
+        if !self.is_reliable() {
+            return;
+        }
+        let reader_id = self.entity.guid.entity_id;
+
+        let writer_proxy = match self.writer_proxy_mut(writer_guid) {
+            Some(writer_proxy) => writer_proxy,
+            None => return,
+        };
+        if !writer_proxy.heartbeat_frag_count_update(heartbeat_frag.count) {
+            return;
+        }
+        let missing_fragments = writer_proxy
+            .missing_fragments(heartbeat_frag.writer_sn, heartbeat_frag.last_fragment_num);
+
+        let base = match missing_fragments.first() {
+            Some(base) => *base,
+            None => return,
+        };
+        let mut fragment_number_state = FragmentNumberSet_t::new(base);
+        for fragment in missing_fragments {
+            if !fragment_number_state.insert(fragment) {
+                break;
+            }
+        }
+
+        self.nack_frag_count = Count_t::from(i32::from(self.nack_frag_count) + 1);
+        self.pending_submessages.push((
+            writer_guid,
+            EntitySubmessage::NackFrag(NackFrag {
+                reader_id,
+                writer_id: writer_guid.entity_id,
+                writer_sn: heartbeat_frag.writer_sn,
+                fragment_number_state,
+                count: self.nack_frag_count,
+            }),
+        ));
+    }
+
+    /// Returns the Submessages that have to be sent to the matched Writers.
+    pub fn pending_submessages(&mut self) -> Vec<(GUID_t, EntitySubmessage)> {
+        std::mem::take(&mut self.pending_submessages)
+    }
+
+    /// Returns the number of changes lost since the last call: the changes
+    /// skipped by the best-effort reader and the ones no longer available
+    /// in the Writer before the reliable reader received them.
+    pub fn take_lost_changes(&mut self) -> usize {
+        std::mem::take(&mut self.lost_changes)
+    }
+
+    fn writer_proxy_mut(&mut self, writer_guid: GUID_t) -> Option<&mut WriterProxy> {
+        self.matched_writers
+            .iter_mut()
+            .find(|proxy| proxy.remote_writer_guid == writer_guid)
+    }
+
+    fn change_received(&mut self, change: CacheChange) -> bool {
+        let is_reliable = self.is_reliable();
+        let sequence_number = change.sequence_number;
+        let writer_proxy = match self.writer_proxy_mut(change.writer_guid) {
+            Some(writer_proxy) => writer_proxy,
+            None => return false,
+        };
+
+        if !writer_proxy.received_change_set(sequence_number) {
+            return false;
+        }
+        if !is_reliable {
+            // The best-effort reader never accepts changes older than the
+            // already received ones
+            let lost_changes = writer_proxy.lost_changes_update(sequence_number);
+            self.lost_changes += lost_changes;
+        }
+
+        self.reader.reader_cache.add_change(change);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::fragment_number::FragmentNumber_t;
+    use crate::messages::submessage_elements::parameter::Parameter;
+    use crate::messages::submessage_elements::parameter_list::ParameterList;
+    use crate::messages::submessage_elements::serialized_payload::SerializedPayload;
+    use crate::structure::duration::Duration_t;
+    use crate::structure::entity_id::EntityId_t;
+    use crate::structure::guid_prefix::GuidPrefix_t;
+    use crate::structure::history_cache::HistoryCache;
+    use crate::structure::instance_handle::InstanceHandle_t;
+    use crate::structure::parameter_id::ParameterId;
+    use crate::structure::topic_kind::TopicKind_t;
+    use bytes::Bytes;
+
+    const WRITER_GUID: GUID_t = GUID_t {
+        guid_prefix: GuidPrefix_t {
+            entity_key: [0x01; 12],
+        },
+        entity_id: EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_WRITER,
+    };
+
+    fn stateful_reader(reliability_level: ReliabilityKind_t) -> StatefulReader {
+        let mut reader = StatefulReader::new(
+            EntityAttributes {
+                guid: GUID_t {
+                    guid_prefix: GuidPrefix_t {
+                        entity_key: [0x02; 12],
+                    },
+                    entity_id: EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_READER,
+                },
+            },
+            EndpointAttributes {
+                topic_kind: TopicKind_t::NO_KEY,
+                reliability_level,
+                unicast_locator_list: vec![],
+                multicast_locator_list: vec![],
+            },
+            ReaderAttributes {
+                expects_inline_qos: false,
+                heartbeat_response_delay: Duration_t::DURATION_ZERO,
+                heartbeat_suppression_duration: Duration_t::DURATION_ZERO,
+                reader_cache: HistoryCache::new(),
+            },
+        );
+        reader.matched_writer_add(WriterProxy::new(WRITER_GUID, vec![], vec![]));
+        reader
+    }
+
+    fn data(writer_sn: i64, payload: &'static [u8]) -> DataSubmessage {
+        DataSubmessage {
+            reader_id: EntityId_t::ENTITYID_UNKNOWN,
+            writer_id: WRITER_GUID.entity_id,
+            writer_sn: SequenceNumber_t::from(writer_sn),
+            inline_qos: None,
+            serialized_payload: Some(SerializedPayload::from(Bytes::from_static(payload))),
+        }
+    }
+
+    fn data_frag(fragment: u32, payload: &'static [u8]) -> DataFrag {
+        DataFrag {
+            reader_id: EntityId_t::ENTITYID_UNKNOWN,
+            writer_id: WRITER_GUID.entity_id,
+            writer_sn: SequenceNumber_t::from(1),
+            fragment_starting_num: FragmentNumber_t::from(fragment),
+            fragments_in_submessage: 1,
+            data_size: 10,
+            fragment_size: 4,
+            inline_qos: None,
+            serialized_payload: SerializedPayload::from(Bytes::from_static(payload)),
+        }
+    }
+
+    fn heartbeat(first_sn: i64, last_sn: i64, count: i32) -> Heartbeat {
+        Heartbeat {
+            reader_id: EntityId_t::ENTITYID_UNKNOWN,
+            writer_id: WRITER_GUID.entity_id,
+            first_sn: SequenceNumber_t::from(first_sn),
+            last_sn: SequenceNumber_t::from(last_sn),
+            count: Count_t::from(count),
+        }
+    }
+
+    fn received_values(reader: &StatefulReader) -> Vec<&[u8]> {
+        reader
+            .as_reader()
+            .reader_cache
+            .changes()
+            .iter()
+            .map(|change| &change.data_value.value[..])
+            .collect()
+    }
+
+    #[test]
+    fn data_from_matched_writer_is_added_once() {
+        let mut reader = stateful_reader(ReliabilityKind_t::RELIABLE);
+
+        assert!(reader.process_data(WRITER_GUID, &data(1, b"first"), None));
+        assert!(!reader.process_data(WRITER_GUID, &data(1, b"first"), None));
+        assert!(!reader.process_data(GUID_t::GUID_UNKNOWN, &data(2, b"second"), None));
+
+        assert_eq!(vec![&b"first"[..]], received_values(&reader));
+    }
+
+    #[test]
+    fn best_effort_reader_drops_older_changes() {
+        let mut reader = stateful_reader(ReliabilityKind_t::BEST_EFFORT);
+
+        assert!(reader.process_data(WRITER_GUID, &data(2, b"second"), None));
+        assert!(!reader.process_data(WRITER_GUID, &data(1, b"first"), None));
+
+        assert_eq!(vec![&b"second"[..]], received_values(&reader));
+        assert_eq!(1, reader.take_lost_changes());
+        assert_eq!(0, reader.take_lost_changes());
+    }
+
+    #[test]
+    fn fragments_are_assembled_into_change() {
+        let mut reader = stateful_reader(ReliabilityKind_t::RELIABLE);
+
+        let mut first = data_frag(1, b"0123");
+        first.inline_qos = Some(ParameterList {
+            parameters: vec![Parameter::new(ParameterId::PID_KEY_HASH, vec![0x0F; 16])],
+        });
+
+        assert!(!reader.process_data_frag(WRITER_GUID, &data_frag(2, b"4567"), None));
+        assert!(!reader.process_data_frag(WRITER_GUID, &first, None));
+        assert!(reader.process_data_frag(WRITER_GUID, &data_frag(3, b"89"), None));
+
+        assert_eq!(vec![&b"0123456789"[..]], received_values(&reader));
+        assert_eq!(
+            InstanceHandle_t {
+                entity_key: [0x0F; 16]
+            },
+            reader.as_reader().reader_cache.changes()[0].instance_handle
+        );
+    }
+
+    #[test]
+    fn heartbeat_is_answered_with_missing_changes() {
+        let mut reader = stateful_reader(ReliabilityKind_t::RELIABLE);
+        reader.process_data(WRITER_GUID, &data(2, b"second"), None);
+
+        reader.process_heartbeat(
+            WRITER_GUID,
+            &heartbeat(1, 3, 1),
+            SubmessageFlag { flags: 0x01 },
+        );
+
+        let mut reader_sn_state = SequenceNumberSet_t::new(SequenceNumber_t::from(1));
+        reader_sn_state.insert(SequenceNumber_t::from(1));
+        reader_sn_state.insert(SequenceNumber_t::from(3));
+        assert_eq!(
+            vec![(
+                WRITER_GUID,
+                EntitySubmessage::AckNack(
+                    AckNack {
+                        reader_id: EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_READER,
+                        writer_id: WRITER_GUID.entity_id,
+                        reader_sn_state,
+                        count: Count_t::from(1),
+                    },
+                    SubmessageFlag { flags: 0x01 }
+                )
+            )],
+            reader.pending_submessages()
+        );
+        assert!(reader.pending_submessages().is_empty());
+    }
+
+    #[test]
+    fn final_heartbeat_without_missing_changes_is_not_answered() {
+        let mut reader = stateful_reader(ReliabilityKind_t::RELIABLE);
+        reader.process_data(WRITER_GUID, &data(1, b"first"), None);
+
+        reader.process_heartbeat(
+            WRITER_GUID,
+            &heartbeat(1, 1, 1),
+            SubmessageFlag {
+                flags: 0x01 | Heartbeat::FINAL_FLAG,
+            },
+        );
+
+        assert!(reader.pending_submessages().is_empty());
+    }
+
+    #[test]
+    fn gap_marks_changes_as_irrelevant() {
+        let mut reader = stateful_reader(ReliabilityKind_t::RELIABLE);
+
+        reader.process_gap(
+            WRITER_GUID,
+            &Gap {
+                reader_id: EntityId_t::ENTITYID_UNKNOWN,
+                writer_id: WRITER_GUID.entity_id,
+                gap_start: SequenceNumber_t::from(1),
+                gap_list: SequenceNumberSet_t::new(SequenceNumber_t::from(3)),
+            },
+        );
+
+        assert_eq!(
+            SequenceNumber_t::from(2),
+            reader
+                .matched_writer_lookup(WRITER_GUID)
+                .unwrap()
+                .available_changes_max()
+        );
+    }
+
+    #[test]
+    fn heartbeat_frag_is_answered_with_missing_fragments() {
+        let mut reader = stateful_reader(ReliabilityKind_t::RELIABLE);
+        reader.process_data_frag(WRITER_GUID, &data_frag(2, b"4567"), None);
+
+        reader.process_heartbeat_frag(
+            WRITER_GUID,
+            &HeartbeatFrag {
+                reader_id: EntityId_t::ENTITYID_UNKNOWN,
+                writer_id: WRITER_GUID.entity_id,
+                writer_sn: SequenceNumber_t::from(1),
+                last_fragment_num: FragmentNumber_t::from(3),
+                count: Count_t::from(1),
+            },
+        );
+
+        let submessages = reader.pending_submessages();
+        assert_eq!(1, submessages.len());
+        match &submessages[0] {
+            (_, EntitySubmessage::NackFrag(nack_frag)) => assert_eq!(
+                vec![FragmentNumber_t::from(1), FragmentNumber_t::from(3)],
+                nack_frag.fragment_number_state.iter().collect::<Vec<_>>()
+            ),
+            other => panic!("Unexpected submessage {:?}", other),
+        }
+    }
+}
@@ -1,12 +1,38 @@
 use crate::behavior::reader_proxy::ReaderProxy;
 use crate::behavior::writer::{Writer, WriterAttributes};
+use crate::messages::ack_nack::AckNack;
+use crate::messages::data::Data as DataSubmessage;
+use crate::messages::data_frag::DataFrag;
+use crate::messages::fragment_number::FragmentNumber_t;
+use crate::messages::gap::Gap;
+use crate::messages::heartbeat::Heartbeat;
+use crate::messages::heartbeat_frag::HeartbeatFrag;
+use crate::messages::info_timestamp::InfoTimestamp;
+use crate::messages::nack_frag::NackFrag;
+use crate::messages::sender::MessageSender;
+use crate::messages::status_info::StatusInfo_t;
+use crate::messages::submessage::EntitySubmessage;
+use crate::messages::submessage_elements::parameter::Parameter;
+use crate::messages::submessage_elements::parameter_list::ParameterList;
+use crate::messages::submessage_elements::serialized_payload::SerializedPayload;
+use crate::messages::submessage_flag::SubmessageFlag;
 use crate::structure::cache_change::CacheChange;
 use crate::structure::change_kind::ChangeKind_t;
+use crate::structure::count::Count_t;
 use crate::structure::data::Data;
 use crate::structure::endpoint::{Endpoint, EndpointAttributes};
 use crate::structure::entity::{Entity, EntityAttributes};
+use crate::structure::entity_id::EntityId_t;
 use crate::structure::guid::GUID_t;
 use crate::structure::instance_handle::InstanceHandle_t;
+use crate::structure::parameter_id::ParameterId;
+use crate::structure::reliability_kind::ReliabilityKind_t;
+use crate::structure::sequence_number::SequenceNumber_t;
+use crate::structure::sequence_number_set::SequenceNumberSet_t;
+
+/// Flags of all Submessages created by the writer; they are always encoded in
+/// little endian
+const LITTLE_ENDIAN_FLAG: u8 = 0x01;
 
 pub struct StatefulWriter {
     /// The StatefulWriter keeps track of all the RTPS Readers matched with it.
@@ -16,6 +42,20 @@ pub struct StatefulWriter {
     entity: EntityAttributes,
     endpoint: EndpointAttributes,
     writer: WriterAttributes,
+
+    /// Counter of the Heartbeat Submessages sent by this writer.
+    heartbeat_count: Count_t,
+
+    /// Counter of the HeartbeatFrag Submessages sent by this writer.
+    heartbeat_frag_count: Count_t,
+
+    /// Whether the liveliness of the writer was asserted since the last
+    /// call to pending_submessages.
+    liveliness_asserted: bool,
+
+    /// The maximum size of a message of the transport, the changes not
+    /// fitting into it are fragmented.
+    max_message_size: usize,
 }
 
 impl Entity for StatefulWriter {
@@ -35,31 +75,65 @@ impl Writer for StatefulWriter {
         &self.writer
     }
 
+    fn as_writer_mut(&mut self) -> &mut WriterAttributes {
+        &mut self.writer
+    }
+
     /// This operation creates a new CacheChange to be appended to the RTPS
     /// Writer’s HistoryCache. The sequence number of the CacheChange is
     /// automatically set to be the sequenceNumber of the previous change plus
     /// one.
     fn new_change(
         &mut self,
-        _kind: ChangeKind_t,
-        _data: Data,
-        _handle: InstanceHandle_t,
+        kind: ChangeKind_t,
+        data: Data,
+        handle: InstanceHandle_t,
     ) -> CacheChange {
-        unimplemented!();
+        self.writer.last_change_sequence_number =
+            self.writer.last_change_sequence_number + SequenceNumber_t::from(1);
+
+        CacheChange {
+            kind,
+            writer_guid: self.entity.guid,
+            data_value: data,
+            source_timestamp: None,
+            instance_handle: handle,
+            sequence_number: self.writer.last_change_sequence_number,
+        }
     }
 }
 
 impl StatefulWriter {
-    pub fn new() -> Self {
-        unimplemented!();
+    /// The fragment_size of the writer fits into the messages of the
+    /// transports, see RtpsParticipant::add_writer.
+    pub fn new(
+        entity: EntityAttributes,
+        endpoint: EndpointAttributes,
+        writer: WriterAttributes,
+    ) -> Self {
+        StatefulWriter {
+            matched_readers: vec![],
+            entity,
+            endpoint,
+            writer,
+            heartbeat_count: Count_t::from(0),
+            heartbeat_frag_count: Count_t::from(0),
+            liveliness_asserted: false,
+            max_message_size: MessageSender::DEFAULT_MAX_MESSAGE_SIZE,
+        }
+    }
+
+    pub fn set_max_message_size(&mut self, max_message_size: usize) {
+        self.max_message_size = max_message_size
     }
 
     pub fn matched_reader_add(&mut self, a_reader_proxy: ReaderProxy) {
         self.matched_readers.push(a_reader_proxy)
     }
 
-    pub fn matched_reader_remove(&mut self, _a_reader_proxy: ReaderProxy) {
-        unimplemented!();
+    pub fn matched_reader_remove(&mut self, a_reader_guid: GUID_t) {
+        self.matched_readers
+            .retain(|proxy| proxy.remote_reader_guid != a_reader_guid)
     }
 
     pub fn matched_reader_lookup(
@@ -71,10 +145,824 @@ impl StatefulWriter {
             .filter(move |proxy| proxy.remote_reader_guid == a_reader_guid)
     }
 
-    pub fn is_acked_by_all(&self, _a_change: &CacheChange) {
-        unimplemented!();
+    /// Whether the change was acknowledged by all matched reliable Readers
+    /// and sent to all matched best-effort Readers; all Readers of the
+    /// best-effort writer are best-effort.
+    pub fn is_acked_by_all(&self, a_change: &CacheChange) -> bool {
+        let seq_num = a_change.sequence_number;
+        let is_reliable = self.endpoint.reliability_level == ReliabilityKind_t::RELIABLE;
+        self.matched_readers.iter().all(|proxy| {
+            if is_reliable && proxy.is_reliable() {
+                proxy.is_acked(seq_num)
+            } else {
+                proxy.is_sent(seq_num)
+            }
+        })
+    }
+
+    /// Handles a NackFrag Submessage sent by the remote Reader identified by
+    /// reader_guid. The requested fragments are re-sent with the next call to
+    /// pending_submessages.
+    pub fn process_nack_frag(&mut self, reader_guid: GUID_t, nack_frag: &NackFrag) {
+        let fragments_count = match self.writer.writer_cache.get_change(nack_frag.writer_sn) {
+            Some(change) => DataFrag::fragments_count(
+                change.data_value.value.len() as u32,
+                self.writer.fragment_size,
+            ),
+            None => return,
+        };
+
+        if let Some(reader_proxy) = self
+            .matched_readers
+            .iter_mut()
+            .find(|proxy| proxy.remote_reader_guid == reader_guid)
+        {
+            reader_proxy.requested_fragments_set(
+                nack_frag.writer_sn,
+                nack_frag.fragment_number_state.iter().filter(|fragment| {
+                    u32::from(*fragment) >= 1 && u32::from(*fragment) <= fragments_count
+                }),
+                nack_frag.count,
+            );
+        }
+    }
+
+    /// Handles an AckNack Submessage sent by the remote Reader identified by
+    /// reader_guid. The requested changes are re-sent with the next call to
+    /// pending_submessages.
+    pub fn process_ack_nack(&mut self, reader_guid: GUID_t, ack_nack: &AckNack) {
+        if let Some(reader_proxy) = self
+            .matched_readers
+            .iter_mut()
+            .find(|proxy| proxy.remote_reader_guid == reader_guid)
+        {
+            if reader_proxy.requested_changes_set(ack_nack.reader_sn_state.iter(), ack_nack.count) {
+                reader_proxy
+                    .acked_changes_set(ack_nack.reader_sn_state.base() - SequenceNumber_t::from(1));
+            }
+        }
+    }
+
+    /// Returns the Heartbeat Submessages announcing the changes available in
+    /// the writer's HistoryCache to each matched Reader. Only the reliable
+    /// writer sends Heartbeats.
+    pub fn heartbeat(&mut self) -> Vec<(GUID_t, EntitySubmessage)> {
+        if self.endpoint.reliability_level != ReliabilityKind_t::RELIABLE {
+            return vec![];
+        }
+        self.heartbeats(LITTLE_ENDIAN_FLAG)
+    }
+
+    /// Asserts the liveliness of the writer: the next call to
+    /// pending_submessages returns a Heartbeat with the LivelinessFlag for
+    /// each matched Reader, regardless of the reliability of the writer.
+    pub fn assert_liveliness(&mut self) {
+        self.liveliness_asserted = true;
+    }
+
+    fn heartbeats(&mut self, flags: u8) -> Vec<(GUID_t, EntitySubmessage)> {
+        if self.matched_readers.is_empty() {
+            return vec![];
+        }
+
+        let last_sn = self.writer.last_change_sequence_number;
+        let first_sn = self
+            .writer
+            .writer_cache
+            .get_seq_num_min()
+            .cloned()
+            .unwrap_or(last_sn + SequenceNumber_t::from(1));
+        self.heartbeat_count = Count_t::from(i32::from(self.heartbeat_count) + 1);
+
+        let writer_id = self.entity.guid.entity_id;
+        let count = self.heartbeat_count;
+        self.matched_readers
+            .iter()
+            .map(|reader_proxy| {
+                (
+                    reader_proxy.remote_reader_guid,
+                    EntitySubmessage::Heartbeat(
+                        Heartbeat {
+                            reader_id: reader_proxy.remote_reader_guid.entity_id,
+                            writer_id,
+                            first_sn,
+                            last_sn,
+                            count,
+                        },
+                        SubmessageFlag { flags },
+                    ),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns the Submessages that have to be sent to each matched Reader:
+    /// the fragments requested with NackFrag first, then the changes
+    /// requested with AckNack and finally all unsent changes. Changes bigger
+    /// than the fragmentation_threshold are sent as a series of DataFrag
+    /// Submessages, followed by a HeartbeatFrag when the writer is reliable.
+    /// Changes not relevant to the Reader, i.e. rejected by its content
+    /// filter, are sent as Gap. The Heartbeats asserting the liveliness of
+    /// the writer follow the changes.
+    pub fn pending_submessages(&mut self) -> Vec<(GUID_t, EntitySubmessage)> {
+        let writer = &self.writer;
+        let writer_id = self.entity.guid.entity_id;
+        let threshold = writer.fragmentation_threshold(self.max_message_size);
+        let is_reliable = self.endpoint.reliability_level == ReliabilityKind_t::RELIABLE;
+        let heartbeat_frag_count = &mut self.heartbeat_frag_count;
+        let mut submessages = vec![];
+
+        for reader_proxy in self.matched_readers.iter_mut() {
+            let reader_guid = reader_proxy.remote_reader_guid;
+            let reader_id = reader_guid.entity_id;
+            let mut push = |submessage| submessages.push((reader_guid, submessage));
+
+            for (seq_num, fragments) in reader_proxy.take_requested_fragments() {
+                if let Some(change) = writer.writer_cache.get_change(seq_num) {
+                    for fragment in fragments {
+                        push_info_timestamp(change, &mut push);
+                        push(data_frag(writer, reader_id, writer_id, change, fragment));
+                    }
+                }
+            }
+
+            for seq_num in reader_proxy.take_requested_changes() {
+                match writer.writer_cache.get_change(seq_num) {
+                    Some(change) if !reader_proxy.change_for_reader(change).is_relevant => {
+                        push(gap(reader_id, writer_id, seq_num))
+                    }
+                    Some(change) => change_submessages(
+                        writer,
+                        reader_id,
+                        writer_id,
+                        change,
+                        threshold,
+                        is_reliable.then_some(&mut *heartbeat_frag_count),
+                        &mut push,
+                    ),
+                    // The change is not available anymore
+                    None if seq_num <= writer.last_change_sequence_number => {
+                        push(gap(reader_id, writer_id, seq_num))
+                    }
+                    None => (),
+                }
+            }
+
+            while let Some(seq_num) = reader_proxy
+                .next_unsent_change(writer.writer_cache.changes())
+                .map(|change| change.sequence_number)
+            {
+                let change = match writer.writer_cache.get_change(seq_num) {
+                    Some(change) if reader_proxy.change_for_reader(change).is_relevant => change,
+                    // Filtered out for the Reader
+                    _ => {
+                        push(gap(reader_id, writer_id, seq_num));
+                        continue;
+                    }
+                };
+                change_submessages(
+                    writer,
+                    reader_id,
+                    writer_id,
+                    change,
+                    threshold,
+                    is_reliable.then_some(&mut *heartbeat_frag_count),
+                    &mut push,
+                );
+            }
+        }
+
+        if std::mem::take(&mut self.liveliness_asserted) {
+            submessages.extend(self.heartbeats(
+                LITTLE_ENDIAN_FLAG | Heartbeat::FINAL_FLAG | Heartbeat::LIVELINESS_FLAG,
+            ));
+        }
+        submessages
+    }
+}
+
+/// Creates Submessages carrying the change: a single Data Submessage or, for
+/// the change bigger than the threshold, a series of DataFrag Submessages,
+/// followed by a HeartbeatFrag Submessage when
+/// heartbeat_frag_count is provided. Each Data and DataFrag Submessage of the
+/// change with a source timestamp is preceded by an InfoTimestamp Submessage.
+pub(crate) fn change_submessages(
+    writer: &WriterAttributes,
+    reader_id: EntityId_t,
+    writer_id: EntityId_t,
+    change: &CacheChange,
+    threshold: u32,
+    heartbeat_frag_count: Option<&mut Count_t>,
+    push: &mut impl FnMut(EntitySubmessage),
+) {
+    // The changes which are not ALIVE carry only the key, never fragmented
+    let data_size = change.data_value.value.len() as u32;
+    if data_size <= threshold || change.kind != ChangeKind_t::ALIVE {
+        push_info_timestamp(change, push);
+        push(data(reader_id, writer_id, change));
+        return;
+    }
+
+    let fragments_count = DataFrag::fragments_count(data_size, writer.fragment_size);
+    for fragment in 1..=fragments_count {
+        push_info_timestamp(change, push);
+        push(data_frag(
+            writer,
+            reader_id,
+            writer_id,
+            change,
+            FragmentNumber_t::from(fragment),
+        ));
+    }
+
+    if let Some(heartbeat_frag_count) = heartbeat_frag_count {
+        *heartbeat_frag_count = Count_t::from(i32::from(*heartbeat_frag_count) + 1);
+        push(EntitySubmessage::HeartbeatFrag(HeartbeatFrag {
+            reader_id,
+            writer_id,
+            writer_sn: change.sequence_number,
+            last_fragment_num: FragmentNumber_t::from(fragments_count),
+            count: *heartbeat_frag_count,
+        }));
+    }
+}
+
+fn push_info_timestamp(change: &CacheChange, push: &mut impl FnMut(EntitySubmessage)) {
+    if let Some(timestamp) = change.source_timestamp {
+        push(EntitySubmessage::InfoTimestamp(
+            InfoTimestamp {
+                timestamp: Some(timestamp),
+            },
+            SubmessageFlag {
+                flags: LITTLE_ENDIAN_FLAG,
+            },
+        ));
     }
 }
 
+fn gap(
+    reader_id: EntityId_t,
+    writer_id: EntityId_t,
+    seq_num: SequenceNumber_t,
+) -> EntitySubmessage {
+    EntitySubmessage::Gap(Gap {
+        reader_id,
+        writer_id,
+        gap_start: seq_num,
+        gap_list: SequenceNumberSet_t::new(seq_num + SequenceNumber_t::from(1)),
+    })
+}
+
+/// Creates the Data Submessage of the change. The changes which are not
+/// ALIVE carry the key of the instance, with its key hash and the
+/// StatusInfo_t as inline QoS.
+fn data(reader_id: EntityId_t, writer_id: EntityId_t, change: &CacheChange) -> EntitySubmessage {
+    let (inline_qos, flags) = match change.kind {
+        ChangeKind_t::ALIVE => (None, DataSubmessage::DATA_FLAG),
+        kind => {
+            let mut inline_qos = ParameterList::new();
+            if change.instance_handle != InstanceHandle_t::HANDLE_NIL {
+                inline_qos.push(Parameter::new(
+                    ParameterId::PID_KEY_HASH,
+                    change.instance_handle.entity_key.to_vec(),
+                ));
+            }
+            inline_qos.push(Parameter::new(
+                ParameterId::PID_STATUS_INFO,
+                StatusInfo_t::from(kind).bits().to_be_bytes().to_vec(),
+            ));
+            (
+                Some(inline_qos),
+                DataSubmessage::INLINE_QOS_FLAG | DataSubmessage::KEY_FLAG,
+            )
+        }
+    };
+    EntitySubmessage::Data(
+        DataSubmessage {
+            reader_id,
+            writer_id,
+            writer_sn: change.sequence_number,
+            inline_qos,
+            serialized_payload: Some(SerializedPayload::from(change.data_value.value.clone())),
+        },
+        SubmessageFlag {
+            flags: LITTLE_ENDIAN_FLAG | flags,
+        },
+    )
+}
+
+/// Creates DataFrag Submessage carrying a single fragment of the change.
+/// The fragment shares the memory of the change, no copy is made.
+fn data_frag(
+    writer: &WriterAttributes,
+    reader_id: EntityId_t,
+    writer_id: EntityId_t,
+    change: &CacheChange,
+    fragment: FragmentNumber_t,
+) -> EntitySubmessage {
+    let value = &change.data_value.value;
+    let fragment_size = usize::from(writer.fragment_size);
+    let start = (u32::from(fragment) as usize - 1) * fragment_size;
+    let end = std::cmp::min(start + fragment_size, value.len());
+
+    EntitySubmessage::DataFrag(
+        DataFrag {
+            reader_id,
+            writer_id,
+            writer_sn: change.sequence_number,
+            fragment_starting_num: fragment,
+            fragments_in_submessage: 1,
+            data_size: value.len() as u32,
+            fragment_size: writer.fragment_size,
+            inline_qos: None,
+            serialized_payload: SerializedPayload::from(value.slice(start..end)),
+        },
+        SubmessageFlag {
+            flags: LITTLE_ENDIAN_FLAG,
+        },
+    )
+}
+
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+    use crate::messages::fragment_number_set::FragmentNumberSet_t;
+    use crate::structure::duration::Duration_t;
+    use crate::structure::guid_prefix::GuidPrefix_t;
+    use crate::structure::history_cache::HistoryCache;
+    use crate::structure::time::Time_t;
+    use crate::structure::topic_kind::TopicKind_t;
+    use bytes::Bytes;
+    use std::time::{Duration, SystemTime};
+
+    const READER_GUID: GUID_t = GUID_t {
+        guid_prefix: GuidPrefix_t {
+            entity_key: [0x01; 12],
+        },
+        entity_id: EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_READER,
+    };
+
+    fn stateful_writer(reliability_level: ReliabilityKind_t) -> StatefulWriter {
+        StatefulWriter::new(
+            EntityAttributes {
+                guid: GUID_t {
+                    guid_prefix: GuidPrefix_t {
+                        entity_key: [0x02; 12],
+                    },
+                    entity_id: EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_WRITER,
+                },
+            },
+            EndpointAttributes {
+                topic_kind: TopicKind_t::NO_KEY,
+                reliability_level,
+                unicast_locator_list: vec![],
+                multicast_locator_list: vec![],
+            },
+            WriterAttributes {
+                push_mode: true,
+                heartbeat_period: Duration_t::DURATION_ZERO,
+                nack_response_delay: Duration_t::DURATION_ZERO,
+                nack_suppression_duration: Duration_t::DURATION_ZERO,
+                last_change_sequence_number: SequenceNumber_t::from(0),
+                data_max_size_serialized: 8,
+                fragment_size: 4,
+                writer_cache: HistoryCache::new(),
+            },
+        )
+    }
+
+    fn write(writer: &mut StatefulWriter, value: &'static [u8]) {
+        let change = writer.new_change(
+            ChangeKind_t::ALIVE,
+            Data::from(Bytes::from_static(value)),
+            InstanceHandle_t::default(),
+        );
+        writer.as_writer_mut().writer_cache.add_change(change);
+    }
+
+    fn fragments(submessages: &[(GUID_t, EntitySubmessage)]) -> Vec<(u32, &[u8])> {
+        submessages
+            .iter()
+            .filter_map(|(_, submessage)| match submessage {
+                EntitySubmessage::DataFrag(data_frag, _) => Some((
+                    u32::from(data_frag.fragment_starting_num),
+                    &data_frag.serialized_payload.value[..],
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn new_change_increments_sequence_number() {
+        let mut writer = stateful_writer(ReliabilityKind_t::BEST_EFFORT);
+
+        write(&mut writer, b"first");
+        write(&mut writer, b"second");
+
+        assert_eq!(
+            Some(&SequenceNumber_t::from(2)),
+            writer.as_writer().writer_cache.get_seq_num_max()
+        );
+    }
+
+    #[test]
+    fn small_change_is_sent_as_single_data() {
+        let mut writer = stateful_writer(ReliabilityKind_t::RELIABLE);
+        writer.matched_reader_add(ReaderProxy::new(READER_GUID, vec![], vec![], false));
+        write(&mut writer, b"12345678");
+
+        let submessages = writer.pending_submessages();
+
+        assert_eq!(1, submessages.len());
+        match &submessages[0] {
+            (guid, EntitySubmessage::Data(data, flags)) => {
+                assert_eq!(&READER_GUID, guid);
+                assert_eq!(SequenceNumber_t::from(1), data.writer_sn);
+                assert_eq!(
+                    Some(SerializedPayload::from(Bytes::from_static(b"12345678"))),
+                    data.serialized_payload
+                );
+                assert!(flags.is_flag_set(DataSubmessage::DATA_FLAG));
+            }
+            other => panic!("Unexpected submessage {:?}", other),
+        }
+        assert!(writer.pending_submessages().is_empty());
+    }
+
+    #[test]
+    fn change_bigger_than_the_message_is_fragmented() {
+        let mut writer = stateful_writer(ReliabilityKind_t::BEST_EFFORT);
+        writer.as_writer_mut().data_max_size_serialized = 1024;
+        writer.set_max_message_size(80);
+        writer.matched_reader_add(ReaderProxy::new(READER_GUID, vec![], vec![], false));
+        write(&mut writer, b"12345678");
+        write(&mut writer, b"123456789");
+
+        let submessages = writer.pending_submessages();
+
+        assert!(matches!(submessages[0].1, EntitySubmessage::Data(..)));
+        assert_eq!(
+            vec![(1, &b"1234"[..]), (2, &b"5678"[..]), (3, &b"9"[..])],
+            fragments(&submessages)
+        );
+    }
+
+    #[test]
+    fn not_alive_change_is_sent_as_key_with_status_info() {
+        let mut writer = stateful_writer(ReliabilityKind_t::RELIABLE);
+        writer.matched_reader_add(ReaderProxy::new(READER_GUID, vec![], vec![], false));
+        let instance_handle = InstanceHandle_t {
+            entity_key: [0x0F; 16],
+        };
+        // Not fragmented even beyond data_max_size_serialized
+        let change = writer.new_change(
+            ChangeKind_t::NOT_ALIVE_DISPOSED_UNREGISTERED,
+            Data::from(Bytes::from_static(b"0123456789")),
+            instance_handle,
+        );
+        writer.as_writer_mut().writer_cache.add_change(change);
+
+        match &writer.pending_submessages()[..] {
+            [(_, EntitySubmessage::Data(data, flags))] => {
+                assert!(flags.is_flag_set(DataSubmessage::KEY_FLAG));
+                assert!(flags.is_flag_set(DataSubmessage::INLINE_QOS_FLAG));
+                assert!(!flags.is_flag_set(DataSubmessage::DATA_FLAG));
+                assert_eq!(
+                    ChangeKind_t::NOT_ALIVE_DISPOSED_UNREGISTERED,
+                    data.change_kind()
+                );
+                assert_eq!(instance_handle, data.key_hash());
+            }
+            other => panic!("Unexpected submessages {:?}", other),
+        }
+    }
+
+    #[test]
+    fn change_with_source_timestamp_is_preceded_by_info_timestamp() {
+        let mut writer = stateful_writer(ReliabilityKind_t::BEST_EFFORT);
+        writer.matched_reader_add(ReaderProxy::new(READER_GUID, vec![], vec![], false));
+        let timestamp = Time_t::from(SystemTime::UNIX_EPOCH + Duration::from_secs(1));
+        for value in [&b"1234"[..], &b"0123456789"[..]] {
+            let mut change = writer.new_change(
+                ChangeKind_t::ALIVE,
+                Data::from(Bytes::copy_from_slice(value)),
+                InstanceHandle_t::default(),
+            );
+            change.source_timestamp = Some(timestamp);
+            writer.as_writer_mut().writer_cache.add_change(change);
+        }
+
+        let submessages = writer.pending_submessages();
+
+        // The Data and each of the 3 DataFrag Submessages
+        assert_eq!(8, submessages.len());
+        for pair in submessages.chunks(2) {
+            match (&pair[0].1, &pair[1].1) {
+                (
+                    EntitySubmessage::InfoTimestamp(info_timestamp, _),
+                    EntitySubmessage::Data(..) | EntitySubmessage::DataFrag(..),
+                ) => assert_eq!(Some(timestamp), info_timestamp.timestamp),
+                other => panic!("Unexpected submessages {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn big_change_is_split_into_fragments() {
+        let mut writer = stateful_writer(ReliabilityKind_t::BEST_EFFORT);
+        writer.matched_reader_add(ReaderProxy::new(READER_GUID, vec![], vec![], false));
+        write(&mut writer, b"0123456789");
+
+        let submessages = writer.pending_submessages();
+
+        assert_eq!(
+            vec![(1, &b"0123"[..]), (2, &b"4567"[..]), (3, &b"89"[..])],
+            fragments(&submessages)
+        );
+        for (_, submessage) in &submessages {
+            match submessage {
+                EntitySubmessage::DataFrag(data_frag, _) => {
+                    assert_eq!(10, data_frag.data_size);
+                    assert_eq!(4, data_frag.fragment_size);
+                    assert_eq!(1, data_frag.fragments_in_submessage);
+                }
+                other => panic!("Unexpected submessage {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn reliable_writer_sends_heartbeat_frag_after_fragments() {
+        let mut writer = stateful_writer(ReliabilityKind_t::RELIABLE);
+        writer.matched_reader_add(ReaderProxy::new(READER_GUID, vec![], vec![], false));
+        write(&mut writer, b"0123456789");
+
+        let submessages = writer.pending_submessages();
+
+        assert_eq!(4, submessages.len());
+        assert_eq!(
+            (
+                READER_GUID,
+                EntitySubmessage::HeartbeatFrag(HeartbeatFrag {
+                    reader_id: READER_GUID.entity_id,
+                    writer_id: EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_WRITER,
+                    writer_sn: SequenceNumber_t::from(1),
+                    last_fragment_num: FragmentNumber_t::from(3),
+                    count: Count_t::from(1),
+                })
+            ),
+            submessages[3]
+        );
+    }
+
+    #[test]
+    fn nack_frag_resends_only_missing_fragments() {
+        let mut writer = stateful_writer(ReliabilityKind_t::RELIABLE);
+        writer.matched_reader_add(ReaderProxy::new(READER_GUID, vec![], vec![], false));
+        write(&mut writer, b"0123456789");
+        writer.pending_submessages();
+
+        let mut fragment_number_state = FragmentNumberSet_t::new(FragmentNumber_t::from(1));
+        fragment_number_state.insert(FragmentNumber_t::from(1));
+        fragment_number_state.insert(FragmentNumber_t::from(3));
+        // Fragments outside of the change are ignored
+        fragment_number_state.insert(FragmentNumber_t::from(7));
+
+        writer.process_nack_frag(
+            READER_GUID,
+            &NackFrag {
+                reader_id: READER_GUID.entity_id,
+                writer_id: EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_WRITER,
+                writer_sn: SequenceNumber_t::from(1),
+                fragment_number_state,
+                count: Count_t::from(1),
+            },
+        );
+
+        let submessages = writer.pending_submessages();
+        assert_eq!(
+            vec![(1, &b"0123"[..]), (3, &b"89"[..])],
+            fragments(&submessages)
+        );
+        assert_eq!(2, submessages.len());
+        assert!(writer.pending_submessages().is_empty());
+    }
+
+    #[test]
+    fn nack_frag_for_unknown_change_is_ignored() {
+        let mut writer = stateful_writer(ReliabilityKind_t::RELIABLE);
+        writer.matched_reader_add(ReaderProxy::new(READER_GUID, vec![], vec![], false));
+        write(&mut writer, b"0123456789");
+        writer.pending_submessages();
+
+        let mut fragment_number_state = FragmentNumberSet_t::new(FragmentNumber_t::from(1));
+        fragment_number_state.insert(FragmentNumber_t::from(1));
+
+        writer.process_nack_frag(
+            READER_GUID,
+            &NackFrag {
+                reader_id: READER_GUID.entity_id,
+                writer_id: EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_WRITER,
+                writer_sn: SequenceNumber_t::from(2),
+                fragment_number_state,
+                count: Count_t::from(1),
+            },
+        );
+
+        assert!(writer.pending_submessages().is_empty());
+    }
+
+    fn ack_nack(base: i64, missing: &[i64], count: i32) -> AckNack {
+        let mut reader_sn_state = SequenceNumberSet_t::new(SequenceNumber_t::from(base));
+        for seq_num in missing {
+            reader_sn_state.insert(SequenceNumber_t::from(*seq_num));
+        }
+        AckNack {
+            reader_id: READER_GUID.entity_id,
+            writer_id: EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_WRITER,
+            reader_sn_state,
+            count: Count_t::from(count),
+        }
+    }
+
+    #[test]
+    fn ack_nack_resends_requested_changes() {
+        let mut writer = stateful_writer(ReliabilityKind_t::RELIABLE);
+        writer.matched_reader_add(ReaderProxy::new(READER_GUID, vec![], vec![], false));
+        write(&mut writer, b"first");
+        write(&mut writer, b"second");
+        writer.pending_submessages();
+
+        writer.process_ack_nack(READER_GUID, &ack_nack(1, &[1], 1));
+        // Duplicated AckNack is ignored
+        writer.process_ack_nack(READER_GUID, &ack_nack(1, &[2], 1));
+
+        let submessages = writer.pending_submessages();
+        assert_eq!(1, submessages.len());
+        match &submessages[0] {
+            (_, EntitySubmessage::Data(data, _)) => {
+                assert_eq!(SequenceNumber_t::from(1), data.writer_sn)
+            }
+            other => panic!("Unexpected submessage {:?}", other),
+        }
+    }
+
+    #[test]
+    fn change_is_acked_by_all_when_acked_by_reliable_readers_and_sent_to_best_effort_ones() {
+        let best_effort_reader_guid = GUID_t {
+            guid_prefix: READER_GUID.guid_prefix,
+            entity_id: EntityId_t::ENTITYID_SEDP_BUILTIN_SUBSCRIPTIONS_READER,
+        };
+        let mut writer = stateful_writer(ReliabilityKind_t::RELIABLE);
+        writer.matched_reader_add(ReaderProxy::new(READER_GUID, vec![], vec![], false));
+        let mut best_effort_reader =
+            ReaderProxy::new(best_effort_reader_guid, vec![], vec![], false);
+        best_effort_reader.set_reliable(false);
+        writer.matched_reader_add(best_effort_reader);
+        write(&mut writer, b"first");
+        let is_acked_by_all = |writer: &StatefulWriter| {
+            writer.is_acked_by_all(&writer.as_writer().writer_cache.changes()[0])
+        };
+        assert!(!is_acked_by_all(&writer));
+
+        writer.pending_submessages();
+        assert!(!is_acked_by_all(&writer));
+
+        writer.process_ack_nack(READER_GUID, &ack_nack(2, &[], 1));
+        assert!(is_acked_by_all(&writer));
+    }
+
+    #[test]
+    fn requested_change_removed_from_history_is_sent_as_gap() {
+        let mut writer = stateful_writer(ReliabilityKind_t::RELIABLE);
+        writer.matched_reader_add(ReaderProxy::new(READER_GUID, vec![], vec![], false));
+        write(&mut writer, b"first");
+        writer.pending_submessages();
+        writer
+            .as_writer_mut()
+            .writer_cache
+            .remove_change(SequenceNumber_t::from(1));
+
+        writer.process_ack_nack(READER_GUID, &ack_nack(1, &[1], 1));
+
+        assert_eq!(
+            vec![(
+                READER_GUID,
+                EntitySubmessage::Gap(Gap {
+                    reader_id: READER_GUID.entity_id,
+                    writer_id: EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_WRITER,
+                    gap_start: SequenceNumber_t::from(1),
+                    gap_list: SequenceNumberSet_t::new(SequenceNumber_t::from(2)),
+                })
+            )],
+            writer.pending_submessages()
+        );
+    }
+
+    #[test]
+    fn changes_rejected_by_content_filter_are_sent_as_gap() {
+        let mut writer = stateful_writer(ReliabilityKind_t::RELIABLE);
+        let mut reader_proxy = ReaderProxy::new(READER_GUID, vec![], vec![], false);
+        reader_proxy.set_content_filter(Some(Box::new(|change: &CacheChange| {
+            change.data_value.value.starts_with(b"a")
+        })));
+        writer.matched_reader_add(reader_proxy);
+        write(&mut writer, b"apple");
+        write(&mut writer, b"banana");
+
+        let submessages = writer.pending_submessages();
+        assert_eq!(2, submessages.len());
+        assert!(
+            matches!(&submessages[0], (_, EntitySubmessage::Data(data, _))
+            if data.writer_sn == SequenceNumber_t::from(1))
+        );
+        assert_eq!(
+            (
+                READER_GUID,
+                EntitySubmessage::Gap(Gap {
+                    reader_id: READER_GUID.entity_id,
+                    writer_id: EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_WRITER,
+                    gap_start: SequenceNumber_t::from(2),
+                    gap_list: SequenceNumberSet_t::new(SequenceNumber_t::from(3)),
+                })
+            ),
+            submessages[1]
+        );
+
+        // The filtered change is not sent when requested either
+        writer.process_ack_nack(READER_GUID, &ack_nack(2, &[2], 1));
+        assert!(matches!(
+            &writer.pending_submessages()[..],
+            [(_, EntitySubmessage::Gap(_))]
+        ));
+    }
+
+    #[test]
+    fn heartbeat_announces_available_changes() {
+        let mut writer = stateful_writer(ReliabilityKind_t::RELIABLE);
+        writer.matched_reader_add(ReaderProxy::new(READER_GUID, vec![], vec![], false));
+        write(&mut writer, b"first");
+        write(&mut writer, b"second");
+
+        let heartbeat = |count| {
+            vec![(
+                READER_GUID,
+                EntitySubmessage::Heartbeat(
+                    Heartbeat {
+                        reader_id: READER_GUID.entity_id,
+                        writer_id: EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_WRITER,
+                        first_sn: SequenceNumber_t::from(1),
+                        last_sn: SequenceNumber_t::from(2),
+                        count: Count_t::from(count),
+                    },
+                    SubmessageFlag {
+                        flags: LITTLE_ENDIAN_FLAG,
+                    },
+                ),
+            )]
+        };
+        assert_eq!(heartbeat(1), writer.heartbeat());
+        assert_eq!(heartbeat(2), writer.heartbeat());
+    }
+
+    #[test]
+    fn best_effort_writer_does_not_send_heartbeats() {
+        let mut writer = stateful_writer(ReliabilityKind_t::BEST_EFFORT);
+        writer.matched_reader_add(ReaderProxy::new(READER_GUID, vec![], vec![], false));
+        write(&mut writer, b"first");
+
+        assert!(writer.heartbeat().is_empty());
+    }
+
+    #[test]
+    fn asserted_liveliness_is_sent_as_heartbeat_with_liveliness_flag() {
+        let mut writer = stateful_writer(ReliabilityKind_t::BEST_EFFORT);
+        writer.matched_reader_add(ReaderProxy::new(READER_GUID, vec![], vec![], false));
+        assert!(writer.pending_submessages().is_empty());
+
+        writer.assert_liveliness();
+
+        assert_eq!(
+            vec![(
+                READER_GUID,
+                EntitySubmessage::Heartbeat(
+                    Heartbeat {
+                        reader_id: READER_GUID.entity_id,
+                        writer_id: EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_WRITER,
+                        first_sn: SequenceNumber_t::from(1),
+                        last_sn: SequenceNumber_t::from(0),
+                        count: Count_t::from(1),
+                    },
+                    SubmessageFlag {
+                        flags: LITTLE_ENDIAN_FLAG
+                            | Heartbeat::FINAL_FLAG
+                            | Heartbeat::LIVELINESS_FLAG,
+                    },
+                ),
+            )],
+            writer.pending_submessages()
+        );
+        assert!(writer.pending_submessages().is_empty());
+    }
+}
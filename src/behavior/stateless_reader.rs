@@ -0,0 +1,149 @@
+use crate::behavior::reader::{Reader, ReaderAttributes};
+use crate::messages::data::Data as DataSubmessage;
+use crate::structure::cache_change::CacheChange;
+use crate::structure::data::Data;
+use crate::structure::endpoint::{Endpoint, EndpointAttributes};
+use crate::structure::entity::{Entity, EntityAttributes};
+use crate::structure::guid::GUID_t;
+
+/// Specialization of RTPS Reader used for the Stateless Reference
+/// Implementation. The RTPS StatelessReader has no knowledge of the number of
+/// matched writers, nor does it maintain any state for each matched RTPS
+/// Writer. Only best-effort reliability is supported.
+pub struct StatelessReader {
+    entity: EntityAttributes,
+    endpoint: EndpointAttributes,
+    reader: ReaderAttributes,
+}
+
+impl Entity for StatelessReader {
+    fn as_entity(&self) -> &EntityAttributes {
+        &self.entity
+    }
+}
+
+impl Endpoint for StatelessReader {
+    fn as_endpoint(&self) -> &EndpointAttributes {
+        &self.endpoint
+    }
+}
+
+impl Reader for StatelessReader {
+    fn as_reader(&self) -> &ReaderAttributes {
+        &self.reader
+    }
+
+    fn as_reader_mut(&mut self) -> &mut ReaderAttributes {
+        &mut self.reader
+    }
+}
+
+impl StatelessReader {
+    pub fn new(
+        entity: EntityAttributes,
+        endpoint: EndpointAttributes,
+        reader: ReaderAttributes,
+    ) -> Self {
+        StatelessReader {
+            entity,
+            endpoint,
+            reader,
+        }
+    }
+
+    /// Handles a Data Submessage sent by any Writer. Every received change is
+    /// added to the reader_cache, as no state is kept about the Writers.
+    /// Returns true if a change was added.
+    pub fn process_data(&mut self, writer_guid: GUID_t, data: &DataSubmessage) -> bool {
+        let value = match &data.serialized_payload {
+            Some(serialized_payload) => serialized_payload.value.clone(),
+            None => return false,
+        };
+
+        self.reader.reader_cache.add_change(CacheChange {
+            kind: data.change_kind(),
+            writer_guid,
+            instance_handle: data.key_hash(),
+            sequence_number: data.writer_sn,
+            data_value: Data::from(value),
+            source_timestamp: None,
+        });
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::submessage_elements::serialized_payload::SerializedPayload;
+    use crate::structure::duration::Duration_t;
+    use crate::structure::entity_id::EntityId_t;
+    use crate::structure::guid_prefix::GuidPrefix_t;
+    use crate::structure::history_cache::HistoryCache;
+    use crate::structure::reliability_kind::ReliabilityKind_t;
+    use crate::structure::sequence_number::SequenceNumber_t;
+    use crate::structure::topic_kind::TopicKind_t;
+    use bytes::Bytes;
+
+    fn stateless_reader() -> StatelessReader {
+        StatelessReader::new(
+            EntityAttributes {
+                guid: GUID_t {
+                    guid_prefix: GuidPrefix_t {
+                        entity_key: [0x02; 12],
+                    },
+                    entity_id: EntityId_t::ENTITYID_SPDP_BUILTIN_PARTICIPANT_READER,
+                },
+            },
+            EndpointAttributes {
+                topic_kind: TopicKind_t::WITH_KEY,
+                reliability_level: ReliabilityKind_t::BEST_EFFORT,
+                unicast_locator_list: vec![],
+                multicast_locator_list: vec![],
+            },
+            ReaderAttributes {
+                expects_inline_qos: false,
+                heartbeat_response_delay: Duration_t::DURATION_ZERO,
+                heartbeat_suppression_duration: Duration_t::DURATION_ZERO,
+                reader_cache: HistoryCache::new(),
+            },
+        )
+    }
+
+    fn data(serialized_payload: Option<&'static [u8]>) -> DataSubmessage {
+        DataSubmessage {
+            reader_id: EntityId_t::ENTITYID_UNKNOWN,
+            writer_id: EntityId_t::ENTITYID_SPDP_BUILTIN_PARTICIPANT_WRITER,
+            writer_sn: SequenceNumber_t::from(1),
+            inline_qos: None,
+            serialized_payload: serialized_payload
+                .map(|value| SerializedPayload::from(Bytes::from_static(value))),
+        }
+    }
+
+    #[test]
+    fn changes_of_all_writers_are_received() {
+        let mut reader = stateless_reader();
+        let writer_guid = |key| GUID_t {
+            guid_prefix: GuidPrefix_t {
+                entity_key: [key; 12],
+            },
+            entity_id: EntityId_t::ENTITYID_SPDP_BUILTIN_PARTICIPANT_WRITER,
+        };
+
+        assert!(reader.process_data(writer_guid(0x03), &data(Some(b"first"))));
+        assert!(reader.process_data(writer_guid(0x04), &data(Some(b"second"))));
+        // Repeated announcements are received too
+        assert!(reader.process_data(writer_guid(0x03), &data(Some(b"first"))));
+
+        assert_eq!(3, reader.as_reader().reader_cache.changes().len());
+    }
+
+    #[test]
+    fn data_without_payload_is_ignored() {
+        let mut reader = stateless_reader();
+
+        assert!(!reader.process_data(GUID_t::GUID_UNKNOWN, &data(None)));
+        assert!(reader.as_reader().reader_cache.changes().is_empty());
+    }
+}
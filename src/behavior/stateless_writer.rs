@@ -1,24 +1,29 @@
 use crate::behavior::reader_locator::ReaderLocator;
+use crate::behavior::stateful_writer::change_submessages;
 use crate::behavior::writer::{Writer, WriterAttributes};
+use crate::messages::sender::MessageSender;
+use crate::messages::submessage::EntitySubmessage;
 use crate::structure::cache_change::CacheChange;
 use crate::structure::change_kind::ChangeKind_t;
 use crate::structure::data::Data;
+use crate::structure::duration::Duration_t;
 use crate::structure::endpoint::{Endpoint, EndpointAttributes};
 use crate::structure::entity::{Entity, EntityAttributes};
+use crate::structure::entity_id::EntityId_t;
 use crate::structure::instance_handle::InstanceHandle_t;
+use crate::structure::locator::Locator_t;
 use crate::structure::sequence_number::SequenceNumber_t;
-use crate::structure::time::Time_t;
 
 /// Specialization of RTPS Writer used for the Stateless Reference
 /// Implementation. The RTPS StatelessWriter has no knowledge of the number of
 /// matched readers, nor does it maintain any state for each matched RTPS Reader
 /// endpoint. The RTPS StatelessWriter maintains only the RTPS Locator_t list
 /// that should be used to send information to the matched readers
-struct StatelessWriter {
+pub struct StatelessWriter {
     /// Protocol tuning parameter that indicates that the StatelessWriter
     /// re-sends all the changes in the writer’s HistoryCache to
     /// all the Locators periodically each resendPeriod
-    resend_data_period: Time_t,
+    resend_data_period: Duration_t,
 
     /// The StatelessWriter maintains the list of locators
     /// to which it sends the CacheChanges. This list may include
@@ -28,6 +33,14 @@ struct StatelessWriter {
     entity_attributes: EntityAttributes,
     endpoint_attributes: EndpointAttributes,
     writer_attributes: WriterAttributes,
+
+    /// The highest sequence number of the writer’s HistoryCache already
+    /// queued as unsent to the reader locators.
+    highest_queued_sequence_number: SequenceNumber_t,
+
+    /// The maximum size of a message of the transport, the changes not
+    /// fitting into it are fragmented.
+    max_message_size: usize,
 }
 
 impl Entity for StatelessWriter {
@@ -47,6 +60,10 @@ impl Writer for StatelessWriter {
         &self.writer_attributes
     }
 
+    fn as_writer_mut(&mut self) -> &mut WriterAttributes {
+        &mut self.writer_attributes
+    }
+
     fn new_change(
         &mut self,
         kind: ChangeKind_t,
@@ -60,6 +77,7 @@ impl Writer for StatelessWriter {
             kind: kind,
             writer_guid: self.entity_attributes.guid,
             data_value: data,
+            source_timestamp: None,
             instance_handle: handle,
             sequence_number: self.writer_attributes.last_change_sequence_number,
         }
@@ -67,33 +85,246 @@ impl Writer for StatelessWriter {
 }
 
 impl StatelessWriter {
+    /// The fragment_size of the writer fits into the messages of the
+    /// transports, see WriterAttributes::fits.
     pub fn new(
         entity_attributes: EntityAttributes,
         endpoint_attributes: EndpointAttributes,
         writer_attributes: WriterAttributes,
-        resend_data_period: Time_t,
+        resend_data_period: Duration_t,
     ) -> Self {
         StatelessWriter {
-            entity_attributes: entity_attributes,
-            endpoint_attributes: endpoint_attributes,
-            writer_attributes: writer_attributes,
-            resend_data_period: resend_data_period,
+            entity_attributes,
+            endpoint_attributes,
+            writer_attributes,
+            resend_data_period,
             reader_locators: vec![],
+            highest_queued_sequence_number: SequenceNumber_t::from(0),
+            max_message_size: MessageSender::DEFAULT_MAX_MESSAGE_SIZE,
         }
     }
 
-    pub fn reader_locator_add(&mut self, a_locator: ReaderLocator) {
+    pub fn set_max_message_size(&mut self, max_message_size: usize) {
+        self.max_message_size = max_message_size
+    }
+
+    pub fn resend_data_period(&self) -> Duration_t {
+        self.resend_data_period
+    }
+
+    pub fn reader_locators(&self) -> &[ReaderLocator] {
+        &self.reader_locators
+    }
+
+    /// Adds the locator; all changes of the writer’s HistoryCache are sent
+    /// to it with the next call to pending_submessages.
+    pub fn reader_locator_add(&mut self, mut a_locator: ReaderLocator) {
+        a_locator.unsent_changes_set(&self.cached_sequence_numbers());
         self.reader_locators.push(a_locator)
     }
 
-    pub fn reader_locator_remove(&mut self, a_locator: ReaderLocator) {
-        self.reader_locators.retain(|x| x != &a_locator)
+    pub fn reader_locator_remove(&mut self, a_locator: Locator_t) {
+        self.reader_locators.retain(|x| x.locator() != a_locator)
     }
 
+    /// Marks all changes of the writer’s HistoryCache as unsent to all
+    /// locators, so they are re-sent with the next call to
+    /// pending_submessages. Called every resend_data_period.
     pub fn unsent_changes_reset(&mut self) {
-        unimplemented!();
+        let seq_num_set = self.cached_sequence_numbers();
+        for reader_locator in self.reader_locators.iter_mut() {
+            reader_locator.unsent_changes_set(&seq_num_set);
+        }
+        self.highest_queued_sequence_number = self.writer_attributes.last_change_sequence_number;
+    }
+
+    /// Returns the Submessages that have to be sent to each locator: the
+    /// requested changes first, then all unsent changes, including the
+    /// changes added to the writer’s HistoryCache since the last call. The
+    /// Submessages are addressed to all Readers (ENTITYID_UNKNOWN).
+    pub fn pending_submessages(&mut self) -> Vec<(Locator_t, EntitySubmessage)> {
+        self.queue_new_changes();
+
+        let writer = &self.writer_attributes;
+        let writer_id = self.entity_attributes.guid.entity_id;
+        let threshold = writer.fragmentation_threshold(self.max_message_size);
+        let mut submessages = vec![];
+
+        for reader_locator in self.reader_locators.iter_mut() {
+            let locator = reader_locator.locator();
+            let mut push = |submessage| submessages.push((locator, submessage));
+
+            while let Some(seq_num) = reader_locator
+                .next_requested_change()
+                .or_else(|| reader_locator.next_unsent_change())
+            {
+                if let Some(change) = writer.writer_cache.get_change(seq_num) {
+                    change_submessages(
+                        writer,
+                        EntityId_t::ENTITYID_UNKNOWN,
+                        writer_id,
+                        change,
+                        threshold,
+                        None,
+                        &mut push,
+                    );
+                }
+            }
+        }
+
+        submessages
+    }
+
+    /// Marks the changes added to the writer’s HistoryCache since the last
+    /// call as unsent to all locators.
+    fn queue_new_changes(&mut self) {
+        let highest_queued = self.highest_queued_sequence_number;
+        let new_changes: Vec<SequenceNumber_t> = self
+            .cached_sequence_numbers()
+            .into_iter()
+            .filter(|seq_num| *seq_num > highest_queued)
+            .collect();
+        if let Some(highest) = new_changes.last() {
+            self.highest_queued_sequence_number = *highest;
+        }
+        for reader_locator in self.reader_locators.iter_mut() {
+            reader_locator.unsent_changes_set(&new_changes);
+        }
+    }
+
+    fn cached_sequence_numbers(&self) -> Vec<SequenceNumber_t> {
+        let mut seq_num_set: Vec<SequenceNumber_t> = self
+            .writer_attributes
+            .writer_cache
+            .changes()
+            .iter()
+            .map(|change| change.sequence_number)
+            .collect();
+        seq_num_set.sort();
+        seq_num_set
     }
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+    use crate::messages::data::Data as DataSubmessage;
+    use crate::structure::guid::GUID_t;
+    use crate::structure::guid_prefix::GuidPrefix_t;
+    use crate::structure::history_cache::HistoryCache;
+    use crate::structure::locator::{Ipv4Addr, SocketAddr};
+    use crate::structure::reliability_kind::ReliabilityKind_t;
+    use crate::structure::topic_kind::TopicKind_t;
+    use bytes::Bytes;
+
+    fn locator(port: u16) -> Locator_t {
+        Locator_t::from(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), port))
+    }
+
+    fn stateless_writer() -> StatelessWriter {
+        StatelessWriter::new(
+            EntityAttributes {
+                guid: GUID_t {
+                    guid_prefix: GuidPrefix_t {
+                        entity_key: [0x01; 12],
+                    },
+                    entity_id: EntityId_t::ENTITYID_SPDP_BUILTIN_PARTICIPANT_WRITER,
+                },
+            },
+            EndpointAttributes {
+                topic_kind: TopicKind_t::WITH_KEY,
+                reliability_level: ReliabilityKind_t::BEST_EFFORT,
+                unicast_locator_list: vec![],
+                multicast_locator_list: vec![],
+            },
+            WriterAttributes {
+                push_mode: true,
+                heartbeat_period: Duration_t::DURATION_ZERO,
+                nack_response_delay: Duration_t::DURATION_ZERO,
+                nack_suppression_duration: Duration_t::DURATION_ZERO,
+                last_change_sequence_number: SequenceNumber_t::from(0),
+                data_max_size_serialized: 64,
+                fragment_size: 32,
+                writer_cache: HistoryCache::new(),
+            },
+            Duration_t::DURATION_ZERO,
+        )
+    }
+
+    fn write(writer: &mut StatelessWriter, value: &'static [u8]) {
+        let change = writer.new_change(
+            ChangeKind_t::ALIVE,
+            Data::from(Bytes::from_static(value)),
+            InstanceHandle_t::default(),
+        );
+        writer.as_writer_mut().writer_cache.add_change(change);
+    }
+
+    fn sent(submessages: &[(Locator_t, EntitySubmessage)]) -> Vec<(u16, i64)> {
+        submessages
+            .iter()
+            .map(|(locator, submessage)| match submessage {
+                EntitySubmessage::Data(data, flags) => {
+                    assert_eq!(EntityId_t::ENTITYID_UNKNOWN, data.reader_id);
+                    assert!(flags.is_flag_set(DataSubmessage::DATA_FLAG));
+                    (locator.port as u16, i64::from(data.writer_sn))
+                }
+                other => panic!("Unexpected submessage {:?}", other),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn changes_are_sent_once_to_every_locator() {
+        let mut writer = stateless_writer();
+        writer.reader_locator_add(ReaderLocator::new(locator(7400), false));
+        write(&mut writer, b"first");
+        writer.reader_locator_add(ReaderLocator::new(locator(7410), false));
+        write(&mut writer, b"second");
+
+        assert_eq!(
+            vec![(7400, 1), (7400, 2), (7410, 1), (7410, 2)],
+            sent(&writer.pending_submessages())
+        );
+        assert!(writer.pending_submessages().is_empty());
+    }
+
+    #[test]
+    fn reset_resends_all_changes() {
+        let mut writer = stateless_writer();
+        writer.reader_locator_add(ReaderLocator::new(locator(7400), false));
+        write(&mut writer, b"first");
+        writer.pending_submessages();
+
+        writer.unsent_changes_reset();
+
+        assert_eq!(vec![(7400, 1)], sent(&writer.pending_submessages()));
+    }
+
+    #[test]
+    fn removed_change_is_not_sent() {
+        let mut writer = stateless_writer();
+        writer.reader_locator_add(ReaderLocator::new(locator(7400), false));
+        write(&mut writer, b"first");
+        writer.pending_submessages();
+        write(&mut writer, b"second");
+        writer
+            .as_writer_mut()
+            .writer_cache
+            .remove_change(SequenceNumber_t::from(1));
+        writer.unsent_changes_reset();
+
+        assert_eq!(vec![(7400, 2)], sent(&writer.pending_submessages()));
+    }
+
+    #[test]
+    fn removed_locator_is_not_reached() {
+        let mut writer = stateless_writer();
+        writer.reader_locator_add(ReaderLocator::new(locator(7400), false));
+        writer.reader_locator_add(ReaderLocator::new(locator(7410), false));
+        writer.reader_locator_remove(locator(7400));
+        write(&mut writer, b"first");
+
+        assert_eq!(vec![(7410, 1)], sent(&writer.pending_submessages()));
+    }
+}
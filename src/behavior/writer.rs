@@ -2,19 +2,71 @@ use crate::structure::cache_change::CacheChange;
 use crate::structure::change_kind::ChangeKind_t;
 use crate::structure::data::Data;
 use crate::structure::duration::Duration_t;
+use crate::structure::history_cache::HistoryCache;
 use crate::structure::instance_handle::InstanceHandle_t;
 use crate::structure::sequence_number::SequenceNumber_t;
 
+use std::convert::TryFrom;
+
 pub struct WriterAttributes {
     pub push_mode: bool,
     pub heartbeat_period: Duration_t,
     pub nack_response_delay: Duration_t,
     pub nack_suppression_duration: Duration_t,
     pub last_change_sequence_number: SequenceNumber_t,
+
+    /// The maximum size of a serialized payload sent in a single Data
+    /// Submessage. Bigger changes, as well as the changes not fitting into a
+    /// single message of the transport, are split into DataFrag Submessages.
+    pub data_max_size_serialized: u32,
+
+    /// The size of a single fragment used when a change is sent in DataFrag
+    /// Submessages, never zero and fitting into a single message of the
+    /// transport, see fits.
+    pub fragment_size: u16,
+
+    /// Contains the history of CacheChange changes for this Writer.
+    pub writer_cache: HistoryCache,
+}
+
+impl WriterAttributes {
+    /// The room taken in a message by everything but the serialized payload
+    /// of a Data Submessage: the Header, InfoDestination, InfoTimestamp and
+    /// the Data Submessage without inline QoS.
+    const DATA_MESSAGE_OVERHEAD: usize = 20 + 16 + 12 + 24;
+
+    /// The room taken in a message by everything but the fragment of a
+    /// DataFrag Submessage: the Header, InfoDestination, InfoTimestamp and
+    /// the DataFrag Submessage without inline QoS.
+    const DATA_FRAG_MESSAGE_OVERHEAD: usize = 20 + 16 + 12 + 36;
+
+    /// The size of the biggest fragment sent in a single message through a
+    /// transport limited to max_message_size.
+    pub fn max_fragment_size(max_message_size: usize) -> u16 {
+        let max_fragment_size = max_message_size.saturating_sub(Self::DATA_FRAG_MESSAGE_OVERHEAD);
+        u16::try_from(max_fragment_size).unwrap_or(u16::MAX)
+    }
+
+    /// Whether the fragment_size is not zero and the fragments fit into the
+    /// messages of a transport limited to max_message_size.
+    pub fn fits(&self, max_message_size: usize) -> bool {
+        self.fragment_size > 0 && self.fragment_size <= Self::max_fragment_size(max_message_size)
+    }
+
+    /// The maximum size of a serialized payload sent in a single Data
+    /// Submessage through a transport limited to max_message_size.
+    pub fn fragmentation_threshold(&self, max_message_size: usize) -> u32 {
+        let max_payload_size = max_message_size.saturating_sub(Self::DATA_MESSAGE_OVERHEAD);
+        std::cmp::min(
+            self.data_max_size_serialized,
+            u32::try_from(max_payload_size).unwrap_or(u32::MAX),
+        )
+    }
 }
 
 pub trait Writer {
     fn as_writer(&self) -> &WriterAttributes;
+    fn as_writer_mut(&mut self) -> &mut WriterAttributes;
     fn new_change(
         &mut self,
         kind: ChangeKind_t,
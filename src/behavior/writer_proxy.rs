@@ -0,0 +1,327 @@
+use crate::behavior::fragmented_change::FragmentedChange;
+use crate::messages::data_frag::DataFrag;
+use crate::messages::fragment_number::FragmentNumber_t;
+use crate::structure::count::Count_t;
+use crate::structure::guid::GUID_t;
+use crate::structure::locator::LocatorList_t;
+use crate::structure::sequence_number::SequenceNumber_t;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The RTPS WriterProxy class represents the information an RTPS
+/// StatefulReader maintains on each matched RTPS Writer.
+pub struct WriterProxy {
+    /// Identifies the matched Writer.
+    pub remote_writer_guid: GUID_t,
+
+    /// List of unicast (address, port) combinations that can be used to send
+    /// messages to the matched Writer or Writers. The list may be empty.
+    pub unicast_locator_list: LocatorList_t,
+
+    /// List of multicast (address, port) combinations that can be used to
+    /// send messages to the matched Writer or Writers. The list may be empty.
+    pub multicast_locator_list: LocatorList_t,
+
+    /// All changes up to this sequence number were received or are not
+    /// available anymore.
+    available_changes_max: SequenceNumber_t,
+
+    /// Changes with sequence number above available_changes_max which were
+    /// received or declared irrelevant by the Writer.
+    received_changes: BTreeSet<SequenceNumber_t>,
+
+    /// The highest sequence number announced by the Writer with a Heartbeat.
+    last_available_seq_num: SequenceNumber_t,
+
+    /// Changes received partially as DataFrag Submessages.
+    fragmented_changes: BTreeMap<SequenceNumber_t, FragmentedChange>,
+
+    /// Count of the last Heartbeat Submessage received from the Writer.
+    last_heartbeat_count: Option<Count_t>,
+
+    /// Count of the last HeartbeatFrag Submessage received from the Writer.
+    last_heartbeat_frag_count: Option<Count_t>,
+}
+
+impl WriterProxy {
+    /// The maximum number of changes received partially at the same time.
+    /// Fragments of further changes are dropped until one of them is
+    /// complete; the Writer sends them again.
+    pub const MAX_FRAGMENTED_CHANGES: usize = 4;
+
+    pub fn new(
+        remote_writer_guid: GUID_t,
+        unicast_locator_list: LocatorList_t,
+        multicast_locator_list: LocatorList_t,
+    ) -> Self {
+        WriterProxy {
+            remote_writer_guid,
+            unicast_locator_list,
+            multicast_locator_list,
+            available_changes_max: SequenceNumber_t::from(0),
+            received_changes: BTreeSet::new(),
+            last_available_seq_num: SequenceNumber_t::from(0),
+            fragmented_changes: BTreeMap::new(),
+            last_heartbeat_count: None,
+            last_heartbeat_frag_count: None,
+        }
+    }
+
+    /// The highest sequence number such that all changes up to it were
+    /// received or are not available anymore.
+    pub fn available_changes_max(&self) -> SequenceNumber_t {
+        self.available_changes_max
+    }
+
+    pub fn is_received(&self, seq_num: SequenceNumber_t) -> bool {
+        seq_num <= self.available_changes_max || self.received_changes.contains(&seq_num)
+    }
+
+    /// Marks the change as received. Returns false if the change was
+    /// already received.
+    pub fn received_change_set(&mut self, seq_num: SequenceNumber_t) -> bool {
+        if self.is_received(seq_num) {
+            return false;
+        }
+        self.fragmented_changes.remove(&seq_num);
+        self.received_changes.insert(seq_num);
+        self.advance_available_changes_max();
+        true
+    }
+
+    /// Marks the change as irrelevant to the Reader, e.g. as a result of a
+    /// Gap Submessage.
+    pub fn irrelevant_change_set(&mut self, seq_num: SequenceNumber_t) {
+        self.received_change_set(seq_num);
+    }
+
+    /// Marks all changes before first_available_seq_num as lost; they are
+    /// not available in the Writer anymore. Returns the number of changes
+    /// lost without being received.
+    pub fn lost_changes_update(&mut self, first_available_seq_num: SequenceNumber_t) -> usize {
+        let lost_changes_max = first_available_seq_num - SequenceNumber_t::from(1);
+        if lost_changes_max <= self.available_changes_max {
+            return 0;
+        }
+        let received_changes = self.received_changes.split_off(&first_available_seq_num);
+        let lost_changes = (i64::from(lost_changes_max) - i64::from(self.available_changes_max))
+            as usize
+            - self.received_changes.len();
+        self.available_changes_max = lost_changes_max;
+        self.received_changes = received_changes;
+        self.fragmented_changes = self.fragmented_changes.split_off(&first_available_seq_num);
+        self.advance_available_changes_max();
+        lost_changes
+    }
+
+    /// Notes that changes up to last_available_seq_num are available in the
+    /// Writer.
+    pub fn missing_changes_update(&mut self, last_available_seq_num: SequenceNumber_t) {
+        if last_available_seq_num > self.last_available_seq_num {
+            self.last_available_seq_num = last_available_seq_num;
+        }
+    }
+
+    /// Changes available in the Writer which were not received yet.
+    pub fn missing_changes(&self) -> impl Iterator<Item = SequenceNumber_t> + '_ {
+        (i64::from(self.available_changes_max) + 1..=i64::from(self.last_available_seq_num))
+            .map(SequenceNumber_t::from)
+            .filter(move |seq_num| !self.received_changes.contains(seq_num))
+    }
+
+    /// Stores fragments carried by the DataFrag Submessage. Returns the
+    /// change once all its fragments are received. Changes bigger than
+    /// max_sample_size are dropped. At most MAX_FRAGMENTED_CHANGES changes
+    /// are received at the same time, the oldest ones are preferred.
+    pub fn data_frag_received(
+        &mut self,
+        data_frag: &DataFrag,
+        max_sample_size: u32,
+    ) -> Option<FragmentedChange> {
+        let seq_num = data_frag.writer_sn;
+        if self.is_received(seq_num) || data_frag.data_size > max_sample_size {
+            return None;
+        }
+
+        if !self.fragmented_changes.contains_key(&seq_num)
+            && self.fragmented_changes.len() >= Self::MAX_FRAGMENTED_CHANGES
+        {
+            match self.fragmented_changes.keys().next_back() {
+                Some(newest) if *newest > seq_num => {
+                    let newest = *newest;
+                    self.fragmented_changes.remove(&newest);
+                }
+                _ => return None,
+            }
+        }
+
+        let fragmented_change = self
+            .fragmented_changes
+            .entry(seq_num)
+            .or_insert_with(|| FragmentedChange::new(data_frag.data_size, data_frag.fragment_size));
+        fragmented_change.insert(data_frag);
+
+        if fragmented_change.is_complete() {
+            self.fragmented_changes.remove(&seq_num)
+        } else {
+            None
+        }
+    }
+
+    /// Fragments of the change up to last_fragment_num which were not
+    /// received yet. Empty if the change was not being received as fragments.
+    pub fn missing_fragments(
+        &self,
+        seq_num: SequenceNumber_t,
+        last_fragment_num: FragmentNumber_t,
+    ) -> Vec<FragmentNumber_t> {
+        self.fragmented_changes
+            .get(&seq_num)
+            .map(|fragmented_change| {
+                fragmented_change
+                    .missing_fragments(last_fragment_num)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns false if a Heartbeat with the same or a newer count was
+    /// already received.
+    pub fn heartbeat_count_update(&mut self, count: Count_t) -> bool {
+        if matches!(self.last_heartbeat_count, Some(last) if last >= count) {
+            return false;
+        }
+        self.last_heartbeat_count = Some(count);
+        true
+    }
+
+    /// Returns false if a HeartbeatFrag with the same or a newer count was
+    /// already received.
+    pub fn heartbeat_frag_count_update(&mut self, count: Count_t) -> bool {
+        if matches!(self.last_heartbeat_frag_count, Some(last) if last >= count) {
+            return false;
+        }
+        self.last_heartbeat_frag_count = Some(count);
+        true
+    }
+
+    fn advance_available_changes_max(&mut self) {
+        while self
+            .received_changes
+            .remove(&(self.available_changes_max + SequenceNumber_t::from(1)))
+        {
+            self.available_changes_max = self.available_changes_max + SequenceNumber_t::from(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::submessage_elements::serialized_payload::SerializedPayload;
+    use crate::structure::entity_id::EntityId_t;
+    use bytes::Bytes;
+
+    fn seq_nums(numbers: &[i64]) -> Vec<SequenceNumber_t> {
+        numbers
+            .iter()
+            .map(|number| SequenceNumber_t::from(*number))
+            .collect()
+    }
+
+    #[test]
+    fn received_changes_advance_available_changes_max() {
+        let mut writer_proxy = WriterProxy::new(GUID_t::GUID_UNKNOWN, vec![], vec![]);
+
+        assert!(writer_proxy.received_change_set(SequenceNumber_t::from(2)));
+        assert_eq!(
+            SequenceNumber_t::from(0),
+            writer_proxy.available_changes_max()
+        );
+
+        assert!(writer_proxy.received_change_set(SequenceNumber_t::from(1)));
+        assert_eq!(
+            SequenceNumber_t::from(2),
+            writer_proxy.available_changes_max()
+        );
+
+        assert!(!writer_proxy.received_change_set(SequenceNumber_t::from(1)));
+    }
+
+    #[test]
+    fn missing_changes_are_bounded_by_heartbeat() {
+        let mut writer_proxy = WriterProxy::new(GUID_t::GUID_UNKNOWN, vec![], vec![]);
+        writer_proxy.received_change_set(SequenceNumber_t::from(3));
+
+        writer_proxy.missing_changes_update(SequenceNumber_t::from(5));
+        assert_eq!(
+            seq_nums(&[1, 2, 4, 5]),
+            writer_proxy.missing_changes().collect::<Vec<_>>()
+        );
+
+        assert_eq!(
+            2,
+            writer_proxy.lost_changes_update(SequenceNumber_t::from(3))
+        );
+        assert_eq!(
+            SequenceNumber_t::from(3),
+            writer_proxy.available_changes_max()
+        );
+        assert_eq!(
+            seq_nums(&[4, 5]),
+            writer_proxy.missing_changes().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn duplicated_heartbeat_is_detected() {
+        let mut writer_proxy = WriterProxy::new(GUID_t::GUID_UNKNOWN, vec![], vec![]);
+
+        assert!(writer_proxy.heartbeat_count_update(Count_t::from(1)));
+        assert!(!writer_proxy.heartbeat_count_update(Count_t::from(1)));
+        assert!(writer_proxy.heartbeat_count_update(Count_t::from(2)));
+    }
+
+    fn data_frag(writer_sn: i64, data_size: u32) -> DataFrag {
+        DataFrag {
+            reader_id: EntityId_t::ENTITYID_UNKNOWN,
+            writer_id: EntityId_t::ENTITYID_UNKNOWN,
+            writer_sn: SequenceNumber_t::from(writer_sn),
+            fragment_starting_num: FragmentNumber_t::from(1),
+            fragments_in_submessage: 1,
+            data_size,
+            fragment_size: 4,
+            inline_qos: None,
+            serialized_payload: SerializedPayload::from(Bytes::from_static(b"0123")),
+        }
+    }
+
+    fn fragmented_seq_nums(writer_proxy: &WriterProxy) -> Vec<SequenceNumber_t> {
+        writer_proxy.fragmented_changes.keys().copied().collect()
+    }
+
+    #[test]
+    fn fragments_of_too_big_change_are_dropped() {
+        let mut writer_proxy = WriterProxy::new(GUID_t::GUID_UNKNOWN, vec![], vec![]);
+
+        assert!(writer_proxy
+            .data_frag_received(&data_frag(1, u32::MAX), 1024)
+            .is_none());
+        assert!(fragmented_seq_nums(&writer_proxy).is_empty());
+        assert!(writer_proxy
+            .data_frag_received(&data_frag(1, 4), 4)
+            .is_some());
+    }
+
+    #[test]
+    fn fragmented_changes_are_limited_to_the_oldest() {
+        let mut writer_proxy = WriterProxy::new(GUID_t::GUID_UNKNOWN, vec![], vec![]);
+
+        for writer_sn in 2..=6 {
+            writer_proxy.data_frag_received(&data_frag(writer_sn, 8), 1024);
+        }
+        assert_eq!(seq_nums(&[2, 3, 4, 5]), fragmented_seq_nums(&writer_proxy));
+
+        writer_proxy.data_frag_received(&data_frag(1, 8), 1024);
+        assert_eq!(seq_nums(&[1, 2, 3, 4]), fragmented_seq_nums(&writer_proxy));
+    }
+}
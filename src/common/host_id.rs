@@ -0,0 +1,82 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::OnceLock;
+use std::time::SystemTime;
+
+/// Identifier of the host shared by all its processes; the machine id or,
+/// when not available, the boot id. When neither is available, a random
+/// identifier of the process: the processes do not know they share the
+/// host, so they do not communicate through shared memory.
+pub fn host_id() -> [u8; 16] {
+    static HOST_ID: OnceLock<[u8; 16]> = OnceLock::new();
+    *HOST_ID.get_or_init(|| {
+        ["/etc/machine-id", "/proc/sys/kernel/random/boot_id"]
+            .iter()
+            .filter_map(|path| std::fs::read_to_string(path).ok())
+            .find_map(|id| parse_host_id(&id))
+            .unwrap_or_else(random_id)
+    })
+}
+
+/// Random identifier from the randomly keyed hashers of the standard
+/// library, the process id and the time.
+fn random_id() -> [u8; 16] {
+    let mut id = [0x00; 16];
+    for half in id.chunks_mut(8) {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u32(std::process::id());
+        if let Ok(elapsed) = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+            hasher.write_u128(elapsed.as_nanos());
+        }
+        half.copy_from_slice(&hasher.finish().to_be_bytes());
+    }
+    id
+}
+
+fn parse_host_id(id: &str) -> Option<[u8; 16]> {
+    let digits: Vec<u8> = id
+        .trim()
+        .chars()
+        .filter(|c| *c != '-')
+        .map(|c| c.to_digit(16).map(|digit| digit as u8))
+        .collect::<Option<_>>()?;
+    if digits.len() != 32 {
+        return None;
+    }
+
+    let mut host_id = [0x00; 16];
+    for (byte, pair) in host_id.iter_mut().zip(digits.chunks(2)) {
+        *byte = pair[0] << 4 | pair[1];
+    }
+    Some(host_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn host_id_is_parsed_from_machine_id_and_boot_id() {
+        let expected = [
+            0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0x01, 0x23, 0x45, 0x67, 0x89, 0xab,
+            0xcd, 0xef,
+        ];
+
+        assert_eq!(
+            Some(expected),
+            parse_host_id("0123456789abcdef0123456789abcdef\n")
+        );
+        assert_eq!(
+            Some(expected),
+            parse_host_id("01234567-89ab-cdef-0123-456789abcdef\n")
+        );
+        assert_eq!(None, parse_host_id("0123"));
+        assert_eq!(None, parse_host_id("x123456789abcdef0123456789abcdef"));
+    }
+
+    #[test]
+    fn random_ids_differ() {
+        assert_ne!(random_id(), random_id());
+        assert_ne!([0x00; 16], random_id());
+    }
+}
@@ -1,3 +1,4 @@
 pub mod bit_set;
+pub mod host_id;
 pub mod ranged_bit_set;
 pub mod validity_trait;
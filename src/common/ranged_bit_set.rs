@@ -26,12 +26,25 @@ where
         }
     }
 
+    pub fn base(&self) -> B
+    where
+        B: Copy,
+    {
+        self.base
+    }
+
     pub fn insert(&mut self, value: B) -> bool {
         match self.normalize(value) {
             Some(normalized) => self.set.insert(normalized),
             None => false,
         }
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = B> + '_ {
+        self.set
+            .iter()
+            .map(move |i| B::from_usize(i).unwrap().checked_add(&self.base).unwrap())
+    }
 }
 
 pub struct RangedBitSetIntoIter<B> {
@@ -107,6 +120,11 @@ mod tests {
         assert!(set.insert(102));
         assert!(set.insert(100 + 255));
 
+        assert_eq!(
+            vec![100, 101, 102, 100 + 255],
+            set.iter().collect::<Vec<_>>()
+        );
+
         let mut iter = set.into_iter();
         assert_eq!(Some(100), iter.next());
         assert_eq!(Some(101), iter.next());
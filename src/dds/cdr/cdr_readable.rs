@@ -0,0 +1,90 @@
+use crate::dds::cdr::cdr_reader::CdrReader;
+
+use std::convert::TryInto;
+use std::io::Error;
+
+/// A value deserialized from the plain CDR representation, see CdrWritable.
+pub trait CdrReadable: Sized {
+    fn read_cdr(reader: &mut CdrReader<'_>) -> Result<Self, Error>;
+}
+
+macro_rules! readable_primitive {
+    ($type:ty, $read:ident) => {
+        impl CdrReadable for $type {
+            fn read_cdr(reader: &mut CdrReader<'_>) -> Result<Self, Error> {
+                reader.$read()
+            }
+        }
+    };
+}
+
+readable_primitive!(u8, read_u8);
+readable_primitive!(i8, read_i8);
+readable_primitive!(u16, read_u16);
+readable_primitive!(i16, read_i16);
+readable_primitive!(u32, read_u32);
+readable_primitive!(i32, read_i32);
+readable_primitive!(u64, read_u64);
+readable_primitive!(i64, read_i64);
+readable_primitive!(f32, read_f32);
+readable_primitive!(f64, read_f64);
+readable_primitive!(bool, read_bool);
+readable_primitive!(String, read_string);
+
+impl CdrReadable for () {
+    fn read_cdr(_reader: &mut CdrReader<'_>) -> Result<Self, Error> {
+        Ok(())
+    }
+}
+
+impl<T: CdrReadable> CdrReadable for Vec<T> {
+    fn read_cdr(reader: &mut CdrReader<'_>) -> Result<Self, Error> {
+        let length = reader.read_sequence_length()?;
+        (0..length).map(|_| T::read_cdr(reader)).collect()
+    }
+}
+
+impl<T: CdrReadable, const N: usize> CdrReadable for [T; N] {
+    fn read_cdr(reader: &mut CdrReader<'_>) -> Result<Self, Error> {
+        let elements = (0..N)
+            .map(|_| T::read_cdr(reader))
+            .collect::<Result<Vec<T>, Error>>()?;
+        Ok(elements
+            .try_into()
+            .unwrap_or_else(|_| unreachable!("The N elements are read")))
+    }
+}
+
+macro_rules! readable_tuple {
+    ($($member:ident),+) => {
+        impl<$($member: CdrReadable),+> CdrReadable for ($($member,)+) {
+            fn read_cdr(reader: &mut CdrReader<'_>) -> Result<Self, Error> {
+                Ok(($($member::read_cdr(reader)?,)+))
+            }
+        }
+    };
+}
+
+readable_tuple!(A, B);
+readable_tuple!(A, B, C);
+readable_tuple!(A, B, C, D);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dds::cdr::cdr_writable::CdrWritable;
+    use crate::dds::cdr::cdr_writer::CdrWriter;
+    use speedy::Endianness;
+
+    #[test]
+    fn values_are_read_as_written() {
+        for endianness in [Endianness::LittleEndian, Endianness::BigEndian] {
+            let value = (vec![1u16, 2, 3], [4u8, 5], (String::from("A"), -6i64, true));
+            let mut writer = CdrWriter::new(endianness);
+            value.write_cdr(&mut writer);
+            let serialized = writer.into_vec();
+            let mut reader = CdrReader::new(&serialized, endianness);
+            assert_eq!(value, CdrReadable::read_cdr(&mut reader).unwrap());
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use speedy::Endianness;
+use std::convert::TryInto;
+use std::io::{Error, ErrorKind};
+
+/// Deserializes values with the plain CDR representation, see CdrWriter.
+pub struct CdrReader<'a> {
+    buffer: &'a [u8],
+    position: usize,
+    endianness: Endianness,
+}
+
+macro_rules! read_primitive {
+    ($name:ident, $type:ty) => {
+        pub fn $name(&mut self) -> Result<$type, Error> {
+            self.align(std::mem::size_of::<$type>());
+            let bytes = self.read_octets(std::mem::size_of::<$type>())?;
+            let bytes = bytes.try_into().expect("The size of the primitive is read");
+            Ok(match self.endianness {
+                Endianness::LittleEndian => <$type>::from_le_bytes(bytes),
+                Endianness::BigEndian => <$type>::from_be_bytes(bytes),
+            })
+        }
+    };
+}
+
+impl<'a> CdrReader<'a> {
+    pub fn new(buffer: &'a [u8], endianness: Endianness) -> Self {
+        CdrReader {
+            buffer,
+            position: 0,
+            endianness,
+        }
+    }
+
+    pub fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
+    /// Skips the padding up to the multiple of the alignment.
+    fn align(&mut self, alignment: usize) {
+        self.position += (alignment - self.position % alignment) % alignment;
+    }
+
+    read_primitive!(read_u8, u8);
+    read_primitive!(read_i8, i8);
+    read_primitive!(read_u16, u16);
+    read_primitive!(read_i16, i16);
+    read_primitive!(read_u32, u32);
+    read_primitive!(read_i32, i32);
+    read_primitive!(read_u64, u64);
+    read_primitive!(read_i64, i64);
+    read_primitive!(read_f32, f32);
+    read_primitive!(read_f64, f64);
+
+    pub fn read_bool(&mut self) -> Result<bool, Error> {
+        match self.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(invalid_data("Invalid boolean")),
+        }
+    }
+
+    /// Reads the next octets as they are.
+    pub fn read_octets(&mut self, length: usize) -> Result<&'a [u8], Error> {
+        let end = self
+            .position
+            .checked_add(length)
+            .filter(|end| *end <= self.buffer.len())
+            .ok_or_else(|| invalid_data("Unexpected end of the CDR data"))?;
+        let octets = &self.buffer[self.position..end];
+        self.position = end;
+        Ok(octets)
+    }
+
+    /// Reads the string preceded by its length, which includes the
+    /// terminating NUL.
+    pub fn read_string(&mut self) -> Result<String, Error> {
+        let length = self.read_u32()? as usize;
+        let octets = self.read_octets(length)?;
+        match octets.split_last() {
+            Some((0, characters)) => String::from_utf8(characters.to_vec())
+                .map_err(|_| invalid_data("The string is not UTF-8")),
+            _ => Err(invalid_data("The string is not terminated by NUL")),
+        }
+    }
+
+    /// Reads the number of elements of a sequence, which cannot exceed the
+    /// number of the remaining octets as each element has one at least.
+    pub fn read_sequence_length(&mut self) -> Result<usize, Error> {
+        let length = self.read_u32()? as usize;
+        if length > self.buffer.len().saturating_sub(self.position) {
+            return Err(invalid_data("Unexpected end of the CDR data"));
+        }
+        Ok(length)
+    }
+}
+
+fn invalid_data(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn primitives_are_read_after_their_alignment() {
+        #[rustfmt::skip]
+        let data = [
+            0x01, 0x00, 0x02, 0x03,
+            0x04, 0x00, 0x00, 0x00,
+            0x05, 0x06, 0x07, 0x08,
+            0x00, 0x00, 0x00, 0x00,
+            0xBF, 0xF0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let mut reader = CdrReader::new(&data, Endianness::BigEndian);
+        assert_eq!(1, reader.read_u8().unwrap());
+        assert_eq!(0x0203, reader.read_u16().unwrap());
+        assert_eq!(4, reader.read_i8().unwrap());
+        assert_eq!(0x0506_0708, reader.read_u32().unwrap());
+        assert_eq!(-1.0, reader.read_f64().unwrap());
+        assert_eq!(ErrorKind::InvalidData, reader.read_u8().unwrap_err().kind());
+    }
+
+    #[test]
+    fn strings_are_terminated_by_nul() {
+        let data = [0x04, 0x00, 0x00, 0x00, b'R', b'E', b'D', 0x00];
+        let mut reader = CdrReader::new(&data, Endianness::LittleEndian);
+        assert_eq!("RED", reader.read_string().unwrap());
+
+        for data in [
+            &[0x03, 0x00, 0x00, 0x00, b'R', b'E', b'D'][..],
+            &[0x00, 0x00, 0x00, 0x00],
+            &[0x09, 0x00, 0x00, 0x00, b'R', b'E', b'D', 0x00],
+        ] {
+            let mut reader = CdrReader::new(data, Endianness::LittleEndian);
+            assert!(reader.read_string().is_err());
+        }
+    }
+
+    #[test]
+    fn sequence_lengths_beyond_the_data_are_rejected() {
+        let data = [0xFF, 0xFF, 0xFF, 0xFF, 0x00];
+        let mut reader = CdrReader::new(&data, Endianness::LittleEndian);
+        assert!(reader.read_sequence_length().is_err());
+    }
+}
@@ -0,0 +1,111 @@
+use crate::dds::cdr::cdr_writer::CdrWriter;
+
+/// A value serialized with the plain CDR representation: the members of a
+/// structure are written in their order of declaration.
+pub trait CdrWritable {
+    fn write_cdr(&self, writer: &mut CdrWriter);
+}
+
+macro_rules! writable_primitive {
+    ($type:ty, $write:ident) => {
+        impl CdrWritable for $type {
+            fn write_cdr(&self, writer: &mut CdrWriter) {
+                writer.$write(*self)
+            }
+        }
+    };
+}
+
+writable_primitive!(u8, write_u8);
+writable_primitive!(i8, write_i8);
+writable_primitive!(u16, write_u16);
+writable_primitive!(i16, write_i16);
+writable_primitive!(u32, write_u32);
+writable_primitive!(i32, write_i32);
+writable_primitive!(u64, write_u64);
+writable_primitive!(i64, write_i64);
+writable_primitive!(f32, write_f32);
+writable_primitive!(f64, write_f64);
+writable_primitive!(bool, write_bool);
+
+/// The value of the type without members, e.g. the key of the type without
+/// key.
+impl CdrWritable for () {
+    fn write_cdr(&self, _writer: &mut CdrWriter) {}
+}
+
+impl CdrWritable for str {
+    fn write_cdr(&self, writer: &mut CdrWriter) {
+        writer.write_string(self)
+    }
+}
+
+impl CdrWritable for String {
+    fn write_cdr(&self, writer: &mut CdrWriter) {
+        writer.write_string(self)
+    }
+}
+
+/// A sequence, preceded by its number of elements.
+impl<T: CdrWritable> CdrWritable for Vec<T> {
+    fn write_cdr(&self, writer: &mut CdrWriter) {
+        writer.write_sequence_length(self.len());
+        for element in self {
+            element.write_cdr(writer);
+        }
+    }
+}
+
+/// An array, of a fixed number of elements.
+impl<T: CdrWritable, const N: usize> CdrWritable for [T; N] {
+    fn write_cdr(&self, writer: &mut CdrWriter) {
+        for element in self {
+            element.write_cdr(writer);
+        }
+    }
+}
+
+impl<T: CdrWritable + ?Sized> CdrWritable for &T {
+    fn write_cdr(&self, writer: &mut CdrWriter) {
+        (**self).write_cdr(writer)
+    }
+}
+
+/// The members of a structure, e.g. the key of several fields.
+macro_rules! writable_tuple {
+    ($($member:ident: $index:tt),+) => {
+        impl<$($member: CdrWritable),+> CdrWritable for ($($member,)+) {
+            fn write_cdr(&self, writer: &mut CdrWriter) {
+                $(self.$index.write_cdr(writer);)+
+            }
+        }
+    };
+}
+
+writable_tuple!(A: 0, B: 1);
+writable_tuple!(A: 0, B: 1, C: 2);
+writable_tuple!(A: 0, B: 1, C: 2, D: 3);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use speedy::Endianness;
+
+    #[test]
+    fn sequences_arrays_and_structures_are_serialized() {
+        let mut writer = CdrWriter::new(Endianness::LittleEndian);
+        (vec![1u16, 2, 3], [4u8, 5], (String::from("A"), 6i64)).write_cdr(&mut writer);
+        #[rustfmt::skip]
+        assert_eq!(
+            vec![
+                0x03, 0x00, 0x00, 0x00, // length
+                0x01, 0x00, 0x02, 0x00, 0x03, 0x00, // sequence
+                0x04, 0x05, // array
+                0x02, 0x00, 0x00, 0x00, b'A', 0x00, // string
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // padding
+                0x06, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            ],
+            writer.into_vec()
+        );
+    }
+}
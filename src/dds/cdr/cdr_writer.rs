@@ -0,0 +1,127 @@
+use speedy::Endianness;
+
+/// Serializes values with the plain CDR representation of the CDR_BE and
+/// CDR_LE encapsulations (10.2): the primitive values are aligned to their
+/// size relative to the start of the serialized data, the strings are
+/// preceded by their length including the terminating NUL and the sequences
+/// by their number of elements.
+pub struct CdrWriter {
+    buffer: Vec<u8>,
+    endianness: Endianness,
+}
+
+macro_rules! write_primitive {
+    ($name:ident, $type:ty) => {
+        pub fn $name(&mut self, value: $type) {
+            self.align(std::mem::size_of::<$type>());
+            match self.endianness {
+                Endianness::LittleEndian => self.buffer.extend_from_slice(&value.to_le_bytes()),
+                Endianness::BigEndian => self.buffer.extend_from_slice(&value.to_be_bytes()),
+            }
+        }
+    };
+}
+
+impl CdrWriter {
+    pub fn new(endianness: Endianness) -> Self {
+        CdrWriter {
+            buffer: vec![],
+            endianness,
+        }
+    }
+
+    pub fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
+    /// The serialized data.
+    pub fn into_vec(self) -> Vec<u8> {
+        self.buffer
+    }
+
+    /// Pads the serialized data with zeros up to the multiple of the
+    /// alignment.
+    fn align(&mut self, alignment: usize) {
+        let padding = (alignment - self.buffer.len() % alignment) % alignment;
+        self.buffer.resize(self.buffer.len() + padding, 0);
+    }
+
+    write_primitive!(write_u8, u8);
+    write_primitive!(write_i8, i8);
+    write_primitive!(write_u16, u16);
+    write_primitive!(write_i16, i16);
+    write_primitive!(write_u32, u32);
+    write_primitive!(write_i32, i32);
+    write_primitive!(write_u64, u64);
+    write_primitive!(write_i64, i64);
+    write_primitive!(write_f32, f32);
+    write_primitive!(write_f64, f64);
+
+    pub fn write_bool(&mut self, value: bool) {
+        self.write_u8(value as u8)
+    }
+
+    /// Writes the octets as they are, e.g. the elements of an array of
+    /// octets.
+    pub fn write_octets(&mut self, octets: &[u8]) {
+        self.buffer.extend_from_slice(octets)
+    }
+
+    /// Writes the length of the string including the terminating NUL, its
+    /// characters and the NUL.
+    pub fn write_string(&mut self, value: &str) {
+        self.write_u32(value.len() as u32 + 1);
+        self.buffer.extend_from_slice(value.as_bytes());
+        self.buffer.push(0);
+    }
+
+    /// Writes the number of elements of a sequence, followed by the
+    /// elements.
+    pub fn write_sequence_length(&mut self, length: usize) {
+        self.write_u32(length as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn primitives_are_aligned_to_their_size() {
+        let mut writer = CdrWriter::new(Endianness::LittleEndian);
+        writer.write_u8(1);
+        writer.write_u16(0x0203);
+        writer.write_u8(4);
+        writer.write_u32(0x0506_0708);
+        writer.write_bool(true);
+        writer.write_u64(0x090A_0B0C_0D0E_0F10);
+        #[rustfmt::skip]
+        assert_eq!(
+            vec![
+                0x01, 0x00, 0x03, 0x02,
+                0x04, 0x00, 0x00, 0x00,
+                0x08, 0x07, 0x06, 0x05,
+                0x01, 0x00, 0x00, 0x00,
+                0x10, 0x0F, 0x0E, 0x0D, 0x0C, 0x0B, 0x0A, 0x09,
+            ],
+            writer.into_vec()
+        );
+    }
+
+    #[test]
+    fn strings_have_their_length_and_a_terminating_nul() {
+        let mut writer = CdrWriter::new(Endianness::BigEndian);
+        writer.write_string("RED");
+        writer.write_string("");
+        writer.write_f64(1.0);
+        #[rustfmt::skip]
+        assert_eq!(
+            vec![
+                0x00, 0x00, 0x00, 0x04, b'R', b'E', b'D', 0x00,
+                0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+                0x3F, 0xF0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            ],
+            writer.into_vec()
+        );
+    }
+}
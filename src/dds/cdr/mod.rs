@@ -0,0 +1,4 @@
+pub mod cdr_readable;
+pub mod cdr_reader;
+pub mod cdr_writable;
+pub mod cdr_writer;
@@ -0,0 +1,61 @@
+use crate::dds::condition::guard_condition::GuardCondition;
+use crate::dds::condition::query_condition::QueryCondition;
+use crate::dds::condition::read_condition::ReadCondition;
+use crate::dds::condition::status_condition::StatusCondition;
+
+use std::task::Context;
+
+/// The conditions a WaitSet waits for (2.2.2.1.7).
+#[derive(Clone, Debug, PartialEq)]
+pub enum Condition {
+    Guard(GuardCondition),
+    Status(StatusCondition),
+    Read(ReadCondition),
+    Query(QueryCondition),
+}
+
+impl From<GuardCondition> for Condition {
+    fn from(condition: GuardCondition) -> Self {
+        Condition::Guard(condition)
+    }
+}
+
+impl From<StatusCondition> for Condition {
+    fn from(condition: StatusCondition) -> Self {
+        Condition::Status(condition)
+    }
+}
+
+impl From<ReadCondition> for Condition {
+    fn from(condition: ReadCondition) -> Self {
+        Condition::Read(condition)
+    }
+}
+
+impl From<QueryCondition> for Condition {
+    fn from(condition: QueryCondition) -> Self {
+        Condition::Query(condition)
+    }
+}
+
+impl Condition {
+    pub fn get_trigger_value(&self) -> bool {
+        match self {
+            Condition::Guard(condition) => condition.get_trigger_value(),
+            Condition::Status(condition) => condition.get_trigger_value(),
+            Condition::Read(condition) => condition.get_trigger_value(),
+            Condition::Query(condition) => condition.get_trigger_value(),
+        }
+    }
+
+    /// Registers the task to be woken when the trigger value may have
+    /// changed.
+    pub(crate) fn register_waker(&self, cx: &Context<'_>) {
+        match self {
+            Condition::Guard(condition) => condition.register_waker(cx),
+            Condition::Status(condition) => condition.register_waker(cx),
+            Condition::Read(condition) => condition.register_waker(cx),
+            Condition::Query(condition) => condition.register_waker(cx),
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use crate::dds::condition::wakers::Wakers;
+
+use std::sync::{Arc, Mutex};
+use std::task::Context;
+
+/// A Condition whose trigger value is set by the application (2.2.2.1.8),
+/// e.g. to stop a thread waiting on a WaitSet.
+#[derive(Clone, Debug, Default)]
+pub struct GuardCondition {
+    state: Arc<Mutex<GuardConditionState>>,
+}
+
+#[derive(Debug, Default)]
+struct GuardConditionState {
+    trigger_value: bool,
+
+    /// The WaitSets waiting for the trigger value.
+    wakers: Wakers,
+}
+
+impl PartialEq for GuardCondition {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.state, &other.state)
+    }
+}
+
+impl GuardCondition {
+    pub fn new() -> Self {
+        GuardCondition::default()
+    }
+
+    pub fn get_trigger_value(&self) -> bool {
+        self.state.lock().unwrap().trigger_value
+    }
+
+    /// Sets the trigger value, waking the WaitSets waiting for it.
+    pub fn set_trigger_value(&self, trigger_value: bool) {
+        let mut state = self.state.lock().unwrap();
+        state.trigger_value = trigger_value;
+        if trigger_value {
+            state.wakers.wake();
+        }
+    }
+
+    pub(crate) fn register_waker(&self, cx: &Context<'_>) {
+        self.state.lock().unwrap().wakers.register(cx)
+    }
+}
@@ -0,0 +1,8 @@
+#[allow(clippy::module_inception)]
+pub mod condition;
+pub mod guard_condition;
+pub mod query_condition;
+pub mod read_condition;
+pub mod status_condition;
+pub mod wait_set;
+pub mod wakers;
@@ -0,0 +1,44 @@
+use crate::dds::condition::read_condition::ReadCondition;
+
+use std::ops::Deref;
+
+/// A ReadCondition which also requires the data of the samples to pass a
+/// filter expression (2.2.2.5.9), created by
+/// DataReader::create_querycondition. Like a ReadCondition, it selects the
+/// samples of DataReader::read_w_condition and take_w_condition.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueryCondition {
+    read_condition: ReadCondition,
+    query_expression: String,
+    query_parameters: Vec<String>,
+}
+
+impl Deref for QueryCondition {
+    type Target = ReadCondition;
+
+    fn deref(&self) -> &ReadCondition {
+        &self.read_condition
+    }
+}
+
+impl QueryCondition {
+    pub(crate) fn new(
+        read_condition: ReadCondition,
+        query_expression: &str,
+        query_parameters: &[String],
+    ) -> Self {
+        QueryCondition {
+            read_condition,
+            query_expression: query_expression.to_string(),
+            query_parameters: query_parameters.to_vec(),
+        }
+    }
+
+    pub fn get_query_expression(&self) -> &str {
+        &self.query_expression
+    }
+
+    pub fn get_query_parameters(&self) -> &[String] {
+        &self.query_parameters
+    }
+}
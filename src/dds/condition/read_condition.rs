@@ -0,0 +1,127 @@
+use crate::dds::domain_participant::DomainParticipant;
+use crate::dds::filter::filter_expression::FilterExpression;
+use crate::dds::filter::filter_fields::SampleDecoder;
+use crate::dds::instance_state_kind::InstanceStateKind;
+use crate::dds::sample_info::SampleInfo;
+use crate::dds::sample_state_kind::SampleStateKind;
+use crate::dds::view_state_kind::ViewStateKind;
+use crate::structure::guid::GUID_t;
+
+use std::sync::Arc;
+use std::task::Context;
+
+/// The Condition of a DataReader triggered while it has samples in one of
+/// the sample states, view states and instance states (2.2.2.5.8), created
+/// by DataReader::create_readcondition. The samples are accessed with
+/// DataReader::read_w_condition and take_w_condition.
+#[derive(Clone)]
+pub struct ReadCondition {
+    participant: DomainParticipant,
+    reader: GUID_t,
+    selection: Arc<SampleSelection>,
+}
+
+/// The samples accessed by the read and take operations of a DataReader:
+/// the ones in the states passing the query, if any. The samples the query
+/// cannot be evaluated for are not selected.
+pub(crate) struct SampleSelection {
+    pub sample_states: Vec<SampleStateKind>,
+    pub view_states: Vec<ViewStateKind>,
+    pub instance_states: Vec<InstanceStateKind>,
+    pub query: Option<(FilterExpression, SampleDecoder)>,
+}
+
+impl SampleSelection {
+    pub fn new(
+        sample_states: &[SampleStateKind],
+        view_states: &[ViewStateKind],
+        instance_states: &[InstanceStateKind],
+    ) -> Self {
+        SampleSelection {
+            sample_states: sample_states.to_vec(),
+            view_states: view_states.to_vec(),
+            instance_states: instance_states.to_vec(),
+            query: None,
+        }
+    }
+
+    /// Whether the sample of the info is in the states, the query is
+    /// evaluated separately on its data.
+    pub fn is_selected(&self, info: &SampleInfo) -> bool {
+        self.sample_states.contains(&info.sample_state)
+            && self.view_states.contains(&info.view_state)
+            && self.instance_states.contains(&info.instance_state)
+    }
+
+    /// Whether the serialized data passes the query.
+    pub fn is_queried(&self, serialized_data: &[u8]) -> bool {
+        match &self.query {
+            Some((filter_expression, sample_decoder)) => sample_decoder(serialized_data)
+                .and_then(|fields| filter_expression.evaluate(&*fields))
+                .unwrap_or(false),
+            None => true,
+        }
+    }
+}
+
+impl PartialEq for ReadCondition {
+    fn eq(&self, other: &Self) -> bool {
+        self.participant == other.participant
+            && self.reader == other.reader
+            && Arc::ptr_eq(&self.selection, &other.selection)
+    }
+}
+
+impl std::fmt::Debug for ReadCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReadCondition")
+            .field("reader", &self.reader)
+            .field("sample_states", &self.selection.sample_states)
+            .field("view_states", &self.selection.view_states)
+            .field("instance_states", &self.selection.instance_states)
+            .finish()
+    }
+}
+
+impl ReadCondition {
+    pub(crate) fn new(
+        participant: DomainParticipant,
+        reader: GUID_t,
+        selection: SampleSelection,
+    ) -> Self {
+        ReadCondition {
+            participant,
+            reader,
+            selection: Arc::new(selection),
+        }
+    }
+
+    /// The GUID of the DataReader of the ReadCondition.
+    pub fn reader(&self) -> GUID_t {
+        self.reader
+    }
+
+    pub fn get_trigger_value(&self) -> bool {
+        self.participant.has_samples(self.reader, &self.selection)
+    }
+
+    pub fn get_sample_state_mask(&self) -> &[SampleStateKind] {
+        &self.selection.sample_states
+    }
+
+    pub fn get_view_state_mask(&self) -> &[ViewStateKind] {
+        &self.selection.view_states
+    }
+
+    pub fn get_instance_state_mask(&self) -> &[InstanceStateKind] {
+        &self.selection.instance_states
+    }
+
+    pub(crate) fn selection(&self) -> &SampleSelection {
+        &self.selection
+    }
+
+    pub(crate) fn register_waker(&self, cx: &Context<'_>) {
+        self.participant.register_condition_waker(cx)
+    }
+}
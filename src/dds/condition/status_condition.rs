@@ -0,0 +1,46 @@
+use crate::dds::domain_participant::DomainParticipant;
+use crate::dds::status::status_kind::StatusMask;
+use crate::structure::guid::GUID_t;
+
+use std::io::Error;
+use std::task::Context;
+
+/// The Condition of an entity triggered while one of its enabled
+/// communication statuses changed since it was last read (2.2.2.1.9). Each
+/// entity has a single StatusCondition, all statuses are enabled initially.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatusCondition {
+    participant: DomainParticipant,
+    entity: GUID_t,
+}
+
+impl StatusCondition {
+    pub(crate) fn new(participant: DomainParticipant, entity: GUID_t) -> Self {
+        StatusCondition {
+            participant,
+            entity,
+        }
+    }
+
+    /// The GUID of the entity of the StatusCondition.
+    pub fn entity(&self) -> GUID_t {
+        self.entity
+    }
+
+    pub fn get_trigger_value(&self) -> bool {
+        self.participant.is_status_condition_triggered(self.entity)
+    }
+
+    pub fn get_enabled_statuses(&self) -> Result<StatusMask, Error> {
+        self.participant.enabled_statuses(self.entity)
+    }
+
+    /// Replaces the statuses triggering the StatusCondition.
+    pub fn set_enabled_statuses(&self, mask: StatusMask) -> Result<(), Error> {
+        self.participant.set_enabled_statuses(self.entity, mask)
+    }
+
+    pub(crate) fn register_waker(&self, cx: &Context<'_>) {
+        self.participant.register_condition_waker(cx)
+    }
+}
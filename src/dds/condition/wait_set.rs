@@ -0,0 +1,116 @@
+use crate::dds::condition::condition::Condition;
+use crate::dds::condition::wakers::block_until;
+use crate::dds::domain_participant::precondition_not_met;
+
+use futures::future::poll_fn;
+use std::io::{Error, ErrorKind};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+/// Waits for one of the attached conditions to trigger (2.2.2.1.6), either
+/// blocking the thread with wait or as a future with wait_async.
+///
+/// The conditions of the entities change as their participant is driven:
+/// the thread or task driving the participant must not be the one waiting.
+#[derive(Clone, Debug, Default)]
+pub struct WaitSet {
+    conditions: Vec<Condition>,
+}
+
+impl WaitSet {
+    pub fn new() -> Self {
+        WaitSet::default()
+    }
+
+    /// Attaches the condition; attaching it again has no effect.
+    pub fn attach_condition(&mut self, condition: impl Into<Condition>) {
+        let condition = condition.into();
+        if !self.conditions.contains(&condition) {
+            self.conditions.push(condition);
+        }
+    }
+
+    pub fn detach_condition(&mut self, condition: impl Into<Condition>) -> Result<(), Error> {
+        let condition = condition.into();
+        match self
+            .conditions
+            .iter()
+            .position(|attached| *attached == condition)
+        {
+            Some(index) => {
+                self.conditions.remove(index);
+                Ok(())
+            }
+            None => Err(precondition_not_met("The condition is not attached")),
+        }
+    }
+
+    pub fn get_conditions(&self) -> &[Condition] {
+        &self.conditions
+    }
+
+    /// Blocks until some of the attached conditions trigger and returns
+    /// them. Fails with TimedOut if none triggered within the timeout.
+    pub fn wait(&self, timeout: Duration) -> Result<Vec<Condition>, Error> {
+        block_until(Some(Instant::now() + timeout), |cx| self.poll_wait(cx))
+            .ok_or_else(|| Error::new(ErrorKind::TimedOut, "No condition triggered"))
+    }
+
+    /// Waits for some of the attached conditions to trigger and returns
+    /// them, see wait.
+    pub async fn wait_async(&self) -> Vec<Condition> {
+        poll_fn(|cx| self.poll_wait(cx)).await
+    }
+
+    /// The triggered conditions, the task is woken when they may change.
+    fn poll_wait(&self, cx: &mut Context<'_>) -> Poll<Vec<Condition>> {
+        // Registered first not to miss a change before returning Pending
+        for condition in &self.conditions {
+            condition.register_waker(cx);
+        }
+        let triggered: Vec<Condition> = self
+            .conditions
+            .iter()
+            .filter(|condition| condition.get_trigger_value())
+            .cloned()
+            .collect();
+        if triggered.is_empty() {
+            Poll::Pending
+        } else {
+            Poll::Ready(triggered)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dds::condition::guard_condition::GuardCondition;
+    use std::thread;
+
+    #[test]
+    fn wait_returns_the_triggered_conditions() {
+        let mut wait_set = WaitSet::new();
+        let first = GuardCondition::new();
+        let second = GuardCondition::new();
+        wait_set.attach_condition(first.clone());
+        wait_set.attach_condition(second.clone());
+        wait_set.attach_condition(second.clone());
+        assert_eq!(2, wait_set.get_conditions().len());
+        assert_eq!(
+            ErrorKind::TimedOut,
+            wait_set.wait(Duration::from_millis(1)).unwrap_err().kind()
+        );
+
+        let triggered = second.clone();
+        let trigger = thread::spawn(move || triggered.set_trigger_value(true));
+        assert_eq!(
+            vec![Condition::from(second)],
+            wait_set.wait(Duration::from_secs(10)).unwrap()
+        );
+        trigger.join().unwrap();
+
+        wait_set.detach_condition(first.clone()).unwrap();
+        assert!(wait_set.detach_condition(first).is_err());
+    }
+}
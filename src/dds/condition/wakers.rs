@@ -0,0 +1,90 @@
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::Instant;
+
+/// The tasks waiting for a condition, e.g. the trigger value of a Condition
+/// or room in the history of a DataWriter, woken once it may have changed.
+#[derive(Debug, Default)]
+pub(crate) struct Wakers {
+    wakers: Vec<Waker>,
+}
+
+impl Wakers {
+    /// Registers the task of the context, once.
+    pub fn register(&mut self, cx: &Context<'_>) {
+        if !self.wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+            self.wakers.push(cx.waker().clone());
+        }
+    }
+
+    /// Wakes the registered tasks, which register again if they keep
+    /// waiting.
+    pub fn wake(&mut self) {
+        for waker in self.wakers.drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+/// Unparks the thread waiting in block_until.
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark()
+    }
+}
+
+/// Polls on the current thread, parked until it is woken, until poll is
+/// ready. Returns None once the deadline passed; without a deadline, it
+/// waits indefinitely.
+pub(crate) fn block_until<T>(
+    deadline: Option<Instant>,
+    mut poll: impl FnMut(&mut Context<'_>) -> Poll<T>,
+) -> Option<T> {
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(value) = poll(&mut cx) {
+            return Some(value);
+        }
+        match deadline {
+            Some(deadline) => {
+                let now = Instant::now();
+                if now >= deadline {
+                    return None;
+                }
+                thread::park_timeout(deadline - now);
+            }
+            None => thread::park(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn tasks_are_registered_once_and_woken_once() {
+        let counter = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        let waker = Waker::from(counter.clone());
+        let cx = Context::from_waker(&waker);
+        let mut wakers = Wakers::default();
+        wakers.register(&cx);
+        wakers.register(&cx);
+        wakers.wake();
+        wakers.wake();
+        assert_eq!(1, counter.0.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,71 @@
+use crate::dds::domain_participant::DomainParticipant;
+use crate::dds::topic::Topic;
+use crate::discovery::content_filter_property::ContentFilterProperty_t;
+
+use std::io::Error;
+
+/// A Topic whose DataReaders only receive the samples passing a filter
+/// expression (2.2.2.3.3), created by
+/// DomainParticipant::create_contentfilteredtopic. The filter is announced
+/// to the matched DataWriters, which filter on behalf of the DataReader if
+/// their type has a SampleDecoder; the DataReader filters the other samples
+/// itself.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContentFilteredTopic {
+    name: String,
+    related_topic: Topic,
+    filter_expression: String,
+    expression_parameters: Vec<String>,
+}
+
+impl ContentFilteredTopic {
+    pub(crate) fn new(
+        name: &str,
+        related_topic: &Topic,
+        filter_expression: &str,
+        expression_parameters: &[String],
+    ) -> Self {
+        ContentFilteredTopic {
+            name: name.to_string(),
+            related_topic: related_topic.clone(),
+            filter_expression: filter_expression.to_string(),
+            expression_parameters: expression_parameters.to_vec(),
+        }
+    }
+
+    pub fn get_participant(&self) -> DomainParticipant {
+        self.related_topic.get_participant()
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// The type name of the related Topic.
+    pub fn get_type_name(&self) -> Result<String, Error> {
+        self.related_topic.get_type_name()
+    }
+
+    pub fn get_related_topic(&self) -> &Topic {
+        &self.related_topic
+    }
+
+    pub fn get_filter_expression(&self) -> &str {
+        &self.filter_expression
+    }
+
+    pub fn get_expression_parameters(&self) -> &[String] {
+        &self.expression_parameters
+    }
+
+    /// The filter announced by the DataReaders, in the DDSSQL filter class.
+    pub(crate) fn content_filter_property(&self) -> Result<ContentFilterProperty_t, Error> {
+        Ok(ContentFilterProperty_t {
+            content_filtered_topic_name: self.name.clone(),
+            related_topic_name: self.related_topic.get_name()?,
+            filter_class_name: ContentFilterProperty_t::DDSSQL.to_string(),
+            filter_expression: self.filter_expression.clone(),
+            extension_parameters: self.expression_parameters.clone(),
+        })
+    }
+}
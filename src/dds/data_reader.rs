@@ -0,0 +1,345 @@
+use crate::dds::condition::query_condition::QueryCondition;
+use crate::dds::condition::read_condition::{ReadCondition, SampleSelection};
+use crate::dds::condition::status_condition::StatusCondition;
+use crate::dds::domain_participant::{precondition_not_met, DomainParticipant};
+use crate::dds::filter::filter_expression::FilterExpression;
+use crate::dds::filter::filter_fields::FilterFields;
+use crate::dds::instance_state_kind::{InstanceStateKind, ANY_INSTANCE_STATE};
+use crate::dds::listener::data_reader_listener::{notify_data_reader_listener, DataReaderListener};
+use crate::dds::qos::data_reader_qos::DataReaderQos;
+use crate::dds::sample::Sample;
+use crate::dds::sample_state_kind::{SampleStateKind, ANY_SAMPLE_STATE};
+use crate::dds::status::communication_status::CommunicationStatus;
+use crate::dds::status::deadline_missed_status::RequestedDeadlineMissedStatus;
+use crate::dds::status::incompatible_qos_status::RequestedIncompatibleQosStatus;
+use crate::dds::status::liveliness_changed_status::LivelinessChangedStatus;
+use crate::dds::status::sample_lost_status::SampleLostStatus;
+use crate::dds::status::sample_rejected_status::SampleRejectedStatus;
+use crate::dds::status::status_kind::{StatusKind, StatusMask};
+use crate::dds::status::subscription_matched_status::SubscriptionMatchedStatus;
+use crate::dds::subscriber::Subscriber;
+use crate::dds::topic::Topic;
+use crate::dds::type_support::{filter_fields_decoder, TypeSupport};
+use crate::dds::view_state_kind::{ViewStateKind, ANY_VIEW_STATE};
+use crate::discovery::pl_cdr;
+use crate::structure::guid::GUID_t;
+use crate::structure::instance_handle::InstanceHandle_t;
+
+use futures::Stream;
+use std::io::Error;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+/// Receives the data of a Topic (2.2.2.5.3), created by
+/// Subscriber::create_datareader. The enabled DataReader is backed by an
+/// RTPS Reader of the same GUID, announced to the discovered participants.
+///
+/// The changes received by the RTPS Reader are deserialized into values of
+/// T when they are read or taken.
+///
+/// The DataReader is also a Stream taking the samples not read yet; the
+/// stream ends when the DataReader is deleted or not enabled. With
+/// StreamExt in scope, the take operation of the DataReader is shadowed by
+/// StreamExt::take and is called as DataReader::take(&reader, max_samples).
+pub struct DataReader<T> {
+    participant: DomainParticipant,
+    guid: GUID_t,
+    data_type: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for DataReader<T> {
+    fn clone(&self) -> Self {
+        DataReader::new(self.participant.clone(), self.guid)
+    }
+}
+
+impl<T> PartialEq for DataReader<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.participant == other.participant && self.guid == other.guid
+    }
+}
+
+impl<T> std::fmt::Debug for DataReader<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DataReader")
+            .field("guid", &self.guid)
+            .finish()
+    }
+}
+
+impl<T> DataReader<T> {
+    pub(crate) fn new(participant: DomainParticipant, guid: GUID_t) -> Self {
+        DataReader {
+            participant,
+            guid,
+            data_type: PhantomData,
+        }
+    }
+
+    pub fn guid(&self) -> GUID_t {
+        self.guid
+    }
+
+    pub fn get_instance_handle(&self) -> InstanceHandle_t {
+        pl_cdr::key_hash(self.guid)
+    }
+
+    pub(crate) fn get_participant(&self) -> DomainParticipant {
+        self.participant.clone()
+    }
+
+    pub fn get_topicdescription(&self) -> Result<Topic, Error> {
+        self.participant.reader_topic(self.guid)
+    }
+
+    pub fn get_subscriber(&self) -> Result<Subscriber, Error> {
+        self.participant.reader_subscriber(self.guid)
+    }
+
+    pub fn get_qos(&self) -> Result<DataReaderQos, Error> {
+        self.participant.reader_qos(self.guid)
+    }
+
+    /// Replaces the QoS; the enabled DataReader is announced again. Changing
+    /// its immutable policies fails (IMMUTABLE_POLICY).
+    pub fn set_qos(&self, qos: DataReaderQos) -> Result<(), Error> {
+        self.participant.set_reader_qos(self.guid, qos)
+    }
+
+    /// Enables the DataReader of the enabled Subscriber and Topic: the RTPS
+    /// Reader is created and announced.
+    pub fn enable(&self) -> Result<(), Error> {
+        self.participant.enable_reader(self.guid)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.participant.is_reader_enabled(self.guid)
+    }
+
+    /// The handles of the DataWriters currently matched with the DataReader.
+    pub fn get_matched_publications(&self) -> Result<Vec<InstanceHandle_t>, Error> {
+        self.participant.matched_publications(self.guid)
+    }
+
+    /// The StatusCondition triggered by the changes of the enabled statuses
+    /// of the DataReader.
+    pub fn get_statuscondition(&self) -> StatusCondition {
+        StatusCondition::new(self.participant.clone(), self.guid)
+    }
+
+    /// The statuses of the DataReader changed since they were last read.
+    pub fn get_status_changes(&self) -> Result<StatusKind, Error> {
+        self.participant.status_changes(self.guid)
+    }
+
+    pub fn get_sample_lost_status(&self) -> Result<SampleLostStatus, Error> {
+        match self
+            .participant
+            .read_status(self.guid, StatusKind::SAMPLE_LOST)?
+        {
+            CommunicationStatus::SampleLost(status) => Ok(status),
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn get_sample_rejected_status(&self) -> Result<SampleRejectedStatus, Error> {
+        match self
+            .participant
+            .read_status(self.guid, StatusKind::SAMPLE_REJECTED)?
+        {
+            CommunicationStatus::SampleRejected(status) => Ok(status),
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn get_requested_deadline_missed_status(
+        &self,
+    ) -> Result<RequestedDeadlineMissedStatus, Error> {
+        match self
+            .participant
+            .read_status(self.guid, StatusKind::REQUESTED_DEADLINE_MISSED)?
+        {
+            CommunicationStatus::RequestedDeadlineMissed(status) => Ok(status),
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn get_requested_incompatible_qos_status(
+        &self,
+    ) -> Result<RequestedIncompatibleQosStatus, Error> {
+        match self
+            .participant
+            .read_status(self.guid, StatusKind::REQUESTED_INCOMPATIBLE_QOS)?
+        {
+            CommunicationStatus::RequestedIncompatibleQos(status) => Ok(status),
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn get_liveliness_changed_status(&self) -> Result<LivelinessChangedStatus, Error> {
+        match self
+            .participant
+            .read_status(self.guid, StatusKind::LIVELINESS_CHANGED)?
+        {
+            CommunicationStatus::LivelinessChanged(status) => Ok(status),
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn get_subscription_matched_status(&self) -> Result<SubscriptionMatchedStatus, Error> {
+        match self
+            .participant
+            .read_status(self.guid, StatusKind::SUBSCRIPTION_MATCHED)?
+        {
+            CommunicationStatus::SubscriptionMatched(status) => Ok(status),
+            _ => unreachable!(),
+        }
+    }
+
+    /// The ReadCondition triggered while the DataReader has samples in one
+    /// of the states.
+    pub fn create_readcondition(
+        &self,
+        sample_states: &[SampleStateKind],
+        view_states: &[ViewStateKind],
+        instance_states: &[InstanceStateKind],
+    ) -> ReadCondition {
+        let selection = SampleSelection::new(sample_states, view_states, instance_states);
+        ReadCondition::new(self.participant.clone(), self.guid, selection)
+    }
+}
+
+impl<T: 'static> DataReader<T> {
+    /// Sets the listener of the statuses in the mask of the DataReader. The
+    /// listener is called as the participant is driven, after the operation
+    /// updated the statuses; it must not drive the participant itself.
+    pub fn set_listener(
+        &self,
+        listener: Option<Box<dyn DataReaderListener<T>>>,
+        mask: StatusMask,
+    ) -> Result<(), Error> {
+        let listener = listener.map(|listener| {
+            let listener = Mutex::new(listener);
+            Arc::new(move |participant: &DomainParticipant, guid, status| {
+                let reader = DataReader::new(participant.clone(), guid);
+                notify_data_reader_listener(&mut **listener.lock().unwrap(), &reader, status)
+            }) as _
+        });
+        self.participant
+            .set_reader_listener(self.guid, listener, mask)
+    }
+}
+
+impl<T: TypeSupport> DataReader<T> {
+    /// Returns up to max_samples received samples in the order of their
+    /// reception. The samples stay in the DataReader, marked as READ.
+    pub fn read(&self, max_samples: usize) -> Result<Vec<Sample<T>>, Error> {
+        self.participant
+            .read_samples(self.guid, max_samples, &any_sample(), false)
+    }
+
+    /// Returns up to max_samples received samples like read, removing them
+    /// from the DataReader.
+    pub fn take(&self, max_samples: usize) -> Result<Vec<Sample<T>>, Error> {
+        self.participant
+            .read_samples(self.guid, max_samples, &any_sample(), true)
+    }
+
+    /// Reads the oldest sample not read yet.
+    pub fn read_next_sample(&self) -> Result<Option<Sample<T>>, Error> {
+        let samples = self
+            .participant
+            .read_samples(self.guid, 1, &not_read_sample(), false)?;
+        Ok(samples.into_iter().next())
+    }
+
+    /// Takes the oldest sample not read yet.
+    pub fn take_next_sample(&self) -> Result<Option<Sample<T>>, Error> {
+        let samples = self
+            .participant
+            .read_samples(self.guid, 1, &not_read_sample(), true)?;
+        Ok(samples.into_iter().next())
+    }
+
+    /// Returns up to max_samples samples selected by the ReadCondition or
+    /// QueryCondition of the DataReader like read.
+    pub fn read_w_condition(
+        &self,
+        max_samples: usize,
+        condition: &ReadCondition,
+    ) -> Result<Vec<Sample<T>>, Error> {
+        self.check_condition(condition)?;
+        self.participant
+            .read_samples(self.guid, max_samples, condition.selection(), false)
+    }
+
+    /// Returns up to max_samples samples selected by the ReadCondition or
+    /// QueryCondition of the DataReader like take.
+    pub fn take_w_condition(
+        &self,
+        max_samples: usize,
+        condition: &ReadCondition,
+    ) -> Result<Vec<Sample<T>>, Error> {
+        self.check_condition(condition)?;
+        self.participant
+            .read_samples(self.guid, max_samples, condition.selection(), true)
+    }
+
+    fn check_condition(&self, condition: &ReadCondition) -> Result<(), Error> {
+        if condition.reader() != self.guid {
+            return Err(precondition_not_met(
+                "The condition belongs to another DataReader",
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl<T: TypeSupport + FilterFields + 'static> DataReader<T> {
+    /// The QueryCondition triggered while the DataReader has samples in one
+    /// of the states whose data passes the query expression, see
+    /// FilterExpression. Fails with InvalidInput if the expression is not
+    /// valid.
+    pub fn create_querycondition(
+        &self,
+        sample_states: &[SampleStateKind],
+        view_states: &[ViewStateKind],
+        instance_states: &[InstanceStateKind],
+        query_expression: &str,
+        query_parameters: &[String],
+    ) -> Result<QueryCondition, Error> {
+        let filter_expression = FilterExpression::parse(query_expression, query_parameters)?;
+        let mut selection = SampleSelection::new(sample_states, view_states, instance_states);
+        selection.query = Some((filter_expression, filter_fields_decoder::<T>()));
+        let read_condition = ReadCondition::new(self.participant.clone(), self.guid, selection);
+        Ok(QueryCondition::new(
+            read_condition,
+            query_expression,
+            query_parameters,
+        ))
+    }
+}
+
+fn any_sample() -> SampleSelection {
+    SampleSelection::new(ANY_SAMPLE_STATE, ANY_VIEW_STATE, ANY_INSTANCE_STATE)
+}
+
+fn not_read_sample() -> SampleSelection {
+    SampleSelection::new(
+        &[SampleStateKind::NOT_READ],
+        ANY_VIEW_STATE,
+        ANY_INSTANCE_STATE,
+    )
+}
+
+impl<T: TypeSupport> Stream for DataReader<T> {
+    type Item = Sample<T>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Sample<T>>> {
+        self.participant
+            .poll_take_next_sample(self.guid, cx)
+            .map(Result::ok)
+    }
+}
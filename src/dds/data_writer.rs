@@ -0,0 +1,346 @@
+use crate::dds::condition::status_condition::StatusCondition;
+use crate::dds::condition::wakers::block_until;
+use crate::dds::domain_participant::{timeout, DomainParticipant};
+use crate::dds::listener::data_writer_listener::{notify_data_writer_listener, DataWriterListener};
+use crate::dds::publisher::Publisher;
+use crate::dds::qos::data_writer_qos::DataWriterQos;
+use crate::dds::status::communication_status::CommunicationStatus;
+use crate::dds::status::deadline_missed_status::OfferedDeadlineMissedStatus;
+use crate::dds::status::incompatible_qos_status::OfferedIncompatibleQosStatus;
+use crate::dds::status::liveliness_lost_status::LivelinessLostStatus;
+use crate::dds::status::publication_matched_status::PublicationMatchedStatus;
+use crate::dds::status::status_kind::{StatusKind, StatusMask};
+use crate::dds::topic::Topic;
+use crate::dds::type_support::TypeSupport;
+use crate::discovery::pl_cdr;
+use crate::messages::submessage_elements::serialized_payload::SerializedPayload;
+use crate::structure::change_kind::ChangeKind_t;
+use crate::structure::guid::GUID_t;
+use crate::structure::instance_handle::InstanceHandle_t;
+use crate::structure::time::Time_t;
+
+use futures::future::poll_fn;
+use std::io::Error;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Publishes the data of a Topic (2.2.2.4.2), created by
+/// Publisher::create_datawriter. The enabled DataWriter is backed by an RTPS
+/// Writer of the same GUID, announced to the discovered participants.
+///
+/// The values of T are written as changes of the RTPS Writer, sent to the
+/// matched DataReaders as the participant is driven. The operations without
+/// an explicit source timestamp use the current system time.
+pub struct DataWriter<T> {
+    participant: DomainParticipant,
+    guid: GUID_t,
+    data_type: PhantomData<fn(T)>,
+}
+
+impl<T> Clone for DataWriter<T> {
+    fn clone(&self) -> Self {
+        DataWriter::new(self.participant.clone(), self.guid)
+    }
+}
+
+impl<T> PartialEq for DataWriter<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.participant == other.participant && self.guid == other.guid
+    }
+}
+
+impl<T> std::fmt::Debug for DataWriter<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DataWriter")
+            .field("guid", &self.guid)
+            .finish()
+    }
+}
+
+impl<T> DataWriter<T> {
+    pub(crate) fn new(participant: DomainParticipant, guid: GUID_t) -> Self {
+        DataWriter {
+            participant,
+            guid,
+            data_type: PhantomData,
+        }
+    }
+
+    pub fn guid(&self) -> GUID_t {
+        self.guid
+    }
+
+    pub fn get_instance_handle(&self) -> InstanceHandle_t {
+        pl_cdr::key_hash(self.guid)
+    }
+
+    pub(crate) fn get_participant(&self) -> DomainParticipant {
+        self.participant.clone()
+    }
+
+    pub fn get_topic(&self) -> Result<Topic, Error> {
+        self.participant.writer_topic(self.guid)
+    }
+
+    pub fn get_publisher(&self) -> Result<Publisher, Error> {
+        self.participant.writer_publisher(self.guid)
+    }
+
+    pub fn get_qos(&self) -> Result<DataWriterQos, Error> {
+        self.participant.writer_qos(self.guid)
+    }
+
+    /// Replaces the QoS; the enabled DataWriter is announced again. Changing
+    /// its immutable policies fails (IMMUTABLE_POLICY).
+    pub fn set_qos(&self, qos: DataWriterQos) -> Result<(), Error> {
+        self.participant.set_writer_qos(self.guid, qos)
+    }
+
+    /// Enables the DataWriter of the enabled Publisher and Topic: the RTPS
+    /// Writer is created and announced.
+    pub fn enable(&self) -> Result<(), Error> {
+        self.participant.enable_writer(self.guid)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.participant.is_writer_enabled(self.guid)
+    }
+
+    /// The handles of the DataReaders currently matched with the DataWriter.
+    pub fn get_matched_subscriptions(&self) -> Result<Vec<InstanceHandle_t>, Error> {
+        self.participant.matched_subscriptions(self.guid)
+    }
+
+    /// The StatusCondition triggered by the changes of the enabled statuses
+    /// of the DataWriter.
+    pub fn get_statuscondition(&self) -> StatusCondition {
+        StatusCondition::new(self.participant.clone(), self.guid)
+    }
+
+    /// The statuses of the DataWriter changed since they were last read.
+    pub fn get_status_changes(&self) -> Result<StatusKind, Error> {
+        self.participant.status_changes(self.guid)
+    }
+
+    pub fn get_liveliness_lost_status(&self) -> Result<LivelinessLostStatus, Error> {
+        match self
+            .participant
+            .read_status(self.guid, StatusKind::LIVELINESS_LOST)?
+        {
+            CommunicationStatus::LivelinessLost(status) => Ok(status),
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn get_offered_deadline_missed_status(&self) -> Result<OfferedDeadlineMissedStatus, Error> {
+        match self
+            .participant
+            .read_status(self.guid, StatusKind::OFFERED_DEADLINE_MISSED)?
+        {
+            CommunicationStatus::OfferedDeadlineMissed(status) => Ok(status),
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn get_offered_incompatible_qos_status(
+        &self,
+    ) -> Result<OfferedIncompatibleQosStatus, Error> {
+        match self
+            .participant
+            .read_status(self.guid, StatusKind::OFFERED_INCOMPATIBLE_QOS)?
+        {
+            CommunicationStatus::OfferedIncompatibleQos(status) => Ok(status),
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn get_publication_matched_status(&self) -> Result<PublicationMatchedStatus, Error> {
+        match self
+            .participant
+            .read_status(self.guid, StatusKind::PUBLICATION_MATCHED)?
+        {
+            CommunicationStatus::PublicationMatched(status) => Ok(status),
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<T: 'static> DataWriter<T> {
+    /// Sets the listener of the statuses in the mask of the DataWriter. The
+    /// listener is called as the participant is driven, after the operation
+    /// updated the statuses; it must not drive the participant itself.
+    pub fn set_listener(
+        &self,
+        listener: Option<Box<dyn DataWriterListener<T>>>,
+        mask: StatusMask,
+    ) -> Result<(), Error> {
+        let listener = listener.map(|listener| {
+            let listener = Mutex::new(listener);
+            Arc::new(move |participant: &DomainParticipant, guid, status| {
+                let writer = DataWriter::new(participant.clone(), guid);
+                notify_data_writer_listener(&mut **listener.lock().unwrap(), &writer, status)
+            }) as _
+        });
+        self.participant
+            .set_writer_listener(self.guid, listener, mask)
+    }
+}
+
+impl<T: TypeSupport> DataWriter<T> {
+    /// Writes the value, registering its instance if needed. While the
+    /// history of the DataWriter is full, blocks the thread up to the
+    /// max_blocking_time of the reliability and then fails with TimedOut
+    /// (TIMEOUT): the acknowledgements of the matched DataReaders make room
+    /// as the participant is driven by another thread, see write_async.
+    pub fn write(&self, data: &T) -> Result<(), Error> {
+        self.write_w_timestamp(data, now())
+    }
+
+    pub fn write_w_timestamp(&self, data: &T, source_timestamp: Time_t) -> Result<(), Error> {
+        self.write_change(
+            ChangeKind_t::ALIVE,
+            data.serialize()?,
+            data.instance_handle(),
+            source_timestamp,
+        )
+    }
+
+    /// Writes the value like write, waiting for room in the history of the
+    /// DataWriter as a future instead of blocking the thread. The value is
+    /// written when the future completes; dropping the future before writes
+    /// nothing.
+    pub async fn write_async(&self, data: &T) -> Result<(), Error> {
+        self.write_w_timestamp_async(data, now()).await
+    }
+
+    pub async fn write_w_timestamp_async(
+        &self,
+        data: &T,
+        source_timestamp: Time_t,
+    ) -> Result<(), Error> {
+        let serialized_payload = data.serialize()?;
+        let instance_handle = data.instance_handle();
+        let write = poll_fn(|cx| {
+            self.participant.poll_write_change(
+                self.guid,
+                ChangeKind_t::ALIVE,
+                &serialized_payload,
+                instance_handle,
+                source_timestamp,
+                cx,
+            )
+        });
+        match self.blocking_deadline()? {
+            Some(deadline) => tokio::time::timeout_at(deadline.into(), write)
+                .await
+                .unwrap_or_else(|_| Err(timeout())),
+            None => write.await,
+        }
+    }
+
+    /// Disposes the instance of the value: the DataReaders are informed
+    /// that the instance was deleted. Only the key of the value is sent.
+    pub fn dispose(&self, data: &T) -> Result<(), Error> {
+        self.dispose_w_timestamp(data, now())
+    }
+
+    pub fn dispose_w_timestamp(&self, data: &T, source_timestamp: Time_t) -> Result<(), Error> {
+        self.write_change(
+            ChangeKind_t::NOT_ALIVE_DISPOSED,
+            data.serialize_key()?,
+            data.instance_handle(),
+            source_timestamp,
+        )
+    }
+
+    /// Registers the instance of the value, the write operations register
+    /// the instance implicitly. Returns the handle of the instance.
+    pub fn register_instance(&self, data: &T) -> Result<InstanceHandle_t, Error> {
+        self.register_instance_w_timestamp(data, now())
+    }
+
+    /// The registration is not sent to the DataReaders, the
+    /// source_timestamp is accepted for the symmetry with the other
+    /// operations.
+    pub fn register_instance_w_timestamp(
+        &self,
+        data: &T,
+        _source_timestamp: Time_t,
+    ) -> Result<InstanceHandle_t, Error> {
+        let instance_handle = data.instance_handle();
+        self.participant
+            .register_instance(self.guid, instance_handle)?;
+        Ok(instance_handle)
+    }
+
+    /// Unregisters the instance of the value registered by the DataWriter:
+    /// the DataWriter does not write the instance anymore.
+    pub fn unregister_instance(&self, data: &T) -> Result<(), Error> {
+        self.unregister_instance_w_timestamp(data, now())
+    }
+
+    pub fn unregister_instance_w_timestamp(
+        &self,
+        data: &T,
+        source_timestamp: Time_t,
+    ) -> Result<(), Error> {
+        self.write_change(
+            ChangeKind_t::NOT_ALIVE_UNREGISTERED,
+            data.serialize_key()?,
+            data.instance_handle(),
+            source_timestamp,
+        )
+    }
+
+    /// Writes the change once the history of the DataWriter has room,
+    /// blocking the thread up to the max_blocking_time.
+    fn write_change(
+        &self,
+        kind: ChangeKind_t,
+        serialized_payload: SerializedPayload,
+        instance_handle: InstanceHandle_t,
+        source_timestamp: Time_t,
+    ) -> Result<(), Error> {
+        block_until(self.blocking_deadline()?, |cx| {
+            self.participant.poll_write_change(
+                self.guid,
+                kind,
+                &serialized_payload,
+                instance_handle,
+                source_timestamp,
+                cx,
+            )
+        })
+        .unwrap_or_else(|| Err(timeout()))
+    }
+
+    /// The end of the max_blocking_time of a write operation started now,
+    /// None if it is too far to be represented.
+    fn blocking_deadline(&self) -> Result<Option<Instant>, Error> {
+        let max_blocking_time = self
+            .participant
+            .writer_qos(self.guid)?
+            .reliability
+            .max_blocking_time;
+        Ok(Instant::now().checked_add(Duration::from(max_blocking_time)))
+    }
+
+    /// The handle of the instance of the value registered by the
+    /// DataWriter, HANDLE_NIL if it is not registered.
+    pub fn lookup_instance(&self, data: &T) -> InstanceHandle_t {
+        let instance_handle = data.instance_handle();
+        if self
+            .participant
+            .is_instance_registered(self.guid, instance_handle)
+        {
+            instance_handle
+        } else {
+            InstanceHandle_t::HANDLE_NIL
+        }
+    }
+}
+
+fn now() -> Time_t {
+    Time_t::from(SystemTime::now())
+}
@@ -0,0 +1,2304 @@
+mod instance_state;
+mod publisher_state;
+mod reader_state;
+mod status;
+mod subscriber_state;
+mod topic_state;
+mod writer_state;
+
+use crate::behavior::writer::WriterAttributes;
+use crate::dds::condition::wakers::Wakers;
+use crate::dds::content_filtered_topic::ContentFilteredTopic;
+use crate::dds::domain_participant::publisher_state::PublisherState;
+use crate::dds::domain_participant::reader_state::ReaderState;
+use crate::dds::domain_participant::subscriber_state::SubscriberState;
+use crate::dds::domain_participant::topic_state::TopicState;
+use crate::dds::domain_participant::writer_state::WriterState;
+use crate::dds::filter::filter_expression::FilterExpression;
+use crate::dds::listener::domain_participant_listener::DomainParticipantListener;
+use crate::dds::listener::notification::SharedListener;
+use crate::dds::publisher::Publisher;
+use crate::dds::qos::data_reader_qos::DataReaderQos;
+use crate::dds::qos::data_writer_qos::DataWriterQos;
+use crate::dds::qos::domain_participant_qos::DomainParticipantQos;
+use crate::dds::qos::publisher_qos::PublisherQos;
+use crate::dds::qos::qos_policy_id::QosPolicyId_t;
+use crate::dds::qos::reliability_qos_policy::ReliabilityQosPolicyKind;
+use crate::dds::qos::subscriber_qos::SubscriberQos;
+use crate::dds::qos::topic_qos::TopicQos;
+use crate::dds::status::entity_statuses::EntityStatuses;
+use crate::dds::status::status_kind::{StatusKind, StatusMask};
+use crate::dds::subscriber::Subscriber;
+use crate::dds::topic::Topic;
+use crate::discovery::discovery_config::DiscoveryConfig;
+use crate::discovery::pl_cdr;
+use crate::discovery::port_mapping::PortMapping;
+use crate::discovery::static_discovery_config::StaticDiscoveryConfig;
+use crate::messages::received_submessage::ReceivedSubmessage;
+use crate::messages::vendor_id::VendorId_t;
+use crate::protocol::event::Event;
+use crate::protocol::transmit::Transmit;
+use crate::structure::entity_id::EntityId_t;
+use crate::structure::guid::GUID_t;
+use crate::structure::instance_handle::InstanceHandle_t;
+use crate::structure::locator::{LocatorList_t, Locator_t};
+use crate::structure::participant::RtpsParticipant;
+use crate::structure::reliability_kind::ReliabilityKind_t;
+use crate::structure::topic_kind::TopicKind_t;
+use crate::transport::transport::Transport;
+use crate::transport::udp_v4_transport::UdpV4Transport;
+
+use bytes::Bytes;
+use log::warn;
+use std::collections::{BTreeMap, VecDeque};
+use std::convert::TryFrom;
+use std::io::{self, Error, ErrorKind};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+use tokio::sync::Notify;
+
+/// Identifies the domain; only the DomainParticipants of the same domain
+/// communicate.
+pub type DomainId_t = u32;
+
+/// The entry point of the application into a domain and the factory of
+/// Topics, Publishers and Subscribers (2.2.2.2.1).
+///
+/// The DomainParticipant owns the RtpsParticipant exchanging the messages of
+/// all its entities. Like the RtpsParticipant, it is free of any I/O: the
+/// caller passes the received datagrams and the expired timers in and sends
+/// the messages collected with poll_transmit. Alternatively, run drives the
+/// DomainParticipant with its transports within the Tokio runtime. The
+/// entities are handles to the state of the DomainParticipant; the
+/// DomainParticipant and its entities can be cloned and shared between
+/// threads.
+#[derive(Clone)]
+pub struct DomainParticipant {
+    state: Arc<Mutex<ParticipantState>>,
+
+    /// Wakes run after the operations of the entities, which may have
+    /// messages to send.
+    operations: Arc<Notify>,
+}
+
+struct ParticipantState {
+    domain_id: DomainId_t,
+    qos: DomainParticipantQos,
+    enabled: bool,
+    deleted: bool,
+    rtps: RtpsParticipant,
+
+    /// The locators the discovery enabled with the participant announces
+    /// the participant to, see add_default_transport. Empty when the
+    /// discovery is enabled explicitly.
+    announcement_locators: LocatorList_t,
+
+    /// The participant id selecting the ports of the transport registered
+    /// by add_default_transport.
+    participant_id: Option<u16>,
+
+    /// The size of the fragments of the samples of the DataWriters, see
+    /// set_fragment_size. None for the biggest fragments fitting into the
+    /// messages of the transports.
+    fragment_size: Option<u16>,
+
+    /// The time of the operations of the entities.
+    clock: Box<dyn Fn() -> Instant + Send>,
+
+    default_topic_qos: TopicQos,
+    default_publisher_qos: PublisherQos,
+    default_subscriber_qos: SubscriberQos,
+    topics: BTreeMap<GUID_t, TopicState>,
+    publishers: BTreeMap<GUID_t, PublisherState>,
+    subscribers: BTreeMap<GUID_t, SubscriberState>,
+    writers: BTreeMap<GUID_t, WriterState>,
+    readers: BTreeMap<GUID_t, ReaderState>,
+
+    /// The events of the RtpsParticipant, once the statuses of the entities
+    /// are updated with them.
+    events: VecDeque<Event>,
+
+    /// The statuses changed since the listeners were last notified, see
+    /// take_notifications.
+    status_changes: Vec<(GUID_t, StatusKind)>,
+
+    listener: Option<(SharedListener<dyn DomainParticipantListener>, StatusMask)>,
+
+    /// The tasks waiting for the conditions of the entities.
+    condition_wakers: Wakers,
+}
+
+impl PartialEq for DomainParticipant {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.state, &other.state)
+    }
+}
+
+impl std::fmt::Debug for DomainParticipant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DomainParticipant")
+            .field("guid", &self.state.lock().unwrap().rtps.guid())
+            .finish()
+    }
+}
+
+impl DomainParticipant {
+    /// Creates the disabled participant, see
+    /// DomainParticipantFactory::create_participant.
+    pub(crate) fn new(
+        domain_id: DomainId_t,
+        qos: DomainParticipantQos,
+        vendor_id: VendorId_t,
+    ) -> Self {
+        let mut rtps = RtpsParticipant::new(vendor_id);
+        rtps.set_domain(domain_id, String::new());
+        DomainParticipant {
+            operations: Arc::new(Notify::new()),
+            state: Arc::new(Mutex::new(ParticipantState {
+                domain_id,
+                qos,
+                enabled: false,
+                deleted: false,
+                rtps,
+                announcement_locators: vec![],
+                participant_id: None,
+                fragment_size: None,
+                clock: Box::new(Instant::now),
+                default_topic_qos: TopicQos::default(),
+                default_publisher_qos: PublisherQos::default(),
+                default_subscriber_qos: SubscriberQos::default(),
+                topics: BTreeMap::new(),
+                publishers: BTreeMap::new(),
+                subscribers: BTreeMap::new(),
+                writers: BTreeMap::new(),
+                readers: BTreeMap::new(),
+                events: VecDeque::new(),
+                status_changes: vec![],
+                listener: None,
+                condition_wakers: Wakers::default(),
+            })),
+        }
+    }
+
+    pub fn get_domain_id(&self) -> DomainId_t {
+        self.state.lock().unwrap().domain_id
+    }
+
+    /// The vendor id of the participant, see
+    /// DomainParticipantFactory::set_vendor_id.
+    pub fn get_vendor_id(&self) -> VendorId_t {
+        self.state.lock().unwrap().rtps.vendor_id()
+    }
+
+    /// The participant id (9.6.1.1) selecting the well-known ports of the
+    /// participant created by the DomainParticipantFactory, unique among
+    /// the participants of the domain on the host. None for the participant
+    /// without such ports.
+    pub fn get_participant_id(&self) -> Option<u16> {
+        self.state.lock().unwrap().participant_id
+    }
+
+    pub fn get_instance_handle(&self) -> InstanceHandle_t {
+        pl_cdr::key_hash(self.guid())
+    }
+
+    pub fn guid(&self) -> GUID_t {
+        self.state.lock().unwrap().rtps.guid()
+    }
+
+    pub fn get_qos(&self) -> Result<DomainParticipantQos, Error> {
+        Ok(self.state()?.qos.clone())
+    }
+
+    pub fn set_qos(&self, qos: DomainParticipantQos) -> Result<(), Error> {
+        self.state()?.qos = qos;
+        Ok(())
+    }
+
+    /// Enables the participant and, with autoenable_created_entities, all
+    /// its entities. The participant created by the
+    /// DomainParticipantFactory enables the discovery unless it was enabled
+    /// explicitly.
+    pub fn enable(&self) -> Result<(), Error> {
+        self.state()?.enable()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.state.lock().unwrap().enabled
+    }
+
+    /// Replaces the source of the time of the operations of the entities,
+    /// Instant::now by default; e.g. the clock of a simulated network.
+    pub fn set_clock(&self, clock: impl Fn() -> Instant + Send + 'static) {
+        self.state.lock().unwrap().clock = Box::new(clock);
+    }
+
+    /// Sets the locators of the user traffic, used by the DataWriters and
+    /// DataReaders enabled afterwards.
+    pub fn set_default_locators(
+        &self,
+        unicast_locator_list: LocatorList_t,
+        multicast_locator_list: LocatorList_t,
+    ) {
+        self.state
+            .lock()
+            .unwrap()
+            .rtps
+            .set_default_locators(unicast_locator_list, multicast_locator_list)
+    }
+
+    /// Sets the locators of the metatraffic (discovery).
+    pub fn set_metatraffic_locators(
+        &self,
+        unicast_locator_list: LocatorList_t,
+        multicast_locator_list: LocatorList_t,
+    ) {
+        self.state
+            .lock()
+            .unwrap()
+            .rtps
+            .set_metatraffic_locators(unicast_locator_list, multicast_locator_list)
+    }
+
+    /// Sets the domain tag announced by the discovery enabled afterwards;
+    /// only the participants with the same domain tag communicate.
+    pub fn set_domain_tag(&self, domain_tag: String) {
+        let mut state = self.state.lock().unwrap();
+        let domain_id = state.domain_id;
+        state.rtps.set_domain(domain_id, domain_tag)
+    }
+
+    /// Sets the size of the fragments the samples not fitting into a single
+    /// message are split into by the DataWriters enabled afterwards. By
+    /// default the fragments are the biggest ones fitting into the messages
+    /// of the transports. Fails when the fragment_size is zero or the
+    /// fragments do not fit into the messages of the registered transports.
+    pub fn set_fragment_size(&self, fragment_size: u16) -> Result<(), Error> {
+        let mut state = self.state()?;
+        let max_fragment_size = WriterAttributes::max_fragment_size(state.rtps.max_message_size());
+        if fragment_size == 0 || fragment_size > max_fragment_size {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "The fragment_size must be between 1 and {}",
+                    max_fragment_size
+                ),
+            ));
+        }
+        state.fragment_size = Some(fragment_size);
+        Ok(())
+    }
+
+    /// See RtpsParticipant::enable_discovery. Replaces the discovery
+    /// enabled with the participant, so it is called before enable.
+    pub fn enable_discovery(&self, announcement_locators: LocatorList_t, config: DiscoveryConfig) {
+        let mut state = self.state.lock().unwrap();
+        state.announcement_locators.clear();
+        let now = (state.clock)();
+        state
+            .rtps
+            .enable_discovery(now, announcement_locators, config);
+        self.operations.notify_one();
+    }
+
+    /// See RtpsParticipant::enable_static_discovery. Replaces the discovery
+    /// enabled with the participant, so it is called before enable.
+    pub fn enable_static_discovery(&self, config: &StaticDiscoveryConfig) {
+        let mut state = self.state.lock().unwrap();
+        state.announcement_locators.clear();
+        let now = (state.clock)();
+        state.rtps.enable_static_discovery(now, config);
+        self.operations.notify_one();
+    }
+
+    /// Registers the transport used by run, see
+    /// RtpsParticipant::add_transport. The transports are registered before
+    /// the participant is run, in addition to the UDPv4 transport of the
+    /// participant created by the DomainParticipantFactory.
+    pub fn add_transport(&self, transport: Box<dyn Transport>) {
+        self.state.lock().unwrap().rtps.add_transport(transport)
+    }
+
+    /// Registers the UDPv4 transport on the well-known ports of the domain
+    /// (9.6.1.1) for the first participant id whose ports are free, uses its
+    /// locators as the locators of the participant and announces the
+    /// participant to its multicast group once enabled. Has to be called
+    /// within the Tokio runtime.
+    pub(crate) fn add_default_transport(&self, port_mapping: &PortMapping) -> io::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let domain_id = u16::try_from(state.domain_id)
+            .map_err(|_| Error::new(ErrorKind::InvalidInput, "Port out of range"))?;
+        let (participant_id, transport) = UdpV4Transport::for_domain(
+            UdpV4Transport::default_unicast_address(),
+            UdpV4Transport::DEFAULT_MULTICAST_GROUP,
+            port_mapping,
+            domain_id,
+        )?;
+        state.rtps.set_metatraffic_locators(
+            vec![transport.metatraffic_unicast_locator()],
+            vec![transport.metatraffic_multicast_locator()],
+        );
+        state.rtps.set_default_locators(
+            vec![transport.user_unicast_locator()],
+            vec![transport.user_multicast_locator()],
+        );
+        state.announcement_locators = vec![transport.metatraffic_multicast_locator()];
+        state.participant_id = Some(participant_id);
+        state.rtps.add_transport(Box::new(transport));
+        Ok(())
+    }
+
+    /// Drives the participant with its transports within the Tokio runtime:
+    /// sends the messages of poll_transmit, passes the messages received by
+    /// the transports and the expired timers to the participant and waits
+    /// for the operations of the entities. The transports failing to receive
+    /// are removed; completes with the error of the last one once no
+    /// transport remains. Completes once the participant is deleted and its
+    /// deletion is announced.
+    pub async fn run(&self) -> io::Result<()> {
+        let mut transports = self.state.lock().unwrap().rtps.transports().clone();
+        loop {
+            while let Some(transmit) = self.poll_transmit() {
+                transports.send_transmit(transmit).await;
+            }
+            if self.state.lock().unwrap().deleted {
+                return Ok(());
+            }
+
+            let deadline = self.poll_timeout();
+            let timeout = async {
+                match deadline {
+                    Some(deadline) => tokio::time::sleep_until(deadline.into()).await,
+                    None => std::future::pending().await,
+                }
+            };
+            tokio::select! {
+                received = transports.recv_submessages() => match received {
+                    Ok((source, submessages)) => self.handle_submessages(source, submessages),
+                    Err((transport, error)) => {
+                        warn!("Receiving failed, the transport is removed: {}", error);
+                        transports.unregister(&transport);
+                        self.state.lock().unwrap().rtps.remove_transport(&transport);
+                        if transports.is_empty() {
+                            return Err(error);
+                        }
+                    }
+                },
+                _ = timeout => self.handle_timeout(self.now()),
+                _ = self.operations.notified() => (),
+            }
+        }
+    }
+
+    /// Like the other operations driving the participant, it updates the
+    /// statuses of the entities, wakes the waiting tasks and calls the
+    /// listeners of the changed statuses.
+    pub fn handle_datagram(&self, now: Instant, src: Locator_t, datagram: Bytes) {
+        let mut state = self.state.lock().unwrap();
+        state.rtps.handle_datagram(now, src, datagram);
+        state.update(now);
+        self.notify_listeners(state);
+    }
+
+    /// Processes the Submessages received by a transport of run.
+    fn handle_submessages(&self, src: Locator_t, submessages: Vec<ReceivedSubmessage>) {
+        let mut state = self.state.lock().unwrap();
+        let now = (state.clock)();
+        state.rtps.handle_submessages(now, src, submessages);
+        state.update(now);
+        self.notify_listeners(state);
+    }
+
+    fn now(&self) -> Instant {
+        (self.state.lock().unwrap().clock)()
+    }
+
+    /// Also checks the deadlines of the instances of the DataWriters and
+    /// DataReaders and purges the not alive instances of the DataReaders.
+    pub fn handle_timeout(&self, now: Instant) {
+        let mut state = self.state.lock().unwrap();
+        state.rtps.handle_timeout(now);
+        state.handle_deadlines(now);
+        state.purge_instances(now);
+        state.update(now);
+        self.notify_listeners(state);
+    }
+
+    pub fn poll_timeout(&self) -> Option<Instant> {
+        let state = self.state.lock().unwrap();
+        let writer_deadlines = state
+            .writers
+            .values()
+            .flat_map(|writer| writer.deadlines.values());
+        let reader_deadlines = state
+            .readers
+            .values()
+            .flat_map(|reader| reader.deadlines.values());
+        let purge_times = state
+            .readers
+            .values()
+            .flat_map(|reader| reader.instances.values())
+            .filter_map(|instance| instance.purge_time);
+        state
+            .rtps
+            .poll_timeout()
+            .into_iter()
+            .chain(writer_deadlines.chain(reader_deadlines).cloned())
+            .chain(purge_times)
+            .min()
+    }
+
+    /// Also delivers the changes written to the local DataReaders.
+    pub fn poll_transmit(&self) -> Option<Transmit> {
+        let mut state = self.state.lock().unwrap();
+        let transmit = state.rtps.poll_transmit();
+        let now = (state.clock)();
+        state.update(now);
+        self.notify_listeners(state);
+        transmit
+    }
+
+    pub fn poll_event(&self) -> Option<Event> {
+        let mut state = self.state.lock().unwrap();
+        let now = (state.clock)();
+        state.update(now);
+        let event = state.events.pop_front();
+        self.notify_listeners(state);
+        event
+    }
+
+    /// Sets the listener of the statuses in the mask of all the entities
+    /// without a listener of their own, or of their Publisher or Subscriber,
+    /// for the status. The listeners are called as the participant is
+    /// driven, after the operation updated the statuses; they must not
+    /// drive the participant themselves.
+    pub fn set_listener(
+        &self,
+        listener: Option<Box<dyn DomainParticipantListener>>,
+        mask: StatusMask,
+    ) -> Result<(), Error> {
+        self.state()?.listener = listener.map(|listener| (Arc::new(Mutex::new(listener)), mask));
+        Ok(())
+    }
+
+    /// Creates the Topic of the name and type. A Topic of the same name
+    /// must have the same type.
+    pub fn create_topic(
+        &self,
+        topic_name: &str,
+        type_name: &str,
+        qos: TopicQos,
+    ) -> Result<Topic, Error> {
+        qos.validate().map_err(inconsistent_policy)?;
+        let mut state = self.state()?;
+        if state
+            .topics
+            .values()
+            .any(|topic| topic.topic_name == topic_name && topic.type_name != type_name)
+        {
+            return Err(precondition_not_met(format!(
+                "Topic {} exists with another type",
+                topic_name
+            )));
+        }
+        let guid = state.next_guid(EntityId_t::ENTITYKIND_USER_DEFINED_UNKNOWN);
+        let enabled = state.enabled && state.qos.entity_factory.autoenable_created_entities;
+        state.topics.insert(
+            guid,
+            TopicState {
+                topic_name: topic_name.to_string(),
+                type_name: type_name.to_string(),
+                qos,
+                enabled,
+            },
+        );
+        Ok(Topic::new(self.clone(), guid))
+    }
+
+    /// Deletes the Topic not used by any DataWriter or DataReader.
+    pub fn delete_topic(&self, topic: &Topic) -> Result<(), Error> {
+        let mut state = self.state()?;
+        let guid = topic.guid();
+        if topic.get_participant() != *self || !state.topics.contains_key(&guid) {
+            return Err(precondition_not_met(
+                "The Topic belongs to another participant",
+            ));
+        }
+        if state.writers.values().any(|writer| writer.topic == guid)
+            || state.readers.values().any(|reader| reader.topic == guid)
+        {
+            return Err(precondition_not_met("The Topic is in use"));
+        }
+        state.topics.remove(&guid);
+        Ok(())
+    }
+
+    /// The Topic of the name created by the participant.
+    pub fn lookup_topicdescription(&self, topic_name: &str) -> Option<Topic> {
+        let state = self.state.lock().unwrap();
+        state
+            .topics
+            .iter()
+            .find(|(_, topic)| topic.topic_name == topic_name)
+            .map(|(guid, _)| Topic::new(self.clone(), *guid))
+    }
+
+    /// Creates the ContentFilteredTopic of the samples of the related Topic
+    /// passing the filter expression of the DDSSQL filter class, with the
+    /// expression parameters. It is a description read by DataReaders and
+    /// has no state of its own in the participant.
+    pub fn create_contentfilteredtopic(
+        &self,
+        name: &str,
+        related_topic: &Topic,
+        filter_expression: &str,
+        expression_parameters: &[String],
+    ) -> Result<ContentFilteredTopic, Error> {
+        let state = self.state()?;
+        if related_topic.get_participant() != *self
+            || !state.topics.contains_key(&related_topic.guid())
+        {
+            return Err(precondition_not_met(
+                "The Topic belongs to another participant",
+            ));
+        }
+        if name.is_empty() {
+            return Err(Error::new(ErrorKind::InvalidInput, "Empty topic name"));
+        }
+        FilterExpression::parse(filter_expression, expression_parameters)?;
+        Ok(ContentFilteredTopic::new(
+            name,
+            related_topic,
+            filter_expression,
+            expression_parameters,
+        ))
+    }
+
+    pub fn create_publisher(&self, qos: PublisherQos) -> Result<Publisher, Error> {
+        let mut state = self.state()?;
+        let guid = state.next_guid(EntityId_t::ENTITYKIND_USER_DEFINED_WRITER_GROUP);
+        let enabled = state.enabled && state.qos.entity_factory.autoenable_created_entities;
+        state.publishers.insert(
+            guid,
+            PublisherState {
+                qos,
+                default_datawriter_qos: DataWriterQos::default(),
+                enabled,
+                statuses: EntityStatuses::default(),
+                listener: None,
+            },
+        );
+        Ok(Publisher::new(self.clone(), guid))
+    }
+
+    /// Deletes the Publisher without DataWriters.
+    pub fn delete_publisher(&self, publisher: &Publisher) -> Result<(), Error> {
+        let mut state = self.state()?;
+        let guid = publisher.guid();
+        if publisher.get_participant() != *self || !state.publishers.contains_key(&guid) {
+            return Err(precondition_not_met(
+                "The Publisher belongs to another participant",
+            ));
+        }
+        if state
+            .writers
+            .values()
+            .any(|writer| writer.publisher == guid)
+        {
+            return Err(precondition_not_met("The Publisher has DataWriters"));
+        }
+        state.publishers.remove(&guid);
+        Ok(())
+    }
+
+    pub fn create_subscriber(&self, qos: SubscriberQos) -> Result<Subscriber, Error> {
+        let mut state = self.state()?;
+        let guid = state.next_guid(EntityId_t::ENTITYKIND_USER_DEFINED_READER_GROUP);
+        let enabled = state.enabled && state.qos.entity_factory.autoenable_created_entities;
+        state.subscribers.insert(
+            guid,
+            SubscriberState {
+                qos,
+                default_datareader_qos: DataReaderQos::default(),
+                enabled,
+                statuses: EntityStatuses::default(),
+                listener: None,
+            },
+        );
+        Ok(Subscriber::new(self.clone(), guid))
+    }
+
+    /// Deletes the Subscriber without DataReaders.
+    pub fn delete_subscriber(&self, subscriber: &Subscriber) -> Result<(), Error> {
+        let mut state = self.state()?;
+        let guid = subscriber.guid();
+        if subscriber.get_participant() != *self || !state.subscribers.contains_key(&guid) {
+            return Err(precondition_not_met(
+                "The Subscriber belongs to another participant",
+            ));
+        }
+        if state
+            .readers
+            .values()
+            .any(|reader| reader.subscriber == guid)
+        {
+            return Err(precondition_not_met("The Subscriber has DataReaders"));
+        }
+        state.subscribers.remove(&guid);
+        Ok(())
+    }
+
+    /// Deletes all Topics, Publishers and Subscribers together with their
+    /// DataWriters and DataReaders.
+    pub fn delete_contained_entities(&self) -> Result<(), Error> {
+        let mut state = self.state()?;
+        let writers: Vec<GUID_t> = state.writers.keys().cloned().collect();
+        for guid in writers {
+            state.delete_writer(guid);
+        }
+        let readers: Vec<GUID_t> = state.readers.keys().cloned().collect();
+        for guid in readers {
+            state.delete_reader(guid);
+        }
+        state.publishers.clear();
+        state.subscribers.clear();
+        state.topics.clear();
+        Ok(())
+    }
+
+    /// Whether the entity of the handle was created by the participant,
+    /// directly or by its Publishers and Subscribers.
+    pub fn contains_entity(&self, handle: InstanceHandle_t) -> bool {
+        let state = self.state.lock().unwrap();
+        let guids = state
+            .topics
+            .keys()
+            .chain(state.publishers.keys())
+            .chain(state.subscribers.keys())
+            .chain(state.writers.keys())
+            .chain(state.readers.keys());
+        guids
+            .map(|guid| pl_cdr::key_hash(*guid))
+            .any(|entity_handle| entity_handle == handle)
+    }
+
+    pub fn get_default_topic_qos(&self) -> TopicQos {
+        self.state.lock().unwrap().default_topic_qos.clone()
+    }
+
+    pub fn set_default_topic_qos(&self, qos: TopicQos) -> Result<(), Error> {
+        qos.validate().map_err(inconsistent_policy)?;
+        self.state.lock().unwrap().default_topic_qos = qos;
+        Ok(())
+    }
+
+    pub fn get_default_publisher_qos(&self) -> PublisherQos {
+        self.state.lock().unwrap().default_publisher_qos.clone()
+    }
+
+    pub fn set_default_publisher_qos(&self, qos: PublisherQos) {
+        self.state.lock().unwrap().default_publisher_qos = qos;
+    }
+
+    pub fn get_default_subscriber_qos(&self) -> SubscriberQos {
+        self.state.lock().unwrap().default_subscriber_qos.clone()
+    }
+
+    pub fn set_default_subscriber_qos(&self, qos: SubscriberQos) {
+        self.state.lock().unwrap().default_subscriber_qos = qos;
+    }
+
+    /// Whether the participant has no entities, the condition of its
+    /// deletion.
+    pub(crate) fn is_empty(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        state.topics.is_empty() && state.publishers.is_empty() && state.subscribers.is_empty()
+    }
+
+    /// Makes all operations fail and announces the deletion, see
+    /// DomainParticipantFactory::delete_participant.
+    pub(crate) fn mark_deleted(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.deleted = true;
+        state.rtps.dispose();
+        self.operations.notify_one();
+        for writer in state.writers.values_mut() {
+            writer.wakers.wake();
+        }
+        for reader in state.readers.values_mut() {
+            reader.wakers.wake();
+        }
+        state.condition_wakers.wake();
+    }
+
+    /// The state of the participant not yet deleted.
+    /// The state for the operations of the entities; wakes run, as the
+    /// operation may have messages to send.
+    fn state(&self) -> Result<MutexGuard<'_, ParticipantState>, Error> {
+        self.operations.notify_one();
+        let state = self.state.lock().unwrap();
+        if state.deleted {
+            return Err(already_deleted());
+        }
+        Ok(state)
+    }
+}
+
+impl ParticipantState {
+    fn next_guid(&mut self, entity_kind: u8) -> GUID_t {
+        GUID_t {
+            guid_prefix: self.rtps.guid().guid_prefix,
+            entity_id: self.rtps.next_entity_id(entity_kind),
+        }
+    }
+
+    fn enable(&mut self) -> Result<(), Error> {
+        if self.enabled {
+            return Ok(());
+        }
+        self.enabled = true;
+        if !self.announcement_locators.is_empty() {
+            let now = (self.clock)();
+            let announcement_locators = std::mem::take(&mut self.announcement_locators);
+            self.rtps
+                .enable_discovery(now, announcement_locators, DiscoveryConfig::default());
+        }
+        if !self.qos.entity_factory.autoenable_created_entities {
+            return Ok(());
+        }
+        for topic in self.topics.values_mut() {
+            topic.enabled = true;
+        }
+        let publishers: Vec<GUID_t> = self.publishers.keys().cloned().collect();
+        for guid in publishers {
+            self.enable_publisher(guid)?;
+        }
+        let subscribers: Vec<GUID_t> = self.subscribers.keys().cloned().collect();
+        for guid in subscribers {
+            self.enable_subscriber(guid)?;
+        }
+        Ok(())
+    }
+
+    /// Updates the statuses with the events of the RtpsParticipant and
+    /// wakes the tasks, after an operation driving the participant.
+    fn update(&mut self, now: Instant) {
+        self.process_events(now);
+        self.wake_tasks();
+    }
+
+    /// Updates the statuses of the entities with the events of the
+    /// RtpsParticipant and queues the events for poll_event.
+    fn process_events(&mut self, now: Instant) {
+        while let Some(event) = self.rtps.poll_event() {
+            match &event {
+                Event::DataAvailable { reader_guid } => self.receive_changes(now, *reader_guid),
+                Event::SampleLost {
+                    reader_guid, count, ..
+                } => {
+                    if let Some(reader) = self.readers.get_mut(reader_guid) {
+                        reader.sample_lost.increment(*count as i32);
+                        self.status_changed(*reader_guid, StatusKind::SAMPLE_LOST);
+                    }
+                }
+                Event::PublicationMatched {
+                    writer_guid,
+                    reader_guid,
+                } => self.publication_matched(*writer_guid, *reader_guid, true),
+                Event::PublicationUnmatched {
+                    writer_guid,
+                    reader_guid,
+                } => self.publication_matched(*writer_guid, *reader_guid, false),
+                Event::SubscriptionMatched {
+                    reader_guid,
+                    writer_guid,
+                } => self.subscription_matched(*reader_guid, *writer_guid, true),
+                Event::SubscriptionUnmatched {
+                    reader_guid,
+                    writer_guid,
+                } => {
+                    self.subscription_matched(*reader_guid, *writer_guid, false);
+                    self.writer_lost(now, *reader_guid, *writer_guid);
+                }
+                Event::OfferedIncompatibleQos { writer_guid, .. } => {
+                    self.status_changed(*writer_guid, StatusKind::OFFERED_INCOMPATIBLE_QOS)
+                }
+                Event::RequestedIncompatibleQos { reader_guid, .. } => {
+                    self.status_changed(*reader_guid, StatusKind::REQUESTED_INCOMPATIBLE_QOS)
+                }
+                Event::LivelinessChanged { reader_guid, .. } => {
+                    self.status_changed(*reader_guid, StatusKind::LIVELINESS_CHANGED)
+                }
+                Event::LivelinessLost { writer_guid } => {
+                    self.status_changed(*writer_guid, StatusKind::LIVELINESS_LOST)
+                }
+                _ => (),
+            }
+            self.events.push_back(event);
+        }
+    }
+
+    /// Wakes the tasks waiting for the DataWriters, as the changes may have
+    /// been acknowledged, the ones waiting for the DataReaders with a sample
+    /// not read yet and the ones waiting for the conditions.
+    fn wake_tasks(&mut self) {
+        for writer in self.writers.values_mut() {
+            writer.wakers.wake();
+        }
+        for reader in self.readers.values_mut() {
+            let read_changes = &reader.read_changes;
+            if reader
+                .received_changes
+                .keys()
+                .any(|change_id| !read_changes.contains(change_id))
+            {
+                reader.wakers.wake();
+            }
+        }
+        self.condition_wakers.wake();
+    }
+}
+
+fn topic_kind(entity_id: EntityId_t) -> TopicKind_t {
+    match entity_id.entity_kind() {
+        EntityId_t::ENTITYKIND_USER_DEFINED_WRITER_WITH_KEY
+        | EntityId_t::ENTITYKIND_USER_DEFINED_READER_WITH_KEY => TopicKind_t::WITH_KEY,
+        _ => TopicKind_t::NO_KEY,
+    }
+}
+
+fn reliability_level(kind: ReliabilityQosPolicyKind) -> ReliabilityKind_t {
+    match kind {
+        ReliabilityQosPolicyKind::BEST_EFFORT => ReliabilityKind_t::BEST_EFFORT,
+        ReliabilityQosPolicyKind::RELIABLE => ReliabilityKind_t::RELIABLE,
+    }
+}
+
+/// The operation on the deleted entity (ALREADY_DELETED).
+pub(crate) fn already_deleted() -> Error {
+    Error::new(ErrorKind::NotFound, "The entity was deleted")
+}
+
+/// The operation requires the enabled entity (NOT_ENABLED).
+pub(crate) fn not_enabled() -> Error {
+    Error::new(ErrorKind::NotConnected, "The entity is not enabled")
+}
+
+/// The operation did not complete within its time limit, e.g. the
+/// max_blocking_time of the write operations (TIMEOUT).
+pub(crate) fn timeout() -> Error {
+    Error::new(ErrorKind::TimedOut, "The operation timed out")
+}
+
+/// The policies of the QoS are not consistent with each other
+/// (INCONSISTENT_POLICY).
+pub(crate) fn inconsistent_policy(policy_id: QosPolicyId_t) -> Error {
+    Error::new(
+        ErrorKind::InvalidInput,
+        format!("The {} QoS policy is inconsistent", policy_id),
+    )
+}
+
+/// The policy cannot be changed once the entity is enabled
+/// (IMMUTABLE_POLICY).
+pub(crate) fn immutable_policy(policy_id: QosPolicyId_t) -> Error {
+    Error::new(
+        ErrorKind::PermissionDenied,
+        format!("The {} QoS policy cannot be changed", policy_id),
+    )
+}
+
+/// The entity is not in the state required by the operation
+/// (PRECONDITION_NOT_MET).
+pub(crate) fn precondition_not_met(message: impl Into<String>) -> Error {
+    Error::new(ErrorKind::InvalidInput, message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::behavior::writer::Writer;
+    use crate::dds::condition::condition::Condition;
+    use crate::dds::condition::wait_set::WaitSet;
+    use crate::dds::data_reader::DataReader;
+    use crate::dds::data_writer::DataWriter;
+    use crate::dds::instance_state_kind::{InstanceStateKind, ANY_INSTANCE_STATE};
+    use crate::dds::listener::data_reader_listener::DataReaderListener;
+    use crate::dds::listener::data_writer_listener::DataWriterListener;
+    use crate::dds::listener::subscriber_listener::SubscriberListener;
+    use crate::dds::qos::entity_factory_qos_policy::EntityFactoryQosPolicy;
+    use crate::dds::qos::history_qos_policy::HistoryQosPolicyKind;
+    use crate::dds::qos::partition_qos_policy::PartitionQosPolicy;
+    use crate::dds::qos::reliability_qos_policy::ReliabilityQosPolicy;
+    use crate::dds::sample_info::SampleInfo;
+    use crate::dds::sample_state_kind::{SampleStateKind, ANY_SAMPLE_STATE};
+    use crate::dds::status::publication_matched_status::PublicationMatchedStatus;
+    use crate::dds::status::sample_rejected_status_kind::SampleRejectedStatusKind;
+    use crate::dds::status::subscription_matched_status::SubscriptionMatchedStatus;
+    use crate::dds::type_support::tests::Shape;
+    use crate::dds::type_support::TypeSupport;
+    use crate::dds::view_state_kind::{ViewStateKind, ANY_VIEW_STATE};
+    use crate::messages::sender::MessageSender;
+    use crate::structure::duration::Duration_t;
+    use crate::structure::locator::{Ipv4Addr, SocketAddr};
+    use crate::structure::time::Time_t;
+    use crate::transport::simulated_network::{SimulatedNetwork, SimulatedTransport};
+    use crate::transport::transport_registry::tests::FailingTransport;
+    use std::time::Duration;
+
+    /// The enabled participant without transports, driven by the test.
+    fn participant() -> DomainParticipant {
+        enabled_participant(DomainParticipantQos::default())
+    }
+
+    fn enabled_participant(qos: DomainParticipantQos) -> DomainParticipant {
+        let participant = DomainParticipant::new(0, qos, VendorId_t::VENDOR_UNKNOWN);
+        participant.enable().unwrap();
+        participant
+    }
+
+    #[test]
+    fn entities_have_guids_of_their_kind() {
+        let participant = participant();
+        let topic = participant
+            .create_topic("topic", Shape::TYPE_NAME, TopicQos::default())
+            .unwrap();
+        let publisher = participant
+            .create_publisher(PublisherQos::default())
+            .unwrap();
+        let subscriber = participant
+            .create_subscriber(SubscriberQos::default())
+            .unwrap();
+        let writer = publisher
+            .create_datawriter::<Shape>(&topic, DataWriterQos::default())
+            .unwrap();
+        let reader = subscriber
+            .create_datareader::<Shape>(&topic, DataReaderQos::default())
+            .unwrap();
+
+        let guid_prefix = participant.guid().guid_prefix;
+        for (guid, entity_kind) in &[
+            (topic.guid(), EntityId_t::ENTITYKIND_USER_DEFINED_UNKNOWN),
+            (
+                publisher.guid(),
+                EntityId_t::ENTITYKIND_USER_DEFINED_WRITER_GROUP,
+            ),
+            (
+                subscriber.guid(),
+                EntityId_t::ENTITYKIND_USER_DEFINED_READER_GROUP,
+            ),
+            (
+                writer.guid(),
+                EntityId_t::ENTITYKIND_USER_DEFINED_WRITER_WITH_KEY,
+            ),
+            (
+                reader.guid(),
+                EntityId_t::ENTITYKIND_USER_DEFINED_READER_WITH_KEY,
+            ),
+        ] {
+            assert_eq!(guid_prefix, guid.guid_prefix);
+            assert_eq!(*entity_kind, guid.entity_id.entity_kind());
+        }
+        assert!(participant.contains_entity(writer.get_instance_handle()));
+        assert!(!participant.contains_entity(participant.get_instance_handle()));
+        assert_eq!(
+            Some(topic.clone()),
+            participant.lookup_topicdescription("topic")
+        );
+        assert_eq!(
+            Some(writer.clone()),
+            publisher.lookup_datawriter::<Shape>("topic")
+        );
+        assert_eq!(
+            Some(reader.clone()),
+            subscriber.lookup_datareader::<Shape>("topic")
+        );
+        assert_eq!(topic, writer.get_topic().unwrap());
+        assert_eq!(subscriber, reader.get_subscriber().unwrap());
+        assert_eq!(
+            ErrorKind::InvalidInput,
+            participant
+                .create_topic("topic", "other type", TopicQos::default())
+                .unwrap_err()
+                .kind()
+        );
+    }
+
+    #[test]
+    fn local_writers_and_readers_are_matched_until_deleted() {
+        let participant = participant();
+        let topic = participant
+            .create_topic("topic", Shape::TYPE_NAME, TopicQos::default())
+            .unwrap();
+        let publisher = participant
+            .create_publisher(PublisherQos::default())
+            .unwrap();
+        let subscriber = participant
+            .create_subscriber(SubscriberQos::default())
+            .unwrap();
+        let writer = publisher
+            .create_datawriter::<Shape>(&topic, DataWriterQos::default())
+            .unwrap();
+        let reader = subscriber
+            .create_datareader::<Shape>(&topic, DataReaderQos::default())
+            .unwrap();
+
+        assert!(writer.is_enabled() && reader.is_enabled());
+        assert_eq!(
+            vec![reader.get_instance_handle()],
+            writer.get_matched_subscriptions().unwrap()
+        );
+        assert_eq!(
+            vec![writer.get_instance_handle()],
+            reader.get_matched_publications().unwrap()
+        );
+
+        // The partitions of the Subscriber no longer match
+        subscriber
+            .set_qos(SubscriberQos {
+                partition: PartitionQosPolicy {
+                    name: vec!["other".to_string()],
+                },
+                ..SubscriberQos::default()
+            })
+            .unwrap();
+        assert!(writer.get_matched_subscriptions().unwrap().is_empty());
+        subscriber.set_qos(SubscriberQos::default()).unwrap();
+        assert_eq!(1, writer.get_matched_subscriptions().unwrap().len());
+
+        let other_publisher = participant
+            .create_publisher(PublisherQos::default())
+            .unwrap();
+        for error in [
+            participant.delete_topic(&topic),
+            participant.delete_publisher(&publisher),
+            participant.delete_subscriber(&subscriber),
+            other_publisher.delete_datawriter(&writer),
+        ] {
+            assert_eq!(ErrorKind::InvalidInput, error.unwrap_err().kind());
+        }
+
+        publisher.delete_datawriter(&writer).unwrap();
+        assert!(reader.get_matched_publications().unwrap().is_empty());
+        assert_eq!(
+            ErrorKind::NotFound,
+            writer.get_matched_subscriptions().unwrap_err().kind()
+        );
+
+        participant.delete_contained_entities().unwrap();
+        assert!(participant.is_empty());
+        assert_eq!(ErrorKind::NotFound, reader.get_qos().unwrap_err().kind());
+    }
+
+    #[test]
+    fn entities_are_enabled_by_their_factory() {
+        let participant = enabled_participant(DomainParticipantQos {
+            entity_factory: EntityFactoryQosPolicy {
+                autoenable_created_entities: false,
+            },
+            ..DomainParticipantQos::default()
+        });
+        let topic = participant
+            .create_topic("topic", Shape::TYPE_NAME, TopicQos::default())
+            .unwrap();
+        let publisher = participant
+            .create_publisher(PublisherQos::default())
+            .unwrap();
+        let writer = publisher
+            .create_datawriter::<Shape>(&topic, DataWriterQos::default())
+            .unwrap();
+        assert!(!topic.is_enabled() && !publisher.is_enabled() && !writer.is_enabled());
+        assert_eq!(
+            ErrorKind::NotConnected,
+            writer.get_matched_subscriptions().unwrap_err().kind()
+        );
+        assert_eq!(ErrorKind::InvalidInput, writer.enable().unwrap_err().kind());
+
+        topic.enable().unwrap();
+        // The DataWriter is enabled together with its Publisher
+        publisher.enable().unwrap();
+        assert!(writer.is_enabled());
+
+        participant
+            .set_qos(DomainParticipantQos::default())
+            .unwrap();
+        let subscriber = participant
+            .create_subscriber(SubscriberQos::default())
+            .unwrap();
+        let reader = subscriber
+            .create_datareader::<Shape>(&topic, DataReaderQos::default())
+            .unwrap();
+        assert!(subscriber.is_enabled() && reader.is_enabled());
+    }
+
+    #[test]
+    fn entities_with_inconsistent_qos_are_not_created() {
+        let participant = participant();
+        let mut topic_qos = TopicQos::default();
+        topic_qos.durability_service.history_depth = 0;
+        assert_eq!(
+            ErrorKind::InvalidInput,
+            participant
+                .create_topic("topic", Shape::TYPE_NAME, topic_qos.clone())
+                .unwrap_err()
+                .kind()
+        );
+        assert!(participant.set_default_topic_qos(topic_qos).is_err());
+
+        let topic = participant
+            .create_topic("topic", Shape::TYPE_NAME, TopicQos::default())
+            .unwrap();
+        let mut writer_qos = DataWriterQos::default();
+        writer_qos.resource_limits.max_samples = 0;
+        let publisher = participant
+            .create_publisher(PublisherQos::default())
+            .unwrap();
+        assert!(publisher
+            .create_datawriter::<Shape>(&topic, writer_qos.clone())
+            .is_err());
+        let writer = publisher
+            .create_datawriter::<Shape>(&topic, DataWriterQos::default())
+            .unwrap();
+        assert!(writer.set_qos(writer_qos).is_err());
+        assert_eq!(DataWriterQos::default(), writer.get_qos().unwrap());
+    }
+
+    #[test]
+    fn fragments_fit_into_the_messages_of_the_transports() {
+        let participant = participant();
+        let topic = participant
+            .create_topic("topic", Shape::TYPE_NAME, TopicQos::default())
+            .unwrap();
+        let publisher = participant
+            .create_publisher(PublisherQos::default())
+            .unwrap();
+        let fragment_size = |writer: &DataWriter<Shape>| {
+            let mut state = participant.state.lock().unwrap();
+            state
+                .rtps
+                .protocol_mut()
+                .writer_mut(writer.guid().entity_id)
+                .unwrap()
+                .as_writer()
+                .fragment_size
+        };
+
+        // The biggest fragments fitting into the messages by default
+        let writer = publisher
+            .create_datawriter::<Shape>(&topic, DataWriterQos::default())
+            .unwrap();
+        let max_fragment_size =
+            WriterAttributes::max_fragment_size(MessageSender::DEFAULT_MAX_MESSAGE_SIZE);
+        assert_eq!(max_fragment_size, fragment_size(&writer));
+
+        for invalid_fragment_size in [0, max_fragment_size + 1] {
+            let error = participant
+                .set_fragment_size(invalid_fragment_size)
+                .unwrap_err();
+            assert_eq!(ErrorKind::InvalidInput, error.kind());
+        }
+        participant.set_fragment_size(1024).unwrap();
+        let writer = publisher
+            .create_datawriter::<Shape>(&topic, DataWriterQos::default())
+            .unwrap();
+        assert_eq!(1024, fragment_size(&writer));
+    }
+
+    #[test]
+    fn immutable_policies_of_enabled_entities_are_not_changed() {
+        let participant = enabled_participant(DomainParticipantQos {
+            entity_factory: EntityFactoryQosPolicy {
+                autoenable_created_entities: false,
+            },
+            ..DomainParticipantQos::default()
+        });
+        let topic = participant
+            .create_topic("topic", Shape::TYPE_NAME, TopicQos::default())
+            .unwrap();
+        let writer = participant
+            .create_publisher(PublisherQos::default())
+            .unwrap()
+            .create_datawriter::<Shape>(&topic, DataWriterQos::default())
+            .unwrap();
+        let reader = participant
+            .create_subscriber(SubscriberQos::default())
+            .unwrap()
+            .create_datareader::<Shape>(&topic, DataReaderQos::default())
+            .unwrap();
+        let writer_qos = DataWriterQos {
+            reliability: ReliabilityQosPolicy::default(),
+            ..DataWriterQos::default()
+        };
+        let mut reader_qos = DataReaderQos::default();
+        reader_qos.history.depth = 10;
+
+        // Any policy is changed before the entities are enabled
+        writer.set_qos(writer_qos.clone()).unwrap();
+        reader.set_qos(reader_qos.clone()).unwrap();
+        topic.enable().unwrap();
+        writer.get_publisher().unwrap().enable().unwrap();
+        reader.get_subscriber().unwrap().enable().unwrap();
+        assert!(writer.is_enabled() && reader.is_enabled());
+
+        assert_eq!(
+            ErrorKind::PermissionDenied,
+            writer.set_qos(DataWriterQos::default()).unwrap_err().kind()
+        );
+        assert_eq!(writer_qos, writer.get_qos().unwrap());
+        assert_eq!(
+            ErrorKind::PermissionDenied,
+            reader.set_qos(DataReaderQos::default()).unwrap_err().kind()
+        );
+        assert_eq!(reader_qos, reader.get_qos().unwrap());
+
+        let mut changed_reader_qos = reader_qos;
+        changed_reader_qos.deadline.period = Duration_t::from(Duration::from_secs(1));
+        reader.set_qos(changed_reader_qos.clone()).unwrap();
+        assert_eq!(changed_reader_qos, reader.get_qos().unwrap());
+    }
+
+    fn timestamp(seconds: u64) -> Time_t {
+        Time_t::from(std::time::SystemTime::UNIX_EPOCH + Duration::from_secs(seconds))
+    }
+
+    #[test]
+    fn written_samples_are_read_and_taken() {
+        let participant = participant();
+        let topic = participant
+            .create_topic("topic", Shape::TYPE_NAME, TopicQos::default())
+            .unwrap();
+        let writer = participant
+            .create_publisher(PublisherQos::default())
+            .unwrap()
+            .create_datawriter::<Shape>(&topic, DataWriterQos::default())
+            .unwrap();
+        let mut reader_qos = DataReaderQos::default();
+        reader_qos.history.depth = 2;
+        let reader = participant
+            .create_subscriber(SubscriberQos::default())
+            .unwrap()
+            .create_datareader::<Shape>(&topic, reader_qos)
+            .unwrap();
+        let red = Shape::new("RED", 1, 2);
+        let blue = Shape::new("BLUE", 3, 4);
+
+        writer.write_w_timestamp(&red, timestamp(1)).unwrap();
+        writer.write_w_timestamp(&blue, timestamp(2)).unwrap();
+        while participant.poll_transmit().is_some() {}
+
+        let samples = reader.read(10).unwrap();
+        assert_eq!(
+            vec![Some(red.clone()), Some(blue.clone())],
+            samples
+                .iter()
+                .map(|sample| sample.data.clone())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            SampleInfo {
+                sample_state: SampleStateKind::NOT_READ,
+                view_state: ViewStateKind::NEW,
+                instance_state: InstanceStateKind::ALIVE,
+                disposed_generation_count: 0,
+                no_writers_generation_count: 0,
+                source_timestamp: timestamp(1),
+                instance_handle: red.instance_handle(),
+                publication_handle: writer.get_instance_handle(),
+                valid_data: true,
+            },
+            samples[0].info
+        );
+        assert!(reader.read_next_sample().unwrap().is_none());
+
+        writer.write_w_timestamp(&red, timestamp(3)).unwrap();
+        while participant.poll_transmit().is_some() {}
+
+        // Only the new sample is not read, its instance was already seen
+        let sample = reader.take_next_sample().unwrap().unwrap();
+        assert_eq!(Some(red.clone()), sample.data);
+        assert_eq!(SampleStateKind::NOT_READ, sample.info.sample_state);
+        assert_eq!(ViewStateKind::NOT_NEW, sample.info.view_state);
+        assert_eq!(timestamp(3), sample.info.source_timestamp);
+
+        let samples = reader.take(1).unwrap();
+        assert_eq!(1, samples.len());
+        assert_eq!(SampleStateKind::READ, samples[0].info.sample_state);
+        assert_eq!(Some(red), samples[0].data);
+        assert_eq!(1, reader.take(10).unwrap().len());
+        assert!(reader.read(10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn instances_are_registered_until_unregistered() {
+        let participant = participant();
+        let topic = participant
+            .create_topic("topic", Shape::TYPE_NAME, TopicQos::default())
+            .unwrap();
+        let publisher = participant
+            .create_publisher(PublisherQos::default())
+            .unwrap();
+        let writer = publisher
+            .create_datawriter::<Shape>(&topic, DataWriterQos::default())
+            .unwrap();
+        let red = Shape::new("RED", 1, 2);
+
+        assert_eq!(InstanceHandle_t::HANDLE_NIL, writer.lookup_instance(&red));
+        assert_eq!(
+            red.instance_handle(),
+            writer.register_instance(&red).unwrap()
+        );
+        assert_eq!(red.instance_handle(), writer.lookup_instance(&red));
+        writer.unregister_instance(&red).unwrap();
+        assert_eq!(InstanceHandle_t::HANDLE_NIL, writer.lookup_instance(&red));
+        assert_eq!(
+            ErrorKind::InvalidInput,
+            writer.unregister_instance(&red).unwrap_err().kind()
+        );
+
+        writer.write(&red).unwrap();
+        assert_eq!(red.instance_handle(), writer.lookup_instance(&red));
+        writer.dispose(&red).unwrap();
+        assert_eq!(
+            ErrorKind::InvalidInput,
+            publisher
+                .create_datawriter::<Shape>(
+                    &participant
+                        .create_topic("other topic", "other type", TopicQos::default())
+                        .unwrap(),
+                    DataWriterQos::default()
+                )
+                .unwrap_err()
+                .kind()
+        );
+
+        publisher
+            .set_qos(PublisherQos {
+                entity_factory: EntityFactoryQosPolicy {
+                    autoenable_created_entities: false,
+                },
+                ..PublisherQos::default()
+            })
+            .unwrap();
+        let disabled_writer = publisher
+            .create_datawriter::<Shape>(&topic, DataWriterQos::default())
+            .unwrap();
+        assert_eq!(
+            ErrorKind::NotConnected,
+            disabled_writer.write(&red).unwrap_err().kind()
+        );
+    }
+
+    fn writer_and_reader(
+        participant: &DomainParticipant,
+        writer_qos: DataWriterQos,
+    ) -> (DataWriter<Shape>, Subscriber, DataReader<Shape>) {
+        let topic = participant
+            .create_topic("topic", Shape::TYPE_NAME, TopicQos::default())
+            .unwrap();
+        let writer = participant
+            .create_publisher(PublisherQos::default())
+            .unwrap()
+            .create_datawriter::<Shape>(&topic, writer_qos)
+            .unwrap();
+        let subscriber = participant
+            .create_subscriber(SubscriberQos::default())
+            .unwrap();
+        let reader = subscriber
+            .create_datareader::<Shape>(&topic, DataReaderQos::default())
+            .unwrap();
+        (writer, subscriber, reader)
+    }
+
+    fn keep_all(max_samples: i32) -> DataWriterQos {
+        let mut qos = DataWriterQos::default();
+        qos.history.kind = HistoryQosPolicyKind::KEEP_ALL;
+        qos.resource_limits.max_samples = max_samples;
+        qos
+    }
+
+    #[test]
+    fn keep_last_history_keeps_the_latest_samples_of_each_instance() {
+        let participant = participant();
+        let mut qos = DataWriterQos::default();
+        qos.history.depth = 2;
+        let (writer, subscriber, _) = writer_and_reader(&participant, qos);
+        let mut reader_qos = DataReaderQos::default();
+        reader_qos.history.depth = 10;
+        let reader = subscriber
+            .create_datareader::<Shape>(&writer.get_topic().unwrap(), reader_qos)
+            .unwrap();
+
+        for x in 1..=3 {
+            writer.write(&Shape::new("RED", x, 0)).unwrap();
+        }
+        writer.write(&Shape::new("BLUE", 1, 0)).unwrap();
+        while participant.poll_transmit().is_some() {}
+
+        assert_eq!(
+            vec![
+                Some(Shape::new("RED", 2, 0)),
+                Some(Shape::new("RED", 3, 0)),
+                Some(Shape::new("BLUE", 1, 0))
+            ],
+            reader
+                .take(10)
+                .unwrap()
+                .into_iter()
+                .map(|sample| sample.data)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn keep_last_reader_keeps_the_latest_samples_of_each_instance() {
+        let participant = participant();
+        let (writer, _, reader) = writer_and_reader(&participant, keep_all(10));
+
+        for x in 1..=3 {
+            writer.write(&Shape::new("RED", x, 0)).unwrap();
+        }
+        while participant.poll_transmit().is_some() {}
+
+        // The default DataReaderQos keeps the last sample
+        assert_eq!(
+            vec![Some(Shape::new("RED", 3, 0))],
+            reader
+                .read(10)
+                .unwrap()
+                .into_iter()
+                .map(|sample| sample.data)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn keep_all_history_is_full_until_the_changes_are_acknowledged() {
+        let participant = participant();
+        let mut qos = keep_all(1);
+        qos.reliability.max_blocking_time = Duration_t::from(Duration::from_millis(200));
+        let (writer, _, reader) = writer_and_reader(&participant, qos);
+        let red = Shape::new("RED", 1, 2);
+        let blue = Shape::new("BLUE", 3, 4);
+
+        writer.write(&red).unwrap();
+        let started = Instant::now();
+        assert_eq!(ErrorKind::TimedOut, writer.write(&blue).unwrap_err().kind());
+        assert!(started.elapsed() >= Duration::from_millis(200));
+
+        // The write blocks until the participant is driven by another
+        // thread; the best-effort DataReader does not acknowledge the sent
+        // changes
+        let driver = participant.clone();
+        let driving = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(10));
+            while driver.poll_transmit().is_some() {}
+        });
+        writer.write(&blue).unwrap();
+        driving.join().unwrap();
+        while participant.poll_transmit().is_some() {}
+
+        assert_eq!(2, reader.take(10).unwrap().len());
+    }
+
+    #[tokio::test]
+    async fn async_write_times_out_after_the_max_blocking_time() {
+        let participant = participant();
+        let mut qos = keep_all(1);
+        qos.reliability.max_blocking_time = Duration_t::from(Duration::from_millis(10));
+        let (writer, _, _) = writer_and_reader(&participant, qos);
+
+        writer.write_async(&Shape::new("RED", 1, 2)).await.unwrap();
+        assert_eq!(
+            ErrorKind::TimedOut,
+            writer
+                .write_async(&Shape::new("BLUE", 3, 4))
+                .await
+                .unwrap_err()
+                .kind()
+        );
+    }
+
+    #[tokio::test]
+    async fn async_write_waits_for_room_and_reader_streams_samples() {
+        use futures::StreamExt;
+
+        let participant = participant();
+        let (writer, subscriber, reader) = writer_and_reader(&participant, keep_all(1));
+        let mut stream = reader.clone();
+        let red = Shape::new("RED", 1, 2);
+        let blue = Shape::new("BLUE", 3, 4);
+        let green = Shape::new("GREEN", 5, 6);
+
+        assert!(futures::poll!(stream.next()).is_pending());
+        writer.write_async(&red).await.unwrap();
+
+        // The dropped write does not write anything
+        let mut write_blue = Box::pin(writer.write_async(&blue));
+        assert!(futures::poll!(&mut write_blue).is_pending());
+        drop(write_blue);
+
+        let mut write_green = Box::pin(writer.write_async(&green));
+        assert!(futures::poll!(&mut write_green).is_pending());
+        while participant.poll_transmit().is_some() {}
+        write_green.await.unwrap();
+        while participant.poll_transmit().is_some() {}
+
+        assert_eq!(Some(red), stream.next().await.unwrap().data);
+        assert_eq!(Some(green), stream.next().await.unwrap().data);
+        assert!(futures::poll!(stream.next()).is_pending());
+
+        // The stream ends with the deletion of the DataReader
+        subscriber.delete_datareader(&reader).unwrap();
+        assert!(stream.next().await.is_none());
+    }
+
+    /// Participants of the domain with the discovery enabled on the
+    /// network, their default locators are the metatraffic unicast
+    /// locators.
+    fn discovering_participants(
+        network: &SimulatedNetwork,
+    ) -> Vec<(DomainParticipant, SimulatedTransport)> {
+        let multicast_locator =
+            Locator_t::from(SocketAddr::new(Ipv4Addr::new(239, 255, 0, 1).into(), 7400));
+        (0..2)
+            .map(|index| {
+                let unicast_locator =
+                    Locator_t::from(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 7410 + index));
+                let participant = participant();
+                let clock = network.clone();
+                participant.set_clock(move || clock.now());
+                participant
+                    .set_metatraffic_locators(vec![unicast_locator], vec![multicast_locator]);
+                participant.set_default_locators(vec![unicast_locator], vec![]);
+                participant.enable_discovery(
+                    vec![multicast_locator],
+                    DiscoveryConfig {
+                        resend_period: Duration::from_secs(1),
+                        lease_duration: Duration::from_secs(3),
+                        heartbeat_period: Duration::from_secs(1),
+                    },
+                );
+                let transport = network.transport(unicast_locator, &[multicast_locator]);
+                (participant, transport)
+            })
+            .collect()
+    }
+
+    /// Runs the participants attached to the network for the duration.
+    fn run(
+        network: &SimulatedNetwork,
+        participants: &[(DomainParticipant, SimulatedTransport)],
+        duration: Duration,
+    ) {
+        let until = network.now() + duration;
+        loop {
+            let now = network.now();
+            for (participant, transport) in participants {
+                if participant
+                    .poll_timeout()
+                    .is_some_and(|timeout| timeout <= now)
+                {
+                    participant.handle_timeout(now);
+                }
+                while let Some(transmit) = participant.poll_transmit() {
+                    transport
+                        .send_datagram(transmit.destination, transmit.contents)
+                        .unwrap();
+                }
+            }
+            network.advance_to(now);
+
+            let mut received = false;
+            for (participant, transport) in participants {
+                while let Some((src, datagram)) = transport.try_recv() {
+                    participant.handle_datagram(now, src, datagram);
+                    received = true;
+                }
+            }
+            if received {
+                continue;
+            }
+
+            let next = participants
+                .iter()
+                .filter_map(|(participant, _)| participant.poll_timeout())
+                .chain(network.next_delivery())
+                .min();
+            match next {
+                Some(next) if next <= until => network.advance_to(next),
+                _ => break,
+            }
+        }
+        network.advance_to(until);
+    }
+
+    #[test]
+    fn remote_writers_and_readers_are_matched_and_communicate() {
+        let network = SimulatedNetwork::new(17, Instant::now());
+        let participants = discovering_participants(&network);
+        let topic = participants[0]
+            .0
+            .create_topic("topic", Shape::TYPE_NAME, TopicQos::default())
+            .unwrap();
+        let writer = participants[0]
+            .0
+            .create_publisher(PublisherQos::default())
+            .unwrap()
+            .create_datawriter::<Shape>(&topic, DataWriterQos::default())
+            .unwrap();
+        let remote_topic = participants[1]
+            .0
+            .create_topic("topic", Shape::TYPE_NAME, TopicQos::default())
+            .unwrap();
+        let reader = participants[1]
+            .0
+            .create_subscriber(SubscriberQos::default())
+            .unwrap()
+            .create_datareader::<Shape>(&remote_topic, DataReaderQos::default())
+            .unwrap();
+
+        run(&network, &participants, Duration::from_secs(5));
+
+        assert_eq!(
+            vec![reader.get_instance_handle()],
+            writer.get_matched_subscriptions().unwrap()
+        );
+        assert_eq!(
+            vec![writer.get_instance_handle()],
+            reader.get_matched_publications().unwrap()
+        );
+
+        let red = Shape::new("RED", 1, 2);
+        writer.write_w_timestamp(&red, timestamp(1)).unwrap();
+        run(&network, &participants, Duration::from_secs(1));
+
+        let samples = reader.take(10).unwrap();
+        assert_eq!(1, samples.len());
+        assert_eq!(Some(red), samples[0].data);
+        assert_eq!(timestamp(1), samples[0].info.source_timestamp);
+        assert_eq!(
+            writer.get_instance_handle(),
+            samples[0].info.publication_handle
+        );
+    }
+
+    #[test]
+    fn content_filtered_samples_are_filtered_by_the_remote_writer() {
+        use crate::behavior::writer::Writer;
+
+        let network = SimulatedNetwork::new(23, Instant::now());
+        let participants = discovering_participants(&network);
+        let topic = participants[0]
+            .0
+            .create_topic("topic", Shape::TYPE_NAME, TopicQos::default())
+            .unwrap();
+        let writer = participants[0]
+            .0
+            .create_publisher(PublisherQos::default())
+            .unwrap()
+            .create_datawriter::<Shape>(&topic, DataWriterQos::default())
+            .unwrap();
+        let remote_topic = participants[1]
+            .0
+            .create_topic("topic", Shape::TYPE_NAME, TopicQos::default())
+            .unwrap();
+        assert_eq!(
+            ErrorKind::InvalidInput,
+            participants[1]
+                .0
+                .create_contentfilteredtopic("filtered", &remote_topic, "x >", &[])
+                .unwrap_err()
+                .kind()
+        );
+        let filtered_topic = participants[1]
+            .0
+            .create_contentfilteredtopic("filtered", &remote_topic, "x > %0", &["10".to_string()])
+            .unwrap();
+        let reader = participants[1]
+            .0
+            .create_subscriber(SubscriberQos::default())
+            .unwrap()
+            .create_datareader::<Shape>(&filtered_topic, DataReaderQos::default())
+            .unwrap();
+        run(&network, &participants, Duration::from_secs(5));
+
+        let blue = Shape::new("BLUE", 20, 0);
+        writer.write(&Shape::new("RED", 5, 0)).unwrap();
+        writer.write(&blue).unwrap();
+        run(&network, &participants, Duration::from_secs(1));
+
+        let samples = reader.take(10).unwrap();
+        assert_eq!(1, samples.len());
+        assert_eq!(Some(blue), samples[0].data);
+
+        let mut state = participants[0].0.state.lock().unwrap();
+        let rtps_writer = state
+            .rtps
+            .protocol_mut()
+            .writer_mut(writer.guid().entity_id)
+            .unwrap();
+        let reader_proxy = rtps_writer
+            .matched_reader_lookup(reader.guid())
+            .next()
+            .unwrap();
+        let relevant: Vec<bool> = rtps_writer
+            .as_writer()
+            .writer_cache
+            .changes()
+            .iter()
+            .map(|change| reader_proxy.change_for_reader(change).is_relevant)
+            .collect();
+        assert_eq!(vec![false, true], relevant);
+    }
+
+    #[test]
+    fn content_filtered_samples_are_filtered_by_the_reader() {
+        use crate::dds::cdr::cdr_readable::CdrReadable;
+        use crate::dds::cdr::cdr_reader::CdrReader;
+        use crate::dds::cdr::cdr_writable::CdrWritable;
+        use crate::dds::cdr::cdr_writer::CdrWriter;
+
+        /// A Shape whose DataWriters cannot filter.
+        struct UnfilteredShape(Shape);
+
+        impl CdrWritable for UnfilteredShape {
+            fn write_cdr(&self, writer: &mut CdrWriter) {
+                self.0.write_cdr(writer)
+            }
+        }
+
+        impl CdrReadable for UnfilteredShape {
+            fn read_cdr(reader: &mut CdrReader<'_>) -> Result<Self, std::io::Error> {
+                Shape::read_cdr(reader).map(UnfilteredShape)
+            }
+        }
+
+        impl TypeSupport for UnfilteredShape {
+            const TYPE_NAME: &'static str = Shape::TYPE_NAME;
+            const WITH_KEY: bool = true;
+
+            type Key = String;
+
+            fn key(&self) -> String {
+                self.0.key()
+            }
+        }
+
+        let participant = participant();
+        let topic = participant
+            .create_topic("topic", Shape::TYPE_NAME, TopicQos::default())
+            .unwrap();
+        let filtered_topic = participant
+            .create_contentfilteredtopic("filtered", &topic, "color = 'BLUE'", &[])
+            .unwrap();
+        let subscriber = participant
+            .create_subscriber(SubscriberQos::default())
+            .unwrap();
+        assert_eq!(
+            ErrorKind::InvalidInput,
+            subscriber
+                .create_datareader::<UnfilteredShape>(&filtered_topic, DataReaderQos::default())
+                .unwrap_err()
+                .kind()
+        );
+        let reader = subscriber
+            .create_datareader::<Shape>(&filtered_topic, DataReaderQos::default())
+            .unwrap();
+        let writer = participant
+            .create_publisher(PublisherQos::default())
+            .unwrap()
+            .create_datawriter::<UnfilteredShape>(&topic, DataWriterQos::default())
+            .unwrap();
+        for color in ["RED", "BLUE"] {
+            writer
+                .write(&UnfilteredShape(Shape::new(color, 1, 2)))
+                .unwrap();
+        }
+        while participant.poll_transmit().is_some() {}
+
+        let samples = reader.take(10).unwrap();
+        assert_eq!(1, samples.len());
+        assert_eq!(Some(Shape::new("BLUE", 1, 2)), samples[0].data);
+    }
+
+    #[tokio::test]
+    async fn participants_run_with_udp_transports_communicate() {
+        use crate::transport::udp_v4_transport::UdpV4Transport;
+        use futures::StreamExt;
+
+        let transports: Vec<UdpV4Transport> = (0..2)
+            .map(|_| {
+                UdpV4Transport::new(
+                    Ipv4Addr::LOCALHOST,
+                    UdpV4Transport::DEFAULT_MULTICAST_GROUP,
+                    0,
+                    0,
+                    0,
+                    0,
+                )
+                .unwrap()
+            })
+            .collect();
+        let announcement_locators: Vec<Locator_t> = transports
+            .iter()
+            .map(|transport| transport.metatraffic_unicast_locator())
+            .collect();
+        let participants: Vec<DomainParticipant> = transports
+            .into_iter()
+            .map(|transport| {
+                let participant = participant();
+                participant.set_metatraffic_locators(
+                    vec![transport.metatraffic_unicast_locator()],
+                    vec![],
+                );
+                participant.set_default_locators(vec![transport.user_unicast_locator()], vec![]);
+                participant.add_transport(Box::new(transport));
+                participant.enable_discovery(
+                    announcement_locators.clone(),
+                    DiscoveryConfig {
+                        resend_period: Duration::from_millis(100),
+                        lease_duration: Duration::from_secs(3),
+                        heartbeat_period: Duration::from_millis(100),
+                    },
+                );
+                participant
+            })
+            .collect();
+
+        let topic = participants[0]
+            .create_topic("topic", Shape::TYPE_NAME, TopicQos::default())
+            .unwrap();
+        let writer = participants[0]
+            .create_publisher(PublisherQos::default())
+            .unwrap()
+            .create_datawriter::<Shape>(&topic, DataWriterQos::default())
+            .unwrap();
+        let remote_topic = participants[1]
+            .create_topic("topic", Shape::TYPE_NAME, TopicQos::default())
+            .unwrap();
+        let mut reader = participants[1]
+            .create_subscriber(SubscriberQos::default())
+            .unwrap()
+            .create_datareader::<Shape>(&remote_topic, DataReaderQos::default())
+            .unwrap();
+
+        let red = Shape::new("RED", 1, 2);
+        let exchange = async {
+            while writer.get_matched_subscriptions().unwrap().is_empty() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            writer.write(&red).unwrap();
+            reader.next().await.unwrap()
+        };
+        let sample = tokio::time::timeout(Duration::from_secs(10), async {
+            tokio::select! {
+                sample = exchange => sample,
+                result = participants[0].run() => panic!("{:?}", result),
+                result = participants[1].run() => panic!("{:?}", result),
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(Some(red), sample.data);
+    }
+
+    #[tokio::test]
+    async fn participant_runs_until_no_transport_remains() {
+        let participant = participant();
+        participant.add_transport(Box::new(FailingTransport));
+        participant.add_transport(Box::new(FailingTransport));
+
+        let error = tokio::time::timeout(Duration::from_secs(5), participant.run())
+            .await
+            .unwrap()
+            .unwrap_err();
+
+        assert_eq!(ErrorKind::NotConnected, error.kind());
+        assert!(participant
+            .state
+            .lock()
+            .unwrap()
+            .rtps
+            .transports()
+            .is_empty());
+    }
+
+    /// Records the notified statuses.
+    #[derive(Clone, Default)]
+    struct Notified(Arc<Mutex<Vec<String>>>);
+
+    impl Notified {
+        fn take(&self) -> Vec<String> {
+            std::mem::take(&mut *self.0.lock().unwrap())
+        }
+
+        fn push(&self, notification: String) {
+            self.0.lock().unwrap().push(notification)
+        }
+    }
+
+    impl DataWriterListener<Shape> for Notified {
+        fn on_publication_matched(
+            &mut self,
+            _writer: &DataWriter<Shape>,
+            status: PublicationMatchedStatus,
+        ) {
+            self.push(format!("publication matched {}", status.current_count))
+        }
+    }
+
+    impl DataReaderListener<Shape> for Notified {
+        fn on_data_available(&mut self, reader: &DataReader<Shape>) {
+            let samples = reader.take(10).unwrap();
+            self.push(format!("data available {}", samples.len()))
+        }
+
+        fn on_subscription_matched(
+            &mut self,
+            _reader: &DataReader<Shape>,
+            status: SubscriptionMatchedStatus,
+        ) {
+            self.push(format!("subscription matched {}", status.current_count))
+        }
+    }
+
+    impl SubscriberListener for Notified {
+        fn on_data_on_readers(&mut self, _subscriber: &Subscriber) {
+            self.push("data on readers".to_string())
+        }
+    }
+
+    #[test]
+    fn listeners_are_notified_of_the_changed_statuses() {
+        let participant = participant();
+        let (writer, subscriber, reader) =
+            writer_and_reader(&participant, DataWriterQos::default());
+        let notified = Notified::default();
+        writer
+            .set_listener(Some(Box::new(notified.clone())), StatusMask::all())
+            .unwrap();
+        reader
+            .set_listener(Some(Box::new(notified.clone())), StatusMask::all())
+            .unwrap();
+
+        writer.write(&Shape::new("RED", 1, 2)).unwrap();
+        while participant.poll_transmit().is_some() {}
+        let mut notifications = notified.take();
+        notifications.sort();
+        assert_eq!(
+            vec![
+                "data available 1",
+                "publication matched 1",
+                "subscription matched 1"
+            ],
+            notifications
+        );
+        // The statuses are read by the listeners
+        assert!(reader.get_status_changes().unwrap().is_empty());
+        assert_eq!(
+            0,
+            reader
+                .get_subscription_matched_status()
+                .unwrap()
+                .current_count_change
+        );
+
+        // The Subscriber is notified instead of its DataReaders
+        subscriber
+            .set_listener(
+                Some(Box::new(notified.clone())),
+                StatusKind::DATA_ON_READERS,
+            )
+            .unwrap();
+        writer.write(&Shape::new("RED", 3, 4)).unwrap();
+        while participant.poll_transmit().is_some() {}
+        assert_eq!(vec!["data on readers"], notified.take());
+        assert_eq!(1, reader.take(10).unwrap().len());
+
+        reader.set_listener(None, StatusMask::empty()).unwrap();
+        subscriber.set_listener(None, StatusMask::empty()).unwrap();
+        writer.write(&Shape::new("RED", 5, 6)).unwrap();
+        while participant.poll_transmit().is_some() {}
+        assert!(notified.take().is_empty());
+        assert_eq!(
+            StatusKind::DATA_AVAILABLE,
+            reader.get_status_changes().unwrap()
+        );
+    }
+
+    #[test]
+    fn conditions_trigger_the_wait_set() {
+        let participant = participant();
+        let (writer, _, reader) = writer_and_reader(&participant, DataWriterQos::default());
+        let status_condition = reader.get_statuscondition();
+        status_condition
+            .set_enabled_statuses(StatusKind::DATA_AVAILABLE)
+            .unwrap();
+        let read_condition = reader.create_readcondition(
+            &[SampleStateKind::NOT_READ],
+            ANY_VIEW_STATE,
+            ANY_INSTANCE_STATE,
+        );
+        let query_condition = reader
+            .create_querycondition(
+                ANY_SAMPLE_STATE,
+                ANY_VIEW_STATE,
+                ANY_INSTANCE_STATE,
+                "x > %0",
+                &["1".to_string()],
+            )
+            .unwrap();
+        let mut wait_set = WaitSet::new();
+        wait_set.attach_condition(status_condition.clone());
+        wait_set.attach_condition(read_condition.clone());
+        wait_set.attach_condition(query_condition.clone());
+        while participant.poll_transmit().is_some() {}
+        assert_eq!(
+            ErrorKind::TimedOut,
+            wait_set.wait(Duration::ZERO).unwrap_err().kind()
+        );
+
+        writer.write(&Shape::new("RED", 1, 0)).unwrap();
+        while participant.poll_transmit().is_some() {}
+        assert_eq!(
+            vec![
+                Condition::from(status_condition.clone()),
+                Condition::from(read_condition.clone())
+            ],
+            wait_set.wait(Duration::ZERO).unwrap()
+        );
+
+        writer.write(&Shape::new("BLUE", 2, 0)).unwrap();
+        while participant.poll_transmit().is_some() {}
+        let samples = reader.take_w_condition(10, &query_condition).unwrap();
+        assert_eq!(
+            vec![Some(Shape::new("BLUE", 2, 0))],
+            samples
+                .into_iter()
+                .map(|sample| sample.data)
+                .collect::<Vec<_>>()
+        );
+        // Reading the samples resets DATA_AVAILABLE
+        assert_eq!(
+            1,
+            reader.read_w_condition(10, &read_condition).unwrap().len()
+        );
+        assert!(!status_condition.get_trigger_value());
+        assert!(!read_condition.get_trigger_value() && !query_condition.get_trigger_value());
+
+        let other_reader = participant
+            .create_subscriber(SubscriberQos::default())
+            .unwrap()
+            .create_datareader::<Shape>(
+                &reader.get_topicdescription().unwrap(),
+                DataReaderQos::default(),
+            )
+            .unwrap();
+        assert_eq!(
+            ErrorKind::InvalidInput,
+            other_reader
+                .read_w_condition(10, &read_condition)
+                .unwrap_err()
+                .kind()
+        );
+        assert!(reader
+            .create_querycondition(
+                ANY_SAMPLE_STATE,
+                ANY_VIEW_STATE,
+                ANY_INSTANCE_STATE,
+                "x >",
+                &[]
+            )
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn wait_set_waits_asynchronously_for_the_conditions() {
+        let participant = participant();
+        let (writer, _, reader) = writer_and_reader(&participant, DataWriterQos::default());
+        let mut wait_set = WaitSet::new();
+        wait_set.attach_condition(reader.create_readcondition(
+            ANY_SAMPLE_STATE,
+            ANY_VIEW_STATE,
+            ANY_INSTANCE_STATE,
+        ));
+
+        let (conditions, _) = tokio::join!(wait_set.wait_async(), async {
+            tokio::task::yield_now().await;
+            writer.write(&Shape::new("RED", 1, 2)).unwrap();
+            while participant.poll_transmit().is_some() {}
+        });
+        assert_eq!(wait_set.get_conditions(), &conditions[..]);
+    }
+
+    #[test]
+    fn samples_beyond_the_resource_limits_are_rejected() {
+        let participant = participant();
+        let (writer, subscriber, _) = writer_and_reader(&participant, keep_all(10));
+        let mut qos = DataReaderQos::default();
+        qos.history.kind = HistoryQosPolicyKind::KEEP_ALL;
+        qos.resource_limits.max_samples = 2;
+        qos.resource_limits.max_instances = 1;
+        let reader = subscriber
+            .create_datareader::<Shape>(&writer.get_topic().unwrap(), qos)
+            .unwrap();
+
+        for shape in &[
+            Shape::new("RED", 1, 2),
+            Shape::new("BLUE", 1, 2),
+            Shape::new("RED", 3, 4),
+            Shape::new("RED", 5, 6),
+        ] {
+            writer.write(shape).unwrap();
+        }
+        while participant.poll_transmit().is_some() {}
+
+        let status = reader.get_sample_rejected_status().unwrap();
+        assert_eq!(2, status.total_count);
+        assert_eq!(2, status.total_count_change);
+        assert_eq!(
+            SampleRejectedStatusKind::REJECTED_BY_SAMPLES_LIMIT,
+            status.last_reason
+        );
+        assert_eq!(
+            0,
+            reader
+                .get_sample_rejected_status()
+                .unwrap()
+                .total_count_change
+        );
+        assert_eq!(
+            vec![Some(Shape::new("RED", 1, 2)), Some(Shape::new("RED", 3, 4))],
+            reader
+                .take(10)
+                .unwrap()
+                .into_iter()
+                .map(|sample| sample.data)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn missed_deadlines_are_counted_per_instance() {
+        let participant = participant();
+        let now = Arc::new(Mutex::new(Instant::now()));
+        let clock = now.clone();
+        participant.set_clock(move || *clock.lock().unwrap());
+        let mut qos = DataWriterQos::default();
+        qos.deadline.period = Duration_t::from(Duration::from_secs(1));
+        let (writer, subscriber, _) = writer_and_reader(&participant, qos);
+        let mut reader_qos = DataReaderQos::default();
+        reader_qos.deadline.period = Duration_t::from(Duration::from_secs(2));
+        let reader = subscriber
+            .create_datareader::<Shape>(&writer.get_topic().unwrap(), reader_qos)
+            .unwrap();
+        let red = Shape::new("RED", 1, 2);
+        writer.write(&red).unwrap();
+        while participant.poll_transmit().is_some() {}
+
+        let start = *now.lock().unwrap();
+        assert!(participant.poll_timeout().unwrap() <= start + Duration::from_secs(1));
+        participant.handle_timeout(start + Duration::from_secs(2));
+        let status = writer.get_offered_deadline_missed_status().unwrap();
+        assert_eq!(2, status.total_count);
+        assert_eq!(red.instance_handle(), status.last_instance_handle);
+        assert_eq!(
+            1,
+            reader
+                .get_requested_deadline_missed_status()
+                .unwrap()
+                .total_count
+        );
+        assert!(reader
+            .get_status_changes()
+            .unwrap()
+            .contains(StatusKind::DATA_AVAILABLE));
+    }
+
+    #[test]
+    fn disposed_and_unregistered_instances_are_not_alive() {
+        let participant = participant();
+        let mut qos = keep_all(10);
+        qos.writer_data_lifecycle.autodispose_unregistered_instances = false;
+        let (writer, _, reader) = writer_and_reader(&participant, qos);
+        let red = Shape::new("RED", 1, 2);
+        let blue = Shape::new("BLUE", 3, 4);
+        writer.write(&red).unwrap();
+        writer.write(&blue).unwrap();
+        while participant.poll_transmit().is_some() {}
+        assert_eq!(2, reader.take(10).unwrap().len());
+
+        writer.dispose(&red).unwrap();
+        writer.unregister_instance(&blue).unwrap();
+        while participant.poll_transmit().is_some() {}
+        let samples = reader.take(10).unwrap();
+        assert_eq!(
+            vec![
+                (red.instance_handle(), InstanceStateKind::NOT_ALIVE_DISPOSED),
+                (
+                    blue.instance_handle(),
+                    InstanceStateKind::NOT_ALIVE_NO_WRITERS
+                ),
+            ],
+            samples
+                .iter()
+                .map(|sample| (sample.info.instance_handle, sample.info.instance_state))
+                .collect::<Vec<_>>()
+        );
+        assert!(samples
+            .iter()
+            .all(|sample| sample.data.is_none() && !sample.info.valid_data));
+        assert!(samples
+            .iter()
+            .all(|sample| sample.info.view_state == ViewStateKind::NOT_NEW));
+
+        writer.write(&red).unwrap();
+        writer.write(&blue).unwrap();
+        while participant.poll_transmit().is_some() {}
+        let samples = reader.take(10).unwrap();
+        assert_eq!(
+            vec![(1, 0), (0, 1)],
+            samples
+                .iter()
+                .map(|sample| (
+                    sample.info.disposed_generation_count,
+                    sample.info.no_writers_generation_count
+                ))
+                .collect::<Vec<_>>()
+        );
+        assert!(samples.iter().all(|sample| sample.info.valid_data
+            && sample.info.instance_state == InstanceStateKind::ALIVE
+            && sample.info.view_state == ViewStateKind::NEW));
+    }
+
+    #[test]
+    fn not_alive_instances_are_purged_after_the_autopurge_delay() {
+        let participant = participant();
+        let now = Arc::new(Mutex::new(Instant::now()));
+        let clock = now.clone();
+        participant.set_clock(move || *clock.lock().unwrap());
+        let (writer, subscriber, _) = writer_and_reader(&participant, keep_all(10));
+        let mut reader_qos = DataReaderQos::default();
+        reader_qos
+            .reader_data_lifecycle
+            .autopurge_disposed_samples_delay = Duration_t::from(Duration::from_secs(1));
+        let reader = subscriber
+            .create_datareader::<Shape>(&writer.get_topic().unwrap(), reader_qos)
+            .unwrap();
+        let red = Shape::new("RED", 1, 2);
+        writer.write(&red).unwrap();
+        writer.unregister_instance(&red).unwrap();
+        while participant.poll_transmit().is_some() {}
+
+        let samples = reader.read(10).unwrap();
+        assert_eq!(2, samples.len());
+        assert!(samples
+            .iter()
+            .all(|sample| sample.info.instance_state == InstanceStateKind::NOT_ALIVE_DISPOSED));
+
+        let start = *now.lock().unwrap();
+        assert!(participant.poll_timeout().unwrap() <= start + Duration::from_secs(1));
+        participant.handle_timeout(start + Duration::from_secs(1));
+        assert!(reader.read(10).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,249 @@
+use crate::behavior::reader::Reader;
+use crate::dds::domain_participant::reader_state::{ReaderState, ReceivedChange};
+use crate::dds::domain_participant::ParticipantState;
+use crate::dds::instance_state_kind::InstanceStateKind;
+use crate::dds::qos::data_reader_qos::DataReaderQos;
+use crate::dds::qos::deadline_qos_policy::DeadlineQosPolicy;
+use crate::dds::status::status_kind::StatusKind;
+use crate::dds::type_support::TypeSupport;
+use crate::dds::view_state_kind::ViewStateKind;
+use crate::messages::submessage_elements::serialized_payload::SerializedPayload;
+use crate::structure::cache_change::CacheChange;
+use crate::structure::change_kind::ChangeKind_t;
+use crate::structure::duration::Duration_t;
+use crate::structure::guid::GUID_t;
+use crate::structure::instance_handle::InstanceHandle_t;
+use crate::structure::sequence_number::SequenceNumber_t;
+
+use std::collections::BTreeSet;
+use std::time::{Duration, Instant};
+
+/// An instance as seen by a DataReader (2.2.2.5.1).
+pub(super) struct InstanceState {
+    pub(super) instance_state: InstanceStateKind,
+    pub(super) view_state: ViewStateKind,
+
+    /// The times the instance became ALIVE again after being disposed or
+    /// left without DataWriters.
+    pub(super) disposed_generation_count: i32,
+    pub(super) no_writers_generation_count: i32,
+
+    /// The DataWriters writing the instance, until they unregister it or
+    /// they are no longer matched.
+    pub(super) writers: BTreeSet<GUID_t>,
+
+    /// The time the not alive instance is purged together with its
+    /// samples, see ReaderDataLifecycleQosPolicy.
+    pub(super) purge_time: Option<Instant>,
+}
+
+impl InstanceState {
+    pub(super) fn new() -> Self {
+        InstanceState {
+            instance_state: InstanceStateKind::ALIVE,
+            view_state: ViewStateKind::NEW,
+            disposed_generation_count: 0,
+            no_writers_generation_count: 0,
+            writers: BTreeSet::new(),
+            purge_time: None,
+        }
+    }
+}
+
+impl ParticipantState {
+    /// Removes the not alive instances of the DataReaders whose autopurge
+    /// delay elapsed, together with their samples.
+    pub(super) fn purge_instances(&mut self, now: Instant) {
+        for (guid, reader) in self.readers.iter_mut() {
+            let purged: BTreeSet<_> = reader
+                .instances
+                .iter()
+                .filter(|(_, instance)| instance.purge_time.is_some_and(|time| time <= now))
+                .map(|(instance_handle, _)| *instance_handle)
+                .collect();
+            if purged.is_empty() {
+                continue;
+            }
+            let purged_changes: Vec<_> = reader
+                .received_changes
+                .iter()
+                .filter(|(_, received_change)| purged.contains(&received_change.instance_handle))
+                .map(|(change_id, _)| *change_id)
+                .collect();
+            if let Some(rtps_reader) = self.rtps.protocol_mut().reader_mut(guid.entity_id) {
+                let reader_cache = &mut rtps_reader.as_reader_mut().reader_cache;
+                for (writer_guid, sequence_number) in &purged_changes {
+                    reader_cache.remove_writer_change(*writer_guid, *sequence_number);
+                }
+            }
+            for change_id in &purged_changes {
+                reader.received_changes.remove(change_id);
+                reader.read_changes.remove(change_id);
+            }
+            for instance_handle in &purged {
+                reader.instances.remove(instance_handle);
+            }
+        }
+    }
+
+    /// Counts a missed deadline for each instance of the DataWriters and
+    /// DataReaders not written or received within each deadline period.
+    pub(super) fn handle_deadlines(&mut self, now: Instant) {
+        let mut missed_deadlines = vec![];
+        for (guid, writer) in self.writers.iter_mut() {
+            let period = Duration::from(writer.qos.deadline.period);
+            for (instance_handle, deadline) in writer.deadlines.iter_mut() {
+                while *deadline <= now {
+                    writer.offered_deadline_missed.increment(*instance_handle);
+                    *deadline += period;
+                    missed_deadlines.push((*guid, StatusKind::OFFERED_DEADLINE_MISSED));
+                }
+            }
+        }
+        for (guid, reader) in self.readers.iter_mut() {
+            let period = Duration::from(reader.qos.deadline.period);
+            for (instance_handle, deadline) in reader.deadlines.iter_mut() {
+                while *deadline <= now {
+                    reader.requested_deadline_missed.increment(*instance_handle);
+                    *deadline += period;
+                    missed_deadlines.push((*guid, StatusKind::REQUESTED_DEADLINE_MISSED));
+                }
+            }
+        }
+        for (guid, kind) in missed_deadlines {
+            self.status_changed(guid, kind);
+        }
+    }
+}
+
+/// The instance of the change of a DataReader of T: the key hash sent with
+/// the change, else the key hash of its payload, which holds only the key
+/// fields unless the change is ALIVE. See TypeSupport::instance_handle.
+pub(super) fn instance_handle<T: TypeSupport>(change: &CacheChange) -> InstanceHandle_t {
+    if change.instance_handle != InstanceHandle_t::HANDLE_NIL || !T::WITH_KEY {
+        return change.instance_handle;
+    }
+    let serialized_payload = SerializedPayload::from(change.data_value.value.clone());
+    let key = match change.kind {
+        ChangeKind_t::ALIVE => T::deserialize(&serialized_payload).map(|data| data.key()),
+        _ => T::deserialize_key(&serialized_payload),
+    };
+    key.map(|key| T::key_hash(&key))
+        .unwrap_or(InstanceHandle_t::HANDLE_NIL)
+}
+
+/// The time by which the instance updated at now misses its deadline, none
+/// without a finite deadline period.
+pub(super) fn deadline(now: Instant, deadline: DeadlineQosPolicy) -> Option<Instant> {
+    match deadline.period {
+        Duration_t::DURATION_INFINITE | Duration_t::DURATION_ZERO => None,
+        period => Some(now + Duration::from(period)),
+    }
+}
+
+/// Applies the disposal or unregistration of an instance by a DataWriter to
+/// the DataReader, see ParticipantState::receive_changes. The change is
+/// accepted as a sample without valid data when it changes the
+/// instance_state, otherwise it is dropped.
+pub(super) fn receive_not_alive_change(
+    now: Instant,
+    reader: &mut ReaderState,
+    change_id: (GUID_t, SequenceNumber_t),
+    kind: ChangeKind_t,
+    instance_handle: InstanceHandle_t,
+) -> bool {
+    let instance = match reader.instances.get_mut(&instance_handle) {
+        Some(instance) => instance,
+        None => return false,
+    };
+    if kind != ChangeKind_t::NOT_ALIVE_DISPOSED {
+        instance.writers.remove(&change_id.0);
+    }
+    let instance_state = if kind != ChangeKind_t::NOT_ALIVE_UNREGISTERED {
+        InstanceStateKind::NOT_ALIVE_DISPOSED
+    } else if instance.writers.is_empty() && instance.instance_state == InstanceStateKind::ALIVE {
+        InstanceStateKind::NOT_ALIVE_NO_WRITERS
+    } else {
+        instance.instance_state
+    };
+    if instance_state == instance.instance_state {
+        return false;
+    }
+
+    instance.instance_state = instance_state;
+    instance.purge_time = purge_time(now, instance_state, &reader.qos);
+    reader.received_changes.insert(
+        change_id,
+        ReceivedChange {
+            instance_handle,
+            disposed_generation_count: instance.disposed_generation_count,
+            no_writers_generation_count: instance.no_writers_generation_count,
+        },
+    );
+    reader.deadlines.remove(&instance_handle);
+    true
+}
+
+/// The time by which the instance that became not alive at now is purged,
+/// none with an infinite autopurge delay.
+pub(super) fn purge_time(
+    now: Instant,
+    instance_state: InstanceStateKind,
+    qos: &DataReaderQos,
+) -> Option<Instant> {
+    let delay = match instance_state {
+        InstanceStateKind::ALIVE => return None,
+        InstanceStateKind::NOT_ALIVE_DISPOSED => {
+            qos.reader_data_lifecycle.autopurge_disposed_samples_delay
+        }
+        InstanceStateKind::NOT_ALIVE_NO_WRITERS => {
+            qos.reader_data_lifecycle.autopurge_nowriter_samples_delay
+        }
+    };
+    match delay {
+        Duration_t::DURATION_INFINITE => None,
+        delay => Some(now + Duration::from(delay)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dds::type_support::tests::Shape;
+    use crate::structure::data::Data;
+
+    fn change(kind: ChangeKind_t, serialized_payload: SerializedPayload) -> CacheChange {
+        CacheChange {
+            kind,
+            writer_guid: GUID_t::GUID_UNKNOWN,
+            instance_handle: InstanceHandle_t::HANDLE_NIL,
+            sequence_number: SequenceNumber_t::from(1),
+            data_value: Data::from(serialized_payload.value),
+            source_timestamp: None,
+        }
+    }
+
+    #[test]
+    fn instance_is_computed_from_payload_without_key_hash() {
+        let red = Shape::new("RED", 1, 2);
+
+        assert_eq!(
+            red.instance_handle(),
+            instance_handle::<Shape>(&change(ChangeKind_t::ALIVE, red.serialize().unwrap()))
+        );
+        assert_eq!(
+            red.instance_handle(),
+            instance_handle::<Shape>(&change(
+                ChangeKind_t::NOT_ALIVE_DISPOSED,
+                red.serialize_key().unwrap()
+            ))
+        );
+        assert_eq!(
+            InstanceHandle_t::HANDLE_NIL,
+            instance_handle::<Shape>(&change(
+                ChangeKind_t::NOT_ALIVE_UNREGISTERED,
+                SerializedPayload::default()
+            ))
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use crate::dds::domain_participant::{
+    already_deleted, inconsistent_policy, precondition_not_met, DomainParticipant, ParticipantState,
+};
+use crate::dds::listener::notification::SharedListener;
+use crate::dds::listener::publisher_listener::PublisherListener;
+use crate::dds::qos::data_writer_qos::DataWriterQos;
+use crate::dds::qos::publisher_qos::PublisherQos;
+use crate::dds::status::entity_statuses::EntityStatuses;
+use crate::dds::status::status_kind::StatusMask;
+use crate::structure::guid::GUID_t;
+
+use std::io::Error;
+use std::sync::{Arc, Mutex};
+
+pub(super) struct PublisherState {
+    pub(super) qos: PublisherQos,
+    pub(super) default_datawriter_qos: DataWriterQos,
+    pub(super) enabled: bool,
+    pub(super) statuses: EntityStatuses,
+    pub(super) listener: Option<(SharedListener<dyn PublisherListener>, StatusMask)>,
+}
+
+impl DomainParticipant {
+    pub(crate) fn publisher_qos(&self, publisher: GUID_t) -> Result<PublisherQos, Error> {
+        let state = self.state()?;
+        let publisher = state
+            .publishers
+            .get(&publisher)
+            .ok_or_else(already_deleted)?;
+        Ok(publisher.qos.clone())
+    }
+
+    /// Replaces the QoS of the Publisher and announces its enabled
+    /// DataWriters again.
+    pub(crate) fn set_publisher_qos(
+        &self,
+        publisher: GUID_t,
+        qos: PublisherQos,
+    ) -> Result<(), Error> {
+        let mut state = self.state()?;
+        state
+            .publishers
+            .get_mut(&publisher)
+            .ok_or_else(already_deleted)?
+            .qos = qos;
+        let writers: Vec<GUID_t> = state
+            .writers
+            .iter()
+            .filter(|(_, writer)| writer.publisher == publisher && writer.enabled)
+            .map(|(guid, _)| *guid)
+            .collect();
+        for guid in writers {
+            state.announce_writer(guid);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn enable_publisher(&self, publisher: GUID_t) -> Result<(), Error> {
+        self.state()?.enable_publisher(publisher)
+    }
+
+    pub(crate) fn is_publisher_enabled(&self, publisher: GUID_t) -> bool {
+        let state = self.state.lock().unwrap();
+        state
+            .publishers
+            .get(&publisher)
+            .is_some_and(|publisher| publisher.enabled)
+    }
+
+    pub(crate) fn default_datawriter_qos(&self, publisher: GUID_t) -> Result<DataWriterQos, Error> {
+        let state = self.state()?;
+        let publisher = state
+            .publishers
+            .get(&publisher)
+            .ok_or_else(already_deleted)?;
+        Ok(publisher.default_datawriter_qos.clone())
+    }
+
+    pub(crate) fn set_default_datawriter_qos(
+        &self,
+        publisher: GUID_t,
+        qos: DataWriterQos,
+    ) -> Result<(), Error> {
+        qos.validate().map_err(inconsistent_policy)?;
+        let mut state = self.state()?;
+        state
+            .publishers
+            .get_mut(&publisher)
+            .ok_or_else(already_deleted)?
+            .default_datawriter_qos = qos;
+        Ok(())
+    }
+
+    pub(crate) fn set_publisher_listener(
+        &self,
+        publisher: GUID_t,
+        listener: Option<Box<dyn PublisherListener>>,
+        mask: StatusMask,
+    ) -> Result<(), Error> {
+        let mut state = self.state()?;
+        let publisher = state
+            .publishers
+            .get_mut(&publisher)
+            .ok_or_else(already_deleted)?;
+        publisher.listener = listener.map(|listener| (Arc::new(Mutex::new(listener)), mask));
+        Ok(())
+    }
+}
+
+impl ParticipantState {
+    /// Enables the Publisher and, with autoenable_created_entities, its
+    /// DataWriters with enabled Topics.
+    pub(super) fn enable_publisher(&mut self, guid: GUID_t) -> Result<(), Error> {
+        if !self.enabled {
+            return Err(precondition_not_met("The DomainParticipant is not enabled"));
+        }
+        let publisher = self.publishers.get_mut(&guid).ok_or_else(already_deleted)?;
+        if publisher.enabled {
+            return Ok(());
+        }
+        publisher.enabled = true;
+        if !publisher.qos.entity_factory.autoenable_created_entities {
+            return Ok(());
+        }
+        let writers: Vec<GUID_t> = self
+            .writers
+            .iter()
+            .filter(|(_, writer)| writer.publisher == guid && self.topics[&writer.topic].enabled)
+            .map(|(guid, _)| *guid)
+            .collect();
+        for guid in writers {
+            self.enable_writer(guid)?;
+        }
+        Ok(())
+    }
+}
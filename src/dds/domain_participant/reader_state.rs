@@ -0,0 +1,720 @@
+use crate::behavior::reader::{Reader, ReaderAttributes};
+use crate::dds::condition::read_condition::SampleSelection;
+use crate::dds::condition::wakers::Wakers;
+use crate::dds::domain_participant::instance_state::{
+    deadline, instance_handle, purge_time, receive_not_alive_change, InstanceState,
+};
+use crate::dds::domain_participant::{
+    already_deleted, immutable_policy, inconsistent_policy, not_enabled, precondition_not_met,
+    reliability_level, topic_kind, DomainParticipant, ParticipantState,
+};
+use crate::dds::filter::filter_expression::FilterExpression;
+use crate::dds::filter::filter_fields::SampleDecoder;
+use crate::dds::instance_state_kind::{InstanceStateKind, ANY_INSTANCE_STATE};
+use crate::dds::listener::notification::EndpointListener;
+use crate::dds::qos::data_reader_qos::DataReaderQos;
+use crate::dds::qos::history_qos_policy::HistoryQosPolicyKind;
+use crate::dds::qos::resource_limits_qos_policy::ResourceLimitsQosPolicy;
+use crate::dds::sample::Sample;
+use crate::dds::sample_info::SampleInfo;
+use crate::dds::sample_state_kind::SampleStateKind;
+use crate::dds::status::deadline_missed_status::RequestedDeadlineMissedStatus;
+use crate::dds::status::entity_statuses::EntityStatuses;
+use crate::dds::status::sample_lost_status::SampleLostStatus;
+use crate::dds::status::sample_rejected_status::SampleRejectedStatus;
+use crate::dds::status::sample_rejected_status_kind::SampleRejectedStatusKind;
+use crate::dds::status::status_kind::{StatusKind, StatusMask};
+use crate::dds::status::subscription_matched_status::SubscriptionMatchedStatus;
+use crate::dds::subscriber::Subscriber;
+use crate::dds::topic::Topic;
+use crate::dds::topic_description::TopicDescription;
+use crate::dds::type_support::TypeSupport;
+use crate::dds::view_state_kind::{ViewStateKind, ANY_VIEW_STATE};
+use crate::discovery::content_filter_property::ContentFilterProperty_t;
+use crate::discovery::discovered_reader_data::DiscoveredReaderData;
+use crate::discovery::pl_cdr;
+use crate::messages::submessage_elements::serialized_payload::SerializedPayload;
+use crate::structure::cache_change::CacheChange;
+use crate::structure::change_kind::ChangeKind_t;
+use crate::structure::duration::Duration_t;
+use crate::structure::entity_id::EntityId_t;
+use crate::structure::guid::GUID_t;
+use crate::structure::history_cache::HistoryCache;
+use crate::structure::instance_handle::InstanceHandle_t;
+use crate::structure::sequence_number::SequenceNumber_t;
+use crate::structure::time::Time_t;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Error;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+/// The timing of the reliable protocol of the DataReaders.
+const HEARTBEAT_RESPONSE_DELAY: Duration = Duration::from_millis(500);
+
+/// The RTPS Reader of the DataReader exists while it is enabled; the
+/// received changes stay in its reader_cache until they are taken.
+pub(super) struct ReaderState {
+    pub(super) subscriber: GUID_t,
+    pub(super) topic: GUID_t,
+    pub(super) qos: DataReaderQos,
+    pub(super) enabled: bool,
+
+    /// The instance of a change of the data type of the DataReader.
+    pub(super) instance_handle: fn(&CacheChange) -> InstanceHandle_t,
+
+    /// The filter of the ContentFilteredTopic of the DataReader, announced
+    /// to the DataWriters and applied to the samples they did not filter.
+    pub(super) content_filter: Option<(ContentFilterProperty_t, FilterExpression, SampleDecoder)>,
+
+    /// The changes of the reader_cache accepted into the history of the
+    /// DataReader, see ParticipantState::receive_changes.
+    pub(super) received_changes: BTreeMap<(GUID_t, SequenceNumber_t), ReceivedChange>,
+
+    /// The changes of the reader_cache already read, identified by their
+    /// Writer and sequence number.
+    pub(super) read_changes: BTreeSet<(GUID_t, SequenceNumber_t)>,
+
+    /// The instances of the received changes, kept until they are purged,
+    /// see ReaderDataLifecycleQosPolicy.
+    pub(super) instances: BTreeMap<InstanceHandle_t, InstanceState>,
+
+    /// The tasks waiting for a sample not read yet.
+    pub(super) wakers: Wakers,
+
+    pub(super) statuses: EntityStatuses,
+    pub(super) listener: Option<(EndpointListener, StatusMask)>,
+    pub(super) requested_deadline_missed: RequestedDeadlineMissedStatus,
+    pub(super) subscription_matched: SubscriptionMatchedStatus,
+    pub(super) sample_lost: SampleLostStatus,
+    pub(super) sample_rejected: SampleRejectedStatus,
+
+    /// The time by which a sample of each instance has to be received to
+    /// meet the DeadlineQosPolicy.
+    pub(super) deadlines: BTreeMap<InstanceHandle_t, Instant>,
+}
+
+/// A change accepted into the history of a DataReader, with the generation
+/// counts of its instance at its reception.
+pub(super) struct ReceivedChange {
+    pub(super) instance_handle: InstanceHandle_t,
+    pub(super) disposed_generation_count: i32,
+    pub(super) no_writers_generation_count: i32,
+}
+
+impl ReaderState {
+    /// Whether the change is not filtered out by the content filter: the
+    /// samples which cannot be evaluated are filtered out, the changes
+    /// without data pass.
+    fn passes_content_filter(&self, change: &CacheChange) -> bool {
+        match &self.content_filter {
+            Some((_, filter_expression, sample_decoder)) if change.kind == ChangeKind_t::ALIVE => {
+                sample_decoder(&change.data_value.value)
+                    .and_then(|fields| filter_expression.evaluate(&*fields))
+                    .unwrap_or(false)
+            }
+            _ => true,
+        }
+    }
+}
+
+impl DomainParticipant {
+    /// Creates the DataReader of the Topic of the type T, returns its GUID.
+    pub(crate) fn create_datareader<T: TypeSupport>(
+        &self,
+        subscriber: GUID_t,
+        topic_description: &TopicDescription,
+        qos: DataReaderQos,
+    ) -> Result<GUID_t, Error> {
+        qos.validate().map_err(inconsistent_policy)?;
+        let topic = topic_description.topic();
+        let content_filter = match topic_description {
+            TopicDescription::Topic(_) => None,
+            TopicDescription::ContentFilteredTopic(content_filtered_topic) => {
+                let sample_decoder = T::sample_decoder().ok_or_else(|| {
+                    precondition_not_met(format!("The type {} cannot be filtered", T::TYPE_NAME))
+                })?;
+                let content_filter = content_filtered_topic.content_filter_property()?;
+                let filter_expression = content_filter.to_filter_expression()?;
+                Some((content_filter, filter_expression, sample_decoder))
+            }
+        };
+        let mut state = self.state()?;
+        let autoenable = match state.subscribers.get(&subscriber) {
+            Some(subscriber) => {
+                subscriber.enabled && subscriber.qos.entity_factory.autoenable_created_entities
+            }
+            None => return Err(already_deleted()),
+        };
+        if topic.get_participant() != *self || !state.topics.contains_key(&topic.guid()) {
+            return Err(precondition_not_met(
+                "The Topic belongs to another participant",
+            ));
+        }
+        if state.topics[&topic.guid()].type_name != T::TYPE_NAME {
+            return Err(precondition_not_met(format!(
+                "The type of the Topic is not {}",
+                T::TYPE_NAME
+            )));
+        }
+        let guid = state.next_guid(if T::WITH_KEY {
+            EntityId_t::ENTITYKIND_USER_DEFINED_READER_WITH_KEY
+        } else {
+            EntityId_t::ENTITYKIND_USER_DEFINED_READER_NO_KEY
+        });
+        state.readers.insert(
+            guid,
+            ReaderState {
+                subscriber,
+                topic: topic.guid(),
+                qos,
+                enabled: false,
+                instance_handle: instance_handle::<T>,
+                content_filter,
+                received_changes: BTreeMap::new(),
+                read_changes: BTreeSet::new(),
+                instances: BTreeMap::new(),
+                wakers: Wakers::default(),
+                statuses: EntityStatuses::default(),
+                listener: None,
+                requested_deadline_missed: RequestedDeadlineMissedStatus::default(),
+                subscription_matched: SubscriptionMatchedStatus::default(),
+                sample_lost: SampleLostStatus::default(),
+                sample_rejected: SampleRejectedStatus::default(),
+                deadlines: BTreeMap::new(),
+            },
+        );
+        if autoenable {
+            state.enable_reader(guid)?;
+        }
+        Ok(guid)
+    }
+
+    pub(crate) fn delete_datareader(
+        &self,
+        subscriber: GUID_t,
+        reader: GUID_t,
+    ) -> Result<(), Error> {
+        let mut state = self.state()?;
+        match state.readers.get(&reader) {
+            Some(state) if state.subscriber == subscriber => {}
+            _ => {
+                return Err(precondition_not_met(
+                    "The DataReader belongs to another Subscriber",
+                ))
+            }
+        }
+        state.delete_reader(reader);
+        Ok(())
+    }
+
+    /// The DataReaders of the Subscriber, of the topic_name if provided.
+    pub(crate) fn datareaders(&self, subscriber: GUID_t, topic_name: Option<&str>) -> Vec<GUID_t> {
+        let state = self.state.lock().unwrap();
+        state
+            .readers
+            .iter()
+            .filter(|(_, reader)| reader.subscriber == subscriber)
+            .filter(|(_, reader)| {
+                topic_name
+                    .is_none_or(|topic_name| state.topics[&reader.topic].topic_name == topic_name)
+            })
+            .map(|(guid, _)| *guid)
+            .collect()
+    }
+
+    pub(crate) fn reader_topic(&self, reader: GUID_t) -> Result<Topic, Error> {
+        let state = self.state()?;
+        let reader = state.readers.get(&reader).ok_or_else(already_deleted)?;
+        Ok(Topic::new(self.clone(), reader.topic))
+    }
+
+    pub(crate) fn reader_subscriber(&self, reader: GUID_t) -> Result<Subscriber, Error> {
+        let state = self.state()?;
+        let reader = state.readers.get(&reader).ok_or_else(already_deleted)?;
+        Ok(Subscriber::new(self.clone(), reader.subscriber))
+    }
+
+    pub(crate) fn reader_qos(&self, reader: GUID_t) -> Result<DataReaderQos, Error> {
+        let state = self.state()?;
+        let reader = state.readers.get(&reader).ok_or_else(already_deleted)?;
+        Ok(reader.qos.clone())
+    }
+
+    /// Replaces the QoS of the DataReader, announced again if enabled;
+    /// the immutable policies of the enabled DataReader are kept.
+    pub(crate) fn set_reader_qos(&self, reader: GUID_t, qos: DataReaderQos) -> Result<(), Error> {
+        qos.validate().map_err(inconsistent_policy)?;
+        let mut state = self.state()?;
+        let reader_state = state.readers.get_mut(&reader).ok_or_else(already_deleted)?;
+        if reader_state.enabled {
+            reader_state
+                .qos
+                .check_changeable(&qos)
+                .map_err(immutable_policy)?;
+        }
+        reader_state.qos = qos;
+        if reader_state.enabled {
+            state.announce_reader(reader);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn enable_reader(&self, reader: GUID_t) -> Result<(), Error> {
+        self.state()?.enable_reader(reader)
+    }
+
+    pub(crate) fn is_reader_enabled(&self, reader: GUID_t) -> bool {
+        let state = self.state.lock().unwrap();
+        state
+            .readers
+            .get(&reader)
+            .is_some_and(|reader| reader.enabled)
+    }
+
+    /// The handles of the DataWriters matched with the enabled DataReader.
+    pub(crate) fn matched_publications(
+        &self,
+        reader: GUID_t,
+    ) -> Result<Vec<InstanceHandle_t>, Error> {
+        let state = self.state()?;
+        match state.readers.get(&reader) {
+            Some(reader_state) if reader_state.enabled => Ok(state
+                .rtps
+                .matcher()
+                .matched_writers(reader)
+                .map(pl_cdr::key_hash)
+                .collect()),
+            Some(_) => Err(not_enabled()),
+            None => Err(already_deleted()),
+        }
+    }
+
+    /// Reads or takes up to max_samples samples of the enabled DataReader
+    /// of the selection, see ParticipantState::read_samples.
+    pub(crate) fn read_samples<T: TypeSupport>(
+        &self,
+        reader: GUID_t,
+        max_samples: usize,
+        selection: &SampleSelection,
+        take: bool,
+    ) -> Result<Vec<Sample<T>>, Error> {
+        self.state()?
+            .read_samples(reader, max_samples, selection, take)
+    }
+
+    /// Whether the enabled DataReader has samples of the selection.
+    pub(crate) fn has_samples(&self, reader: GUID_t, selection: &SampleSelection) -> bool {
+        self.state()
+            .and_then(|state| state.select_changes(reader, 1, selection))
+            .is_ok_and(|changes| !changes.is_empty())
+    }
+
+    /// Takes the oldest sample of the enabled DataReader not read yet; the
+    /// task waiting for the sample is woken as the participant is driven.
+    pub(crate) fn poll_take_next_sample<T: TypeSupport>(
+        &self,
+        reader: GUID_t,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Sample<T>, Error>> {
+        let mut state = self.state()?;
+        let selection = SampleSelection::new(
+            &[SampleStateKind::NOT_READ],
+            ANY_VIEW_STATE,
+            ANY_INSTANCE_STATE,
+        );
+        let mut samples = state.read_samples(reader, 1, &selection, true)?;
+        match samples.pop() {
+            Some(sample) => Poll::Ready(Ok(sample)),
+            None => {
+                state.readers.get_mut(&reader).unwrap().wakers.register(cx);
+                Poll::Pending
+            }
+        }
+    }
+
+    pub(crate) fn set_reader_listener(
+        &self,
+        reader: GUID_t,
+        listener: Option<EndpointListener>,
+        mask: StatusMask,
+    ) -> Result<(), Error> {
+        let mut state = self.state()?;
+        let reader = state.readers.get_mut(&reader).ok_or_else(already_deleted)?;
+        reader.listener = listener.map(|listener| (listener, mask));
+        Ok(())
+    }
+}
+
+impl ParticipantState {
+    /// Creates the RTPS Reader of the DataReader and announces it.
+    pub(super) fn enable_reader(&mut self, guid: GUID_t) -> Result<(), Error> {
+        let reader = self.readers.get(&guid).ok_or_else(already_deleted)?;
+        if reader.enabled {
+            return Ok(());
+        }
+        if !self.subscribers[&reader.subscriber].enabled {
+            return Err(precondition_not_met("The Subscriber is not enabled"));
+        }
+        if !self.topics[&reader.topic].enabled {
+            return Err(precondition_not_met("The Topic is not enabled"));
+        }
+        let reliability_level = reliability_level(reader.qos.reliability.kind);
+
+        self.rtps.add_reader(
+            guid.entity_id,
+            topic_kind(guid.entity_id),
+            reliability_level,
+            ReaderAttributes {
+                expects_inline_qos: false,
+                heartbeat_response_delay: Duration_t::from(HEARTBEAT_RESPONSE_DELAY),
+                heartbeat_suppression_duration: Duration_t::DURATION_ZERO,
+                reader_cache: HistoryCache::new(),
+            },
+        );
+        self.readers.get_mut(&guid).unwrap().enabled = true;
+        self.announce_reader(guid);
+        Ok(())
+    }
+
+    /// Matches the enabled DataReader and announces it, see
+    /// announce_writer.
+    pub(super) fn announce_reader(&mut self, guid: GUID_t) {
+        let reader = &self.readers[&guid];
+        let topic = &self.topics[&reader.topic];
+        let subscriber = &self.subscribers[&reader.subscriber];
+        let mut reader_data = DiscoveredReaderData::new(guid, &topic.topic_name, &topic.type_name);
+        reader_data.durability = reader.qos.durability;
+        reader_data.deadline = reader.qos.deadline;
+        reader_data.liveliness = reader.qos.liveliness;
+        reader_data.reliability = reader.qos.reliability;
+        reader_data.ownership = reader.qos.ownership;
+        reader_data.destination_order = reader.qos.destination_order;
+        reader_data.presentation = subscriber.qos.presentation;
+        reader_data.partition = subscriber.qos.partition.clone();
+        reader_data.latency_budget = reader.qos.latency_budget;
+        reader_data.time_based_filter = reader.qos.time_based_filter;
+        reader_data.history = reader.qos.history;
+        reader_data.resource_limits = reader.qos.resource_limits;
+        reader_data.user_data = reader.qos.user_data.clone();
+        reader_data.topic_data = topic.qos.topic_data.clone();
+        reader_data.group_data = subscriber.qos.group_data.clone();
+        reader_data.content_filter = reader
+            .content_filter
+            .as_ref()
+            .map(|(content_filter, _, _)| content_filter.clone());
+
+        let now = (self.clock)();
+        self.rtps.announce_reader(now, &reader_data);
+    }
+
+    /// Deserializes up to max_samples changes of the selection from the
+    /// history of the enabled DataReader, in the order of their reception.
+    /// The read changes stay in the reader_cache, the taken ones are
+    /// removed. The changes which are not ALIVE and the ones not
+    /// deserialized as T have no valid data. The view_state of the instances
+    /// of the samples becomes NOT_NEW.
+    pub(super) fn read_samples<T: TypeSupport>(
+        &mut self,
+        guid: GUID_t,
+        max_samples: usize,
+        selection: &SampleSelection,
+        take: bool,
+    ) -> Result<Vec<Sample<T>>, Error> {
+        let selected_changes = self.select_changes(guid, max_samples, selection)?;
+        let reader = self.readers.get_mut(&guid).unwrap();
+        let reader_cache = &mut self
+            .rtps
+            .protocol_mut()
+            .reader_mut(guid.entity_id)
+            .expect("The enabled DataReader has its RTPS Reader")
+            .as_reader_mut()
+            .reader_cache;
+
+        let mut samples = vec![];
+        let mut changes = vec![];
+        for (index, mut info) in selected_changes {
+            let change = &reader_cache.changes()[index];
+            let data = if info.valid_data {
+                T::deserialize(&SerializedPayload::from(change.data_value.value.clone())).ok()
+            } else {
+                None
+            };
+            info.valid_data = data.is_some();
+            samples.push(Sample { data, info });
+            changes.push((change.writer_guid, change.sequence_number));
+        }
+
+        for sample in &samples {
+            if let Some(instance) = reader.instances.get_mut(&sample.info.instance_handle) {
+                instance.view_state = ViewStateKind::NOT_NEW;
+            }
+        }
+        for change_id in changes {
+            if take {
+                reader.read_changes.remove(&change_id);
+                reader.received_changes.remove(&change_id);
+                reader_cache.remove_writer_change(change_id.0, change_id.1);
+            } else {
+                reader.read_changes.insert(change_id);
+            }
+        }
+        reader.statuses.read(StatusKind::DATA_AVAILABLE);
+        if let Some(subscriber) = self.subscribers.get_mut(&reader.subscriber) {
+            subscriber.statuses.read(StatusKind::DATA_ON_READERS);
+        }
+        Ok(samples)
+    }
+
+    /// The index in the reader_cache and the SampleInfo of up to
+    /// max_samples changes of the selection in the history of the enabled
+    /// DataReader. The data of the ALIVE changes is assumed valid.
+    pub(super) fn select_changes(
+        &self,
+        guid: GUID_t,
+        max_samples: usize,
+        selection: &SampleSelection,
+    ) -> Result<Vec<(usize, SampleInfo)>, Error> {
+        let reader = self.readers.get(&guid).ok_or_else(already_deleted)?;
+        if !reader.enabled {
+            return Err(not_enabled());
+        }
+        let reader_cache = &self
+            .rtps
+            .protocol()
+            .reader(guid.entity_id)
+            .expect("The enabled DataReader has its RTPS Reader")
+            .as_reader()
+            .reader_cache;
+
+        let mut selected_changes = vec![];
+        for (index, change) in reader_cache.changes().iter().enumerate() {
+            if selected_changes.len() == max_samples {
+                break;
+            }
+            let change_id = (change.writer_guid, change.sequence_number);
+            let received_change = match reader.received_changes.get(&change_id) {
+                Some(received_change) => received_change,
+                None => continue,
+            };
+            let instance = &reader.instances[&received_change.instance_handle];
+            let sample_state = if reader.read_changes.contains(&change_id) {
+                SampleStateKind::READ
+            } else {
+                SampleStateKind::NOT_READ
+            };
+            let info = SampleInfo {
+                sample_state,
+                view_state: instance.view_state,
+                instance_state: instance.instance_state,
+                disposed_generation_count: received_change.disposed_generation_count,
+                no_writers_generation_count: received_change.no_writers_generation_count,
+                source_timestamp: change.source_timestamp.unwrap_or(Time_t::TIME_INVALID),
+                instance_handle: received_change.instance_handle,
+                publication_handle: pl_cdr::key_hash(change.writer_guid),
+                valid_data: change.kind == ChangeKind_t::ALIVE,
+            };
+            if selection.is_selected(&info) && selection.is_queried(&change.data_value.value) {
+                selected_changes.push((index, info));
+            }
+        }
+        Ok(selected_changes)
+    }
+
+    pub(super) fn subscription_matched(
+        &mut self,
+        reader_guid: GUID_t,
+        writer_guid: GUID_t,
+        matched: bool,
+    ) {
+        if let Some(reader) = self.readers.get_mut(&reader_guid) {
+            reader
+                .subscription_matched
+                .change(pl_cdr::key_hash(writer_guid), matched);
+            self.status_changed(reader_guid, StatusKind::SUBSCRIPTION_MATCHED);
+        }
+    }
+
+    /// The instances of the DataReader only written by the unmatched
+    /// DataWriter have no DataWriters anymore.
+    pub(super) fn writer_lost(&mut self, now: Instant, reader_guid: GUID_t, writer_guid: GUID_t) {
+        let reader = match self.readers.get_mut(&reader_guid) {
+            Some(reader) => reader,
+            None => return,
+        };
+        for instance in reader.instances.values_mut() {
+            if instance.writers.remove(&writer_guid)
+                && instance.writers.is_empty()
+                && instance.instance_state == InstanceStateKind::ALIVE
+            {
+                instance.instance_state = InstanceStateKind::NOT_ALIVE_NO_WRITERS;
+                instance.purge_time = purge_time(now, instance.instance_state, &reader.qos);
+            }
+        }
+    }
+
+    /// Accepts the changes added to the reader_cache of the enabled
+    /// DataReader into its history. KEEP_LAST removes the oldest samples of
+    /// the instance beyond the depth, whether read or not. The changes
+    /// beyond the ResourceLimitsQosPolicy are rejected and removed from the
+    /// reader_cache; they are not sent again, even to a reliable DataReader.
+    pub(super) fn receive_changes(&mut self, now: Instant, guid: GUID_t) {
+        let reader = match self.readers.get_mut(&guid) {
+            Some(reader) if reader.enabled => reader,
+            _ => return,
+        };
+        let reader_cache = &mut self
+            .rtps
+            .protocol_mut()
+            .reader_mut(guid.entity_id)
+            .expect("The enabled DataReader has its RTPS Reader")
+            .as_reader_mut()
+            .reader_cache;
+        let history = reader.qos.history;
+        let resource_limits = reader.qos.resource_limits;
+
+        let new_changes: Vec<_> = reader_cache
+            .changes()
+            .iter()
+            .filter(|change| {
+                !reader
+                    .received_changes
+                    .contains_key(&(change.writer_guid, change.sequence_number))
+            })
+            .map(|change| {
+                (
+                    (change.writer_guid, change.sequence_number),
+                    change.kind,
+                    (reader.instance_handle)(change),
+                    reader.passes_content_filter(change),
+                )
+            })
+            .collect();
+        let mut accepted = false;
+        let mut rejected = false;
+        for (change_id, kind, instance_handle, passes_content_filter) in new_changes {
+            if !passes_content_filter {
+                reader_cache.remove_writer_change(change_id.0, change_id.1);
+                continue;
+            }
+            if kind != ChangeKind_t::ALIVE {
+                if receive_not_alive_change(now, reader, change_id, kind, instance_handle) {
+                    accepted = true;
+                } else {
+                    reader_cache.remove_writer_change(change_id.0, change_id.1);
+                }
+                continue;
+            }
+
+            if history.kind == HistoryQosPolicyKind::KEEP_LAST {
+                let instance_changes: Vec<_> = reader_cache
+                    .changes()
+                    .iter()
+                    .map(|change| (change.writer_guid, change.sequence_number))
+                    .filter(|change_id| {
+                        reader
+                            .received_changes
+                            .get(change_id)
+                            .is_some_and(|received_change| {
+                                received_change.instance_handle == instance_handle
+                            })
+                    })
+                    .collect();
+                let excess =
+                    (instance_changes.len() + 1).saturating_sub(history.depth.max(1) as usize);
+                for (writer_guid, sequence_number) in instance_changes.into_iter().take(excess) {
+                    reader
+                        .received_changes
+                        .remove(&(writer_guid, sequence_number));
+                    reader_cache.remove_writer_change(writer_guid, sequence_number);
+                }
+            }
+
+            let instance_changes = reader
+                .received_changes
+                .values()
+                .filter(|received_change| received_change.instance_handle == instance_handle)
+                .count();
+            let reason = if ResourceLimitsQosPolicy::is_reached(
+                resource_limits.max_samples,
+                reader.received_changes.len(),
+            ) {
+                Some(SampleRejectedStatusKind::REJECTED_BY_SAMPLES_LIMIT)
+            } else if !reader.instances.contains_key(&instance_handle)
+                && ResourceLimitsQosPolicy::is_reached(
+                    resource_limits.max_instances,
+                    reader.instances.len(),
+                )
+            {
+                Some(SampleRejectedStatusKind::REJECTED_BY_INSTANCES_LIMIT)
+            } else if ResourceLimitsQosPolicy::is_reached(
+                resource_limits.max_samples_per_instance,
+                instance_changes,
+            ) {
+                Some(SampleRejectedStatusKind::REJECTED_BY_SAMPLES_PER_INSTANCE_LIMIT)
+            } else {
+                None
+            };
+            match reason {
+                Some(reason) => {
+                    reader_cache.remove_writer_change(change_id.0, change_id.1);
+                    reader.sample_rejected.increment(reason, instance_handle);
+                    rejected = true;
+                }
+                None => {
+                    let instance = reader
+                        .instances
+                        .entry(instance_handle)
+                        .or_insert_with(InstanceState::new);
+                    match instance.instance_state {
+                        InstanceStateKind::NOT_ALIVE_DISPOSED => {
+                            instance.disposed_generation_count += 1;
+                            instance.view_state = ViewStateKind::NEW;
+                        }
+                        InstanceStateKind::NOT_ALIVE_NO_WRITERS => {
+                            instance.no_writers_generation_count += 1;
+                            instance.view_state = ViewStateKind::NEW;
+                        }
+                        InstanceStateKind::ALIVE => (),
+                    }
+                    instance.instance_state = InstanceStateKind::ALIVE;
+                    instance.writers.insert(change_id.0);
+                    instance.purge_time = None;
+                    reader.received_changes.insert(
+                        change_id,
+                        ReceivedChange {
+                            instance_handle,
+                            disposed_generation_count: instance.disposed_generation_count,
+                            no_writers_generation_count: instance.no_writers_generation_count,
+                        },
+                    );
+                    if let Some(deadline) = deadline(now, reader.qos.deadline) {
+                        reader.deadlines.insert(instance_handle, deadline);
+                    }
+                    accepted = true;
+                }
+            }
+        }
+
+        let subscriber = reader.subscriber;
+        if rejected {
+            self.status_changed(guid, StatusKind::SAMPLE_REJECTED);
+        }
+        if accepted {
+            self.status_changed(guid, StatusKind::DATA_AVAILABLE);
+            if let Some(subscriber) = self.subscribers.get_mut(&subscriber) {
+                subscriber.statuses.change(StatusKind::DATA_ON_READERS);
+            }
+        }
+    }
+
+    pub(super) fn delete_reader(&mut self, guid: GUID_t) {
+        if let Some(mut reader) = self.readers.remove(&guid) {
+            reader.wakers.wake();
+            if reader.enabled {
+                let now = (self.clock)();
+                self.rtps.delete_reader(now, guid);
+            }
+        }
+    }
+}
@@ -0,0 +1,280 @@
+use crate::dds::domain_participant::{already_deleted, DomainParticipant, ParticipantState};
+use crate::dds::listener::notification::{Listener, Notification};
+use crate::dds::status::communication_status::CommunicationStatus;
+use crate::dds::status::entity_statuses::EntityStatuses;
+use crate::dds::status::status_kind::{StatusKind, StatusMask};
+use crate::structure::guid::GUID_t;
+
+use std::io::Error;
+use std::sync::MutexGuard;
+use std::task::Context;
+
+impl DomainParticipant {
+    /// Reads the status of the kind of the entity, resetting its changes.
+    pub(crate) fn read_status(
+        &self,
+        entity: GUID_t,
+        kind: StatusKind,
+    ) -> Result<CommunicationStatus, Error> {
+        self.state()?
+            .read_status(entity, kind)
+            .ok_or_else(already_deleted)
+    }
+
+    /// The statuses of the entity changed since they were last read.
+    pub(crate) fn status_changes(&self, entity: GUID_t) -> Result<StatusKind, Error> {
+        let state = self.state()?;
+        let statuses = state.statuses(entity).ok_or_else(already_deleted)?;
+        Ok(statuses.changed())
+    }
+
+    pub(crate) fn enabled_statuses(&self, entity: GUID_t) -> Result<StatusMask, Error> {
+        let state = self.state()?;
+        let statuses = state.statuses(entity).ok_or_else(already_deleted)?;
+        Ok(statuses.enabled())
+    }
+
+    /// Replaces the statuses triggering the StatusCondition of the entity
+    /// and wakes the tasks waiting for it.
+    pub(crate) fn set_enabled_statuses(
+        &self,
+        entity: GUID_t,
+        mask: StatusMask,
+    ) -> Result<(), Error> {
+        let mut state = self.state()?;
+        state
+            .statuses_mut(entity)
+            .ok_or_else(already_deleted)?
+            .set_enabled(mask);
+        state.condition_wakers.wake();
+        Ok(())
+    }
+
+    pub(crate) fn is_status_condition_triggered(&self, entity: GUID_t) -> bool {
+        let state = self.state.lock().unwrap();
+        state
+            .statuses(entity)
+            .is_some_and(|statuses| statuses.is_triggered())
+    }
+
+    /// Registers the task waiting for the conditions of the entities, woken
+    /// as the participant is driven.
+    pub(crate) fn register_condition_waker(&self, cx: &Context<'_>) {
+        self.state.lock().unwrap().condition_wakers.register(cx)
+    }
+
+    /// Unlocks the state and calls the listeners of the changed statuses,
+    /// see ParticipantState::take_notifications.
+    pub(super) fn notify_listeners(&self, mut state: MutexGuard<'_, ParticipantState>) {
+        let notifications = state.take_notifications();
+        drop(state);
+        for notification in notifications {
+            notification.notify(self);
+        }
+    }
+}
+
+impl ParticipantState {
+    pub(super) fn statuses(&self, guid: GUID_t) -> Option<&EntityStatuses> {
+        self.writers
+            .get(&guid)
+            .map(|writer| &writer.statuses)
+            .or_else(|| self.readers.get(&guid).map(|reader| &reader.statuses))
+            .or_else(|| {
+                self.publishers
+                    .get(&guid)
+                    .map(|publisher| &publisher.statuses)
+            })
+            .or_else(|| {
+                self.subscribers
+                    .get(&guid)
+                    .map(|subscriber| &subscriber.statuses)
+            })
+    }
+
+    pub(super) fn statuses_mut(&mut self, guid: GUID_t) -> Option<&mut EntityStatuses> {
+        if let Some(writer) = self.writers.get_mut(&guid) {
+            return Some(&mut writer.statuses);
+        }
+        if let Some(reader) = self.readers.get_mut(&guid) {
+            return Some(&mut reader.statuses);
+        }
+        if let Some(publisher) = self.publishers.get_mut(&guid) {
+            return Some(&mut publisher.statuses);
+        }
+        self.subscribers
+            .get_mut(&guid)
+            .map(|subscriber| &mut subscriber.statuses)
+    }
+
+    /// Marks the status of the entity as changed, it is notified to the
+    /// listeners once the operation completes.
+    pub(super) fn status_changed(&mut self, guid: GUID_t, kind: StatusKind) {
+        if let Some(statuses) = self.statuses_mut(guid) {
+            statuses.change(kind);
+            if !self.status_changes.contains(&(guid, kind)) {
+                self.status_changes.push((guid, kind));
+            }
+        }
+    }
+
+    /// Reads the status of the kind of the entity, resetting its changes.
+    /// None if the entity has no status of the kind.
+    pub(super) fn read_status(
+        &mut self,
+        guid: GUID_t,
+        kind: StatusKind,
+    ) -> Option<CommunicationStatus> {
+        let status = if kind == StatusKind::DATA_ON_READERS {
+            self.subscribers.get(&guid)?;
+            CommunicationStatus::DataOnReaders
+        } else if kind == StatusKind::DATA_AVAILABLE {
+            self.readers.get(&guid)?;
+            CommunicationStatus::DataAvailable
+        } else if kind == StatusKind::SAMPLE_LOST {
+            CommunicationStatus::SampleLost(self.readers.get_mut(&guid)?.sample_lost.read())
+        } else if kind == StatusKind::SAMPLE_REJECTED {
+            CommunicationStatus::SampleRejected(self.readers.get_mut(&guid)?.sample_rejected.read())
+        } else if kind == StatusKind::REQUESTED_DEADLINE_MISSED {
+            let reader = self.readers.get_mut(&guid)?;
+            CommunicationStatus::RequestedDeadlineMissed(reader.requested_deadline_missed.read())
+        } else if kind == StatusKind::SUBSCRIPTION_MATCHED {
+            let reader = self.readers.get_mut(&guid)?;
+            CommunicationStatus::SubscriptionMatched(reader.subscription_matched.read())
+        } else if kind == StatusKind::REQUESTED_INCOMPATIBLE_QOS {
+            self.readers.get(&guid)?;
+            let matcher = self.rtps.matcher_mut();
+            CommunicationStatus::RequestedIncompatibleQos(
+                matcher
+                    .requested_incompatible_qos_status(guid)
+                    .unwrap_or_default(),
+            )
+        } else if kind == StatusKind::LIVELINESS_CHANGED {
+            self.readers.get(&guid)?;
+            CommunicationStatus::LivelinessChanged(
+                self.rtps
+                    .wlp_mut()
+                    .and_then(|wlp| wlp.liveliness_changed_status(guid))
+                    .unwrap_or_default(),
+            )
+        } else if kind == StatusKind::OFFERED_DEADLINE_MISSED {
+            let writer = self.writers.get_mut(&guid)?;
+            CommunicationStatus::OfferedDeadlineMissed(writer.offered_deadline_missed.read())
+        } else if kind == StatusKind::PUBLICATION_MATCHED {
+            let writer = self.writers.get_mut(&guid)?;
+            CommunicationStatus::PublicationMatched(writer.publication_matched.read())
+        } else if kind == StatusKind::OFFERED_INCOMPATIBLE_QOS {
+            self.writers.get(&guid)?;
+            let matcher = self.rtps.matcher_mut();
+            CommunicationStatus::OfferedIncompatibleQos(
+                matcher
+                    .offered_incompatible_qos_status(guid)
+                    .unwrap_or_default(),
+            )
+        } else if kind == StatusKind::LIVELINESS_LOST {
+            self.writers.get(&guid)?;
+            CommunicationStatus::LivelinessLost(
+                self.rtps
+                    .wlp_mut()
+                    .and_then(|wlp| wlp.liveliness_lost_status(guid))
+                    .unwrap_or_default(),
+            )
+        } else {
+            return None;
+        };
+        self.statuses_mut(guid)?.read(kind);
+        Some(status)
+    }
+
+    /// Reads the statuses changed by the operation for the listeners
+    /// interested in them; the statuses already read by the application are
+    /// not notified. The DATA_AVAILABLE status of a DataReader is notified
+    /// as the DATA_ON_READERS status of its Subscriber if a listener is
+    /// interested in it.
+    pub(super) fn take_notifications(&mut self) -> Vec<Notification> {
+        let mut notifications = vec![];
+        for (guid, kind) in std::mem::take(&mut self.status_changes) {
+            let (entity, kind) = match self.readers.get(&guid) {
+                Some(reader)
+                    if kind == StatusKind::DATA_AVAILABLE
+                        && self
+                            .subscriber_listener(reader.subscriber, StatusKind::DATA_ON_READERS)
+                            .is_some() =>
+                {
+                    (reader.subscriber, StatusKind::DATA_ON_READERS)
+                }
+                _ => (guid, kind),
+            };
+            if !self
+                .statuses(entity)
+                .is_some_and(|statuses| statuses.is_changed(kind))
+            {
+                continue;
+            }
+            let listener = match self.listener(entity, kind) {
+                Some(listener) => listener,
+                None => continue,
+            };
+            if let Some(status) = self.read_status(entity, kind) {
+                notifications.push(Notification {
+                    listener,
+                    entity,
+                    status,
+                });
+            }
+        }
+        notifications
+    }
+
+    /// The listener of the status of the entity: the listener of the entity
+    /// if it is interested in the status, else the one of its Publisher or
+    /// Subscriber, else the one of the DomainParticipant.
+    pub(super) fn listener(&self, guid: GUID_t, kind: StatusKind) -> Option<Listener> {
+        if let Some(writer) = self.writers.get(&guid) {
+            return match &writer.listener {
+                Some((listener, mask)) if mask.contains(kind) => {
+                    Some(Listener::Endpoint(listener.clone()))
+                }
+                _ => self.publisher_listener(writer.publisher, kind),
+            };
+        }
+        if let Some(reader) = self.readers.get(&guid) {
+            return match &reader.listener {
+                Some((listener, mask)) if mask.contains(kind) => {
+                    Some(Listener::Endpoint(listener.clone()))
+                }
+                _ => self.subscriber_listener(reader.subscriber, kind),
+            };
+        }
+        self.subscriber_listener(guid, kind)
+    }
+
+    pub(super) fn publisher_listener(&self, guid: GUID_t, kind: StatusKind) -> Option<Listener> {
+        let publisher = self.publishers.get(&guid);
+        match publisher.and_then(|publisher| publisher.listener.as_ref()) {
+            Some((listener, mask)) if mask.contains(kind) => {
+                Some(Listener::Publisher(listener.clone()))
+            }
+            _ => self.participant_listener(kind),
+        }
+    }
+
+    pub(super) fn subscriber_listener(&self, guid: GUID_t, kind: StatusKind) -> Option<Listener> {
+        let subscriber = self.subscribers.get(&guid);
+        match subscriber.and_then(|subscriber| subscriber.listener.as_ref()) {
+            Some((listener, mask)) if mask.contains(kind) => {
+                Some(Listener::Subscriber(listener.clone()))
+            }
+            _ => self.participant_listener(kind),
+        }
+    }
+
+    pub(super) fn participant_listener(&self, kind: StatusKind) -> Option<Listener> {
+        match &self.listener {
+            Some((listener, mask)) if mask.contains(kind) => {
+                Some(Listener::DomainParticipant(listener.clone()))
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use crate::dds::domain_participant::{
+    already_deleted, inconsistent_policy, precondition_not_met, DomainParticipant, ParticipantState,
+};
+use crate::dds::listener::notification::SharedListener;
+use crate::dds::listener::subscriber_listener::SubscriberListener;
+use crate::dds::qos::data_reader_qos::DataReaderQos;
+use crate::dds::qos::subscriber_qos::SubscriberQos;
+use crate::dds::status::entity_statuses::EntityStatuses;
+use crate::dds::status::status_kind::StatusMask;
+use crate::structure::guid::GUID_t;
+
+use std::io::Error;
+use std::sync::{Arc, Mutex};
+
+pub(super) struct SubscriberState {
+    pub(super) qos: SubscriberQos,
+    pub(super) default_datareader_qos: DataReaderQos,
+    pub(super) enabled: bool,
+    pub(super) statuses: EntityStatuses,
+    pub(super) listener: Option<(SharedListener<dyn SubscriberListener>, StatusMask)>,
+}
+
+impl DomainParticipant {
+    pub(crate) fn subscriber_qos(&self, subscriber: GUID_t) -> Result<SubscriberQos, Error> {
+        let state = self.state()?;
+        let subscriber = state
+            .subscribers
+            .get(&subscriber)
+            .ok_or_else(already_deleted)?;
+        Ok(subscriber.qos.clone())
+    }
+
+    /// Replaces the QoS of the Subscriber and announces its enabled
+    /// DataReaders again.
+    pub(crate) fn set_subscriber_qos(
+        &self,
+        subscriber: GUID_t,
+        qos: SubscriberQos,
+    ) -> Result<(), Error> {
+        let mut state = self.state()?;
+        state
+            .subscribers
+            .get_mut(&subscriber)
+            .ok_or_else(already_deleted)?
+            .qos = qos;
+        let readers: Vec<GUID_t> = state
+            .readers
+            .iter()
+            .filter(|(_, reader)| reader.subscriber == subscriber && reader.enabled)
+            .map(|(guid, _)| *guid)
+            .collect();
+        for guid in readers {
+            state.announce_reader(guid);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn enable_subscriber(&self, subscriber: GUID_t) -> Result<(), Error> {
+        self.state()?.enable_subscriber(subscriber)
+    }
+
+    pub(crate) fn is_subscriber_enabled(&self, subscriber: GUID_t) -> bool {
+        let state = self.state.lock().unwrap();
+        state
+            .subscribers
+            .get(&subscriber)
+            .is_some_and(|subscriber| subscriber.enabled)
+    }
+
+    pub(crate) fn default_datareader_qos(
+        &self,
+        subscriber: GUID_t,
+    ) -> Result<DataReaderQos, Error> {
+        let state = self.state()?;
+        let subscriber = state
+            .subscribers
+            .get(&subscriber)
+            .ok_or_else(already_deleted)?;
+        Ok(subscriber.default_datareader_qos.clone())
+    }
+
+    pub(crate) fn set_default_datareader_qos(
+        &self,
+        subscriber: GUID_t,
+        qos: DataReaderQos,
+    ) -> Result<(), Error> {
+        qos.validate().map_err(inconsistent_policy)?;
+        let mut state = self.state()?;
+        state
+            .subscribers
+            .get_mut(&subscriber)
+            .ok_or_else(already_deleted)?
+            .default_datareader_qos = qos;
+        Ok(())
+    }
+
+    pub(crate) fn set_subscriber_listener(
+        &self,
+        subscriber: GUID_t,
+        listener: Option<Box<dyn SubscriberListener>>,
+        mask: StatusMask,
+    ) -> Result<(), Error> {
+        let mut state = self.state()?;
+        let subscriber = state
+            .subscribers
+            .get_mut(&subscriber)
+            .ok_or_else(already_deleted)?;
+        subscriber.listener = listener.map(|listener| (Arc::new(Mutex::new(listener)), mask));
+        Ok(())
+    }
+}
+
+impl ParticipantState {
+    /// Enables the Subscriber and, with autoenable_created_entities, its
+    /// DataReaders with enabled Topics.
+    pub(super) fn enable_subscriber(&mut self, guid: GUID_t) -> Result<(), Error> {
+        if !self.enabled {
+            return Err(precondition_not_met("The DomainParticipant is not enabled"));
+        }
+        let subscriber = self
+            .subscribers
+            .get_mut(&guid)
+            .ok_or_else(already_deleted)?;
+        if subscriber.enabled {
+            return Ok(());
+        }
+        subscriber.enabled = true;
+        if !subscriber.qos.entity_factory.autoenable_created_entities {
+            return Ok(());
+        }
+        let readers: Vec<GUID_t> = self
+            .readers
+            .iter()
+            .filter(|(_, reader)| reader.subscriber == guid && self.topics[&reader.topic].enabled)
+            .map(|(guid, _)| *guid)
+            .collect();
+        for guid in readers {
+            self.enable_reader(guid)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,54 @@
+use crate::dds::domain_participant::{
+    already_deleted, inconsistent_policy, precondition_not_met, DomainParticipant,
+};
+use crate::dds::qos::topic_qos::TopicQos;
+use crate::structure::guid::GUID_t;
+
+use std::io::Error;
+
+pub(super) struct TopicState {
+    pub(super) topic_name: String,
+    pub(super) type_name: String,
+    pub(super) qos: TopicQos,
+    pub(super) enabled: bool,
+}
+
+impl DomainParticipant {
+    pub(crate) fn topic_name(&self, topic: GUID_t) -> Result<(String, String), Error> {
+        let state = self.state()?;
+        let topic = state.topics.get(&topic).ok_or_else(already_deleted)?;
+        Ok((topic.topic_name.clone(), topic.type_name.clone()))
+    }
+
+    pub(crate) fn topic_qos(&self, topic: GUID_t) -> Result<TopicQos, Error> {
+        let state = self.state()?;
+        let topic = state.topics.get(&topic).ok_or_else(already_deleted)?;
+        Ok(topic.qos.clone())
+    }
+
+    pub(crate) fn set_topic_qos(&self, topic: GUID_t, qos: TopicQos) -> Result<(), Error> {
+        qos.validate().map_err(inconsistent_policy)?;
+        let mut state = self.state()?;
+        let topic = state.topics.get_mut(&topic).ok_or_else(already_deleted)?;
+        topic.qos = qos;
+        Ok(())
+    }
+
+    pub(crate) fn enable_topic(&self, topic: GUID_t) -> Result<(), Error> {
+        let mut state = self.state()?;
+        if !state.enabled {
+            return Err(precondition_not_met("The DomainParticipant is not enabled"));
+        }
+        state
+            .topics
+            .get_mut(&topic)
+            .ok_or_else(already_deleted)?
+            .enabled = true;
+        Ok(())
+    }
+
+    pub(crate) fn is_topic_enabled(&self, topic: GUID_t) -> bool {
+        let state = self.state.lock().unwrap();
+        state.topics.get(&topic).is_some_and(|topic| topic.enabled)
+    }
+}
@@ -0,0 +1,546 @@
+use crate::behavior::stateful_writer::StatefulWriter;
+use crate::behavior::writer::{Writer, WriterAttributes};
+use crate::dds::condition::wakers::Wakers;
+use crate::dds::domain_participant::instance_state::deadline;
+use crate::dds::domain_participant::{
+    already_deleted, immutable_policy, inconsistent_policy, not_enabled, precondition_not_met,
+    reliability_level, topic_kind, DomainParticipant, ParticipantState,
+};
+use crate::dds::filter::filter_fields::SampleDecoder;
+use crate::dds::listener::notification::EndpointListener;
+use crate::dds::publisher::Publisher;
+use crate::dds::qos::data_writer_qos::DataWriterQos;
+use crate::dds::qos::history_qos_policy::HistoryQosPolicyKind;
+use crate::dds::qos::resource_limits_qos_policy::ResourceLimitsQosPolicy;
+use crate::dds::status::deadline_missed_status::OfferedDeadlineMissedStatus;
+use crate::dds::status::entity_statuses::EntityStatuses;
+use crate::dds::status::publication_matched_status::PublicationMatchedStatus;
+use crate::dds::status::status_kind::{StatusKind, StatusMask};
+use crate::dds::topic::Topic;
+use crate::dds::type_support::TypeSupport;
+use crate::discovery::discovered_writer_data::DiscoveredWriterData;
+use crate::discovery::pl_cdr;
+use crate::messages::submessage_elements::serialized_payload::SerializedPayload;
+use crate::structure::change_kind::ChangeKind_t;
+use crate::structure::data::Data;
+use crate::structure::duration::Duration_t;
+use crate::structure::entity_id::EntityId_t;
+use crate::structure::guid::GUID_t;
+use crate::structure::history_cache::HistoryCache;
+use crate::structure::instance_handle::InstanceHandle_t;
+use crate::structure::sequence_number::SequenceNumber_t;
+use crate::structure::time::Time_t;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{Error, ErrorKind};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+/// The timing of the reliable protocol of the DataWriters.
+const HEARTBEAT_PERIOD: Duration = Duration::from_secs(1);
+const NACK_RESPONSE_DELAY: Duration = Duration::from_millis(200);
+
+/// Samples bigger than a UDP datagram are fragmented, as well as the ones
+/// not fitting into a single message of the transports.
+const DATA_MAX_SIZE_SERIALIZED: u32 = 60 * 1024;
+
+/// The RTPS Writer of the DataWriter exists while it is enabled.
+pub(super) struct WriterState {
+    pub(super) publisher: GUID_t,
+    pub(super) topic: GUID_t,
+    pub(super) qos: DataWriterQos,
+    pub(super) enabled: bool,
+
+    /// The decoder of the samples filtered on behalf of the DataReaders of
+    /// a ContentFilteredTopic, None if the type cannot be filtered.
+    pub(super) sample_decoder: Option<SampleDecoder>,
+
+    /// The instances registered and not yet unregistered.
+    pub(super) instances: BTreeSet<InstanceHandle_t>,
+
+    /// The tasks waiting for room in the history of the DataWriter.
+    pub(super) wakers: Wakers,
+
+    pub(super) statuses: EntityStatuses,
+    pub(super) listener: Option<(EndpointListener, StatusMask)>,
+    pub(super) offered_deadline_missed: OfferedDeadlineMissedStatus,
+    pub(super) publication_matched: PublicationMatchedStatus,
+
+    /// The time by which each instance has to be written again to meet
+    /// the DeadlineQosPolicy.
+    pub(super) deadlines: BTreeMap<InstanceHandle_t, Instant>,
+}
+
+impl DomainParticipant {
+    /// Creates the DataWriter of the Topic of the type, returns its GUID.
+    pub(crate) fn create_datawriter<T: TypeSupport>(
+        &self,
+        publisher: GUID_t,
+        topic: &Topic,
+        qos: DataWriterQos,
+    ) -> Result<GUID_t, Error> {
+        qos.validate().map_err(inconsistent_policy)?;
+        let mut state = self.state()?;
+        let autoenable = match state.publishers.get(&publisher) {
+            Some(publisher) => {
+                publisher.enabled && publisher.qos.entity_factory.autoenable_created_entities
+            }
+            None => return Err(already_deleted()),
+        };
+        if topic.get_participant() != *self || !state.topics.contains_key(&topic.guid()) {
+            return Err(precondition_not_met(
+                "The Topic belongs to another participant",
+            ));
+        }
+        if state.topics[&topic.guid()].type_name != T::TYPE_NAME {
+            return Err(precondition_not_met(format!(
+                "The type of the Topic is not {}",
+                T::TYPE_NAME
+            )));
+        }
+        let guid = state.next_guid(if T::WITH_KEY {
+            EntityId_t::ENTITYKIND_USER_DEFINED_WRITER_WITH_KEY
+        } else {
+            EntityId_t::ENTITYKIND_USER_DEFINED_WRITER_NO_KEY
+        });
+        state.writers.insert(
+            guid,
+            WriterState {
+                publisher,
+                topic: topic.guid(),
+                qos,
+                enabled: false,
+                sample_decoder: T::sample_decoder(),
+                instances: BTreeSet::new(),
+                wakers: Wakers::default(),
+                statuses: EntityStatuses::default(),
+                listener: None,
+                offered_deadline_missed: OfferedDeadlineMissedStatus::default(),
+                publication_matched: PublicationMatchedStatus::default(),
+                deadlines: BTreeMap::new(),
+            },
+        );
+        if autoenable {
+            state.enable_writer(guid)?;
+        }
+        Ok(guid)
+    }
+
+    pub(crate) fn delete_datawriter(&self, publisher: GUID_t, writer: GUID_t) -> Result<(), Error> {
+        let mut state = self.state()?;
+        match state.writers.get(&writer) {
+            Some(state) if state.publisher == publisher => (),
+            _ => {
+                return Err(precondition_not_met(
+                    "The DataWriter belongs to another Publisher",
+                ))
+            }
+        }
+        state.delete_writer(writer);
+        Ok(())
+    }
+
+    /// The DataWriters of the Publisher, of the topic_name if provided.
+    pub(crate) fn datawriters(&self, publisher: GUID_t, topic_name: Option<&str>) -> Vec<GUID_t> {
+        let state = self.state.lock().unwrap();
+        state
+            .writers
+            .iter()
+            .filter(|(_, writer)| writer.publisher == publisher)
+            .filter(|(_, writer)| {
+                topic_name
+                    .is_none_or(|topic_name| state.topics[&writer.topic].topic_name == topic_name)
+            })
+            .map(|(guid, _)| *guid)
+            .collect()
+    }
+
+    pub(crate) fn writer_topic(&self, writer: GUID_t) -> Result<Topic, Error> {
+        let state = self.state()?;
+        let writer = state.writers.get(&writer).ok_or_else(already_deleted)?;
+        Ok(Topic::new(self.clone(), writer.topic))
+    }
+
+    pub(crate) fn writer_publisher(&self, writer: GUID_t) -> Result<Publisher, Error> {
+        let state = self.state()?;
+        let writer = state.writers.get(&writer).ok_or_else(already_deleted)?;
+        Ok(Publisher::new(self.clone(), writer.publisher))
+    }
+
+    pub(crate) fn writer_qos(&self, writer: GUID_t) -> Result<DataWriterQos, Error> {
+        let state = self.state()?;
+        let writer = state.writers.get(&writer).ok_or_else(already_deleted)?;
+        Ok(writer.qos.clone())
+    }
+
+    /// Replaces the QoS of the DataWriter, announced again if enabled;
+    /// the immutable policies of the enabled DataWriter are kept.
+    pub(crate) fn set_writer_qos(&self, writer: GUID_t, qos: DataWriterQos) -> Result<(), Error> {
+        qos.validate().map_err(inconsistent_policy)?;
+        let mut state = self.state()?;
+        let writer_state = state.writers.get_mut(&writer).ok_or_else(already_deleted)?;
+        if writer_state.enabled {
+            writer_state
+                .qos
+                .check_changeable(&qos)
+                .map_err(immutable_policy)?;
+        }
+        writer_state.qos = qos;
+        if writer_state.enabled {
+            state.announce_writer(writer);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn enable_writer(&self, writer: GUID_t) -> Result<(), Error> {
+        self.state()?.enable_writer(writer)
+    }
+
+    pub(crate) fn is_writer_enabled(&self, writer: GUID_t) -> bool {
+        let state = self.state.lock().unwrap();
+        state
+            .writers
+            .get(&writer)
+            .is_some_and(|writer| writer.enabled)
+    }
+
+    /// The handles of the DataReaders matched with the enabled DataWriter.
+    pub(crate) fn matched_subscriptions(
+        &self,
+        writer: GUID_t,
+    ) -> Result<Vec<InstanceHandle_t>, Error> {
+        let state = self.state()?;
+        match state.writers.get(&writer) {
+            Some(writer_state) if writer_state.enabled => Ok(state
+                .rtps
+                .matcher()
+                .matched_readers(writer)
+                .map(pl_cdr::key_hash)
+                .collect()),
+            Some(_) => Err(not_enabled()),
+            None => Err(already_deleted()),
+        }
+    }
+
+    /// Adds the change of the instance to the history of the enabled
+    /// DataWriter, see ParticipantState::write_change.
+    /// Writes the change once the history of the DataWriter has room; the
+    /// waiting task is woken as the participant is driven.
+    pub(crate) fn poll_write_change(
+        &self,
+        writer: GUID_t,
+        kind: ChangeKind_t,
+        serialized_payload: &SerializedPayload,
+        instance_handle: InstanceHandle_t,
+        source_timestamp: Time_t,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Error>> {
+        let mut state = self.state()?;
+        match state.write_change(
+            writer,
+            kind,
+            serialized_payload.clone(),
+            instance_handle,
+            source_timestamp,
+        ) {
+            Err(error) if error.kind() == ErrorKind::WouldBlock => {
+                state.writers.get_mut(&writer).unwrap().wakers.register(cx);
+                Poll::Pending
+            }
+            result => Poll::Ready(result),
+        }
+    }
+
+    pub(crate) fn register_instance(
+        &self,
+        writer: GUID_t,
+        instance_handle: InstanceHandle_t,
+    ) -> Result<(), Error> {
+        let mut state = self.state()?;
+        match state.writers.get_mut(&writer) {
+            Some(writer_state) if writer_state.enabled => {
+                writer_state.instances.insert(instance_handle);
+                Ok(())
+            }
+            Some(_) => Err(not_enabled()),
+            None => Err(already_deleted()),
+        }
+    }
+
+    pub(crate) fn is_instance_registered(
+        &self,
+        writer: GUID_t,
+        instance_handle: InstanceHandle_t,
+    ) -> bool {
+        let state = self.state.lock().unwrap();
+        state
+            .writers
+            .get(&writer)
+            .is_some_and(|writer| writer.instances.contains(&instance_handle))
+    }
+
+    pub(crate) fn set_writer_listener(
+        &self,
+        writer: GUID_t,
+        listener: Option<EndpointListener>,
+        mask: StatusMask,
+    ) -> Result<(), Error> {
+        let mut state = self.state()?;
+        let writer = state.writers.get_mut(&writer).ok_or_else(already_deleted)?;
+        writer.listener = listener.map(|listener| (listener, mask));
+        Ok(())
+    }
+}
+
+impl ParticipantState {
+    /// Creates the RTPS Writer of the DataWriter and announces it.
+    pub(super) fn enable_writer(&mut self, guid: GUID_t) -> Result<(), Error> {
+        let writer = self.writers.get(&guid).ok_or_else(already_deleted)?;
+        if writer.enabled {
+            return Ok(());
+        }
+        if !self.publishers[&writer.publisher].enabled {
+            return Err(precondition_not_met("The Publisher is not enabled"));
+        }
+        if !self.topics[&writer.topic].enabled {
+            return Err(precondition_not_met("The Topic is not enabled"));
+        }
+        let reliability_level = reliability_level(writer.qos.reliability.kind);
+        if let Some(sample_decoder) = &writer.sample_decoder {
+            self.rtps
+                .matcher_mut()
+                .set_sample_decoder(guid, sample_decoder.clone());
+        }
+
+        let fragment_size = self
+            .fragment_size
+            .unwrap_or_else(|| WriterAttributes::max_fragment_size(self.rtps.max_message_size()));
+        let now = (self.clock)();
+        self.rtps.add_writer(
+            now,
+            guid.entity_id,
+            topic_kind(guid.entity_id),
+            reliability_level,
+            WriterAttributes {
+                push_mode: true,
+                heartbeat_period: Duration_t::from(HEARTBEAT_PERIOD),
+                nack_response_delay: Duration_t::from(NACK_RESPONSE_DELAY),
+                nack_suppression_duration: Duration_t::DURATION_ZERO,
+                last_change_sequence_number: SequenceNumber_t::from(0),
+                data_max_size_serialized: DATA_MAX_SIZE_SERIALIZED,
+                fragment_size,
+                writer_cache: HistoryCache::new(),
+            },
+        )?;
+        self.writers.get_mut(&guid).unwrap().enabled = true;
+        self.announce_writer(guid);
+        Ok(())
+    }
+
+    /// Matches the enabled DataWriter and announces it with its QoS
+    /// combined with the ones of its Publisher and Topic.
+    pub(super) fn announce_writer(&mut self, guid: GUID_t) {
+        let writer = &self.writers[&guid];
+        let topic = &self.topics[&writer.topic];
+        let publisher = &self.publishers[&writer.publisher];
+        let mut writer_data = DiscoveredWriterData::new(guid, &topic.topic_name, &topic.type_name);
+        writer_data.durability = writer.qos.durability;
+        writer_data.deadline = writer.qos.deadline;
+        writer_data.liveliness = writer.qos.liveliness;
+        writer_data.reliability = writer.qos.reliability;
+        writer_data.ownership = writer.qos.ownership;
+        writer_data.destination_order = writer.qos.destination_order;
+        writer_data.presentation = publisher.qos.presentation;
+        writer_data.partition = publisher.qos.partition.clone();
+        writer_data.latency_budget = writer.qos.latency_budget;
+        writer_data.lifespan = writer.qos.lifespan;
+        writer_data.durability_service = writer.qos.durability_service;
+        writer_data.history = writer.qos.history;
+        writer_data.resource_limits = writer.qos.resource_limits;
+        writer_data.transport_priority = writer.qos.transport_priority;
+        writer_data.ownership_strength = writer.qos.ownership_strength;
+        writer_data.user_data = writer.qos.user_data.clone();
+        writer_data.topic_data = topic.qos.topic_data.clone();
+        writer_data.group_data = publisher.qos.group_data.clone();
+
+        let now = (self.clock)();
+        self.rtps.announce_writer(now, &writer_data);
+    }
+
+    /// Adds the change to the history of the enabled DataWriter, see
+    /// make_room. Writing the ALIVE change registers the instance and
+    /// asserts the liveliness of the DataWriter; only the registered
+    /// instance can be unregistered, it is disposed too with
+    /// autodispose_unregistered_instances.
+    pub(super) fn write_change(
+        &mut self,
+        guid: GUID_t,
+        kind: ChangeKind_t,
+        serialized_payload: SerializedPayload,
+        instance_handle: InstanceHandle_t,
+        source_timestamp: Time_t,
+    ) -> Result<(), Error> {
+        let writer = self.writers.get(&guid).ok_or_else(already_deleted)?;
+        if !writer.enabled {
+            return Err(not_enabled());
+        }
+        if kind == ChangeKind_t::NOT_ALIVE_UNREGISTERED
+            && !writer.instances.contains(&instance_handle)
+        {
+            return Err(precondition_not_met("The instance is not registered"));
+        }
+        let kind = if kind == ChangeKind_t::NOT_ALIVE_UNREGISTERED
+            && writer
+                .qos
+                .writer_data_lifecycle
+                .autodispose_unregistered_instances
+        {
+            ChangeKind_t::NOT_ALIVE_DISPOSED_UNREGISTERED
+        } else {
+            kind
+        };
+        self.make_room(guid, instance_handle)?;
+
+        let writer = self.writers.get_mut(&guid).unwrap();
+        match kind {
+            ChangeKind_t::ALIVE => {
+                writer.instances.insert(instance_handle);
+            }
+            ChangeKind_t::NOT_ALIVE_UNREGISTERED
+            | ChangeKind_t::NOT_ALIVE_DISPOSED_UNREGISTERED => {
+                writer.instances.remove(&instance_handle);
+                writer.deadlines.remove(&instance_handle);
+            }
+            ChangeKind_t::NOT_ALIVE_DISPOSED => {
+                writer.deadlines.remove(&instance_handle);
+            }
+        }
+        let is_alive = kind == ChangeKind_t::ALIVE;
+        let deadline_qos = writer.qos.deadline;
+
+        let rtps_writer = self
+            .rtps
+            .protocol_mut()
+            .writer_mut(guid.entity_id)
+            .expect("The enabled DataWriter has its RTPS Writer");
+        let mut change =
+            rtps_writer.new_change(kind, Data::from(serialized_payload.value), instance_handle);
+        change.source_timestamp = Some(source_timestamp);
+        rtps_writer.as_writer_mut().writer_cache.add_change(change);
+
+        if is_alive {
+            let now = (self.clock)();
+            if let Some(deadline) = deadline(now, deadline_qos) {
+                let writer = self.writers.get_mut(&guid).unwrap();
+                writer.deadlines.insert(instance_handle, deadline);
+            }
+            self.rtps.assert_writer_liveliness(now, guid);
+        }
+        Ok(())
+    }
+
+    /// Makes room in the history of the enabled DataWriter for a change of
+    /// the instance. KEEP_LAST removes the oldest changes of the instance
+    /// beyond the depth. When the ResourceLimitsQosPolicy is reached, the
+    /// changes acknowledged by all matched Readers are removed; the history
+    /// still without room is full.
+    pub(super) fn make_room(
+        &mut self,
+        guid: GUID_t,
+        instance_handle: InstanceHandle_t,
+    ) -> Result<(), Error> {
+        let qos = &self.writers[&guid].qos;
+        let history = qos.history;
+        let resource_limits = qos.resource_limits;
+        let rtps_writer = self
+            .rtps
+            .protocol_mut()
+            .writer_mut(guid.entity_id)
+            .expect("The enabled DataWriter has its RTPS Writer");
+
+        let instance_changes = |rtps_writer: &StatefulWriter| {
+            rtps_writer
+                .as_writer()
+                .writer_cache
+                .changes()
+                .iter()
+                .filter(|change| change.instance_handle == instance_handle)
+                .map(|change| change.sequence_number)
+                .collect::<Vec<_>>()
+        };
+        if history.kind == HistoryQosPolicyKind::KEEP_LAST {
+            let instance_changes = instance_changes(rtps_writer);
+            let excess = (instance_changes.len() + 1).saturating_sub(history.depth.max(1) as usize);
+            for sequence_number in instance_changes.into_iter().take(excess) {
+                rtps_writer
+                    .as_writer_mut()
+                    .writer_cache
+                    .remove_change(sequence_number);
+            }
+        }
+
+        let is_full = |rtps_writer: &StatefulWriter| {
+            ResourceLimitsQosPolicy::is_reached(
+                resource_limits.max_samples,
+                rtps_writer.as_writer().writer_cache.changes().len(),
+            ) || ResourceLimitsQosPolicy::is_reached(
+                resource_limits.max_samples_per_instance,
+                instance_changes(rtps_writer).len(),
+            )
+        };
+        if !is_full(rtps_writer) {
+            return Ok(());
+        }
+        let acked_changes: Vec<SequenceNumber_t> = rtps_writer
+            .as_writer()
+            .writer_cache
+            .changes()
+            .iter()
+            .filter(|change| rtps_writer.is_acked_by_all(change))
+            .map(|change| change.sequence_number)
+            .collect();
+        for sequence_number in acked_changes {
+            rtps_writer
+                .as_writer_mut()
+                .writer_cache
+                .remove_change(sequence_number);
+        }
+        if is_full(rtps_writer) {
+            return Err(history_full());
+        }
+        Ok(())
+    }
+
+    pub(super) fn publication_matched(
+        &mut self,
+        writer_guid: GUID_t,
+        reader_guid: GUID_t,
+        matched: bool,
+    ) {
+        if let Some(writer) = self.writers.get_mut(&writer_guid) {
+            writer
+                .publication_matched
+                .change(pl_cdr::key_hash(reader_guid), matched);
+            self.status_changed(writer_guid, StatusKind::PUBLICATION_MATCHED);
+        }
+    }
+
+    pub(super) fn delete_writer(&mut self, guid: GUID_t) {
+        if let Some(mut writer) = self.writers.remove(&guid) {
+            writer.wakers.wake();
+            if writer.enabled {
+                let now = (self.clock)();
+                self.rtps.delete_writer(now, guid);
+            }
+        }
+    }
+}
+
+/// The history of the DataWriter is full; the write operation would block
+/// until the changes are acknowledged.
+pub(super) fn history_full() -> Error {
+    Error::new(
+        ErrorKind::WouldBlock,
+        "The history of the DataWriter is full",
+    )
+}
@@ -0,0 +1,306 @@
+use crate::dds::domain_participant::{precondition_not_met, DomainId_t, DomainParticipant};
+use crate::dds::qos::domain_participant_factory_qos::DomainParticipantFactoryQos;
+use crate::dds::qos::domain_participant_qos::DomainParticipantQos;
+use crate::discovery::port_mapping::PortMapping;
+use crate::messages::vendor_id::VendorId_t;
+
+use std::io::Error;
+use std::sync::{Mutex, OnceLock};
+
+/// The factory of the DomainParticipants (2.2.2.2.2). The application uses
+/// the single instance returned by get_instance.
+#[derive(Default)]
+pub struct DomainParticipantFactory {
+    state: Mutex<FactoryState>,
+}
+
+#[derive(Default)]
+struct FactoryState {
+    qos: DomainParticipantFactoryQos,
+    default_participant_qos: DomainParticipantQos,
+
+    /// The vendor id of the created participants. No vendor id has been
+    /// assigned to this implementation by the OMG, so it is VENDOR_UNKNOWN
+    /// unless configured.
+    vendor_id: VendorId_t,
+    participants: Vec<DomainParticipant>,
+}
+
+impl DomainParticipantFactory {
+    pub fn get_instance() -> &'static DomainParticipantFactory {
+        static INSTANCE: OnceLock<DomainParticipantFactory> = OnceLock::new();
+        INSTANCE.get_or_init(DomainParticipantFactory::default)
+    }
+
+    /// Creates the DomainParticipant of the domain communicating over
+    /// UDPv4 on the well-known ports of the domain for the first free
+    /// participant id, see DomainParticipant::get_participant_id. It is
+    /// enabled together with its discovery if autoenable_created_entities
+    /// is set. Without it, the transports, locators and discovery of the
+    /// DomainParticipant can be overridden before it is enabled. Has to be
+    /// called within the Tokio runtime.
+    pub fn create_participant(
+        &self,
+        domain_id: DomainId_t,
+        qos: DomainParticipantQos,
+    ) -> Result<DomainParticipant, Error> {
+        let mut state = self.state.lock().unwrap();
+        let participant = DomainParticipant::new(domain_id, qos, state.vendor_id);
+        participant.add_default_transport(&PortMapping::default())?;
+        if state.qos.entity_factory.autoenable_created_entities {
+            participant.enable()?;
+        }
+        state.participants.push(participant.clone());
+        Ok(participant)
+    }
+
+    /// Deletes the DomainParticipant without Topics, Publishers and
+    /// Subscribers. All further operations on it fail. The deletion is
+    /// announced to the remote participants by run, which then completes.
+    pub fn delete_participant(&self, participant: &DomainParticipant) -> Result<(), Error> {
+        let mut state = self.state.lock().unwrap();
+        let index = state
+            .participants
+            .iter()
+            .position(|created| created == participant)
+            .ok_or_else(|| precondition_not_met("The DomainParticipant was not created here"))?;
+        if !participant.is_empty() {
+            return Err(precondition_not_met("The DomainParticipant has entities"));
+        }
+        state.participants.remove(index);
+        participant.mark_deleted();
+        Ok(())
+    }
+
+    /// A DomainParticipant of the domain created by the factory.
+    pub fn lookup_participant(&self, domain_id: DomainId_t) -> Option<DomainParticipant> {
+        let state = self.state.lock().unwrap();
+        state
+            .participants
+            .iter()
+            .find(|participant| participant.get_domain_id() == domain_id)
+            .cloned()
+    }
+
+    pub fn get_qos(&self) -> DomainParticipantFactoryQos {
+        self.state.lock().unwrap().qos.clone()
+    }
+
+    pub fn set_qos(&self, qos: DomainParticipantFactoryQos) {
+        self.state.lock().unwrap().qos = qos;
+    }
+
+    pub fn get_vendor_id(&self) -> VendorId_t {
+        self.state.lock().unwrap().vendor_id
+    }
+
+    /// Sets the vendor id announced by the participants created afterwards,
+    /// e.g. the one assigned to the deployment by the OMG.
+    pub fn set_vendor_id(&self, vendor_id: VendorId_t) {
+        self.state.lock().unwrap().vendor_id = vendor_id;
+    }
+
+    pub fn get_default_participant_qos(&self) -> DomainParticipantQos {
+        self.state.lock().unwrap().default_participant_qos.clone()
+    }
+
+    pub fn set_default_participant_qos(&self, qos: DomainParticipantQos) {
+        self.state.lock().unwrap().default_participant_qos = qos;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dds::qos::data_reader_qos::DataReaderQos;
+    use crate::dds::qos::data_writer_qos::DataWriterQos;
+    use crate::dds::qos::entity_factory_qos_policy::EntityFactoryQosPolicy;
+    use crate::dds::qos::publisher_qos::PublisherQos;
+    use crate::dds::qos::subscriber_qos::SubscriberQos;
+    use crate::dds::qos::topic_qos::TopicQos;
+    use crate::dds::type_support::tests::Shape;
+    use crate::dds::type_support::TypeSupport;
+    use crate::protocol::event::Event;
+    use crate::structure::guid_prefix::GuidPrefix_t;
+    use futures::StreamExt;
+    use std::io::ErrorKind;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn participants_are_created_looked_up_and_deleted() {
+        let factory = DomainParticipantFactory::default();
+        let participant = factory
+            .create_participant(7, factory.get_default_participant_qos())
+            .unwrap();
+        assert!(participant.is_enabled());
+        assert_eq!(Some(participant.clone()), factory.lookup_participant(7));
+        assert_eq!(None, factory.lookup_participant(8));
+
+        let topic = participant
+            .create_topic("topic", "type", TopicQos::default())
+            .unwrap();
+        assert_eq!(
+            ErrorKind::InvalidInput,
+            factory.delete_participant(&participant).unwrap_err().kind()
+        );
+        participant.delete_topic(&topic).unwrap();
+        factory.delete_participant(&participant).unwrap();
+
+        assert_eq!(None, factory.lookup_participant(7));
+        assert_eq!(
+            ErrorKind::NotFound,
+            participant
+                .create_topic("topic", "type", TopicQos::default())
+                .unwrap_err()
+                .kind()
+        );
+        assert_eq!(
+            ErrorKind::InvalidInput,
+            factory.delete_participant(&participant).unwrap_err().kind()
+        );
+    }
+
+    #[tokio::test]
+    async fn participants_are_not_enabled_without_autoenable() {
+        let factory = DomainParticipantFactory::default();
+        factory.set_qos(DomainParticipantFactoryQos {
+            entity_factory: EntityFactoryQosPolicy {
+                autoenable_created_entities: false,
+            },
+        });
+
+        let participant = factory
+            .create_participant(0, DomainParticipantQos::default())
+            .unwrap();
+
+        assert!(!participant.is_enabled());
+        participant.enable().unwrap();
+        assert!(participant.is_enabled());
+    }
+
+    #[tokio::test]
+    async fn participants_are_created_with_the_vendor_id() {
+        let factory = DomainParticipantFactory::default();
+        assert_eq!(VendorId_t::VENDOR_UNKNOWN, factory.get_vendor_id());
+        factory.set_vendor_id(VendorId_t::from([0x01, 0x7F]));
+
+        let participant = factory
+            .create_participant(0, DomainParticipantQos::default())
+            .unwrap();
+
+        assert_eq!(VendorId_t::from([0x01, 0x7F]), participant.get_vendor_id());
+    }
+
+    #[tokio::test]
+    async fn participants_discover_each_other_over_udp() {
+        let factory = DomainParticipantFactory::default();
+        let writer_participant = factory
+            .create_participant(17, DomainParticipantQos::default())
+            .unwrap();
+        let reader_participant = factory
+            .create_participant(17, DomainParticipantQos::default())
+            .unwrap();
+        let writer_participant_id = writer_participant.get_participant_id().unwrap();
+        let reader_participant_id = reader_participant.get_participant_id().unwrap();
+        assert_ne!(writer_participant_id, reader_participant_id);
+
+        let topic = writer_participant
+            .create_topic("topic", Shape::TYPE_NAME, TopicQos::default())
+            .unwrap();
+        let writer = writer_participant
+            .create_publisher(PublisherQos::default())
+            .unwrap()
+            .create_datawriter::<Shape>(&topic, DataWriterQos::default())
+            .unwrap();
+        let remote_topic = reader_participant
+            .create_topic("topic", Shape::TYPE_NAME, TopicQos::default())
+            .unwrap();
+        let mut reader = reader_participant
+            .create_subscriber(SubscriberQos::default())
+            .unwrap()
+            .create_datareader::<Shape>(&remote_topic, DataReaderQos::default())
+            .unwrap();
+
+        let red = Shape::new("RED", 1, 2);
+        let exchange = async {
+            while writer.get_matched_subscriptions().unwrap().is_empty() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            writer.write(&red).unwrap();
+            reader.next().await.unwrap()
+        };
+        let sample = tokio::time::timeout(Duration::from_secs(10), async {
+            tokio::select! {
+                sample = exchange => sample,
+                result = writer_participant.run() => panic!("{:?}", result),
+                result = reader_participant.run() => panic!("{:?}", result),
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(Some(red), sample.data);
+    }
+
+    /// Waits until the participant discovers or loses the remote one.
+    async fn discovery_event(
+        participant: &DomainParticipant,
+        guid_prefix: GuidPrefix_t,
+        discovered: bool,
+    ) {
+        loop {
+            match participant.poll_event() {
+                Some(Event::ParticipantDiscovered { participant_data })
+                    if discovered && participant_data.guid_prefix == guid_prefix =>
+                {
+                    return
+                }
+                Some(Event::ParticipantLost { guid_prefix: lost })
+                    if !discovered && lost == guid_prefix =>
+                {
+                    return
+                }
+                Some(_) => (),
+                None => tokio::time::sleep(Duration::from_millis(10)).await,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn deleted_participants_are_lost_by_the_remote_ones() {
+        let factory = DomainParticipantFactory::default();
+        let remaining = factory
+            .create_participant(19, DomainParticipantQos::default())
+            .unwrap();
+        let deleted = factory
+            .create_participant(19, DomainParticipantQos::default())
+            .unwrap();
+        let deleted_guid_prefix = deleted.guid().guid_prefix;
+
+        tokio::time::timeout(Duration::from_secs(10), async {
+            tokio::select! {
+                _ = discovery_event(&remaining, deleted_guid_prefix, true) => (),
+                result = remaining.run() => panic!("{:?}", result),
+                result = deleted.run() => panic!("{:?}", result),
+            }
+        })
+        .await
+        .unwrap();
+        factory.delete_participant(&deleted).unwrap();
+
+        // Lost well before the lease duration of the deleted participant
+        tokio::time::timeout(Duration::from_secs(5), async {
+            let (_, result) = tokio::join!(
+                async {
+                    tokio::select! {
+                        _ = discovery_event(&remaining, deleted_guid_prefix, false) => (),
+                        result = remaining.run() => panic!("{:?}", result),
+                    }
+                },
+                deleted.run()
+            );
+            result.unwrap();
+        })
+        .await
+        .unwrap();
+    }
+}
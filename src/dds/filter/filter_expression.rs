@@ -0,0 +1,537 @@
+use crate::dds::filter::filter_fields::FilterFields;
+use crate::dds::filter::filter_value::FilterValue;
+use std::cmp::Ordering;
+use std::io::{Error, ErrorKind, Result};
+
+/// A filter expression of the DDS SQL subset (DDS Annex B), e.g.
+/// `x > %0 AND (name LIKE 'sensor%' OR NOT active)`.
+///
+/// Supported are the comparisons `=`, `<>`, `<`, `<=`, `>`, `>=`, `LIKE`,
+/// `BETWEEN .. AND ..`, optionally negated with `NOT`, combined with `AND`,
+/// `OR`, `NOT` and parentheses. The operands are field names, integer,
+/// float, string ('text') and boolean (TRUE, FALSE) literals and the
+/// parameters %0 to %99, which are substituted with the expression
+/// parameters when parsing. The parentheses and NOT may be nested at most
+/// MAX_NESTING_DEPTH levels deep.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FilterExpression {
+    condition: Condition,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Condition {
+    And(Vec<Condition>),
+    Or(Vec<Condition>),
+    Not(Box<Condition>),
+    Comparison(Operand, RelOp, Operand),
+    Between(Operand, Operand, Operand),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Operand {
+    Field(String),
+    Value(FilterValue),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum RelOp {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Like,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Identifier(String),
+    Value(FilterValue),
+    Parameter(usize),
+    RelOp(RelOp),
+    LeftParenthesis,
+    RightParenthesis,
+}
+
+impl FilterExpression {
+    pub const MAX_NESTING_DEPTH: usize = 64;
+
+    pub fn parse(expression: &str, parameters: &[String]) -> Result<Self> {
+        let mut parser = Parser {
+            tokens: tokenize(expression)?,
+            position: 0,
+            depth: 0,
+            parameters,
+        };
+        let condition = parser.or()?;
+        match parser.next() {
+            None => Ok(FilterExpression { condition }),
+            Some(token) => Err(invalid(format!("Unexpected {:?}", token))),
+        }
+    }
+
+    /// Whether the sample passes the filter. None if the sample cannot be
+    /// evaluated, i.e. a field is missing or the compared values are not
+    /// comparable. Like in SQL, a condition which cannot be evaluated does
+    /// not matter when combined by AND with a false or by OR with a true
+    /// condition.
+    pub fn evaluate(&self, fields: &dyn FilterFields) -> Option<bool> {
+        self.condition.evaluate(fields)
+    }
+}
+
+impl Condition {
+    fn evaluate(&self, fields: &dyn FilterFields) -> Option<bool> {
+        match self {
+            Condition::And(conditions) => combine(conditions, fields, false),
+            Condition::Or(conditions) => combine(conditions, fields, true),
+            Condition::Not(condition) => condition.evaluate(fields).map(|result| !result),
+            Condition::Comparison(first, RelOp::Like, second) => {
+                match (first.value(fields)?, second.value(fields)?) {
+                    (FilterValue::String(value), FilterValue::String(pattern)) => {
+                        Some(like(pattern.as_bytes(), value.as_bytes()))
+                    }
+                    _ => None,
+                }
+            }
+            Condition::Comparison(first, rel_op, second) => {
+                let ordering = first.value(fields)?.compare(&second.value(fields)?)?;
+                Some(match rel_op {
+                    RelOp::Equal => ordering == Ordering::Equal,
+                    RelOp::NotEqual => ordering != Ordering::Equal,
+                    RelOp::Less => ordering == Ordering::Less,
+                    RelOp::LessOrEqual => ordering != Ordering::Greater,
+                    RelOp::Greater => ordering == Ordering::Greater,
+                    RelOp::GreaterOrEqual => ordering != Ordering::Less,
+                    RelOp::Like => unreachable!(),
+                })
+            }
+            Condition::Between(operand, low, high) => {
+                let value = operand.value(fields)?;
+                Some(
+                    value.compare(&low.value(fields)?)? != Ordering::Less
+                        && value.compare(&high.value(fields)?)? != Ordering::Greater,
+                )
+            }
+        }
+    }
+}
+
+impl Operand {
+    fn value(&self, fields: &dyn FilterFields) -> Option<FilterValue> {
+        match self {
+            Operand::Field(name) => fields.field(name),
+            Operand::Value(value) => Some(value.clone()),
+        }
+    }
+}
+
+/// Combines the conditions by AND (decisive false) or OR (decisive true):
+/// the decisive result of any condition is the result, otherwise None if
+/// any condition cannot be evaluated.
+fn combine(conditions: &[Condition], fields: &dyn FilterFields, decisive: bool) -> Option<bool> {
+    let mut result = Some(!decisive);
+    for condition in conditions {
+        match condition.evaluate(fields) {
+            Some(value) if value == decisive => return Some(decisive),
+            Some(_) => (),
+            None => result = None,
+        }
+    }
+    result
+}
+
+/// Matches the value with the LIKE pattern, where '%' matches any sequence
+/// of characters and '_' any single character. On a mismatch, only the
+/// last '%' is retried with one more character, so the matching takes at
+/// most pattern.len() * value.len() steps.
+fn like(pattern: &[u8], value: &[u8]) -> bool {
+    let (mut p, mut v) = (0, 0);
+    // The position after the last '%' and the value position it was tried at
+    let mut wildcard = None;
+    while v < value.len() {
+        match pattern.get(p) {
+            Some(b'%') => {
+                p += 1;
+                wildcard = Some((p, v));
+            }
+            Some(&c) if c == b'_' || c == value[v] => {
+                p += 1;
+                v += 1;
+            }
+            _ => match wildcard {
+                Some((after_wildcard, tried)) => {
+                    p = after_wildcard;
+                    v = tried + 1;
+                    wildcard = Some((after_wildcard, v));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'%')
+}
+
+fn invalid(message: String) -> Error {
+    Error::new(ErrorKind::InvalidInput, message)
+}
+
+fn tokenize(expression: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = expression.chars().collect();
+    let mut tokens = vec![];
+    let mut position = 0;
+
+    while let Some(&c) = chars.get(position) {
+        let next = chars.get(position + 1).copied();
+        let (token, length) = match c {
+            _ if c.is_whitespace() => {
+                position += 1;
+                continue;
+            }
+            '(' => (Token::LeftParenthesis, 1),
+            ')' => (Token::RightParenthesis, 1),
+            '=' => (Token::RelOp(RelOp::Equal), 1),
+            '<' if next == Some('>') => (Token::RelOp(RelOp::NotEqual), 2),
+            '!' if next == Some('=') => (Token::RelOp(RelOp::NotEqual), 2),
+            '<' if next == Some('=') => (Token::RelOp(RelOp::LessOrEqual), 2),
+            '<' => (Token::RelOp(RelOp::Less), 1),
+            '>' if next == Some('=') => (Token::RelOp(RelOp::GreaterOrEqual), 2),
+            '>' => (Token::RelOp(RelOp::Greater), 1),
+            '\'' | '`' => {
+                let end = chars[position + 1..]
+                    .iter()
+                    .position(|&other| other == c)
+                    .ok_or_else(|| invalid("Unterminated string".to_string()))?;
+                let value: String = chars[position + 1..position + 1 + end].iter().collect();
+                (Token::Value(FilterValue::String(value)), end + 2)
+            }
+            '%' => {
+                let length = chars[position + 1..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_digit())
+                    .count();
+                let index: String = chars[position + 1..position + 1 + length].iter().collect();
+                let index = index
+                    .parse()
+                    .map_err(|_| invalid("Invalid parameter".to_string()))?;
+                (Token::Parameter(index), length + 1)
+            }
+            _ if c.is_ascii_digit() || (c == '-' && next.is_some_and(|c| c.is_ascii_digit())) => {
+                let length = 1 + chars[position + 1..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_alphanumeric() || **c == '.')
+                    .count();
+                let literal: String = chars[position..position + length].iter().collect();
+                (Token::Value(number(&literal)?), length)
+            }
+            _ if c.is_alphabetic() || c == '_' => {
+                let length = chars[position..]
+                    .iter()
+                    .take_while(|c| c.is_alphanumeric() || **c == '_' || **c == '.')
+                    .count();
+                let identifier: String = chars[position..position + length].iter().collect();
+                let token = match identifier.to_ascii_uppercase().as_str() {
+                    "TRUE" => Token::Value(FilterValue::Boolean(true)),
+                    "FALSE" => Token::Value(FilterValue::Boolean(false)),
+                    "LIKE" => Token::RelOp(RelOp::Like),
+                    _ => Token::Identifier(identifier),
+                };
+                (token, length)
+            }
+            _ => return Err(invalid(format!("Unexpected character {:?}", c))),
+        };
+        tokens.push(token);
+        position += length;
+    }
+
+    Ok(tokens)
+}
+
+fn number(literal: &str) -> Result<FilterValue> {
+    literal
+        .parse()
+        .map(FilterValue::Integer)
+        .or_else(|_| literal.parse().map(FilterValue::Float))
+        .map_err(|_| invalid(format!("Invalid number {}", literal)))
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    position: usize,
+
+    /// The number of enclosing parentheses and NOT.
+    depth: usize,
+    parameters: &'a [String],
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    /// Consumes the keyword if it is the next token.
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Identifier(identifier)) if identifier.eq_ignore_ascii_case(keyword) => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn or(&mut self) -> Result<Condition> {
+        let mut conditions = vec![self.and()?];
+        while self.keyword("OR") {
+            conditions.push(self.and()?);
+        }
+        Ok(match conditions.len() {
+            1 => conditions.remove(0),
+            _ => Condition::Or(conditions),
+        })
+    }
+
+    fn and(&mut self) -> Result<Condition> {
+        let mut conditions = vec![self.not()?];
+        while self.keyword("AND") {
+            conditions.push(self.not()?);
+        }
+        Ok(match conditions.len() {
+            1 => conditions.remove(0),
+            _ => Condition::And(conditions),
+        })
+    }
+
+    fn not(&mut self) -> Result<Condition> {
+        if self.keyword("NOT") {
+            self.nested(|parser| Ok(Condition::Not(Box::new(parser.not()?))))
+        } else if self.peek() == Some(&Token::LeftParenthesis) {
+            self.position += 1;
+            let condition = self.nested(Parser::or)?;
+            match self.next() {
+                Some(Token::RightParenthesis) => Ok(condition),
+                _ => Err(invalid("Missing closing parenthesis".to_string())),
+            }
+        } else {
+            self.predicate()
+        }
+    }
+
+    /// Parses one level deeper, within the MAX_NESTING_DEPTH.
+    fn nested(&mut self, parse: impl FnOnce(&mut Self) -> Result<Condition>) -> Result<Condition> {
+        if self.depth == FilterExpression::MAX_NESTING_DEPTH {
+            return Err(invalid("Expression nested too deeply".to_string()));
+        }
+        self.depth += 1;
+        let condition = parse(self);
+        self.depth -= 1;
+        condition
+    }
+
+    fn predicate(&mut self) -> Result<Condition> {
+        let operand = self.operand()?;
+        let negated = self.keyword("NOT");
+        let condition = if self.keyword("BETWEEN") {
+            let low = self.operand()?;
+            if !self.keyword("AND") {
+                return Err(invalid("Missing AND of BETWEEN".to_string()));
+            }
+            Condition::Between(operand, low, self.operand()?)
+        } else {
+            match self.next() {
+                Some(Token::RelOp(RelOp::Like)) => {
+                    Condition::Comparison(operand, RelOp::Like, self.operand()?)
+                }
+                Some(Token::RelOp(rel_op)) if !negated => {
+                    Condition::Comparison(operand, rel_op, self.operand()?)
+                }
+                other => return Err(invalid(format!("Expected operator, found {:?}", other))),
+            }
+        };
+
+        if negated {
+            Ok(Condition::Not(Box::new(condition)))
+        } else {
+            Ok(condition)
+        }
+    }
+
+    fn operand(&mut self) -> Result<Operand> {
+        match self.next() {
+            Some(Token::Identifier(name)) => Ok(Operand::Field(name)),
+            Some(Token::Value(value)) => Ok(Operand::Value(value)),
+            Some(Token::Parameter(index)) => {
+                let parameter = self
+                    .parameters
+                    .get(index)
+                    .ok_or_else(|| invalid(format!("Missing parameter %{}", index)))?;
+                match tokenize(parameter)?.as_slice() {
+                    [Token::Value(value)] => Ok(Operand::Value(value.clone())),
+                    _ => Err(invalid(format!("Invalid parameter %{}", index))),
+                }
+            }
+            other => Err(invalid(format!("Expected operand, found {:?}", other))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn sample() -> BTreeMap<String, FilterValue> {
+        vec![
+            ("id", FilterValue::from(7)),
+            ("temperature", FilterValue::from(21.5)),
+            ("name", FilterValue::from("sensor_front")),
+            ("active", FilterValue::from(true)),
+            ("position.x", FilterValue::from(-3)),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect()
+    }
+
+    fn evaluate(expression: &str, parameters: &[&str]) -> Option<bool> {
+        let parameters: Vec<String> = parameters.iter().map(|p| p.to_string()).collect();
+        FilterExpression::parse(expression, &parameters)
+            .unwrap()
+            .evaluate(&sample())
+    }
+
+    #[test]
+    fn comparisons_are_evaluated() {
+        assert_eq!(Some(true), evaluate("id = 7", &[]));
+        assert_eq!(Some(false), evaluate("id <> 7", &[]));
+        assert_eq!(Some(true), evaluate("temperature > 21", &[]));
+        assert_eq!(Some(true), evaluate("21.5 <= temperature", &[]));
+        assert_eq!(Some(true), evaluate("position.x < 0", &[]));
+        assert_eq!(Some(true), evaluate("name >= 'sensor'", &[]));
+        assert_eq!(Some(true), evaluate("active = TRUE", &[]));
+        assert_eq!(Some(false), evaluate("id > temperature", &[]));
+    }
+
+    #[test]
+    fn conditions_are_combined() {
+        assert_eq!(Some(true), evaluate("id = 7 AND active = true", &[]));
+        assert_eq!(Some(true), evaluate("id = 8 OR id = 7", &[]));
+        assert_eq!(Some(false), evaluate("NOT (id = 8 OR id = 7)", &[]));
+        // AND binds stronger than OR
+        assert_eq!(Some(true), evaluate("id = 7 OR id = 8 AND id = 9", &[]));
+        assert_eq!(Some(false), evaluate("(id = 7 OR id = 8) and id = 9", &[]));
+    }
+
+    #[test]
+    fn between_and_like_are_evaluated() {
+        assert_eq!(Some(true), evaluate("temperature BETWEEN 20 AND 22", &[]));
+        assert_eq!(Some(false), evaluate("id NOT BETWEEN 5 AND 10", &[]));
+        assert_eq!(Some(true), evaluate("name LIKE 'sensor%'", &[]));
+        assert_eq!(Some(true), evaluate("name LIKE 'sensor_fron_'", &[]));
+        assert_eq!(Some(false), evaluate("name LIKE 'sensor'", &[]));
+        assert_eq!(Some(true), evaluate("name NOT LIKE '%rear'", &[]));
+    }
+
+    #[test]
+    fn like_with_many_wildcards_is_matched_in_polynomial_time() {
+        let value = "a".repeat(10_000);
+        let fields: BTreeMap<String, FilterValue> =
+            vec![("name".to_string(), FilterValue::from(value.as_str()))]
+                .into_iter()
+                .collect();
+        let matches = |pattern: &str| {
+            FilterExpression::parse(&format!("name LIKE '{}'", pattern), &[])
+                .unwrap()
+                .evaluate(&fields)
+        };
+
+        assert_eq!(Some(false), matches(&format!("{}b", "%a".repeat(20))));
+        assert_eq!(Some(true), matches(&format!("{}%", "%a".repeat(20))));
+        assert_eq!(Some(true), matches(&format!("%a_{}", "a".repeat(9_997))));
+        assert_eq!(Some(false), matches("%a_b%"));
+    }
+
+    #[test]
+    fn parameters_are_substituted() {
+        assert_eq!(
+            Some(true),
+            evaluate("id = %0 AND name LIKE %1", &["7", "'%front'"])
+        );
+        assert_eq!(
+            Some(false),
+            evaluate("temperature BETWEEN %1 AND %0", &["30.0", "25"])
+        );
+    }
+
+    #[test]
+    fn missing_fields_and_incomparable_values_are_not_evaluated() {
+        assert_eq!(None, evaluate("humidity > 50", &[]));
+        assert_eq!(None, evaluate("name > 5", &[]));
+        assert_eq!(None, evaluate("id LIKE '7'", &[]));
+        assert_eq!(None, evaluate("id = 7 AND humidity > 50", &[]));
+    }
+
+    #[test]
+    fn decided_combinations_ignore_unevaluated_conditions() {
+        assert_eq!(Some(false), evaluate("id = 8 AND humidity > 50", &[]));
+        assert_eq!(Some(false), evaluate("humidity > 50 AND id = 8", &[]));
+        assert_eq!(Some(true), evaluate("humidity > 50 OR id = 7", &[]));
+        assert_eq!(None, evaluate("humidity > 50 OR id = 8", &[]));
+    }
+
+    #[test]
+    fn deeply_nested_expressions_are_rejected() {
+        let depth = FilterExpression::MAX_NESTING_DEPTH;
+        let nested = |depth: usize| {
+            format!(
+                "{}{}id = 7{}",
+                "NOT ".repeat(depth / 2),
+                "(".repeat(depth - depth / 2),
+                ")".repeat(depth - depth / 2)
+            )
+        };
+
+        assert!(FilterExpression::parse(&nested(depth), &[]).is_ok());
+        assert_eq!(
+            ErrorKind::InvalidInput,
+            FilterExpression::parse(&nested(depth + 1), &[])
+                .unwrap_err()
+                .kind()
+        );
+        assert_eq!(
+            ErrorKind::InvalidInput,
+            FilterExpression::parse(&"(".repeat(100_000), &[])
+                .unwrap_err()
+                .kind()
+        );
+    }
+
+    #[test]
+    fn invalid_expressions_are_rejected() {
+        let parse = |expression: &str, parameters: &[String]| {
+            FilterExpression::parse(expression, parameters)
+                .unwrap_err()
+                .kind()
+        };
+        assert_eq!(ErrorKind::InvalidInput, parse("id =", &[]));
+        assert_eq!(ErrorKind::InvalidInput, parse("(id = 7", &[]));
+        assert_eq!(ErrorKind::InvalidInput, parse("id = 7 id", &[]));
+        assert_eq!(ErrorKind::InvalidInput, parse("name = 'abc", &[]));
+        assert_eq!(ErrorKind::InvalidInput, parse("id BETWEEN 1 OR 2", &[]));
+        assert_eq!(
+            ErrorKind::InvalidInput,
+            parse("id = %1", &["7".to_string()])
+        );
+        assert_eq!(
+            ErrorKind::InvalidInput,
+            parse("id = %0", &["id".to_string()])
+        );
+    }
+}
@@ -0,0 +1,20 @@
+use crate::dds::filter::filter_value::FilterValue;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Access to the fields of a sample evaluated by a FilterExpression.
+pub trait FilterFields {
+    /// The value of the field; the fields of nested structures are named
+    /// with dots, e.g. "position.x". None if the sample has no such field.
+    fn field(&self, name: &str) -> Option<FilterValue>;
+}
+
+impl FilterFields for BTreeMap<String, FilterValue> {
+    fn field(&self, name: &str) -> Option<FilterValue> {
+        self.get(name).cloned()
+    }
+}
+
+/// Deserializes the serialized data of a change into the fields of the
+/// sample, None if the data is not a valid sample.
+pub type SampleDecoder = Arc<dyn Fn(&[u8]) -> Option<Box<dyn FilterFields>> + Send + Sync>;
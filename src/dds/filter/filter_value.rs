@@ -0,0 +1,55 @@
+use std::cmp::Ordering;
+
+/// A value compared by a FilterExpression: a literal or parameter of the
+/// expression, or a field of the filtered sample.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FilterValue {
+    Integer(i64),
+    Float(f64),
+    String(String),
+    Boolean(bool),
+}
+
+impl FilterValue {
+    /// Compares values of the same kind; integers are compared with floats
+    /// as floats. Values of other kinds are not comparable.
+    pub fn compare(&self, other: &FilterValue) -> Option<Ordering> {
+        match (self, other) {
+            (FilterValue::Integer(first), FilterValue::Integer(second)) => Some(first.cmp(second)),
+            (FilterValue::Integer(first), FilterValue::Float(second)) => {
+                (*first as f64).partial_cmp(second)
+            }
+            (FilterValue::Float(first), FilterValue::Integer(second)) => {
+                first.partial_cmp(&(*second as f64))
+            }
+            (FilterValue::Float(first), FilterValue::Float(second)) => first.partial_cmp(second),
+            (FilterValue::String(first), FilterValue::String(second)) => Some(first.cmp(second)),
+            (FilterValue::Boolean(first), FilterValue::Boolean(second)) => Some(first.cmp(second)),
+            _ => None,
+        }
+    }
+}
+
+impl From<i64> for FilterValue {
+    fn from(value: i64) -> Self {
+        FilterValue::Integer(value)
+    }
+}
+
+impl From<f64> for FilterValue {
+    fn from(value: f64) -> Self {
+        FilterValue::Float(value)
+    }
+}
+
+impl From<&str> for FilterValue {
+    fn from(value: &str) -> Self {
+        FilterValue::String(value.to_string())
+    }
+}
+
+impl From<bool> for FilterValue {
+    fn from(value: bool) -> Self {
+        FilterValue::Boolean(value)
+    }
+}
@@ -0,0 +1,3 @@
+pub mod filter_expression;
+pub mod filter_fields;
+pub mod filter_value;
@@ -0,0 +1,16 @@
+/// The state of the instance as seen by the DataReader (2.2.2.5.1.6): alive,
+/// disposed by a DataWriter or not alive since no DataWriter writes it
+/// anymore.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InstanceStateKind {
+    ALIVE,
+    NOT_ALIVE_DISPOSED,
+    NOT_ALIVE_NO_WRITERS,
+}
+
+/// The samples of the instances in any instance state.
+pub const ANY_INSTANCE_STATE: &[InstanceStateKind] = &[
+    InstanceStateKind::ALIVE,
+    InstanceStateKind::NOT_ALIVE_DISPOSED,
+    InstanceStateKind::NOT_ALIVE_NO_WRITERS,
+];
@@ -0,0 +1,70 @@
+use crate::dds::data_reader::DataReader;
+use crate::dds::status::communication_status::CommunicationStatus;
+use crate::dds::status::deadline_missed_status::RequestedDeadlineMissedStatus;
+use crate::dds::status::incompatible_qos_status::RequestedIncompatibleQosStatus;
+use crate::dds::status::liveliness_changed_status::LivelinessChangedStatus;
+use crate::dds::status::sample_lost_status::SampleLostStatus;
+use crate::dds::status::sample_rejected_status::SampleRejectedStatus;
+use crate::dds::status::subscription_matched_status::SubscriptionMatchedStatus;
+
+/// Notified of the changes of the communication statuses of a DataReader
+/// (2.2.4.4.4), see DataReader::set_listener. The methods are called as the
+/// participant is driven, with the status read; they do nothing by default.
+pub trait DataReaderListener<T>: Send {
+    fn on_requested_deadline_missed(
+        &mut self,
+        _reader: &DataReader<T>,
+        _status: RequestedDeadlineMissedStatus,
+    ) {
+    }
+
+    fn on_requested_incompatible_qos(
+        &mut self,
+        _reader: &DataReader<T>,
+        _status: RequestedIncompatibleQosStatus,
+    ) {
+    }
+
+    fn on_sample_rejected(&mut self, _reader: &DataReader<T>, _status: SampleRejectedStatus) {}
+
+    fn on_liveliness_changed(&mut self, _reader: &DataReader<T>, _status: LivelinessChangedStatus) {
+    }
+
+    /// New samples are available to be read or taken.
+    fn on_data_available(&mut self, _reader: &DataReader<T>) {}
+
+    fn on_subscription_matched(
+        &mut self,
+        _reader: &DataReader<T>,
+        _status: SubscriptionMatchedStatus,
+    ) {
+    }
+
+    fn on_sample_lost(&mut self, _reader: &DataReader<T>, _status: SampleLostStatus) {}
+}
+
+/// Calls the method of the listener for the status of the reader.
+pub(crate) fn notify_data_reader_listener<T>(
+    listener: &mut dyn DataReaderListener<T>,
+    reader: &DataReader<T>,
+    status: CommunicationStatus,
+) {
+    match status {
+        CommunicationStatus::RequestedDeadlineMissed(status) => {
+            listener.on_requested_deadline_missed(reader, status)
+        }
+        CommunicationStatus::RequestedIncompatibleQos(status) => {
+            listener.on_requested_incompatible_qos(reader, status)
+        }
+        CommunicationStatus::SampleRejected(status) => listener.on_sample_rejected(reader, status),
+        CommunicationStatus::LivelinessChanged(status) => {
+            listener.on_liveliness_changed(reader, status)
+        }
+        CommunicationStatus::DataAvailable => listener.on_data_available(reader),
+        CommunicationStatus::SubscriptionMatched(status) => {
+            listener.on_subscription_matched(reader, status)
+        }
+        CommunicationStatus::SampleLost(status) => listener.on_sample_lost(reader, status),
+        _ => (),
+    }
+}
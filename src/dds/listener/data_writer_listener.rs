@@ -0,0 +1,55 @@
+use crate::dds::data_writer::DataWriter;
+use crate::dds::status::communication_status::CommunicationStatus;
+use crate::dds::status::deadline_missed_status::OfferedDeadlineMissedStatus;
+use crate::dds::status::incompatible_qos_status::OfferedIncompatibleQosStatus;
+use crate::dds::status::liveliness_lost_status::LivelinessLostStatus;
+use crate::dds::status::publication_matched_status::PublicationMatchedStatus;
+
+/// Notified of the changes of the communication statuses of a DataWriter
+/// (2.2.4.4.2), see DataWriter::set_listener. The methods are called as the
+/// participant is driven, with the status read; they do nothing by default.
+pub trait DataWriterListener<T>: Send {
+    fn on_liveliness_lost(&mut self, _writer: &DataWriter<T>, _status: LivelinessLostStatus) {}
+
+    fn on_offered_deadline_missed(
+        &mut self,
+        _writer: &DataWriter<T>,
+        _status: OfferedDeadlineMissedStatus,
+    ) {
+    }
+
+    fn on_offered_incompatible_qos(
+        &mut self,
+        _writer: &DataWriter<T>,
+        _status: OfferedIncompatibleQosStatus,
+    ) {
+    }
+
+    fn on_publication_matched(
+        &mut self,
+        _writer: &DataWriter<T>,
+        _status: PublicationMatchedStatus,
+    ) {
+    }
+}
+
+/// Calls the method of the listener for the status of the writer.
+pub(crate) fn notify_data_writer_listener<T>(
+    listener: &mut dyn DataWriterListener<T>,
+    writer: &DataWriter<T>,
+    status: CommunicationStatus,
+) {
+    match status {
+        CommunicationStatus::LivelinessLost(status) => listener.on_liveliness_lost(writer, status),
+        CommunicationStatus::OfferedDeadlineMissed(status) => {
+            listener.on_offered_deadline_missed(writer, status)
+        }
+        CommunicationStatus::OfferedIncompatibleQos(status) => {
+            listener.on_offered_incompatible_qos(writer, status)
+        }
+        CommunicationStatus::PublicationMatched(status) => {
+            listener.on_publication_matched(writer, status)
+        }
+        _ => (),
+    }
+}
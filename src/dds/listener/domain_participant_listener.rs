@@ -0,0 +1,7 @@
+use crate::dds::listener::publisher_listener::PublisherListener;
+use crate::dds::listener::subscriber_listener::SubscriberListener;
+
+/// Notified of the statuses of all the entities of a DomainParticipant for
+/// which neither the entity nor its Publisher or Subscriber has a listener
+/// (2.2.4.4.1), see DomainParticipant::set_listener.
+pub trait DomainParticipantListener: PublisherListener + SubscriberListener {}
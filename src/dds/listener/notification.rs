@@ -0,0 +1,63 @@
+use crate::dds::domain_participant::DomainParticipant;
+use crate::dds::listener::domain_participant_listener::DomainParticipantListener;
+use crate::dds::listener::publisher_listener::{notify_publisher_listener, PublisherListener};
+use crate::dds::listener::subscriber_listener::{notify_subscriber_listener, SubscriberListener};
+use crate::dds::status::communication_status::CommunicationStatus;
+use crate::structure::guid::GUID_t;
+
+use std::sync::{Arc, Mutex};
+
+/// The listener of a DataWriter or a DataReader of any type, calling the
+/// typed listener set with DataWriter::set_listener or
+/// DataReader::set_listener.
+pub(crate) type EndpointListener =
+    Arc<dyn Fn(&DomainParticipant, GUID_t, CommunicationStatus) + Send + Sync>;
+
+/// The listener of the DomainParticipant, a Publisher or a Subscriber.
+pub(crate) type SharedListener<L> = Arc<Mutex<Box<L>>>;
+
+/// The listener chosen for a changed status: the one of the entity, else
+/// the one of its Publisher or Subscriber, else the one of its
+/// DomainParticipant.
+pub(crate) enum Listener {
+    Endpoint(EndpointListener),
+    Publisher(SharedListener<dyn PublisherListener>),
+    Subscriber(SharedListener<dyn SubscriberListener>),
+    DomainParticipant(SharedListener<dyn DomainParticipantListener>),
+}
+
+/// The status of the entity read for its listener.
+pub(crate) struct Notification {
+    pub listener: Listener,
+    pub entity: GUID_t,
+    pub status: CommunicationStatus,
+}
+
+impl Notification {
+    /// Calls the listener with the status; the state of the participant
+    /// must not be locked, as the listener may call its entities.
+    pub fn notify(self, participant: &DomainParticipant) {
+        let Notification {
+            listener,
+            entity,
+            status,
+        } = self;
+        match listener {
+            Listener::Endpoint(listener) => listener(participant, entity, status),
+            Listener::Publisher(listener) => {
+                notify_publisher_listener(&mut **listener.lock().unwrap(), entity, status)
+            }
+            Listener::Subscriber(listener) => notify_subscriber_listener(
+                &mut **listener.lock().unwrap(),
+                participant,
+                entity,
+                status,
+            ),
+            Listener::DomainParticipant(listener) => {
+                let mut listener = listener.lock().unwrap();
+                notify_publisher_listener(&mut **listener, entity, status.clone());
+                notify_subscriber_listener(&mut **listener, participant, entity, status);
+            }
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use crate::dds::status::communication_status::CommunicationStatus;
+use crate::dds::status::deadline_missed_status::OfferedDeadlineMissedStatus;
+use crate::dds::status::incompatible_qos_status::OfferedIncompatibleQosStatus;
+use crate::dds::status::liveliness_lost_status::LivelinessLostStatus;
+use crate::dds::status::publication_matched_status::PublicationMatchedStatus;
+use crate::discovery::pl_cdr;
+use crate::structure::guid::GUID_t;
+use crate::structure::instance_handle::InstanceHandle_t;
+
+/// Notified of the statuses of the DataWriters of a Publisher without their
+/// own listener for the status (2.2.4.4.3), see Publisher::set_listener. The
+/// DataWriters of any type are identified by their instance handle.
+pub trait PublisherListener: Send {
+    fn on_liveliness_lost(&mut self, _writer: InstanceHandle_t, _status: LivelinessLostStatus) {}
+
+    fn on_offered_deadline_missed(
+        &mut self,
+        _writer: InstanceHandle_t,
+        _status: OfferedDeadlineMissedStatus,
+    ) {
+    }
+
+    fn on_offered_incompatible_qos(
+        &mut self,
+        _writer: InstanceHandle_t,
+        _status: OfferedIncompatibleQosStatus,
+    ) {
+    }
+
+    fn on_publication_matched(
+        &mut self,
+        _writer: InstanceHandle_t,
+        _status: PublicationMatchedStatus,
+    ) {
+    }
+}
+
+/// Calls the method of the listener for the status of the DataWriter.
+pub(crate) fn notify_publisher_listener<L: PublisherListener + ?Sized>(
+    listener: &mut L,
+    writer: GUID_t,
+    status: CommunicationStatus,
+) {
+    let writer = pl_cdr::key_hash(writer);
+    match status {
+        CommunicationStatus::LivelinessLost(status) => listener.on_liveliness_lost(writer, status),
+        CommunicationStatus::OfferedDeadlineMissed(status) => {
+            listener.on_offered_deadline_missed(writer, status)
+        }
+        CommunicationStatus::OfferedIncompatibleQos(status) => {
+            listener.on_offered_incompatible_qos(writer, status)
+        }
+        CommunicationStatus::PublicationMatched(status) => {
+            listener.on_publication_matched(writer, status)
+        }
+        _ => (),
+    }
+}
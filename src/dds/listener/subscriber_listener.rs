@@ -0,0 +1,87 @@
+use crate::dds::domain_participant::DomainParticipant;
+use crate::dds::status::communication_status::CommunicationStatus;
+use crate::dds::status::deadline_missed_status::RequestedDeadlineMissedStatus;
+use crate::dds::status::incompatible_qos_status::RequestedIncompatibleQosStatus;
+use crate::dds::status::liveliness_changed_status::LivelinessChangedStatus;
+use crate::dds::status::sample_lost_status::SampleLostStatus;
+use crate::dds::status::sample_rejected_status::SampleRejectedStatus;
+use crate::dds::status::subscription_matched_status::SubscriptionMatchedStatus;
+use crate::dds::subscriber::Subscriber;
+use crate::discovery::pl_cdr;
+use crate::structure::guid::GUID_t;
+use crate::structure::instance_handle::InstanceHandle_t;
+
+/// Notified of the statuses of the DataReaders of a Subscriber without their
+/// own listener for the status (2.2.4.4.5), see Subscriber::set_listener.
+/// The DataReaders of any type are identified by their instance handle.
+pub trait SubscriberListener: Send {
+    /// New samples are available in some of the DataReaders of the
+    /// Subscriber; on_data_available is not called for them.
+    fn on_data_on_readers(&mut self, _subscriber: &Subscriber) {}
+
+    fn on_requested_deadline_missed(
+        &mut self,
+        _reader: InstanceHandle_t,
+        _status: RequestedDeadlineMissedStatus,
+    ) {
+    }
+
+    fn on_requested_incompatible_qos(
+        &mut self,
+        _reader: InstanceHandle_t,
+        _status: RequestedIncompatibleQosStatus,
+    ) {
+    }
+
+    fn on_sample_rejected(&mut self, _reader: InstanceHandle_t, _status: SampleRejectedStatus) {}
+
+    fn on_liveliness_changed(
+        &mut self,
+        _reader: InstanceHandle_t,
+        _status: LivelinessChangedStatus,
+    ) {
+    }
+
+    fn on_data_available(&mut self, _reader: InstanceHandle_t) {}
+
+    fn on_subscription_matched(
+        &mut self,
+        _reader: InstanceHandle_t,
+        _status: SubscriptionMatchedStatus,
+    ) {
+    }
+
+    fn on_sample_lost(&mut self, _reader: InstanceHandle_t, _status: SampleLostStatus) {}
+}
+
+/// Calls the method of the listener for the status of the entity: the
+/// Subscriber for DATA_ON_READERS, a DataReader otherwise.
+pub(crate) fn notify_subscriber_listener<L: SubscriberListener + ?Sized>(
+    listener: &mut L,
+    participant: &DomainParticipant,
+    entity: GUID_t,
+    status: CommunicationStatus,
+) {
+    let reader = pl_cdr::key_hash(entity);
+    match status {
+        CommunicationStatus::DataOnReaders => {
+            listener.on_data_on_readers(&Subscriber::new(participant.clone(), entity))
+        }
+        CommunicationStatus::RequestedDeadlineMissed(status) => {
+            listener.on_requested_deadline_missed(reader, status)
+        }
+        CommunicationStatus::RequestedIncompatibleQos(status) => {
+            listener.on_requested_incompatible_qos(reader, status)
+        }
+        CommunicationStatus::SampleRejected(status) => listener.on_sample_rejected(reader, status),
+        CommunicationStatus::LivelinessChanged(status) => {
+            listener.on_liveliness_changed(reader, status)
+        }
+        CommunicationStatus::DataAvailable => listener.on_data_available(reader),
+        CommunicationStatus::SubscriptionMatched(status) => {
+            listener.on_subscription_matched(reader, status)
+        }
+        CommunicationStatus::SampleLost(status) => listener.on_sample_lost(reader, status),
+        _ => (),
+    }
+}
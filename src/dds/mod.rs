@@ -1 +1,21 @@
-
+pub mod cdr;
+pub mod condition;
+pub mod content_filtered_topic;
+pub mod data_reader;
+pub mod data_writer;
+pub mod domain_participant;
+pub mod domain_participant_factory;
+pub mod filter;
+pub mod instance_state_kind;
+pub mod listener;
+pub mod publisher;
+pub mod qos;
+pub mod sample;
+pub mod sample_info;
+pub mod sample_state_kind;
+pub mod status;
+pub mod subscriber;
+pub mod topic;
+pub mod topic_description;
+pub mod type_support;
+pub mod view_state_kind;
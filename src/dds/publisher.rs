@@ -0,0 +1,138 @@
+use crate::dds::condition::status_condition::StatusCondition;
+use crate::dds::data_writer::DataWriter;
+use crate::dds::domain_participant::{precondition_not_met, DomainParticipant};
+use crate::dds::listener::publisher_listener::PublisherListener;
+use crate::dds::qos::data_writer_qos::DataWriterQos;
+use crate::dds::qos::publisher_qos::PublisherQos;
+use crate::dds::status::status_kind::{StatusKind, StatusMask};
+use crate::dds::topic::Topic;
+use crate::dds::type_support::TypeSupport;
+use crate::discovery::pl_cdr;
+use crate::structure::guid::GUID_t;
+use crate::structure::instance_handle::InstanceHandle_t;
+
+use std::io::Error;
+
+/// The factory and the group of DataWriters (2.2.2.4.1), created by
+/// DomainParticipant::create_publisher. Its presentation and partition
+/// apply to all its DataWriters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Publisher {
+    participant: DomainParticipant,
+    guid: GUID_t,
+}
+
+impl Publisher {
+    pub(crate) fn new(participant: DomainParticipant, guid: GUID_t) -> Self {
+        Publisher { participant, guid }
+    }
+
+    pub fn guid(&self) -> GUID_t {
+        self.guid
+    }
+
+    pub fn get_instance_handle(&self) -> InstanceHandle_t {
+        pl_cdr::key_hash(self.guid)
+    }
+
+    pub fn get_participant(&self) -> DomainParticipant {
+        self.participant.clone()
+    }
+
+    pub fn get_qos(&self) -> Result<PublisherQos, Error> {
+        self.participant.publisher_qos(self.guid)
+    }
+
+    /// Replaces the QoS; the enabled DataWriters are announced again.
+    pub fn set_qos(&self, qos: PublisherQos) -> Result<(), Error> {
+        self.participant.set_publisher_qos(self.guid, qos)
+    }
+
+    /// Enables the Publisher of the enabled DomainParticipant and, with
+    /// autoenable_created_entities, its DataWriters.
+    pub fn enable(&self) -> Result<(), Error> {
+        self.participant.enable_publisher(self.guid)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.participant.is_publisher_enabled(self.guid)
+    }
+
+    /// Sets the listener of the statuses in the mask of the DataWriters
+    /// without a listener of their own for the status. The listener is
+    /// called as the participant is driven, after the operation updated the
+    /// statuses; it must not drive the participant itself.
+    pub fn set_listener(
+        &self,
+        listener: Option<Box<dyn PublisherListener>>,
+        mask: StatusMask,
+    ) -> Result<(), Error> {
+        self.participant
+            .set_publisher_listener(self.guid, listener, mask)
+    }
+
+    /// The StatusCondition triggered by the changes of the enabled statuses
+    /// of the Publisher.
+    pub fn get_statuscondition(&self) -> StatusCondition {
+        StatusCondition::new(self.participant.clone(), self.guid)
+    }
+
+    /// The statuses of the Publisher changed since they were last read.
+    pub fn get_status_changes(&self) -> Result<StatusKind, Error> {
+        self.participant.status_changes(self.guid)
+    }
+
+    /// Creates the DataWriter of the Topic of the type T created by the same
+    /// DomainParticipant. The DataWriter is enabled if the Publisher is
+    /// enabled and autoenable_created_entities is set.
+    pub fn create_datawriter<T: TypeSupport>(
+        &self,
+        topic: &Topic,
+        qos: DataWriterQos,
+    ) -> Result<DataWriter<T>, Error> {
+        let guid = self
+            .participant
+            .create_datawriter::<T>(self.guid, topic, qos)?;
+        Ok(DataWriter::new(self.participant.clone(), guid))
+    }
+
+    /// Deletes the DataWriter created by the Publisher.
+    pub fn delete_datawriter<T>(&self, writer: &DataWriter<T>) -> Result<(), Error> {
+        if writer.get_participant() != self.participant {
+            return Err(precondition_not_met(
+                "The DataWriter belongs to another Publisher",
+            ));
+        }
+        self.participant.delete_datawriter(self.guid, writer.guid())
+    }
+
+    /// A DataWriter of the Topic of the name created by the Publisher, if the
+    /// Topic has the type T.
+    pub fn lookup_datawriter<T: TypeSupport>(&self, topic_name: &str) -> Option<DataWriter<T>> {
+        let topic = self.participant.lookup_topicdescription(topic_name)?;
+        if topic.get_type_name().ok()? != T::TYPE_NAME {
+            return None;
+        }
+        self.participant
+            .datawriters(self.guid, Some(topic_name))
+            .into_iter()
+            .next()
+            .map(|guid| DataWriter::new(self.participant.clone(), guid))
+    }
+
+    /// Deletes all DataWriters created by the Publisher.
+    pub fn delete_contained_entities(&self) -> Result<(), Error> {
+        for writer in self.participant.datawriters(self.guid, None) {
+            self.participant.delete_datawriter(self.guid, writer)?;
+        }
+        Ok(())
+    }
+
+    pub fn get_default_datawriter_qos(&self) -> Result<DataWriterQos, Error> {
+        self.participant.default_datawriter_qos(self.guid)
+    }
+
+    pub fn set_default_datawriter_qos(&self, qos: DataWriterQos) -> Result<(), Error> {
+        self.participant.set_default_datawriter_qos(self.guid, qos)
+    }
+}
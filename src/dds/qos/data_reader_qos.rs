@@ -0,0 +1,123 @@
+use crate::dds::qos::deadline_qos_policy::DeadlineQosPolicy;
+use crate::dds::qos::destination_order_qos_policy::DestinationOrderQosPolicy;
+use crate::dds::qos::durability_qos_policy::DurabilityQosPolicy;
+use crate::dds::qos::history_qos_policy::HistoryQosPolicy;
+use crate::dds::qos::latency_budget_qos_policy::LatencyBudgetQosPolicy;
+use crate::dds::qos::liveliness_qos_policy::LivelinessQosPolicy;
+use crate::dds::qos::ownership_qos_policy::OwnershipQosPolicy;
+use crate::dds::qos::qos_policy_id::QosPolicyId_t;
+use crate::dds::qos::reader_data_lifecycle_qos_policy::ReaderDataLifecycleQosPolicy;
+use crate::dds::qos::reliability_qos_policy::ReliabilityQosPolicy;
+use crate::dds::qos::resource_limits_qos_policy::ResourceLimitsQosPolicy;
+use crate::dds::qos::time_based_filter_qos_policy::TimeBasedFilterQosPolicy;
+use crate::dds::qos::user_data_qos_policy::UserDataQosPolicy;
+
+/// The QoS of the DataReader, requested from the matched DataWriters.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DataReaderQos {
+    pub durability: DurabilityQosPolicy,
+    pub deadline: DeadlineQosPolicy,
+    pub latency_budget: LatencyBudgetQosPolicy,
+    pub liveliness: LivelinessQosPolicy,
+    pub reliability: ReliabilityQosPolicy,
+    pub destination_order: DestinationOrderQosPolicy,
+    pub history: HistoryQosPolicy,
+    pub resource_limits: ResourceLimitsQosPolicy,
+    pub user_data: UserDataQosPolicy,
+    pub ownership: OwnershipQosPolicy,
+    pub time_based_filter: TimeBasedFilterQosPolicy,
+    pub reader_data_lifecycle: ReaderDataLifecycleQosPolicy,
+}
+
+impl DataReaderQos {
+    /// Checks that the policies are consistent with each other, returns the
+    /// first inconsistent policy (INCONSISTENT_POLICY). The deadline period
+    /// must not be shorter than the minimum separation of the samples.
+    pub fn validate(&self) -> Result<(), QosPolicyId_t> {
+        if !self.resource_limits.is_consistent() {
+            return Err(QosPolicyId_t::RESOURCELIMITS_QOS_POLICY_ID);
+        }
+        if !self.history.is_consistent_with(&self.resource_limits) {
+            return Err(QosPolicyId_t::HISTORY_QOS_POLICY_ID);
+        }
+        if self.deadline.period < self.time_based_filter.minimum_separation {
+            return Err(QosPolicyId_t::TIMEBASEDFILTER_QOS_POLICY_ID);
+        }
+        Ok(())
+    }
+
+    /// Checks that the QoS of the enabled DataReader can be replaced by qos,
+    /// returns the first changed policy which cannot be changed once the
+    /// DataReader is enabled (IMMUTABLE_POLICY).
+    pub fn check_changeable(&self, qos: &DataReaderQos) -> Result<(), QosPolicyId_t> {
+        let immutable = [
+            (
+                self.durability == qos.durability,
+                QosPolicyId_t::DURABILITY_QOS_POLICY_ID,
+            ),
+            (
+                self.liveliness == qos.liveliness,
+                QosPolicyId_t::LIVELINESS_QOS_POLICY_ID,
+            ),
+            (
+                self.reliability == qos.reliability,
+                QosPolicyId_t::RELIABILITY_QOS_POLICY_ID,
+            ),
+            (
+                self.destination_order == qos.destination_order,
+                QosPolicyId_t::DESTINATIONORDER_QOS_POLICY_ID,
+            ),
+            (
+                self.history == qos.history,
+                QosPolicyId_t::HISTORY_QOS_POLICY_ID,
+            ),
+            (
+                self.resource_limits == qos.resource_limits,
+                QosPolicyId_t::RESOURCELIMITS_QOS_POLICY_ID,
+            ),
+            (
+                self.ownership == qos.ownership,
+                QosPolicyId_t::OWNERSHIP_QOS_POLICY_ID,
+            ),
+        ];
+        match immutable.iter().find(|(unchanged, _)| !unchanged) {
+            Some((_, policy_id)) => Err(*policy_id),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::duration::Duration_t;
+    use std::time::Duration;
+
+    #[test]
+    fn minimum_separation_must_not_exceed_deadline() {
+        let mut qos = DataReaderQos::default();
+        assert_eq!(Ok(()), qos.validate());
+
+        qos.deadline.period = Duration_t::from(Duration::from_secs(1));
+        qos.time_based_filter.minimum_separation = Duration_t::from(Duration::from_secs(2));
+        assert_eq!(
+            Err(QosPolicyId_t::TIMEBASEDFILTER_QOS_POLICY_ID),
+            qos.validate()
+        );
+    }
+
+    #[test]
+    fn changed_immutable_policy_is_reported() {
+        let qos = DataReaderQos::default();
+        let mut changed = qos.clone();
+        changed.deadline.period = Duration_t::from(Duration::from_secs(1));
+        changed.time_based_filter.minimum_separation = Duration_t::from(Duration::from_secs(1));
+        assert_eq!(Ok(()), qos.check_changeable(&changed));
+
+        changed.history.depth = 10;
+        assert_eq!(
+            Err(QosPolicyId_t::HISTORY_QOS_POLICY_ID),
+            qos.check_changeable(&changed)
+        );
+    }
+}
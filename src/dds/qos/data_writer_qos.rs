@@ -0,0 +1,166 @@
+use crate::dds::qos::deadline_qos_policy::DeadlineQosPolicy;
+use crate::dds::qos::destination_order_qos_policy::DestinationOrderQosPolicy;
+use crate::dds::qos::durability_qos_policy::DurabilityQosPolicy;
+use crate::dds::qos::durability_service_qos_policy::DurabilityServiceQosPolicy;
+use crate::dds::qos::history_qos_policy::HistoryQosPolicy;
+use crate::dds::qos::latency_budget_qos_policy::LatencyBudgetQosPolicy;
+use crate::dds::qos::lifespan_qos_policy::LifespanQosPolicy;
+use crate::dds::qos::liveliness_qos_policy::LivelinessQosPolicy;
+use crate::dds::qos::ownership_qos_policy::OwnershipQosPolicy;
+use crate::dds::qos::ownership_strength_qos_policy::OwnershipStrengthQosPolicy;
+use crate::dds::qos::qos_policy_id::QosPolicyId_t;
+use crate::dds::qos::reliability_qos_policy::ReliabilityQosPolicy;
+use crate::dds::qos::resource_limits_qos_policy::ResourceLimitsQosPolicy;
+use crate::dds::qos::transport_priority_qos_policy::TransportPriorityQosPolicy;
+use crate::dds::qos::user_data_qos_policy::UserDataQosPolicy;
+use crate::dds::qos::writer_data_lifecycle_qos_policy::WriterDataLifecycleQosPolicy;
+
+/// The QoS of the DataWriter, offered to the matched DataReaders.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DataWriterQos {
+    pub durability: DurabilityQosPolicy,
+    pub durability_service: DurabilityServiceQosPolicy,
+    pub deadline: DeadlineQosPolicy,
+    pub latency_budget: LatencyBudgetQosPolicy,
+    pub liveliness: LivelinessQosPolicy,
+    pub reliability: ReliabilityQosPolicy,
+    pub destination_order: DestinationOrderQosPolicy,
+    pub history: HistoryQosPolicy,
+    pub resource_limits: ResourceLimitsQosPolicy,
+    pub transport_priority: TransportPriorityQosPolicy,
+    pub lifespan: LifespanQosPolicy,
+    pub user_data: UserDataQosPolicy,
+    pub ownership: OwnershipQosPolicy,
+    pub ownership_strength: OwnershipStrengthQosPolicy,
+    pub writer_data_lifecycle: WriterDataLifecycleQosPolicy,
+}
+
+impl Default for DataWriterQos {
+    fn default() -> Self {
+        DataWriterQos {
+            durability: DurabilityQosPolicy::default(),
+            durability_service: DurabilityServiceQosPolicy::default(),
+            deadline: DeadlineQosPolicy::default(),
+            latency_budget: LatencyBudgetQosPolicy::default(),
+            liveliness: LivelinessQosPolicy::default(),
+            reliability: ReliabilityQosPolicy::reliable(),
+            destination_order: DestinationOrderQosPolicy::default(),
+            history: HistoryQosPolicy::default(),
+            resource_limits: ResourceLimitsQosPolicy::default(),
+            transport_priority: TransportPriorityQosPolicy::default(),
+            lifespan: LifespanQosPolicy::default(),
+            user_data: UserDataQosPolicy::default(),
+            ownership: OwnershipQosPolicy::default(),
+            ownership_strength: OwnershipStrengthQosPolicy::default(),
+            writer_data_lifecycle: WriterDataLifecycleQosPolicy::default(),
+        }
+    }
+}
+
+impl DataWriterQos {
+    /// Checks that the policies are consistent with each other, returns the
+    /// first inconsistent policy (INCONSISTENT_POLICY).
+    pub fn validate(&self) -> Result<(), QosPolicyId_t> {
+        if !self.resource_limits.is_consistent() {
+            return Err(QosPolicyId_t::RESOURCELIMITS_QOS_POLICY_ID);
+        }
+        if !self.history.is_consistent_with(&self.resource_limits) {
+            return Err(QosPolicyId_t::HISTORY_QOS_POLICY_ID);
+        }
+        if !self.durability_service.is_consistent() {
+            return Err(QosPolicyId_t::DURABILITYSERVICE_QOS_POLICY_ID);
+        }
+        Ok(())
+    }
+
+    /// Checks that the QoS of the enabled DataWriter can be replaced by qos,
+    /// returns the first changed policy which cannot be changed once the
+    /// DataWriter is enabled (IMMUTABLE_POLICY).
+    pub fn check_changeable(&self, qos: &DataWriterQos) -> Result<(), QosPolicyId_t> {
+        let immutable = [
+            (
+                self.durability == qos.durability,
+                QosPolicyId_t::DURABILITY_QOS_POLICY_ID,
+            ),
+            (
+                self.durability_service == qos.durability_service,
+                QosPolicyId_t::DURABILITYSERVICE_QOS_POLICY_ID,
+            ),
+            (
+                self.liveliness == qos.liveliness,
+                QosPolicyId_t::LIVELINESS_QOS_POLICY_ID,
+            ),
+            (
+                self.reliability == qos.reliability,
+                QosPolicyId_t::RELIABILITY_QOS_POLICY_ID,
+            ),
+            (
+                self.destination_order == qos.destination_order,
+                QosPolicyId_t::DESTINATIONORDER_QOS_POLICY_ID,
+            ),
+            (
+                self.history == qos.history,
+                QosPolicyId_t::HISTORY_QOS_POLICY_ID,
+            ),
+            (
+                self.resource_limits == qos.resource_limits,
+                QosPolicyId_t::RESOURCELIMITS_QOS_POLICY_ID,
+            ),
+            (
+                self.ownership == qos.ownership,
+                QosPolicyId_t::OWNERSHIP_QOS_POLICY_ID,
+            ),
+        ];
+        match immutable.iter().find(|(unchanged, _)| !unchanged) {
+            Some((_, policy_id)) => Err(*policy_id),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dds::qos::history_qos_policy::HistoryQosPolicyKind;
+    use crate::structure::duration::Duration_t;
+    use std::time::Duration;
+
+    #[test]
+    fn default_qos_is_consistent() {
+        assert_eq!(Ok(()), DataWriterQos::default().validate());
+    }
+
+    #[test]
+    fn inconsistent_policy_is_reported() {
+        let mut qos = DataWriterQos {
+            history: HistoryQosPolicy {
+                kind: HistoryQosPolicyKind::KEEP_LAST,
+                depth: 10,
+            },
+            ..DataWriterQos::default()
+        };
+        qos.resource_limits.max_samples_per_instance = 5;
+        assert_eq!(Err(QosPolicyId_t::HISTORY_QOS_POLICY_ID), qos.validate());
+
+        qos.resource_limits.max_samples = 4;
+        assert_eq!(
+            Err(QosPolicyId_t::RESOURCELIMITS_QOS_POLICY_ID),
+            qos.validate()
+        );
+    }
+
+    #[test]
+    fn changed_immutable_policy_is_reported() {
+        let qos = DataWriterQos::default();
+        let mut changed = qos.clone();
+        changed.deadline.period = Duration_t::from(Duration::from_secs(1));
+        changed.ownership_strength.value = 1;
+        assert_eq!(Ok(()), qos.check_changeable(&changed));
+
+        changed.reliability = ReliabilityQosPolicy::default();
+        assert_eq!(
+            Err(QosPolicyId_t::RELIABILITY_QOS_POLICY_ID),
+            qos.check_changeable(&changed)
+        );
+    }
+}
@@ -0,0 +1,31 @@
+use crate::structure::duration::Duration_t;
+use speedy::{Readable, Writable};
+
+/// The DataReader expects a new sample updating the value of each instance
+/// at least once every deadline period. The DataWriter commits to write a
+/// new value for each instance every deadline period.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Readable, Writable)]
+pub struct DeadlineQosPolicy {
+    pub period: Duration_t,
+}
+
+impl Default for DeadlineQosPolicy {
+    fn default() -> Self {
+        DeadlineQosPolicy {
+            period: Duration_t::DURATION_INFINITE,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    serialization_test!( type = DeadlineQosPolicy,
+    {
+        deadline_infinite,
+        DeadlineQosPolicy::default(),
+        le = [0xFF, 0xFF, 0xFF, 0x7F, 0xFF, 0xFF, 0xFF, 0xFF],
+        be = [0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]
+    });
+}
@@ -0,0 +1,40 @@
+use speedy::{Readable, Writable};
+
+/// The kinds are ordered; the offered kind must be at least the requested
+/// one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Readable, Writable)]
+#[speedy(tag_type = u32)]
+pub enum DestinationOrderQosPolicyKind {
+    BY_RECEPTION_TIMESTAMP = 0,
+    BY_SOURCE_TIMESTAMP = 1,
+}
+
+/// Controls the criteria used to determine the logical order among changes
+/// made by Publisher entities to the same instance of data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Readable, Writable)]
+pub struct DestinationOrderQosPolicy {
+    pub kind: DestinationOrderQosPolicyKind,
+}
+
+impl Default for DestinationOrderQosPolicy {
+    fn default() -> Self {
+        DestinationOrderQosPolicy {
+            kind: DestinationOrderQosPolicyKind::BY_RECEPTION_TIMESTAMP,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    serialization_test!( type = DestinationOrderQosPolicy,
+    {
+        destination_order_by_source_timestamp,
+        DestinationOrderQosPolicy {
+            kind: DestinationOrderQosPolicyKind::BY_SOURCE_TIMESTAMP
+        },
+        le = [0x01, 0x00, 0x00, 0x00],
+        be = [0x00, 0x00, 0x00, 0x01]
+    });
+}
@@ -0,0 +1,7 @@
+use crate::dds::qos::entity_factory_qos_policy::EntityFactoryQosPolicy;
+
+/// The QoS of the DomainParticipantFactory.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DomainParticipantFactoryQos {
+    pub entity_factory: EntityFactoryQosPolicy,
+}
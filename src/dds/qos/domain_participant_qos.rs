@@ -0,0 +1,9 @@
+use crate::dds::qos::entity_factory_qos_policy::EntityFactoryQosPolicy;
+use crate::dds::qos::user_data_qos_policy::UserDataQosPolicy;
+
+/// The QoS of the DomainParticipant.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DomainParticipantQos {
+    pub user_data: UserDataQosPolicy,
+    pub entity_factory: EntityFactoryQosPolicy,
+}
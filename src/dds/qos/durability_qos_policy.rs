@@ -0,0 +1,41 @@
+use speedy::{Readable, Writable};
+
+/// The kinds are ordered from the weakest to the strongest durability; the
+/// offered kind must be at least the requested one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Readable, Writable)]
+#[speedy(tag_type = u32)]
+pub enum DurabilityQosPolicyKind {
+    VOLATILE = 0,
+    TRANSIENT_LOCAL = 1,
+    TRANSIENT = 2,
+    PERSISTENT = 3,
+}
+
+/// Expresses if the data should ‘outlive’ its writing time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Readable, Writable)]
+pub struct DurabilityQosPolicy {
+    pub kind: DurabilityQosPolicyKind,
+}
+
+impl Default for DurabilityQosPolicy {
+    fn default() -> Self {
+        DurabilityQosPolicy {
+            kind: DurabilityQosPolicyKind::VOLATILE,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    serialization_test!( type = DurabilityQosPolicy,
+    {
+        durability_transient_local,
+        DurabilityQosPolicy {
+            kind: DurabilityQosPolicyKind::TRANSIENT_LOCAL
+        },
+        le = [0x01, 0x00, 0x00, 0x00],
+        be = [0x00, 0x00, 0x00, 0x01]
+    });
+}
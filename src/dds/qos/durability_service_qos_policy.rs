@@ -0,0 +1,95 @@
+use crate::dds::qos::history_qos_policy::{HistoryQosPolicy, HistoryQosPolicyKind};
+use crate::dds::qos::resource_limits_qos_policy::{ResourceLimitsQosPolicy, LENGTH_UNLIMITED};
+use crate::structure::duration::Duration_t;
+use speedy::{Readable, Writable};
+
+/// Specifies the configuration of the durability service, the service
+/// keeping the samples of the TRANSIENT and PERSISTENT DataWriters for the
+/// late-joining DataReaders.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Readable, Writable)]
+pub struct DurabilityServiceQosPolicy {
+    /// How long the service keeps the information of an instance after it
+    /// was disposed and unregistered by all DataWriters.
+    pub service_cleanup_delay: Duration_t,
+    pub history_kind: HistoryQosPolicyKind,
+    pub history_depth: i32,
+    pub max_samples: i32,
+    pub max_instances: i32,
+    pub max_samples_per_instance: i32,
+}
+
+impl Default for DurabilityServiceQosPolicy {
+    fn default() -> Self {
+        DurabilityServiceQosPolicy {
+            service_cleanup_delay: Duration_t::DURATION_ZERO,
+            history_kind: HistoryQosPolicyKind::KEEP_LAST,
+            history_depth: 1,
+            max_samples: LENGTH_UNLIMITED,
+            max_instances: LENGTH_UNLIMITED,
+            max_samples_per_instance: LENGTH_UNLIMITED,
+        }
+    }
+}
+
+impl DurabilityServiceQosPolicy {
+    pub fn history(&self) -> HistoryQosPolicy {
+        HistoryQosPolicy {
+            kind: self.history_kind,
+            depth: self.history_depth,
+        }
+    }
+
+    pub fn resource_limits(&self) -> ResourceLimitsQosPolicy {
+        ResourceLimitsQosPolicy {
+            max_samples: self.max_samples,
+            max_instances: self.max_instances,
+            max_samples_per_instance: self.max_samples_per_instance,
+        }
+    }
+
+    /// Whether the history and the resource limits of the service are
+    /// consistent, like the HistoryQosPolicy and ResourceLimitsQosPolicy of
+    /// an entity.
+    pub fn is_consistent(&self) -> bool {
+        let resource_limits = self.resource_limits();
+        resource_limits.is_consistent() && self.history().is_consistent_with(&resource_limits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    serialization_test!( type = DurabilityServiceQosPolicy,
+    {
+        durability_service_default,
+        DurabilityServiceQosPolicy::default(),
+        le = [
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x01, 0x00, 0x00, 0x00,
+            0xFF, 0xFF, 0xFF, 0xFF,
+            0xFF, 0xFF, 0xFF, 0xFF,
+            0xFF, 0xFF, 0xFF, 0xFF
+        ],
+        be = [
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x01,
+            0xFF, 0xFF, 0xFF, 0xFF,
+            0xFF, 0xFF, 0xFF, 0xFF,
+            0xFF, 0xFF, 0xFF, 0xFF
+        ]
+    });
+
+    #[test]
+    fn history_depth_must_fit_the_samples_per_instance() {
+        let mut policy = DurabilityServiceQosPolicy::default();
+        assert!(policy.is_consistent());
+        policy.history_depth = 5;
+        policy.max_samples_per_instance = 4;
+        assert!(!policy.is_consistent());
+    }
+}
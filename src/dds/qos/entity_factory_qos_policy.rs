@@ -0,0 +1,14 @@
+/// Controls the behavior of the Entity when acting as a factory for other
+/// entities: whether the created entities are enabled automatically.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EntityFactoryQosPolicy {
+    pub autoenable_created_entities: bool,
+}
+
+impl Default for EntityFactoryQosPolicy {
+    fn default() -> Self {
+        EntityFactoryQosPolicy {
+            autoenable_created_entities: true,
+        }
+    }
+}
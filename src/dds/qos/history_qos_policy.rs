@@ -0,0 +1,87 @@
+use crate::dds::qos::resource_limits_qos_policy::{ResourceLimitsQosPolicy, LENGTH_UNLIMITED};
+use speedy::{Readable, Writable};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Readable, Writable)]
+#[speedy(tag_type = u32)]
+pub enum HistoryQosPolicyKind {
+    KEEP_LAST = 0,
+    KEEP_ALL = 1,
+}
+
+/// Specifies the behavior of the Service when the value of an instance
+/// changes before it is delivered: KEEP_LAST keeps only the depth most
+/// recent samples of each instance, KEEP_ALL keeps all samples within the
+/// ResourceLimitsQosPolicy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Readable, Writable)]
+pub struct HistoryQosPolicy {
+    pub kind: HistoryQosPolicyKind,
+
+    /// The number of samples kept of each instance; used by KEEP_LAST only.
+    pub depth: i32,
+}
+
+impl Default for HistoryQosPolicy {
+    fn default() -> Self {
+        HistoryQosPolicy {
+            kind: HistoryQosPolicyKind::KEEP_LAST,
+            depth: 1,
+        }
+    }
+}
+
+impl HistoryQosPolicy {
+    /// Whether the positive depth of KEEP_LAST fits the samples per instance
+    /// of the resource limits.
+    pub fn is_consistent_with(&self, resource_limits: &ResourceLimitsQosPolicy) -> bool {
+        match self.kind {
+            HistoryQosPolicyKind::KEEP_LAST => {
+                self.depth > 0
+                    && (resource_limits.max_samples_per_instance == LENGTH_UNLIMITED
+                        || self.depth <= resource_limits.max_samples_per_instance)
+            }
+            HistoryQosPolicyKind::KEEP_ALL => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    serialization_test!( type = HistoryQosPolicy,
+    {
+        history_keep_all,
+        HistoryQosPolicy {
+            kind: HistoryQosPolicyKind::KEEP_ALL,
+            depth: 1,
+        },
+        le = [
+            0x01, 0x00, 0x00, 0x00,
+            0x01, 0x00, 0x00, 0x00
+        ],
+        be = [
+            0x00, 0x00, 0x00, 0x01,
+            0x00, 0x00, 0x00, 0x01
+        ]
+    });
+
+    #[test]
+    fn keep_last_depth_must_fit_the_samples_per_instance() {
+        let mut resource_limits = ResourceLimitsQosPolicy::default();
+        let keep_last = |depth| HistoryQosPolicy {
+            kind: HistoryQosPolicyKind::KEEP_LAST,
+            depth,
+        };
+        assert!(keep_last(5).is_consistent_with(&resource_limits));
+        assert!(!keep_last(0).is_consistent_with(&resource_limits));
+
+        resource_limits.max_samples_per_instance = 4;
+        assert!(!keep_last(5).is_consistent_with(&resource_limits));
+        assert!(keep_last(4).is_consistent_with(&resource_limits));
+        assert!(HistoryQosPolicy {
+            kind: HistoryQosPolicyKind::KEEP_ALL,
+            depth: 5,
+        }
+        .is_consistent_with(&resource_limits));
+    }
+}
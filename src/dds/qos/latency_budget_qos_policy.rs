@@ -0,0 +1,33 @@
+use crate::structure::duration::Duration_t;
+use speedy::{Readable, Writable};
+
+/// A hint of the maximum acceptable delay from the time the data is written
+/// to the time it is received by the subscribing application. The offered
+/// duration must be at most the requested one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Readable, Writable)]
+pub struct LatencyBudgetQosPolicy {
+    pub duration: Duration_t,
+}
+
+impl Default for LatencyBudgetQosPolicy {
+    fn default() -> Self {
+        LatencyBudgetQosPolicy {
+            duration: Duration_t::DURATION_ZERO,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    serialization_test!( type = LatencyBudgetQosPolicy,
+    {
+        latency_budget_infinite,
+        LatencyBudgetQosPolicy {
+            duration: Duration_t::DURATION_INFINITE,
+        },
+        le = [0xFF, 0xFF, 0xFF, 0x7F, 0xFF, 0xFF, 0xFF, 0xFF],
+        be = [0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]
+    });
+}
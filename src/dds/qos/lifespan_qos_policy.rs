@@ -0,0 +1,32 @@
+use crate::structure::duration::Duration_t;
+use speedy::{Readable, Writable};
+
+/// Specifies how long the data written by the DataWriter is valid; the
+/// expired samples are removed from the histories.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Readable, Writable)]
+pub struct LifespanQosPolicy {
+    pub duration: Duration_t,
+}
+
+impl Default for LifespanQosPolicy {
+    fn default() -> Self {
+        LifespanQosPolicy {
+            duration: Duration_t::DURATION_INFINITE,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    serialization_test!( type = LifespanQosPolicy,
+    {
+        lifespan_zero,
+        LifespanQosPolicy {
+            duration: Duration_t::DURATION_ZERO,
+        },
+        le = [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        be = [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]
+    });
+}
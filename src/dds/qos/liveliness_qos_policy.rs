@@ -0,0 +1,53 @@
+use crate::structure::duration::Duration_t;
+use speedy::{Readable, Writable};
+
+/// The kinds are ordered from the weakest to the strongest liveliness; the
+/// offered kind must be at least the requested one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Readable, Writable)]
+#[speedy(tag_type = u32)]
+pub enum LivelinessQosPolicyKind {
+    AUTOMATIC = 0,
+    MANUAL_BY_PARTICIPANT = 1,
+    MANUAL_BY_TOPIC = 2,
+}
+
+/// Determines the mechanism and parameters used by the application to
+/// determine whether an Entity is “active” (alive).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Readable, Writable)]
+pub struct LivelinessQosPolicy {
+    pub kind: LivelinessQosPolicyKind,
+    pub lease_duration: Duration_t,
+}
+
+impl Default for LivelinessQosPolicy {
+    fn default() -> Self {
+        LivelinessQosPolicy {
+            kind: LivelinessQosPolicyKind::AUTOMATIC,
+            lease_duration: Duration_t::DURATION_INFINITE,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    serialization_test!( type = LivelinessQosPolicy,
+    {
+        liveliness_manual_by_topic,
+        LivelinessQosPolicy {
+            kind: LivelinessQosPolicyKind::MANUAL_BY_TOPIC,
+            lease_duration: Duration_t::DURATION_ZERO,
+        },
+        le = [
+            0x02, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00
+        ],
+        be = [
+            0x00, 0x00, 0x00, 0x02,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00
+        ]
+    });
+}
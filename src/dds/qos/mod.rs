@@ -0,0 +1,30 @@
+pub mod data_reader_qos;
+pub mod data_writer_qos;
+pub mod deadline_qos_policy;
+pub mod destination_order_qos_policy;
+pub mod domain_participant_factory_qos;
+pub mod domain_participant_qos;
+pub mod durability_qos_policy;
+pub mod durability_service_qos_policy;
+pub mod entity_factory_qos_policy;
+pub mod group_data_qos_policy;
+pub mod history_qos_policy;
+pub mod latency_budget_qos_policy;
+pub mod lifespan_qos_policy;
+pub mod liveliness_qos_policy;
+pub mod ownership_qos_policy;
+pub mod ownership_strength_qos_policy;
+pub mod partition_qos_policy;
+pub mod presentation_qos_policy;
+pub mod publisher_qos;
+pub mod qos_policy_id;
+pub mod reader_data_lifecycle_qos_policy;
+pub mod reliability_qos_policy;
+pub mod resource_limits_qos_policy;
+pub mod subscriber_qos;
+pub mod time_based_filter_qos_policy;
+pub mod topic_data_qos_policy;
+pub mod topic_qos;
+pub mod transport_priority_qos_policy;
+pub mod user_data_qos_policy;
+pub mod writer_data_lifecycle_qos_policy;
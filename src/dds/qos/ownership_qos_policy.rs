@@ -0,0 +1,39 @@
+use speedy::{Readable, Writable};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Readable, Writable)]
+#[speedy(tag_type = u32)]
+pub enum OwnershipQosPolicyKind {
+    SHARED = 0,
+    EXCLUSIVE = 1,
+}
+
+/// Specifies whether it is allowed for multiple DataWriters to write the same
+/// instance of the data and if so, how these modifications should be
+/// arbitrated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Readable, Writable)]
+pub struct OwnershipQosPolicy {
+    pub kind: OwnershipQosPolicyKind,
+}
+
+impl Default for OwnershipQosPolicy {
+    fn default() -> Self {
+        OwnershipQosPolicy {
+            kind: OwnershipQosPolicyKind::SHARED,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    serialization_test!( type = OwnershipQosPolicy,
+    {
+        ownership_exclusive,
+        OwnershipQosPolicy {
+            kind: OwnershipQosPolicyKind::EXCLUSIVE
+        },
+        le = [0x01, 0x00, 0x00, 0x00],
+        be = [0x00, 0x00, 0x00, 0x01]
+    });
+}
@@ -0,0 +1,21 @@
+use speedy::{Readable, Writable};
+
+/// The strength of the DataWriter arbitrating the EXCLUSIVE ownership of
+/// the instances; the DataWriter of the greatest value owns the instance.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Readable, Writable)]
+pub struct OwnershipStrengthQosPolicy {
+    pub value: i32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    serialization_test!( type = OwnershipStrengthQosPolicy,
+    {
+        ownership_strength_ten,
+        OwnershipStrengthQosPolicy { value: 10 },
+        le = [0x0A, 0x00, 0x00, 0x00],
+        be = [0x00, 0x00, 0x00, 0x0A]
+    });
+}
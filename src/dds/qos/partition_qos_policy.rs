@@ -0,0 +1,81 @@
+/// Introduces a logical partition among the topics visible by the Publisher
+/// and Subscriber. A DataWriter and a DataReader only communicate when they
+/// share a partition; no names stand for the default partition "".
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PartitionQosPolicy {
+    pub name: Vec<String>,
+}
+
+impl PartitionQosPolicy {
+    /// Whether the policies share a partition. The names may contain the
+    /// wildcards '*' and '?' of fnmatch; two names that both contain
+    /// wildcards do not match unless they are equal.
+    pub fn matches(&self, other: &PartitionQosPolicy) -> bool {
+        self.names()
+            .any(|name| other.names().any(|other| names_match(name, other)))
+    }
+
+    fn names(&self) -> impl Iterator<Item = &str> + Clone {
+        let default: &[&str] = if self.name.is_empty() { &[""] } else { &[] };
+        self.name
+            .iter()
+            .map(String::as_str)
+            .chain(default.iter().copied())
+    }
+}
+
+fn names_match(first: &str, second: &str) -> bool {
+    match (is_pattern(first), is_pattern(second)) {
+        _ if first == second => true,
+        (true, false) => pattern_matches(first.as_bytes(), second.as_bytes()),
+        (false, true) => pattern_matches(second.as_bytes(), first.as_bytes()),
+        _ => false,
+    }
+}
+
+fn is_pattern(name: &str) -> bool {
+    name.contains(['*', '?'])
+}
+
+fn pattern_matches(pattern: &[u8], name: &[u8]) -> bool {
+    match (pattern.split_first(), name.split_first()) {
+        (None, _) => name.is_empty(),
+        (Some((b'*', rest)), _) => {
+            (0..=name.len()).any(|skipped| pattern_matches(rest, &name[skipped..]))
+        }
+        (Some((b'?', rest)), Some((_, name_rest))) => pattern_matches(rest, name_rest),
+        (Some((c, rest)), Some((n, name_rest))) => c == n && pattern_matches(rest, name_rest),
+        (Some(_), None) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn partition(names: &[&str]) -> PartitionQosPolicy {
+        PartitionQosPolicy {
+            name: names.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn default_partition_matches_only_empty_name() {
+        assert!(partition(&[]).matches(&partition(&[])));
+        assert!(partition(&[]).matches(&partition(&["", "sensors"])));
+        assert!(!partition(&[]).matches(&partition(&["sensors"])));
+        assert!(partition(&[]).matches(&partition(&["*"])));
+    }
+
+    #[test]
+    fn names_match_with_wildcards() {
+        assert!(partition(&["sensors"]).matches(&partition(&["actuators", "sensors"])));
+        assert!(partition(&["sensors/*"]).matches(&partition(&["sensors/front"])));
+        assert!(partition(&["sensor?"]).matches(&partition(&["sensors"])));
+        assert!(!partition(&["sensor?"]).matches(&partition(&["sensor"])));
+        assert!(!partition(&["sensors/*"]).matches(&partition(&["actuators/front"])));
+        // Two patterns only match when equal
+        assert!(!partition(&["sensors/*"]).matches(&partition(&["sensors/?"])));
+        assert!(partition(&["sensors/*"]).matches(&partition(&["sensors/*"])));
+    }
+}
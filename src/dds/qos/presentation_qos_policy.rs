@@ -0,0 +1,47 @@
+use speedy::{Readable, Writable};
+
+/// The scopes are ordered; the offered scope must be at least the requested
+/// one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Readable, Writable)]
+#[speedy(tag_type = u32)]
+pub enum PresentationQosPolicyAccessScopeKind {
+    INSTANCE = 0,
+    TOPIC = 1,
+    GROUP = 2,
+}
+
+/// Specifies how the samples representing changes to data instances are
+/// presented to the subscribing application.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Readable, Writable)]
+pub struct PresentationQosPolicy {
+    pub access_scope: PresentationQosPolicyAccessScopeKind,
+    pub coherent_access: bool,
+    pub ordered_access: bool,
+}
+
+impl Default for PresentationQosPolicy {
+    fn default() -> Self {
+        PresentationQosPolicy {
+            access_scope: PresentationQosPolicyAccessScopeKind::INSTANCE,
+            coherent_access: false,
+            ordered_access: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    serialization_test!( type = PresentationQosPolicy,
+    {
+        presentation_topic_ordered,
+        PresentationQosPolicy {
+            access_scope: PresentationQosPolicyAccessScopeKind::TOPIC,
+            coherent_access: false,
+            ordered_access: true,
+        },
+        le = [0x01, 0x00, 0x00, 0x00, 0x00, 0x01],
+        be = [0x00, 0x00, 0x00, 0x01, 0x00, 0x01]
+    });
+}
@@ -0,0 +1,67 @@
+use std::fmt;
+
+/// Identifies a QoS policy, e.g. the one that caused an incompatibility
+/// between an offered and a requested QoS.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum QosPolicyId_t {
+    INVALID_QOS_POLICY_ID = 0,
+    USERDATA_QOS_POLICY_ID = 1,
+    DURABILITY_QOS_POLICY_ID = 2,
+    PRESENTATION_QOS_POLICY_ID = 3,
+    DEADLINE_QOS_POLICY_ID = 4,
+    LATENCYBUDGET_QOS_POLICY_ID = 5,
+    OWNERSHIP_QOS_POLICY_ID = 6,
+    OWNERSHIPSTRENGTH_QOS_POLICY_ID = 7,
+    LIVELINESS_QOS_POLICY_ID = 8,
+    TIMEBASEDFILTER_QOS_POLICY_ID = 9,
+    PARTITION_QOS_POLICY_ID = 10,
+    RELIABILITY_QOS_POLICY_ID = 11,
+    DESTINATIONORDER_QOS_POLICY_ID = 12,
+    HISTORY_QOS_POLICY_ID = 13,
+    RESOURCELIMITS_QOS_POLICY_ID = 14,
+    ENTITYFACTORY_QOS_POLICY_ID = 15,
+    WRITERDATALIFECYCLE_QOS_POLICY_ID = 16,
+    READERDATALIFECYCLE_QOS_POLICY_ID = 17,
+    TOPICDATA_QOS_POLICY_ID = 18,
+    GROUPDATA_QOS_POLICY_ID = 19,
+    TRANSPORTPRIORITY_QOS_POLICY_ID = 20,
+    LIFESPAN_QOS_POLICY_ID = 21,
+    DURABILITYSERVICE_QOS_POLICY_ID = 22,
+}
+
+impl QosPolicyId_t {
+    /// The name of the policy defined by the DDS specification.
+    pub fn name(self) -> &'static str {
+        match self {
+            QosPolicyId_t::INVALID_QOS_POLICY_ID => "Invalid",
+            QosPolicyId_t::USERDATA_QOS_POLICY_ID => "UserData",
+            QosPolicyId_t::DURABILITY_QOS_POLICY_ID => "Durability",
+            QosPolicyId_t::PRESENTATION_QOS_POLICY_ID => "Presentation",
+            QosPolicyId_t::DEADLINE_QOS_POLICY_ID => "Deadline",
+            QosPolicyId_t::LATENCYBUDGET_QOS_POLICY_ID => "LatencyBudget",
+            QosPolicyId_t::OWNERSHIP_QOS_POLICY_ID => "Ownership",
+            QosPolicyId_t::OWNERSHIPSTRENGTH_QOS_POLICY_ID => "OwnershipStrength",
+            QosPolicyId_t::LIVELINESS_QOS_POLICY_ID => "Liveliness",
+            QosPolicyId_t::TIMEBASEDFILTER_QOS_POLICY_ID => "TimeBasedFilter",
+            QosPolicyId_t::PARTITION_QOS_POLICY_ID => "Partition",
+            QosPolicyId_t::RELIABILITY_QOS_POLICY_ID => "Reliability",
+            QosPolicyId_t::DESTINATIONORDER_QOS_POLICY_ID => "DestinationOrder",
+            QosPolicyId_t::HISTORY_QOS_POLICY_ID => "History",
+            QosPolicyId_t::RESOURCELIMITS_QOS_POLICY_ID => "ResourceLimits",
+            QosPolicyId_t::ENTITYFACTORY_QOS_POLICY_ID => "EntityFactory",
+            QosPolicyId_t::WRITERDATALIFECYCLE_QOS_POLICY_ID => "WriterDataLifecycle",
+            QosPolicyId_t::READERDATALIFECYCLE_QOS_POLICY_ID => "ReaderDataLifecycle",
+            QosPolicyId_t::TOPICDATA_QOS_POLICY_ID => "TopicData",
+            QosPolicyId_t::GROUPDATA_QOS_POLICY_ID => "GroupData",
+            QosPolicyId_t::TRANSPORTPRIORITY_QOS_POLICY_ID => "TransportPriority",
+            QosPolicyId_t::LIFESPAN_QOS_POLICY_ID => "Lifespan",
+            QosPolicyId_t::DURABILITYSERVICE_QOS_POLICY_ID => "DurabilityService",
+        }
+    }
+}
+
+impl fmt::Display for QosPolicyId_t {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
@@ -0,0 +1,20 @@
+use crate::structure::duration::Duration_t;
+
+/// Specifies how long the DataReader keeps the instances without
+/// DataWriters and the disposed instances after they became not alive;
+/// their samples not taken by then are dropped. Local to the DataReader, it
+/// is not announced.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReaderDataLifecycleQosPolicy {
+    pub autopurge_nowriter_samples_delay: Duration_t,
+    pub autopurge_disposed_samples_delay: Duration_t,
+}
+
+impl Default for ReaderDataLifecycleQosPolicy {
+    fn default() -> Self {
+        ReaderDataLifecycleQosPolicy {
+            autopurge_nowriter_samples_delay: Duration_t::DURATION_INFINITE,
+            autopurge_disposed_samples_delay: Duration_t::DURATION_INFINITE,
+        }
+    }
+}
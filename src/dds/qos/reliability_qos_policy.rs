@@ -0,0 +1,67 @@
+use crate::structure::duration::Duration_t;
+use speedy::{Readable, Writable};
+use std::time::Duration;
+
+/// The kinds are ordered from the weakest to the strongest reliability; the
+/// offered kind must be at least the requested one. The values are the ones
+/// of the ReliabilityKind_t on the wire.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Readable, Writable)]
+#[speedy(tag_type = u32)]
+pub enum ReliabilityQosPolicyKind {
+    BEST_EFFORT = 1,
+    RELIABLE = 2,
+}
+
+/// Indicates the level of reliability offered/requested by the Service.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Readable, Writable)]
+pub struct ReliabilityQosPolicy {
+    pub kind: ReliabilityQosPolicyKind,
+
+    /// The maximum time the write operation may block when the resources
+    /// are exhausted; used by the reliable DataWriter only.
+    pub max_blocking_time: Duration_t,
+}
+
+impl ReliabilityQosPolicy {
+    /// The default of the DataWriter.
+    pub fn reliable() -> Self {
+        ReliabilityQosPolicy {
+            kind: ReliabilityQosPolicyKind::RELIABLE,
+            max_blocking_time: Duration_t::from(Duration::from_millis(100)),
+        }
+    }
+}
+
+/// The default of the DataReader and the Topic.
+impl Default for ReliabilityQosPolicy {
+    fn default() -> Self {
+        ReliabilityQosPolicy {
+            kind: ReliabilityQosPolicyKind::BEST_EFFORT,
+            max_blocking_time: Duration_t::from(Duration::from_millis(100)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    serialization_test!( type = ReliabilityQosPolicy,
+    {
+        reliability_reliable,
+        ReliabilityQosPolicy {
+            kind: ReliabilityQosPolicyKind::RELIABLE,
+            max_blocking_time: Duration_t::DURATION_ZERO,
+        },
+        le = [
+            0x02, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00
+        ],
+        be = [
+            0x00, 0x00, 0x00, 0x02,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00
+        ]
+    });
+}
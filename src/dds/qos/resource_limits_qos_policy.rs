@@ -0,0 +1,79 @@
+use speedy::{Readable, Writable};
+
+/// The value of the limits that are not limited.
+pub const LENGTH_UNLIMITED: i32 = -1;
+
+/// Specifies the resources the Service can consume to meet the requested
+/// QoS: the samples kept in the history of the entity, in total and per
+/// instance, and the number of instances.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Readable, Writable)]
+pub struct ResourceLimitsQosPolicy {
+    pub max_samples: i32,
+    pub max_instances: i32,
+    pub max_samples_per_instance: i32,
+}
+
+impl Default for ResourceLimitsQosPolicy {
+    fn default() -> Self {
+        ResourceLimitsQosPolicy {
+            max_samples: LENGTH_UNLIMITED,
+            max_instances: LENGTH_UNLIMITED,
+            max_samples_per_instance: LENGTH_UNLIMITED,
+        }
+    }
+}
+
+impl ResourceLimitsQosPolicy {
+    /// Whether the limits are positive or unlimited and the limited samples
+    /// per instance do not exceed the limited samples.
+    pub fn is_consistent(&self) -> bool {
+        let is_valid = |limit| limit == LENGTH_UNLIMITED || limit > 0;
+        is_valid(self.max_samples)
+            && is_valid(self.max_instances)
+            && is_valid(self.max_samples_per_instance)
+            && (self.max_samples == LENGTH_UNLIMITED
+                || self.max_samples_per_instance == LENGTH_UNLIMITED
+                || self.max_samples_per_instance <= self.max_samples)
+    }
+
+    /// Whether the count reached the limit.
+    pub(crate) fn is_reached(limit: i32, count: usize) -> bool {
+        limit != LENGTH_UNLIMITED && count >= limit as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    serialization_test!( type = ResourceLimitsQosPolicy,
+    {
+        resource_limits_unlimited,
+        ResourceLimitsQosPolicy::default(),
+        le = [
+            0xFF, 0xFF, 0xFF, 0xFF,
+            0xFF, 0xFF, 0xFF, 0xFF,
+            0xFF, 0xFF, 0xFF, 0xFF
+        ],
+        be = [
+            0xFF, 0xFF, 0xFF, 0xFF,
+            0xFF, 0xFF, 0xFF, 0xFF,
+            0xFF, 0xFF, 0xFF, 0xFF
+        ]
+    });
+
+    #[test]
+    fn samples_per_instance_must_not_exceed_samples() {
+        let resource_limits = |max_samples, max_samples_per_instance| ResourceLimitsQosPolicy {
+            max_samples,
+            max_instances: LENGTH_UNLIMITED,
+            max_samples_per_instance,
+        };
+        assert!(resource_limits(LENGTH_UNLIMITED, LENGTH_UNLIMITED).is_consistent());
+        assert!(resource_limits(10, 5).is_consistent());
+        assert!(resource_limits(LENGTH_UNLIMITED, 5).is_consistent());
+        assert!(resource_limits(10, LENGTH_UNLIMITED).is_consistent());
+        assert!(!resource_limits(4, 5).is_consistent());
+        assert!(!resource_limits(0, LENGTH_UNLIMITED).is_consistent());
+    }
+}
@@ -0,0 +1,14 @@
+use crate::dds::qos::entity_factory_qos_policy::EntityFactoryQosPolicy;
+use crate::dds::qos::group_data_qos_policy::GroupDataQosPolicy;
+use crate::dds::qos::partition_qos_policy::PartitionQosPolicy;
+use crate::dds::qos::presentation_qos_policy::PresentationQosPolicy;
+
+/// The QoS of the Subscriber, the presentation and the partition apply to
+/// all its DataReaders.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SubscriberQos {
+    pub presentation: PresentationQosPolicy,
+    pub partition: PartitionQosPolicy,
+    pub group_data: GroupDataQosPolicy,
+    pub entity_factory: EntityFactoryQosPolicy,
+}
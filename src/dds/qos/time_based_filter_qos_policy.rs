@@ -0,0 +1,32 @@
+use crate::structure::duration::Duration_t;
+use speedy::{Readable, Writable};
+
+/// The DataReader is interested in at most one sample of each instance per
+/// minimum_separation; the zero separation delivers all samples.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Readable, Writable)]
+pub struct TimeBasedFilterQosPolicy {
+    pub minimum_separation: Duration_t,
+}
+
+impl Default for TimeBasedFilterQosPolicy {
+    fn default() -> Self {
+        TimeBasedFilterQosPolicy {
+            minimum_separation: Duration_t::DURATION_ZERO,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    serialization_test!( type = TimeBasedFilterQosPolicy,
+    {
+        time_based_filter_one_second,
+        TimeBasedFilterQosPolicy {
+            minimum_separation: Duration_t::from(std::time::Duration::from_secs(1)),
+        },
+        le = [0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        be = [0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00]
+    });
+}
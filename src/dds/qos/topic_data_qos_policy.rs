@@ -0,0 +1,24 @@
+use speedy::{Readable, Writable};
+
+/// Data not interpreted by the Service, attached by the application to the
+/// Topic and announced by the discovery with its DataWriters and
+/// DataReaders.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Readable, Writable)]
+pub struct TopicDataQosPolicy {
+    pub value: Vec<u8>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    serialization_test!( type = TopicDataQosPolicy,
+    {
+        topic_data_abc,
+        TopicDataQosPolicy {
+            value: vec![0x61, 0x62, 0x63],
+        },
+        le = [0x03, 0x00, 0x00, 0x00, 0x61, 0x62, 0x63],
+        be = [0x00, 0x00, 0x00, 0x03, 0x61, 0x62, 0x63]
+    });
+}
@@ -0,0 +1,49 @@
+use crate::dds::qos::deadline_qos_policy::DeadlineQosPolicy;
+use crate::dds::qos::destination_order_qos_policy::DestinationOrderQosPolicy;
+use crate::dds::qos::durability_qos_policy::DurabilityQosPolicy;
+use crate::dds::qos::durability_service_qos_policy::DurabilityServiceQosPolicy;
+use crate::dds::qos::history_qos_policy::HistoryQosPolicy;
+use crate::dds::qos::latency_budget_qos_policy::LatencyBudgetQosPolicy;
+use crate::dds::qos::lifespan_qos_policy::LifespanQosPolicy;
+use crate::dds::qos::liveliness_qos_policy::LivelinessQosPolicy;
+use crate::dds::qos::ownership_qos_policy::OwnershipQosPolicy;
+use crate::dds::qos::qos_policy_id::QosPolicyId_t;
+use crate::dds::qos::reliability_qos_policy::ReliabilityQosPolicy;
+use crate::dds::qos::resource_limits_qos_policy::ResourceLimitsQosPolicy;
+use crate::dds::qos::topic_data_qos_policy::TopicDataQosPolicy;
+use crate::dds::qos::transport_priority_qos_policy::TransportPriorityQosPolicy;
+
+/// The QoS of the Topic.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TopicQos {
+    pub topic_data: TopicDataQosPolicy,
+    pub durability: DurabilityQosPolicy,
+    pub durability_service: DurabilityServiceQosPolicy,
+    pub deadline: DeadlineQosPolicy,
+    pub latency_budget: LatencyBudgetQosPolicy,
+    pub liveliness: LivelinessQosPolicy,
+    pub reliability: ReliabilityQosPolicy,
+    pub destination_order: DestinationOrderQosPolicy,
+    pub history: HistoryQosPolicy,
+    pub resource_limits: ResourceLimitsQosPolicy,
+    pub transport_priority: TransportPriorityQosPolicy,
+    pub lifespan: LifespanQosPolicy,
+    pub ownership: OwnershipQosPolicy,
+}
+
+impl TopicQos {
+    /// Checks that the policies are consistent with each other, returns the
+    /// first inconsistent policy (INCONSISTENT_POLICY).
+    pub fn validate(&self) -> Result<(), QosPolicyId_t> {
+        if !self.resource_limits.is_consistent() {
+            return Err(QosPolicyId_t::RESOURCELIMITS_QOS_POLICY_ID);
+        }
+        if !self.history.is_consistent_with(&self.resource_limits) {
+            return Err(QosPolicyId_t::HISTORY_QOS_POLICY_ID);
+        }
+        if !self.durability_service.is_consistent() {
+            return Err(QosPolicyId_t::DURABILITYSERVICE_QOS_POLICY_ID);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,21 @@
+use speedy::{Readable, Writable};
+
+/// A hint of the priority of the data written by the DataWriter to the
+/// transports; the greater value is the higher priority.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Readable, Writable)]
+pub struct TransportPriorityQosPolicy {
+    pub value: i32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    serialization_test!( type = TransportPriorityQosPolicy,
+    {
+        transport_priority_one,
+        TransportPriorityQosPolicy { value: 1 },
+        le = [0x01, 0x00, 0x00, 0x00],
+        be = [0x00, 0x00, 0x00, 0x01]
+    });
+}
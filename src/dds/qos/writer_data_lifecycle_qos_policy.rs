@@ -0,0 +1,14 @@
+/// Specifies whether the DataWriter disposes the instances it unregisters.
+/// Local to the DataWriter, it is not announced.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WriterDataLifecycleQosPolicy {
+    pub autodispose_unregistered_instances: bool,
+}
+
+impl Default for WriterDataLifecycleQosPolicy {
+    fn default() -> Self {
+        WriterDataLifecycleQosPolicy {
+            autodispose_unregistered_instances: true,
+        }
+    }
+}
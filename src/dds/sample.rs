@@ -0,0 +1,10 @@
+use crate::dds::sample_info::SampleInfo;
+
+/// A sample returned by the read and take operations of the DataReader: the
+/// data together with its SampleInfo. The data is only present when
+/// info.valid_data is set.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sample<T> {
+    pub data: Option<T>,
+    pub info: SampleInfo,
+}
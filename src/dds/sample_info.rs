@@ -0,0 +1,34 @@
+use crate::dds::instance_state_kind::InstanceStateKind;
+use crate::dds::sample_state_kind::SampleStateKind;
+use crate::dds::view_state_kind::ViewStateKind;
+use crate::structure::instance_handle::InstanceHandle_t;
+use crate::structure::time::Time_t;
+
+/// The information accompanying each sample returned by the read and take
+/// operations of the DataReader (2.2.2.5.5).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SampleInfo {
+    pub sample_state: SampleStateKind,
+    pub view_state: ViewStateKind,
+    pub instance_state: InstanceStateKind,
+
+    /// The times the instance became ALIVE again after being disposed and
+    /// after having no DataWriters, before the sample was received.
+    pub disposed_generation_count: i32,
+    pub no_writers_generation_count: i32,
+
+    /// The time set by the DataWriter when the sample was written,
+    /// TIME_INVALID if the DataWriter did not provide it.
+    pub source_timestamp: Time_t,
+
+    /// Identifies the instance of the sample locally.
+    pub instance_handle: InstanceHandle_t,
+
+    /// Identifies the DataWriter that wrote the sample locally, it is the
+    /// instance handle of the matched publication.
+    pub publication_handle: InstanceHandle_t,
+
+    /// Whether the sample carries data; the samples without data only
+    /// inform about the change of the instance_state.
+    pub valid_data: bool,
+}
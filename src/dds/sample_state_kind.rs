@@ -0,0 +1,11 @@
+/// Whether the sample was already returned by a read operation of the
+/// DataReader (2.2.2.5.1.4).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SampleStateKind {
+    READ,
+    NOT_READ,
+}
+
+/// The samples in any sample state.
+pub const ANY_SAMPLE_STATE: &[SampleStateKind] =
+    &[SampleStateKind::READ, SampleStateKind::NOT_READ];
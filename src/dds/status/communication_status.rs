@@ -0,0 +1,30 @@
+use crate::dds::status::deadline_missed_status::{
+    OfferedDeadlineMissedStatus, RequestedDeadlineMissedStatus,
+};
+use crate::dds::status::incompatible_qos_status::{
+    OfferedIncompatibleQosStatus, RequestedIncompatibleQosStatus,
+};
+use crate::dds::status::liveliness_changed_status::LivelinessChangedStatus;
+use crate::dds::status::liveliness_lost_status::LivelinessLostStatus;
+use crate::dds::status::publication_matched_status::PublicationMatchedStatus;
+use crate::dds::status::sample_lost_status::SampleLostStatus;
+use crate::dds::status::sample_rejected_status::SampleRejectedStatus;
+use crate::dds::status::subscription_matched_status::SubscriptionMatchedStatus;
+
+/// A changed status of an entity as read for its listener; the statuses
+/// without a value only signal the arrival of data.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum CommunicationStatus {
+    OfferedDeadlineMissed(OfferedDeadlineMissedStatus),
+    RequestedDeadlineMissed(RequestedDeadlineMissedStatus),
+    OfferedIncompatibleQos(OfferedIncompatibleQosStatus),
+    RequestedIncompatibleQos(RequestedIncompatibleQosStatus),
+    SampleLost(SampleLostStatus),
+    SampleRejected(SampleRejectedStatus),
+    DataOnReaders,
+    DataAvailable,
+    LivelinessLost(LivelinessLostStatus),
+    LivelinessChanged(LivelinessChangedStatus),
+    PublicationMatched(PublicationMatchedStatus),
+    SubscriptionMatched(SubscriptionMatchedStatus),
+}
@@ -0,0 +1,39 @@
+use crate::structure::instance_handle::InstanceHandle_t;
+
+/// The status of a DataWriter that failed to write a sample of an instance
+/// within the period of its DeadlineQosPolicy.
+pub type OfferedDeadlineMissedStatus = DeadlineMissedStatus;
+
+/// The status of a DataReader that did not receive a sample of an instance
+/// within the period of its DeadlineQosPolicy.
+pub type RequestedDeadlineMissedStatus = DeadlineMissedStatus;
+
+/// The OFFERED_DEADLINE_MISSED and REQUESTED_DEADLINE_MISSED statuses.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DeadlineMissedStatus {
+    /// Total cumulative number of missed deadlines, counted for each
+    /// instance and each period.
+    pub total_count: i32,
+
+    /// The change in total_count since the last time the status was read.
+    pub total_count_change: i32,
+
+    /// Handle to the last instance which missed its deadline.
+    pub last_instance_handle: InstanceHandle_t,
+}
+
+impl DeadlineMissedStatus {
+    pub fn increment(&mut self, instance_handle: InstanceHandle_t) {
+        self.total_count += 1;
+        self.total_count_change += 1;
+        self.last_instance_handle = instance_handle;
+    }
+
+    /// Returns the status and resets its total_count_change, like reading
+    /// the status through the Entity.
+    pub fn read(&mut self) -> DeadlineMissedStatus {
+        let status = *self;
+        self.total_count_change = 0;
+        status
+    }
+}
@@ -0,0 +1,76 @@
+use crate::dds::status::status_kind::{StatusKind, StatusMask};
+
+/// The communication statuses of an entity, see StatusCondition.
+#[derive(Debug)]
+pub(crate) struct EntityStatuses {
+    /// The statuses changed since they were last read.
+    changed: StatusKind,
+
+    /// The statuses triggering the StatusCondition of the entity.
+    enabled: StatusMask,
+}
+
+impl Default for EntityStatuses {
+    fn default() -> Self {
+        EntityStatuses {
+            changed: StatusKind::empty(),
+            enabled: StatusKind::all(),
+        }
+    }
+}
+
+impl EntityStatuses {
+    pub fn changed(&self) -> StatusKind {
+        self.changed
+    }
+
+    pub fn is_changed(&self, kind: StatusKind) -> bool {
+        self.changed.contains(kind)
+    }
+
+    /// Marks the status as changed until it is read.
+    pub fn change(&mut self, kind: StatusKind) {
+        self.changed.insert(kind);
+    }
+
+    /// Marks the status as read.
+    pub fn read(&mut self, kind: StatusKind) {
+        self.changed.remove(kind);
+    }
+
+    pub fn enabled(&self) -> StatusMask {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, mask: StatusMask) {
+        self.enabled = mask;
+    }
+
+    /// Whether the StatusCondition of the entity is triggered: one of its
+    /// enabled statuses changed since it was last read.
+    pub fn is_triggered(&self) -> bool {
+        self.changed.intersects(self.enabled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enabled_statuses_trigger_until_read() {
+        let mut statuses = EntityStatuses::default();
+        assert!(!statuses.is_triggered());
+
+        statuses.set_enabled(StatusKind::DATA_AVAILABLE);
+        statuses.change(StatusKind::SAMPLE_LOST);
+        assert!(statuses.is_changed(StatusKind::SAMPLE_LOST));
+        assert!(!statuses.is_triggered());
+
+        statuses.change(StatusKind::DATA_AVAILABLE);
+        assert!(statuses.is_triggered());
+        statuses.read(StatusKind::DATA_AVAILABLE);
+        assert!(!statuses.is_triggered());
+        assert_eq!(StatusKind::SAMPLE_LOST, statuses.changed());
+    }
+}
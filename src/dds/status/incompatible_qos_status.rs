@@ -0,0 +1,107 @@
+use crate::dds::qos::qos_policy_id::QosPolicyId_t;
+use crate::dds::status::qos_policy_count::QosPolicyCount;
+
+/// The status of a DataWriter that found a DataReader with the same Topic
+/// requesting a QoS incompatible with the offered one.
+pub type OfferedIncompatibleQosStatus = IncompatibleQosStatus;
+
+/// The status of a DataReader that found a DataWriter with the same Topic
+/// offering a QoS incompatible with the requested one.
+pub type RequestedIncompatibleQosStatus = IncompatibleQosStatus;
+
+/// The OFFERED_INCOMPATIBLE_QOS and REQUESTED_INCOMPATIBLE_QOS statuses.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IncompatibleQosStatus {
+    /// Total cumulative number of remote Entities found incompatible.
+    pub total_count: i32,
+
+    /// The change in total_count since the last time the status was read.
+    pub total_count_change: i32,
+
+    /// The id of one of the policies found incompatible the last time an
+    /// incompatibility was detected.
+    pub last_policy_id: QosPolicyId_t,
+
+    /// For each policy, the total number of times it was found
+    /// incompatible.
+    pub policies: Vec<QosPolicyCount>,
+}
+
+impl Default for IncompatibleQosStatus {
+    fn default() -> Self {
+        IncompatibleQosStatus {
+            total_count: 0,
+            total_count_change: 0,
+            last_policy_id: QosPolicyId_t::INVALID_QOS_POLICY_ID,
+            policies: vec![],
+        }
+    }
+}
+
+impl IncompatibleQosStatus {
+    /// Records a remote Entity found incompatible because of the policies.
+    pub fn increment(&mut self, policy_ids: &[QosPolicyId_t]) {
+        let last_policy_id = match policy_ids.first() {
+            Some(policy_id) => *policy_id,
+            None => return,
+        };
+        self.total_count += 1;
+        self.total_count_change += 1;
+        self.last_policy_id = last_policy_id;
+        for policy_id in policy_ids {
+            match self
+                .policies
+                .iter_mut()
+                .find(|policy_count| policy_count.policy_id == *policy_id)
+            {
+                Some(policy_count) => policy_count.count += 1,
+                None => self.policies.push(QosPolicyCount {
+                    policy_id: *policy_id,
+                    count: 1,
+                }),
+            }
+        }
+    }
+
+    /// Returns the status and resets its total_count_change, like reading
+    /// the status through the Entity.
+    pub fn read(&mut self) -> IncompatibleQosStatus {
+        let status = self.clone();
+        self.total_count_change = 0;
+        status
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policies_are_counted_per_incompatibility() {
+        let mut status = IncompatibleQosStatus::default();
+        status.increment(&[
+            QosPolicyId_t::RELIABILITY_QOS_POLICY_ID,
+            QosPolicyId_t::DURABILITY_QOS_POLICY_ID,
+        ]);
+        status.increment(&[QosPolicyId_t::DURABILITY_QOS_POLICY_ID]);
+
+        let read = status.read();
+        assert_eq!(2, read.total_count);
+        assert_eq!(2, read.total_count_change);
+        assert_eq!(QosPolicyId_t::DURABILITY_QOS_POLICY_ID, read.last_policy_id);
+        assert_eq!(
+            vec![
+                QosPolicyCount {
+                    policy_id: QosPolicyId_t::RELIABILITY_QOS_POLICY_ID,
+                    count: 1,
+                },
+                QosPolicyCount {
+                    policy_id: QosPolicyId_t::DURABILITY_QOS_POLICY_ID,
+                    count: 2,
+                },
+            ],
+            read.policies
+        );
+        assert_eq!(0, status.read().total_count_change);
+    }
+}
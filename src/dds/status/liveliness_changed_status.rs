@@ -0,0 +1,51 @@
+use crate::structure::instance_handle::InstanceHandle_t;
+
+/// The LIVELINESS_CHANGED status of a DataReader: the liveliness of the
+/// DataWriters matched with it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LivelinessChangedStatus {
+    /// The total number of currently active DataWriters that write the
+    /// Topic read by the DataReader.
+    pub alive_count: i32,
+
+    /// The total count of matched DataWriters that are no longer asserting
+    /// their liveliness.
+    pub not_alive_count: i32,
+
+    /// The change in alive_count since the last time the status was read.
+    pub alive_count_change: i32,
+
+    /// The change in not_alive_count since the last time the status was
+    /// read.
+    pub not_alive_count_change: i32,
+
+    /// Handle to the last DataWriter whose change in liveliness caused this
+    /// status to change.
+    pub last_publication_handle: InstanceHandle_t,
+}
+
+impl LivelinessChangedStatus {
+    /// Records a change in the liveliness of the DataWriter identified by
+    /// publication_handle: the counts are changed by the given amounts.
+    pub fn change(
+        &mut self,
+        publication_handle: InstanceHandle_t,
+        alive_count_change: i32,
+        not_alive_count_change: i32,
+    ) {
+        self.alive_count += alive_count_change;
+        self.not_alive_count += not_alive_count_change;
+        self.alive_count_change += alive_count_change;
+        self.not_alive_count_change += not_alive_count_change;
+        self.last_publication_handle = publication_handle;
+    }
+
+    /// Returns the status and resets its changes, like reading the status
+    /// through the Entity.
+    pub fn read(&mut self) -> LivelinessChangedStatus {
+        let status = *self;
+        self.alive_count_change = 0;
+        self.not_alive_count_change = 0;
+        status
+    }
+}
@@ -0,0 +1,26 @@
+/// The LIVELINESS_LOST status of a DataWriter.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LivelinessLostStatus {
+    /// Total cumulative number of times that a previously-alive DataWriter
+    /// became not alive due to a failure to actively signal its liveliness
+    /// within its offered liveliness period.
+    pub total_count: i32,
+
+    /// The change in total_count since the last time the status was read.
+    pub total_count_change: i32,
+}
+
+impl LivelinessLostStatus {
+    pub fn increment(&mut self) {
+        self.total_count += 1;
+        self.total_count_change += 1;
+    }
+
+    /// Returns the status and resets its total_count_change, like reading
+    /// the status through the Entity.
+    pub fn read(&mut self) -> LivelinessLostStatus {
+        let status = *self;
+        self.total_count_change = 0;
+        status
+    }
+}
@@ -0,0 +1,13 @@
+pub mod communication_status;
+pub mod deadline_missed_status;
+pub mod entity_statuses;
+pub mod incompatible_qos_status;
+pub mod liveliness_changed_status;
+pub mod liveliness_lost_status;
+pub mod publication_matched_status;
+pub mod qos_policy_count;
+pub mod sample_lost_status;
+pub mod sample_rejected_status;
+pub mod sample_rejected_status_kind;
+pub mod status_kind;
+pub mod subscription_matched_status;
@@ -0,0 +1,81 @@
+use crate::structure::instance_handle::InstanceHandle_t;
+
+/// The PUBLICATION_MATCHED status of a DataWriter: the DataReaders found
+/// with a matching Topic and a compatible QoS.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PublicationMatchedStatus {
+    /// Total cumulative count of the DataReaders matched with the
+    /// DataWriter.
+    pub total_count: i32,
+
+    /// The change in total_count since the last time the status was read.
+    pub total_count_change: i32,
+
+    /// The number of DataReaders currently matched with the DataWriter.
+    pub current_count: i32,
+
+    /// The change in current_count since the last time the status was read.
+    pub current_count_change: i32,
+
+    /// Handle to the last DataReader matched with or unmatched from the
+    /// DataWriter.
+    pub last_subscription_handle: InstanceHandle_t,
+}
+
+impl PublicationMatchedStatus {
+    /// Records the DataReader identified by subscription_handle being
+    /// matched, or unmatched if matched is false.
+    pub fn change(&mut self, subscription_handle: InstanceHandle_t, matched: bool) {
+        if matched {
+            self.total_count += 1;
+            self.total_count_change += 1;
+            self.current_count += 1;
+            self.current_count_change += 1;
+        } else {
+            self.current_count -= 1;
+            self.current_count_change -= 1;
+        }
+        self.last_subscription_handle = subscription_handle;
+    }
+
+    /// Returns the status and resets its changes, like reading the status
+    /// through the Entity.
+    pub fn read(&mut self) -> PublicationMatchedStatus {
+        let status = *self;
+        self.total_count_change = 0;
+        self.current_count_change = 0;
+        status
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unmatched_readers_are_only_removed_from_the_current_count() {
+        let mut status = PublicationMatchedStatus::default();
+        let handle = InstanceHandle_t {
+            entity_key: [0x01; 16],
+        };
+        status.change(InstanceHandle_t::HANDLE_NIL, true);
+        status.change(handle, true);
+        status.change(InstanceHandle_t::HANDLE_NIL, false);
+
+        assert_eq!(
+            PublicationMatchedStatus {
+                total_count: 2,
+                total_count_change: 2,
+                current_count: 1,
+                current_count_change: 1,
+                last_subscription_handle: InstanceHandle_t::HANDLE_NIL,
+            },
+            status.read()
+        );
+        status.change(handle, false);
+        let read = status.read();
+        assert_eq!((2, 0), (read.total_count, read.total_count_change));
+        assert_eq!((0, -1), (read.current_count, read.current_count_change));
+        assert_eq!(handle, read.last_subscription_handle);
+    }
+}
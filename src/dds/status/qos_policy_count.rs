@@ -0,0 +1,8 @@
+use crate::dds::qos::qos_policy_id::QosPolicyId_t;
+
+/// The number of times the policy was found incompatible.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QosPolicyCount {
+    pub policy_id: QosPolicyId_t,
+    pub count: i32,
+}
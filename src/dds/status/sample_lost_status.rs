@@ -0,0 +1,26 @@
+/// The SAMPLE_LOST status of a DataReader: the samples of the matched
+/// DataWriters that were never received.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SampleLostStatus {
+    /// Total cumulative count of all samples lost across all instances of
+    /// the data published under the Topic.
+    pub total_count: i32,
+
+    /// The change in total_count since the last time the status was read.
+    pub total_count_change: i32,
+}
+
+impl SampleLostStatus {
+    pub fn increment(&mut self, count: i32) {
+        self.total_count += count;
+        self.total_count_change += count;
+    }
+
+    /// Returns the status and resets its total_count_change, like reading
+    /// the status through the Entity.
+    pub fn read(&mut self) -> SampleLostStatus {
+        let status = *self;
+        self.total_count_change = 0;
+        status
+    }
+}
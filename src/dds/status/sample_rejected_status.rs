@@ -0,0 +1,41 @@
+use crate::dds::status::sample_rejected_status_kind::SampleRejectedStatusKind;
+use crate::structure::instance_handle::InstanceHandle_t;
+
+/// The SAMPLE_REJECTED status of a DataReader: the received samples
+/// rejected because of its ResourceLimitsQosPolicy.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SampleRejectedStatus {
+    /// Total cumulative count of samples rejected by the DataReader.
+    pub total_count: i32,
+
+    /// The change in total_count since the last time the status was read.
+    pub total_count_change: i32,
+
+    /// The reason for rejecting the last sample rejected.
+    pub last_reason: SampleRejectedStatusKind,
+
+    /// Handle to the instance of the last sample rejected.
+    pub last_instance_handle: InstanceHandle_t,
+}
+
+impl SampleRejectedStatus {
+    /// Records a sample of the instance rejected for the reason.
+    pub fn increment(
+        &mut self,
+        reason: SampleRejectedStatusKind,
+        instance_handle: InstanceHandle_t,
+    ) {
+        self.total_count += 1;
+        self.total_count_change += 1;
+        self.last_reason = reason;
+        self.last_instance_handle = instance_handle;
+    }
+
+    /// Returns the status and resets its total_count_change, like reading
+    /// the status through the Entity.
+    pub fn read(&mut self) -> SampleRejectedStatus {
+        let status = *self;
+        self.total_count_change = 0;
+        status
+    }
+}
@@ -0,0 +1,10 @@
+/// The reason of the last sample rejected by a DataReader.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SampleRejectedStatusKind {
+    #[default]
+    NOT_REJECTED,
+    REJECTED_BY_INSTANCES_LIMIT,
+    REJECTED_BY_SAMPLES_LIMIT,
+    REJECTED_BY_SAMPLES_PER_INSTANCE_LIMIT,
+}
+
@@ -0,0 +1,26 @@
+use bitflags::bitflags;
+
+/// The set of communication statuses a listener or a StatusCondition is
+/// interested in.
+pub type StatusMask = StatusKind;
+
+bitflags! {
+    /// The communication statuses of the entities (2.2.4.1), with the
+    /// values of the DDS PSM.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    pub struct StatusKind: u32 {
+        const INCONSISTENT_TOPIC = 1 << 0;
+        const OFFERED_DEADLINE_MISSED = 1 << 1;
+        const REQUESTED_DEADLINE_MISSED = 1 << 2;
+        const OFFERED_INCOMPATIBLE_QOS = 1 << 5;
+        const REQUESTED_INCOMPATIBLE_QOS = 1 << 6;
+        const SAMPLE_LOST = 1 << 7;
+        const SAMPLE_REJECTED = 1 << 8;
+        const DATA_ON_READERS = 1 << 9;
+        const DATA_AVAILABLE = 1 << 10;
+        const LIVELINESS_LOST = 1 << 11;
+        const LIVELINESS_CHANGED = 1 << 12;
+        const PUBLICATION_MATCHED = 1 << 13;
+        const SUBSCRIPTION_MATCHED = 1 << 14;
+    }
+}
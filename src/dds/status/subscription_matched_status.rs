@@ -0,0 +1,49 @@
+use crate::structure::instance_handle::InstanceHandle_t;
+
+/// The SUBSCRIPTION_MATCHED status of a DataReader: the DataWriters found
+/// with a matching Topic and a compatible QoS.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SubscriptionMatchedStatus {
+    /// Total cumulative count of the DataWriters matched with the
+    /// DataReader.
+    pub total_count: i32,
+
+    /// The change in total_count since the last time the status was read.
+    pub total_count_change: i32,
+
+    /// The number of DataWriters currently matched with the DataReader.
+    pub current_count: i32,
+
+    /// The change in current_count since the last time the status was read.
+    pub current_count_change: i32,
+
+    /// Handle to the last DataWriter matched with or unmatched from the
+    /// DataReader.
+    pub last_publication_handle: InstanceHandle_t,
+}
+
+impl SubscriptionMatchedStatus {
+    /// Records the DataWriter identified by publication_handle being
+    /// matched, or unmatched if matched is false.
+    pub fn change(&mut self, publication_handle: InstanceHandle_t, matched: bool) {
+        if matched {
+            self.total_count += 1;
+            self.total_count_change += 1;
+            self.current_count += 1;
+            self.current_count_change += 1;
+        } else {
+            self.current_count -= 1;
+            self.current_count_change -= 1;
+        }
+        self.last_publication_handle = publication_handle;
+    }
+
+    /// Returns the status and resets its changes, like reading the status
+    /// through the Entity.
+    pub fn read(&mut self) -> SubscriptionMatchedStatus {
+        let status = *self;
+        self.total_count_change = 0;
+        self.current_count_change = 0;
+        status
+    }
+}
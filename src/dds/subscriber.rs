@@ -0,0 +1,139 @@
+use crate::dds::condition::status_condition::StatusCondition;
+use crate::dds::data_reader::DataReader;
+use crate::dds::domain_participant::{precondition_not_met, DomainParticipant};
+use crate::dds::listener::subscriber_listener::SubscriberListener;
+use crate::dds::qos::data_reader_qos::DataReaderQos;
+use crate::dds::qos::subscriber_qos::SubscriberQos;
+use crate::dds::status::status_kind::{StatusKind, StatusMask};
+use crate::dds::topic_description::TopicDescription;
+use crate::dds::type_support::TypeSupport;
+use crate::discovery::pl_cdr;
+use crate::structure::guid::GUID_t;
+use crate::structure::instance_handle::InstanceHandle_t;
+
+use std::io::Error;
+
+/// The factory and the group of DataReaders (2.2.2.5.2), created by
+/// DomainParticipant::create_subscriber. Its presentation and partition
+/// apply to all its DataReaders.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Subscriber {
+    participant: DomainParticipant,
+    guid: GUID_t,
+}
+
+impl Subscriber {
+    pub(crate) fn new(participant: DomainParticipant, guid: GUID_t) -> Self {
+        Subscriber { participant, guid }
+    }
+
+    pub fn guid(&self) -> GUID_t {
+        self.guid
+    }
+
+    pub fn get_instance_handle(&self) -> InstanceHandle_t {
+        pl_cdr::key_hash(self.guid)
+    }
+
+    pub fn get_participant(&self) -> DomainParticipant {
+        self.participant.clone()
+    }
+
+    pub fn get_qos(&self) -> Result<SubscriberQos, Error> {
+        self.participant.subscriber_qos(self.guid)
+    }
+
+    /// Replaces the QoS; the enabled DataReaders are announced again.
+    pub fn set_qos(&self, qos: SubscriberQos) -> Result<(), Error> {
+        self.participant.set_subscriber_qos(self.guid, qos)
+    }
+
+    /// Enables the Subscriber of the enabled DomainParticipant and, with
+    /// autoenable_created_entities, its DataReaders.
+    pub fn enable(&self) -> Result<(), Error> {
+        self.participant.enable_subscriber(self.guid)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.participant.is_subscriber_enabled(self.guid)
+    }
+
+    /// Sets the listener of the statuses in the mask of the DataReaders
+    /// without a listener of their own for the status. The listener is
+    /// called as the participant is driven, after the operation updated the
+    /// statuses; it must not drive the participant itself.
+    pub fn set_listener(
+        &self,
+        listener: Option<Box<dyn SubscriberListener>>,
+        mask: StatusMask,
+    ) -> Result<(), Error> {
+        self.participant
+            .set_subscriber_listener(self.guid, listener, mask)
+    }
+
+    /// The StatusCondition triggered by the changes of the enabled statuses
+    /// of the Subscriber.
+    pub fn get_statuscondition(&self) -> StatusCondition {
+        StatusCondition::new(self.participant.clone(), self.guid)
+    }
+
+    /// The statuses of the Subscriber changed since they were last read.
+    pub fn get_status_changes(&self) -> Result<StatusKind, Error> {
+        self.participant.status_changes(self.guid)
+    }
+
+    /// Creates the DataReader of the Topic or ContentFilteredTopic of the
+    /// type T created by the same DomainParticipant. The DataReader is
+    /// enabled if the Subscriber is enabled and autoenable_created_entities
+    /// is set.
+    pub fn create_datareader<T: TypeSupport>(
+        &self,
+        topic: impl Into<TopicDescription>,
+        qos: DataReaderQos,
+    ) -> Result<DataReader<T>, Error> {
+        let guid = self
+            .participant
+            .create_datareader::<T>(self.guid, &topic.into(), qos)?;
+        Ok(DataReader::new(self.participant.clone(), guid))
+    }
+
+    /// Deletes the DataReader created by the Subscriber.
+    pub fn delete_datareader<T>(&self, reader: &DataReader<T>) -> Result<(), Error> {
+        if reader.get_participant() != self.participant {
+            return Err(precondition_not_met(
+                "The DataReader belongs to another Subscriber",
+            ));
+        }
+        self.participant.delete_datareader(self.guid, reader.guid())
+    }
+
+    /// A DataReader of the Topic of the name created by the Subscriber, if the
+    /// Topic has the type T.
+    pub fn lookup_datareader<T: TypeSupport>(&self, topic_name: &str) -> Option<DataReader<T>> {
+        let topic = self.participant.lookup_topicdescription(topic_name)?;
+        if topic.get_type_name().ok()? != T::TYPE_NAME {
+            return None;
+        }
+        self.participant
+            .datareaders(self.guid, Some(topic_name))
+            .into_iter()
+            .next()
+            .map(|guid| DataReader::new(self.participant.clone(), guid))
+    }
+
+    /// Deletes all DataReaders created by the Subscriber.
+    pub fn delete_contained_entities(&self) -> Result<(), Error> {
+        for reader in self.participant.datareaders(self.guid, None) {
+            self.participant.delete_datareader(self.guid, reader)?;
+        }
+        Ok(())
+    }
+
+    pub fn get_default_datareader_qos(&self) -> Result<DataReaderQos, Error> {
+        self.participant.default_datareader_qos(self.guid)
+    }
+
+    pub fn set_default_datareader_qos(&self, qos: DataReaderQos) -> Result<(), Error> {
+        self.participant.set_default_datareader_qos(self.guid, qos)
+    }
+}
@@ -0,0 +1,61 @@
+use crate::dds::domain_participant::DomainParticipant;
+use crate::dds::qos::topic_qos::TopicQos;
+use crate::discovery::pl_cdr;
+use crate::structure::guid::GUID_t;
+use crate::structure::instance_handle::InstanceHandle_t;
+
+use std::io::Error;
+
+/// The data type and the name identifying the data exchanged by the
+/// DataWriters and DataReaders of a domain (2.2.2.3.2), created by
+/// DomainParticipant::create_topic.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Topic {
+    participant: DomainParticipant,
+    guid: GUID_t,
+}
+
+impl Topic {
+    pub(crate) fn new(participant: DomainParticipant, guid: GUID_t) -> Self {
+        Topic { participant, guid }
+    }
+
+    pub fn guid(&self) -> GUID_t {
+        self.guid
+    }
+
+    pub fn get_instance_handle(&self) -> InstanceHandle_t {
+        pl_cdr::key_hash(self.guid)
+    }
+
+    pub fn get_participant(&self) -> DomainParticipant {
+        self.participant.clone()
+    }
+
+    pub fn get_name(&self) -> Result<String, Error> {
+        Ok(self.participant.topic_name(self.guid)?.0)
+    }
+
+    pub fn get_type_name(&self) -> Result<String, Error> {
+        Ok(self.participant.topic_name(self.guid)?.1)
+    }
+
+    pub fn get_qos(&self) -> Result<TopicQos, Error> {
+        self.participant.topic_qos(self.guid)
+    }
+
+    /// Replaces the QoS, used by the DataWriters and DataReaders created
+    /// afterwards.
+    pub fn set_qos(&self, qos: TopicQos) -> Result<(), Error> {
+        self.participant.set_topic_qos(self.guid, qos)
+    }
+
+    /// Enables the Topic of the enabled DomainParticipant.
+    pub fn enable(&self) -> Result<(), Error> {
+        self.participant.enable_topic(self.guid)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.participant.is_topic_enabled(self.guid)
+    }
+}
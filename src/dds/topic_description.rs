@@ -0,0 +1,32 @@
+use crate::dds::content_filtered_topic::ContentFilteredTopic;
+use crate::dds::topic::Topic;
+
+/// The data read by a DataReader (2.2.2.3.1): all the samples of a Topic or
+/// the ones of a ContentFilteredTopic.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TopicDescription {
+    Topic(Topic),
+    ContentFilteredTopic(ContentFilteredTopic),
+}
+
+impl From<&Topic> for TopicDescription {
+    fn from(topic: &Topic) -> Self {
+        TopicDescription::Topic(topic.clone())
+    }
+}
+
+impl From<&ContentFilteredTopic> for TopicDescription {
+    fn from(topic: &ContentFilteredTopic) -> Self {
+        TopicDescription::ContentFilteredTopic(topic.clone())
+    }
+}
+
+impl TopicDescription {
+    /// The Topic the samples belong to.
+    pub fn topic(&self) -> &Topic {
+        match self {
+            TopicDescription::Topic(topic) => topic,
+            TopicDescription::ContentFilteredTopic(topic) => topic.get_related_topic(),
+        }
+    }
+}
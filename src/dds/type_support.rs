@@ -0,0 +1,369 @@
+use crate::dds::cdr::cdr_readable::CdrReadable;
+use crate::dds::cdr::cdr_reader::CdrReader;
+use crate::dds::cdr::cdr_writable::CdrWritable;
+use crate::dds::cdr::cdr_writer::CdrWriter;
+use crate::dds::filter::filter_fields::{FilterFields, SampleDecoder};
+use crate::messages::submessage_elements::serialized_payload::SerializedPayload;
+use crate::structure::instance_handle::InstanceHandle_t;
+
+use bytes::{BufMut, Bytes, BytesMut};
+use speedy::Endianness;
+use std::io::{Error, ErrorKind};
+use std::sync::Arc;
+
+/// Encapsulation identifiers of the data serialized with plain CDR (10.2).
+const CDR_BE: [u8; 2] = [0x00, 0x00];
+const CDR_LE: [u8; 2] = [0x00, 0x01];
+
+/// The data type of a Topic (2.2.2.3.6), identified by its name. The values
+/// are serialized with the CDR encapsulation into the SerializedPayload of
+/// the changes; the key fields returned by key identify the instance the
+/// value belongs to.
+pub trait TypeSupport: CdrWritable + CdrReadable {
+    /// The name of the type announced by the discovery, it has to match the
+    /// type name of the Topic.
+    const TYPE_NAME: &'static str;
+
+    /// Whether the type has key fields. All values of the type without key
+    /// belong to a single instance.
+    const WITH_KEY: bool = false;
+
+    /// The maximum size of the key fields serialized with CDR, None if it is
+    /// unbounded, e.g. for a key with a string. The key hash of the type
+    /// whose key may exceed 16 bytes is always an MD5 digest (9.6.3.8).
+    const KEY_MAX_SERIALIZED_SIZE: Option<usize> = None;
+
+    /// The key fields of the values, serialized in their order of
+    /// declaration; () for the type without key.
+    type Key: CdrWritable + CdrReadable;
+
+    fn key(&self) -> Self::Key;
+
+    /// Serializes the value preceded by the encapsulation header.
+    fn serialize(&self) -> Result<SerializedPayload, Error> {
+        Ok(encapsulate(self))
+    }
+
+    /// Serializes the key fields of the value preceded by the encapsulation
+    /// header, the payload of the changes of the instance without data.
+    fn serialize_key(&self) -> Result<SerializedPayload, Error> {
+        Ok(encapsulate(&self.key()))
+    }
+
+    /// Deserializes the value preceded by the encapsulation header.
+    fn deserialize(serialized_payload: &SerializedPayload) -> Result<Self, Error> {
+        decapsulate(serialized_payload)
+    }
+
+    /// Deserializes the key fields preceded by the encapsulation header, the
+    /// payload of the changes of the instance without data, see
+    /// serialize_key.
+    fn deserialize_key(serialized_payload: &SerializedPayload) -> Result<Self::Key, Error> {
+        decapsulate(serialized_payload)
+    }
+
+    /// The instance of the value, see key_hash. HANDLE_NIL for the type
+    /// without key.
+    fn instance_handle(&self) -> InstanceHandle_t {
+        if !Self::WITH_KEY {
+            return InstanceHandle_t::HANDLE_NIL;
+        }
+        Self::key_hash(&self.key())
+    }
+
+    /// The key hash of the key fields (9.6.3.8): the key fields serialized
+    /// with big endian CDR, padded with zeros to 16 bytes if their maximum
+    /// size fits, else hashed with MD5.
+    fn key_hash(key: &Self::Key) -> InstanceHandle_t {
+        let mut writer = CdrWriter::new(Endianness::BigEndian);
+        key.write_cdr(&mut writer);
+        let key = writer.into_vec();
+        let mut instance_handle = InstanceHandle_t::HANDLE_NIL;
+        match Self::KEY_MAX_SERIALIZED_SIZE {
+            Some(max_size) if max_size <= instance_handle.entity_key.len() => {
+                instance_handle.entity_key[..key.len()].copy_from_slice(&key)
+            }
+            _ => instance_handle.entity_key = md5::compute(&key).0,
+        }
+        instance_handle
+    }
+
+    /// Deserializes the serialized values into the fields evaluated by the
+    /// content filters, see filter_fields_decoder. None if the values of the
+    /// type cannot be filtered: its DataWriters do not filter on behalf of
+    /// the DataReaders and its DataReaders cannot read a
+    /// ContentFilteredTopic.
+    fn sample_decoder() -> Option<SampleDecoder> {
+        None
+    }
+}
+
+/// The SampleDecoder of the type whose fields are accessed with
+/// FilterFields, returned by its TypeSupport::sample_decoder.
+pub fn filter_fields_decoder<T: TypeSupport + FilterFields + 'static>() -> SampleDecoder {
+    Arc::new(|serialized_data: &[u8]| {
+        let serialized_payload = SerializedPayload::from(Bytes::copy_from_slice(serialized_data));
+        T::deserialize(&serialized_payload)
+            .ok()
+            .map(|data| Box::new(data) as Box<dyn FilterFields>)
+    })
+}
+
+/// Serializes the value in little endian preceded by the encapsulation
+/// header.
+fn encapsulate(value: &(impl CdrWritable + ?Sized)) -> SerializedPayload {
+    let mut writer = CdrWriter::new(Endianness::LittleEndian);
+    value.write_cdr(&mut writer);
+    let mut payload = BytesMut::new();
+    payload.put_slice(&CDR_LE);
+    payload.put_slice(&[0x00, 0x00]);
+    payload.put_slice(&writer.into_vec());
+    SerializedPayload::from(payload.freeze())
+}
+
+/// Deserializes the value preceded by the encapsulation header, the
+/// alignment is relative to the end of the header.
+fn decapsulate<T: CdrReadable>(serialized_payload: &SerializedPayload) -> Result<T, Error> {
+    let payload = &serialized_payload.value;
+    if payload.len() < 4 {
+        return Err(Error::new(ErrorKind::InvalidData, "Missing encapsulation"));
+    }
+    let endianness = match [payload[0], payload[1]] {
+        CDR_LE => Endianness::LittleEndian,
+        CDR_BE => Endianness::BigEndian,
+        _ => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "Encapsulation is not CDR",
+            ))
+        }
+    };
+    T::read_cdr(&mut CdrReader::new(&payload[4..], endianness))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::dds::filter::filter_value::FilterValue;
+
+    /// The keyed type used by the tests of the DCPS entities, its color is
+    /// the key.
+    #[derive(Clone, Debug, PartialEq)]
+    pub(crate) struct Shape {
+        pub color: String,
+        pub x: i32,
+        pub y: i32,
+    }
+
+    impl Shape {
+        pub fn new(color: &str, x: i32, y: i32) -> Self {
+            Shape {
+                color: color.to_string(),
+                x,
+                y,
+            }
+        }
+    }
+
+    impl CdrWritable for Shape {
+        fn write_cdr(&self, writer: &mut CdrWriter) {
+            self.color.write_cdr(writer);
+            self.x.write_cdr(writer);
+            self.y.write_cdr(writer);
+        }
+    }
+
+    impl CdrReadable for Shape {
+        fn read_cdr(reader: &mut CdrReader<'_>) -> Result<Self, Error> {
+            Ok(Shape {
+                color: String::read_cdr(reader)?,
+                x: i32::read_cdr(reader)?,
+                y: i32::read_cdr(reader)?,
+            })
+        }
+    }
+
+    impl TypeSupport for Shape {
+        const TYPE_NAME: &'static str = "ShapeType";
+        const WITH_KEY: bool = true;
+
+        type Key = String;
+
+        fn key(&self) -> String {
+            self.color.clone()
+        }
+
+        fn sample_decoder() -> Option<SampleDecoder> {
+            Some(filter_fields_decoder::<Self>())
+        }
+    }
+
+    impl FilterFields for Shape {
+        fn field(&self, name: &str) -> Option<FilterValue> {
+            match name {
+                "color" => Some(FilterValue::String(self.color.clone())),
+                "x" => Some(FilterValue::Integer(self.x.into())),
+                "y" => Some(FilterValue::Integer(self.y.into())),
+                _ => None,
+            }
+        }
+    }
+
+    /// A type whose key fits in the key hash without MD5.
+    #[derive(Clone, Debug, PartialEq)]
+    struct Counter {
+        id: i32,
+        count: u64,
+    }
+
+    impl CdrWritable for Counter {
+        fn write_cdr(&self, writer: &mut CdrWriter) {
+            self.id.write_cdr(writer);
+            self.count.write_cdr(writer);
+        }
+    }
+
+    impl CdrReadable for Counter {
+        fn read_cdr(reader: &mut CdrReader<'_>) -> Result<Self, Error> {
+            Ok(Counter {
+                id: i32::read_cdr(reader)?,
+                count: u64::read_cdr(reader)?,
+            })
+        }
+    }
+
+    impl TypeSupport for Counter {
+        const TYPE_NAME: &'static str = "Counter";
+        const WITH_KEY: bool = true;
+        const KEY_MAX_SERIALIZED_SIZE: Option<usize> = Some(4);
+
+        type Key = i32;
+
+        fn key(&self) -> i32 {
+            self.id
+        }
+    }
+
+    #[test]
+    fn value_is_serialized_with_encapsulation() {
+        let shape = Shape::new("RED", 1, -1);
+
+        let serialized_payload = shape.serialize().unwrap();
+
+        #[rustfmt::skip]
+        assert_eq!(
+            vec![
+                0x00, 0x01, 0x00, 0x00, // CDR_LE
+                0x04, 0x00, 0x00, 0x00, // color length with NUL
+                b'R', b'E', b'D', 0x00, // color
+                0x01, 0x00, 0x00, 0x00, // x
+                0xFF, 0xFF, 0xFF, 0xFF, // y
+            ],
+            serialized_payload.value
+        );
+        assert_eq!(shape, Shape::deserialize(&serialized_payload).unwrap());
+        assert!(Shape::deserialize(&SerializedPayload::default()).is_err());
+        #[rustfmt::skip]
+        assert_eq!(
+            vec![
+                0x00, 0x01, 0x00, 0x00, // CDR_LE
+                0x04, 0x00, 0x00, 0x00, // color length with NUL
+                b'R', b'E', b'D', 0x00, // color
+            ],
+            shape.serialize_key().unwrap().value
+        );
+    }
+
+    #[test]
+    fn alignment_is_relative_to_the_end_of_encapsulation() {
+        let counter = Counter { id: 7, count: 1 };
+
+        let serialized_payload = counter.serialize().unwrap();
+
+        #[rustfmt::skip]
+        assert_eq!(
+            vec![
+                0x00, 0x01, 0x00, 0x00, // CDR_LE
+                0x07, 0x00, 0x00, 0x00, // id
+                0x00, 0x00, 0x00, 0x00, // padding
+                0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // count
+            ],
+            serialized_payload.value
+        );
+        assert_eq!(counter, Counter::deserialize(&serialized_payload).unwrap());
+    }
+
+    #[test]
+    fn big_endian_value_is_deserialized() {
+        #[rustfmt::skip]
+        let serialized_payload = SerializedPayload::from(Bytes::from_static(&[
+            0x00, 0x00, 0x00, 0x00, // CDR_BE
+            0x00, 0x00, 0x00, 0x04, // color length with NUL
+            b'R', b'E', b'D', 0x00, // color
+            0x00, 0x00, 0x00, 0x01, // x
+            0xFF, 0xFF, 0xFF, 0xFF, // y
+        ]));
+
+        assert_eq!(
+            Shape::new("RED", 1, -1),
+            Shape::deserialize(&serialized_payload).unwrap()
+        );
+    }
+
+    #[test]
+    fn value_with_other_encapsulation_is_rejected() {
+        #[rustfmt::skip]
+        let serialized_payload = SerializedPayload::from(Bytes::from_static(&[
+            0x00, 0x03, 0x00, 0x00, // PL_CDR_LE
+            0x04, 0x00, 0x00, 0x00, b'R', b'E', b'D', 0x00,
+            0x01, 0x00, 0x00, 0x00,
+            0xFF, 0xFF, 0xFF, 0xFF,
+        ]));
+
+        assert_eq!(
+            ErrorKind::InvalidData,
+            Shape::deserialize(&serialized_payload).unwrap_err().kind()
+        );
+    }
+
+    #[test]
+    fn bounded_key_hash_is_padded_big_endian_key() {
+        #[rustfmt::skip]
+        assert_eq!(
+            [
+                0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            ],
+            Counter { id: 5, count: 9 }.instance_handle().entity_key
+        );
+    }
+
+    #[test]
+    fn unbounded_key_hash_is_md5_of_big_endian_key() {
+        // md5(00 00 00 04 'R' 'E' 'D' 00): the key with a string is
+        // unbounded, it is hashed even if it is shorter than 16 bytes
+        #[rustfmt::skip]
+        assert_eq!(
+            [
+                0xd3, 0x6d, 0xe8, 0x65, 0xfa, 0xc2, 0x95, 0x15,
+                0x5f, 0x18, 0xdf, 0x71, 0x57, 0xb2, 0x17, 0xe6,
+            ],
+            Shape::new("RED", 1, 2).instance_handle().entity_key
+        );
+        assert_eq!(
+            Shape::new("RED", 1, 2).instance_handle(),
+            Shape::new("RED", 3, 4).instance_handle()
+        );
+        // md5(00 00 00 17 'LIGHT GOLDENROD YELLOW' 00)
+        #[rustfmt::skip]
+        assert_eq!(
+            [
+                0x53, 0x54, 0x8f, 0x01, 0x41, 0x48, 0x18, 0xbb,
+                0xbf, 0x48, 0xc9, 0xae, 0x82, 0x78, 0x67, 0x21,
+            ],
+            Shape::new("LIGHT GOLDENROD YELLOW", 0, 0)
+                .instance_handle()
+                .entity_key
+        );
+    }
+}
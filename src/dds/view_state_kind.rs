@@ -0,0 +1,10 @@
+/// Whether the DataReader already accessed a sample of the instance in the
+/// current generation of the instance (2.2.2.5.1.7).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ViewStateKind {
+    NEW,
+    NOT_NEW,
+}
+
+/// The samples of the instances in any view state.
+pub const ANY_VIEW_STATE: &[ViewStateKind] = &[ViewStateKind::NEW, ViewStateKind::NOT_NEW];
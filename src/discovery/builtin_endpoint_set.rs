@@ -0,0 +1,88 @@
+use bitflags::bitflags;
+use speedy::{Context, Readable, Reader, Writable, Writer};
+
+bitflags! {
+    /// The built-in Endpoints available in a Participant (8.5.3.2), announced
+    /// by the SPDP as PID_BUILTIN_ENDPOINT_SET. Only the built-in Endpoints of
+    /// a remote Participant present in the set are matched by the discovery.
+    /// The bits not defined here are retained as received.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    pub struct BuiltinEndpointSet_t: u32 {
+        const DISC_BUILTIN_ENDPOINT_PARTICIPANT_ANNOUNCER = 1 << 0;
+        const DISC_BUILTIN_ENDPOINT_PARTICIPANT_DETECTOR = 1 << 1;
+        const DISC_BUILTIN_ENDPOINT_PUBLICATIONS_ANNOUNCER = 1 << 2;
+        const DISC_BUILTIN_ENDPOINT_PUBLICATIONS_DETECTOR = 1 << 3;
+        const DISC_BUILTIN_ENDPOINT_SUBSCRIPTIONS_ANNOUNCER = 1 << 4;
+        const DISC_BUILTIN_ENDPOINT_SUBSCRIPTIONS_DETECTOR = 1 << 5;
+        const DISC_BUILTIN_ENDPOINT_PARTICIPANT_PROXY_ANNOUNCER = 1 << 6;
+        const DISC_BUILTIN_ENDPOINT_PARTICIPANT_PROXY_DETECTOR = 1 << 7;
+        const DISC_BUILTIN_ENDPOINT_PARTICIPANT_STATE_ANNOUNCER = 1 << 8;
+        const DISC_BUILTIN_ENDPOINT_PARTICIPANT_STATE_DETECTOR = 1 << 9;
+        const BUILTIN_ENDPOINT_PARTICIPANT_MESSAGE_DATA_WRITER = 1 << 10;
+        const BUILTIN_ENDPOINT_PARTICIPANT_MESSAGE_DATA_READER = 1 << 11;
+
+        // DDS-Security 7.4.7.1
+        const SEDP_BUILTIN_PUBLICATIONS_SECURE_WRITER = 1 << 16;
+        const SEDP_BUILTIN_PUBLICATIONS_SECURE_READER = 1 << 17;
+        const SEDP_BUILTIN_SUBSCRIPTIONS_SECURE_WRITER = 1 << 18;
+        const SEDP_BUILTIN_SUBSCRIPTIONS_SECURE_READER = 1 << 19;
+        const BUILTIN_PARTICIPANT_MESSAGE_SECURE_WRITER = 1 << 20;
+        const BUILTIN_PARTICIPANT_MESSAGE_SECURE_READER = 1 << 21;
+        const BUILTIN_PARTICIPANT_STATELESS_MESSAGE_WRITER = 1 << 22;
+        const BUILTIN_PARTICIPANT_STATELESS_MESSAGE_READER = 1 << 23;
+        const BUILTIN_PARTICIPANT_VOLATILE_MESSAGE_SECURE_WRITER = 1 << 24;
+        const BUILTIN_PARTICIPANT_VOLATILE_MESSAGE_SECURE_READER = 1 << 25;
+        const SPDP_BUILTIN_PARTICIPANT_SECURE_WRITER = 1 << 26;
+        const SPDP_BUILTIN_PARTICIPANT_SECURE_READER = 1 << 27;
+
+        const DISC_BUILTIN_ENDPOINT_TOPICS_ANNOUNCER = 1 << 28;
+        const DISC_BUILTIN_ENDPOINT_TOPICS_DETECTOR = 1 << 29;
+    }
+}
+
+impl<'a, C: Context> Readable<'a, C> for BuiltinEndpointSet_t {
+    #[inline]
+    fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+        Ok(BuiltinEndpointSet_t::from_bits_retain(reader.read_u32()?))
+    }
+
+    #[inline]
+    fn minimum_bytes_needed() -> usize {
+        std::mem::size_of::<Self>()
+    }
+}
+
+impl<C: Context> Writable<C> for BuiltinEndpointSet_t {
+    #[inline]
+    fn write_to<T: ?Sized + Writer<C>>(&self, writer: &mut T) -> Result<(), C::Error> {
+        writer.write_u32(self.bits())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    serialization_test!( type = BuiltinEndpointSet_t,
+        {
+            builtin_endpoint_set_spdp,
+            BuiltinEndpointSet_t::DISC_BUILTIN_ENDPOINT_PARTICIPANT_ANNOUNCER
+                | BuiltinEndpointSet_t::DISC_BUILTIN_ENDPOINT_PARTICIPANT_DETECTOR,
+            le = [0x03, 0x00, 0x00, 0x00],
+            be = [0x00, 0x00, 0x00, 0x03]
+        },
+        {
+            builtin_endpoint_set_topics,
+            BuiltinEndpointSet_t::DISC_BUILTIN_ENDPOINT_TOPICS_ANNOUNCER
+                | BuiltinEndpointSet_t::BUILTIN_ENDPOINT_PARTICIPANT_MESSAGE_DATA_READER,
+            le = [0x00, 0x08, 0x00, 0x10],
+            be = [0x10, 0x00, 0x08, 0x00]
+        },
+        {
+            builtin_endpoint_set_unknown_bits,
+            BuiltinEndpointSet_t::from_bits_retain(0x8000_0001),
+            le = [0x01, 0x00, 0x00, 0x80],
+            be = [0x80, 0x00, 0x00, 0x01]
+        }
+    );
+}
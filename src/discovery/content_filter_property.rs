@@ -1,3 +1,11 @@
+use crate::dds::filter::filter_expression::FilterExpression;
+use crate::discovery::pl_cdr;
+use crate::messages::submessage_elements::parameter::Parameter;
+use crate::structure::parameter_id::ParameterId;
+
+use speedy::Endianness;
+use std::io::{Error, ErrorKind};
+
 /// The ContentFilterProperty_t field provides all the required information to
 /// enable content filtering on the Writer side. For example, for the default
 /// DDSSQL filter class, a valid filter expression for a data type containing
@@ -6,14 +14,15 @@
 /// the filter, it must have been configured to handle filters of the specified
 /// filter class. If not, the Writer will simply ignore the filter information
 /// and not filter any data samples.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ContentFilterProperty_t {
     /// Name of the Content-filtered Topic associated with the Reader.
     /// Must have non-zero length.
-    content_filtered_topic_name: String,
+    pub content_filtered_topic_name: String,
 
     /// Name of the Topic related to the Content-filtered Topic.
     /// Must have non-zero length.
-    related_topic_name: String,
+    pub related_topic_name: String,
 
     /// Identifies the filter class this filter belongs to. RTPS can support
     /// multiple filter classes (SQL, regular expressions, custom filters,
@@ -22,14 +31,139 @@ pub struct ContentFilterProperty_t {
     /// “DDSSQL” Default filter class name if none specified.
     /// Matches the SQL filter specified by DDS, which must be available in all
     /// implementations.
-    filter_class_name: String,
+    pub filter_class_name: String,
 
     /// The actual filter expression. Must be a valid expression for the filter
     /// class specified using filter_class_name.
     /// Must have non-zero length.
-    filter_expression: String,
+    pub filter_expression: String,
 
     /// Defines the value for each parameter in the filter expression.
     /// Can have zero length if the filter expression contains no parameters.
-    extension_parameters: Vec<String>,
+    pub extension_parameters: Vec<String>,
+}
+
+impl ContentFilterProperty_t {
+    /// The default filter class, the SQL subset defined by DDS.
+    pub const DDSSQL: &'static str = "DDSSQL";
+
+    /// Serializes the property as PID_CONTENT_FILTER_PROPERTY.
+    pub fn to_parameter(&self, endianness: Endianness) -> Parameter {
+        let mut serialized = vec![];
+        for value in [
+            &self.content_filtered_topic_name,
+            &self.related_topic_name,
+            &self.filter_class_name,
+            &self.filter_expression,
+        ] {
+            pl_cdr::write_string(&mut serialized, value, endianness);
+        }
+        pl_cdr::write_strings(&mut serialized, &self.extension_parameters, endianness);
+        Parameter::new(ParameterId::PID_CONTENT_FILTER_PROPERTY, serialized)
+    }
+
+    /// Parses the filter expression of the DDSSQL filter class with its
+    /// parameters; other filter classes are not supported.
+    pub fn to_filter_expression(&self) -> Result<FilterExpression, Error> {
+        if self.filter_class_name != ContentFilterProperty_t::DDSSQL {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                format!("Unsupported filter class {}", self.filter_class_name),
+            ));
+        }
+        FilterExpression::parse(&self.filter_expression, &self.extension_parameters)
+    }
+
+    pub fn from_parameter(parameter: &Parameter, endianness: Endianness) -> Result<Self, Error> {
+        let value = &parameter.value;
+        let offset = &mut 0;
+        Ok(ContentFilterProperty_t {
+            content_filtered_topic_name: pl_cdr::read_string_at(value, offset, endianness)?,
+            related_topic_name: pl_cdr::read_string_at(value, offset, endianness)?,
+            filter_class_name: pl_cdr::read_string_at(value, offset, endianness)?,
+            filter_expression: pl_cdr::read_string_at(value, offset, endianness)?,
+            extension_parameters: pl_cdr::read_strings_at(value, offset, endianness)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_filter_property_is_serialized() {
+        let content_filter_property = ContentFilterProperty_t {
+            content_filtered_topic_name: "cf".to_string(),
+            related_topic_name: "t".to_string(),
+            filter_class_name: ContentFilterProperty_t::DDSSQL.to_string(),
+            filter_expression: "a > %0".to_string(),
+            extension_parameters: vec!["5".to_string()],
+        };
+
+        let parameter = content_filter_property.to_parameter(Endianness::BigEndian);
+
+        assert_eq!(
+            ParameterId::PID_CONTENT_FILTER_PROPERTY,
+            parameter.parameter_id
+        );
+        assert_eq!(
+            vec![
+                0x00, 0x00, 0x00, 0x03, 0x63, 0x66, 0x00, 0x00, // "cf"
+                0x00, 0x00, 0x00, 0x02, 0x74, 0x00, 0x00, 0x00, // "t"
+                0x00, 0x00, 0x00, 0x07, 0x44, 0x44, 0x53, 0x53, // "DDSSQL"
+                0x51, 0x4c, 0x00, 0x00, //
+                0x00, 0x00, 0x00, 0x07, 0x61, 0x20, 0x3e, 0x20, // "a > %0"
+                0x25, 0x30, 0x00, 0x00, //
+                0x00, 0x00, 0x00, 0x01, // parameters
+                0x00, 0x00, 0x00, 0x02, 0x35, 0x00, // "5"
+            ],
+            parameter.value
+        );
+        assert_eq!(
+            content_filter_property,
+            ContentFilterProperty_t::from_parameter(&parameter, Endianness::BigEndian).unwrap()
+        );
+    }
+
+    #[test]
+    fn truncated_property_is_rejected() {
+        let mut parameter = ContentFilterProperty_t {
+            content_filtered_topic_name: "cf".to_string(),
+            related_topic_name: "t".to_string(),
+            filter_class_name: ContentFilterProperty_t::DDSSQL.to_string(),
+            filter_expression: "a > 1".to_string(),
+            extension_parameters: vec![],
+        }
+        .to_parameter(Endianness::LittleEndian);
+        parameter.value.truncate(20);
+
+        assert!(
+            ContentFilterProperty_t::from_parameter(&parameter, Endianness::LittleEndian).is_err()
+        );
+    }
+
+    #[test]
+    fn only_ddssql_filters_are_parsed() {
+        let mut content_filter_property = ContentFilterProperty_t {
+            content_filtered_topic_name: "cf".to_string(),
+            related_topic_name: "t".to_string(),
+            filter_class_name: ContentFilterProperty_t::DDSSQL.to_string(),
+            filter_expression: "a > %0".to_string(),
+            extension_parameters: vec!["5".to_string()],
+        };
+        assert_eq!(
+            FilterExpression::parse("a > 5", &[]).unwrap(),
+            content_filter_property.to_filter_expression().unwrap()
+        );
+
+        content_filter_property.filter_class_name = "REGEX".to_string();
+        assert_eq!(
+            ErrorKind::Unsupported,
+            content_filter_property
+                .to_filter_expression()
+                .unwrap_err()
+                .kind()
+        );
+    }
 }
@@ -0,0 +1,521 @@
+use crate::common::validity_trait::Validity;
+use crate::dds::qos::deadline_qos_policy::DeadlineQosPolicy;
+use crate::dds::qos::destination_order_qos_policy::DestinationOrderQosPolicy;
+use crate::dds::qos::durability_qos_policy::DurabilityQosPolicy;
+use crate::dds::qos::group_data_qos_policy::GroupDataQosPolicy;
+use crate::dds::qos::history_qos_policy::HistoryQosPolicy;
+use crate::dds::qos::latency_budget_qos_policy::LatencyBudgetQosPolicy;
+use crate::dds::qos::liveliness_qos_policy::LivelinessQosPolicy;
+use crate::dds::qos::ownership_qos_policy::OwnershipQosPolicy;
+use crate::dds::qos::partition_qos_policy::PartitionQosPolicy;
+use crate::dds::qos::presentation_qos_policy::PresentationQosPolicy;
+use crate::dds::qos::reliability_qos_policy::ReliabilityQosPolicy;
+use crate::dds::qos::resource_limits_qos_policy::ResourceLimitsQosPolicy;
+use crate::dds::qos::time_based_filter_qos_policy::TimeBasedFilterQosPolicy;
+use crate::dds::qos::topic_data_qos_policy::TopicDataQosPolicy;
+use crate::dds::qos::user_data_qos_policy::UserDataQosPolicy;
+use crate::discovery::content_filter_property::ContentFilterProperty_t;
+use crate::discovery::pl_cdr;
+use crate::messages::submessage_elements::parameter_list::ParameterList;
+use crate::structure::entity_id::EntityId_t;
+use crate::structure::guid::GUID_t;
+use crate::structure::instance_handle::InstanceHandle_t;
+use crate::structure::locator::LocatorList_t;
+use crate::structure::parameter_id::ParameterId;
+
+use bytes::Bytes;
+use speedy::Endianness;
+use std::io::{Error, ErrorKind};
+
+/// The data announced by the SEDPbuiltinSubscriptionsWriter for every local
+/// DataReader (8.5.4.2): the ReaderProxy of the DataReader together with its
+/// Topic, QoS and content filter. Serialized as a ParameterList with the PL_CDR
+/// encapsulation (9.6.2.2); the QoS policies with default values are
+/// omitted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiscoveredReaderData {
+    /// Identifies the remote Reader, also the key of the data.
+    pub remote_reader_guid: GUID_t,
+
+    /// List of unicast locators (transport, address, port combinations) that
+    /// can be used to send messages to the matched Reader. The list may be
+    /// empty, the default locators of the Participant are used then.
+    pub unicast_locator_list: LocatorList_t,
+
+    /// List of multicast locators (transport, address, port combinations)
+    /// that can be used to send messages to the matched Reader. The list may
+    /// be empty.
+    pub multicast_locator_list: LocatorList_t,
+
+    pub topic_name: String,
+    pub type_name: String,
+    pub durability: DurabilityQosPolicy,
+    pub deadline: DeadlineQosPolicy,
+    pub liveliness: LivelinessQosPolicy,
+    pub reliability: ReliabilityQosPolicy,
+    pub ownership: OwnershipQosPolicy,
+    pub destination_order: DestinationOrderQosPolicy,
+    pub presentation: PresentationQosPolicy,
+    pub partition: PartitionQosPolicy,
+    pub latency_budget: LatencyBudgetQosPolicy,
+    pub time_based_filter: TimeBasedFilterQosPolicy,
+    pub history: HistoryQosPolicy,
+    pub resource_limits: ResourceLimitsQosPolicy,
+    pub user_data: UserDataQosPolicy,
+    pub topic_data: TopicDataQosPolicy,
+    pub group_data: GroupDataQosPolicy,
+
+    /// Specifies whether the remote Reader expects in-line QoS to be sent
+    /// along with any data.
+    pub expects_inline_qos: bool,
+
+    /// The filter of the content-filtered Topic read by the Reader; the
+    /// Writer may apply it and send only the relevant samples.
+    pub content_filter: Option<ContentFilterProperty_t>,
+}
+
+impl DiscoveredReaderData {
+    /// The data of the Reader with the default QoS of the DataReader.
+    pub fn new(remote_reader_guid: GUID_t, topic_name: &str, type_name: &str) -> Self {
+        DiscoveredReaderData {
+            remote_reader_guid,
+            unicast_locator_list: vec![],
+            multicast_locator_list: vec![],
+            topic_name: topic_name.to_string(),
+            type_name: type_name.to_string(),
+            durability: DurabilityQosPolicy::default(),
+            deadline: DeadlineQosPolicy::default(),
+            liveliness: LivelinessQosPolicy::default(),
+            reliability: ReliabilityQosPolicy::default(),
+            ownership: OwnershipQosPolicy::default(),
+            destination_order: DestinationOrderQosPolicy::default(),
+            presentation: PresentationQosPolicy::default(),
+            partition: PartitionQosPolicy::default(),
+            latency_budget: LatencyBudgetQosPolicy::default(),
+            time_based_filter: TimeBasedFilterQosPolicy::default(),
+            history: HistoryQosPolicy::default(),
+            resource_limits: ResourceLimitsQosPolicy::default(),
+            user_data: UserDataQosPolicy::default(),
+            topic_data: TopicDataQosPolicy::default(),
+            group_data: GroupDataQosPolicy::default(),
+            expects_inline_qos: false,
+            content_filter: None,
+        }
+    }
+
+    /// The GUID of the Participant containing the Reader.
+    pub fn participant_guid(&self) -> GUID_t {
+        GUID_t {
+            guid_prefix: self.remote_reader_guid.guid_prefix,
+            entity_id: EntityId_t::ENTITYID_PARTICIPANT,
+        }
+    }
+
+    /// The key of the data, the GUID of the Reader.
+    pub fn key_hash(&self) -> InstanceHandle_t {
+        pl_cdr::key_hash(self.remote_reader_guid)
+    }
+
+    /// Serializes the data with the PL_CDR encapsulation of the endianness.
+    pub fn serialize(&self, endianness: Endianness) -> Bytes {
+        pl_cdr::serialize(&self.to_parameter_list(endianness), endianness)
+    }
+
+    /// Deserializes the data with the PL_CDR encapsulation. The parameters
+    /// not known to this implementation are ignored.
+    pub fn deserialize(payload: &[u8]) -> Result<Self, Error> {
+        let (endianness, parameter_list) = pl_cdr::deserialize(payload)?;
+        DiscoveredReaderData::from_parameter_list(&parameter_list, endianness)
+    }
+
+    pub fn to_parameter_list(&self, endianness: Endianness) -> ParameterList {
+        let mut parameter_list = ParameterList::new();
+        parameter_list.push(pl_cdr::parameter(
+            ParameterId::PID_ENDPOINT_GUID,
+            &self.remote_reader_guid,
+            endianness,
+        ));
+        parameter_list.push(pl_cdr::parameter(
+            ParameterId::PID_PARTICIPANT_GUID,
+            &self.participant_guid(),
+            endianness,
+        ));
+        parameter_list.push(pl_cdr::string_parameter(
+            ParameterId::PID_TOPIC_NAME,
+            &self.topic_name,
+            endianness,
+        ));
+        parameter_list.push(pl_cdr::string_parameter(
+            ParameterId::PID_TYPE_NAME,
+            &self.type_name,
+            endianness,
+        ));
+        for locator in &self.unicast_locator_list {
+            parameter_list.push(pl_cdr::parameter(
+                ParameterId::PID_UNICAST_LOCATOR,
+                locator,
+                endianness,
+            ));
+        }
+        for locator in &self.multicast_locator_list {
+            parameter_list.push(pl_cdr::parameter(
+                ParameterId::PID_MULTICAST_LOCATOR,
+                locator,
+                endianness,
+            ));
+        }
+        // The default reliability differs between the Writers and the
+        // Readers, it is always announced
+        parameter_list.push(pl_cdr::parameter(
+            ParameterId::PID_RELIABILITY,
+            &self.reliability,
+            endianness,
+        ));
+        pl_cdr::push_if_not_default(
+            &mut parameter_list,
+            ParameterId::PID_DURABILITY,
+            &self.durability,
+            endianness,
+        );
+        pl_cdr::push_if_not_default(
+            &mut parameter_list,
+            ParameterId::PID_DEADLINE,
+            &self.deadline,
+            endianness,
+        );
+        pl_cdr::push_if_not_default(
+            &mut parameter_list,
+            ParameterId::PID_LIVELINESS,
+            &self.liveliness,
+            endianness,
+        );
+        pl_cdr::push_if_not_default(
+            &mut parameter_list,
+            ParameterId::PID_OWNERSHIP,
+            &self.ownership,
+            endianness,
+        );
+        pl_cdr::push_if_not_default(
+            &mut parameter_list,
+            ParameterId::PID_DESTINATION_ORDER,
+            &self.destination_order,
+            endianness,
+        );
+        pl_cdr::push_if_not_default(
+            &mut parameter_list,
+            ParameterId::PID_PRESENTATION,
+            &self.presentation,
+            endianness,
+        );
+        pl_cdr::push_if_not_default(
+            &mut parameter_list,
+            ParameterId::PID_LATENCY_BUDGET,
+            &self.latency_budget,
+            endianness,
+        );
+        pl_cdr::push_if_not_default(
+            &mut parameter_list,
+            ParameterId::PID_TIME_BASED_FILTER,
+            &self.time_based_filter,
+            endianness,
+        );
+        pl_cdr::push_if_not_default(
+            &mut parameter_list,
+            ParameterId::PID_HISTORY,
+            &self.history,
+            endianness,
+        );
+        pl_cdr::push_if_not_default(
+            &mut parameter_list,
+            ParameterId::PID_RESOURCE_LIMITS,
+            &self.resource_limits,
+            endianness,
+        );
+        pl_cdr::push_if_not_default(
+            &mut parameter_list,
+            ParameterId::PID_USER_DATA,
+            &self.user_data,
+            endianness,
+        );
+        pl_cdr::push_if_not_default(
+            &mut parameter_list,
+            ParameterId::PID_TOPIC_DATA,
+            &self.topic_data,
+            endianness,
+        );
+        pl_cdr::push_if_not_default(
+            &mut parameter_list,
+            ParameterId::PID_GROUP_DATA,
+            &self.group_data,
+            endianness,
+        );
+        if !self.partition.name.is_empty() {
+            parameter_list.push(pl_cdr::strings_parameter(
+                ParameterId::PID_PARTITION,
+                &self.partition.name,
+                endianness,
+            ));
+        }
+        if self.expects_inline_qos {
+            parameter_list.push(pl_cdr::parameter(
+                ParameterId::PID_EXPECTS_INLINE_QOS,
+                &true,
+                endianness,
+            ));
+        }
+        if let Some(content_filter) = &self.content_filter {
+            parameter_list.push(content_filter.to_parameter(endianness));
+        }
+        parameter_list
+    }
+
+    pub fn from_parameter_list(
+        parameter_list: &ParameterList,
+        endianness: Endianness,
+    ) -> Result<Self, Error> {
+        let topic_name = parameter_list
+            .find(&ParameterId::PID_TOPIC_NAME)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Missing PID_TOPIC_NAME"))?;
+        let type_name = parameter_list
+            .find(&ParameterId::PID_TYPE_NAME)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Missing PID_TYPE_NAME"))?;
+        let partition = parameter_list
+            .find(&ParameterId::PID_PARTITION)
+            .map(|parameter| pl_cdr::read_strings(parameter, endianness))
+            .transpose()?
+            .unwrap_or_default();
+        let expects_inline_qos: Option<u8> = pl_cdr::read_optional(
+            parameter_list,
+            &ParameterId::PID_EXPECTS_INLINE_QOS,
+            endianness,
+        )?;
+        let content_filter = parameter_list
+            .find(&ParameterId::PID_CONTENT_FILTER_PROPERTY)
+            .map(|parameter| ContentFilterProperty_t::from_parameter(parameter, endianness))
+            .transpose()?;
+
+        let reader_data = DiscoveredReaderData {
+            remote_reader_guid: pl_cdr::read_endpoint_guid(parameter_list, endianness)?,
+            unicast_locator_list: pl_cdr::read_locators(
+                parameter_list,
+                &ParameterId::PID_UNICAST_LOCATOR,
+                endianness,
+            )?,
+            multicast_locator_list: pl_cdr::read_locators(
+                parameter_list,
+                &ParameterId::PID_MULTICAST_LOCATOR,
+                endianness,
+            )?,
+            topic_name: pl_cdr::read_string(topic_name, endianness)?,
+            type_name: pl_cdr::read_string(type_name, endianness)?,
+            durability: pl_cdr::read_or_default(
+                parameter_list,
+                ParameterId::PID_DURABILITY,
+                endianness,
+            )?,
+            deadline: pl_cdr::read_or_default(
+                parameter_list,
+                ParameterId::PID_DEADLINE,
+                endianness,
+            )?,
+            liveliness: pl_cdr::read_or_default(
+                parameter_list,
+                ParameterId::PID_LIVELINESS,
+                endianness,
+            )?,
+            reliability: pl_cdr::read_optional(
+                parameter_list,
+                &ParameterId::PID_RELIABILITY,
+                endianness,
+            )?
+            .unwrap_or_default(),
+            ownership: pl_cdr::read_or_default(
+                parameter_list,
+                ParameterId::PID_OWNERSHIP,
+                endianness,
+            )?,
+            destination_order: pl_cdr::read_or_default(
+                parameter_list,
+                ParameterId::PID_DESTINATION_ORDER,
+                endianness,
+            )?,
+            presentation: pl_cdr::read_or_default(
+                parameter_list,
+                ParameterId::PID_PRESENTATION,
+                endianness,
+            )?,
+            partition: PartitionQosPolicy { name: partition },
+            latency_budget: pl_cdr::read_or_default(
+                parameter_list,
+                ParameterId::PID_LATENCY_BUDGET,
+                endianness,
+            )?,
+            time_based_filter: pl_cdr::read_or_default(
+                parameter_list,
+                ParameterId::PID_TIME_BASED_FILTER,
+                endianness,
+            )?,
+            history: pl_cdr::read_or_default(parameter_list, ParameterId::PID_HISTORY, endianness)?,
+            resource_limits: pl_cdr::read_or_default(
+                parameter_list,
+                ParameterId::PID_RESOURCE_LIMITS,
+                endianness,
+            )?,
+            user_data: pl_cdr::read_or_default(
+                parameter_list,
+                ParameterId::PID_USER_DATA,
+                endianness,
+            )?,
+            topic_data: pl_cdr::read_or_default(
+                parameter_list,
+                ParameterId::PID_TOPIC_DATA,
+                endianness,
+            )?,
+            group_data: pl_cdr::read_or_default(
+                parameter_list,
+                ParameterId::PID_GROUP_DATA,
+                endianness,
+            )?,
+            expects_inline_qos: expects_inline_qos.is_some_and(|value| value != 0),
+            content_filter,
+        };
+        let durations = [
+            reader_data.deadline.period,
+            reader_data.liveliness.lease_duration,
+            reader_data.reliability.max_blocking_time,
+            reader_data.latency_budget.duration,
+            reader_data.time_based_filter.minimum_separation,
+        ];
+        if !durations.iter().all(Validity::valid) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "Negative duration in the QoS",
+            ));
+        }
+        Ok(reader_data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dds::qos::liveliness_qos_policy::LivelinessQosPolicyKind;
+    use crate::dds::qos::reliability_qos_policy::ReliabilityQosPolicyKind;
+    use crate::messages::submessage_elements::parameter::Parameter;
+    use crate::structure::duration::Duration_t;
+    use crate::structure::guid_prefix::GuidPrefix_t;
+    use crate::structure::locator::{Ipv4Addr, Locator_t, SocketAddr};
+
+    const READER_GUID: GUID_t = GUID_t {
+        guid_prefix: GuidPrefix_t {
+            entity_key: [0x02; 12],
+        },
+        entity_id: EntityId_t::new([0x00, 0x00, 0x01], 0x07),
+    };
+
+    #[test]
+    fn reader_data_is_serialized_as_parameter_list() {
+        let reader_data = DiscoveredReaderData {
+            expects_inline_qos: true,
+            ..DiscoveredReaderData::new(READER_GUID, "ab", "T")
+        };
+
+        assert_eq!(
+            &[
+                0x00, 0x02, 0x00, 0x00, // PL_CDR_BE
+                0x00, 0x5a, 0x00, 0x10, // PID_ENDPOINT_GUID
+                0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, //
+                0x02, 0x02, 0x02, 0x02, 0x00, 0x00, 0x01, 0x07, //
+                0x00, 0x50, 0x00, 0x10, // PID_PARTICIPANT_GUID
+                0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, //
+                0x02, 0x02, 0x02, 0x02, 0x00, 0x00, 0x01, 0xc1, //
+                0x00, 0x05, 0x00, 0x08, // PID_TOPIC_NAME
+                0x00, 0x00, 0x00, 0x03, 0x61, 0x62, 0x00, 0x00, //
+                0x00, 0x07, 0x00, 0x08, // PID_TYPE_NAME
+                0x00, 0x00, 0x00, 0x02, 0x54, 0x00, 0x00, 0x00, //
+                0x00, 0x1a, 0x00, 0x0c, // PID_RELIABILITY
+                0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, //
+                0x19, 0x99, 0x99, 0x9a, // 100 ms
+                0x00, 0x43, 0x00, 0x04, // PID_EXPECTS_INLINE_QOS
+                0x01, 0x00, 0x00, 0x00, //
+                0x00, 0x01, 0x00, 0x00, // PID_SENTINEL
+            ],
+            &reader_data.serialize(Endianness::BigEndian)[..]
+        );
+    }
+
+    #[test]
+    fn reader_data_round_trips() {
+        let reader_data = DiscoveredReaderData {
+            multicast_locator_list: vec![Locator_t::from(SocketAddr::new(
+                Ipv4Addr::new(239, 255, 0, 1).into(),
+                7401,
+            ))],
+            liveliness: LivelinessQosPolicy {
+                kind: LivelinessQosPolicyKind::MANUAL_BY_PARTICIPANT,
+                lease_duration: Duration_t::from(std::time::Duration::from_secs(2)),
+            },
+            reliability: ReliabilityQosPolicy {
+                kind: ReliabilityQosPolicyKind::RELIABLE,
+                max_blocking_time: Duration_t::DURATION_ZERO,
+            },
+            time_based_filter: TimeBasedFilterQosPolicy {
+                minimum_separation: Duration_t::from(std::time::Duration::from_millis(100)),
+            },
+            user_data: UserDataQosPolicy {
+                value: vec![0x61, 0x62, 0x63],
+            },
+            group_data: GroupDataQosPolicy { value: vec![0x64] },
+            content_filter: Some(ContentFilterProperty_t {
+                content_filtered_topic_name: "filtered".to_string(),
+                related_topic_name: "topic".to_string(),
+                filter_class_name: ContentFilterProperty_t::DDSSQL.to_string(),
+                filter_expression: "x > %0".to_string(),
+                extension_parameters: vec!["1".to_string()],
+            }),
+            ..DiscoveredReaderData::new(READER_GUID, "topic", "type")
+        };
+
+        for endianness in [Endianness::LittleEndian, Endianness::BigEndian] {
+            assert_eq!(
+                reader_data,
+                DiscoveredReaderData::deserialize(&reader_data.serialize(endianness)).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn missing_policies_have_reader_defaults() {
+        let mut parameter_list = DiscoveredReaderData::new(READER_GUID, "topic", "type")
+            .to_parameter_list(Endianness::LittleEndian);
+        parameter_list
+            .parameters
+            .retain(|parameter| parameter.parameter_id != ParameterId::PID_RELIABILITY);
+
+        let reader_data =
+            DiscoveredReaderData::from_parameter_list(&parameter_list, Endianness::LittleEndian)
+                .unwrap();
+
+        assert_eq!(ReliabilityQosPolicy::default(), reader_data.reliability);
+        assert_eq!(DurabilityQosPolicy::default(), reader_data.durability);
+        assert_eq!(None, reader_data.content_filter);
+        assert_eq!(pl_cdr::key_hash(READER_GUID), reader_data.key_hash());
+    }
+
+    #[test]
+    fn reader_data_with_negative_duration_is_rejected() {
+        let mut parameter_list = DiscoveredReaderData::new(READER_GUID, "topic", "type")
+            .to_parameter_list(Endianness::LittleEndian);
+        parameter_list.push(Parameter::new(
+            ParameterId::PID_TIME_BASED_FILTER,
+            vec![0xfe, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00],
+        ));
+
+        assert_eq!(
+            ErrorKind::InvalidData,
+            DiscoveredReaderData::from_parameter_list(&parameter_list, Endianness::LittleEndian)
+                .unwrap_err()
+                .kind()
+        );
+    }
+}
@@ -0,0 +1,582 @@
+use crate::common::validity_trait::Validity;
+use crate::dds::qos::deadline_qos_policy::DeadlineQosPolicy;
+use crate::dds::qos::destination_order_qos_policy::DestinationOrderQosPolicy;
+use crate::dds::qos::durability_qos_policy::DurabilityQosPolicy;
+use crate::dds::qos::durability_service_qos_policy::DurabilityServiceQosPolicy;
+use crate::dds::qos::group_data_qos_policy::GroupDataQosPolicy;
+use crate::dds::qos::history_qos_policy::HistoryQosPolicy;
+use crate::dds::qos::latency_budget_qos_policy::LatencyBudgetQosPolicy;
+use crate::dds::qos::lifespan_qos_policy::LifespanQosPolicy;
+use crate::dds::qos::liveliness_qos_policy::LivelinessQosPolicy;
+use crate::dds::qos::ownership_qos_policy::OwnershipQosPolicy;
+use crate::dds::qos::ownership_strength_qos_policy::OwnershipStrengthQosPolicy;
+use crate::dds::qos::partition_qos_policy::PartitionQosPolicy;
+use crate::dds::qos::presentation_qos_policy::PresentationQosPolicy;
+use crate::dds::qos::reliability_qos_policy::ReliabilityQosPolicy;
+use crate::dds::qos::resource_limits_qos_policy::ResourceLimitsQosPolicy;
+use crate::dds::qos::topic_data_qos_policy::TopicDataQosPolicy;
+use crate::dds::qos::transport_priority_qos_policy::TransportPriorityQosPolicy;
+use crate::dds::qos::user_data_qos_policy::UserDataQosPolicy;
+use crate::discovery::pl_cdr;
+use crate::messages::submessage_elements::parameter_list::ParameterList;
+use crate::structure::entity_id::EntityId_t;
+use crate::structure::guid::GUID_t;
+use crate::structure::instance_handle::InstanceHandle_t;
+use crate::structure::locator::LocatorList_t;
+use crate::structure::parameter_id::ParameterId;
+
+use bytes::Bytes;
+use speedy::Endianness;
+use std::io::{Error, ErrorKind};
+
+/// The data announced by the SEDPbuiltinPublicationsWriter for every local
+/// DataWriter (8.5.4.2): the WriterProxy of the DataWriter together with its
+/// Topic and QoS. Serialized as a ParameterList with the PL_CDR
+/// encapsulation (9.6.2.2); the QoS policies with default values are
+/// omitted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiscoveredWriterData {
+    /// Identifies the remote Writer, also the key of the data.
+    pub remote_writer_guid: GUID_t,
+
+    /// List of unicast locators (transport, address, port combinations) that
+    /// can be used to send messages to the matched Writer. The list may be
+    /// empty, the default locators of the Participant are used then.
+    pub unicast_locator_list: LocatorList_t,
+
+    /// List of multicast locators (transport, address, port combinations)
+    /// that can be used to send messages to the matched Writer. The list may
+    /// be empty.
+    pub multicast_locator_list: LocatorList_t,
+
+    pub topic_name: String,
+    pub type_name: String,
+    pub durability: DurabilityQosPolicy,
+    pub deadline: DeadlineQosPolicy,
+    pub liveliness: LivelinessQosPolicy,
+    pub reliability: ReliabilityQosPolicy,
+    pub ownership: OwnershipQosPolicy,
+    pub destination_order: DestinationOrderQosPolicy,
+    pub presentation: PresentationQosPolicy,
+    pub partition: PartitionQosPolicy,
+    pub latency_budget: LatencyBudgetQosPolicy,
+    pub lifespan: LifespanQosPolicy,
+    pub durability_service: DurabilityServiceQosPolicy,
+    pub history: HistoryQosPolicy,
+    pub resource_limits: ResourceLimitsQosPolicy,
+    pub transport_priority: TransportPriorityQosPolicy,
+    pub ownership_strength: OwnershipStrengthQosPolicy,
+    pub user_data: UserDataQosPolicy,
+    pub topic_data: TopicDataQosPolicy,
+    pub group_data: GroupDataQosPolicy,
+}
+
+impl DiscoveredWriterData {
+    /// The data of the Writer with the default QoS of the DataWriter.
+    pub fn new(remote_writer_guid: GUID_t, topic_name: &str, type_name: &str) -> Self {
+        DiscoveredWriterData {
+            remote_writer_guid,
+            unicast_locator_list: vec![],
+            multicast_locator_list: vec![],
+            topic_name: topic_name.to_string(),
+            type_name: type_name.to_string(),
+            durability: DurabilityQosPolicy::default(),
+            deadline: DeadlineQosPolicy::default(),
+            liveliness: LivelinessQosPolicy::default(),
+            reliability: ReliabilityQosPolicy::reliable(),
+            ownership: OwnershipQosPolicy::default(),
+            destination_order: DestinationOrderQosPolicy::default(),
+            presentation: PresentationQosPolicy::default(),
+            partition: PartitionQosPolicy::default(),
+            latency_budget: LatencyBudgetQosPolicy::default(),
+            lifespan: LifespanQosPolicy::default(),
+            durability_service: DurabilityServiceQosPolicy::default(),
+            history: HistoryQosPolicy::default(),
+            resource_limits: ResourceLimitsQosPolicy::default(),
+            transport_priority: TransportPriorityQosPolicy::default(),
+            ownership_strength: OwnershipStrengthQosPolicy::default(),
+            user_data: UserDataQosPolicy::default(),
+            topic_data: TopicDataQosPolicy::default(),
+            group_data: GroupDataQosPolicy::default(),
+        }
+    }
+
+    /// The GUID of the Participant containing the Writer.
+    pub fn participant_guid(&self) -> GUID_t {
+        GUID_t {
+            guid_prefix: self.remote_writer_guid.guid_prefix,
+            entity_id: EntityId_t::ENTITYID_PARTICIPANT,
+        }
+    }
+
+    /// The key of the data, the GUID of the Writer.
+    pub fn key_hash(&self) -> InstanceHandle_t {
+        pl_cdr::key_hash(self.remote_writer_guid)
+    }
+
+    /// Serializes the data with the PL_CDR encapsulation of the endianness.
+    pub fn serialize(&self, endianness: Endianness) -> Bytes {
+        pl_cdr::serialize(&self.to_parameter_list(endianness), endianness)
+    }
+
+    /// Deserializes the data with the PL_CDR encapsulation. The parameters
+    /// not known to this implementation are ignored.
+    pub fn deserialize(payload: &[u8]) -> Result<Self, Error> {
+        let (endianness, parameter_list) = pl_cdr::deserialize(payload)?;
+        DiscoveredWriterData::from_parameter_list(&parameter_list, endianness)
+    }
+
+    pub fn to_parameter_list(&self, endianness: Endianness) -> ParameterList {
+        let mut parameter_list = ParameterList::new();
+        parameter_list.push(pl_cdr::parameter(
+            ParameterId::PID_ENDPOINT_GUID,
+            &self.remote_writer_guid,
+            endianness,
+        ));
+        parameter_list.push(pl_cdr::parameter(
+            ParameterId::PID_PARTICIPANT_GUID,
+            &self.participant_guid(),
+            endianness,
+        ));
+        parameter_list.push(pl_cdr::string_parameter(
+            ParameterId::PID_TOPIC_NAME,
+            &self.topic_name,
+            endianness,
+        ));
+        parameter_list.push(pl_cdr::string_parameter(
+            ParameterId::PID_TYPE_NAME,
+            &self.type_name,
+            endianness,
+        ));
+        for locator in &self.unicast_locator_list {
+            parameter_list.push(pl_cdr::parameter(
+                ParameterId::PID_UNICAST_LOCATOR,
+                locator,
+                endianness,
+            ));
+        }
+        for locator in &self.multicast_locator_list {
+            parameter_list.push(pl_cdr::parameter(
+                ParameterId::PID_MULTICAST_LOCATOR,
+                locator,
+                endianness,
+            ));
+        }
+        // The default reliability differs between the Writers and the
+        // Readers, it is always announced
+        parameter_list.push(pl_cdr::parameter(
+            ParameterId::PID_RELIABILITY,
+            &self.reliability,
+            endianness,
+        ));
+        pl_cdr::push_if_not_default(
+            &mut parameter_list,
+            ParameterId::PID_DURABILITY,
+            &self.durability,
+            endianness,
+        );
+        pl_cdr::push_if_not_default(
+            &mut parameter_list,
+            ParameterId::PID_DEADLINE,
+            &self.deadline,
+            endianness,
+        );
+        pl_cdr::push_if_not_default(
+            &mut parameter_list,
+            ParameterId::PID_LIVELINESS,
+            &self.liveliness,
+            endianness,
+        );
+        pl_cdr::push_if_not_default(
+            &mut parameter_list,
+            ParameterId::PID_OWNERSHIP,
+            &self.ownership,
+            endianness,
+        );
+        pl_cdr::push_if_not_default(
+            &mut parameter_list,
+            ParameterId::PID_DESTINATION_ORDER,
+            &self.destination_order,
+            endianness,
+        );
+        pl_cdr::push_if_not_default(
+            &mut parameter_list,
+            ParameterId::PID_PRESENTATION,
+            &self.presentation,
+            endianness,
+        );
+        pl_cdr::push_if_not_default(
+            &mut parameter_list,
+            ParameterId::PID_LATENCY_BUDGET,
+            &self.latency_budget,
+            endianness,
+        );
+        pl_cdr::push_if_not_default(
+            &mut parameter_list,
+            ParameterId::PID_LIFESPAN,
+            &self.lifespan,
+            endianness,
+        );
+        pl_cdr::push_if_not_default(
+            &mut parameter_list,
+            ParameterId::PID_DURABILITY_SERVICE,
+            &self.durability_service,
+            endianness,
+        );
+        pl_cdr::push_if_not_default(
+            &mut parameter_list,
+            ParameterId::PID_HISTORY,
+            &self.history,
+            endianness,
+        );
+        pl_cdr::push_if_not_default(
+            &mut parameter_list,
+            ParameterId::PID_RESOURCE_LIMITS,
+            &self.resource_limits,
+            endianness,
+        );
+        pl_cdr::push_if_not_default(
+            &mut parameter_list,
+            ParameterId::PID_TRANSPORT_PRIO,
+            &self.transport_priority,
+            endianness,
+        );
+        pl_cdr::push_if_not_default(
+            &mut parameter_list,
+            ParameterId::PID_OWNERSHIP_STRENGTH,
+            &self.ownership_strength,
+            endianness,
+        );
+        pl_cdr::push_if_not_default(
+            &mut parameter_list,
+            ParameterId::PID_USER_DATA,
+            &self.user_data,
+            endianness,
+        );
+        pl_cdr::push_if_not_default(
+            &mut parameter_list,
+            ParameterId::PID_TOPIC_DATA,
+            &self.topic_data,
+            endianness,
+        );
+        pl_cdr::push_if_not_default(
+            &mut parameter_list,
+            ParameterId::PID_GROUP_DATA,
+            &self.group_data,
+            endianness,
+        );
+        if !self.partition.name.is_empty() {
+            parameter_list.push(pl_cdr::strings_parameter(
+                ParameterId::PID_PARTITION,
+                &self.partition.name,
+                endianness,
+            ));
+        }
+        parameter_list
+    }
+
+    pub fn from_parameter_list(
+        parameter_list: &ParameterList,
+        endianness: Endianness,
+    ) -> Result<Self, Error> {
+        let topic_name = parameter_list
+            .find(&ParameterId::PID_TOPIC_NAME)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Missing PID_TOPIC_NAME"))?;
+        let type_name = parameter_list
+            .find(&ParameterId::PID_TYPE_NAME)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Missing PID_TYPE_NAME"))?;
+        let partition = parameter_list
+            .find(&ParameterId::PID_PARTITION)
+            .map(|parameter| pl_cdr::read_strings(parameter, endianness))
+            .transpose()?
+            .unwrap_or_default();
+
+        let writer_data = DiscoveredWriterData {
+            remote_writer_guid: pl_cdr::read_endpoint_guid(parameter_list, endianness)?,
+            unicast_locator_list: pl_cdr::read_locators(
+                parameter_list,
+                &ParameterId::PID_UNICAST_LOCATOR,
+                endianness,
+            )?,
+            multicast_locator_list: pl_cdr::read_locators(
+                parameter_list,
+                &ParameterId::PID_MULTICAST_LOCATOR,
+                endianness,
+            )?,
+            topic_name: pl_cdr::read_string(topic_name, endianness)?,
+            type_name: pl_cdr::read_string(type_name, endianness)?,
+            durability: pl_cdr::read_or_default(
+                parameter_list,
+                ParameterId::PID_DURABILITY,
+                endianness,
+            )?,
+            deadline: pl_cdr::read_or_default(
+                parameter_list,
+                ParameterId::PID_DEADLINE,
+                endianness,
+            )?,
+            liveliness: pl_cdr::read_or_default(
+                parameter_list,
+                ParameterId::PID_LIVELINESS,
+                endianness,
+            )?,
+            reliability: pl_cdr::read_optional(
+                parameter_list,
+                &ParameterId::PID_RELIABILITY,
+                endianness,
+            )?
+            .unwrap_or_else(ReliabilityQosPolicy::reliable),
+            ownership: pl_cdr::read_or_default(
+                parameter_list,
+                ParameterId::PID_OWNERSHIP,
+                endianness,
+            )?,
+            destination_order: pl_cdr::read_or_default(
+                parameter_list,
+                ParameterId::PID_DESTINATION_ORDER,
+                endianness,
+            )?,
+            presentation: pl_cdr::read_or_default(
+                parameter_list,
+                ParameterId::PID_PRESENTATION,
+                endianness,
+            )?,
+            partition: PartitionQosPolicy { name: partition },
+            latency_budget: pl_cdr::read_or_default(
+                parameter_list,
+                ParameterId::PID_LATENCY_BUDGET,
+                endianness,
+            )?,
+            lifespan: pl_cdr::read_or_default(
+                parameter_list,
+                ParameterId::PID_LIFESPAN,
+                endianness,
+            )?,
+            durability_service: pl_cdr::read_or_default(
+                parameter_list,
+                ParameterId::PID_DURABILITY_SERVICE,
+                endianness,
+            )?,
+            history: pl_cdr::read_or_default(parameter_list, ParameterId::PID_HISTORY, endianness)?,
+            resource_limits: pl_cdr::read_or_default(
+                parameter_list,
+                ParameterId::PID_RESOURCE_LIMITS,
+                endianness,
+            )?,
+            transport_priority: pl_cdr::read_or_default(
+                parameter_list,
+                ParameterId::PID_TRANSPORT_PRIO,
+                endianness,
+            )?,
+            ownership_strength: pl_cdr::read_or_default(
+                parameter_list,
+                ParameterId::PID_OWNERSHIP_STRENGTH,
+                endianness,
+            )?,
+            user_data: pl_cdr::read_or_default(
+                parameter_list,
+                ParameterId::PID_USER_DATA,
+                endianness,
+            )?,
+            topic_data: pl_cdr::read_or_default(
+                parameter_list,
+                ParameterId::PID_TOPIC_DATA,
+                endianness,
+            )?,
+            group_data: pl_cdr::read_or_default(
+                parameter_list,
+                ParameterId::PID_GROUP_DATA,
+                endianness,
+            )?,
+        };
+        let durations = [
+            writer_data.deadline.period,
+            writer_data.liveliness.lease_duration,
+            writer_data.reliability.max_blocking_time,
+            writer_data.latency_budget.duration,
+            writer_data.lifespan.duration,
+            writer_data.durability_service.service_cleanup_delay,
+        ];
+        if !durations.iter().all(Validity::valid) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "Negative duration in the QoS",
+            ));
+        }
+        Ok(writer_data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dds::qos::durability_qos_policy::DurabilityQosPolicyKind;
+    use crate::dds::qos::history_qos_policy::HistoryQosPolicyKind;
+    use crate::dds::qos::reliability_qos_policy::ReliabilityQosPolicyKind;
+    use crate::messages::submessage_elements::parameter::Parameter;
+    use crate::structure::duration::Duration_t;
+    use crate::structure::guid_prefix::GuidPrefix_t;
+    use crate::structure::locator::{Ipv4Addr, Locator_t, SocketAddr};
+
+    const WRITER_GUID: GUID_t = GUID_t {
+        guid_prefix: GuidPrefix_t {
+            entity_key: [0x01; 12],
+        },
+        entity_id: EntityId_t::new([0x00, 0x00, 0x01], 0x02),
+    };
+
+    #[test]
+    fn writer_data_is_serialized_as_parameter_list() {
+        let writer_data = DiscoveredWriterData::new(WRITER_GUID, "ab", "T");
+
+        assert_eq!(
+            &[
+                0x00, 0x03, 0x00, 0x00, // PL_CDR_LE
+                0x5a, 0x00, 0x10, 0x00, // PID_ENDPOINT_GUID
+                0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, //
+                0x01, 0x01, 0x01, 0x01, 0x00, 0x00, 0x01, 0x02, //
+                0x50, 0x00, 0x10, 0x00, // PID_PARTICIPANT_GUID
+                0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, //
+                0x01, 0x01, 0x01, 0x01, 0x00, 0x00, 0x01, 0xc1, //
+                0x05, 0x00, 0x08, 0x00, // PID_TOPIC_NAME
+                0x03, 0x00, 0x00, 0x00, 0x61, 0x62, 0x00, 0x00, //
+                0x07, 0x00, 0x08, 0x00, // PID_TYPE_NAME
+                0x02, 0x00, 0x00, 0x00, 0x54, 0x00, 0x00, 0x00, //
+                0x1a, 0x00, 0x0c, 0x00, // PID_RELIABILITY
+                0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, //
+                0x9a, 0x99, 0x99, 0x19, // 100 ms
+                0x01, 0x00, 0x00, 0x00, // PID_SENTINEL
+            ],
+            &writer_data.serialize(Endianness::LittleEndian)[..]
+        );
+        assert_eq!(
+            writer_data,
+            DiscoveredWriterData::deserialize(&writer_data.serialize(Endianness::LittleEndian))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn writer_data_round_trips_in_big_endian() {
+        let writer_data = DiscoveredWriterData {
+            unicast_locator_list: vec![Locator_t::from(SocketAddr::new(
+                Ipv4Addr::new(192, 168, 1, 2).into(),
+                7411,
+            ))],
+            durability: DurabilityQosPolicy {
+                kind: DurabilityQosPolicyKind::TRANSIENT_LOCAL,
+            },
+            deadline: DeadlineQosPolicy {
+                period: Duration_t::from(std::time::Duration::from_secs(1)),
+            },
+            reliability: ReliabilityQosPolicy {
+                kind: ReliabilityQosPolicyKind::BEST_EFFORT,
+                max_blocking_time: Duration_t::DURATION_ZERO,
+            },
+            partition: PartitionQosPolicy {
+                name: vec!["a".to_string(), "b*".to_string()],
+            },
+            lifespan: LifespanQosPolicy {
+                duration: Duration_t::from(std::time::Duration::from_secs(10)),
+            },
+            durability_service: DurabilityServiceQosPolicy {
+                history_depth: 5,
+                ..DurabilityServiceQosPolicy::default()
+            },
+            history: HistoryQosPolicy {
+                kind: HistoryQosPolicyKind::KEEP_ALL,
+                depth: 1,
+            },
+            ownership_strength: OwnershipStrengthQosPolicy { value: 7 },
+            user_data: UserDataQosPolicy {
+                value: vec![0x61, 0x62, 0x63],
+            },
+            topic_data: TopicDataQosPolicy { value: vec![0x64] },
+            ..DiscoveredWriterData::new(WRITER_GUID, "topic", "type")
+        };
+
+        assert_eq!(
+            writer_data,
+            DiscoveredWriterData::deserialize(&writer_data.serialize(Endianness::BigEndian))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn key_hash_identifies_older_announcements() {
+        let mut parameter_list = DiscoveredWriterData::new(WRITER_GUID, "topic", "type")
+            .to_parameter_list(Endianness::LittleEndian);
+        parameter_list
+            .parameters
+            .retain(|parameter| parameter.parameter_id != ParameterId::PID_ENDPOINT_GUID);
+        let key_hash = pl_cdr::key_hash(WRITER_GUID);
+        parameter_list.push(Parameter::new(
+            ParameterId::PID_KEY_HASH,
+            key_hash.entity_key.to_vec(),
+        ));
+
+        let writer_data =
+            DiscoveredWriterData::from_parameter_list(&parameter_list, Endianness::LittleEndian)
+                .unwrap();
+
+        assert_eq!(WRITER_GUID, writer_data.remote_writer_guid);
+        assert_eq!(key_hash, writer_data.key_hash());
+    }
+
+    #[test]
+    fn qos_durations_are_decoded_in_seconds_and_fractions() {
+        let mut parameter_list = DiscoveredWriterData::new(WRITER_GUID, "topic", "type")
+            .to_parameter_list(Endianness::LittleEndian);
+        parameter_list.push(Parameter::new(
+            ParameterId::PID_DEADLINE,
+            vec![0xff, 0xff, 0xff, 0x7f, 0xff, 0xff, 0xff, 0xff],
+        ));
+        parameter_list.push(Parameter::new(
+            ParameterId::PID_LIFESPAN,
+            vec![0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80],
+        ));
+
+        let writer_data =
+            DiscoveredWriterData::from_parameter_list(&parameter_list, Endianness::LittleEndian)
+                .unwrap();
+
+        assert_eq!(Duration_t::DURATION_INFINITE, writer_data.deadline.period);
+        assert_eq!(
+            std::time::Duration::from_millis(2500),
+            std::time::Duration::from(writer_data.lifespan.duration)
+        );
+    }
+
+    #[test]
+    fn writer_data_with_negative_duration_is_rejected() {
+        let mut parameter_list = DiscoveredWriterData::new(WRITER_GUID, "topic", "type")
+            .to_parameter_list(Endianness::LittleEndian);
+        parameter_list.push(Parameter::new(
+            ParameterId::PID_DEADLINE,
+            vec![0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+        ));
+
+        assert_eq!(
+            ErrorKind::InvalidData,
+            DiscoveredWriterData::from_parameter_list(&parameter_list, Endianness::LittleEndian)
+                .unwrap_err()
+                .kind()
+        );
+    }
+
+    #[test]
+    fn writer_data_without_topic_is_rejected() {
+        let mut parameter_list = DiscoveredWriterData::new(WRITER_GUID, "topic", "type")
+            .to_parameter_list(Endianness::LittleEndian);
+        parameter_list
+            .parameters
+            .retain(|parameter| parameter.parameter_id != ParameterId::PID_TOPIC_NAME);
+
+        assert_eq!(
+            ErrorKind::InvalidData,
+            DiscoveredWriterData::from_parameter_list(&parameter_list, Endianness::LittleEndian)
+                .unwrap_err()
+                .kind()
+        );
+    }
+}
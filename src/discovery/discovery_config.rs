@@ -0,0 +1,25 @@
+use std::time::Duration;
+
+/// The timing of the discovery protocols of a Participant.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiscoveryConfig {
+    /// The period of the SPDP announcements of the Participant.
+    pub resend_period: Duration,
+
+    /// How long the remote Participants consider the Participant alive after
+    /// every announcement.
+    pub lease_duration: Duration,
+
+    /// The period of the Heartbeats of the SEDP built-in Writers.
+    pub heartbeat_period: Duration,
+}
+
+impl Default for DiscoveryConfig {
+    fn default() -> Self {
+        DiscoveryConfig {
+            resend_period: Duration::from_secs(30),
+            lease_duration: Duration::from_secs(100),
+            heartbeat_period: Duration::from_secs(1),
+        }
+    }
+}
@@ -0,0 +1,953 @@
+use crate::behavior::reader_locator::ReaderLocator;
+use crate::behavior::reader_proxy::{ChangeFilter, ReaderProxy};
+use crate::behavior::writer_proxy::WriterProxy;
+use crate::dds::filter::filter_fields::SampleDecoder;
+use crate::dds::qos::qos_policy_id::QosPolicyId_t;
+use crate::dds::qos::reliability_qos_policy::ReliabilityQosPolicyKind;
+use crate::dds::status::incompatible_qos_status::{
+    OfferedIncompatibleQosStatus, RequestedIncompatibleQosStatus,
+};
+use crate::discovery::discovered_reader_data::DiscoveredReaderData;
+use crate::discovery::discovered_writer_data::DiscoveredWriterData;
+use crate::protocol::event::Event;
+use crate::protocol::protocol_core::ProtocolCore;
+use crate::structure::cache_change::CacheChange;
+use crate::structure::guid::GUID_t;
+use crate::structure::locator::Locator_t;
+
+use log::debug;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+/// Matches the local Writers and Readers with the local and discovered
+/// remote ones. A Writer and a Reader are matched when they share the Topic
+/// name, type name and a partition, and the QoS offered by the Writer is
+/// compatible with the QoS requested by the Reader (the “requested vs
+/// offered” rules of DDS). The matched remote Readers are added to the local
+/// Writers as ReaderProxy, or as ReaderLocator for a StatelessWriter; the
+/// matched remote Writers are added to the local Readers as WriterProxy.
+///
+/// The content filters of the matched Readers are applied by the local
+/// StatefulWriters which have a SampleDecoder: the changes rejected by the
+/// filter of a Reader are sent to it as Gap.
+///
+/// The remote locator lists are expected to be complete, i.e. the default
+/// locators of the remote Participant are used if the Endpoint announced
+/// none.
+#[derive(Default)]
+pub struct EndpointMatcher {
+    local_writers: BTreeMap<GUID_t, DiscoveredWriterData>,
+    local_readers: BTreeMap<GUID_t, DiscoveredReaderData>,
+    remote_writers: BTreeMap<GUID_t, DiscoveredWriterData>,
+    remote_readers: BTreeMap<GUID_t, DiscoveredReaderData>,
+
+    /// The matched pairs of Writer and Reader.
+    matched: BTreeSet<(GUID_t, GUID_t)>,
+
+    /// The pairs of Writer and Reader found incompatible, reported once.
+    incompatible: BTreeSet<(GUID_t, GUID_t)>,
+
+    /// Decoders of the samples of the local Writers filtering on behalf of
+    /// the Readers.
+    sample_decoders: BTreeMap<GUID_t, SampleDecoder>,
+
+    offered_incompatible_qos: BTreeMap<GUID_t, OfferedIncompatibleQosStatus>,
+    requested_incompatible_qos: BTreeMap<GUID_t, RequestedIncompatibleQosStatus>,
+    events: VecDeque<Event>,
+}
+
+impl EndpointMatcher {
+    pub fn new() -> Self {
+        EndpointMatcher::default()
+    }
+
+    /// Adds or updates the local Writer installed in the core.
+    pub fn add_local_writer(&mut self, core: &mut ProtocolCore, writer_data: DiscoveredWriterData) {
+        let writer_guid = writer_data.remote_writer_guid;
+        self.local_writers.insert(writer_guid, writer_data);
+        self.offered_incompatible_qos
+            .entry(writer_guid)
+            .or_default();
+        let reader_guids: Vec<GUID_t> = self
+            .local_readers
+            .keys()
+            .chain(self.remote_readers.keys())
+            .cloned()
+            .collect();
+        for reader_guid in reader_guids {
+            self.update(core, writer_guid, reader_guid);
+        }
+    }
+
+    /// Enables the content filtering by the local Writer for the Readers
+    /// matched afterwards.
+    pub fn set_sample_decoder(&mut self, writer_guid: GUID_t, sample_decoder: SampleDecoder) {
+        self.sample_decoders.insert(writer_guid, sample_decoder);
+    }
+
+    /// Adds or updates the local Reader installed in the core.
+    pub fn add_local_reader(&mut self, core: &mut ProtocolCore, reader_data: DiscoveredReaderData) {
+        let reader_guid = reader_data.remote_reader_guid;
+        self.local_readers.insert(reader_guid, reader_data);
+        self.requested_incompatible_qos
+            .entry(reader_guid)
+            .or_default();
+        let writer_guids: Vec<GUID_t> = self
+            .local_writers
+            .keys()
+            .chain(self.remote_writers.keys())
+            .cloned()
+            .collect();
+        for writer_guid in writer_guids {
+            self.update(core, writer_guid, reader_guid);
+        }
+    }
+
+    /// Adds or updates the remote Writer announced by the discovery.
+    pub fn add_remote_writer(
+        &mut self,
+        core: &mut ProtocolCore,
+        writer_data: DiscoveredWriterData,
+    ) {
+        let writer_guid = writer_data.remote_writer_guid;
+        self.remote_writers.insert(writer_guid, writer_data);
+        let reader_guids: Vec<GUID_t> = self.local_readers.keys().cloned().collect();
+        for reader_guid in reader_guids {
+            self.update(core, writer_guid, reader_guid);
+        }
+    }
+
+    /// Adds or updates the remote Reader announced by the discovery.
+    pub fn add_remote_reader(
+        &mut self,
+        core: &mut ProtocolCore,
+        reader_data: DiscoveredReaderData,
+    ) {
+        let reader_guid = reader_data.remote_reader_guid;
+        self.remote_readers.insert(reader_guid, reader_data);
+        let writer_guids: Vec<GUID_t> = self.local_writers.keys().cloned().collect();
+        for writer_guid in writer_guids {
+            self.update(core, writer_guid, reader_guid);
+        }
+    }
+
+    /// Unmatches the Writer, local or remote, from all Readers and forgets
+    /// it.
+    pub fn remove_writer(&mut self, core: &mut ProtocolCore, writer_guid: GUID_t) {
+        let pairs: Vec<(GUID_t, GUID_t)> = self
+            .matched
+            .iter()
+            .filter(|(writer, _)| *writer == writer_guid)
+            .cloned()
+            .collect();
+        for (writer_guid, reader_guid) in pairs {
+            self.unmatch(core, writer_guid, reader_guid);
+        }
+        self.incompatible
+            .retain(|(writer, _)| *writer != writer_guid);
+        self.local_writers.remove(&writer_guid);
+        self.remote_writers.remove(&writer_guid);
+        self.offered_incompatible_qos.remove(&writer_guid);
+        self.sample_decoders.remove(&writer_guid);
+    }
+
+    /// Unmatches the Reader, local or remote, from all Writers and forgets
+    /// it.
+    pub fn remove_reader(&mut self, core: &mut ProtocolCore, reader_guid: GUID_t) {
+        let pairs: Vec<(GUID_t, GUID_t)> = self
+            .matched
+            .iter()
+            .filter(|(_, reader)| *reader == reader_guid)
+            .cloned()
+            .collect();
+        for (writer_guid, reader_guid) in pairs {
+            self.unmatch(core, writer_guid, reader_guid);
+        }
+        self.incompatible
+            .retain(|(_, reader)| *reader != reader_guid);
+        self.local_readers.remove(&reader_guid);
+        self.remote_readers.remove(&reader_guid);
+        self.requested_incompatible_qos.remove(&reader_guid);
+    }
+
+    #[cfg(test)]
+    pub fn is_matched(&self, writer_guid: GUID_t, reader_guid: GUID_t) -> bool {
+        self.matched.contains(&(writer_guid, reader_guid))
+    }
+
+    /// The Readers matched with the Writer.
+    pub fn matched_readers(&self, writer_guid: GUID_t) -> impl Iterator<Item = GUID_t> + '_ {
+        self.matched
+            .iter()
+            .filter(move |(writer, _)| *writer == writer_guid)
+            .map(|(_, reader)| *reader)
+    }
+
+    /// The Writers matched with the Reader.
+    pub fn matched_writers(&self, reader_guid: GUID_t) -> impl Iterator<Item = GUID_t> + '_ {
+        self.matched
+            .iter()
+            .filter(move |(_, reader)| *reader == reader_guid)
+            .map(|(writer, _)| *writer)
+    }
+
+    /// Reads the OFFERED_INCOMPATIBLE_QOS status of the local Writer.
+    pub fn offered_incompatible_qos_status(
+        &mut self,
+        writer_guid: GUID_t,
+    ) -> Option<OfferedIncompatibleQosStatus> {
+        self.offered_incompatible_qos
+            .get_mut(&writer_guid)
+            .map(OfferedIncompatibleQosStatus::read)
+    }
+
+    /// Reads the REQUESTED_INCOMPATIBLE_QOS status of the local Reader.
+    pub fn requested_incompatible_qos_status(
+        &mut self,
+        reader_guid: GUID_t,
+    ) -> Option<RequestedIncompatibleQosStatus> {
+        self.requested_incompatible_qos
+            .get_mut(&reader_guid)
+            .map(RequestedIncompatibleQosStatus::read)
+    }
+
+    pub fn poll_event(&mut self) -> Option<Event> {
+        self.events.pop_front()
+    }
+
+    /// The data of the local or remote Writer.
+    pub fn writer_data(&self, writer_guid: GUID_t) -> Option<&DiscoveredWriterData> {
+        self.local_writers
+            .get(&writer_guid)
+            .or_else(|| self.remote_writers.get(&writer_guid))
+    }
+
+    fn reader_data(&self, reader_guid: GUID_t) -> Option<&DiscoveredReaderData> {
+        self.local_readers
+            .get(&reader_guid)
+            .or_else(|| self.remote_readers.get(&reader_guid))
+    }
+
+    /// Matches or unmatches the Writer and the Reader according to their
+    /// current data.
+    fn update(&mut self, core: &mut ProtocolCore, writer_guid: GUID_t, reader_guid: GUID_t) {
+        let (writer_data, reader_data) =
+            match (self.writer_data(writer_guid), self.reader_data(reader_guid)) {
+                (Some(writer_data), Some(reader_data)) => {
+                    (writer_data.clone(), reader_data.clone())
+                }
+                _ => return,
+            };
+        if writer_data.topic_name != reader_data.topic_name
+            || writer_data.type_name != reader_data.type_name
+            || !writer_data.partition.matches(&reader_data.partition)
+        {
+            self.incompatible.remove(&(writer_guid, reader_guid));
+            self.unmatch(core, writer_guid, reader_guid);
+            return;
+        }
+
+        let policy_ids = incompatible_policies(&writer_data, &reader_data);
+        if policy_ids.is_empty() {
+            self.incompatible.remove(&(writer_guid, reader_guid));
+            self.match_endpoints(core, &writer_data, &reader_data);
+        } else {
+            self.unmatch(core, writer_guid, reader_guid);
+            if self.incompatible.insert((writer_guid, reader_guid)) {
+                self.report_incompatible(writer_guid, reader_guid, &policy_ids);
+            }
+        }
+    }
+
+    fn match_endpoints(
+        &mut self,
+        core: &mut ProtocolCore,
+        writer_data: &DiscoveredWriterData,
+        reader_data: &DiscoveredReaderData,
+    ) {
+        let writer_guid = writer_data.remote_writer_guid;
+        let reader_guid = reader_data.remote_reader_guid;
+        if !self.matched.insert((writer_guid, reader_guid)) {
+            return;
+        }
+
+        if self.local_writers.contains_key(&writer_guid) {
+            let writer_id = writer_guid.entity_id;
+            if let Some(writer) = core.writer_mut(writer_id) {
+                let mut reader_proxy = ReaderProxy::new(
+                    reader_guid,
+                    reader_data.unicast_locator_list.clone(),
+                    reader_data.multicast_locator_list.clone(),
+                    reader_data.expects_inline_qos,
+                );
+                reader_proxy.set_reliable(
+                    reader_data.reliability.kind == ReliabilityQosPolicyKind::RELIABLE,
+                );
+                reader_proxy.set_content_filter(
+                    self.sample_decoders
+                        .get(&writer_guid)
+                        .and_then(|sample_decoder| content_filter(reader_data, sample_decoder)),
+                );
+                writer.matched_reader_add(reader_proxy);
+            } else if let Some(writer) = core.stateless_writer_mut(writer_id) {
+                for locator in reader_locators(reader_data) {
+                    if writer
+                        .reader_locators()
+                        .iter()
+                        .all(|reader_locator| reader_locator.locator() != locator)
+                    {
+                        writer.reader_locator_add(ReaderLocator::new(
+                            locator,
+                            reader_data.expects_inline_qos,
+                        ));
+                    }
+                }
+            }
+            self.events.push_back(Event::PublicationMatched {
+                writer_guid,
+                reader_guid,
+            });
+        }
+
+        if self.local_readers.contains_key(&reader_guid) {
+            if let Some(reader) = core.reader_mut(reader_guid.entity_id) {
+                reader.matched_writer_add(WriterProxy::new(
+                    writer_guid,
+                    writer_data.unicast_locator_list.clone(),
+                    writer_data.multicast_locator_list.clone(),
+                ));
+            }
+            self.events.push_back(Event::SubscriptionMatched {
+                reader_guid,
+                writer_guid,
+            });
+        }
+    }
+
+    fn unmatch(&mut self, core: &mut ProtocolCore, writer_guid: GUID_t, reader_guid: GUID_t) {
+        if !self.matched.remove(&(writer_guid, reader_guid)) {
+            return;
+        }
+
+        if self.local_writers.contains_key(&writer_guid) {
+            let writer_id = writer_guid.entity_id;
+            if let Some(writer) = core.writer_mut(writer_id) {
+                writer.matched_reader_remove(reader_guid);
+            } else if let Some(writer) = core.stateless_writer_mut(writer_id) {
+                // The locators may be shared with the other matched Readers
+                let still_used: Vec<Locator_t> = self
+                    .matched
+                    .iter()
+                    .filter(|(writer, _)| *writer == writer_guid)
+                    .filter_map(|(_, reader)| {
+                        self.local_readers
+                            .get(reader)
+                            .or_else(|| self.remote_readers.get(reader))
+                    })
+                    .flat_map(reader_locators)
+                    .collect();
+                if let Some(reader_data) = self
+                    .local_readers
+                    .get(&reader_guid)
+                    .or_else(|| self.remote_readers.get(&reader_guid))
+                {
+                    for locator in reader_locators(reader_data) {
+                        if !still_used.contains(&locator) {
+                            writer.reader_locator_remove(locator);
+                        }
+                    }
+                }
+            }
+            self.events.push_back(Event::PublicationUnmatched {
+                writer_guid,
+                reader_guid,
+            });
+        }
+
+        if self.local_readers.contains_key(&reader_guid) {
+            if let Some(reader) = core.reader_mut(reader_guid.entity_id) {
+                reader.matched_writer_remove(writer_guid);
+            }
+            self.events.push_back(Event::SubscriptionUnmatched {
+                reader_guid,
+                writer_guid,
+            });
+        }
+    }
+
+    fn report_incompatible(
+        &mut self,
+        writer_guid: GUID_t,
+        reader_guid: GUID_t,
+        policy_ids: &[QosPolicyId_t],
+    ) {
+        if let Some(status) = self.offered_incompatible_qos.get_mut(&writer_guid) {
+            status.increment(policy_ids);
+            self.events.push_back(Event::OfferedIncompatibleQos {
+                writer_guid,
+                reader_guid,
+                policy_id: status.last_policy_id,
+            });
+        }
+        if let Some(status) = self.requested_incompatible_qos.get_mut(&reader_guid) {
+            status.increment(policy_ids);
+            self.events.push_back(Event::RequestedIncompatibleQos {
+                reader_guid,
+                writer_guid,
+                policy_id: status.last_policy_id,
+            });
+        }
+    }
+}
+
+/// The policies for which the QoS offered by the Writer does not satisfy the
+/// QoS requested by the Reader, in the order they are checked.
+pub fn incompatible_policies(
+    offered: &DiscoveredWriterData,
+    requested: &DiscoveredReaderData,
+) -> Vec<QosPolicyId_t> {
+    let mut policy_ids = vec![];
+    if offered.reliability.kind < requested.reliability.kind {
+        policy_ids.push(QosPolicyId_t::RELIABILITY_QOS_POLICY_ID);
+    }
+    if offered.durability.kind < requested.durability.kind {
+        policy_ids.push(QosPolicyId_t::DURABILITY_QOS_POLICY_ID);
+    }
+    if offered.deadline.period > requested.deadline.period {
+        policy_ids.push(QosPolicyId_t::DEADLINE_QOS_POLICY_ID);
+    }
+    if offered.latency_budget.duration > requested.latency_budget.duration {
+        policy_ids.push(QosPolicyId_t::LATENCYBUDGET_QOS_POLICY_ID);
+    }
+    if offered.liveliness.kind < requested.liveliness.kind
+        || offered.liveliness.lease_duration > requested.liveliness.lease_duration
+    {
+        policy_ids.push(QosPolicyId_t::LIVELINESS_QOS_POLICY_ID);
+    }
+    if offered.ownership.kind != requested.ownership.kind {
+        policy_ids.push(QosPolicyId_t::OWNERSHIP_QOS_POLICY_ID);
+    }
+    if offered.destination_order.kind < requested.destination_order.kind {
+        policy_ids.push(QosPolicyId_t::DESTINATIONORDER_QOS_POLICY_ID);
+    }
+    if offered.presentation.access_scope < requested.presentation.access_scope
+        || (requested.presentation.coherent_access && !offered.presentation.coherent_access)
+        || (requested.presentation.ordered_access && !offered.presentation.ordered_access)
+    {
+        policy_ids.push(QosPolicyId_t::PRESENTATION_QOS_POLICY_ID);
+    }
+    policy_ids
+}
+
+/// The filter of the changes relevant to the Reader, None if the Reader has
+/// no content filter or it cannot be applied by the Writer. The samples which
+/// cannot be evaluated are relevant, the Reader filters them itself.
+fn content_filter(
+    reader_data: &DiscoveredReaderData,
+    sample_decoder: &SampleDecoder,
+) -> Option<ChangeFilter> {
+    let content_filter = reader_data.content_filter.as_ref()?;
+    let filter_expression = match content_filter.to_filter_expression() {
+        Ok(filter_expression) => filter_expression,
+        Err(error) => {
+            debug!(
+                "Not filtering for {:?}: {}",
+                reader_data.remote_reader_guid, error
+            );
+            return None;
+        }
+    };
+    let sample_decoder = sample_decoder.clone();
+    Some(Box::new(move |change: &CacheChange| {
+        sample_decoder(&change.data_value.value)
+            .and_then(|fields| filter_expression.evaluate(&*fields))
+            .unwrap_or(true)
+    }))
+}
+
+fn reader_locators(reader_data: &DiscoveredReaderData) -> impl Iterator<Item = Locator_t> + '_ {
+    reader_data
+        .unicast_locator_list
+        .iter()
+        .chain(&reader_data.multicast_locator_list)
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::behavior::reader::ReaderAttributes;
+    use crate::behavior::stateful_reader::StatefulReader;
+    use crate::behavior::stateful_writer::StatefulWriter;
+    use crate::behavior::stateless_writer::StatelessWriter;
+    use crate::behavior::writer::{Writer, WriterAttributes};
+    use crate::dds::filter::filter_fields::FilterFields;
+    use crate::dds::filter::filter_value::FilterValue;
+    use crate::dds::qos::destination_order_qos_policy::DestinationOrderQosPolicyKind;
+    use crate::dds::qos::durability_qos_policy::DurabilityQosPolicyKind;
+    use crate::dds::qos::ownership_qos_policy::OwnershipQosPolicyKind;
+    use crate::dds::qos::presentation_qos_policy::PresentationQosPolicyAccessScopeKind;
+    use crate::dds::qos::reliability_qos_policy::{ReliabilityQosPolicy, ReliabilityQosPolicyKind};
+    use crate::discovery::content_filter_property::ContentFilterProperty_t;
+    use crate::messages::submessage::EntitySubmessage;
+    use crate::messages::vendor_id::VendorId_t;
+    use crate::structure::change_kind::ChangeKind_t;
+    use crate::structure::data::Data;
+    use crate::structure::duration::Duration_t;
+    use crate::structure::endpoint::EndpointAttributes;
+    use crate::structure::entity::EntityAttributes;
+    use crate::structure::entity_id::EntityId_t;
+    use crate::structure::guid_prefix::GuidPrefix_t;
+    use crate::structure::history_cache::HistoryCache;
+    use crate::structure::instance_handle::InstanceHandle_t;
+    use crate::structure::locator::{Ipv4Addr, SocketAddr};
+    use crate::structure::reliability_kind::ReliabilityKind_t;
+    use crate::structure::sequence_number::SequenceNumber_t;
+    use crate::structure::topic_kind::TopicKind_t;
+    use bytes::Bytes;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    const LOCAL: u8 = 0x01;
+    const REMOTE: u8 = 0x02;
+
+    fn guid(participant: u8, entity_key: u8, entity_kind: u8) -> GUID_t {
+        GUID_t {
+            guid_prefix: GuidPrefix_t::from([participant; 12]),
+            entity_id: EntityId_t::new([0x00, 0x00, entity_key], entity_kind),
+        }
+    }
+
+    fn writer_guid(participant: u8) -> GUID_t {
+        guid(
+            participant,
+            0x01,
+            EntityId_t::ENTITYKIND_USER_DEFINED_WRITER_WITH_KEY,
+        )
+    }
+
+    fn reader_guid(participant: u8) -> GUID_t {
+        guid(
+            participant,
+            0x02,
+            EntityId_t::ENTITYKIND_USER_DEFINED_READER_WITH_KEY,
+        )
+    }
+
+    fn locator(port: u16) -> Locator_t {
+        Locator_t::from(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), port))
+    }
+
+    fn endpoint(reliability_level: ReliabilityKind_t) -> EndpointAttributes {
+        EndpointAttributes {
+            topic_kind: TopicKind_t::WITH_KEY,
+            reliability_level,
+            unicast_locator_list: vec![],
+            multicast_locator_list: vec![],
+        }
+    }
+
+    fn writer_attributes() -> WriterAttributes {
+        WriterAttributes {
+            push_mode: true,
+            heartbeat_period: Duration_t::from(Duration::from_secs(1)),
+            nack_response_delay: Duration_t::DURATION_ZERO,
+            nack_suppression_duration: Duration_t::DURATION_ZERO,
+            last_change_sequence_number: SequenceNumber_t::from(0),
+            data_max_size_serialized: 1024,
+            fragment_size: 1024,
+            writer_cache: HistoryCache::new(),
+        }
+    }
+
+    fn core() -> ProtocolCore {
+        let mut core =
+            ProtocolCore::new(GuidPrefix_t::from([LOCAL; 12]), VendorId_t::VENDOR_UNKNOWN);
+        core.add_writer(
+            Instant::now(),
+            StatefulWriter::new(
+                EntityAttributes {
+                    guid: writer_guid(LOCAL),
+                },
+                endpoint(ReliabilityKind_t::RELIABLE),
+                writer_attributes(),
+            ),
+        );
+        core.add_reader(StatefulReader::new(
+            EntityAttributes {
+                guid: reader_guid(LOCAL),
+            },
+            endpoint(ReliabilityKind_t::RELIABLE),
+            ReaderAttributes {
+                expects_inline_qos: false,
+                heartbeat_response_delay: Duration_t::DURATION_ZERO,
+                heartbeat_suppression_duration: Duration_t::DURATION_ZERO,
+                reader_cache: HistoryCache::new(),
+            },
+        ));
+        core
+    }
+
+    fn remote_reader_data(topic_name: &str) -> DiscoveredReaderData {
+        let mut reader_data = DiscoveredReaderData::new(reader_guid(REMOTE), topic_name, "type");
+        reader_data.unicast_locator_list = vec![locator(7411)];
+        reader_data
+    }
+
+    fn remote_writer_data(topic_name: &str) -> DiscoveredWriterData {
+        let mut writer_data = DiscoveredWriterData::new(writer_guid(REMOTE), topic_name, "type");
+        writer_data.unicast_locator_list = vec![locator(7411)];
+        writer_data
+    }
+
+    fn events(matcher: &mut EndpointMatcher) -> Vec<Event> {
+        std::iter::from_fn(|| matcher.poll_event()).collect()
+    }
+
+    #[test]
+    fn endpoints_of_same_topic_are_matched() {
+        let mut core = core();
+        let mut matcher = EndpointMatcher::new();
+        matcher.add_local_writer(
+            &mut core,
+            DiscoveredWriterData::new(writer_guid(LOCAL), "topic", "type"),
+        );
+        matcher.add_local_reader(
+            &mut core,
+            DiscoveredReaderData::new(reader_guid(LOCAL), "other", "type"),
+        );
+
+        matcher.add_remote_reader(&mut core, remote_reader_data("topic"));
+        matcher.add_remote_writer(&mut core, remote_writer_data("topic"));
+
+        assert_eq!(
+            vec![Event::PublicationMatched {
+                writer_guid: writer_guid(LOCAL),
+                reader_guid: reader_guid(REMOTE),
+            }],
+            events(&mut matcher)
+        );
+        assert_eq!(
+            1,
+            core.writer_mut(writer_guid(LOCAL).entity_id)
+                .unwrap()
+                .matched_reader_lookup(reader_guid(REMOTE))
+                .count()
+        );
+        assert!(core
+            .reader(reader_guid(LOCAL).entity_id)
+            .unwrap()
+            .matched_writer_lookup(writer_guid(REMOTE))
+            .is_none());
+    }
+
+    #[test]
+    fn local_endpoints_are_matched_with_each_other() {
+        let mut core = core();
+        let mut matcher = EndpointMatcher::new();
+        matcher.add_local_reader(
+            &mut core,
+            DiscoveredReaderData::new(reader_guid(LOCAL), "topic", "type"),
+        );
+        matcher.add_local_writer(
+            &mut core,
+            DiscoveredWriterData::new(writer_guid(LOCAL), "topic", "type"),
+        );
+
+        assert!(matcher.is_matched(writer_guid(LOCAL), reader_guid(LOCAL)));
+        assert_eq!(
+            vec![
+                Event::PublicationMatched {
+                    writer_guid: writer_guid(LOCAL),
+                    reader_guid: reader_guid(LOCAL),
+                },
+                Event::SubscriptionMatched {
+                    reader_guid: reader_guid(LOCAL),
+                    writer_guid: writer_guid(LOCAL),
+                },
+            ],
+            events(&mut matcher)
+        );
+        assert!(core
+            .reader(reader_guid(LOCAL).entity_id)
+            .unwrap()
+            .matched_writer_lookup(writer_guid(LOCAL))
+            .is_some());
+    }
+
+    #[test]
+    fn endpoints_of_different_partitions_or_types_are_not_matched() {
+        let mut core = core();
+        let mut matcher = EndpointMatcher::new();
+        let mut writer_data = DiscoveredWriterData::new(writer_guid(LOCAL), "topic", "type");
+        writer_data.partition.name = vec!["sensors".to_string()];
+        matcher.add_local_writer(&mut core, writer_data.clone());
+
+        matcher.add_remote_reader(&mut core, remote_reader_data("topic"));
+        let mut other_type = remote_reader_data("topic");
+        other_type.remote_reader_guid = guid(REMOTE, 0x03, 0x07);
+        other_type.type_name = "other".to_string();
+        other_type.partition.name = vec!["sens*".to_string()];
+        matcher.add_remote_reader(&mut core, other_type);
+        assert_eq!(0, matcher.matched_readers(writer_guid(LOCAL)).count());
+
+        // Moving the Writer to the default partition matches the Reader
+        writer_data.partition.name = vec![];
+        matcher.add_local_writer(&mut core, writer_data);
+        assert_eq!(
+            vec![reader_guid(REMOTE)],
+            matcher
+                .matched_readers(writer_guid(LOCAL))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn incompatible_qos_is_reported_with_policy() {
+        let mut core = core();
+        let mut matcher = EndpointMatcher::new();
+        let mut writer_data = DiscoveredWriterData::new(writer_guid(LOCAL), "topic", "type");
+        writer_data.reliability = ReliabilityQosPolicy::default();
+        matcher.add_local_writer(&mut core, writer_data);
+        let mut reader_data = remote_reader_data("topic");
+        reader_data.reliability.kind = ReliabilityQosPolicyKind::RELIABLE;
+        reader_data.durability.kind = DurabilityQosPolicyKind::TRANSIENT_LOCAL;
+
+        matcher.add_remote_reader(&mut core, reader_data.clone());
+        // Announcing the same Reader again is not another incompatibility
+        matcher.add_remote_reader(&mut core, reader_data);
+
+        assert_eq!(
+            vec![Event::OfferedIncompatibleQos {
+                writer_guid: writer_guid(LOCAL),
+                reader_guid: reader_guid(REMOTE),
+                policy_id: QosPolicyId_t::RELIABILITY_QOS_POLICY_ID,
+            }],
+            events(&mut matcher)
+        );
+        let status = matcher
+            .offered_incompatible_qos_status(writer_guid(LOCAL))
+            .unwrap();
+        assert_eq!(1, status.total_count);
+        assert_eq!(2, status.policies.len());
+        assert_eq!(0, matcher.matched_readers(writer_guid(LOCAL)).count());
+    }
+
+    #[test]
+    fn requested_incompatible_qos_is_reported_by_reader() {
+        let mut core = core();
+        let mut matcher = EndpointMatcher::new();
+        let mut reader_data = DiscoveredReaderData::new(reader_guid(LOCAL), "topic", "type");
+        reader_data.presentation.access_scope = PresentationQosPolicyAccessScopeKind::TOPIC;
+        matcher.add_local_reader(&mut core, reader_data);
+
+        matcher.add_remote_writer(&mut core, remote_writer_data("topic"));
+
+        assert_eq!(
+            vec![Event::RequestedIncompatibleQos {
+                reader_guid: reader_guid(LOCAL),
+                writer_guid: writer_guid(REMOTE),
+                policy_id: QosPolicyId_t::PRESENTATION_QOS_POLICY_ID,
+            }],
+            events(&mut matcher)
+        );
+        assert_eq!(
+            QosPolicyId_t::PRESENTATION_QOS_POLICY_ID,
+            matcher
+                .requested_incompatible_qos_status(reader_guid(LOCAL))
+                .unwrap()
+                .last_policy_id
+        );
+    }
+
+    #[test]
+    fn changed_qos_unmatches_endpoints() {
+        let mut core = core();
+        let mut matcher = EndpointMatcher::new();
+        matcher.add_local_reader(
+            &mut core,
+            DiscoveredReaderData::new(reader_guid(LOCAL), "topic", "type"),
+        );
+        let mut writer_data = remote_writer_data("topic");
+        matcher.add_remote_writer(&mut core, writer_data.clone());
+        events(&mut matcher);
+
+        writer_data.deadline.period = Duration_t::from(Duration::from_secs(1));
+        let mut reader_data = DiscoveredReaderData::new(reader_guid(LOCAL), "topic", "type");
+        reader_data.deadline.period = Duration_t::from(Duration::from_millis(500));
+        matcher.add_local_reader(&mut core, reader_data);
+        matcher.add_remote_writer(&mut core, writer_data);
+
+        assert_eq!(
+            vec![
+                Event::SubscriptionUnmatched {
+                    reader_guid: reader_guid(LOCAL),
+                    writer_guid: writer_guid(REMOTE),
+                },
+                Event::RequestedIncompatibleQos {
+                    reader_guid: reader_guid(LOCAL),
+                    writer_guid: writer_guid(REMOTE),
+                    policy_id: QosPolicyId_t::DEADLINE_QOS_POLICY_ID,
+                },
+            ],
+            events(&mut matcher)
+        );
+        assert!(core
+            .reader(reader_guid(LOCAL).entity_id)
+            .unwrap()
+            .matched_writer_lookup(writer_guid(REMOTE))
+            .is_none());
+    }
+
+    #[test]
+    fn removed_reader_is_unmatched() {
+        let mut core = core();
+        let mut matcher = EndpointMatcher::new();
+        matcher.add_local_writer(
+            &mut core,
+            DiscoveredWriterData::new(writer_guid(LOCAL), "topic", "type"),
+        );
+        matcher.add_remote_reader(&mut core, remote_reader_data("topic"));
+        events(&mut matcher);
+
+        matcher.remove_reader(&mut core, reader_guid(REMOTE));
+
+        assert_eq!(
+            vec![Event::PublicationUnmatched {
+                writer_guid: writer_guid(LOCAL),
+                reader_guid: reader_guid(REMOTE),
+            }],
+            events(&mut matcher)
+        );
+        assert_eq!(
+            0,
+            core.writer_mut(writer_guid(LOCAL).entity_id)
+                .unwrap()
+                .matched_reader_lookup(reader_guid(REMOTE))
+                .count()
+        );
+    }
+
+    #[test]
+    fn stateless_writer_gets_reader_locators() {
+        let mut core = core();
+        let stateless_writer_guid = guid(
+            LOCAL,
+            0x03,
+            EntityId_t::ENTITYKIND_USER_DEFINED_WRITER_NO_KEY,
+        );
+        core.add_stateless_writer(
+            Instant::now(),
+            StatelessWriter::new(
+                EntityAttributes {
+                    guid: stateless_writer_guid,
+                },
+                endpoint(ReliabilityKind_t::BEST_EFFORT),
+                writer_attributes(),
+                Duration_t::DURATION_INFINITE,
+            ),
+        );
+        let mut matcher = EndpointMatcher::new();
+        let mut writer_data = DiscoveredWriterData::new(stateless_writer_guid, "topic", "type");
+        writer_data.reliability = ReliabilityQosPolicy::default();
+        matcher.add_local_writer(&mut core, writer_data);
+        matcher.add_remote_reader(&mut core, remote_reader_data("topic"));
+        let mut second_reader = remote_reader_data("topic");
+        second_reader.remote_reader_guid = guid(REMOTE, 0x04, 0x07);
+        matcher.add_remote_reader(&mut core, second_reader);
+
+        let locators = |core: &mut ProtocolCore| {
+            core.stateless_writer_mut(stateless_writer_guid.entity_id)
+                .unwrap()
+                .reader_locators()
+                .iter()
+                .map(ReaderLocator::locator)
+                .collect::<Vec<_>>()
+        };
+        // The shared locator is added once and kept for the other Reader
+        assert_eq!(vec![locator(7411)], locators(&mut core));
+        matcher.remove_reader(&mut core, reader_guid(REMOTE));
+        assert_eq!(vec![locator(7411)], locators(&mut core));
+        matcher.remove_reader(&mut core, guid(REMOTE, 0x04, 0x07));
+        assert!(locators(&mut core).is_empty());
+    }
+
+    #[test]
+    fn writer_filters_changes_for_reader() {
+        let mut core = core();
+        let mut matcher = EndpointMatcher::new();
+        matcher.set_sample_decoder(
+            writer_guid(LOCAL),
+            Arc::new(|serialized_data: &[u8]| {
+                let value = std::str::from_utf8(serialized_data).ok()?.parse().ok()?;
+                let mut fields = BTreeMap::new();
+                fields.insert("value".to_string(), FilterValue::Integer(value));
+                Some(Box::new(fields) as Box<dyn FilterFields>)
+            }),
+        );
+        matcher.add_local_writer(
+            &mut core,
+            DiscoveredWriterData::new(writer_guid(LOCAL), "topic", "type"),
+        );
+        let mut reader_data = remote_reader_data("topic");
+        reader_data.content_filter = Some(ContentFilterProperty_t {
+            content_filtered_topic_name: "filtered".to_string(),
+            related_topic_name: "topic".to_string(),
+            filter_class_name: ContentFilterProperty_t::DDSSQL.to_string(),
+            filter_expression: "value > %0".to_string(),
+            extension_parameters: vec!["10".to_string()],
+        });
+        matcher.add_remote_reader(&mut core, reader_data);
+
+        let writer = core.writer_mut(writer_guid(LOCAL).entity_id).unwrap();
+        for value in &["5", "50"] {
+            let change = writer.new_change(
+                ChangeKind_t::ALIVE,
+                Data::from(Bytes::from_static(value.as_bytes())),
+                InstanceHandle_t::default(),
+            );
+            writer.as_writer_mut().writer_cache.add_change(change);
+        }
+
+        let submessages = writer.pending_submessages();
+        assert!(matches!(
+            &submessages[..],
+            [
+                (_, EntitySubmessage::Gap(gap)),
+                (_, EntitySubmessage::Data(data, _)),
+            ] if gap.gap_start == SequenceNumber_t::from(1)
+                && data.writer_sn == SequenceNumber_t::from(2)
+        ));
+    }
+
+    #[test]
+    fn offered_qos_must_satisfy_requested_qos() {
+        let writer_data = DiscoveredWriterData::new(writer_guid(LOCAL), "topic", "type");
+        let reader_data = DiscoveredReaderData::new(reader_guid(REMOTE), "topic", "type");
+        // The defaults are compatible
+        assert!(incompatible_policies(&writer_data, &reader_data).is_empty());
+
+        let mut stronger = reader_data.clone();
+        stronger.liveliness.lease_duration = Duration_t::from(Duration::from_secs(1));
+        stronger.presentation.coherent_access = true;
+        assert_eq!(
+            vec![
+                QosPolicyId_t::LIVELINESS_QOS_POLICY_ID,
+                QosPolicyId_t::PRESENTATION_QOS_POLICY_ID,
+            ],
+            incompatible_policies(&writer_data, &stronger)
+        );
+
+        let mut exclusive = writer_data;
+        exclusive.ownership.kind = OwnershipQosPolicyKind::EXCLUSIVE;
+        // The offered order is stronger than the requested one
+        exclusive.destination_order.kind = DestinationOrderQosPolicyKind::BY_SOURCE_TIMESTAMP;
+        exclusive.latency_budget.duration = Duration_t::from(Duration::from_millis(10));
+        assert_eq!(
+            vec![
+                QosPolicyId_t::LATENCYBUDGET_QOS_POLICY_ID,
+                QosPolicyId_t::OWNERSHIP_QOS_POLICY_ID,
+            ],
+            incompatible_policies(&exclusive, &reader_data)
+        );
+    }
+}
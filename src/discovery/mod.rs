@@ -1 +1,14 @@
+pub mod builtin_endpoint_set;
 pub mod content_filter_property;
+pub mod discovered_reader_data;
+pub mod discovered_writer_data;
+pub mod discovery_config;
+pub mod endpoint_matcher;
+pub mod participant_message_data;
+pub mod pl_cdr;
+pub mod port_mapping;
+pub mod sedp;
+pub mod spdp;
+pub mod spdp_discovered_participant_data;
+pub mod static_discovery_config;
+pub mod wlp;
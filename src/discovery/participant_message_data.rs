@@ -0,0 +1,119 @@
+use crate::structure::guid_prefix::GuidPrefix_t;
+use crate::structure::instance_handle::InstanceHandle_t;
+
+use bytes::{BufMut, Bytes, BytesMut};
+use speedy::{Endianness, Readable, Writable};
+use std::io::{Error, ErrorKind};
+
+/// Encapsulation identifiers of the data serialized with plain CDR (10.2).
+const CDR_BE: [u8; 2] = [0x00, 0x00];
+const CDR_LE: [u8; 2] = [0x00, 0x01];
+
+/// The data exchanged by the BuiltinParticipantMessageWriter and Reader
+/// (9.6.2.1), used by the Writer Liveliness Protocol to assert the
+/// liveliness of the Writers of a Participant. The participant_guid_prefix
+/// and the kind form the key of the data.
+#[derive(Clone, Debug, PartialEq, Eq, Readable, Writable)]
+pub struct ParticipantMessageData {
+    pub participant_guid_prefix: GuidPrefix_t,
+    pub kind: [u8; 4],
+    pub data: Vec<u8>,
+}
+
+impl ParticipantMessageData {
+    /// Asserts the liveliness of the Writers with the AUTOMATIC liveliness.
+    pub const PARTICIPANT_MESSAGE_DATA_KIND_AUTOMATIC_LIVELINESS_UPDATE: [u8; 4] =
+        [0x00, 0x00, 0x00, 0x01];
+
+    /// Asserts the liveliness of the Writers with the MANUAL_BY_PARTICIPANT
+    /// liveliness.
+    pub const PARTICIPANT_MESSAGE_DATA_KIND_MANUAL_LIVELINESS_UPDATE: [u8; 4] =
+        [0x00, 0x00, 0x00, 0x02];
+
+    pub fn new(participant_guid_prefix: GuidPrefix_t, kind: [u8; 4]) -> Self {
+        ParticipantMessageData {
+            participant_guid_prefix,
+            kind,
+            data: vec![],
+        }
+    }
+
+    /// The instance of the data: the bytes of its key.
+    pub fn instance_handle(&self) -> InstanceHandle_t {
+        let mut instance_handle = InstanceHandle_t::default();
+        instance_handle.entity_key[..12].copy_from_slice(&self.participant_guid_prefix.entity_key);
+        instance_handle.entity_key[12..].copy_from_slice(&self.kind);
+        instance_handle
+    }
+
+    /// Serializes the data with the CDR encapsulation.
+    pub fn serialize(&self, endianness: Endianness) -> Bytes {
+        let mut payload = BytesMut::new();
+        payload.put_slice(match endianness {
+            Endianness::LittleEndian => &CDR_LE,
+            Endianness::BigEndian => &CDR_BE,
+        });
+        payload.put_slice(&[0x00, 0x00]);
+        payload.put_slice(
+            &self
+                .write_to_vec_with_ctx(endianness)
+                .expect("ParticipantMessageData is always serializable"),
+        );
+        payload.freeze()
+    }
+
+    /// Deserializes the data preceded by the encapsulation header.
+    pub fn deserialize(payload: &[u8]) -> Result<Self, Error> {
+        if payload.len() < 4 {
+            return Err(Error::new(ErrorKind::InvalidData, "Missing encapsulation"));
+        }
+        let endianness = match [payload[0], payload[1]] {
+            CDR_LE => Endianness::LittleEndian,
+            CDR_BE => Endianness::BigEndian,
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "Encapsulation is not CDR",
+                ))
+            }
+        };
+        Ok(ParticipantMessageData::read_from_buffer_with_ctx(
+            endianness,
+            &payload[4..],
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn participant_message_data_is_serialized() {
+        let participant_message_data = ParticipantMessageData {
+            participant_guid_prefix: GuidPrefix_t::from([0x01; 12]),
+            kind: ParticipantMessageData::PARTICIPANT_MESSAGE_DATA_KIND_MANUAL_LIVELINESS_UPDATE,
+            data: vec![0xAB],
+        };
+
+        let serialized = participant_message_data.serialize(Endianness::LittleEndian);
+
+        assert_eq!(
+            vec![
+                0x00, 0x01, 0x00, 0x00, // CDR_LE
+                0x01, 0x01, 0x01, 0x01, // participant_guid_prefix
+                0x01, 0x01, 0x01, 0x01, //
+                0x01, 0x01, 0x01, 0x01, //
+                0x00, 0x00, 0x00, 0x02, // kind
+                0x01, 0x00, 0x00, 0x00, // data
+                0xAB,
+            ],
+            serialized
+        );
+        assert_eq!(
+            participant_message_data,
+            ParticipantMessageData::deserialize(&serialized).unwrap()
+        );
+        assert!(ParticipantMessageData::deserialize(&serialized[..20]).is_err());
+    }
+}
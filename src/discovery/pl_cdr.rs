@@ -0,0 +1,499 @@
+use crate::messages::submessage_elements::parameter::Parameter;
+use crate::messages::submessage_elements::parameter_list::ParameterList;
+use crate::structure::guid::GUID_t;
+use crate::structure::instance_handle::InstanceHandle_t;
+use crate::structure::locator::{LocatorKind_t, LocatorList_t, Locator_t};
+use crate::structure::parameter_id::ParameterId;
+
+use bytes::{BufMut, Bytes, BytesMut};
+use log::debug;
+use speedy::{Endianness, Readable, Writable};
+use std::io::{Error, ErrorKind};
+
+/// Encapsulation identifiers of the ParameterList serialized in the
+/// SerializedPayload of the built-in Endpoints (10.2).
+pub const PL_CDR_BE: [u8; 2] = [0x00, 0x02];
+pub const PL_CDR_LE: [u8; 2] = [0x00, 0x03];
+
+/// Serializes the ParameterList with the encapsulation header.
+pub fn serialize(parameter_list: &ParameterList, endianness: Endianness) -> Bytes {
+    let mut payload = BytesMut::new();
+    payload.put_slice(match endianness {
+        Endianness::LittleEndian => &PL_CDR_LE,
+        Endianness::BigEndian => &PL_CDR_BE,
+    });
+    payload.put_slice(&[0x00, 0x00]);
+    payload.put_slice(
+        &parameter_list
+            .write_to_vec_with_ctx(endianness)
+            .expect("ParameterList is always serializable"),
+    );
+    payload.freeze()
+}
+
+/// Deserializes the ParameterList preceded by the encapsulation header.
+/// Returns the endianness of the values of the parameters too.
+pub fn deserialize(payload: &[u8]) -> Result<(Endianness, ParameterList), Error> {
+    if payload.len() < 4 {
+        return Err(Error::new(ErrorKind::InvalidData, "Missing encapsulation"));
+    }
+    let endianness = match [payload[0], payload[1]] {
+        PL_CDR_LE => Endianness::LittleEndian,
+        PL_CDR_BE => Endianness::BigEndian,
+        _ => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "Encapsulation is not PL_CDR",
+            ))
+        }
+    };
+    let parameter_list = ParameterList::read_from_buffer_with_ctx(endianness, &payload[4..])?;
+    Ok((endianness, parameter_list))
+}
+
+pub fn parameter<T: Writable<Endianness>>(
+    parameter_id: ParameterId,
+    value: &T,
+    endianness: Endianness,
+) -> Parameter {
+    Parameter::new(
+        parameter_id,
+        value
+            .write_to_vec_with_ctx(endianness)
+            .expect("Parameter value is always serializable"),
+    )
+}
+
+/// The CDR string: the length including the terminating NUL, the characters
+/// and the NUL.
+pub fn string_parameter(
+    parameter_id: ParameterId,
+    value: &str,
+    endianness: Endianness,
+) -> Parameter {
+    let mut serialized = vec![];
+    write_string(&mut serialized, value, endianness);
+    Parameter::new(parameter_id, serialized)
+}
+
+/// The CDR sequence of strings: the number of strings followed by the
+/// strings.
+pub fn strings_parameter(
+    parameter_id: ParameterId,
+    values: &[String],
+    endianness: Endianness,
+) -> Parameter {
+    let mut serialized = vec![];
+    write_strings(&mut serialized, values, endianness);
+    Parameter::new(parameter_id, serialized)
+}
+
+/// Appends the CDR string to the value of a parameter, aligned to 4 bytes
+/// from the start of the value.
+pub fn write_string(serialized: &mut Vec<u8>, value: &str, endianness: Endianness) {
+    align(serialized);
+    serialized.extend_from_slice(
+        &(value.len() as u32 + 1)
+            .write_to_vec_with_ctx(endianness)
+            .expect("Length is always serializable"),
+    );
+    serialized.extend_from_slice(value.as_bytes());
+    serialized.push(0x00);
+}
+
+pub fn write_strings(serialized: &mut Vec<u8>, values: &[String], endianness: Endianness) {
+    align(serialized);
+    serialized.extend_from_slice(
+        &(values.len() as u32)
+            .write_to_vec_with_ctx(endianness)
+            .expect("Length is always serializable"),
+    );
+    for value in values {
+        write_string(serialized, value, endianness);
+    }
+}
+
+fn align(serialized: &mut Vec<u8>) {
+    while !serialized.len().is_multiple_of(4) {
+        serialized.push(0x00);
+    }
+}
+
+/// The CDR sequence of octets: the length followed by the octets.
+pub fn octets_parameter(
+    parameter_id: ParameterId,
+    value: &[u8],
+    endianness: Endianness,
+) -> Parameter {
+    let mut serialized = (value.len() as u32)
+        .write_to_vec_with_ctx(endianness)
+        .expect("Length is always serializable");
+    serialized.extend_from_slice(value);
+    Parameter::new(parameter_id, serialized)
+}
+
+pub fn read<'a, T: Readable<'a, Endianness>>(
+    parameter: &'a Parameter,
+    endianness: Endianness,
+) -> Result<T, Error> {
+    Ok(T::read_from_buffer_with_ctx(endianness, &parameter.value)?)
+}
+
+/// Reads the value of the first parameter with the id, None when missing.
+pub fn read_optional<T: for<'a> Readable<'a, Endianness>>(
+    parameter_list: &ParameterList,
+    parameter_id: &ParameterId,
+    endianness: Endianness,
+) -> Result<Option<T>, Error> {
+    parameter_list
+        .find(parameter_id)
+        .map(|parameter| read(parameter, endianness))
+        .transpose()
+}
+
+/// Reads the locators of all parameters with the id. The locators of the
+/// kinds not known to this implementation, e.g. the shared memory locators
+/// of other vendors, are skipped.
+pub fn read_locators(
+    parameter_list: &ParameterList,
+    parameter_id: &ParameterId,
+    endianness: Endianness,
+) -> Result<LocatorList_t, Error> {
+    let mut locators = vec![];
+    for parameter in &parameter_list.parameters {
+        if &parameter.parameter_id != parameter_id {
+            continue;
+        }
+        let kind = parameter.value.get(..4).unwrap_or(&parameter.value);
+        if LocatorKind_t::read_from_buffer_with_ctx(endianness, kind).is_err() {
+            debug!("Skipping locator of unknown kind {:02X?}", kind);
+            continue;
+        }
+        locators.push(read::<Locator_t>(parameter, endianness)?);
+    }
+    Ok(locators)
+}
+
+pub fn read_string(parameter: &Parameter, endianness: Endianness) -> Result<String, Error> {
+    read_string_at(&parameter.value, &mut 0, endianness)
+}
+
+pub fn read_strings(parameter: &Parameter, endianness: Endianness) -> Result<Vec<String>, Error> {
+    read_strings_at(&parameter.value, &mut 0, endianness)
+}
+
+/// Reads the CDR string at the offset of the value of a parameter, aligned
+/// to 4 bytes from the start of the value. The offset is moved past the
+/// string.
+pub fn read_string_at(
+    value: &[u8],
+    offset: &mut usize,
+    endianness: Endianness,
+) -> Result<String, Error> {
+    let length = read_length_at(value, offset, endianness)?;
+    let octets = value
+        .get(*offset..*offset + length)
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "String exceeds the parameter"))?;
+    *offset += length;
+    match octets.split_last() {
+        Some((0x00, characters)) => String::from_utf8(characters.to_vec())
+            .map_err(|error| Error::new(ErrorKind::InvalidData, error)),
+        _ => Err(Error::new(
+            ErrorKind::InvalidData,
+            "String is not terminated",
+        )),
+    }
+}
+
+pub fn read_strings_at(
+    value: &[u8],
+    offset: &mut usize,
+    endianness: Endianness,
+) -> Result<Vec<String>, Error> {
+    let count = read_length_at(value, offset, endianness)?;
+    // Every string takes at least 5 bytes
+    if count > value.len() / 5 {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "Sequence exceeds the parameter",
+        ));
+    }
+    (0..count)
+        .map(|_| read_string_at(value, offset, endianness))
+        .collect()
+}
+
+fn read_length_at(
+    value: &[u8],
+    offset: &mut usize,
+    endianness: Endianness,
+) -> Result<usize, Error> {
+    *offset += (4 - *offset % 4) % 4;
+    let length = value
+        .get(*offset..*offset + 4)
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Length exceeds the parameter"))?;
+    *offset += 4;
+    Ok(u32::read_from_buffer_with_ctx(endianness, length)? as usize)
+}
+
+pub fn read_octets(parameter: &Parameter, endianness: Endianness) -> Result<Vec<u8>, Error> {
+    let length: u32 = read(parameter, endianness)?;
+    parameter
+        .value
+        .get(4..4 + length as usize)
+        .map(<[u8]>::to_vec)
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Sequence exceeds the parameter"))
+}
+
+/// The GUID of the Endpoint; announced either as PID_ENDPOINT_GUID or, by
+/// the older implementations, as PID_KEY_HASH.
+pub fn read_endpoint_guid(
+    parameter_list: &ParameterList,
+    endianness: Endianness,
+) -> Result<GUID_t, Error> {
+    let endpoint_guid =
+        match read_optional(parameter_list, &ParameterId::PID_ENDPOINT_GUID, endianness)? {
+            Some(endpoint_guid) => Some(endpoint_guid),
+            // The key hash is always big endian
+            None => read_optional(
+                parameter_list,
+                &ParameterId::PID_KEY_HASH,
+                Endianness::BigEndian,
+            )?,
+        };
+    endpoint_guid.ok_or_else(|| Error::new(ErrorKind::InvalidData, "Missing PID_ENDPOINT_GUID"))
+}
+
+/// The key hash of the builtin topic data: the bytes of the GUID.
+pub fn key_hash(guid: GUID_t) -> InstanceHandle_t {
+    let mut key_hash = InstanceHandle_t::default();
+    key_hash.entity_key.copy_from_slice(
+        &guid
+            .write_to_vec_with_ctx(Endianness::BigEndian)
+            .expect("GUID is always serializable"),
+    );
+    key_hash
+}
+
+/// The serialized key of the builtin topic data identified by the GUID,
+/// the payload of the change disposing it.
+pub fn serialize_key(parameter_id: ParameterId, guid: GUID_t, endianness: Endianness) -> Bytes {
+    let mut parameter_list = ParameterList::new();
+    parameter_list.push(parameter(parameter_id, &guid, endianness));
+    serialize(&parameter_list, endianness)
+}
+
+/// The GUID identifying the builtin topic data of a change without data:
+/// its key hash if sent, else the parameter of its serialized key.
+pub fn deserialize_key(
+    key_hash: InstanceHandle_t,
+    payload: &[u8],
+    parameter_id: &ParameterId,
+) -> Result<GUID_t, Error> {
+    if key_hash != InstanceHandle_t::HANDLE_NIL {
+        return Ok(GUID_t::read_from_buffer_with_ctx(
+            Endianness::BigEndian,
+            &key_hash.entity_key,
+        )?);
+    }
+    let (endianness, parameter_list) = deserialize(payload)?;
+    read_optional(&parameter_list, parameter_id, endianness)?
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Missing key"))
+}
+
+pub fn push_if_not_default<T: Default + PartialEq + Writable<Endianness>>(
+    parameter_list: &mut ParameterList,
+    parameter_id: ParameterId,
+    policy: &T,
+    endianness: Endianness,
+) {
+    if policy != &T::default() {
+        parameter_list.push(parameter(parameter_id, policy, endianness));
+    }
+}
+
+pub fn read_or_default<T: Default + for<'a> Readable<'a, Endianness>>(
+    parameter_list: &ParameterList,
+    parameter_id: ParameterId,
+    endianness: Endianness,
+) -> Result<T, Error> {
+    Ok(read_optional(parameter_list, &parameter_id, endianness)?.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::entity_id::EntityId_t;
+    use crate::structure::guid_prefix::GuidPrefix_t;
+
+    #[test]
+    fn parameter_list_is_encapsulated() {
+        let parameter_list = ParameterList {
+            parameters: vec![string_parameter(
+                ParameterId::PID_TOPIC_NAME,
+                "ab",
+                Endianness::BigEndian,
+            )],
+        };
+
+        let payload = serialize(&parameter_list, Endianness::BigEndian);
+
+        assert_eq!(
+            &[
+                0x00, 0x02, 0x00, 0x00, // PL_CDR_BE
+                0x00, 0x05, 0x00, 0x08, // PID_TOPIC_NAME
+                0x00, 0x00, 0x00, 0x03, //
+                0x61, 0x62, 0x00, 0x00, //
+                0x00, 0x01, 0x00, 0x00, // PID_SENTINEL
+            ],
+            &payload[..]
+        );
+        let (endianness, deserialized) = deserialize(&payload).unwrap();
+        assert_eq!(Endianness::BigEndian, endianness);
+        assert_eq!(
+            "ab",
+            read_string(&deserialized.parameters[0], endianness).unwrap()
+        );
+    }
+
+    #[test]
+    fn string_and_octets_are_read_back() {
+        let string = string_parameter(
+            ParameterId::PID_ENTITY_NAME,
+            "rtps",
+            Endianness::LittleEndian,
+        );
+        let octets = octets_parameter(
+            ParameterId::PID_USER_DATA,
+            &[0x01, 0x02],
+            Endianness::LittleEndian,
+        );
+
+        assert_eq!(
+            "rtps",
+            read_string(&string, Endianness::LittleEndian).unwrap()
+        );
+        assert_eq!(
+            vec![0x01, 0x02],
+            read_octets(&octets, Endianness::LittleEndian).unwrap()
+        );
+    }
+
+    #[test]
+    fn strings_are_aligned() {
+        let parameter = strings_parameter(
+            ParameterId::PID_PARTITION,
+            &["a".to_string(), "bcdef".to_string()],
+            Endianness::LittleEndian,
+        );
+
+        assert_eq!(
+            vec![
+                0x02, 0x00, 0x00, 0x00, //
+                0x02, 0x00, 0x00, 0x00, 0x61, 0x00, 0x00, 0x00, //
+                0x06, 0x00, 0x00, 0x00, 0x62, 0x63, 0x64, 0x65, 0x66, 0x00,
+            ],
+            parameter.value
+        );
+        assert_eq!(
+            vec!["a".to_string(), "bcdef".to_string()],
+            read_strings(&parameter, Endianness::LittleEndian).unwrap()
+        );
+    }
+
+    #[test]
+    fn locators_of_unknown_kinds_are_skipped() {
+        let mut parameter_list = ParameterList::new();
+        parameter_list.push(Parameter::new(
+            ParameterId::PID_DEFAULT_UNICAST_LOCATOR,
+            [
+                &[0x10, 0x00, 0x00, 0x00, 0x2B, 0x1D, 0x00, 0x00][..],
+                &[0x53; 16][..],
+            ]
+            .concat(),
+        ));
+        parameter_list.push(Parameter::new(
+            ParameterId::PID_DEFAULT_UNICAST_LOCATOR,
+            [
+                &[0x01, 0x00, 0x00, 0x00, 0xF3, 0x1C, 0x00, 0x00][..],
+                &[0x00; 12][..],
+                &[0x7F, 0x00, 0x00, 0x01][..],
+            ]
+            .concat(),
+        ));
+
+        assert_eq!(
+            vec![Locator_t::from(std::net::SocketAddr::from((
+                [127, 0, 0, 1],
+                7411
+            )))],
+            read_locators(
+                &parameter_list,
+                &ParameterId::PID_DEFAULT_UNICAST_LOCATOR,
+                Endianness::LittleEndian
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn other_encapsulations_are_rejected() {
+        // CDR_LE
+        let payload = [0x00, 0x01, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00];
+
+        assert_eq!(
+            ErrorKind::InvalidData,
+            deserialize(&payload).unwrap_err().kind()
+        );
+    }
+
+    #[test]
+    fn sequence_exceeding_parameter_is_rejected() {
+        let parameter = Parameter::new(
+            ParameterId::PID_USER_DATA,
+            vec![0x08, 0x00, 0x00, 0x00, 0x01],
+        );
+
+        assert_eq!(
+            ErrorKind::InvalidData,
+            read_octets(&parameter, Endianness::LittleEndian)
+                .unwrap_err()
+                .kind()
+        );
+    }
+
+    #[test]
+    fn key_is_read_from_key_hash_or_serialized_key() {
+        let guid = GUID_t {
+            guid_prefix: GuidPrefix_t {
+                entity_key: [0x01; 12],
+            },
+            entity_id: EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_WRITER,
+        };
+        let payload = serialize_key(
+            ParameterId::PID_ENDPOINT_GUID,
+            guid,
+            Endianness::LittleEndian,
+        );
+
+        assert_eq!(
+            guid,
+            deserialize_key(
+                InstanceHandle_t::HANDLE_NIL,
+                &payload,
+                &ParameterId::PID_ENDPOINT_GUID
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            guid,
+            deserialize_key(key_hash(guid), &[], &ParameterId::PID_ENDPOINT_GUID).unwrap()
+        );
+        assert!(deserialize_key(
+            InstanceHandle_t::HANDLE_NIL,
+            &payload,
+            &ParameterId::PID_PARTICIPANT_GUID
+        )
+        .is_err());
+    }
+}
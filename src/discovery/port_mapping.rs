@@ -0,0 +1,184 @@
+use std::convert::TryFrom;
+use std::io;
+
+/// Mapping of the domain id and the participant id to the well-known ports
+/// (9.6.1.1). The metatraffic (discovery) and the user traffic of all
+/// Participants of a domain use the same multicast ports, every Participant
+/// on the host receives the unicast traffic on its own ports.
+///
+/// The default parameters are the ones defined by the specification.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PortMapping {
+    /// PB, the port base number.
+    pub port_base: u16,
+    /// DG, the domain id gain.
+    pub domain_id_gain: u16,
+    /// PG, the participant id gain.
+    pub participant_id_gain: u16,
+    /// d0, the offset of the metatraffic multicast port.
+    pub metatraffic_multicast_offset: u16,
+    /// d1, the offset of the metatraffic unicast port.
+    pub metatraffic_unicast_offset: u16,
+    /// d2, the offset of the user traffic multicast port.
+    pub user_multicast_offset: u16,
+    /// d3, the offset of the user traffic unicast port.
+    pub user_unicast_offset: u16,
+}
+
+impl PortMapping {
+    /// Port used by SPDP: PB + DG * domainId + d0
+    pub fn metatraffic_multicast_port(&self, domain_id: u16) -> Option<u16> {
+        self.port(domain_id, self.metatraffic_multicast_offset, None)
+    }
+
+    /// Port used by SPDP: PB + DG * domainId + d1 + PG * participantId
+    pub fn metatraffic_unicast_port(&self, domain_id: u16, participant_id: u16) -> Option<u16> {
+        self.port(
+            domain_id,
+            self.metatraffic_unicast_offset,
+            Some(participant_id),
+        )
+    }
+
+    /// PB + DG * domainId + d2
+    pub fn user_multicast_port(&self, domain_id: u16) -> Option<u16> {
+        self.port(domain_id, self.user_multicast_offset, None)
+    }
+
+    /// PB + DG * domainId + d3 + PG * participantId
+    pub fn user_unicast_port(&self, domain_id: u16, participant_id: u16) -> Option<u16> {
+        self.port(domain_id, self.user_unicast_offset, Some(participant_id))
+    }
+
+    /// Finds the first participant id whose ports are free on the host.
+    /// Calls open with the successive participant ids until it does not fail
+    /// with AddrInUse, returns the participant id with the opened resources.
+    pub fn first_free_participant_id<T>(
+        &self,
+        domain_id: u16,
+        mut open: impl FnMut(u16) -> io::Result<T>,
+    ) -> io::Result<(u16, T)> {
+        let mut participant_id = 0;
+        while self
+            .metatraffic_unicast_port(domain_id, participant_id)
+            .is_some()
+            && self.user_unicast_port(domain_id, participant_id).is_some()
+        {
+            match open(participant_id) {
+                Ok(opened) => return Ok((participant_id, opened)),
+                Err(error) if error.kind() == io::ErrorKind::AddrInUse => participant_id += 1,
+                Err(error) => return Err(error),
+            }
+        }
+        Err(io::Error::new(
+            io::ErrorKind::AddrInUse,
+            "No free participant id",
+        ))
+    }
+
+    /// Computes the port, None when it does not fit into the port range.
+    fn port(&self, domain_id: u16, offset: u16, participant_id: Option<u16>) -> Option<u16> {
+        let participant =
+            u32::from(self.participant_id_gain) * u32::from(participant_id.unwrap_or_default());
+        let port = u32::from(self.port_base)
+            + u32::from(self.domain_id_gain) * u32::from(domain_id)
+            + u32::from(offset)
+            + participant;
+        u16::try_from(port).ok()
+    }
+}
+
+impl Default for PortMapping {
+    fn default() -> Self {
+        PortMapping {
+            port_base: 7400,
+            domain_id_gain: 250,
+            participant_id_gain: 2,
+            metatraffic_multicast_offset: 0,
+            metatraffic_unicast_offset: 10,
+            user_multicast_offset: 1,
+            user_unicast_offset: 11,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_ports_of_first_participant_in_domain_0() {
+        let port_mapping = PortMapping::default();
+
+        assert_eq!(Some(7400), port_mapping.metatraffic_multicast_port(0));
+        assert_eq!(Some(7410), port_mapping.metatraffic_unicast_port(0, 0));
+        assert_eq!(Some(7401), port_mapping.user_multicast_port(0));
+        assert_eq!(Some(7411), port_mapping.user_unicast_port(0, 0));
+    }
+
+    #[test]
+    fn ports_depend_on_domain_id_and_participant_id() {
+        let port_mapping = PortMapping::default();
+
+        assert_eq!(Some(7650), port_mapping.metatraffic_multicast_port(1));
+        assert_eq!(Some(7664), port_mapping.metatraffic_unicast_port(1, 2));
+        assert_eq!(Some(7651), port_mapping.user_multicast_port(1));
+        assert_eq!(Some(7665), port_mapping.user_unicast_port(1, 2));
+    }
+
+    #[test]
+    fn ports_out_of_range_are_not_mapped() {
+        let port_mapping = PortMapping::default();
+
+        assert_eq!(Some(65_400), port_mapping.metatraffic_multicast_port(232));
+        assert_eq!(None, port_mapping.metatraffic_multicast_port(233));
+        assert_eq!(None, port_mapping.user_unicast_port(232, 190));
+    }
+
+    #[test]
+    fn parameters_are_configurable() {
+        let port_mapping = PortMapping {
+            port_base: 10_000,
+            participant_id_gain: 10,
+            ..PortMapping::default()
+        };
+
+        assert_eq!(Some(10_250), port_mapping.metatraffic_multicast_port(1));
+        assert_eq!(Some(10_280), port_mapping.metatraffic_unicast_port(1, 2));
+    }
+
+    #[test]
+    fn first_participant_id_with_free_ports_is_selected() {
+        let result = PortMapping::default().first_free_participant_id(0, |participant_id| {
+            if participant_id < 2 {
+                Err(io::Error::from(io::ErrorKind::AddrInUse))
+            } else {
+                Ok(participant_id * 10)
+            }
+        });
+
+        assert_eq!(2, result.as_ref().unwrap().0);
+        assert_eq!(20, result.unwrap().1);
+    }
+
+    #[test]
+    fn other_errors_stop_the_selection() {
+        let mut tried = Vec::new();
+        let result: io::Result<(u16, ())> =
+            PortMapping::default().first_free_participant_id(0, |participant_id| {
+                tried.push(participant_id);
+                Err(io::Error::from(io::ErrorKind::PermissionDenied))
+            });
+
+        assert_eq!(io::ErrorKind::PermissionDenied, result.unwrap_err().kind());
+        assert_eq!(vec![0], tried);
+    }
+
+    #[test]
+    fn selection_fails_when_all_ports_are_used() {
+        let result: io::Result<(u16, ())> = PortMapping::default()
+            .first_free_participant_id(232, |_| Err(io::Error::from(io::ErrorKind::AddrInUse)));
+
+        assert_eq!(io::ErrorKind::AddrInUse, result.unwrap_err().kind());
+    }
+}
@@ -0,0 +1,827 @@
+use crate::behavior::reader::{Reader, ReaderAttributes};
+use crate::behavior::reader_proxy::ReaderProxy;
+use crate::behavior::stateful_reader::StatefulReader;
+use crate::behavior::stateful_writer::StatefulWriter;
+use crate::behavior::writer::{Writer, WriterAttributes};
+use crate::behavior::writer_proxy::WriterProxy;
+use crate::discovery::builtin_endpoint_set::BuiltinEndpointSet_t;
+use crate::discovery::discovered_reader_data::DiscoveredReaderData;
+use crate::discovery::discovered_writer_data::DiscoveredWriterData;
+use crate::discovery::pl_cdr;
+use crate::discovery::spdp_discovered_participant_data::SpdpDiscoveredParticipantData;
+use crate::protocol::event::Event;
+use crate::protocol::protocol_core::ProtocolCore;
+use crate::structure::cache_change::CacheChange;
+use crate::structure::change_kind::ChangeKind_t;
+use crate::structure::data::Data;
+use crate::structure::duration::Duration_t;
+use crate::structure::endpoint::EndpointAttributes;
+use crate::structure::entity::EntityAttributes;
+use crate::structure::entity_id::EntityId_t;
+use crate::structure::guid::GUID_t;
+use crate::structure::guid_prefix::GuidPrefix_t;
+use crate::structure::history_cache::HistoryCache;
+use crate::structure::parameter_id::ParameterId;
+use crate::structure::reliability_kind::ReliabilityKind_t;
+use crate::structure::sequence_number::SequenceNumber_t;
+use crate::structure::topic_kind::TopicKind_t;
+
+use bytes::Bytes;
+use log::debug;
+use speedy::Endianness;
+use std::collections::{BTreeMap, VecDeque};
+use std::io::Error;
+use std::time::{Duration, Instant};
+
+/// The announcements are sent in a single Data Submessage unless they exceed
+/// a UDP datagram.
+const DATA_MAX_SIZE_SERIALIZED: u32 = 60 * 1024;
+const FRAGMENT_SIZE: u16 = 1024;
+
+/// The Simple Endpoint Discovery Protocol (8.5.4).
+///
+/// The local DataWriters and DataReaders are announced by the reliable
+/// SEDPbuiltinPublicationsWriter and SEDPbuiltinSubscriptionsWriter to all
+/// Participants discovered by the SPDP; the corresponding built-in Readers
+/// receive the announcements of the remote Endpoints. The built-in Endpoints
+/// of a remote Participant are matched according to its
+/// available_builtin_endpoints.
+///
+/// Like the ProtocolCore, Sedp is free of any I/O and driven by the caller;
+/// the Endpoints are installed into the ProtocolCore of the Participant.
+pub struct Sedp {
+    guid_prefix: GuidPrefix_t,
+
+    /// The sequence numbers of the announcements of the local Endpoints in
+    /// the HistoryCache of the built-in Writers.
+    announced: BTreeMap<GUID_t, SequenceNumber_t>,
+
+    /// The built-in Writers and sequence numbers of the changes disposing
+    /// the deleted local Endpoints, kept until acknowledged by all remote
+    /// Participants.
+    disposed: Vec<(EntityId_t, SequenceNumber_t)>,
+    discovered_writers: BTreeMap<GUID_t, DiscoveredWriterData>,
+    discovered_readers: BTreeMap<GUID_t, DiscoveredReaderData>,
+    events: VecDeque<Event>,
+}
+
+impl Sedp {
+    /// The SEDP bits of the available_builtin_endpoints announced by the
+    /// SPDP.
+    pub const BUILTIN_ENDPOINTS: BuiltinEndpointSet_t =
+        BuiltinEndpointSet_t::DISC_BUILTIN_ENDPOINT_PUBLICATIONS_ANNOUNCER
+            .union(BuiltinEndpointSet_t::DISC_BUILTIN_ENDPOINT_PUBLICATIONS_DETECTOR)
+            .union(BuiltinEndpointSet_t::DISC_BUILTIN_ENDPOINT_SUBSCRIPTIONS_ANNOUNCER)
+            .union(BuiltinEndpointSet_t::DISC_BUILTIN_ENDPOINT_SUBSCRIPTIONS_DETECTOR);
+
+    /// Installs the SEDP built-in Endpoints into the core. The Writers send
+    /// Heartbeats every heartbeat_period, starting from now.
+    pub fn new(
+        now: Instant,
+        core: &mut ProtocolCore,
+        guid_prefix: GuidPrefix_t,
+        heartbeat_period: Duration,
+    ) -> Self {
+        let guid = |entity_id| GUID_t {
+            guid_prefix,
+            entity_id,
+        };
+
+        for entity_id in &[
+            EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_WRITER,
+            EntityId_t::ENTITYID_SEDP_BUILTIN_SUBSCRIPTIONS_WRITER,
+        ] {
+            core.add_writer(
+                now,
+                StatefulWriter::new(
+                    EntityAttributes {
+                        guid: guid(*entity_id),
+                    },
+                    endpoint(),
+                    WriterAttributes {
+                        push_mode: true,
+                        heartbeat_period: Duration_t::from(heartbeat_period),
+                        nack_response_delay: Duration_t::DURATION_ZERO,
+                        nack_suppression_duration: Duration_t::DURATION_ZERO,
+                        last_change_sequence_number: SequenceNumber_t::from(0),
+                        data_max_size_serialized: DATA_MAX_SIZE_SERIALIZED,
+                        fragment_size: FRAGMENT_SIZE,
+                        writer_cache: HistoryCache::new(),
+                    },
+                ),
+            );
+        }
+        for entity_id in &[
+            EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_READER,
+            EntityId_t::ENTITYID_SEDP_BUILTIN_SUBSCRIPTIONS_READER,
+        ] {
+            core.add_reader(StatefulReader::new(
+                EntityAttributes {
+                    guid: guid(*entity_id),
+                },
+                endpoint(),
+                ReaderAttributes {
+                    expects_inline_qos: false,
+                    heartbeat_response_delay: Duration_t::DURATION_ZERO,
+                    heartbeat_suppression_duration: Duration_t::DURATION_ZERO,
+                    reader_cache: HistoryCache::new(),
+                },
+            ));
+        }
+
+        Sedp {
+            guid_prefix,
+            announced: BTreeMap::new(),
+            disposed: vec![],
+            discovered_writers: BTreeMap::new(),
+            discovered_readers: BTreeMap::new(),
+            events: VecDeque::new(),
+        }
+    }
+
+    /// Whether the reader_guid identifies one of the built-in Readers, whose
+    /// DataAvailable events are handled by handle_data_available.
+    pub fn is_builtin_reader(&self, reader_guid: GUID_t) -> bool {
+        reader_guid.guid_prefix == self.guid_prefix
+            && (reader_guid.entity_id == EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_READER
+                || reader_guid.entity_id == EntityId_t::ENTITYID_SEDP_BUILTIN_SUBSCRIPTIONS_READER)
+    }
+
+    #[cfg(test)]
+    pub fn discovered_writers(&self) -> impl Iterator<Item = &DiscoveredWriterData> {
+        self.discovered_writers.values()
+    }
+
+    #[cfg(test)]
+    pub fn discovered_readers(&self) -> impl Iterator<Item = &DiscoveredReaderData> {
+        self.discovered_readers.values()
+    }
+
+    /// Matches the built-in Endpoints with the ones available in the
+    /// Participant discovered by the SPDP. The remote built-in Readers
+    /// receive the announcements of all local Endpoints.
+    pub fn add_participant(
+        &mut self,
+        core: &mut ProtocolCore,
+        participant_data: &SpdpDiscoveredParticipantData,
+    ) {
+        let remote_guid = |entity_id| GUID_t {
+            guid_prefix: participant_data.guid_prefix,
+            entity_id,
+        };
+        let unicast_locator_list = &participant_data.metatraffic_unicast_locator_list;
+        let multicast_locator_list = &participant_data.metatraffic_multicast_locator_list;
+        let available = participant_data.available_builtin_endpoints;
+
+        for (bit, writer_id, remote_reader_id) in &[
+            (
+                BuiltinEndpointSet_t::DISC_BUILTIN_ENDPOINT_PUBLICATIONS_DETECTOR,
+                EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_WRITER,
+                EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_READER,
+            ),
+            (
+                BuiltinEndpointSet_t::DISC_BUILTIN_ENDPOINT_SUBSCRIPTIONS_DETECTOR,
+                EntityId_t::ENTITYID_SEDP_BUILTIN_SUBSCRIPTIONS_WRITER,
+                EntityId_t::ENTITYID_SEDP_BUILTIN_SUBSCRIPTIONS_READER,
+            ),
+        ] {
+            let writer = match core.writer_mut(*writer_id) {
+                Some(writer) if available.contains(*bit) => writer,
+                _ => continue,
+            };
+            let remote_reader_guid = remote_guid(*remote_reader_id);
+            if writer
+                .matched_reader_lookup(remote_reader_guid)
+                .next()
+                .is_none()
+            {
+                writer.matched_reader_add(ReaderProxy::new(
+                    remote_reader_guid,
+                    unicast_locator_list.clone(),
+                    multicast_locator_list.clone(),
+                    false,
+                ));
+            }
+        }
+
+        for (bit, reader_id, remote_writer_id) in &[
+            (
+                BuiltinEndpointSet_t::DISC_BUILTIN_ENDPOINT_PUBLICATIONS_ANNOUNCER,
+                EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_READER,
+                EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_WRITER,
+            ),
+            (
+                BuiltinEndpointSet_t::DISC_BUILTIN_ENDPOINT_SUBSCRIPTIONS_ANNOUNCER,
+                EntityId_t::ENTITYID_SEDP_BUILTIN_SUBSCRIPTIONS_READER,
+                EntityId_t::ENTITYID_SEDP_BUILTIN_SUBSCRIPTIONS_WRITER,
+            ),
+        ] {
+            let reader = match core.reader_mut(*reader_id) {
+                Some(reader) if available.contains(*bit) => reader,
+                _ => continue,
+            };
+            let remote_writer_guid = remote_guid(*remote_writer_id);
+            if reader.matched_writer_lookup(remote_writer_guid).is_none() {
+                reader.matched_writer_add(WriterProxy::new(
+                    remote_writer_guid,
+                    unicast_locator_list.clone(),
+                    multicast_locator_list.clone(),
+                ));
+            }
+        }
+    }
+
+    /// Unmatches the built-in Endpoints of the Participant lost by the SPDP.
+    /// All Endpoints of the Participant are lost as well.
+    pub fn remove_participant(&mut self, core: &mut ProtocolCore, guid_prefix: GuidPrefix_t) {
+        let remote_guid = |entity_id| GUID_t {
+            guid_prefix,
+            entity_id,
+        };
+        for (writer_id, remote_reader_id) in &[
+            (
+                EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_WRITER,
+                EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_READER,
+            ),
+            (
+                EntityId_t::ENTITYID_SEDP_BUILTIN_SUBSCRIPTIONS_WRITER,
+                EntityId_t::ENTITYID_SEDP_BUILTIN_SUBSCRIPTIONS_READER,
+            ),
+        ] {
+            if let Some(writer) = core.writer_mut(*writer_id) {
+                writer.matched_reader_remove(remote_guid(*remote_reader_id));
+            }
+            if let Some(reader) = core.reader_mut(*remote_reader_id) {
+                reader.matched_writer_remove(remote_guid(*writer_id));
+            }
+        }
+
+        let lost_writers: Vec<GUID_t> = self
+            .discovered_writers
+            .keys()
+            .filter(|writer_guid| writer_guid.guid_prefix == guid_prefix)
+            .cloned()
+            .collect();
+        for writer_guid in lost_writers {
+            self.discovered_writers.remove(&writer_guid);
+            self.events
+                .push_back(Event::PublicationLost { writer_guid });
+        }
+
+        let lost_readers: Vec<GUID_t> = self
+            .discovered_readers
+            .keys()
+            .filter(|reader_guid| reader_guid.guid_prefix == guid_prefix)
+            .cloned()
+            .collect();
+        for reader_guid in lost_readers {
+            self.discovered_readers.remove(&reader_guid);
+            self.events
+                .push_back(Event::SubscriptionLost { reader_guid });
+        }
+    }
+
+    /// Announces the local Writer to the discovered Participants. Announcing
+    /// the Writer again replaces its previous announcement.
+    pub fn announce_writer(&mut self, core: &mut ProtocolCore, writer_data: &DiscoveredWriterData) {
+        self.announce(
+            core,
+            EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_WRITER,
+            writer_data.remote_writer_guid,
+            writer_data.serialize(Endianness::LittleEndian),
+        )
+    }
+
+    /// Announces the local Reader to the discovered Participants. Announcing
+    /// the Reader again replaces its previous announcement.
+    pub fn announce_reader(&mut self, core: &mut ProtocolCore, reader_data: &DiscoveredReaderData) {
+        self.announce(
+            core,
+            EntityId_t::ENTITYID_SEDP_BUILTIN_SUBSCRIPTIONS_WRITER,
+            reader_data.remote_reader_guid,
+            reader_data.serialize(Endianness::LittleEndian),
+        )
+    }
+
+    /// Replaces the announcement of the deleted local Writer with a change
+    /// disposing it, the remote Participants lose the Writer as soon as
+    /// they receive it.
+    pub fn remove_local_writer(&mut self, core: &mut ProtocolCore, writer_guid: GUID_t) {
+        self.withdraw(
+            core,
+            EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_WRITER,
+            writer_guid,
+        )
+    }
+
+    /// Removes the announcement of the deleted local Reader, see
+    /// remove_local_writer.
+    pub fn remove_local_reader(&mut self, core: &mut ProtocolCore, reader_guid: GUID_t) {
+        self.withdraw(
+            core,
+            EntityId_t::ENTITYID_SEDP_BUILTIN_SUBSCRIPTIONS_WRITER,
+            reader_guid,
+        )
+    }
+
+    /// Processes the announcements received by the built-in Readers of the
+    /// core, to be called for their DataAvailable events. New Endpoints and
+    /// the Endpoints announced with a changed data are reported with
+    /// PublicationDiscovered and SubscriptionDiscovered events, the disposed
+    /// and unregistered ones with PublicationLost and SubscriptionLost.
+    pub fn handle_data_available(&mut self, core: &mut ProtocolCore) {
+        for change in take_changes(core, EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_READER) {
+            if change.kind != ChangeKind_t::ALIVE {
+                match endpoint_guid(&change) {
+                    Ok(writer_guid) => {
+                        if self.discovered_writers.remove(&writer_guid).is_some() {
+                            self.events
+                                .push_back(Event::PublicationLost { writer_guid });
+                        }
+                    }
+                    Err(error) => debug!("Dropping malformed disposed publication: {}", error),
+                }
+                continue;
+            }
+            match DiscoveredWriterData::deserialize(&change.data_value.value) {
+                Ok(writer_data) => {
+                    let writer_guid = writer_data.remote_writer_guid;
+                    if self.discovered_writers.get(&writer_guid) != Some(&writer_data) {
+                        self.discovered_writers
+                            .insert(writer_guid, writer_data.clone());
+                        self.events
+                            .push_back(Event::PublicationDiscovered { writer_data });
+                    }
+                }
+                Err(error) => debug!("Dropping malformed publication: {}", error),
+            }
+        }
+
+        for change in take_changes(core, EntityId_t::ENTITYID_SEDP_BUILTIN_SUBSCRIPTIONS_READER) {
+            if change.kind != ChangeKind_t::ALIVE {
+                match endpoint_guid(&change) {
+                    Ok(reader_guid) => {
+                        if self.discovered_readers.remove(&reader_guid).is_some() {
+                            self.events
+                                .push_back(Event::SubscriptionLost { reader_guid });
+                        }
+                    }
+                    Err(error) => debug!("Dropping malformed disposed subscription: {}", error),
+                }
+                continue;
+            }
+            match DiscoveredReaderData::deserialize(&change.data_value.value) {
+                Ok(reader_data) => {
+                    let reader_guid = reader_data.remote_reader_guid;
+                    if self.discovered_readers.get(&reader_guid) != Some(&reader_data) {
+                        self.discovered_readers
+                            .insert(reader_guid, reader_data.clone());
+                        self.events
+                            .push_back(Event::SubscriptionDiscovered { reader_data });
+                    }
+                }
+                Err(error) => debug!("Dropping malformed subscription: {}", error),
+            }
+        }
+    }
+
+    pub fn poll_event(&mut self) -> Option<Event> {
+        self.events.pop_front()
+    }
+
+    fn announce(
+        &mut self,
+        core: &mut ProtocolCore,
+        writer_id: EntityId_t,
+        endpoint_guid: GUID_t,
+        serialized_data: Bytes,
+    ) {
+        let writer = match core.writer_mut(writer_id) {
+            Some(writer) => writer,
+            None => return,
+        };
+        if let Some(sequence_number) = self.announced.get(&endpoint_guid) {
+            writer
+                .as_writer_mut()
+                .writer_cache
+                .remove_change(*sequence_number);
+        }
+        let change = writer.new_change(
+            ChangeKind_t::ALIVE,
+            Data::from(serialized_data),
+            pl_cdr::key_hash(endpoint_guid),
+        );
+        self.announced.insert(endpoint_guid, change.sequence_number);
+        writer.as_writer_mut().writer_cache.add_change(change);
+    }
+
+    /// Replaces the announcement of the Endpoint with a change disposing and
+    /// unregistering it, which carries its key only. The changes disposing
+    /// the Endpoints deleted earlier are removed once acknowledged.
+    fn withdraw(&mut self, core: &mut ProtocolCore, writer_id: EntityId_t, endpoint_guid: GUID_t) {
+        let writer = match core.writer_mut(writer_id) {
+            Some(writer) => writer,
+            None => return,
+        };
+        let sequence_number = match self.announced.remove(&endpoint_guid) {
+            Some(sequence_number) => sequence_number,
+            None => return,
+        };
+        writer
+            .as_writer_mut()
+            .writer_cache
+            .remove_change(sequence_number);
+
+        self.disposed
+            .retain(|(disposed_writer_id, sequence_number)| {
+                if *disposed_writer_id != writer_id {
+                    return true;
+                }
+                let acknowledged = writer
+                    .as_writer()
+                    .writer_cache
+                    .get_change(*sequence_number)
+                    .is_none_or(|change| writer.is_acked_by_all(change));
+                if acknowledged {
+                    writer
+                        .as_writer_mut()
+                        .writer_cache
+                        .remove_change(*sequence_number);
+                }
+                !acknowledged
+            });
+
+        let change = writer.new_change(
+            ChangeKind_t::NOT_ALIVE_DISPOSED_UNREGISTERED,
+            Data::from(pl_cdr::serialize_key(
+                ParameterId::PID_ENDPOINT_GUID,
+                endpoint_guid,
+                Endianness::LittleEndian,
+            )),
+            pl_cdr::key_hash(endpoint_guid),
+        );
+        self.disposed.push((writer_id, change.sequence_number));
+        writer.as_writer_mut().writer_cache.add_change(change);
+    }
+}
+
+fn endpoint() -> EndpointAttributes {
+    EndpointAttributes {
+        topic_kind: TopicKind_t::WITH_KEY,
+        reliability_level: ReliabilityKind_t::RELIABLE,
+        unicast_locator_list: vec![],
+        multicast_locator_list: vec![],
+    }
+}
+
+/// Removes all received changes from the reader_cache of the built-in Reader.
+fn take_changes(core: &mut ProtocolCore, reader_id: EntityId_t) -> Vec<CacheChange> {
+    match core.reader_mut(reader_id) {
+        Some(reader) => std::mem::take(&mut reader.as_reader_mut().reader_cache).into_changes(),
+        None => vec![],
+    }
+}
+
+/// The GUID of the Endpoint disposed or unregistered by the change.
+fn endpoint_guid(change: &CacheChange) -> Result<GUID_t, Error> {
+    pl_cdr::deserialize_key(
+        change.instance_handle,
+        &change.data_value.value,
+        &ParameterId::PID_ENDPOINT_GUID,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::protocol_version::ProtocolVersion_t;
+    use crate::messages::vendor_id::VendorId_t;
+    use crate::structure::count::Count_t;
+    use crate::structure::locator::{Ipv4Addr, Locator_t, SocketAddr};
+
+    fn locator(key: u8) -> Locator_t {
+        Locator_t::from(SocketAddr::new(
+            Ipv4Addr::LOCALHOST.into(),
+            7410 + u16::from(key),
+        ))
+    }
+
+    fn participant_data(
+        key: u8,
+        available_builtin_endpoints: BuiltinEndpointSet_t,
+    ) -> SpdpDiscoveredParticipantData {
+        SpdpDiscoveredParticipantData {
+            domain_id: None,
+            domain_tag: String::new(),
+            protocol_version: ProtocolVersion_t::PROTOCOLVERSION,
+            guid_prefix: GuidPrefix_t::from([key; 12]),
+            vendor_id: VendorId_t::VENDOR_UNKNOWN,
+            expects_inline_qos: false,
+            available_builtin_endpoints,
+            metatraffic_unicast_locator_list: vec![locator(key)],
+            metatraffic_multicast_locator_list: vec![],
+            default_unicast_locator_list: vec![],
+            default_multicast_locator_list: vec![],
+            manual_liveliness_count: Count_t::from(0),
+            lease_duration: Duration_t::DURATION_INFINITE,
+            user_data: vec![],
+        }
+    }
+
+    struct Participant {
+        key: u8,
+        core: ProtocolCore,
+        sedp: Sedp,
+    }
+
+    impl Participant {
+        fn new(now: Instant, key: u8) -> Self {
+            let guid_prefix = GuidPrefix_t::from([key; 12]);
+            let mut core = ProtocolCore::new(guid_prefix, VendorId_t::VENDOR_UNKNOWN);
+            let sedp = Sedp::new(now, &mut core, guid_prefix, Duration::from_secs(1));
+            Participant { key, core, sedp }
+        }
+
+        fn guid(&self, entity_key: u8, entity_kind: u8) -> GUID_t {
+            GUID_t {
+                guid_prefix: self.core.guid_prefix(),
+                entity_id: EntityId_t::new([0x00, 0x00, entity_key], entity_kind),
+            }
+        }
+
+        /// Processes all messages sent by the other Participant, returns
+        /// whether any was sent.
+        fn receive(&mut self, now: Instant, from: &mut Participant) -> bool {
+            let mut received = false;
+            while let Some(transmit) = from.core.poll_transmit() {
+                assert_eq!(locator(self.key), transmit.destination);
+                self.core
+                    .handle_datagram(now, locator(from.key), transmit.contents);
+                received = true;
+            }
+            while let Some(event) = self.core.poll_event() {
+                match event {
+                    Event::DataAvailable { reader_guid } => {
+                        assert!(self.sedp.is_builtin_reader(reader_guid));
+                        self.sedp.handle_data_available(&mut self.core);
+                    }
+                    other => panic!("Unexpected event {:?}", other),
+                }
+            }
+            received
+        }
+    }
+
+    /// Exchanges the messages until both Participants have nothing to send.
+    fn exchange(now: Instant, first: &mut Participant, second: &mut Participant) {
+        while second.receive(now, first) | first.receive(now, second) {}
+    }
+
+    fn discover(first: &mut Participant, second: &mut Participant) {
+        first.sedp.add_participant(
+            &mut first.core,
+            &participant_data(second.key, Sedp::BUILTIN_ENDPOINTS),
+        );
+        second.sedp.add_participant(
+            &mut second.core,
+            &participant_data(first.key, Sedp::BUILTIN_ENDPOINTS),
+        );
+    }
+
+    #[test]
+    fn endpoints_are_announced_to_discovered_participants() {
+        let now = Instant::now();
+        let mut first = Participant::new(now, 0x01);
+        let mut second = Participant::new(now, 0x02);
+        let writer_data = DiscoveredWriterData::new(first.guid(0x01, 0x02), "topic", "type");
+        let reader_data = DiscoveredReaderData::new(second.guid(0x01, 0x07), "topic", "type");
+
+        // Announced before the Participants discover each other
+        first.sedp.announce_writer(&mut first.core, &writer_data);
+        second.sedp.announce_reader(&mut second.core, &reader_data);
+        assert_eq!(None, first.core.poll_transmit());
+
+        discover(&mut first, &mut second);
+        exchange(now, &mut first, &mut second);
+
+        assert_eq!(
+            Some(Event::PublicationDiscovered {
+                writer_data: writer_data.clone()
+            }),
+            second.sedp.poll_event()
+        );
+        assert_eq!(None, second.sedp.poll_event());
+        assert_eq!(
+            Some(Event::SubscriptionDiscovered {
+                reader_data: reader_data.clone()
+            }),
+            first.sedp.poll_event()
+        );
+        assert_eq!(
+            vec![&writer_data],
+            second.sedp.discovered_writers().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![&reader_data],
+            first.sedp.discovered_readers().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn changed_announcement_replaces_previous_one() {
+        let now = Instant::now();
+        let mut first = Participant::new(now, 0x01);
+        let mut second = Participant::new(now, 0x02);
+        discover(&mut first, &mut second);
+        let mut writer_data = DiscoveredWriterData::new(first.guid(0x01, 0x02), "topic", "type");
+        first.sedp.announce_writer(&mut first.core, &writer_data);
+        exchange(now, &mut first, &mut second);
+        second.sedp.poll_event();
+
+        writer_data.partition.name = vec!["partition".to_string()];
+        first.sedp.announce_writer(&mut first.core, &writer_data);
+        exchange(now, &mut first, &mut second);
+
+        assert_eq!(
+            Some(Event::PublicationDiscovered {
+                writer_data: writer_data.clone()
+            }),
+            second.sedp.poll_event()
+        );
+        assert_eq!(1, second.sedp.discovered_writers().count());
+        assert_eq!(
+            1,
+            first
+                .core
+                .writer_mut(EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_WRITER)
+                .unwrap()
+                .as_writer()
+                .writer_cache
+                .changes()
+                .len()
+        );
+    }
+
+    #[test]
+    fn lost_announcement_is_repaired() {
+        let now = Instant::now();
+        let mut first = Participant::new(now, 0x01);
+        let mut second = Participant::new(now, 0x02);
+        discover(&mut first, &mut second);
+        let writer_data = DiscoveredWriterData::new(first.guid(0x01, 0x02), "topic", "type");
+        first.sedp.announce_writer(&mut first.core, &writer_data);
+
+        // The Data message is lost
+        while first.core.poll_transmit().is_some() {}
+        let now = now + Duration::from_secs(1);
+        first.core.handle_timeout(now);
+        exchange(now, &mut first, &mut second);
+
+        assert_eq!(
+            Some(Event::PublicationDiscovered { writer_data }),
+            second.sedp.poll_event()
+        );
+    }
+
+    #[test]
+    fn endpoints_of_removed_participant_are_lost() {
+        let now = Instant::now();
+        let mut first = Participant::new(now, 0x01);
+        let mut second = Participant::new(now, 0x02);
+        discover(&mut first, &mut second);
+        let writer_guid = first.guid(0x01, 0x02);
+        let reader_guid = first.guid(0x02, 0x07);
+        first.sedp.announce_writer(
+            &mut first.core,
+            &DiscoveredWriterData::new(writer_guid, "topic", "type"),
+        );
+        first.sedp.announce_reader(
+            &mut first.core,
+            &DiscoveredReaderData::new(reader_guid, "topic", "type"),
+        );
+        exchange(now, &mut first, &mut second);
+        while second.sedp.poll_event().is_some() {}
+
+        second
+            .sedp
+            .remove_participant(&mut second.core, first.core.guid_prefix());
+
+        assert_eq!(
+            Some(Event::PublicationLost { writer_guid }),
+            second.sedp.poll_event()
+        );
+        assert_eq!(
+            Some(Event::SubscriptionLost { reader_guid }),
+            second.sedp.poll_event()
+        );
+        assert_eq!(0, second.sedp.discovered_writers().count());
+        // No more Heartbeats are sent to the lost Participant
+        second.core.handle_timeout(now + Duration::from_secs(1));
+        assert_eq!(None, second.core.poll_transmit());
+    }
+
+    #[test]
+    fn deleted_endpoints_are_lost_by_remote_participants() {
+        let now = Instant::now();
+        let mut first = Participant::new(now, 0x01);
+        let mut second = Participant::new(now, 0x02);
+        discover(&mut first, &mut second);
+        let writer_guids = [first.guid(0x01, 0x02), first.guid(0x02, 0x02)];
+        let reader_guid = first.guid(0x03, 0x07);
+        for writer_guid in &writer_guids {
+            first.sedp.announce_writer(
+                &mut first.core,
+                &DiscoveredWriterData::new(*writer_guid, "topic", "type"),
+            );
+        }
+        first.sedp.announce_reader(
+            &mut first.core,
+            &DiscoveredReaderData::new(reader_guid, "topic", "type"),
+        );
+        exchange(now, &mut first, &mut second);
+        while second.sedp.poll_event().is_some() {}
+
+        first
+            .sedp
+            .remove_local_writer(&mut first.core, writer_guids[0]);
+        first.sedp.remove_local_reader(&mut first.core, reader_guid);
+        exchange(now, &mut first, &mut second);
+
+        assert_eq!(
+            Some(Event::PublicationLost {
+                writer_guid: writer_guids[0]
+            }),
+            second.sedp.poll_event()
+        );
+        assert_eq!(
+            Some(Event::SubscriptionLost { reader_guid }),
+            second.sedp.poll_event()
+        );
+        assert_eq!(None, second.sedp.poll_event());
+        assert_eq!(1, second.sedp.discovered_writers().count());
+        assert_eq!(0, second.sedp.discovered_readers().count());
+
+        // The disposal acknowledged after the next Heartbeat is removed
+        // with the next one
+        let now = now + Duration::from_secs(1);
+        first.core.handle_timeout(now);
+        exchange(now, &mut first, &mut second);
+        first
+            .sedp
+            .remove_local_writer(&mut first.core, writer_guids[1]);
+        exchange(now, &mut first, &mut second);
+        assert_eq!(
+            Some(Event::PublicationLost {
+                writer_guid: writer_guids[1]
+            }),
+            second.sedp.poll_event()
+        );
+        let changes = first
+            .core
+            .writer_mut(EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_WRITER)
+            .unwrap()
+            .as_writer()
+            .writer_cache
+            .changes()
+            .iter()
+            .map(|change| (change.kind, change.instance_handle))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![(
+                ChangeKind_t::NOT_ALIVE_DISPOSED_UNREGISTERED,
+                pl_cdr::key_hash(writer_guids[1])
+            )],
+            changes
+        );
+    }
+
+    #[test]
+    fn only_available_builtin_endpoints_are_matched() {
+        let now = Instant::now();
+        let mut first = Participant::new(now, 0x01);
+        first.sedp.add_participant(
+            &mut first.core,
+            &participant_data(
+                0x02,
+                BuiltinEndpointSet_t::DISC_BUILTIN_ENDPOINT_PUBLICATIONS_DETECTOR,
+            ),
+        );
+        let writer_data = DiscoveredWriterData::new(first.guid(0x01, 0x02), "topic", "type");
+        let reader_data = DiscoveredReaderData::new(first.guid(0x02, 0x07), "topic", "type");
+        first.sedp.announce_writer(&mut first.core, &writer_data);
+        first.sedp.announce_reader(&mut first.core, &reader_data);
+
+        // Only the publication is sent
+        assert!(first.core.poll_transmit().is_some());
+        assert_eq!(None, first.core.poll_transmit());
+        assert!(first
+            .core
+            .reader(EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_READER)
+            .unwrap()
+            .matched_writer_lookup(GUID_t {
+                guid_prefix: GuidPrefix_t::from([0x02; 12]),
+                entity_id: EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_WRITER,
+            })
+            .is_none());
+    }
+}
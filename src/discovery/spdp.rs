@@ -0,0 +1,598 @@
+use crate::behavior::reader::{Reader, ReaderAttributes};
+use crate::behavior::reader_locator::ReaderLocator;
+use crate::behavior::stateless_reader::StatelessReader;
+use crate::behavior::stateless_writer::StatelessWriter;
+use crate::behavior::writer::{Writer, WriterAttributes};
+use crate::common::validity_trait::Validity;
+use crate::discovery::builtin_endpoint_set::BuiltinEndpointSet_t;
+use crate::discovery::pl_cdr;
+use crate::discovery::spdp_discovered_participant_data::SpdpDiscoveredParticipantData;
+use crate::protocol::event::Event;
+use crate::protocol::protocol_core::ProtocolCore;
+use crate::structure::change_kind::ChangeKind_t;
+use crate::structure::data::Data;
+use crate::structure::duration::Duration_t;
+use crate::structure::endpoint::EndpointAttributes;
+use crate::structure::entity::EntityAttributes;
+use crate::structure::entity_id::EntityId_t;
+use crate::structure::guid::GUID_t;
+use crate::structure::guid_prefix::GuidPrefix_t;
+use crate::structure::history_cache::HistoryCache;
+use crate::structure::instance_handle::InstanceHandle_t;
+use crate::structure::locator::LocatorList_t;
+use crate::structure::parameter_id::ParameterId;
+use crate::structure::reliability_kind::ReliabilityKind_t;
+use crate::structure::sequence_number::SequenceNumber_t;
+use crate::structure::topic_kind::TopicKind_t;
+
+use log::debug;
+use speedy::Endianness;
+use std::collections::{BTreeMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// The announcement always fits into a single Data Submessage.
+const DATA_MAX_SIZE_SERIALIZED: u32 = 64 * 1024;
+
+/// A remote Participant known from its SPDP announcements.
+struct DiscoveredParticipant {
+    participant_data: SpdpDiscoveredParticipantData,
+
+    /// When the Participant is considered gone unless announced again. None
+    /// for the infinite lease duration.
+    lease_expiration: Option<Instant>,
+}
+
+/// The Simple Participant Discovery Protocol (8.5.3).
+///
+/// The SPDPbuiltinParticipantWriter, a best-effort StatelessWriter,
+/// periodically announces the local Participant to the announcement
+/// locators, usually the metatraffic multicast locator and the initial peers.
+/// The SPDPbuiltinParticipantReader receives the announcements of the remote
+/// Participants, which are kept until their lease duration passes without
+/// a new announcement.
+///
+/// Like the ProtocolCore, Spdp is free of any I/O and driven by the caller;
+/// the Endpoints are installed into the ProtocolCore of the Participant.
+pub struct Spdp {
+    participant_data: SpdpDiscoveredParticipantData,
+    discovered_participants: BTreeMap<GuidPrefix_t, DiscoveredParticipant>,
+    events: VecDeque<Event>,
+}
+
+impl Spdp {
+    pub const WRITER_ID: EntityId_t = EntityId_t::ENTITYID_SPDP_BUILTIN_PARTICIPANT_WRITER;
+    pub const READER_ID: EntityId_t = EntityId_t::ENTITYID_SPDP_BUILTIN_PARTICIPANT_READER;
+
+    /// The SPDP bits of the available_builtin_endpoints (8.5.3.2):
+    /// DISC_BUILTIN_ENDPOINT_PARTICIPANT_ANNOUNCER and _DETECTOR.
+    pub const BUILTIN_ENDPOINTS: BuiltinEndpointSet_t =
+        BuiltinEndpointSet_t::DISC_BUILTIN_ENDPOINT_PARTICIPANT_ANNOUNCER
+            .union(BuiltinEndpointSet_t::DISC_BUILTIN_ENDPOINT_PARTICIPANT_DETECTOR);
+
+    /// Installs the SPDP built-in Endpoints into the core. The participant
+    /// data is announced to the announcement locators right away and then
+    /// every resend_period.
+    pub fn new(
+        now: Instant,
+        core: &mut ProtocolCore,
+        participant_data: SpdpDiscoveredParticipantData,
+        announcement_locators: LocatorList_t,
+        resend_period: Duration,
+    ) -> Self {
+        let guid = |entity_id| GUID_t {
+            guid_prefix: participant_data.guid_prefix,
+            entity_id,
+        };
+
+        let mut writer = StatelessWriter::new(
+            EntityAttributes {
+                guid: guid(Spdp::WRITER_ID),
+            },
+            EndpointAttributes {
+                topic_kind: TopicKind_t::WITH_KEY,
+                reliability_level: ReliabilityKind_t::BEST_EFFORT,
+                unicast_locator_list: vec![],
+                multicast_locator_list: vec![],
+            },
+            WriterAttributes {
+                push_mode: true,
+                heartbeat_period: Duration_t::DURATION_ZERO,
+                nack_response_delay: Duration_t::DURATION_ZERO,
+                nack_suppression_duration: Duration_t::DURATION_ZERO,
+                last_change_sequence_number: SequenceNumber_t::from(0),
+                data_max_size_serialized: DATA_MAX_SIZE_SERIALIZED,
+                fragment_size: 1024,
+                writer_cache: HistoryCache::new(),
+            },
+            Duration_t::from(resend_period),
+        );
+        for locator in announcement_locators {
+            writer.reader_locator_add(ReaderLocator::new(locator, false));
+        }
+        let change = writer.new_change(
+            ChangeKind_t::ALIVE,
+            Data::from(participant_data.serialize(Endianness::LittleEndian)),
+            InstanceHandle_t::default(),
+        );
+        writer.as_writer_mut().writer_cache.add_change(change);
+
+        let reader = StatelessReader::new(
+            EntityAttributes {
+                guid: guid(Spdp::READER_ID),
+            },
+            EndpointAttributes {
+                topic_kind: TopicKind_t::WITH_KEY,
+                reliability_level: ReliabilityKind_t::BEST_EFFORT,
+                unicast_locator_list: participant_data.metatraffic_unicast_locator_list.clone(),
+                multicast_locator_list: participant_data.metatraffic_multicast_locator_list.clone(),
+            },
+            ReaderAttributes {
+                expects_inline_qos: false,
+                heartbeat_response_delay: Duration_t::DURATION_ZERO,
+                heartbeat_suppression_duration: Duration_t::DURATION_ZERO,
+                reader_cache: HistoryCache::new(),
+            },
+        );
+
+        core.add_stateless_writer(now, writer);
+        core.add_stateless_reader(reader);
+
+        Spdp {
+            participant_data,
+            discovered_participants: BTreeMap::new(),
+            events: VecDeque::new(),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn discovered_participants(&self) -> impl Iterator<Item = &SpdpDiscoveredParticipantData> {
+        self.discovered_participants
+            .values()
+            .map(|participant| &participant.participant_data)
+    }
+
+    pub fn discovered_participant(
+        &self,
+        guid_prefix: GuidPrefix_t,
+    ) -> Option<&SpdpDiscoveredParticipantData> {
+        self.discovered_participants
+            .get(&guid_prefix)
+            .map(|participant| &participant.participant_data)
+    }
+
+    /// Processes the announcements received by the SPDP reader of the core,
+    /// to be called for its DataAvailable events. Every announcement renews
+    /// the lease of the remote Participant. A newly discovered Participant
+    /// is answered with an immediate announcement, so it does not have to
+    /// wait for the next resend_period. The disposed and unregistered
+    /// Participants are lost right away.
+    pub fn handle_data_available(&mut self, now: Instant, core: &mut ProtocolCore) {
+        let reader_cache = match core.stateless_reader_mut(Spdp::READER_ID) {
+            Some(reader) => std::mem::take(&mut reader.as_reader_mut().reader_cache),
+            None => return,
+        };
+
+        let mut discovered = false;
+        for change in reader_cache.changes() {
+            if change.kind != ChangeKind_t::ALIVE {
+                match pl_cdr::deserialize_key(
+                    change.instance_handle,
+                    &change.data_value.value,
+                    &ParameterId::PID_PARTICIPANT_GUID,
+                ) {
+                    Ok(guid) => {
+                        let guid_prefix = guid.guid_prefix;
+                        if self.discovered_participants.remove(&guid_prefix).is_some() {
+                            self.events
+                                .push_back(Event::ParticipantLost { guid_prefix });
+                        }
+                    }
+                    Err(error) => debug!("Dropping malformed disposed participant: {}", error),
+                }
+                continue;
+            }
+            let participant_data =
+                match SpdpDiscoveredParticipantData::deserialize(&change.data_value.value) {
+                    Ok(participant_data) => participant_data,
+                    Err(error) => {
+                        debug!(
+                            "Dropping malformed announcement of {:?}: {}",
+                            change.writer_guid, error
+                        );
+                        continue;
+                    }
+                };
+            let guid_prefix = participant_data.guid_prefix;
+            if guid_prefix == self.participant_data.guid_prefix {
+                continue;
+            }
+            if !self.accepts(&participant_data) {
+                debug!(
+                    "Ignoring announcement of {:?} of domain {:?} {:?} with lease duration {:?}",
+                    guid_prefix,
+                    participant_data.domain_id,
+                    participant_data.domain_tag,
+                    participant_data.lease_duration
+                );
+                continue;
+            }
+
+            let lease_expiration = lease_expiration(now, participant_data.lease_duration);
+            let previous = self.discovered_participants.insert(
+                guid_prefix,
+                DiscoveredParticipant {
+                    participant_data: participant_data.clone(),
+                    lease_expiration,
+                },
+            );
+            if previous.is_none() {
+                discovered = true;
+                self.events
+                    .push_back(Event::ParticipantDiscovered { participant_data });
+            }
+        }
+
+        if discovered {
+            if let Some(writer) = core.stateless_writer_mut(Spdp::WRITER_ID) {
+                writer.unsent_changes_reset();
+            }
+        }
+    }
+
+    /// Announces the deletion of the local Participant: its announcement is
+    /// replaced by the change disposing it, sent to the announcement locators
+    /// right away, so the remote Participants do not wait for its lease
+    /// duration to pass.
+    pub fn dispose(&mut self, core: &mut ProtocolCore) {
+        let writer = match core.stateless_writer_mut(Spdp::WRITER_ID) {
+            Some(writer) => writer,
+            None => return,
+        };
+        let guid = GUID_t {
+            guid_prefix: self.participant_data.guid_prefix,
+            entity_id: EntityId_t::ENTITYID_PARTICIPANT,
+        };
+        let change = writer.new_change(
+            ChangeKind_t::NOT_ALIVE_DISPOSED_UNREGISTERED,
+            Data::from(pl_cdr::serialize_key(
+                ParameterId::PID_PARTICIPANT_GUID,
+                guid,
+                Endianness::LittleEndian,
+            )),
+            pl_cdr::key_hash(guid),
+        );
+        let writer_cache = &mut writer.as_writer_mut().writer_cache;
+        *writer_cache = HistoryCache::new();
+        writer_cache.add_change(change);
+        writer.unsent_changes_reset();
+    }
+
+    /// Whether the announced Participant belongs to the domain of the local
+    /// one and its lease duration is valid. An announcement without the
+    /// domain id belongs to the domain of the receiver (9.6.2.2).
+    fn accepts(&self, participant_data: &SpdpDiscoveredParticipantData) -> bool {
+        let domain_id_matches = match (participant_data.domain_id, self.participant_data.domain_id)
+        {
+            (Some(domain_id), Some(local_domain_id)) => domain_id == local_domain_id,
+            _ => true,
+        };
+        domain_id_matches
+            && participant_data.domain_tag == self.participant_data.domain_tag
+            && participant_data.lease_duration.valid()
+    }
+
+    /// Removes the remote Participants whose lease expired before or at now.
+    pub fn handle_timeout(&mut self, now: Instant) {
+        let expired: Vec<GuidPrefix_t> = self
+            .discovered_participants
+            .iter()
+            .filter(|(_, participant)| {
+                participant
+                    .lease_expiration
+                    .is_some_and(|lease_expiration| lease_expiration <= now)
+            })
+            .map(|(guid_prefix, _)| *guid_prefix)
+            .collect();
+
+        for guid_prefix in expired {
+            self.discovered_participants.remove(&guid_prefix);
+            self.events
+                .push_back(Event::ParticipantLost { guid_prefix });
+        }
+    }
+
+    /// The earliest time at which a lease expires.
+    pub fn poll_timeout(&self) -> Option<Instant> {
+        self.discovered_participants
+            .values()
+            .filter_map(|participant| participant.lease_expiration)
+            .min()
+    }
+
+    pub fn poll_event(&mut self) -> Option<Event> {
+        self.events.pop_front()
+    }
+}
+
+fn lease_expiration(now: Instant, lease_duration: Duration_t) -> Option<Instant> {
+    if lease_duration == Duration_t::DURATION_INFINITE {
+        return None;
+    }
+    now.checked_add(Duration::from(lease_duration))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::protocol_version::ProtocolVersion_t;
+    use crate::messages::vendor_id::VendorId_t;
+    use crate::structure::count::Count_t;
+    use crate::structure::locator::{Ipv4Addr, Locator_t, SocketAddr};
+
+    const MULTICAST_PORT: u16 = 7400;
+
+    fn locator(port: u16) -> Locator_t {
+        Locator_t::from(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), port))
+    }
+
+    fn participant_data(key: u8, lease_duration: Duration) -> SpdpDiscoveredParticipantData {
+        SpdpDiscoveredParticipantData {
+            domain_id: None,
+            domain_tag: String::new(),
+            protocol_version: ProtocolVersion_t::PROTOCOLVERSION,
+            guid_prefix: GuidPrefix_t::from([key; 12]),
+            vendor_id: VendorId_t::VENDOR_UNKNOWN,
+            expects_inline_qos: false,
+            available_builtin_endpoints: Spdp::BUILTIN_ENDPOINTS,
+            metatraffic_unicast_locator_list: vec![locator(7410 + u16::from(key))],
+            metatraffic_multicast_locator_list: vec![locator(MULTICAST_PORT)],
+            default_unicast_locator_list: vec![],
+            default_multicast_locator_list: vec![],
+            manual_liveliness_count: Count_t::from(0),
+            lease_duration: Duration_t::from(lease_duration),
+            user_data: vec![],
+        }
+    }
+
+    struct Participant {
+        core: ProtocolCore,
+        spdp: Spdp,
+    }
+
+    impl Participant {
+        fn new(now: Instant, key: u8, lease_duration: Duration) -> Self {
+            Participant::announcing(now, participant_data(key, lease_duration))
+        }
+
+        fn announcing(now: Instant, participant_data: SpdpDiscoveredParticipantData) -> Self {
+            let mut core =
+                ProtocolCore::new(participant_data.guid_prefix, VendorId_t::VENDOR_UNKNOWN);
+            let spdp = Spdp::new(
+                now,
+                &mut core,
+                participant_data,
+                vec![locator(MULTICAST_PORT)],
+                Duration::from_secs(1),
+            );
+            Participant { core, spdp }
+        }
+
+        /// Processes the messages sent by the other Participant to the
+        /// multicast locator.
+        fn receive(&mut self, now: Instant, from: &mut Participant) -> usize {
+            let mut received = 0;
+            while let Some(transmit) = from.core.poll_transmit() {
+                assert_eq!(locator(MULTICAST_PORT), transmit.destination);
+                self.core
+                    .handle_datagram(now, locator(7410), transmit.contents);
+                received += 1;
+            }
+            while let Some(event) = self.core.poll_event() {
+                assert_eq!(
+                    Event::DataAvailable {
+                        reader_guid: GUID_t {
+                            guid_prefix: self.core.guid_prefix(),
+                            entity_id: Spdp::READER_ID,
+                        }
+                    },
+                    event
+                );
+                self.spdp.handle_data_available(now, &mut self.core);
+            }
+            received
+        }
+
+        fn handle_timeout(&mut self, now: Instant) {
+            self.core.handle_timeout(now);
+            self.spdp.handle_timeout(now);
+        }
+    }
+
+    #[test]
+    fn participants_discover_each_other() {
+        let now = Instant::now();
+        let mut first = Participant::new(now, 0x01, Duration::from_secs(10));
+        let mut second = Participant::new(now, 0x02, Duration::from_secs(10));
+
+        assert_eq!(1, second.receive(now, &mut first));
+        assert_eq!(
+            Some(Event::ParticipantDiscovered {
+                participant_data: participant_data(0x01, Duration::from_secs(10))
+            }),
+            second.spdp.poll_event()
+        );
+        assert_eq!(None, second.spdp.poll_event());
+
+        // Announced immediately and once more as the reply to the discovery
+        assert_eq!(1, first.receive(now, &mut second));
+        assert_eq!(
+            vec![&participant_data(0x02, Duration::from_secs(10))],
+            first.spdp.discovered_participants().collect::<Vec<_>>()
+        );
+        assert!(matches!(
+            first.spdp.poll_event(),
+            Some(Event::ParticipantDiscovered { .. })
+        ));
+
+        // Repeated announcements do not discover the Participant again
+        assert_eq!(1, second.receive(now, &mut first));
+        assert_eq!(None, second.spdp.poll_event());
+    }
+
+    #[test]
+    fn announcements_of_other_domains_are_ignored() {
+        let now = Instant::now();
+        let mut local = participant_data(0x01, Duration::from_secs(10));
+        local.domain_id = Some(1);
+        local.domain_tag = String::from("tag");
+        let mut first = Participant::announcing(now, local.clone());
+
+        let mut other_domain = local.clone();
+        other_domain.guid_prefix = GuidPrefix_t::from([0x02; 12]);
+        other_domain.domain_id = Some(2);
+        let mut other_tag = local.clone();
+        other_tag.guid_prefix = GuidPrefix_t::from([0x03; 12]);
+        other_tag.domain_tag = String::new();
+        for participant_data in [other_domain, other_tag] {
+            let mut second = Participant::announcing(now, participant_data);
+            assert_eq!(1, first.receive(now, &mut second));
+            assert_eq!(None, first.spdp.poll_event());
+        }
+
+        // Without the domain id, the announcement belongs to the domain
+        let mut same_domain = local;
+        same_domain.guid_prefix = GuidPrefix_t::from([0x04; 12]);
+        same_domain.domain_id = None;
+        let mut second = Participant::announcing(now, same_domain);
+        assert_eq!(1, first.receive(now, &mut second));
+        assert!(matches!(
+            first.spdp.poll_event(),
+            Some(Event::ParticipantDiscovered { .. })
+        ));
+    }
+
+    #[test]
+    fn announcement_with_invalid_lease_duration_is_ignored() {
+        let now = Instant::now();
+        let mut first = Participant::new(now, 0x01, Duration::from_secs(10));
+        let mut invalid = participant_data(0x02, Duration::from_secs(10));
+        invalid.lease_duration = Duration_t::DURATION_INVALID;
+        let mut second = Participant::announcing(now, invalid);
+
+        assert_eq!(1, first.receive(now, &mut second));
+        assert_eq!(None, first.spdp.poll_event());
+        assert_eq!(None, first.spdp.poll_timeout());
+    }
+
+    #[test]
+    fn participant_is_announced_every_resend_period() {
+        let now = Instant::now();
+        let mut first = Participant::new(now, 0x01, Duration::from_secs(10));
+        let mut second = Participant::new(now, 0x02, Duration::from_secs(10));
+        first.core.poll_transmit();
+
+        assert_eq!(
+            Some(now + Duration::from_secs(1)),
+            first.core.poll_timeout()
+        );
+        assert_eq!(0, second.receive(now, &mut first));
+
+        let now = now + Duration::from_secs(1);
+        first.handle_timeout(now);
+
+        assert_eq!(1, second.receive(now, &mut first));
+        assert!(second
+            .spdp
+            .discovered_participant(first.core.guid_prefix())
+            .is_some());
+    }
+
+    #[test]
+    fn participant_is_lost_after_lease_duration() {
+        let now = Instant::now();
+        let mut first = Participant::new(now, 0x01, Duration::from_secs(3));
+        let mut second = Participant::new(now, 0x02, Duration::from_secs(10));
+        second.receive(now, &mut first);
+        second.spdp.poll_event();
+
+        // The lease is renewed by the announcement
+        let now = now + Duration::from_secs(2);
+        first.handle_timeout(now);
+        second.receive(now, &mut first);
+        assert_eq!(
+            Some(now + Duration::from_secs(3)),
+            second.spdp.poll_timeout()
+        );
+
+        // The announcements are lost
+        let now = now + Duration::from_secs(3);
+        second.handle_timeout(now);
+
+        assert_eq!(
+            Some(Event::ParticipantLost {
+                guid_prefix: first.core.guid_prefix()
+            }),
+            second.spdp.poll_event()
+        );
+        assert_eq!(0, second.spdp.discovered_participants().count());
+        assert_eq!(None, second.spdp.poll_timeout());
+    }
+
+    #[test]
+    fn disposed_participant_is_lost_immediately() {
+        let now = Instant::now();
+        let mut first = Participant::new(now, 0x01, Duration::from_secs(10));
+        let mut second = Participant::new(now, 0x02, Duration::from_secs(10));
+        second.receive(now, &mut first);
+        second.spdp.poll_event();
+
+        first.spdp.dispose(&mut first.core);
+
+        assert_eq!(1, second.receive(now, &mut first));
+        assert_eq!(
+            Some(Event::ParticipantLost {
+                guid_prefix: first.core.guid_prefix()
+            }),
+            second.spdp.poll_event()
+        );
+        assert_eq!(0, second.spdp.discovered_participants().count());
+        assert_eq!(None, second.spdp.poll_timeout());
+
+        // Only the disposal is announced further
+        let now = now + Duration::from_secs(1);
+        first.handle_timeout(now);
+        assert_eq!(1, second.receive(now, &mut first));
+        assert_eq!(None, second.spdp.poll_event());
+    }
+
+    #[test]
+    fn own_announcement_is_ignored() {
+        let now = Instant::now();
+        let mut participant = Participant::new(now, 0x01, Duration::from_secs(10));
+        let transmit = participant.core.poll_transmit().unwrap();
+
+        participant
+            .core
+            .handle_datagram(now, locator(7400), transmit.contents);
+        participant
+            .spdp
+            .handle_data_available(now, &mut participant.core);
+
+        assert_eq!(None, participant.spdp.poll_event());
+        assert_eq!(0, participant.spdp.discovered_participants().count());
+    }
+
+    #[test]
+    fn infinite_lease_never_expires() {
+        let now = Instant::now();
+
+        assert_eq!(None, lease_expiration(now, Duration_t::DURATION_INFINITE));
+        assert_eq!(
+            Some(now + Duration::from_secs(100)),
+            lease_expiration(
+                now,
+                Duration_t::from(SpdpDiscoveredParticipantData::DEFAULT_LEASE_DURATION)
+            )
+        );
+    }
+}
@@ -0,0 +1,612 @@
+use crate::discovery::builtin_endpoint_set::BuiltinEndpointSet_t;
+use crate::discovery::pl_cdr;
+use crate::messages::protocol_version::ProtocolVersion_t;
+use crate::messages::submessage_elements::parameter_list::ParameterList;
+use crate::messages::vendor_id::VendorId_t;
+use crate::structure::count::Count_t;
+use crate::structure::duration::Duration_t;
+use crate::structure::entity_id::EntityId_t;
+use crate::structure::guid::GUID_t;
+use crate::structure::guid_prefix::GuidPrefix_t;
+use crate::structure::locator::LocatorList_t;
+use crate::structure::parameter_id::ParameterId;
+
+use bytes::Bytes;
+use speedy::Endianness;
+use std::io::{Error, ErrorKind};
+use std::time::Duration;
+
+/// The data announced by the SPDPbuiltinParticipantWriter (8.5.3.2): the
+/// ParticipantProxy of the local Participant together with its lease
+/// duration and user data. Serialized as a ParameterList with the PL_CDR
+/// encapsulation (9.6.2.2).
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpdpDiscoveredParticipantData {
+    /// Identifies the DDS domainId of the associated DDS DomainParticipant.
+    /// Not announced by all implementations.
+    pub domain_id: Option<u32>,
+
+    /// Further isolates the Participants within the DDS domain; only the
+    /// Participants with the same domain tag communicate. Empty by default.
+    pub domain_tag: String,
+
+    /// Identifies the RTPS protocol version used by the Participant.
+    pub protocol_version: ProtocolVersion_t,
+
+    /// The common GuidPrefix_t of the Participant and all the Endpoints
+    /// contained within the Participant.
+    pub guid_prefix: GuidPrefix_t,
+
+    /// Identifies the vendor of the DDS middleware that contains the
+    /// Participant.
+    pub vendor_id: VendorId_t,
+
+    /// Describes whether the Readers within the Participant expect that
+    /// the QoS values that apply to each data modification are encapsulated
+    /// included with each Data.
+    pub expects_inline_qos: bool,
+
+    /// All Participants must support the SEDP. This attribute identifies the
+    /// kinds of built-in SEDP Endpoints that are available in the
+    /// Participant.
+    pub available_builtin_endpoints: BuiltinEndpointSet_t,
+
+    /// List of unicast locators that can be used to send messages to the
+    /// built-in Endpoints contained in the Participant.
+    pub metatraffic_unicast_locator_list: LocatorList_t,
+
+    /// List of multicast locators that can be used to send messages to the
+    /// built-in Endpoints contained in the Participant.
+    pub metatraffic_multicast_locator_list: LocatorList_t,
+
+    /// Default list of unicast locators that can be used to send messages to
+    /// the user-defined Endpoints contained in the Participant.
+    pub default_unicast_locator_list: LocatorList_t,
+
+    /// Default list of multicast locators that can be used to send messages
+    /// to the user-defined Endpoints contained in the Participant.
+    pub default_multicast_locator_list: LocatorList_t,
+
+    /// Used to implement MANUAL_BY_PARTICIPANT liveliness QoS.
+    pub manual_liveliness_count: Count_t,
+
+    /// How long a Participant should be considered alive every time an
+    /// announcement is received from the Participant.
+    pub lease_duration: Duration_t,
+
+    /// The user data of the DomainParticipant.
+    pub user_data: Vec<u8>,
+}
+
+impl SpdpDiscoveredParticipantData {
+    /// The lease duration assumed when the announcement does not contain it.
+    pub const DEFAULT_LEASE_DURATION: Duration = Duration::from_secs(100);
+
+    /// The participant GUID; the Participant entity within the prefix.
+    pub fn participant_guid(&self) -> GUID_t {
+        GUID_t {
+            guid_prefix: self.guid_prefix,
+            entity_id: EntityId_t::ENTITYID_PARTICIPANT,
+        }
+    }
+
+    /// Serializes the data with the PL_CDR encapsulation of the endianness.
+    /// The optional parameters with default values (domain tag, inline QoS
+    /// expectation, manual liveliness count and user data) are omitted; the
+    /// lease duration and the built-in endpoints are always written, as not
+    /// all implementations assume their defaults.
+    pub fn serialize(&self, endianness: Endianness) -> Bytes {
+        pl_cdr::serialize(&self.to_parameter_list(endianness), endianness)
+    }
+
+    /// Deserializes the data with the PL_CDR encapsulation. The parameters
+    /// not known to this implementation, including the vendor-specific ones,
+    /// are ignored.
+    pub fn deserialize(payload: &[u8]) -> Result<Self, Error> {
+        let (endianness, parameter_list) = pl_cdr::deserialize(payload)?;
+        SpdpDiscoveredParticipantData::from_parameter_list(&parameter_list, endianness)
+    }
+
+    pub fn to_parameter_list(&self, endianness: Endianness) -> ParameterList {
+        let mut parameter_list = ParameterList::new();
+        parameter_list.push(pl_cdr::parameter(
+            ParameterId::PID_PROTOCOL_VERSION,
+            &self.protocol_version,
+            endianness,
+        ));
+        parameter_list.push(pl_cdr::parameter(
+            ParameterId::PID_VENDOR_ID,
+            &self.vendor_id,
+            endianness,
+        ));
+        parameter_list.push(pl_cdr::parameter(
+            ParameterId::PID_PARTICIPANT_GUID,
+            &self.participant_guid(),
+            endianness,
+        ));
+        if let Some(domain_id) = self.domain_id {
+            parameter_list.push(pl_cdr::parameter(
+                ParameterId::PID_DOMAIN_ID,
+                &domain_id,
+                endianness,
+            ));
+        }
+        if !self.domain_tag.is_empty() {
+            parameter_list.push(pl_cdr::string_parameter(
+                ParameterId::PID_DOMAIN_TAG,
+                &self.domain_tag,
+                endianness,
+            ));
+        }
+        if self.expects_inline_qos {
+            parameter_list.push(pl_cdr::parameter(
+                ParameterId::PID_EXPECTS_INLINE_QOS,
+                &true,
+                endianness,
+            ));
+        }
+        for (parameter_id, locator_list) in [
+            (
+                ParameterId::PID_METATRAFFIC_UNICAST_LOCATOR,
+                &self.metatraffic_unicast_locator_list,
+            ),
+            (
+                ParameterId::PID_METATRAFFIC_MULTICAST_LOCATOR,
+                &self.metatraffic_multicast_locator_list,
+            ),
+            (
+                ParameterId::PID_DEFAULT_UNICAST_LOCATOR,
+                &self.default_unicast_locator_list,
+            ),
+            (
+                ParameterId::PID_DEFAULT_MULTICAST_LOCATOR,
+                &self.default_multicast_locator_list,
+            ),
+        ] {
+            for locator in locator_list {
+                parameter_list.push(pl_cdr::parameter(parameter_id, locator, endianness));
+            }
+        }
+        parameter_list.push(pl_cdr::parameter(
+            ParameterId::PID_PARTICIPANT_LEASE_DURATION,
+            &self.lease_duration,
+            endianness,
+        ));
+        parameter_list.push(pl_cdr::parameter(
+            ParameterId::PID_BUILTIN_ENDPOINT_SET,
+            &self.available_builtin_endpoints,
+            endianness,
+        ));
+        if self.manual_liveliness_count != Count_t::from(0) {
+            parameter_list.push(pl_cdr::parameter(
+                ParameterId::PID_PARTICIPANT_MANUAL_LIVELINESS_COUNT,
+                &self.manual_liveliness_count,
+                endianness,
+            ));
+        }
+        if !self.user_data.is_empty() {
+            parameter_list.push(pl_cdr::octets_parameter(
+                ParameterId::PID_USER_DATA,
+                &self.user_data,
+                endianness,
+            ));
+        }
+        parameter_list
+    }
+
+    pub fn from_parameter_list(
+        parameter_list: &ParameterList,
+        endianness: Endianness,
+    ) -> Result<Self, Error> {
+        let participant_guid: GUID_t = pl_cdr::read_optional(
+            parameter_list,
+            &ParameterId::PID_PARTICIPANT_GUID,
+            endianness,
+        )?
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Missing PID_PARTICIPANT_GUID"))?;
+        // PID_PARTICIPANT_BUILTIN_ENDPOINTS is the deprecated id of the set
+        let available_builtin_endpoints = match pl_cdr::read_optional(
+            parameter_list,
+            &ParameterId::PID_BUILTIN_ENDPOINT_SET,
+            endianness,
+        )? {
+            Some(available_builtin_endpoints) => available_builtin_endpoints,
+            None => pl_cdr::read_optional(
+                parameter_list,
+                &ParameterId::PID_PARTICIPANT_BUILTIN_ENDPOINTS,
+                endianness,
+            )?
+            .unwrap_or_default(),
+        };
+        let expects_inline_qos: Option<u8> = pl_cdr::read_optional(
+            parameter_list,
+            &ParameterId::PID_EXPECTS_INLINE_QOS,
+            endianness,
+        )?;
+        let user_data = parameter_list
+            .find(&ParameterId::PID_USER_DATA)
+            .map(|parameter| pl_cdr::read_octets(parameter, endianness))
+            .transpose()?;
+        let domain_tag = parameter_list
+            .find(&ParameterId::PID_DOMAIN_TAG)
+            .map(|parameter| pl_cdr::read_string(parameter, endianness))
+            .transpose()?;
+
+        Ok(SpdpDiscoveredParticipantData {
+            domain_id: pl_cdr::read_optional(
+                parameter_list,
+                &ParameterId::PID_DOMAIN_ID,
+                endianness,
+            )?,
+            domain_tag: domain_tag.unwrap_or_default(),
+            protocol_version: pl_cdr::read_optional(
+                parameter_list,
+                &ParameterId::PID_PROTOCOL_VERSION,
+                endianness,
+            )?
+            .unwrap_or(ProtocolVersion_t::PROTOCOLVERSION),
+            guid_prefix: participant_guid.guid_prefix,
+            vendor_id: pl_cdr::read_optional(
+                parameter_list,
+                &ParameterId::PID_VENDOR_ID,
+                endianness,
+            )?
+            .unwrap_or(VendorId_t::VENDOR_UNKNOWN),
+            expects_inline_qos: expects_inline_qos.is_some_and(|value| value != 0),
+            available_builtin_endpoints,
+            metatraffic_unicast_locator_list: pl_cdr::read_locators(
+                parameter_list,
+                &ParameterId::PID_METATRAFFIC_UNICAST_LOCATOR,
+                endianness,
+            )?,
+            metatraffic_multicast_locator_list: pl_cdr::read_locators(
+                parameter_list,
+                &ParameterId::PID_METATRAFFIC_MULTICAST_LOCATOR,
+                endianness,
+            )?,
+            default_unicast_locator_list: pl_cdr::read_locators(
+                parameter_list,
+                &ParameterId::PID_DEFAULT_UNICAST_LOCATOR,
+                endianness,
+            )?,
+            default_multicast_locator_list: pl_cdr::read_locators(
+                parameter_list,
+                &ParameterId::PID_DEFAULT_MULTICAST_LOCATOR,
+                endianness,
+            )?,
+            manual_liveliness_count: pl_cdr::read_optional(
+                parameter_list,
+                &ParameterId::PID_PARTICIPANT_MANUAL_LIVELINESS_COUNT,
+                endianness,
+            )?
+            .unwrap_or_else(|| Count_t::from(0)),
+            lease_duration: pl_cdr::read_optional(
+                parameter_list,
+                &ParameterId::PID_PARTICIPANT_LEASE_DURATION,
+                endianness,
+            )?
+            .unwrap_or_else(|| {
+                Duration_t::from(SpdpDiscoveredParticipantData::DEFAULT_LEASE_DURATION)
+            }),
+            user_data: user_data.unwrap_or_default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::locator::{Ipv4Addr, Locator_t, SocketAddr};
+
+    fn locator(address: Ipv4Addr, port: u16) -> Locator_t {
+        Locator_t::from(SocketAddr::new(address.into(), port))
+    }
+
+    fn participant_data() -> SpdpDiscoveredParticipantData {
+        SpdpDiscoveredParticipantData {
+            domain_id: Some(0),
+            domain_tag: String::new(),
+            protocol_version: ProtocolVersion_t::PROTOCOLVERSION_2_4,
+            guid_prefix: GuidPrefix_t::from([
+                0x00, 0x00, 0x01, 0x02, 0x03, 0x04, 0x00, 0x00, 0x00, 0x2A, 0x00, 0x00,
+            ]),
+            vendor_id: VendorId_t::VENDOR_UNKNOWN,
+            expects_inline_qos: false,
+            available_builtin_endpoints: BuiltinEndpointSet_t::from_bits_retain(0x0000_003F),
+            metatraffic_unicast_locator_list: vec![locator(Ipv4Addr::new(10, 0, 0, 1), 7410)],
+            metatraffic_multicast_locator_list: vec![locator(Ipv4Addr::new(239, 255, 0, 1), 7400)],
+            default_unicast_locator_list: vec![locator(Ipv4Addr::new(10, 0, 0, 1), 7411)],
+            default_multicast_locator_list: vec![],
+            manual_liveliness_count: Count_t::from(0),
+            lease_duration: Duration_t::from(Duration::from_secs(20)),
+            user_data: vec![0x61, 0x62, 0x63],
+        }
+    }
+
+    #[test]
+    fn participant_data_is_serialized_as_parameter_list() {
+        #[rustfmt::skip]
+        let expected: &[u8] = &[
+            0x00, 0x03, 0x00, 0x00, // PL_CDR_LE
+            0x15, 0x00, 0x04, 0x00, // PID_PROTOCOL_VERSION
+            0x02, 0x04, 0x00, 0x00,
+            0x16, 0x00, 0x04, 0x00, // PID_VENDOR_ID
+            0x00, 0x00, 0x00, 0x00,
+            0x50, 0x00, 0x10, 0x00, // PID_PARTICIPANT_GUID
+            0x00, 0x00, 0x01, 0x02,
+            0x03, 0x04, 0x00, 0x00,
+            0x00, 0x2A, 0x00, 0x00,
+            0x00, 0x00, 0x01, 0xC1,
+            0x0F, 0x00, 0x04, 0x00, // PID_DOMAIN_ID
+            0x00, 0x00, 0x00, 0x00,
+            0x32, 0x00, 0x18, 0x00, // PID_METATRAFFIC_UNICAST_LOCATOR
+            0x01, 0x00, 0x00, 0x00,
+            0xF2, 0x1C, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x0A, 0x00, 0x00, 0x01,
+            0x33, 0x00, 0x18, 0x00, // PID_METATRAFFIC_MULTICAST_LOCATOR
+            0x01, 0x00, 0x00, 0x00,
+            0xE8, 0x1C, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0xEF, 0xFF, 0x00, 0x01,
+            0x31, 0x00, 0x18, 0x00, // PID_DEFAULT_UNICAST_LOCATOR
+            0x01, 0x00, 0x00, 0x00,
+            0xF3, 0x1C, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x0A, 0x00, 0x00, 0x01,
+            0x02, 0x00, 0x08, 0x00, // PID_PARTICIPANT_LEASE_DURATION
+            0x14, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x58, 0x00, 0x04, 0x00, // PID_BUILTIN_ENDPOINT_SET
+            0x3F, 0x00, 0x00, 0x00,
+            0x2C, 0x00, 0x08, 0x00, // PID_USER_DATA
+            0x03, 0x00, 0x00, 0x00,
+            0x61, 0x62, 0x63, 0x00,
+            0x01, 0x00, 0x00, 0x00, // PID_SENTINEL
+        ];
+
+        let payload = participant_data().serialize(Endianness::LittleEndian);
+
+        assert_eq!(expected, &payload[..]);
+        assert_eq!(
+            participant_data(),
+            SpdpDiscoveredParticipantData::deserialize(expected).unwrap()
+        );
+    }
+
+    #[test]
+    fn participant_data_round_trips_in_big_endian() {
+        let participant_data = SpdpDiscoveredParticipantData {
+            expects_inline_qos: true,
+            manual_liveliness_count: Count_t::from(3),
+            ..participant_data()
+        };
+
+        let payload = participant_data.serialize(Endianness::BigEndian);
+
+        assert_eq!(&pl_cdr::PL_CDR_BE, &payload[..2]);
+        assert_eq!(
+            participant_data,
+            SpdpDiscoveredParticipantData::deserialize(&payload).unwrap()
+        );
+    }
+
+    /// Written by hand after the layout of the announcements of a Fast DDS
+    /// 2.x Participant with the name "RTPSParticipant", the vendor-specific
+    /// product version and the shared memory transport enabled, not
+    /// captured from the wire; the shared memory locator of kind 16 is not
+    /// known to this implementation.
+    #[test]
+    fn fast_dds_announcement_is_deserialized() {
+        #[rustfmt::skip]
+        let announcement: &[u8] = &[
+            0x00, 0x03, 0x00, 0x00, // PL_CDR_LE
+            0x15, 0x00, 0x04, 0x00, // PID_PROTOCOL_VERSION
+            0x02, 0x03, 0x00, 0x00,
+            0x16, 0x00, 0x04, 0x00, // PID_VENDOR_ID
+            0x01, 0x0F, 0x00, 0x00,
+            0x07, 0x80, 0x04, 0x00, // vendor-specific PID_PRODUCT_VERSION
+            0x02, 0x0A, 0x00, 0x00,
+            0x50, 0x00, 0x10, 0x00, // PID_PARTICIPANT_GUID
+            0x01, 0x0F, 0x4C, 0x8B,
+            0x2E, 0x3A, 0x00, 0x00,
+            0x01, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x01, 0xC1,
+            0x32, 0x00, 0x18, 0x00, // PID_METATRAFFIC_UNICAST_LOCATOR
+            0x01, 0x00, 0x00, 0x00,
+            0xF2, 0x1C, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0xC0, 0xA8, 0x01, 0x0A,
+            0x33, 0x00, 0x18, 0x00, // PID_METATRAFFIC_MULTICAST_LOCATOR
+            0x01, 0x00, 0x00, 0x00,
+            0xE8, 0x1C, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0xEF, 0xFF, 0x00, 0x01,
+            0x31, 0x00, 0x18, 0x00, // PID_DEFAULT_UNICAST_LOCATOR, SHM
+            0x10, 0x00, 0x00, 0x00,
+            0xF3, 0x1C, 0x00, 0x00,
+            0x4D, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x31, 0x00, 0x18, 0x00, // PID_DEFAULT_UNICAST_LOCATOR
+            0x01, 0x00, 0x00, 0x00,
+            0xF3, 0x1C, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0xC0, 0xA8, 0x01, 0x0A,
+            0x48, 0x00, 0x18, 0x00, // PID_DEFAULT_MULTICAST_LOCATOR
+            0x01, 0x00, 0x00, 0x00,
+            0xE9, 0x1C, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0xEF, 0xFF, 0x00, 0x01,
+            0x02, 0x00, 0x08, 0x00, // PID_PARTICIPANT_LEASE_DURATION
+            0x14, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x58, 0x00, 0x04, 0x00, // PID_BUILTIN_ENDPOINT_SET
+            0x3F, 0x0C, 0x00, 0x00,
+            0x62, 0x00, 0x14, 0x00, // PID_ENTITY_NAME
+            0x10, 0x00, 0x00, 0x00,
+            0x52, 0x54, 0x50, 0x53, // RTPS
+            0x50, 0x61, 0x72, 0x74, // Part
+            0x69, 0x63, 0x69, 0x70, // icip
+            0x61, 0x6E, 0x74, 0x00, // ant
+            0x01, 0x00, 0x00, 0x00, // PID_SENTINEL
+        ];
+
+        assert_eq!(
+            SpdpDiscoveredParticipantData {
+                domain_id: None,
+                domain_tag: String::new(),
+                protocol_version: ProtocolVersion_t::PROTOCOLVERSION_2_3,
+                guid_prefix: GuidPrefix_t::from([
+                    0x01, 0x0F, 0x4C, 0x8B, 0x2E, 0x3A, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+                ]),
+                vendor_id: VendorId_t::VENDOR_EPROSIMA_FAST_DDS,
+                expects_inline_qos: false,
+                available_builtin_endpoints: BuiltinEndpointSet_t::from_bits_retain(0x0000_0C3F),
+                metatraffic_unicast_locator_list: vec![locator(
+                    Ipv4Addr::new(192, 168, 1, 10),
+                    7410
+                )],
+                metatraffic_multicast_locator_list: vec![locator(
+                    Ipv4Addr::new(239, 255, 0, 1),
+                    7400
+                )],
+                default_unicast_locator_list: vec![locator(Ipv4Addr::new(192, 168, 1, 10), 7411)],
+                default_multicast_locator_list: vec![locator(Ipv4Addr::new(239, 255, 0, 1), 7401)],
+                manual_liveliness_count: Count_t::from(0),
+                lease_duration: Duration_t::from(Duration::from_secs(20)),
+                user_data: vec![],
+            },
+            SpdpDiscoveredParticipantData::deserialize(announcement).unwrap()
+        );
+    }
+
+    /// Written by hand after the layout of the announcements of an Eclipse
+    /// Cyclone DDS Participant, not captured from the wire: in big endian,
+    /// announcing the domain id, the deprecated builtin endpoints
+    /// parameter, the user data and the vendor-specific version
+    /// information.
+    #[test]
+    fn cyclone_dds_announcement_is_deserialized() {
+        #[rustfmt::skip]
+        let announcement: &[u8] = &[
+            0x00, 0x02, 0x00, 0x00, // PL_CDR_BE
+            0x00, 0x0F, 0x00, 0x04, // PID_DOMAIN_ID
+            0x00, 0x00, 0x00, 0x07,
+            0x00, 0x50, 0x00, 0x10, // PID_PARTICIPANT_GUID
+            0x01, 0x10, 0x9D, 0x5C,
+            0x63, 0xC1, 0x02, 0x11,
+            0x7F, 0x4A, 0x1B, 0x80,
+            0x00, 0x00, 0x01, 0xC1,
+            0x00, 0x44, 0x00, 0x04, // PID_PARTICIPANT_BUILTIN_ENDPOINTS
+            0x00, 0x00, 0x0C, 0x3F,
+            0x00, 0x15, 0x00, 0x04, // PID_PROTOCOL_VERSION
+            0x02, 0x01, 0x00, 0x00,
+            0x00, 0x16, 0x00, 0x04, // PID_VENDOR_ID
+            0x01, 0x10, 0x00, 0x00,
+            0x00, 0x31, 0x00, 0x18, // PID_DEFAULT_UNICAST_LOCATOR
+            0x00, 0x00, 0x00, 0x01,
+            0x00, 0x00, 0xE5, 0x3B,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0xAC, 0x11, 0x00, 0x02,
+            0x00, 0x32, 0x00, 0x18, // PID_METATRAFFIC_UNICAST_LOCATOR
+            0x00, 0x00, 0x00, 0x01,
+            0x00, 0x00, 0xE5, 0x3B,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0xAC, 0x11, 0x00, 0x02,
+            0x00, 0x02, 0x00, 0x08, // PID_PARTICIPANT_LEASE_DURATION
+            0x00, 0x00, 0x00, 0x0A,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x2C, 0x00, 0x0C, // PID_USER_DATA
+            0x00, 0x00, 0x00, 0x07,
+            0x63, 0x79, 0x63, 0x6C, // cycl
+            0x6F, 0x6E, 0x65, 0x00, // one
+            0x80, 0x07, 0x00, 0x08, // vendor-specific PID_ADLINK_PARTICIPANT_VERSION_INFO
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x03,
+            0x00, 0x01, 0x00, 0x00, // PID_SENTINEL
+        ];
+
+        let cyclone_locator = locator(Ipv4Addr::new(172, 17, 0, 2), 58683);
+        assert_eq!(
+            SpdpDiscoveredParticipantData {
+                domain_id: Some(7),
+                domain_tag: String::new(),
+                protocol_version: ProtocolVersion_t::PROTOCOLVERSION_2_1,
+                guid_prefix: GuidPrefix_t::from([
+                    0x01, 0x10, 0x9D, 0x5C, 0x63, 0xC1, 0x02, 0x11, 0x7F, 0x4A, 0x1B, 0x80,
+                ]),
+                vendor_id: VendorId_t::VENDOR_ECLIPSE_CYCLONE,
+                expects_inline_qos: false,
+                available_builtin_endpoints: BuiltinEndpointSet_t::from_bits_retain(0x0000_0C3F),
+                metatraffic_unicast_locator_list: vec![cyclone_locator],
+                metatraffic_multicast_locator_list: vec![],
+                default_unicast_locator_list: vec![cyclone_locator],
+                default_multicast_locator_list: vec![],
+                manual_liveliness_count: Count_t::from(0),
+                lease_duration: Duration_t::from(Duration::from_secs(10)),
+                user_data: b"cyclone".to_vec(),
+            },
+            SpdpDiscoveredParticipantData::deserialize(announcement).unwrap()
+        );
+    }
+
+    #[test]
+    fn missing_parameters_have_default_values() {
+        #[rustfmt::skip]
+        let announcement: &[u8] = &[
+            0x00, 0x03, 0x00, 0x00, // PL_CDR_LE
+            0x50, 0x00, 0x10, 0x00, // PID_PARTICIPANT_GUID
+            0x01, 0x02, 0x03, 0x04,
+            0x05, 0x06, 0x07, 0x08,
+            0x09, 0x0A, 0x0B, 0x0C,
+            0x00, 0x00, 0x01, 0xC1,
+            0x01, 0x00, 0x00, 0x00, // PID_SENTINEL
+        ];
+
+        let participant_data = SpdpDiscoveredParticipantData::deserialize(announcement).unwrap();
+
+        assert_eq!(
+            Duration_t::from(SpdpDiscoveredParticipantData::DEFAULT_LEASE_DURATION),
+            participant_data.lease_duration
+        );
+        assert_eq!(VendorId_t::VENDOR_UNKNOWN, participant_data.vendor_id);
+        assert_eq!(
+            ProtocolVersion_t::PROTOCOLVERSION,
+            participant_data.protocol_version
+        );
+        assert!(participant_data.metatraffic_unicast_locator_list.is_empty());
+        assert!(participant_data.user_data.is_empty());
+    }
+
+    #[test]
+    fn announcement_without_guid_is_rejected() {
+        let announcement = [0x00, 0x03, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00];
+
+        assert_eq!(
+            ErrorKind::InvalidData,
+            SpdpDiscoveredParticipantData::deserialize(&announcement)
+                .unwrap_err()
+                .kind()
+        );
+    }
+}
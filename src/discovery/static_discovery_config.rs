@@ -0,0 +1,612 @@
+use crate::dds::qos::destination_order_qos_policy::DestinationOrderQosPolicyKind;
+use crate::dds::qos::durability_qos_policy::DurabilityQosPolicyKind;
+use crate::dds::qos::liveliness_qos_policy::LivelinessQosPolicyKind;
+use crate::dds::qos::ownership_qos_policy::OwnershipQosPolicyKind;
+use crate::dds::qos::presentation_qos_policy::PresentationQosPolicyAccessScopeKind;
+use crate::dds::qos::reliability_qos_policy::ReliabilityQosPolicyKind;
+use crate::discovery::builtin_endpoint_set::BuiltinEndpointSet_t;
+use crate::discovery::discovered_reader_data::DiscoveredReaderData;
+use crate::discovery::discovered_writer_data::DiscoveredWriterData;
+use crate::discovery::spdp_discovered_participant_data::SpdpDiscoveredParticipantData;
+use crate::messages::protocol_version::ProtocolVersion_t;
+use crate::messages::vendor_id::VendorId_t;
+use crate::structure::count::Count_t;
+use crate::structure::duration::Duration_t;
+use crate::structure::entity_id::EntityId_t;
+use crate::structure::guid::GUID_t;
+use crate::structure::guid_prefix::GuidPrefix_t;
+use crate::structure::locator::{LocatorList_t, Locator_t, SocketAddr};
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::BTreeMap;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+use std::time::Duration;
+
+/// The remote Participants and their Endpoints known in advance, used instead
+/// of the SPDP and SEDP where the discovery traffic is not possible or not
+/// wanted.
+///
+/// The configuration lists the participants with their guid_prefix,
+/// optional vendor_id, and default_unicast_locator,
+/// default_multicast_locator, metatraffic_unicast_locator and
+/// metatraffic_multicast_locator lists. Each participant contains its
+/// writers and readers with the entity_id, topic, type, optional
+/// unicast_locator and multicast_locator lists and the QoS: reliability,
+/// durability, deadline and liveliness_lease_duration in seconds,
+/// liveliness, ownership, destination_order, presentation, coherent_access,
+/// ordered_access, partition and, for the readers, expects_inline_qos. The
+/// kinds are spelled as in the specification, e.g. TRANSIENT_LOCAL.
+///
+/// In TOML the participants are the `[[participant]]` tables with the
+/// `[[participant.writer]]` and `[[participant.reader]]` tables. In XML the
+/// root element contains the `<participant>` elements, the values are given
+/// as attributes or as child elements which can be repeated for the lists.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StaticDiscoveryConfig {
+    pub participants: Vec<SpdpDiscoveredParticipantData>,
+    pub writers: Vec<DiscoveredWriterData>,
+    pub readers: Vec<DiscoveredReaderData>,
+}
+
+/// Sets the given QoS policies of the DiscoveredWriterData or
+/// DiscoveredReaderData.
+macro_rules! apply_qos {
+    ($qos:expr, $data:expr) => {{
+        let qos = $qos;
+        let data = &mut $data;
+        fn set<T>(target: &mut T, value: Option<T>) {
+            if let Some(value) = value {
+                *target = value;
+            }
+        }
+        set(&mut data.reliability.kind, qos.reliability);
+        set(&mut data.durability.kind, qos.durability);
+        set(&mut data.deadline.period, qos.deadline);
+        set(&mut data.liveliness.kind, qos.liveliness);
+        set(
+            &mut data.liveliness.lease_duration,
+            qos.liveliness_lease_duration,
+        );
+        set(&mut data.ownership.kind, qos.ownership);
+        set(&mut data.destination_order.kind, qos.destination_order);
+        set(&mut data.presentation.access_scope, qos.presentation);
+        set(&mut data.presentation.coherent_access, qos.coherent_access);
+        set(&mut data.presentation.ordered_access, qos.ordered_access);
+        data.partition.name = qos.partition;
+    }};
+}
+
+impl StaticDiscoveryConfig {
+    /// Reads the configuration from the file, in XML if its extension is
+    /// xml and in TOML otherwise.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("xml") => {
+                StaticDiscoveryConfig::from_xml(&contents)
+            }
+            _ => StaticDiscoveryConfig::from_toml(&contents),
+        }
+    }
+
+    pub fn from_toml(contents: &str) -> Result<Self, Error> {
+        let table = contents
+            .parse::<toml::Table>()
+            .map_err(|err| invalid_data(err.to_string()))?;
+        StaticDiscoveryConfig::from_node(&ConfigNode::from_toml(&table))
+    }
+
+    pub fn from_xml(contents: &str) -> Result<Self, Error> {
+        StaticDiscoveryConfig::from_node(&ConfigNode::from_xml(contents)?)
+    }
+
+    /// The participant with the GuidPrefix_t.
+    pub fn participant(&self, guid_prefix: GuidPrefix_t) -> Option<&SpdpDiscoveredParticipantData> {
+        self.participants
+            .iter()
+            .find(|participant_data| participant_data.guid_prefix == guid_prefix)
+    }
+
+    fn from_node(root: &ConfigNode) -> Result<Self, Error> {
+        let mut config = StaticDiscoveryConfig::default();
+        for participant in root.children("participant") {
+            let participant_data = participant_data(participant)?;
+            let guid = |endpoint: &ConfigNode| -> Result<GUID_t, Error> {
+                Ok(GUID_t {
+                    guid_prefix: participant_data.guid_prefix,
+                    entity_id: entity_id(endpoint.required("entity_id")?)?,
+                })
+            };
+            for writer in participant.children("writer") {
+                let mut writer_data = DiscoveredWriterData::new(
+                    guid(writer)?,
+                    writer.required("topic")?,
+                    writer.required("type")?,
+                );
+                writer_data.unicast_locator_list = writer.locators("unicast_locator")?;
+                writer_data.multicast_locator_list = writer.locators("multicast_locator")?;
+                apply_qos!(EndpointQos::from_node(writer)?, writer_data);
+                config.writers.push(writer_data);
+            }
+            for reader in participant.children("reader") {
+                let mut reader_data = DiscoveredReaderData::new(
+                    guid(reader)?,
+                    reader.required("topic")?,
+                    reader.required("type")?,
+                );
+                reader_data.unicast_locator_list = reader.locators("unicast_locator")?;
+                reader_data.multicast_locator_list = reader.locators("multicast_locator")?;
+                if let Some(expects_inline_qos) = reader.boolean("expects_inline_qos")? {
+                    reader_data.expects_inline_qos = expects_inline_qos;
+                }
+                apply_qos!(EndpointQos::from_node(reader)?, reader_data);
+                config.readers.push(reader_data);
+            }
+            config.participants.push(participant_data);
+        }
+        Ok(config)
+    }
+}
+
+/// The QoS given for an Endpoint, the policies not given keep the defaults
+/// of the Endpoint.
+#[derive(Default)]
+struct EndpointQos {
+    reliability: Option<ReliabilityQosPolicyKind>,
+    durability: Option<DurabilityQosPolicyKind>,
+    deadline: Option<Duration_t>,
+    liveliness: Option<LivelinessQosPolicyKind>,
+    liveliness_lease_duration: Option<Duration_t>,
+    ownership: Option<OwnershipQosPolicyKind>,
+    destination_order: Option<DestinationOrderQosPolicyKind>,
+    presentation: Option<PresentationQosPolicyAccessScopeKind>,
+    coherent_access: Option<bool>,
+    ordered_access: Option<bool>,
+    partition: Vec<String>,
+}
+
+impl EndpointQos {
+    fn from_node(node: &ConfigNode) -> Result<Self, Error> {
+        use DestinationOrderQosPolicyKind::*;
+        use DurabilityQosPolicyKind::*;
+        use LivelinessQosPolicyKind::*;
+        use OwnershipQosPolicyKind::*;
+        use PresentationQosPolicyAccessScopeKind::*;
+        use ReliabilityQosPolicyKind::*;
+
+        Ok(EndpointQos {
+            reliability: node.kind(
+                "reliability",
+                &[("BEST_EFFORT", BEST_EFFORT), ("RELIABLE", RELIABLE)],
+            )?,
+            durability: node.kind(
+                "durability",
+                &[
+                    ("VOLATILE", VOLATILE),
+                    ("TRANSIENT_LOCAL", TRANSIENT_LOCAL),
+                    ("TRANSIENT", TRANSIENT),
+                    ("PERSISTENT", PERSISTENT),
+                ],
+            )?,
+            deadline: node.duration("deadline")?,
+            liveliness: node.kind(
+                "liveliness",
+                &[
+                    ("AUTOMATIC", AUTOMATIC),
+                    ("MANUAL_BY_PARTICIPANT", MANUAL_BY_PARTICIPANT),
+                    ("MANUAL_BY_TOPIC", MANUAL_BY_TOPIC),
+                ],
+            )?,
+            liveliness_lease_duration: node.duration("liveliness_lease_duration")?,
+            ownership: node.kind("ownership", &[("SHARED", SHARED), ("EXCLUSIVE", EXCLUSIVE)])?,
+            destination_order: node.kind(
+                "destination_order",
+                &[
+                    ("BY_RECEPTION_TIMESTAMP", BY_RECEPTION_TIMESTAMP),
+                    ("BY_SOURCE_TIMESTAMP", BY_SOURCE_TIMESTAMP),
+                ],
+            )?,
+            presentation: node.kind(
+                "presentation",
+                &[("INSTANCE", INSTANCE), ("TOPIC", TOPIC), ("GROUP", GROUP)],
+            )?,
+            coherent_access: node.boolean("coherent_access")?,
+            ordered_access: node.boolean("ordered_access")?,
+            partition: node.values("partition").map(str::to_string).collect(),
+        })
+    }
+}
+
+fn participant_data(node: &ConfigNode) -> Result<SpdpDiscoveredParticipantData, Error> {
+    let guid_prefix = hex_bytes::<12>(node.required("guid_prefix")?)
+        .map(GuidPrefix_t::from)
+        .ok_or_else(|| invalid_data("Invalid guid_prefix, expected 12 hexadecimal bytes"))?;
+    let vendor_id = match node.value("vendor_id") {
+        Some(vendor_id) => vendor_id
+            .parse::<VendorId_t>()
+            .map_err(|_| invalid_data(format!("Invalid vendor_id {}", vendor_id)))?,
+        None => VendorId_t::VENDOR_UNKNOWN,
+    };
+    Ok(SpdpDiscoveredParticipantData {
+        domain_id: None,
+        domain_tag: String::new(),
+        protocol_version: ProtocolVersion_t::PROTOCOLVERSION,
+        guid_prefix,
+        vendor_id,
+        expects_inline_qos: node.boolean("expects_inline_qos")?.unwrap_or(false),
+        available_builtin_endpoints: BuiltinEndpointSet_t::empty(),
+        metatraffic_unicast_locator_list: node.locators("metatraffic_unicast_locator")?,
+        metatraffic_multicast_locator_list: node.locators("metatraffic_multicast_locator")?,
+        default_unicast_locator_list: node.locators("default_unicast_locator")?,
+        default_multicast_locator_list: node.locators("default_multicast_locator")?,
+        manual_liveliness_count: Count_t::from(0),
+        lease_duration: Duration_t::DURATION_INFINITE,
+        user_data: vec![],
+    })
+}
+
+fn entity_id(value: &str) -> Result<EntityId_t, Error> {
+    hex_bytes::<4>(value)
+        .map(|bytes| EntityId_t::new([bytes[0], bytes[1], bytes[2]], bytes[3]))
+        .ok_or_else(|| invalid_data(format!("Invalid entity_id {}", value)))
+}
+
+/// Parses the hexadecimal bytes, optionally separated by dots.
+fn hex_bytes<const N: usize>(value: &str) -> Option<[u8; N]> {
+    let digits: Vec<u8> = value.bytes().filter(|&byte| byte != b'.').collect();
+    if digits.len() != 2 * N {
+        return None;
+    }
+    let mut bytes = [0; N];
+    for (byte, pair) in bytes.iter_mut().zip(digits.chunks(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok()?;
+    }
+    Some(bytes)
+}
+
+fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(error: E) -> Error {
+    Error::new(ErrorKind::InvalidData, error)
+}
+
+/// The configuration independent of its format: the named values and the
+/// named child nodes, both possibly repeated.
+#[derive(Debug, Default)]
+struct ConfigNode {
+    values: BTreeMap<String, Vec<String>>,
+    children: Vec<(String, ConfigNode)>,
+}
+
+impl ConfigNode {
+    fn from_toml(table: &toml::Table) -> Self {
+        fn scalar(value: &toml::Value) -> Option<String> {
+            match value {
+                toml::Value::String(value) => Some(value.clone()),
+                toml::Value::Integer(value) => Some(value.to_string()),
+                toml::Value::Float(value) => Some(value.to_string()),
+                toml::Value::Boolean(value) => Some(value.to_string()),
+                toml::Value::Datetime(value) => Some(value.to_string()),
+                toml::Value::Array(_) | toml::Value::Table(_) => None,
+            }
+        }
+
+        let mut node = ConfigNode::default();
+        for (key, value) in table {
+            let items = match value {
+                toml::Value::Array(items) => items.iter().collect(),
+                value => vec![value],
+            };
+            for item in items {
+                match item {
+                    toml::Value::Table(table) => node
+                        .children
+                        .push((key.clone(), ConfigNode::from_toml(table))),
+                    item => node
+                        .values
+                        .entry(key.clone())
+                        .or_default()
+                        .extend(scalar(item)),
+                }
+            }
+        }
+        node
+    }
+
+    /// The root element of the document. The elements with attributes or
+    /// child elements are nodes, the others are values.
+    fn from_xml(contents: &str) -> Result<Self, Error> {
+        fn start(element: &BytesStart) -> Result<(String, ConfigNode), Error> {
+            let mut node = ConfigNode::default();
+            for attribute in element.attributes() {
+                let attribute = attribute.map_err(|err| invalid_data(err.to_string()))?;
+                let value = attribute
+                    .unescape_value()
+                    .map_err(|err| invalid_data(err.to_string()))?;
+                node.values
+                    .entry(String::from_utf8_lossy(attribute.key.as_ref()).into_owned())
+                    .or_default()
+                    .push(value.into_owned());
+            }
+            let name = String::from_utf8_lossy(element.name().as_ref()).into_owned();
+            Ok((name, node))
+        }
+
+        let mut reader = Reader::from_str(contents);
+        reader.trim_text(true);
+        let mut open: Vec<(String, ConfigNode, String)> = vec![];
+        let mut root = None;
+        loop {
+            let closed = match reader
+                .read_event()
+                .map_err(|err| invalid_data(err.to_string()))?
+            {
+                Event::Start(element) => {
+                    let (name, node) = start(&element)?;
+                    open.push((name, node, String::new()));
+                    None
+                }
+                Event::Empty(element) => {
+                    let (name, node) = start(&element)?;
+                    Some((name, node, String::new()))
+                }
+                Event::Text(text) => {
+                    if let Some((_, _, contents)) = open.last_mut() {
+                        contents.push_str(
+                            &text
+                                .unescape()
+                                .map_err(|err| invalid_data(err.to_string()))?,
+                        );
+                    }
+                    None
+                }
+                Event::End(_) => open.pop(),
+                Event::Eof => break,
+                _ => None,
+            };
+            if let Some((name, node, text)) = closed {
+                match open.last_mut() {
+                    Some((_, parent, _)) if node.values.is_empty() && node.children.is_empty() => {
+                        parent.values.entry(name).or_default().push(text)
+                    }
+                    Some((_, parent, _)) => parent.children.push((name, node)),
+                    None => root = Some(node),
+                }
+            }
+        }
+        root.ok_or_else(|| invalid_data("Missing root element"))
+    }
+
+    fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a ConfigNode> + 'a {
+        self.children
+            .iter()
+            .filter(move |(child_name, _)| child_name == name)
+            .map(|(_, child)| child)
+    }
+
+    fn values<'a>(&'a self, name: &str) -> impl Iterator<Item = &'a str> + 'a {
+        self.values
+            .get(name)
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+    }
+
+    fn value(&self, name: &str) -> Option<&str> {
+        self.values(name).next()
+    }
+
+    fn required(&self, name: &str) -> Result<&str, Error> {
+        self.value(name)
+            .ok_or_else(|| invalid_data(format!("Missing {}", name)))
+    }
+
+    fn boolean(&self, name: &str) -> Result<Option<bool>, Error> {
+        self.value(name)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| invalid_data(format!("Invalid {} {}", name, value)))
+            })
+            .transpose()
+    }
+
+    /// The duration in seconds, or INFINITE.
+    fn duration(&self, name: &str) -> Result<Option<Duration_t>, Error> {
+        self.value(name)
+            .map(|value| {
+                if value.eq_ignore_ascii_case("INFINITE") {
+                    return Ok(Duration_t::DURATION_INFINITE);
+                }
+                value
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+                    .map(Duration_t::from)
+                    .ok_or_else(|| invalid_data(format!("Invalid {} {}", name, value)))
+            })
+            .transpose()
+    }
+
+    fn kind<T: Copy>(&self, name: &str, kinds: &[(&str, T)]) -> Result<Option<T>, Error> {
+        self.value(name)
+            .map(|value| {
+                kinds
+                    .iter()
+                    .find(|(kind_name, _)| kind_name.eq_ignore_ascii_case(value))
+                    .map(|&(_, kind)| kind)
+                    .ok_or_else(|| invalid_data(format!("Invalid {} {}", name, value)))
+            })
+            .transpose()
+    }
+
+    /// The locators given as socket addresses, e.g. 192.168.1.2:7411.
+    fn locators(&self, name: &str) -> Result<LocatorList_t, Error> {
+        self.values(name)
+            .map(|value| {
+                value
+                    .parse::<SocketAddr>()
+                    .map(Locator_t::from)
+                    .map_err(|_| invalid_data(format!("Invalid {} {}", name, value)))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::locator::Ipv4Addr;
+
+    const TOML: &str = r#"
+        [[participant]]
+        guid_prefix = "01.0f.00.00.00.00.00.00.00.00.00.01"
+        vendor_id = "01.0F"
+        default_unicast_locator = ["192.168.1.2:7411"]
+        metatraffic_unicast_locator = ["192.168.1.2:7410"]
+
+        [[participant.writer]]
+        entity_id = "00.00.01.02"
+        topic = "Square"
+        type = "ShapeType"
+        reliability = "BEST_EFFORT"
+        durability = "TRANSIENT_LOCAL"
+        deadline = 0.5
+        partition = ["shapes", "colors"]
+
+        [[participant.reader]]
+        entity_id = "00.00.02.07"
+        topic = "Circle"
+        type = "ShapeType"
+        unicast_locator = ["192.168.1.2:7412"]
+        liveliness = "MANUAL_BY_TOPIC"
+        liveliness_lease_duration = 2
+        expects_inline_qos = true
+    "#;
+
+    const XML: &str = r#"
+        <?xml version="1.0" encoding="UTF-8"?>
+        <static_discovery>
+            <participant guid_prefix="01.0f.00.00.00.00.00.00.00.00.00.01" vendor_id="01.0F">
+                <default_unicast_locator>192.168.1.2:7411</default_unicast_locator>
+                <metatraffic_unicast_locator>192.168.1.2:7410</metatraffic_unicast_locator>
+                <writer entity_id="00.00.01.02" topic="Square" type="ShapeType">
+                    <reliability>BEST_EFFORT</reliability>
+                    <durability>TRANSIENT_LOCAL</durability>
+                    <deadline>0.5</deadline>
+                    <partition>shapes</partition>
+                    <partition>colors</partition>
+                </writer>
+                <reader entity_id="00.00.02.07" topic="Circle" type="ShapeType">
+                    <unicast_locator>192.168.1.2:7412</unicast_locator>
+                    <liveliness>MANUAL_BY_TOPIC</liveliness>
+                    <liveliness_lease_duration>2</liveliness_lease_duration>
+                    <expects_inline_qos>true</expects_inline_qos>
+                </reader>
+            </participant>
+        </static_discovery>
+    "#;
+
+    fn locator(port: u16) -> Locator_t {
+        Locator_t::from(SocketAddr::new(Ipv4Addr::new(192, 168, 1, 2).into(), port))
+    }
+
+    fn expected_config() -> StaticDiscoveryConfig {
+        let guid_prefix = GuidPrefix_t::from([0x01, 0x0F, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01]);
+        let mut writer_data = DiscoveredWriterData::new(
+            GUID_t {
+                guid_prefix,
+                entity_id: EntityId_t::new(
+                    [0x00, 0x00, 0x01],
+                    EntityId_t::ENTITYKIND_USER_DEFINED_WRITER_WITH_KEY,
+                ),
+            },
+            "Square",
+            "ShapeType",
+        );
+        writer_data.reliability.kind = ReliabilityQosPolicyKind::BEST_EFFORT;
+        writer_data.durability.kind = DurabilityQosPolicyKind::TRANSIENT_LOCAL;
+        writer_data.deadline.period = Duration_t::from(Duration::from_millis(500));
+        writer_data.partition.name = vec!["shapes".to_string(), "colors".to_string()];
+
+        let mut reader_data = DiscoveredReaderData::new(
+            GUID_t {
+                guid_prefix,
+                entity_id: EntityId_t::new(
+                    [0x00, 0x00, 0x02],
+                    EntityId_t::ENTITYKIND_USER_DEFINED_READER_WITH_KEY,
+                ),
+            },
+            "Circle",
+            "ShapeType",
+        );
+        reader_data.unicast_locator_list = vec![locator(7412)];
+        reader_data.liveliness.kind = LivelinessQosPolicyKind::MANUAL_BY_TOPIC;
+        reader_data.liveliness.lease_duration = Duration_t::from(Duration::from_secs(2));
+        reader_data.expects_inline_qos = true;
+
+        StaticDiscoveryConfig {
+            participants: vec![SpdpDiscoveredParticipantData {
+                domain_id: None,
+                domain_tag: String::new(),
+                protocol_version: ProtocolVersion_t::PROTOCOLVERSION,
+                guid_prefix,
+                vendor_id: VendorId_t {
+                    vendor_id: [0x01, 0x0F],
+                },
+                expects_inline_qos: false,
+                available_builtin_endpoints: BuiltinEndpointSet_t::empty(),
+                metatraffic_unicast_locator_list: vec![locator(7410)],
+                metatraffic_multicast_locator_list: vec![],
+                default_unicast_locator_list: vec![locator(7411)],
+                default_multicast_locator_list: vec![],
+                manual_liveliness_count: Count_t::from(0),
+                lease_duration: Duration_t::DURATION_INFINITE,
+                user_data: vec![],
+            }],
+            writers: vec![writer_data],
+            readers: vec![reader_data],
+        }
+    }
+
+    #[test]
+    fn toml_config_is_parsed() {
+        assert_eq!(
+            expected_config(),
+            StaticDiscoveryConfig::from_toml(TOML).unwrap()
+        );
+    }
+
+    #[test]
+    fn xml_config_is_parsed() {
+        assert_eq!(
+            expected_config(),
+            StaticDiscoveryConfig::from_xml(XML.trim()).unwrap()
+        );
+    }
+
+    #[test]
+    fn invalid_config_is_rejected() {
+        for contents in [
+            "[[participant]]\nguid_prefix = \"01.0f\"",
+            "[[participant]]\nguid_prefix = \"010f00000000000000000001\"\n\
+             [[participant.writer]]\nentity_id = \"00000102\"\ntopic = \"Square\"",
+            "[[participant]]\nguid_prefix = \"010f00000000000000000001\"\n\
+             [[participant.writer]]\nentity_id = \"00000102\"\ntopic = \"Square\"\n\
+             type = \"ShapeType\"\nreliability = \"SOMETIMES\"",
+        ] {
+            assert_eq!(
+                ErrorKind::InvalidData,
+                StaticDiscoveryConfig::from_toml(contents)
+                    .unwrap_err()
+                    .kind()
+            );
+        }
+        assert_eq!(
+            StaticDiscoveryConfig::default(),
+            StaticDiscoveryConfig::from_xml("<static_discovery/>").unwrap()
+        );
+        assert!(StaticDiscoveryConfig::from_xml("<static_discovery>").is_err());
+    }
+}
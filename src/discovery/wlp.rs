@@ -0,0 +1,802 @@
+use crate::behavior::reader::{Reader, ReaderAttributes};
+use crate::behavior::reader_proxy::ReaderProxy;
+use crate::behavior::stateful_reader::StatefulReader;
+use crate::behavior::stateful_writer::StatefulWriter;
+use crate::behavior::writer::{Writer, WriterAttributes};
+use crate::behavior::writer_proxy::WriterProxy;
+use crate::dds::qos::liveliness_qos_policy::{LivelinessQosPolicy, LivelinessQosPolicyKind};
+use crate::dds::status::liveliness_changed_status::LivelinessChangedStatus;
+use crate::dds::status::liveliness_lost_status::LivelinessLostStatus;
+use crate::discovery::builtin_endpoint_set::BuiltinEndpointSet_t;
+use crate::discovery::participant_message_data::ParticipantMessageData;
+use crate::discovery::pl_cdr;
+use crate::discovery::spdp_discovered_participant_data::SpdpDiscoveredParticipantData;
+use crate::protocol::event::Event;
+use crate::protocol::protocol_core::ProtocolCore;
+use crate::structure::change_kind::ChangeKind_t;
+use crate::structure::data::Data;
+use crate::structure::duration::Duration_t;
+use crate::structure::endpoint::EndpointAttributes;
+use crate::structure::entity::EntityAttributes;
+use crate::structure::entity_id::EntityId_t;
+use crate::structure::guid::GUID_t;
+use crate::structure::guid_prefix::GuidPrefix_t;
+use crate::structure::history_cache::HistoryCache;
+use crate::structure::reliability_kind::ReliabilityKind_t;
+use crate::structure::sequence_number::SequenceNumber_t;
+use crate::structure::topic_kind::TopicKind_t;
+
+use log::debug;
+use speedy::Endianness;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::time::{Duration, Instant};
+
+/// The AUTOMATIC liveliness is asserted every third of the shortest lease,
+/// the lease does not expire when an assertion is late.
+const AUTOMATIC_ASSERTIONS_PER_LEASE: u32 = 3;
+
+/// A local Writer whose liveliness is asserted by the Participant.
+struct LocalWriter {
+    liveliness: LivelinessQosPolicy,
+
+    /// The expiration of the lease of the Writer with a MANUAL liveliness;
+    /// None while the liveliness is lost.
+    lease_expiration: Option<Instant>,
+    liveliness_lost: LivelinessLostStatus,
+}
+
+/// A Writer matched with local Readers whose liveliness is tracked.
+struct MatchedWriter {
+    liveliness: LivelinessQosPolicy,
+    alive: bool,
+
+    /// The expiration of the lease of the alive Writer.
+    lease_expiration: Option<Instant>,
+    readers: BTreeSet<GUID_t>,
+}
+
+/// The Writer Liveliness Protocol (8.4.13).
+///
+/// The liveliness of the local Writers with the AUTOMATIC and the
+/// MANUAL_BY_PARTICIPANT liveliness is asserted for all of them by the
+/// reliable BuiltinParticipantMessageWriter, sending ParticipantMessageData
+/// to the BuiltinParticipantMessageReaders of the discovered Participants.
+/// The AUTOMATIC assertions are sent periodically, the MANUAL ones with
+/// assert_participant. The Writers with the MANUAL_BY_TOPIC liveliness
+/// assert it with Heartbeats with the LivelinessFlag.
+///
+/// The liveliness of the Writers matched with the local Readers is tracked
+/// with their lease_duration: they are alive when matched and lose their
+/// liveliness when their lease expires without an assertion. The changes
+/// are reported with LivelinessChanged events; the local Writers failing to
+/// assert their liveliness are reported with LivelinessLost events.
+///
+/// Like the ProtocolCore, Wlp is free of any I/O and driven by the caller;
+/// the Endpoints are installed into the ProtocolCore of the Participant.
+pub struct Wlp {
+    guid_prefix: GuidPrefix_t,
+
+    /// The sequence numbers of the assertions of each kind in the
+    /// HistoryCache of the built-in Writer.
+    asserted: BTreeMap<[u8; 4], SequenceNumber_t>,
+    local_writers: BTreeMap<GUID_t, LocalWriter>,
+    matched_writers: BTreeMap<GUID_t, MatchedWriter>,
+    liveliness_changed: BTreeMap<GUID_t, LivelinessChangedStatus>,
+
+    /// The time of the next AUTOMATIC assertion, if there is a local Writer
+    /// with the AUTOMATIC liveliness and a finite lease.
+    automatic_assertion: Option<Instant>,
+    events: VecDeque<Event>,
+}
+
+impl Wlp {
+    /// The WLP bits of the available_builtin_endpoints announced by the
+    /// SPDP.
+    pub const BUILTIN_ENDPOINTS: BuiltinEndpointSet_t =
+        BuiltinEndpointSet_t::BUILTIN_ENDPOINT_PARTICIPANT_MESSAGE_DATA_WRITER
+            .union(BuiltinEndpointSet_t::BUILTIN_ENDPOINT_PARTICIPANT_MESSAGE_DATA_READER);
+
+    /// Installs the BuiltinParticipantMessageWriter and Reader into the
+    /// core. The Writer sends Heartbeats every heartbeat_period, starting
+    /// from now.
+    pub fn new(
+        now: Instant,
+        core: &mut ProtocolCore,
+        guid_prefix: GuidPrefix_t,
+        heartbeat_period: Duration,
+    ) -> Self {
+        let guid = |entity_id| GUID_t {
+            guid_prefix,
+            entity_id,
+        };
+
+        core.add_writer(
+            now,
+            StatefulWriter::new(
+                EntityAttributes {
+                    guid: guid(EntityId_t::ENTITYID_P2P_BUILTIN_PARTICIPANT_MESSAGE_WRITER),
+                },
+                endpoint(),
+                WriterAttributes {
+                    push_mode: true,
+                    heartbeat_period: Duration_t::from(heartbeat_period),
+                    nack_response_delay: Duration_t::DURATION_ZERO,
+                    nack_suppression_duration: Duration_t::DURATION_ZERO,
+                    last_change_sequence_number: SequenceNumber_t::from(0),
+                    data_max_size_serialized: 1024,
+                    fragment_size: 1024,
+                    writer_cache: HistoryCache::new(),
+                },
+            ),
+        );
+        core.add_reader(StatefulReader::new(
+            EntityAttributes {
+                guid: guid(EntityId_t::ENTITYID_P2P_BUILTIN_PARTICIPANT_MESSAGE_READER),
+            },
+            endpoint(),
+            ReaderAttributes {
+                expects_inline_qos: false,
+                heartbeat_response_delay: Duration_t::DURATION_ZERO,
+                heartbeat_suppression_duration: Duration_t::DURATION_ZERO,
+                reader_cache: HistoryCache::new(),
+            },
+        ));
+
+        Wlp {
+            guid_prefix,
+            asserted: BTreeMap::new(),
+            local_writers: BTreeMap::new(),
+            matched_writers: BTreeMap::new(),
+            liveliness_changed: BTreeMap::new(),
+            automatic_assertion: None,
+            events: VecDeque::new(),
+        }
+    }
+
+    /// Whether the reader_guid identifies the built-in Reader, whose
+    /// DataAvailable events are handled by handle_data_available.
+    pub fn is_builtin_reader(&self, reader_guid: GUID_t) -> bool {
+        reader_guid
+            == GUID_t {
+                guid_prefix: self.guid_prefix,
+                entity_id: EntityId_t::ENTITYID_P2P_BUILTIN_PARTICIPANT_MESSAGE_READER,
+            }
+    }
+
+    /// Matches the built-in Endpoints with the ones available in the
+    /// Participant discovered by the SPDP.
+    pub fn add_participant(
+        &mut self,
+        core: &mut ProtocolCore,
+        participant_data: &SpdpDiscoveredParticipantData,
+    ) {
+        let remote_guid = |entity_id| GUID_t {
+            guid_prefix: participant_data.guid_prefix,
+            entity_id,
+        };
+        let unicast_locator_list = &participant_data.metatraffic_unicast_locator_list;
+        let multicast_locator_list = &participant_data.metatraffic_multicast_locator_list;
+        let available = participant_data.available_builtin_endpoints;
+
+        let remote_reader_guid =
+            remote_guid(EntityId_t::ENTITYID_P2P_BUILTIN_PARTICIPANT_MESSAGE_READER);
+        match core.writer_mut(EntityId_t::ENTITYID_P2P_BUILTIN_PARTICIPANT_MESSAGE_WRITER) {
+            Some(writer)
+                if available.contains(
+                    BuiltinEndpointSet_t::BUILTIN_ENDPOINT_PARTICIPANT_MESSAGE_DATA_READER,
+                ) && writer
+                    .matched_reader_lookup(remote_reader_guid)
+                    .next()
+                    .is_none() =>
+            {
+                writer.matched_reader_add(ReaderProxy::new(
+                    remote_reader_guid,
+                    unicast_locator_list.clone(),
+                    multicast_locator_list.clone(),
+                    false,
+                ))
+            }
+            _ => (),
+        }
+
+        let remote_writer_guid =
+            remote_guid(EntityId_t::ENTITYID_P2P_BUILTIN_PARTICIPANT_MESSAGE_WRITER);
+        match core.reader_mut(EntityId_t::ENTITYID_P2P_BUILTIN_PARTICIPANT_MESSAGE_READER) {
+            Some(reader)
+                if available.contains(
+                    BuiltinEndpointSet_t::BUILTIN_ENDPOINT_PARTICIPANT_MESSAGE_DATA_WRITER,
+                ) && reader.matched_writer_lookup(remote_writer_guid).is_none() =>
+            {
+                reader.matched_writer_add(WriterProxy::new(
+                    remote_writer_guid,
+                    unicast_locator_list.clone(),
+                    multicast_locator_list.clone(),
+                ))
+            }
+            _ => (),
+        }
+    }
+
+    /// Unmatches the built-in Endpoints of the Participant lost by the SPDP.
+    pub fn remove_participant(&mut self, core: &mut ProtocolCore, guid_prefix: GuidPrefix_t) {
+        let remote_guid = |entity_id| GUID_t {
+            guid_prefix,
+            entity_id,
+        };
+        if let Some(writer) =
+            core.writer_mut(EntityId_t::ENTITYID_P2P_BUILTIN_PARTICIPANT_MESSAGE_WRITER)
+        {
+            writer.matched_reader_remove(remote_guid(
+                EntityId_t::ENTITYID_P2P_BUILTIN_PARTICIPANT_MESSAGE_READER,
+            ));
+        }
+        if let Some(reader) =
+            core.reader_mut(EntityId_t::ENTITYID_P2P_BUILTIN_PARTICIPANT_MESSAGE_READER)
+        {
+            reader.matched_writer_remove(remote_guid(
+                EntityId_t::ENTITYID_P2P_BUILTIN_PARTICIPANT_MESSAGE_WRITER,
+            ));
+        }
+    }
+
+    /// Adds or updates the local Writer whose liveliness is asserted by the
+    /// Participant. The lease of the Writer with a MANUAL liveliness starts
+    /// at now.
+    pub fn add_local_writer(
+        &mut self,
+        now: Instant,
+        writer_guid: GUID_t,
+        liveliness: LivelinessQosPolicy,
+    ) {
+        let lease_expiration = match liveliness.kind {
+            LivelinessQosPolicyKind::AUTOMATIC => None,
+            _ => lease_expiration(now, liveliness),
+        };
+        let liveliness_lost = self
+            .local_writers
+            .remove(&writer_guid)
+            .map(|local_writer| local_writer.liveliness_lost)
+            .unwrap_or_default();
+        self.local_writers.insert(
+            writer_guid,
+            LocalWriter {
+                liveliness,
+                lease_expiration,
+                liveliness_lost,
+            },
+        );
+        self.automatic_assertion = match (self.automatic_assertion, self.automatic_period()) {
+            (Some(automatic_assertion), Some(period)) => {
+                Some(std::cmp::min(automatic_assertion, now + period))
+            }
+            (None, Some(period)) => Some(now + period),
+            (_, None) => None,
+        };
+    }
+
+    pub fn remove_local_writer(&mut self, writer_guid: GUID_t) {
+        self.local_writers.remove(&writer_guid);
+        if self.automatic_period().is_none() {
+            self.automatic_assertion = None;
+        }
+    }
+
+    /// Starts tracking the liveliness of the Writer matched with the local
+    /// Reader. The Writer is alive, its lease starts at now.
+    pub fn writer_matched(
+        &mut self,
+        now: Instant,
+        reader_guid: GUID_t,
+        writer_guid: GUID_t,
+        liveliness: LivelinessQosPolicy,
+    ) {
+        let matched_writer =
+            self.matched_writers
+                .entry(writer_guid)
+                .or_insert_with(|| MatchedWriter {
+                    liveliness,
+                    alive: true,
+                    lease_expiration: lease_expiration(now, liveliness),
+                    readers: BTreeSet::new(),
+                });
+        if !matched_writer.readers.insert(reader_guid) {
+            return;
+        }
+        let alive = matched_writer.alive;
+        self.liveliness_changed
+            .entry(reader_guid)
+            .or_default()
+            .change(
+                pl_cdr::key_hash(writer_guid),
+                i32::from(alive),
+                i32::from(!alive),
+            );
+        if alive {
+            self.events.push_back(Event::LivelinessChanged {
+                reader_guid,
+                writer_guid,
+                alive,
+            });
+        }
+    }
+
+    /// Stops tracking the liveliness of the Writer for the local Reader.
+    pub fn writer_unmatched(&mut self, reader_guid: GUID_t, writer_guid: GUID_t) {
+        let matched_writer = match self.matched_writers.get_mut(&writer_guid) {
+            Some(matched_writer) => matched_writer,
+            None => return,
+        };
+        if !matched_writer.readers.remove(&reader_guid) {
+            return;
+        }
+        let alive = matched_writer.alive;
+        if matched_writer.readers.is_empty() {
+            self.matched_writers.remove(&writer_guid);
+        }
+        if let Some(status) = self.liveliness_changed.get_mut(&reader_guid) {
+            status.change(
+                pl_cdr::key_hash(writer_guid),
+                -i32::from(alive),
+                -i32::from(!alive),
+            );
+        }
+        if alive {
+            self.events.push_back(Event::LivelinessChanged {
+                reader_guid,
+                writer_guid,
+                alive: false,
+            });
+        }
+    }
+
+    /// Asserts the liveliness of the Participant, i.e. of all its Writers
+    /// with the MANUAL_BY_PARTICIPANT liveliness, like the
+    /// assert_liveliness operation of the DomainParticipant.
+    pub fn assert_participant(&mut self, now: Instant, core: &mut ProtocolCore) {
+        self.write(
+            core,
+            ParticipantMessageData::PARTICIPANT_MESSAGE_DATA_KIND_MANUAL_LIVELINESS_UPDATE,
+        );
+        for local_writer in self.local_writers.values_mut() {
+            if local_writer.liveliness.kind == LivelinessQosPolicyKind::MANUAL_BY_PARTICIPANT {
+                local_writer.lease_expiration = lease_expiration(now, local_writer.liveliness);
+            }
+        }
+        self.participant_asserted(
+            now,
+            self.guid_prefix,
+            LivelinessQosPolicyKind::MANUAL_BY_PARTICIPANT,
+        );
+    }
+
+    /// Asserts the liveliness of the local Writer, like the
+    /// assert_liveliness operation of the DataWriter or writing a sample.
+    /// The Writer with the MANUAL_BY_PARTICIPANT liveliness asserts the
+    /// liveliness of the Participant, the Writer with the MANUAL_BY_TOPIC
+    /// liveliness sends a Heartbeat with the LivelinessFlag.
+    pub fn assert_writer(&mut self, now: Instant, core: &mut ProtocolCore, writer_guid: GUID_t) {
+        let liveliness = match self.local_writers.get(&writer_guid) {
+            Some(local_writer) => local_writer.liveliness,
+            None => return,
+        };
+        match liveliness.kind {
+            LivelinessQosPolicyKind::AUTOMATIC => (),
+            LivelinessQosPolicyKind::MANUAL_BY_PARTICIPANT => self.assert_participant(now, core),
+            LivelinessQosPolicyKind::MANUAL_BY_TOPIC => {
+                if let Some(local_writer) = self.local_writers.get_mut(&writer_guid) {
+                    local_writer.lease_expiration = lease_expiration(now, liveliness);
+                }
+                if let Some(writer) = core.writer_mut(writer_guid.entity_id) {
+                    writer.assert_liveliness();
+                }
+                self.handle_liveliness_asserted(now, writer_guid);
+            }
+        }
+    }
+
+    /// Renews the lease of the Writer which sent a Heartbeat with the
+    /// LivelinessFlag, to be called for the LivelinessAsserted events of the
+    /// core.
+    pub fn handle_liveliness_asserted(&mut self, now: Instant, writer_guid: GUID_t) {
+        self.renew(now, writer_guid);
+    }
+
+    /// Processes the assertions received by the built-in Reader of the
+    /// core, to be called for its DataAvailable events.
+    pub fn handle_data_available(&mut self, now: Instant, core: &mut ProtocolCore) {
+        let changes =
+            match core.reader_mut(EntityId_t::ENTITYID_P2P_BUILTIN_PARTICIPANT_MESSAGE_READER) {
+                Some(reader) => std::mem::take(&mut reader.as_reader_mut().reader_cache),
+                None => return,
+            };
+        for change in changes.changes() {
+            match ParticipantMessageData::deserialize(&change.data_value.value) {
+                Ok(participant_message_data) => {
+                    let kind = match participant_message_data.kind {
+                        ParticipantMessageData::PARTICIPANT_MESSAGE_DATA_KIND_AUTOMATIC_LIVELINESS_UPDATE => {
+                            LivelinessQosPolicyKind::AUTOMATIC
+                        }
+                        ParticipantMessageData::PARTICIPANT_MESSAGE_DATA_KIND_MANUAL_LIVELINESS_UPDATE => {
+                            LivelinessQosPolicyKind::MANUAL_BY_PARTICIPANT
+                        }
+                        _ => continue,
+                    };
+                    self.participant_asserted(
+                        now,
+                        participant_message_data.participant_guid_prefix,
+                        kind,
+                    );
+                }
+                Err(error) => debug!("Dropping malformed participant message: {}", error),
+            }
+        }
+    }
+
+    /// Sends the periodic AUTOMATIC assertions and expires the leases which
+    /// expired before or at now.
+    pub fn handle_timeout(&mut self, now: Instant, core: &mut ProtocolCore) {
+        if let (Some(automatic_assertion), Some(period)) =
+            (self.automatic_assertion, self.automatic_period())
+        {
+            if automatic_assertion <= now {
+                self.write(
+                    core,
+                    ParticipantMessageData::PARTICIPANT_MESSAGE_DATA_KIND_AUTOMATIC_LIVELINESS_UPDATE,
+                );
+                self.participant_asserted(
+                    now,
+                    self.guid_prefix,
+                    LivelinessQosPolicyKind::AUTOMATIC,
+                );
+                self.automatic_assertion = Some(now + period);
+            }
+        }
+
+        for (writer_guid, local_writer) in self.local_writers.iter_mut() {
+            if local_writer
+                .lease_expiration
+                .is_some_and(|lease_expiration| lease_expiration <= now)
+            {
+                local_writer.lease_expiration = None;
+                local_writer.liveliness_lost.increment();
+                self.events.push_back(Event::LivelinessLost {
+                    writer_guid: *writer_guid,
+                });
+            }
+        }
+
+        for (writer_guid, matched_writer) in self.matched_writers.iter_mut() {
+            if matched_writer
+                .lease_expiration
+                .is_none_or(|lease_expiration| lease_expiration > now)
+            {
+                continue;
+            }
+            matched_writer.alive = false;
+            matched_writer.lease_expiration = None;
+            for reader_guid in &matched_writer.readers {
+                self.liveliness_changed
+                    .entry(*reader_guid)
+                    .or_default()
+                    .change(pl_cdr::key_hash(*writer_guid), -1, 1);
+                self.events.push_back(Event::LivelinessChanged {
+                    reader_guid: *reader_guid,
+                    writer_guid: *writer_guid,
+                    alive: false,
+                });
+            }
+        }
+    }
+
+    /// The earliest time at which handle_timeout has to be called.
+    pub fn poll_timeout(&self) -> Option<Instant> {
+        self.local_writers
+            .values()
+            .filter_map(|local_writer| local_writer.lease_expiration)
+            .chain(
+                self.matched_writers
+                    .values()
+                    .filter_map(|matched_writer| matched_writer.lease_expiration),
+            )
+            .chain(self.automatic_assertion)
+            .min()
+    }
+
+    /// Reads the LIVELINESS_CHANGED status of the local Reader.
+    pub fn liveliness_changed_status(
+        &mut self,
+        reader_guid: GUID_t,
+    ) -> Option<LivelinessChangedStatus> {
+        self.liveliness_changed
+            .get_mut(&reader_guid)
+            .map(LivelinessChangedStatus::read)
+    }
+
+    /// Reads the LIVELINESS_LOST status of the local Writer.
+    pub fn liveliness_lost_status(&mut self, writer_guid: GUID_t) -> Option<LivelinessLostStatus> {
+        self.local_writers
+            .get_mut(&writer_guid)
+            .map(|local_writer| local_writer.liveliness_lost.read())
+    }
+
+    pub fn poll_event(&mut self) -> Option<Event> {
+        self.events.pop_front()
+    }
+
+    /// The period of the AUTOMATIC assertions, derived from the shortest
+    /// finite lease of the local Writers with the AUTOMATIC liveliness.
+    fn automatic_period(&self) -> Option<Duration> {
+        self.local_writers
+            .values()
+            .filter(|local_writer| {
+                local_writer.liveliness.kind == LivelinessQosPolicyKind::AUTOMATIC
+                    && local_writer.liveliness.lease_duration != Duration_t::DURATION_INFINITE
+            })
+            .map(|local_writer| Duration::from(local_writer.liveliness.lease_duration))
+            .min()
+            .map(|lease_duration| lease_duration / AUTOMATIC_ASSERTIONS_PER_LEASE)
+    }
+
+    /// Renews the leases of the matched Writers of the Participant whose
+    /// liveliness is asserted by the assertion of the kind; the MANUAL
+    /// assertion of the Participant implies the AUTOMATIC one.
+    fn participant_asserted(
+        &mut self,
+        now: Instant,
+        guid_prefix: GuidPrefix_t,
+        kind: LivelinessQosPolicyKind,
+    ) {
+        let asserted: Vec<GUID_t> = self
+            .matched_writers
+            .iter()
+            .filter(|(writer_guid, matched_writer)| {
+                writer_guid.guid_prefix == guid_prefix && matched_writer.liveliness.kind <= kind
+            })
+            .map(|(writer_guid, _)| *writer_guid)
+            .collect();
+        for writer_guid in asserted {
+            self.renew(now, writer_guid);
+        }
+    }
+
+    fn renew(&mut self, now: Instant, writer_guid: GUID_t) {
+        let matched_writer = match self.matched_writers.get_mut(&writer_guid) {
+            Some(matched_writer) => matched_writer,
+            None => return,
+        };
+        matched_writer.lease_expiration = lease_expiration(now, matched_writer.liveliness);
+        if matched_writer.alive {
+            return;
+        }
+        matched_writer.alive = true;
+        for reader_guid in &matched_writer.readers {
+            self.liveliness_changed
+                .entry(*reader_guid)
+                .or_default()
+                .change(pl_cdr::key_hash(writer_guid), 1, -1);
+            self.events.push_back(Event::LivelinessChanged {
+                reader_guid: *reader_guid,
+                writer_guid,
+                alive: true,
+            });
+        }
+    }
+
+    /// Writes the assertion of the kind, replacing the previous one.
+    fn write(&mut self, core: &mut ProtocolCore, kind: [u8; 4]) {
+        let writer =
+            match core.writer_mut(EntityId_t::ENTITYID_P2P_BUILTIN_PARTICIPANT_MESSAGE_WRITER) {
+                Some(writer) => writer,
+                None => return,
+            };
+        if let Some(sequence_number) = self.asserted.get(&kind) {
+            writer
+                .as_writer_mut()
+                .writer_cache
+                .remove_change(*sequence_number);
+        }
+        let participant_message_data = ParticipantMessageData::new(self.guid_prefix, kind);
+        let change = writer.new_change(
+            ChangeKind_t::ALIVE,
+            Data::from(participant_message_data.serialize(Endianness::LittleEndian)),
+            participant_message_data.instance_handle(),
+        );
+        self.asserted.insert(kind, change.sequence_number);
+        writer.as_writer_mut().writer_cache.add_change(change);
+    }
+}
+
+/// The expiration of the lease starting at now, None for the infinite lease.
+fn lease_expiration(now: Instant, liveliness: LivelinessQosPolicy) -> Option<Instant> {
+    match liveliness.lease_duration {
+        Duration_t::DURATION_INFINITE => None,
+        lease_duration => Some(now + Duration::from(lease_duration)),
+    }
+}
+
+fn endpoint() -> EndpointAttributes {
+    EndpointAttributes {
+        topic_kind: TopicKind_t::WITH_KEY,
+        reliability_level: ReliabilityKind_t::RELIABLE,
+        unicast_locator_list: vec![],
+        multicast_locator_list: vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::vendor_id::VendorId_t;
+
+    const GUID_PREFIX: GuidPrefix_t = GuidPrefix_t {
+        entity_key: [0x01; 12],
+    };
+
+    fn guid(guid_prefix: GuidPrefix_t, entity_key: u8, entity_kind: u8) -> GUID_t {
+        GUID_t {
+            guid_prefix,
+            entity_id: EntityId_t::new([0x00, 0x00, entity_key], entity_kind),
+        }
+    }
+
+    fn liveliness(kind: LivelinessQosPolicyKind, lease_duration: u64) -> LivelinessQosPolicy {
+        LivelinessQosPolicy {
+            kind,
+            lease_duration: Duration_t::from(Duration::from_secs(lease_duration)),
+        }
+    }
+
+    fn wlp(now: Instant) -> (ProtocolCore, Wlp) {
+        let mut core = ProtocolCore::new(GUID_PREFIX, VendorId_t::VENDOR_UNKNOWN);
+        let wlp = Wlp::new(now, &mut core, GUID_PREFIX, Duration::from_secs(1));
+        (core, wlp)
+    }
+
+    fn events(wlp: &mut Wlp) -> Vec<Event> {
+        std::iter::from_fn(|| wlp.poll_event()).collect()
+    }
+
+    #[test]
+    fn matched_writer_loses_liveliness_when_lease_expires() {
+        let start = Instant::now();
+        let (mut core, mut wlp) = wlp(start);
+        let reader_guid = guid(
+            GUID_PREFIX,
+            1,
+            EntityId_t::ENTITYKIND_USER_DEFINED_READER_WITH_KEY,
+        );
+        let writer_guid = guid(
+            GuidPrefix_t::from([0x02; 12]),
+            1,
+            EntityId_t::ENTITYKIND_USER_DEFINED_WRITER_WITH_KEY,
+        );
+        let changed = |alive| Event::LivelinessChanged {
+            reader_guid,
+            writer_guid,
+            alive,
+        };
+
+        wlp.writer_matched(
+            start,
+            reader_guid,
+            writer_guid,
+            liveliness(LivelinessQosPolicyKind::MANUAL_BY_TOPIC, 2),
+        );
+        assert_eq!(vec![changed(true)], events(&mut wlp));
+        assert_eq!(Some(start + Duration::from_secs(2)), wlp.poll_timeout());
+
+        wlp.handle_liveliness_asserted(start + Duration::from_secs(1), writer_guid);
+        wlp.handle_timeout(start + Duration::from_secs(2), &mut core);
+        assert!(events(&mut wlp).is_empty());
+
+        wlp.handle_timeout(start + Duration::from_secs(3), &mut core);
+        assert_eq!(vec![changed(false)], events(&mut wlp));
+        assert_eq!(None, wlp.poll_timeout());
+        let status = wlp.liveliness_changed_status(reader_guid).unwrap();
+        assert_eq!((0, 1), (status.alive_count, status.not_alive_count));
+        assert_eq!(
+            (0, 1),
+            (status.alive_count_change, status.not_alive_count_change)
+        );
+        assert_eq!(
+            pl_cdr::key_hash(writer_guid),
+            status.last_publication_handle
+        );
+
+        wlp.handle_liveliness_asserted(start + Duration::from_secs(4), writer_guid);
+        assert_eq!(vec![changed(true)], events(&mut wlp));
+        let status = wlp.liveliness_changed_status(reader_guid).unwrap();
+        assert_eq!((1, 0), (status.alive_count, status.not_alive_count));
+        assert_eq!(
+            (1, -1),
+            (status.alive_count_change, status.not_alive_count_change)
+        );
+
+        wlp.writer_unmatched(reader_guid, writer_guid);
+        assert_eq!(vec![changed(false)], events(&mut wlp));
+        assert_eq!(
+            (0, 0),
+            wlp.liveliness_changed_status(reader_guid)
+                .map(|status| (status.alive_count, status.not_alive_count))
+                .unwrap()
+        );
+        assert_eq!(None, wlp.poll_timeout());
+    }
+
+    #[test]
+    fn automatic_liveliness_is_asserted_periodically() {
+        let start = Instant::now();
+        let (mut core, mut wlp) = wlp(start);
+        let writer_guid = guid(
+            GUID_PREFIX,
+            1,
+            EntityId_t::ENTITYKIND_USER_DEFINED_WRITER_WITH_KEY,
+        );
+        wlp.add_local_writer(
+            start,
+            writer_guid,
+            liveliness(LivelinessQosPolicyKind::AUTOMATIC, 3),
+        );
+        assert_eq!(Some(start + Duration::from_secs(1)), wlp.poll_timeout());
+
+        for seconds in 1..=2 {
+            wlp.handle_timeout(start + Duration::from_secs(seconds), &mut core);
+        }
+
+        // The assertion replaces the previous one
+        let writer = core
+            .writer_mut(EntityId_t::ENTITYID_P2P_BUILTIN_PARTICIPANT_MESSAGE_WRITER)
+            .unwrap();
+        match writer.as_writer().writer_cache.changes() {
+            [change] => assert_eq!(
+                ParticipantMessageData::new(
+                    GUID_PREFIX,
+                    ParticipantMessageData::PARTICIPANT_MESSAGE_DATA_KIND_AUTOMATIC_LIVELINESS_UPDATE
+                ),
+                ParticipantMessageData::deserialize(&change.data_value.value).unwrap()
+            ),
+            other => panic!("Unexpected changes {:?}", other),
+        }
+        assert_eq!(Some(start + Duration::from_secs(3)), wlp.poll_timeout());
+        assert!(events(&mut wlp).is_empty());
+    }
+
+    #[test]
+    fn manual_writer_loses_liveliness_unless_participant_asserts_it() {
+        let start = Instant::now();
+        let (mut core, mut wlp) = wlp(start);
+        let writer_guid = guid(
+            GUID_PREFIX,
+            1,
+            EntityId_t::ENTITYKIND_USER_DEFINED_WRITER_WITH_KEY,
+        );
+        wlp.add_local_writer(
+            start,
+            writer_guid,
+            liveliness(LivelinessQosPolicyKind::MANUAL_BY_PARTICIPANT, 2),
+        );
+
+        wlp.assert_writer(start + Duration::from_secs(1), &mut core, writer_guid);
+        wlp.handle_timeout(start + Duration::from_secs(2), &mut core);
+        assert!(events(&mut wlp).is_empty());
+
+        wlp.handle_timeout(start + Duration::from_secs(3), &mut core);
+        assert_eq!(
+            vec![Event::LivelinessLost { writer_guid }],
+            events(&mut wlp)
+        );
+        assert_eq!(
+            LivelinessLostStatus {
+                total_count: 1,
+                total_count_change: 1,
+            },
+            wlp.liveliness_lost_status(writer_guid).unwrap()
+        );
+
+        // Lost once until asserted again
+        wlp.handle_timeout(start + Duration::from_secs(10), &mut core);
+        assert!(events(&mut wlp).is_empty());
+        wlp.assert_participant(start + Duration::from_secs(10), &mut core);
+        assert_eq!(Some(start + Duration::from_secs(12)), wlp.poll_timeout());
+    }
+}
@@ -1,4 +1,3 @@
-#![allow(dead_code)]
 #![allow(non_camel_case_types)]
 #![allow(non_upper_case_globals)]
 
@@ -14,9 +13,27 @@ extern crate tokio_util;
 mod serialization_test;
 #[macro_use]
 mod checked_impl;
+#[allow(dead_code)]
 mod behavior;
+#[allow(dead_code)]
 mod common;
-mod dds;
+pub mod dds;
 mod discovery;
+#[allow(dead_code)]
 mod messages;
+mod protocol;
+#[allow(dead_code)]
 mod structure;
+pub mod transport;
+
+// The RTPS types of the DCPS API and of the transports.
+pub use crate::discovery::discovery_config::DiscoveryConfig;
+pub use crate::discovery::static_discovery_config::StaticDiscoveryConfig;
+pub use crate::messages::vendor_id::VendorId_t;
+pub use crate::protocol::event::Event;
+pub use crate::protocol::transmit::Transmit;
+pub use crate::structure::duration::Duration_t;
+pub use crate::structure::guid::GUID_t;
+pub use crate::structure::instance_handle::InstanceHandle_t;
+pub use crate::structure::locator::{LocatorKind_t, LocatorList_t, Locator_t};
+pub use crate::structure::time::Time_t;
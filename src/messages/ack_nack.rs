@@ -36,6 +36,12 @@ pub struct AckNack {
     pub count: Count_t,
 }
 
+impl AckNack {
+    /// Indicates whether the Reader requires a response from the Writer or
+    /// if it is simply informing the Writer about its state.
+    pub const FINAL_FLAG: u8 = 0x02;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,7 +1,14 @@
+use crate::messages::status_info::StatusInfo_t;
 use crate::messages::submessage_elements::parameter_list::ParameterList;
 use crate::messages::submessage_elements::serialized_payload::SerializedPayload;
+use crate::messages::submessage_flag::SubmessageFlag;
+use crate::structure::change_kind::ChangeKind_t;
 use crate::structure::entity_id::EntityId_t;
+use crate::structure::instance_handle::InstanceHandle_t;
+use crate::structure::parameter_id::ParameterId;
 use crate::structure::sequence_number::SequenceNumber_t;
+use bytes::Bytes;
+use speedy::{Context, Endianness, Readable, Writable, Writer};
 
 /// This Submessage is sent from an RTPS Writer (NO_KEY or WITH_KEY)
 /// to an RTPS Reader (NO_KEY or WITH_KEY)
@@ -28,11 +35,227 @@ pub struct Data {
 
     /// Contains QoS that may affect the interpretation of the message.
     /// Present only if the InlineQosFlag is set in the header.
-    pub inline_qos: ParameterList,
+    pub inline_qos: Option<ParameterList>,
 
     /// If the DataFlag is set, then it contains the encapsulation of
     /// the new value of the data-object after the change.
     /// If the KeyFlag is set, then it contains the encapsulation of
     /// the key of the data-object the message refers to.
-    pub serialized_payload: SerializedPayload,
+    pub serialized_payload: Option<SerializedPayload>,
+}
+
+/// Number of octets between the octetsToInlineQos field and the inline QoS
+const OCTETS_TO_INLINE_QOS: u16 = 16;
+
+impl Data {
+    /// Indicates to the Reader the presence of a ParameterList containing QoS
+    /// parameters that should be used to interpret the message.
+    pub const INLINE_QOS_FLAG: u8 = 0x02;
+
+    /// Indicates to the Reader that the serialized_payload contains the
+    /// serialized value of the data-object.
+    pub const DATA_FLAG: u8 = 0x04;
+
+    /// Indicates to the Reader that the serialized_payload contains the
+    /// serialized value of the key of the data-object.
+    pub const KEY_FLAG: u8 = 0x08;
+
+    /// The Data Submessage layout depends on the flags from the Submessage
+    /// header, so it cannot be read without them. The serialized payload is
+    /// a view into the provided bytes, no copy is made.
+    pub fn read_from_bytes_with_flags(
+        flags: &SubmessageFlag,
+        bytes: Bytes,
+    ) -> Result<Self, speedy::Error> {
+        let endianness = flags.endianness_flag();
+
+        let (_extra_flags, octets_to_inline_qos): (u16, u16) =
+            Readable::read_from_buffer_with_ctx(endianness, &bytes)?;
+        let (reader_id, writer_id, writer_sn) =
+            Readable::read_from_buffer_with_ctx(endianness, &bytes[4..])?;
+
+        let inline_qos_start = 4 + usize::from(octets_to_inline_qos);
+        if inline_qos_start > bytes.len() {
+            return Err(speedy::Error::custom("Data octetsToInlineQos out of range"));
+        }
+
+        let (inline_qos, payload_start) = if flags.is_flag_set(Data::INLINE_QOS_FLAG) {
+            let (inline_qos, read_bytes) = ParameterList::read_with_length_from_buffer_with_ctx(
+                endianness,
+                &bytes[inline_qos_start..],
+            );
+            (Some(inline_qos?), inline_qos_start + read_bytes)
+        } else {
+            (None, inline_qos_start)
+        };
+
+        let serialized_payload =
+            if flags.is_flag_set(Data::DATA_FLAG) || flags.is_flag_set(Data::KEY_FLAG) {
+                Some(SerializedPayload::from(bytes.slice(payload_start..)))
+            } else {
+                None
+            };
+
+        Ok(Data {
+            reader_id,
+            writer_id,
+            writer_sn,
+            inline_qos,
+            serialized_payload,
+        })
+    }
+
+    /// The kind of the change, given by the PID_STATUS_INFO of the inline
+    /// QoS; ALIVE without it.
+    pub fn change_kind(&self) -> ChangeKind_t {
+        self.inline_parameter::<StatusInfo_t>(ParameterId::PID_STATUS_INFO)
+            .unwrap_or_default()
+            .change_kind()
+    }
+
+    /// The instance of the change, given by the PID_KEY_HASH of the inline
+    /// QoS; HANDLE_NIL without it.
+    pub fn key_hash(&self) -> InstanceHandle_t {
+        self.inline_parameter(ParameterId::PID_KEY_HASH)
+            .unwrap_or_default()
+    }
+
+    /// Both the key hash and the status info are octets, read the same in
+    /// any endianness.
+    fn inline_parameter<T: for<'a> Readable<'a, Endianness>>(
+        &self,
+        parameter_id: ParameterId,
+    ) -> Option<T> {
+        let parameter = self.inline_qos.as_ref()?.find(&parameter_id)?;
+        T::read_from_buffer_with_ctx(Endianness::BigEndian, &parameter.value).ok()
+    }
+}
+
+impl<C: Context> Writable<C> for Data {
+    #[inline]
+    fn write_to<T: ?Sized + Writer<C>>(&self, writer: &mut T) -> Result<(), C::Error> {
+        writer.write_u16(0)?;
+        writer.write_u16(OCTETS_TO_INLINE_QOS)?;
+        writer.write_value(&self.reader_id)?;
+        writer.write_value(&self.writer_id)?;
+        writer.write_value(&self.writer_sn)?;
+        if let Some(inline_qos) = &self.inline_qos {
+            writer.write_value(inline_qos)?;
+        }
+        if let Some(serialized_payload) = &self.serialized_payload {
+            writer.write_value(serialized_payload)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::submessage_elements::parameter::Parameter;
+
+    fn data(
+        inline_qos: Option<ParameterList>,
+        serialized_payload: Option<SerializedPayload>,
+    ) -> Data {
+        Data {
+            reader_id: EntityId_t::ENTITYID_SPDP_BUILTIN_PARTICIPANT_READER,
+            writer_id: EntityId_t::ENTITYID_SPDP_BUILTIN_PARTICIPANT_WRITER,
+            writer_sn: SequenceNumber_t::from(1),
+            inline_qos,
+            serialized_payload,
+        }
+    }
+
+    #[test]
+    fn serialize_little_endian() {
+        let serialized = data(
+            None,
+            Some(SerializedPayload::from(Bytes::from_static(&[
+                0x00, 0x03, 0x00, 0x00,
+            ]))),
+        )
+        .write_to_vec_with_ctx(Endianness::LittleEndian)
+        .unwrap();
+
+        assert_eq!(
+            serialized,
+            vec![
+                0x00, 0x00, 0x10, 0x00, // extraFlags, octetsToInlineQos
+                0x00, 0x01, 0x00, 0xC7, // readerId
+                0x00, 0x01, 0x00, 0xC2, // writerId
+                0x00, 0x00, 0x00, 0x00, // writerSN
+                0x01, 0x00, 0x00, 0x00, //
+                0x00, 0x03, 0x00, 0x00, // serializedPayload
+            ]
+        );
+    }
+
+    macro_rules! round_trip_test {
+        ($({ $name:ident, $original:expr, flags = $flags:expr }),+) => {
+            $(#[test]
+            fn $name() {
+                let original: Data = $original;
+                let flags = SubmessageFlag { flags: $flags };
+
+                let serialized = original
+                    .write_to_vec_with_ctx(flags.endianness_flag())
+                    .unwrap();
+                let deserialized =
+                    Data::read_from_bytes_with_flags(&flags, Bytes::from(serialized)).unwrap();
+
+                assert_eq!(original, deserialized);
+            })+
+        }
+    }
+
+    round_trip_test!(
+        {
+            round_trip_without_payload,
+            data(None, None),
+            flags = 0b0000_0001
+        },
+        {
+            round_trip_with_data_big_endian,
+            data(None, Some(SerializedPayload::from(Bytes::from_static(b"payload")))),
+            flags = 0b0000_0100
+        },
+        {
+            round_trip_with_key_and_inline_qos,
+            data(
+                Some(ParameterList {
+                    parameters: vec![Parameter::new(
+                        ParameterId::PID_STATUS_INFO,
+                        vec![0x00, 0x00, 0x00, 0x03]
+                    )]
+                }),
+                Some(SerializedPayload::from(Bytes::from_static(&[0x01; 16])))
+            ),
+            flags = 0b0000_1011
+        }
+    );
+
+    #[test]
+    fn change_kind_and_key_hash_are_read_from_inline_qos() {
+        let alive = data(None, None);
+        assert_eq!(ChangeKind_t::ALIVE, alive.change_kind());
+        assert_eq!(InstanceHandle_t::HANDLE_NIL, alive.key_hash());
+
+        let disposed = data(
+            Some(ParameterList {
+                parameters: vec![
+                    Parameter::new(ParameterId::PID_KEY_HASH, vec![0x0F; 16]),
+                    Parameter::new(ParameterId::PID_STATUS_INFO, vec![0x00, 0x00, 0x00, 0x01]),
+                ],
+            }),
+            None,
+        );
+        assert_eq!(ChangeKind_t::NOT_ALIVE_DISPOSED, disposed.change_kind());
+        assert_eq!(
+            InstanceHandle_t {
+                entity_key: [0x0F; 16]
+            },
+            disposed.key_hash()
+        );
+    }
 }
@@ -1,8 +1,15 @@
 use crate::messages::fragment_number::FragmentNumber_t;
+use crate::messages::status_info::StatusInfo_t;
 use crate::messages::submessage_elements::parameter_list::ParameterList;
 use crate::messages::submessage_elements::serialized_payload::SerializedPayload;
+use crate::messages::submessage_flag::SubmessageFlag;
+use crate::structure::change_kind::ChangeKind_t;
 use crate::structure::entity_id::EntityId_t;
+use crate::structure::instance_handle::InstanceHandle_t;
+use crate::structure::parameter_id::ParameterId;
 use crate::structure::sequence_number::SequenceNumber_t;
+use bytes::Bytes;
+use speedy::{Context, Endianness, Readable, Writable, Writer};
 
 /// The DataFrag Submessage extends the Data Submessage by enabling the
 /// serializedData to be fragmented and sent as multiple DataFrag Submessages.
@@ -12,41 +19,322 @@ use crate::structure::sequence_number::SequenceNumber_t;
 pub struct DataFrag {
     /// Identifies the RTPS Reader entity that is being informed of the change
     /// to the data-object.
-    reader_id: EntityId_t,
+    pub reader_id: EntityId_t,
 
     /// Identifies the RTPS Writer entity that made the change to the
     /// data-object.
-    writer_id: EntityId_t,
+    pub writer_id: EntityId_t,
 
     /// Uniquely identifies the change and the relative order for all changes
     /// made by the RTPS Writer identified by the writerGuid.
     /// Each change gets a consecutive sequence number.
     /// Each RTPS Writer maintains is own sequence number.
-    writer_sn: SequenceNumber_t,
+    pub writer_sn: SequenceNumber_t,
 
     /// Indicates the starting fragment for the series of fragments in
     /// serialized_data. Fragment numbering starts with number 1.
-    fragment_starting_num: FragmentNumber_t,
+    pub fragment_starting_num: FragmentNumber_t,
 
     /// The number of consecutive fragments contained in this Submessage,
     /// starting at fragment_starting_num.
-    fragments_in_submessage: u16,
+    pub fragments_in_submessage: u16,
 
     /// The total size in bytes of the original data before fragmentation.
-    data_size: u32,
+    pub data_size: u32,
 
     /// The size of an individual fragment in bytes. The maximum fragment size
     /// equals 64K.
-    fragment_size: u16,
+    pub fragment_size: u16,
 
     /// Contains QoS that may affect the interpretation of the message.
     /// Present only if the InlineQosFlag is set in the header.
-    inline_qos: ParameterList,
+    pub inline_qos: Option<ParameterList>,
 
     /// Encapsulation of a consecutive series of fragments, starting at
     /// fragment_starting_num for a total of fragments_in_submessage.
     /// Represents part of the new value of the data-object
     /// after the change. Present only if either the DataFlag or the KeyFlag are
     /// set in the header. Present only if DataFlag is set in the header.
-    serialized_payload: SerializedPayload,
+    pub serialized_payload: SerializedPayload,
+}
+
+/// Number of octets between the octetsToInlineQos field and the inline QoS
+const OCTETS_TO_INLINE_QOS: u16 = 28;
+
+impl DataFrag {
+    /// Indicates to the Reader the presence of a ParameterList containing QoS
+    /// parameters that should be used to interpret the message.
+    pub const INLINE_QOS_FLAG: u8 = 0x02;
+
+    /// Indicates that the serialized_payload contains the serialized key
+    /// instead of the serialized data.
+    pub const KEY_FLAG: u8 = 0x04;
+
+    /// Returns the number of fragments needed to send data_size bytes using
+    /// fragments of fragment_size bytes.
+    pub fn fragments_count(data_size: u32, fragment_size: u16) -> u32 {
+        data_size.div_ceil(u32::from(fragment_size))
+    }
+
+    /// The DataFrag Submessage layout depends on the flags from the
+    /// Submessage header, so it cannot be read without them. The serialized
+    /// payload is a view into the provided bytes, no copy is made.
+    pub fn read_from_bytes_with_flags(
+        flags: &SubmessageFlag,
+        bytes: Bytes,
+    ) -> Result<Self, speedy::Error> {
+        let endianness = flags.endianness_flag();
+
+        let (_extra_flags, octets_to_inline_qos): (u16, u16) =
+            Readable::read_from_buffer_with_ctx(endianness, &bytes)?;
+        let (
+            reader_id,
+            writer_id,
+            writer_sn,
+            fragment_starting_num,
+            fragments_in_submessage,
+            fragment_size,
+            data_size,
+        ) = Readable::read_from_buffer_with_ctx(endianness, &bytes[4..])?;
+
+        if fragment_size == 0 {
+            return Err(speedy::Error::custom("DataFrag fragmentSize is zero"));
+        }
+
+        let inline_qos_start = 4 + usize::from(octets_to_inline_qos);
+        if inline_qos_start > bytes.len() {
+            return Err(speedy::Error::custom(
+                "DataFrag octetsToInlineQos out of range",
+            ));
+        }
+
+        let (inline_qos, payload_start) = if flags.is_flag_set(DataFrag::INLINE_QOS_FLAG) {
+            let (inline_qos, read_bytes) = ParameterList::read_with_length_from_buffer_with_ctx(
+                endianness,
+                &bytes[inline_qos_start..],
+            );
+            (Some(inline_qos?), inline_qos_start + read_bytes)
+        } else {
+            (None, inline_qos_start)
+        };
+
+        Ok(DataFrag {
+            reader_id,
+            writer_id,
+            writer_sn,
+            fragment_starting_num,
+            fragments_in_submessage,
+            data_size,
+            fragment_size,
+            inline_qos,
+            serialized_payload: SerializedPayload::from(bytes.slice(payload_start..)),
+        })
+    }
+
+    /// The kind of the change, given by the PID_STATUS_INFO of the inline
+    /// QoS; ALIVE without it.
+    pub fn change_kind(&self) -> ChangeKind_t {
+        self.inline_parameter::<StatusInfo_t>(ParameterId::PID_STATUS_INFO)
+            .unwrap_or_default()
+            .change_kind()
+    }
+
+    /// The instance of the change, given by the PID_KEY_HASH of the inline
+    /// QoS; HANDLE_NIL without it.
+    pub fn key_hash(&self) -> InstanceHandle_t {
+        self.inline_parameter(ParameterId::PID_KEY_HASH)
+            .unwrap_or_default()
+    }
+
+    /// Both the key hash and the status info are octets, read the same in
+    /// any endianness.
+    fn inline_parameter<T: for<'a> Readable<'a, Endianness>>(
+        &self,
+        parameter_id: ParameterId,
+    ) -> Option<T> {
+        let parameter = self.inline_qos.as_ref()?.find(&parameter_id)?;
+        T::read_from_buffer_with_ctx(Endianness::BigEndian, &parameter.value).ok()
+    }
+}
+
+impl<C: Context> Writable<C> for DataFrag {
+    #[inline]
+    fn write_to<T: ?Sized + Writer<C>>(&self, writer: &mut T) -> Result<(), C::Error> {
+        writer.write_u16(0)?;
+        writer.write_u16(OCTETS_TO_INLINE_QOS)?;
+        writer.write_value(&self.reader_id)?;
+        writer.write_value(&self.writer_id)?;
+        writer.write_value(&self.writer_sn)?;
+        writer.write_value(&self.fragment_starting_num)?;
+        writer.write_u16(self.fragments_in_submessage)?;
+        writer.write_u16(self.fragment_size)?;
+        writer.write_u32(self.data_size)?;
+        if let Some(inline_qos) = &self.inline_qos {
+            writer.write_value(inline_qos)?;
+        }
+        writer.write_value(&self.serialized_payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::submessage_elements::parameter::Parameter;
+
+    fn data_frag(inline_qos: Option<ParameterList>) -> DataFrag {
+        DataFrag {
+            reader_id: EntityId_t::ENTITYID_UNKNOWN,
+            writer_id: EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_WRITER,
+            writer_sn: SequenceNumber_t::from(5),
+            fragment_starting_num: FragmentNumber_t::from(2),
+            fragments_in_submessage: 1,
+            data_size: 10,
+            fragment_size: 4,
+            inline_qos,
+            serialized_payload: SerializedPayload::from(Bytes::from_static(&[
+                0x0A, 0x0B, 0x0C, 0x0D,
+            ])),
+        }
+    }
+
+    #[test]
+    fn fragments_count_rounds_up() {
+        assert_eq!(0, DataFrag::fragments_count(0, 4));
+        assert_eq!(1, DataFrag::fragments_count(1, 4));
+        assert_eq!(1, DataFrag::fragments_count(4, 4));
+        assert_eq!(3, DataFrag::fragments_count(10, 4));
+        assert_eq!(2_048, DataFrag::fragments_count(2 * 1024 * 1024, 1024));
+    }
+
+    #[test]
+    fn serialize_little_endian() {
+        let serialized = data_frag(None)
+            .write_to_vec_with_ctx(Endianness::LittleEndian)
+            .unwrap();
+
+        assert_eq!(
+            serialized,
+            vec![
+                0x00, 0x00, 0x1C, 0x00, // extraFlags, octetsToInlineQos
+                0x00, 0x00, 0x00, 0x00, // readerId
+                0x00, 0x00, 0x03, 0xC2, // writerId
+                0x00, 0x00, 0x00, 0x00, // writerSN
+                0x05, 0x00, 0x00, 0x00, //
+                0x02, 0x00, 0x00, 0x00, // fragmentStartingNum
+                0x01, 0x00, 0x04, 0x00, // fragmentsInSubmessage, fragmentSize
+                0x0A, 0x00, 0x00, 0x00, // sampleSize
+                0x0A, 0x0B, 0x0C, 0x0D, // serializedPayload
+            ]
+        );
+    }
+
+    #[test]
+    fn serialize_big_endian() {
+        let serialized = data_frag(None)
+            .write_to_vec_with_ctx(Endianness::BigEndian)
+            .unwrap();
+
+        assert_eq!(
+            serialized,
+            vec![
+                0x00, 0x00, 0x00, 0x1C, // extraFlags, octetsToInlineQos
+                0x00, 0x00, 0x00, 0x00, // readerId
+                0x00, 0x00, 0x03, 0xC2, // writerId
+                0x00, 0x00, 0x00, 0x00, // writerSN
+                0x00, 0x00, 0x00, 0x05, //
+                0x00, 0x00, 0x00, 0x02, // fragmentStartingNum
+                0x00, 0x01, 0x00, 0x04, // fragmentsInSubmessage, fragmentSize
+                0x00, 0x00, 0x00, 0x0A, // sampleSize
+                0x0A, 0x0B, 0x0C, 0x0D, // serializedPayload
+            ]
+        );
+    }
+
+    macro_rules! round_trip_test {
+        ($({ $name:ident, $original:expr, flags = $flags:expr }),+) => {
+            $(#[test]
+            fn $name() {
+                let original: DataFrag = $original;
+                let flags = SubmessageFlag { flags: $flags };
+
+                let serialized = original
+                    .write_to_vec_with_ctx(flags.endianness_flag())
+                    .unwrap();
+                let deserialized =
+                    DataFrag::read_from_bytes_with_flags(&flags, Bytes::from(serialized)).unwrap();
+
+                assert_eq!(original, deserialized);
+            })+
+        }
+    }
+
+    #[test]
+    fn zero_fragment_size_is_rejected() {
+        let mut original = data_frag(None);
+        original.fragment_size = 0;
+        let flags = SubmessageFlag { flags: 0b0000_0001 };
+        let serialized = original
+            .write_to_vec_with_ctx(flags.endianness_flag())
+            .unwrap();
+
+        assert!(DataFrag::read_from_bytes_with_flags(&flags, Bytes::from(serialized)).is_err());
+    }
+
+    round_trip_test!(
+        {
+            round_trip_little_endian,
+            data_frag(None),
+            flags = 0b0000_0001
+        },
+        {
+            round_trip_big_endian,
+            data_frag(None),
+            flags = 0b0000_0000
+        },
+        {
+            round_trip_with_inline_qos,
+            data_frag(Some(ParameterList {
+                parameters: vec![Parameter::new(ParameterId::PID_KEY_HASH, vec![0x01; 16])]
+            })),
+            flags = 0b0000_0011
+        }
+    );
+
+    #[test]
+    fn reading_truncated_submessage_fails() {
+        let flags = SubmessageFlag { flags: 0b0000_0001 };
+        let serialized = data_frag(None)
+            .write_to_vec_with_ctx(flags.endianness_flag())
+            .unwrap();
+
+        assert!(DataFrag::read_from_bytes_with_flags(
+            &flags,
+            Bytes::copy_from_slice(&serialized[..20])
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn change_kind_and_key_hash_are_read_from_inline_qos() {
+        let alive = data_frag(None);
+        assert_eq!(ChangeKind_t::ALIVE, alive.change_kind());
+        assert_eq!(InstanceHandle_t::HANDLE_NIL, alive.key_hash());
+
+        let unregistered = data_frag(Some(ParameterList {
+            parameters: vec![
+                Parameter::new(ParameterId::PID_KEY_HASH, vec![0x0F; 16]),
+                Parameter::new(ParameterId::PID_STATUS_INFO, vec![0x00, 0x00, 0x00, 0x02]),
+            ],
+        }));
+        assert_eq!(
+            ChangeKind_t::NOT_ALIVE_UNREGISTERED,
+            unregistered.change_kind()
+        );
+        assert_eq!(
+            InstanceHandle_t {
+                entity_key: [0x0F; 16]
+            },
+            unregistered.key_hash()
+        );
+    }
 }
@@ -37,6 +37,16 @@ pub struct Heartbeat {
     pub count: Count_t,
 }
 
+impl Heartbeat {
+    /// Indicates whether the Reader is required to respond to the Heartbeat
+    /// or if it is just an advisory heartbeat.
+    pub const FINAL_FLAG: u8 = 0x02;
+
+    /// Indicates that the DDS DataWriter associated with the RTPS Writer of
+    /// the message has manually asserted its liveliness.
+    pub const LIVELINESS_FLAG: u8 = 0x04;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,5 +1,7 @@
 use crate::structure::time::Timestamp;
 
+use speedy::{Context, Writable, Writer};
+
 /// This message modifies the logical source of the Submessages
 /// that follow.
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -10,3 +12,21 @@ pub struct InfoTimestamp {
     /// Present only if the InvalidateFlag is not set in the header.
     pub timestamp: Option<Timestamp>,
 }
+
+impl InfoTimestamp {
+    /// Indicates that the subsequent Submessages should not be considered to
+    /// have a valid timestamp; the timestamp is then omitted.
+    pub const INVALIDATE_FLAG: u8 = 0x02;
+}
+
+/// The presence of the timestamp is encoded in the InvalidateFlag, so only
+/// the timestamp itself is written.
+impl<C: Context> Writable<C> for InfoTimestamp {
+    #[inline]
+    fn write_to<T: ?Sized + Writer<C>>(&self, writer: &mut T) -> Result<(), C::Error> {
+        match &self.timestamp {
+            Some(timestamp) => timestamp.write_to(writer),
+            None => Ok(()),
+        }
+    }
+}
@@ -15,7 +15,10 @@ pub mod nack_frag;
 pub mod pad;
 pub mod protocol_id;
 pub mod protocol_version;
+pub mod received_submessage;
 pub mod receiver;
+pub mod sender;
+pub mod status_info;
 pub mod submessage;
 pub mod submessage_elements;
 pub mod submessage_flag;
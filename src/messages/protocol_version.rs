@@ -14,6 +14,7 @@ impl ProtocolVersion_t {
     pub const PROTOCOLVERSION_2_0: ProtocolVersion_t = ProtocolVersion_t { major: 2, minor: 0 };
     pub const PROTOCOLVERSION_2_1: ProtocolVersion_t = ProtocolVersion_t { major: 2, minor: 1 };
     pub const PROTOCOLVERSION_2_2: ProtocolVersion_t = ProtocolVersion_t { major: 2, minor: 2 };
+    pub const PROTOCOLVERSION_2_3: ProtocolVersion_t = ProtocolVersion_t { major: 2, minor: 3 };
     pub const PROTOCOLVERSION_2_4: ProtocolVersion_t = ProtocolVersion_t { major: 2, minor: 4 };
 }
 
@@ -0,0 +1,25 @@
+use crate::messages::submessage::EntitySubmessage;
+use crate::messages::vendor_id::VendorId_t;
+use crate::structure::guid_prefix::GuidPrefix_t;
+use crate::structure::time::Time_t;
+
+/// A Submessage decoded by the MessageReceiver together with the state of
+/// the receiver needed to interpret it.
+#[derive(Debug, PartialEq)]
+pub struct ReceivedSubmessage {
+    pub source_guid_prefix: GuidPrefix_t,
+
+    /// The vendor of the sender, needed to interpret the vendor-specific
+    /// parameters of the inline QoS.
+    pub source_vendor_id: VendorId_t,
+
+    /// The Participant the Submessage is addressed to, GUIDPREFIX_UNKNOWN
+    /// for all Participants.
+    pub dest_guid_prefix: GuidPrefix_t,
+
+    /// The time set by the InfoTimestamp Submessage preceding the
+    /// Submessage.
+    pub source_timestamp: Option<Time_t>,
+
+    pub submessage: EntitySubmessage,
+}
@@ -1,9 +1,12 @@
 use crate::common::validity_trait::Validity;
+use crate::messages::data::Data;
+use crate::messages::data_frag::DataFrag;
 use crate::messages::heartbeat::Heartbeat;
 use crate::messages::heartbeat_frag::HeartbeatFrag;
 use crate::messages::info_destination::InfoDestination;
 use crate::messages::nack_frag::NackFrag;
 use crate::messages::protocol_version::ProtocolVersion_t;
+use crate::messages::received_submessage::ReceivedSubmessage;
 use crate::messages::submessage::EntitySubmessage;
 use crate::messages::submessage_header::SubmessageHeader;
 use crate::messages::submessage_kind::SubmessageKind;
@@ -14,11 +17,11 @@ use crate::structure::locator::{LocatorKind_t, LocatorList_t, Locator_t};
 use crate::structure::locator_udp_v4::LocatorUDPv4_t;
 use crate::structure::time::Time_t;
 
-use log::info;
+use log::{debug, info};
 use speedy::{Endianness, Readable};
 use std::io::{Error, ErrorKind};
 
-use bytes::BytesMut;
+use bytes::{Bytes, BytesMut};
 use tokio_util::codec::Decoder;
 
 #[derive(Debug, PartialEq)]
@@ -73,6 +76,50 @@ impl MessageReceiver {
             state: DeserializationState::ReadingHeader,
         }
     }
+
+    /// State of the receiver after the last decoded Submessage. Used to
+    /// interpret the decoded Submessages, e.g. to identify their source.
+    pub fn receiver(&self) -> &Receiver {
+        &self.receiver
+    }
+
+    /// Decodes all Submessages of the datagram received from the locator of
+    /// locator_kind. Malformed messages are dropped; the Submessages decoded
+    /// before the malformed part are returned.
+    pub fn decode_datagram(
+        locator_kind: LocatorKind_t,
+        datagram: Bytes,
+    ) -> Vec<ReceivedSubmessage> {
+        let mut message_receiver = MessageReceiver::new(locator_kind);
+        let mut bytes = BytesMut::from(datagram);
+        let mut submessages = vec![];
+
+        while !bytes.is_empty() {
+            let remaining = bytes.len();
+            match message_receiver.decode(&mut bytes) {
+                Ok(Some(submessage)) => {
+                    let receiver = message_receiver.receiver();
+                    submessages.push(ReceivedSubmessage {
+                        source_guid_prefix: receiver.source_guid_prefix,
+                        source_vendor_id: receiver.source_vendor_id,
+                        dest_guid_prefix: receiver.dest_guid_prefix,
+                        source_timestamp: receiver.have_timestamp.then_some(receiver.timestamp),
+                        submessage,
+                    });
+                }
+                Ok(None) if bytes.len() == remaining => {
+                    debug!("Dropping truncated message");
+                    break;
+                }
+                Ok(None) => (),
+                Err(error) => {
+                    debug!("Dropping malformed message: {}", error);
+                    break;
+                }
+            }
+        }
+        submessages
+    }
 }
 
 impl Decoder for MessageReceiver {
@@ -89,6 +136,11 @@ impl Decoder for MessageReceiver {
         };
 
         match self.state {
+            DeserializationState::ReadingHeader
+                if bytes.len() < <Header as Readable<Endianness>>::minimum_bytes_needed() =>
+            {
+                Ok(None)
+            }
             DeserializationState::ReadingHeader => Header::read_from_buffer_owned_with_ctx(
                 Endianness::NATIVE,
                 &bytes.split_to(<Header as Readable<Endianness>>::minimum_bytes_needed()),
@@ -111,19 +163,40 @@ impl Decoder for MessageReceiver {
             }),
 
             DeserializationState::ReadingSubmessage => {
+                let submessage_header_size =
+                    <SubmessageHeader as Readable<Endianness>>::minimum_bytes_needed();
+                if bytes.len() < submessage_header_size {
+                    return Ok(None);
+                }
+
                 SubmessageHeader::read_from_buffer_owned_with_ctx(
                     Endianness::NATIVE,
-                    &bytes.split_to(
-                        <SubmessageHeader as Readable<Endianness>>::minimum_bytes_needed(),
-                    ),
+                    &bytes[..submessage_header_size],
                 )
                 .and_then(|submessage_header| {
+                    if bytes.len()
+                        < submessage_header_size + usize::from(submessage_header.submessage_length)
+                    {
+                        return Err(speedy::Error::custom(
+                            "Submessage exceeds the message".to_owned(),
+                        ));
+                    }
+
+                    use crate::bytes::Buf;
+                    bytes.advance(submessage_header_size);
+                    Ok(submessage_header)
+                })
+                .and_then(|mut submessage_header| {
                     if submessage_header.submessage_length == 0
                         && submessage_header.submessage_id != SubmessageKind::INFO_TS
                         && submessage_header.submessage_id != SubmessageKind::PAD
                     {
-                        // This is a last submessage
+                        // This is a last submessage, which extends up to the end of the message
                         self.state = DeserializationState::ReadingHeader;
+                        submessage_header.submessage_length =
+                            std::convert::TryFrom::try_from(bytes.len()).map_err(|_| {
+                                speedy::Error::custom("Submessage too long".to_owned())
+                            })?;
                     }
                     Ok(submessage_header)
                 })
@@ -139,10 +212,27 @@ impl Decoder for MessageReceiver {
                         )))
                     }
                     SubmessageKind::DATA => {
-                        unimplemented!();
+                        let data = Data::read_from_bytes_with_flags(
+                            &submessage_header.flags,
+                            bytes
+                                .split_to(submessage_header.submessage_length.into())
+                                .freeze(),
+                        )?;
+
+                        Ok(Some(EntitySubmessage::Data(data, submessage_header.flags)))
                     }
                     SubmessageKind::DATA_FRAG => {
-                        unimplemented!();
+                        let data_frag = DataFrag::read_from_bytes_with_flags(
+                            &submessage_header.flags,
+                            bytes
+                                .split_to(submessage_header.submessage_length.into())
+                                .freeze(),
+                        )?;
+
+                        Ok(Some(EntitySubmessage::DataFrag(
+                            data_frag,
+                            submessage_header.flags,
+                        )))
                     }
                     SubmessageKind::GAP => {
                         let gap = Gap::read_from_buffer_owned_with_ctx(
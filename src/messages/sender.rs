@@ -0,0 +1,273 @@
+use crate::messages::header::Header;
+use crate::messages::info_destination::InfoDestination;
+use crate::messages::submessage::EntitySubmessage;
+use crate::messages::submessage_flag::SubmessageFlag;
+use crate::messages::submessage_header::SubmessageHeader;
+use crate::messages::submessage_kind::SubmessageKind;
+use crate::messages::vendor_id::VendorId_t;
+use crate::structure::guid_prefix::GuidPrefix_t;
+
+use bytes::{BufMut, Bytes, BytesMut};
+use log::warn;
+use speedy::{Endianness, Writable};
+
+/// Builds RTPS Messages out of Submessages created by the local Endpoints.
+/// Counterpart of the MessageReceiver.
+pub struct MessageSender {
+    /// Serialized Header shared by all Messages sent by the Participant
+    header: Vec<u8>,
+    max_message_size: usize,
+}
+
+impl MessageSender {
+    /// Default maximum size of a single Message, fits into a single UDP
+    /// datagram.
+    pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 65_500;
+
+    pub fn new(guid_prefix: GuidPrefix_t, vendor_id: VendorId_t) -> Self {
+        let mut header = Header::new(guid_prefix);
+        header.vendor_id = vendor_id;
+
+        MessageSender {
+            header: header
+                .write_to_vec_with_ctx(Endianness::NATIVE)
+                .expect("Header serialization never fails"),
+            max_message_size: MessageSender::DEFAULT_MAX_MESSAGE_SIZE,
+        }
+    }
+
+    pub fn max_message_size(&self) -> usize {
+        self.max_message_size
+    }
+
+    pub fn set_max_message_size(&mut self, max_message_size: usize) {
+        self.max_message_size = max_message_size
+    }
+
+    /// Serializes the Submessages addressed to the Participant identified by
+    /// dest_guid_prefix. Each Message starts with an InfoDestination
+    /// Submessage. Submessages which does not fit into a single Message are
+    /// split into several Messages; Submessages which does not fit even into
+    /// an empty Message are dropped. An InfoTimestamp Submessage is always
+    /// put into the Message of the Submessage following it.
+    pub fn encode(
+        &self,
+        dest_guid_prefix: GuidPrefix_t,
+        submessages: impl IntoIterator<Item = EntitySubmessage>,
+    ) -> Vec<Bytes> {
+        let message_prefix = self.message_prefix(dest_guid_prefix);
+        let mut messages = vec![];
+        let mut message = BytesMut::new();
+
+        let mut submessages = submessages.into_iter();
+        while let Some(submessage) = submessages.next() {
+            let mut serialized_submessage = match submessage.write_to_vec() {
+                Ok(serialized_submessage) => serialized_submessage,
+                Err(error) => {
+                    warn!("Dropping submessage {:?}: {}", submessage, error);
+                    continue;
+                }
+            };
+            // The timestamp applies to the Submessage following it, so both
+            // have to be put into the same Message
+            if let EntitySubmessage::InfoTimestamp(..) = submessage {
+                match submessages.next().map(|next| next.write_to_vec()) {
+                    Some(Ok(serialized_next)) => serialized_submessage.extend(serialized_next),
+                    Some(Err(error)) => {
+                        warn!("Dropping submessage following {:?}: {}", submessage, error);
+                        continue;
+                    }
+                    None => continue,
+                }
+            }
+
+            if message_prefix.len() + serialized_submessage.len() > self.max_message_size {
+                warn!(
+                    "Dropping submessage {:?}: does not fit into a single message",
+                    submessage.submessage_kind()
+                );
+                continue;
+            }
+
+            if !message.is_empty()
+                && message.len() + serialized_submessage.len() > self.max_message_size
+            {
+                messages.push(message.split().freeze());
+            }
+            if message.is_empty() {
+                message.put_slice(&message_prefix);
+            }
+            message.put_slice(&serialized_submessage);
+        }
+
+        if !message.is_empty() {
+            messages.push(message.freeze());
+        }
+        messages
+    }
+
+    fn message_prefix(&self, dest_guid_prefix: GuidPrefix_t) -> Vec<u8> {
+        let flags = SubmessageFlag { flags: 0x01 };
+        let info_dst = InfoDestination {
+            guid_prefix: dest_guid_prefix,
+        }
+        .write_to_vec_with_ctx(flags.endianness_flag())
+        .expect("InfoDestination serialization never fails");
+        let submessage_header = SubmessageHeader {
+            submessage_id: SubmessageKind::INFO_DST,
+            flags,
+            submessage_length: info_dst.len() as u16,
+        }
+        .write_to_vec_with_ctx(flags.endianness_flag())
+        .expect("SubmessageHeader serialization never fails");
+
+        let mut message_prefix = self.header.clone();
+        message_prefix.extend(submessage_header);
+        message_prefix.extend(info_dst);
+        message_prefix
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::data::Data;
+    use crate::messages::heartbeat::Heartbeat;
+    use crate::messages::info_timestamp::InfoTimestamp;
+    use crate::messages::receiver::MessageReceiver;
+    use crate::messages::submessage_elements::serialized_payload::SerializedPayload;
+    use crate::structure::count::Count_t;
+    use crate::structure::entity_id::EntityId_t;
+    use crate::structure::locator::LocatorKind_t;
+    use crate::structure::sequence_number::SequenceNumber_t;
+    use crate::structure::time::Time_t;
+    use std::time::{Duration, SystemTime};
+    use tokio_util::codec::Decoder;
+
+    const SOURCE_GUID_PREFIX: GuidPrefix_t = GuidPrefix_t {
+        entity_key: [0x01; 12],
+    };
+
+    const DEST_GUID_PREFIX: GuidPrefix_t = GuidPrefix_t {
+        entity_key: [0x02; 12],
+    };
+
+    fn data(writer_sn: i64, payload: &'static [u8]) -> EntitySubmessage {
+        EntitySubmessage::Data(
+            Data {
+                reader_id: EntityId_t::ENTITYID_UNKNOWN,
+                writer_id: EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_WRITER,
+                writer_sn: SequenceNumber_t::from(writer_sn),
+                inline_qos: None,
+                serialized_payload: Some(SerializedPayload::from(Bytes::from_static(payload))),
+            },
+            SubmessageFlag {
+                flags: 0x01 | Data::DATA_FLAG,
+            },
+        )
+    }
+
+    fn heartbeat() -> EntitySubmessage {
+        EntitySubmessage::Heartbeat(
+            Heartbeat {
+                reader_id: EntityId_t::ENTITYID_UNKNOWN,
+                writer_id: EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_WRITER,
+                first_sn: SequenceNumber_t::from(1),
+                last_sn: SequenceNumber_t::from(2),
+                count: Count_t::from(1),
+            },
+            SubmessageFlag { flags: 0x00 },
+        )
+    }
+
+    fn decode(message: &Bytes) -> (Vec<EntitySubmessage>, MessageReceiver) {
+        let mut message_receiver = MessageReceiver::new(LocatorKind_t::LOCATOR_KIND_UDPv4);
+        let mut bytes = BytesMut::from(&message[..]);
+        let mut submessages = vec![];
+        while !bytes.is_empty() {
+            if let Some(submessage) = message_receiver.decode(&mut bytes).unwrap() {
+                submessages.push(submessage);
+            }
+        }
+        (submessages, message_receiver)
+    }
+
+    #[test]
+    fn encoded_message_is_decoded_by_message_receiver() {
+        let message_sender = MessageSender::new(SOURCE_GUID_PREFIX, VendorId_t::VENDOR_UNKNOWN);
+
+        let messages = message_sender.encode(DEST_GUID_PREFIX, vec![data(1, b"abcd"), heartbeat()]);
+
+        assert_eq!(1, messages.len());
+        let (submessages, message_receiver) = decode(&messages[0]);
+        assert_eq!(vec![data(1, b"abcd"), heartbeat()], submessages);
+        assert_eq!(
+            SOURCE_GUID_PREFIX,
+            message_receiver.receiver().source_guid_prefix
+        );
+        assert_eq!(
+            DEST_GUID_PREFIX,
+            message_receiver.receiver().dest_guid_prefix
+        );
+    }
+
+    #[test]
+    fn submessages_are_split_into_messages_not_exceeding_max_size() {
+        let mut message_sender = MessageSender::new(SOURCE_GUID_PREFIX, VendorId_t::VENDOR_UNKNOWN);
+        message_sender.set_max_message_size(80);
+
+        let messages = message_sender.encode(
+            DEST_GUID_PREFIX,
+            vec![data(1, b"abcd"), data(2, b"efgh"), data(3, b"ijkl")],
+        );
+
+        assert_eq!(3, messages.len());
+        for (message, writer_sn) in messages.iter().zip(1..) {
+            assert!(message.len() <= 80);
+            let (submessages, _) = decode(message);
+            assert_eq!(1, submessages.len());
+            match &submessages[0] {
+                EntitySubmessage::Data(data, _) => {
+                    assert_eq!(SequenceNumber_t::from(writer_sn), data.writer_sn)
+                }
+                other => panic!("Unexpected submessage {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn submessage_bigger_than_max_message_size_is_dropped() {
+        let mut message_sender = MessageSender::new(SOURCE_GUID_PREFIX, VendorId_t::VENDOR_UNKNOWN);
+        message_sender.set_max_message_size(80);
+
+        let messages =
+            message_sender.encode(DEST_GUID_PREFIX, vec![data(1, &[0x00; 100]), heartbeat()]);
+
+        assert_eq!(1, messages.len());
+        assert_eq!(vec![heartbeat()], decode(&messages[0]).0);
+    }
+
+    #[test]
+    fn info_timestamp_is_put_into_message_of_following_submessage() {
+        let mut message_sender = MessageSender::new(SOURCE_GUID_PREFIX, VendorId_t::VENDOR_UNKNOWN);
+        message_sender.set_max_message_size(80);
+        let timestamp = Time_t::from(SystemTime::UNIX_EPOCH + Duration::from_secs(1));
+        let info_timestamp = EntitySubmessage::InfoTimestamp(
+            InfoTimestamp {
+                timestamp: Some(timestamp),
+            },
+            SubmessageFlag { flags: 0x01 },
+        );
+
+        let messages = message_sender.encode(
+            DEST_GUID_PREFIX,
+            vec![data(1, b"abcd"), info_timestamp, data(2, b"efgh")],
+        );
+
+        assert_eq!(2, messages.len());
+        let (submessages, message_receiver) = decode(&messages[1]);
+        assert_eq!(vec![data(2, b"efgh")], submessages);
+        assert!(message_receiver.receiver().have_timestamp);
+        assert_eq!(timestamp, message_receiver.receiver().timestamp);
+    }
+}
@@ -0,0 +1,90 @@
+use crate::structure::change_kind::ChangeKind_t;
+use bitflags::bitflags;
+use speedy::{Context, Readable, Reader, Writable, Writer};
+
+bitflags! {
+    /// The change of the lifecycle of an instance (9.6.3.9), sent as the
+    /// PID_STATUS_INFO inline QoS of the Data Submessages of the changes
+    /// which are not ALIVE. The flags are in the last of its four octets,
+    /// regardless of the endianness of the Submessage.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    pub struct StatusInfo_t: u32 {
+        const DISPOSED = 1 << 0;
+        const UNREGISTERED = 1 << 1;
+        const FILTERED = 1 << 2;
+    }
+}
+
+impl StatusInfo_t {
+    /// The kind of the change; the FILTERED changes are ALIVE.
+    pub fn change_kind(self) -> ChangeKind_t {
+        let disposed = self.contains(StatusInfo_t::DISPOSED);
+        let unregistered = self.contains(StatusInfo_t::UNREGISTERED);
+        match (disposed, unregistered) {
+            (false, false) => ChangeKind_t::ALIVE,
+            (true, false) => ChangeKind_t::NOT_ALIVE_DISPOSED,
+            (false, true) => ChangeKind_t::NOT_ALIVE_UNREGISTERED,
+            (true, true) => ChangeKind_t::NOT_ALIVE_DISPOSED_UNREGISTERED,
+        }
+    }
+}
+
+impl From<ChangeKind_t> for StatusInfo_t {
+    fn from(kind: ChangeKind_t) -> Self {
+        match kind {
+            ChangeKind_t::ALIVE => StatusInfo_t::empty(),
+            ChangeKind_t::NOT_ALIVE_DISPOSED => StatusInfo_t::DISPOSED,
+            ChangeKind_t::NOT_ALIVE_UNREGISTERED => StatusInfo_t::UNREGISTERED,
+            ChangeKind_t::NOT_ALIVE_DISPOSED_UNREGISTERED => {
+                StatusInfo_t::DISPOSED | StatusInfo_t::UNREGISTERED
+            }
+        }
+    }
+}
+
+impl<'a, C: Context> Readable<'a, C> for StatusInfo_t {
+    #[inline]
+    fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+        let mut octets = [0; 4];
+        reader.read_bytes(&mut octets)?;
+        Ok(StatusInfo_t::from_bits_retain(u32::from_be_bytes(octets)))
+    }
+
+    #[inline]
+    fn minimum_bytes_needed() -> usize {
+        4
+    }
+}
+
+impl<C: Context> Writable<C> for StatusInfo_t {
+    #[inline]
+    fn write_to<T: ?Sized + Writer<C>>(&self, writer: &mut T) -> Result<(), C::Error> {
+        writer.write_bytes(&self.bits().to_be_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    serialization_test!( type = StatusInfo_t,
+    {
+        status_info_disposed_unregistered,
+        StatusInfo_t::DISPOSED | StatusInfo_t::UNREGISTERED,
+        le = [0x00, 0x00, 0x00, 0x03],
+        be = [0x00, 0x00, 0x00, 0x03]
+    });
+
+    #[test]
+    fn status_info_is_converted_from_and_to_change_kind() {
+        for kind in &[
+            ChangeKind_t::ALIVE,
+            ChangeKind_t::NOT_ALIVE_DISPOSED,
+            ChangeKind_t::NOT_ALIVE_UNREGISTERED,
+            ChangeKind_t::NOT_ALIVE_DISPOSED_UNREGISTERED,
+        ] {
+            assert_eq!(*kind, StatusInfo_t::from(*kind).change_kind());
+        }
+        assert_eq!(ChangeKind_t::ALIVE, StatusInfo_t::FILTERED.change_kind());
+    }
+}
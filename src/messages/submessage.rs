@@ -10,6 +10,10 @@ use crate::messages::info_source::InfoSource;
 use crate::messages::info_timestamp::InfoTimestamp;
 use crate::messages::nack_frag::NackFrag;
 use crate::messages::submessage_flag::SubmessageFlag;
+use crate::messages::submessage_header::SubmessageHeader;
+use crate::messages::submessage_kind::SubmessageKind;
+use speedy::Writable;
+use std::convert::TryFrom;
 
 #[derive(Debug, PartialEq)]
 pub enum EntitySubmessage {
@@ -20,6 +24,76 @@ pub enum EntitySubmessage {
     Heartbeat(Heartbeat, SubmessageFlag),
     HeartbeatFrag(HeartbeatFrag),
     NackFrag(NackFrag),
+
+    /// Sets the source timestamp of the Submessages following it in the
+    /// Message; put by the Writers ahead of the changes with a source
+    /// timestamp.
+    InfoTimestamp(InfoTimestamp, SubmessageFlag),
+}
+
+impl EntitySubmessage {
+    /// Flags of the Submessages which does not carry own flags. These
+    /// Submessages are always encoded in little endian.
+    const LITTLE_ENDIAN: SubmessageFlag = SubmessageFlag { flags: 0x01 };
+
+    pub fn submessage_kind(&self) -> SubmessageKind {
+        match self {
+            EntitySubmessage::AckNack(..) => SubmessageKind::ACKNACK,
+            EntitySubmessage::Data(..) => SubmessageKind::DATA,
+            EntitySubmessage::DataFrag(..) => SubmessageKind::DATA_FRAG,
+            EntitySubmessage::Gap(..) => SubmessageKind::GAP,
+            EntitySubmessage::Heartbeat(..) => SubmessageKind::HEARTBEAT,
+            EntitySubmessage::HeartbeatFrag(..) => SubmessageKind::HEARTBEAT_FRAG,
+            EntitySubmessage::NackFrag(..) => SubmessageKind::NACK_FRAG,
+            EntitySubmessage::InfoTimestamp(..) => SubmessageKind::INFO_TS,
+        }
+    }
+
+    pub fn flags(&self) -> SubmessageFlag {
+        match self {
+            EntitySubmessage::AckNack(_, flags)
+            | EntitySubmessage::Data(_, flags)
+            | EntitySubmessage::DataFrag(_, flags)
+            | EntitySubmessage::Heartbeat(_, flags)
+            | EntitySubmessage::InfoTimestamp(_, flags) => *flags,
+            EntitySubmessage::Gap(..)
+            | EntitySubmessage::HeartbeatFrag(..)
+            | EntitySubmessage::NackFrag(..) => EntitySubmessage::LITTLE_ENDIAN,
+        }
+    }
+
+    /// Serializes the Submessage preceded by its SubmessageHeader.
+    pub fn write_to_vec(&self) -> Result<Vec<u8>, speedy::Error> {
+        let flags = self.flags();
+        let endianness = flags.endianness_flag();
+        let content = match self {
+            EntitySubmessage::AckNack(ack_nack, _) => ack_nack.write_to_vec_with_ctx(endianness),
+            EntitySubmessage::Data(data, _) => data.write_to_vec_with_ctx(endianness),
+            EntitySubmessage::DataFrag(data_frag, _) => data_frag.write_to_vec_with_ctx(endianness),
+            EntitySubmessage::Gap(gap) => gap.write_to_vec_with_ctx(endianness),
+            EntitySubmessage::Heartbeat(heartbeat, _) => {
+                heartbeat.write_to_vec_with_ctx(endianness)
+            }
+            EntitySubmessage::HeartbeatFrag(heartbeat_frag) => {
+                heartbeat_frag.write_to_vec_with_ctx(endianness)
+            }
+            EntitySubmessage::NackFrag(nack_frag) => nack_frag.write_to_vec_with_ctx(endianness),
+            EntitySubmessage::InfoTimestamp(info_timestamp, _) => {
+                info_timestamp.write_to_vec_with_ctx(endianness)
+            }
+        }?;
+
+        let submessage_header = SubmessageHeader {
+            submessage_id: self.submessage_kind(),
+            flags,
+            submessage_length: u16::try_from(content.len())
+                .map_err(|_| speedy::Error::custom("Submessage too long".to_owned()))?,
+        };
+
+        let mut bytes = submessage_header.write_to_vec_with_ctx(endianness)?;
+        bytes.extend(content);
+        Ok(bytes)
+    }
 }
 
 #[derive(Debug, PartialEq)]
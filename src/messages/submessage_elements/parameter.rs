@@ -4,10 +4,19 @@ use speedy::{Context, Readable, Reader, Writable, Writer};
 #[derive(Debug, PartialEq)]
 pub struct Parameter {
     /// Uniquely identifies the type of parameter
-    parameter_id: ParameterId,
+    pub parameter_id: ParameterId,
     /// Contains the CDR encapsulation of the Parameter type
     /// that corresponds to the specified parameterId
-    value: Vec<u8>,
+    pub value: Vec<u8>,
+}
+
+impl Parameter {
+    pub fn new(parameter_id: ParameterId, value: Vec<u8>) -> Self {
+        Parameter {
+            parameter_id,
+            value,
+        }
+    }
 }
 
 impl<'a, C: Context> Readable<'a, C> for Parameter {
@@ -15,18 +24,11 @@ impl<'a, C: Context> Readable<'a, C> for Parameter {
     fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
         let parameter_id: ParameterId = reader.read_value()?;
         let length = reader.read_u16()?;
-        let alignment = length % 4;
-
-        let mut value = Vec::with_capacity((length + alignment) as usize);
-
-        for _ in 0..(length + alignment) {
-            let byte = reader.read_u8()?;
-            value.push(byte);
-        }
+        let value = reader.read_vec(length as usize)?;
 
         Ok(Parameter {
-            parameter_id: parameter_id,
-            value: value,
+            parameter_id,
+            value,
         })
     }
 
@@ -41,8 +43,9 @@ impl<C: Context> Writable<C> for Parameter {
     fn write_to<T: ?Sized + Writer<C>>(&self, writer: &mut T) -> Result<(), C::Error> {
         writer.write_value(&self.parameter_id)?;
 
+        // The length of the value is always padded to a multiple of 4
         let length = self.value.len();
-        let alignment = length % 4;
+        let alignment = (4 - length % 4) % 4;
         writer.write_u16((length + alignment) as u16)?;
 
         for byte in &self.value {
@@ -117,5 +120,37 @@ mod tests {
         be = [0x00, 0x02, 0x00, 0x08,
               0xFF, 0xFF, 0xFF, 0x7F,
               0xFF, 0xFF, 0xFF, 0xFF]
+    },
+    {
+        pid_topic_name_aligned_to_4_bytes,
+        Parameter {
+            parameter_id: ParameterId::PID_TOPIC_NAME,
+            value: vec![0x03, 0x00, 0x00, 0x00,
+                        0x61, 0x62, 0x00, 0x00],
+        },
+        le = [0x05, 0x00, 0x08, 0x00,
+              0x03, 0x00, 0x00, 0x00,
+              0x61, 0x62, 0x00, 0x00],
+        be = [0x00, 0x05, 0x00, 0x08,
+              0x03, 0x00, 0x00, 0x00,
+              0x61, 0x62, 0x00, 0x00]
     });
+
+    #[test]
+    fn value_is_padded_to_multiple_of_4_bytes() {
+        use speedy::Endianness;
+
+        let parameter = Parameter::new(
+            ParameterId::PID_TOPIC_NAME,
+            vec![0x01, 0x02, 0x03, 0x04, 0x05],
+        );
+        let serialized = parameter
+            .write_to_vec_with_ctx(Endianness::LittleEndian)
+            .unwrap();
+
+        assert_eq!(
+            serialized,
+            vec![0x05, 0x00, 0x08, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x00, 0x00, 0x00]
+        );
+    }
 }
@@ -1,12 +1,14 @@
 use crate::messages::submessage_elements::parameter::Parameter;
+use crate::structure::parameter_id::ParameterId;
+use speedy::{Context, Readable, Reader, Writable, Writer};
 
 /// ParameterList is used as part of several messages to encapsulate
 /// QoS parameters that may affect the interpretation of the message.
 /// The encapsulation of the parameters follows a mechanism that allows
 /// extensions to the QoS without breaking backwards compatibility.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Default, PartialEq)]
 pub struct ParameterList {
-    parameters: Vec<Parameter>,
+    pub parameters: Vec<Parameter>,
 }
 
 /// The PID_PAD is used to enforce alignment of the parameter
@@ -17,3 +19,146 @@ pub const PID_PAD: u16 = 0x00;
 /// The PID_SENTINEL is used to terminate
 /// the parameter list and its length is ignore
 pub const PID_SENTINEL: u16 = 0x01;
+
+impl ParameterList {
+    pub fn new() -> Self {
+        ParameterList::default()
+    }
+
+    pub fn push(&mut self, parameter: Parameter) {
+        self.parameters.push(parameter)
+    }
+
+    /// Removes the vendor-specific parameters, e.g. of the vendors other than
+    /// the one of the receiver.
+    pub fn remove_vendor_specific(&mut self) {
+        self.parameters
+            .retain(|parameter| !parameter.parameter_id.is_vendor_specific())
+    }
+
+    /// Returns the first parameter with the given id
+    pub fn find(&self, parameter_id: &ParameterId) -> Option<&Parameter> {
+        self.parameters
+            .iter()
+            .find(|parameter| &parameter.parameter_id == parameter_id)
+    }
+}
+
+impl<'a, C: Context> Readable<'a, C> for ParameterList {
+    #[inline]
+    fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+        let mut parameters = vec![];
+        loop {
+            let parameter: Parameter = reader.read_value()?;
+            if parameter.parameter_id == ParameterId::PID_SENTINEL {
+                return Ok(ParameterList { parameters });
+            }
+            if parameter.parameter_id != ParameterId::PID_PAD {
+                parameters.push(parameter);
+            }
+        }
+    }
+
+    #[inline]
+    fn minimum_bytes_needed() -> usize {
+        4
+    }
+}
+
+impl<C: Context> Writable<C> for ParameterList {
+    #[inline]
+    fn write_to<T: ?Sized + Writer<C>>(&self, writer: &mut T) -> Result<(), C::Error> {
+        for parameter in &self.parameters {
+            writer.write_value(parameter)?;
+        }
+        writer.write_value(&ParameterId::PID_SENTINEL)?;
+        writer.write_u16(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pad_parameters_are_skipped() {
+        use speedy::Endianness;
+
+        let serialized = [
+            0x00, 0x00, 0x04, 0x00, // PID_PAD
+            0x00, 0x00, 0x00, 0x00, //
+            0x15, 0x00, 0x04, 0x00, // PID_PROTOCOL_VERSION
+            0x02, 0x04, 0x00, 0x00, //
+            0x01, 0x00, 0x00, 0x00, // PID_SENTINEL
+        ];
+
+        let parameter_list: ParameterList =
+            Readable::read_from_buffer_with_ctx(Endianness::LittleEndian, &serialized).unwrap();
+
+        assert_eq!(
+            parameter_list.parameters,
+            vec![Parameter::new(
+                ParameterId::PID_PROTOCOL_VERSION,
+                vec![0x02, 0x04, 0x00, 0x00]
+            )]
+        );
+    }
+
+    #[test]
+    fn vendor_specific_parameters_are_removed() {
+        use speedy::Endianness;
+
+        let serialized = [
+            0x01, 0x80, 0x04, 0x00, // vendor-specific 0x8001
+            0x2A, 0x00, 0x00, 0x00, //
+            0x71, 0x00, 0x04, 0x00, // PID_STATUS_INFO
+            0x00, 0x00, 0x00, 0x01, //
+            0x01, 0x00, 0x00, 0x00, // PID_SENTINEL
+        ];
+        let mut parameter_list: ParameterList =
+            Readable::read_from_buffer_with_ctx(Endianness::LittleEndian, &serialized).unwrap();
+
+        parameter_list.remove_vendor_specific();
+
+        assert_eq!(
+            parameter_list.parameters,
+            vec![Parameter::new(
+                ParameterId::PID_STATUS_INFO,
+                vec![0x00, 0x00, 0x00, 0x01]
+            )]
+        );
+    }
+
+    serialization_test!( type = ParameterList,
+    {
+        parameter_list_empty,
+        ParameterList::new(),
+        le = [0x01, 0x00, 0x00, 0x00],
+        be = [0x00, 0x01, 0x00, 0x00]
+    },
+    {
+        parameter_list_with_key_hash_and_status_info,
+        ParameterList {
+            parameters: vec![
+                Parameter::new(ParameterId::PID_KEY_HASH, vec![0x0F; 16]),
+                Parameter::new(ParameterId::PID_STATUS_INFO, vec![0x00, 0x00, 0x00, 0x01]),
+            ]
+        },
+        le = [0x70, 0x00, 0x10, 0x00,
+              0x0F, 0x0F, 0x0F, 0x0F,
+              0x0F, 0x0F, 0x0F, 0x0F,
+              0x0F, 0x0F, 0x0F, 0x0F,
+              0x0F, 0x0F, 0x0F, 0x0F,
+              0x71, 0x00, 0x04, 0x00,
+              0x00, 0x00, 0x00, 0x01,
+              0x01, 0x00, 0x00, 0x00],
+        be = [0x00, 0x70, 0x00, 0x10,
+              0x0F, 0x0F, 0x0F, 0x0F,
+              0x0F, 0x0F, 0x0F, 0x0F,
+              0x0F, 0x0F, 0x0F, 0x0F,
+              0x0F, 0x0F, 0x0F, 0x0F,
+              0x00, 0x71, 0x00, 0x04,
+              0x00, 0x00, 0x00, 0x01,
+              0x00, 0x01, 0x00, 0x00]
+    });
+}
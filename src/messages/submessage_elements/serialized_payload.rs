@@ -1,7 +1,25 @@
+use bytes::Bytes;
+use speedy::{Context, Writable, Writer};
+
 /// A SerializedPayload contains the serialized representation of
 /// either value of an application-defined data-object or
 /// the value of the key that uniquely identifies the data-object
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct SerializedPayload {
-    pub value: Vec<u8>,
+    pub value: Bytes,
+}
+
+impl From<Bytes> for SerializedPayload {
+    fn from(value: Bytes) -> Self {
+        SerializedPayload { value }
+    }
+}
+
+/// The SerializedPayload always occupies the remainder of the Submessage,
+/// so it is written without any length prefix.
+impl<C: Context> Writable<C> for SerializedPayload {
+    #[inline]
+    fn write_to<T: ?Sized + Writer<C>>(&self, writer: &mut T) -> Result<(), C::Error> {
+        writer.write_bytes(&self.value)
+    }
 }
@@ -1,14 +1,151 @@
 use speedy::{Context, Readable, Reader, Writable, Writer};
+use std::fmt;
+use std::str::FromStr;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct VendorId_t {
     pub vendor_id: [u8; 2],
 }
 
+/// The vendor ids assigned by the OMG together with the names of their
+/// products.
+const ASSIGNED_VENDOR_IDS: [(VendorId_t, &str); 20] = [
+    (VendorId_t::VENDOR_RTI_CONNEXT, "RTI Connext DDS"),
+    (
+        VendorId_t::VENDOR_ADLINK_OPENSPLICE,
+        "ADLINK OpenSplice DDS",
+    ),
+    (VendorId_t::VENDOR_OCI_OPENDDS, "OCI OpenDDS"),
+    (VendorId_t::VENDOR_MILSOFT_MILDDS, "MilSoft Mil-DDS"),
+    (
+        VendorId_t::VENDOR_KONGSBERG_INTERCOM,
+        "Kongsberg InterCOM DDS",
+    ),
+    (VendorId_t::VENDOR_TWINOAKS_COREDX, "Twin Oaks CoreDX DDS"),
+    (VendorId_t::VENDOR_LAKOTA, "Lakota Technical Solutions"),
+    (VendorId_t::VENDOR_ICOUP, "ICOUP Consulting"),
+    (VendorId_t::VENDOR_ETRI_DIAMOND, "ETRI Diamond DDS"),
+    (
+        VendorId_t::VENDOR_RTI_CONNEXT_MICRO,
+        "RTI Connext DDS Micro",
+    ),
+    (VendorId_t::VENDOR_ADLINK_VORTEX_CAFE, "ADLINK Vortex Cafe"),
+    (VendorId_t::VENDOR_PRISMTECH, "PrismTech"),
+    (VendorId_t::VENDOR_ADLINK_VORTEX_LITE, "ADLINK Vortex Lite"),
+    (VendorId_t::VENDOR_TECHNICOLOR_QEO, "Technicolor Qeo"),
+    (VendorId_t::VENDOR_EPROSIMA_FAST_DDS, "eProsima Fast DDS"),
+    (VendorId_t::VENDOR_ECLIPSE_CYCLONE, "Eclipse Cyclone DDS"),
+    (VendorId_t::VENDOR_GURUM_GURUMDDS, "GurumNetworks GurumDDS"),
+    (VendorId_t::VENDOR_ATOSTEK_RUSTDDS, "Atostek RustDDS"),
+    (VendorId_t::VENDOR_ZRDDS, "Nanjing Zhenrong ZRDDS"),
+    (VendorId_t::VENDOR_DUST_DDS, "S2E Software Systems Dust DDS"),
+];
+
 impl VendorId_t {
     pub const VENDOR_UNKNOWN: VendorId_t = VendorId_t {
         vendor_id: [0x00; 2],
     };
+    pub const VENDOR_RTI_CONNEXT: VendorId_t = VendorId_t {
+        vendor_id: [0x01, 0x01],
+    };
+    pub const VENDOR_ADLINK_OPENSPLICE: VendorId_t = VendorId_t {
+        vendor_id: [0x01, 0x02],
+    };
+    pub const VENDOR_OCI_OPENDDS: VendorId_t = VendorId_t {
+        vendor_id: [0x01, 0x03],
+    };
+    pub const VENDOR_MILSOFT_MILDDS: VendorId_t = VendorId_t {
+        vendor_id: [0x01, 0x04],
+    };
+    pub const VENDOR_KONGSBERG_INTERCOM: VendorId_t = VendorId_t {
+        vendor_id: [0x01, 0x05],
+    };
+    pub const VENDOR_TWINOAKS_COREDX: VendorId_t = VendorId_t {
+        vendor_id: [0x01, 0x06],
+    };
+    pub const VENDOR_LAKOTA: VendorId_t = VendorId_t {
+        vendor_id: [0x01, 0x07],
+    };
+    pub const VENDOR_ICOUP: VendorId_t = VendorId_t {
+        vendor_id: [0x01, 0x08],
+    };
+    pub const VENDOR_ETRI_DIAMOND: VendorId_t = VendorId_t {
+        vendor_id: [0x01, 0x09],
+    };
+    pub const VENDOR_RTI_CONNEXT_MICRO: VendorId_t = VendorId_t {
+        vendor_id: [0x01, 0x0A],
+    };
+    pub const VENDOR_ADLINK_VORTEX_CAFE: VendorId_t = VendorId_t {
+        vendor_id: [0x01, 0x0B],
+    };
+    pub const VENDOR_PRISMTECH: VendorId_t = VendorId_t {
+        vendor_id: [0x01, 0x0C],
+    };
+    pub const VENDOR_ADLINK_VORTEX_LITE: VendorId_t = VendorId_t {
+        vendor_id: [0x01, 0x0D],
+    };
+    pub const VENDOR_TECHNICOLOR_QEO: VendorId_t = VendorId_t {
+        vendor_id: [0x01, 0x0E],
+    };
+    pub const VENDOR_EPROSIMA_FAST_DDS: VendorId_t = VendorId_t {
+        vendor_id: [0x01, 0x0F],
+    };
+    pub const VENDOR_ECLIPSE_CYCLONE: VendorId_t = VendorId_t {
+        vendor_id: [0x01, 0x10],
+    };
+    pub const VENDOR_GURUM_GURUMDDS: VendorId_t = VendorId_t {
+        vendor_id: [0x01, 0x11],
+    };
+    pub const VENDOR_ATOSTEK_RUSTDDS: VendorId_t = VendorId_t {
+        vendor_id: [0x01, 0x12],
+    };
+    pub const VENDOR_ZRDDS: VendorId_t = VendorId_t {
+        vendor_id: [0x01, 0x13],
+    };
+    pub const VENDOR_DUST_DDS: VendorId_t = VendorId_t {
+        vendor_id: [0x01, 0x14],
+    };
+
+    /// The name of the product of the vendor, None for the vendor ids not
+    /// assigned by the OMG.
+    pub fn name(&self) -> Option<&'static str> {
+        ASSIGNED_VENDOR_IDS
+            .iter()
+            .find(|(vendor_id, _)| vendor_id == self)
+            .map(|(_, name)| *name)
+    }
+}
+
+/// Formats the vendor id as in the OMG registry, e.g. "01.0F", followed by
+/// the name of the product when known.
+impl fmt::Display for VendorId_t {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02X}.{:02X}", self.vendor_id[0], self.vendor_id[1])?;
+        match self.name() {
+            Some(name) => write!(f, " ({})", name),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Parses the configured vendor id written as in the OMG registry, e.g.
+/// "01.0F", or as the hexadecimal number, e.g. "0x010F".
+impl FromStr for VendorId_t {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let vendor_id = match s.split_once('.') {
+            Some((first, second)) => [
+                u8::from_str_radix(first, 16)?,
+                u8::from_str_radix(second, 16)?,
+            ],
+            None => {
+                let digits = s.strip_prefix("0x").unwrap_or(s);
+                u16::from_str_radix(digits, 16)?.to_be_bytes()
+            }
+        };
+        Ok(VendorId_t { vendor_id })
+    }
 }
 
 impl From<[u8; 2]> for VendorId_t {
@@ -56,6 +193,47 @@ mod tests {
     use super::*;
     use speedy::Endianness;
 
+    #[test]
+    fn assigned_vendor_ids_have_names() {
+        assert_eq!(
+            Some("eProsima Fast DDS"),
+            VendorId_t::VENDOR_EPROSIMA_FAST_DDS.name()
+        );
+        assert_eq!(
+            Some("Eclipse Cyclone DDS"),
+            VendorId_t::from([0x01, 0x10]).name()
+        );
+        assert_eq!(None, VendorId_t::VENDOR_UNKNOWN.name());
+        assert_eq!(None, VendorId_t::from([0x7F, 0xFF]).name());
+    }
+
+    #[test]
+    fn vendor_id_is_displayed_with_name() {
+        assert_eq!(
+            "01.01 (RTI Connext DDS)",
+            VendorId_t::VENDOR_RTI_CONNEXT.to_string()
+        );
+        assert_eq!("7F.FF", VendorId_t::from([0x7F, 0xFF]).to_string());
+    }
+
+    #[test]
+    fn vendor_id_is_parsed_from_configuration() {
+        assert_eq!(
+            Ok(VendorId_t::VENDOR_OCI_OPENDDS),
+            "01.03".parse::<VendorId_t>()
+        );
+        assert_eq!(
+            Ok(VendorId_t::VENDOR_ECLIPSE_CYCLONE),
+            "0x0110".parse::<VendorId_t>()
+        );
+        assert_eq!(
+            Ok(VendorId_t::from([0xAB, 0xCD])),
+            "abcd".parse::<VendorId_t>()
+        );
+        assert!("01.0G".parse::<VendorId_t>().is_err());
+        assert!("0x10000".parse::<VendorId_t>().is_err());
+    }
+
     #[test]
     fn minimum_bytes_needed() {
         assert_eq!(
@@ -70,5 +248,11 @@ mod tests {
         VendorId_t::VENDOR_UNKNOWN,
         le = [0x00, 0x00],
         be = [0x00, 0x00]
+    },
+    {
+        vendor_eprosima_fast_dds,
+        VendorId_t::VENDOR_EPROSIMA_FAST_DDS,
+        le = [0x01, 0x0F],
+        be = [0x01, 0x0F]
     });
 }
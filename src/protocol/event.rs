@@ -0,0 +1,106 @@
+use crate::dds::qos::qos_policy_id::QosPolicyId_t;
+use crate::discovery::discovered_reader_data::DiscoveredReaderData;
+use crate::discovery::discovered_writer_data::DiscoveredWriterData;
+use crate::discovery::spdp_discovered_participant_data::SpdpDiscoveredParticipantData;
+use crate::structure::guid::GUID_t;
+use crate::structure::guid_prefix::GuidPrefix_t;
+
+/// Notifications produced by the ProtocolCore for the application.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    /// A new change was added to the reader_cache of the local Reader.
+    DataAvailable { reader_guid: GUID_t },
+
+    /// Changes of the Writer matched with the local Reader were lost: they
+    /// are no longer available in the Writer or were skipped by the
+    /// best-effort Reader.
+    SampleLost {
+        reader_guid: GUID_t,
+        writer_guid: GUID_t,
+        count: usize,
+    },
+
+    /// A remote Participant announced itself for the first time.
+    ParticipantDiscovered {
+        participant_data: SpdpDiscoveredParticipantData,
+    },
+
+    /// The lease of the remote Participant expired without a new
+    /// announcement.
+    ParticipantLost { guid_prefix: GuidPrefix_t },
+
+    /// A remote Writer was announced for the first time or with a changed
+    /// data.
+    PublicationDiscovered { writer_data: DiscoveredWriterData },
+
+    /// The Participant of the remote Writer was lost.
+    PublicationLost { writer_guid: GUID_t },
+
+    /// A remote Reader was announced for the first time or with a changed
+    /// data.
+    SubscriptionDiscovered { reader_data: DiscoveredReaderData },
+
+    /// The Participant of the remote Reader was lost.
+    SubscriptionLost { reader_guid: GUID_t },
+
+    /// The local Writer was matched with the Reader.
+    PublicationMatched {
+        writer_guid: GUID_t,
+        reader_guid: GUID_t,
+    },
+
+    /// The local Writer is no longer matched with the Reader.
+    PublicationUnmatched {
+        writer_guid: GUID_t,
+        reader_guid: GUID_t,
+    },
+
+    /// The local Reader was matched with the Writer.
+    SubscriptionMatched {
+        reader_guid: GUID_t,
+        writer_guid: GUID_t,
+    },
+
+    /// The local Reader is no longer matched with the Writer.
+    SubscriptionUnmatched {
+        reader_guid: GUID_t,
+        writer_guid: GUID_t,
+    },
+
+    /// The local Writer offers a QoS incompatible with the one requested by
+    /// the Reader of its Topic; policy_id names one of the incompatible
+    /// policies.
+    OfferedIncompatibleQos {
+        writer_guid: GUID_t,
+        reader_guid: GUID_t,
+        policy_id: QosPolicyId_t,
+    },
+
+    /// The local Reader requests a QoS incompatible with the one offered by
+    /// the Writer of its Topic; policy_id names one of the incompatible
+    /// policies.
+    RequestedIncompatibleQos {
+        reader_guid: GUID_t,
+        writer_guid: GUID_t,
+        policy_id: QosPolicyId_t,
+    },
+
+    /// A Heartbeat with the LivelinessFlag was received from the Writer
+    /// matched with a local Reader, i.e. the Writer asserted its liveliness
+    /// manually.
+    LivelinessAsserted { writer_guid: GUID_t },
+
+    /// The Writer matched with the local Reader became alive, by being
+    /// matched or by asserting its liveliness after its lease expired, or it
+    /// is no longer alive, because its lease expired or because it is no
+    /// longer matched.
+    LivelinessChanged {
+        reader_guid: GUID_t,
+        writer_guid: GUID_t,
+        alive: bool,
+    },
+
+    /// The local Writer did not assert its liveliness within the
+    /// lease_duration of its LIVELINESS QoS.
+    LivelinessLost { writer_guid: GUID_t },
+}
@@ -0,0 +1,3 @@
+pub mod event;
+pub mod protocol_core;
+pub mod transmit;
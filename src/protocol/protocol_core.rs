@@ -0,0 +1,1050 @@
+use crate::behavior::stateful_reader::StatefulReader;
+use crate::behavior::stateful_writer::StatefulWriter;
+use crate::behavior::stateless_reader::StatelessReader;
+use crate::behavior::stateless_writer::StatelessWriter;
+use crate::behavior::writer::Writer;
+use crate::messages::heartbeat::Heartbeat;
+use crate::messages::received_submessage::ReceivedSubmessage;
+#[cfg(test)]
+use crate::messages::receiver::MessageReceiver;
+use crate::messages::sender::MessageSender;
+use crate::messages::submessage::EntitySubmessage;
+use crate::messages::vendor_id::VendorId_t;
+use crate::protocol::event::Event;
+use crate::protocol::transmit::Transmit;
+use crate::structure::endpoint::Endpoint;
+use crate::structure::entity::Entity;
+use crate::structure::entity_id::EntityId_t;
+use crate::structure::guid::GUID_t;
+use crate::structure::guid_prefix::GuidPrefix_t;
+use crate::structure::locator::{LocatorList_t, Locator_t};
+use crate::structure::reliability_kind::ReliabilityKind_t;
+use crate::structure::time::Time_t;
+
+#[cfg(test)]
+use bytes::Bytes;
+use log::warn;
+use std::collections::{BTreeMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// State of a remote Participant known from the discovery, updated by the
+/// messages received from it.
+struct RemoteParticipant {
+    /// The locator the last message from the Participant was received from.
+    /// Used to reply when the remote Endpoint has no known locators.
+    locator: Option<Locator_t>,
+    last_received: Option<Instant>,
+}
+
+/// The RTPS protocol state machines of a single Participant, free of any I/O.
+///
+/// The core is driven by the received datagrams (handle_datagram) and by the
+/// passage of time (handle_timeout); the current time is always provided by
+/// the caller. Messages which have to be sent are collected with
+/// poll_transmit and notifications for the application with poll_event.
+/// This allows running the protocol deterministically, e.g. in tests, or
+/// embedding it into any event loop.
+///
+/// Submessages exchanged by the Endpoints of the same Participant never
+/// leave the core; they are delivered in memory.
+pub struct ProtocolCore {
+    guid_prefix: GuidPrefix_t,
+
+    /// The vendor of the Participant; only the vendor-specific parameters
+    /// sent by the same vendor are interpreted.
+    vendor_id: VendorId_t,
+    message_sender: MessageSender,
+    writers: BTreeMap<EntityId_t, StatefulWriter>,
+    readers: BTreeMap<EntityId_t, StatefulReader>,
+    heartbeat_deadlines: BTreeMap<EntityId_t, Instant>,
+    stateless_writers: BTreeMap<EntityId_t, StatelessWriter>,
+    stateless_readers: BTreeMap<EntityId_t, StatelessReader>,
+    resend_deadlines: BTreeMap<EntityId_t, Instant>,
+    remote_participants: BTreeMap<GuidPrefix_t, RemoteParticipant>,
+
+    /// Decides whether the locator of a remote Endpoint can be reached by
+    /// the transports of the Participant.
+    locator_filter: Box<dyn Fn(&Locator_t) -> bool + Send>,
+
+    /// Submessages created by the local Endpoints together with their
+    /// destination, waiting to be put into messages.
+    outgoing: Vec<(LocatorList_t, GuidPrefix_t, EntitySubmessage)>,
+
+    /// Submessages exchanged between the Endpoints of this Participant. They
+    /// are handed over to the destination Endpoint directly, without
+    /// serialization; the serialized data of the changes is shared, not
+    /// copied.
+    intra_process: VecDeque<EntitySubmessage>,
+    transmits: VecDeque<Transmit>,
+    events: VecDeque<Event>,
+}
+
+impl ProtocolCore {
+    pub fn new(guid_prefix: GuidPrefix_t, vendor_id: VendorId_t) -> Self {
+        ProtocolCore {
+            guid_prefix,
+            vendor_id,
+            message_sender: MessageSender::new(guid_prefix, vendor_id),
+            writers: BTreeMap::new(),
+            readers: BTreeMap::new(),
+            heartbeat_deadlines: BTreeMap::new(),
+            stateless_writers: BTreeMap::new(),
+            stateless_readers: BTreeMap::new(),
+            resend_deadlines: BTreeMap::new(),
+            remote_participants: BTreeMap::new(),
+            locator_filter: Box::new(|_| true),
+            outgoing: vec![],
+            intra_process: VecDeque::new(),
+            transmits: VecDeque::new(),
+            events: VecDeque::new(),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn guid_prefix(&self) -> GuidPrefix_t {
+        self.guid_prefix
+    }
+
+    /// Limits the size of the messages returned by poll_transmit, e.g. to
+    /// the maximum size supported by the transport. The changes of the
+    /// Writers not fitting into a single message are fragmented.
+    pub fn set_max_message_size(&mut self, max_message_size: usize) {
+        self.message_sender.set_max_message_size(max_message_size);
+        for writer in self.writers.values_mut() {
+            writer.set_max_message_size(max_message_size);
+        }
+        for writer in self.stateless_writers.values_mut() {
+            writer.set_max_message_size(max_message_size);
+        }
+    }
+
+    pub fn max_message_size(&self) -> usize {
+        self.message_sender.max_message_size()
+    }
+
+    /// Restricts the locators used to reach the remote Endpoints. Messages
+    /// are sent to the first locator of the Endpoint accepted by the filter,
+    /// so Endpoints announcing several locators are reached through the
+    /// preferred transport supported by both sides.
+    pub fn set_locator_filter(
+        &mut self,
+        locator_filter: impl Fn(&Locator_t) -> bool + Send + 'static,
+    ) {
+        self.locator_filter = Box::new(locator_filter)
+    }
+
+    /// Adds the local Writer. The reliable Writer sends Heartbeats every
+    /// heartbeat_period, starting from now.
+    pub fn add_writer(&mut self, now: Instant, mut writer: StatefulWriter) {
+        writer.set_max_message_size(self.message_sender.max_message_size());
+        let entity_id = writer.as_entity().guid.entity_id;
+        if let Some(heartbeat_period) = heartbeat_period(&writer) {
+            self.heartbeat_deadlines
+                .insert(entity_id, now + heartbeat_period);
+        }
+        self.writers.insert(entity_id, writer);
+    }
+
+    pub fn add_reader(&mut self, reader: StatefulReader) {
+        self.readers
+            .insert(reader.as_entity().guid.entity_id, reader);
+    }
+
+    /// Removes the local Writer together with its timer.
+    pub fn remove_writer(&mut self, entity_id: EntityId_t) -> Option<StatefulWriter> {
+        self.heartbeat_deadlines.remove(&entity_id);
+        self.writers.remove(&entity_id)
+    }
+
+    pub fn remove_reader(&mut self, entity_id: EntityId_t) -> Option<StatefulReader> {
+        self.readers.remove(&entity_id)
+    }
+
+    /// Adds the local StatelessWriter. All its changes are re-sent to its
+    /// locators every resend_data_period, starting from now.
+    pub fn add_stateless_writer(&mut self, now: Instant, mut writer: StatelessWriter) {
+        writer.set_max_message_size(self.message_sender.max_message_size());
+        let entity_id = writer.as_entity().guid.entity_id;
+        if let Some(resend_data_period) = resend_data_period(&writer) {
+            self.resend_deadlines
+                .insert(entity_id, now + resend_data_period);
+        }
+        self.stateless_writers.insert(entity_id, writer);
+    }
+
+    pub fn add_stateless_reader(&mut self, reader: StatelessReader) {
+        self.stateless_readers
+            .insert(reader.as_entity().guid.entity_id, reader);
+    }
+
+    pub fn stateless_writer_mut(&mut self, entity_id: EntityId_t) -> Option<&mut StatelessWriter> {
+        self.stateless_writers.get_mut(&entity_id)
+    }
+
+    pub fn stateless_reader_mut(&mut self, entity_id: EntityId_t) -> Option<&mut StatelessReader> {
+        self.stateless_readers.get_mut(&entity_id)
+    }
+
+    pub fn writer_mut(&mut self, entity_id: EntityId_t) -> Option<&mut StatefulWriter> {
+        self.writers.get_mut(&entity_id)
+    }
+
+    pub fn reader(&self, entity_id: EntityId_t) -> Option<&StatefulReader> {
+        self.readers.get(&entity_id)
+    }
+
+    pub fn reader_mut(&mut self, entity_id: EntityId_t) -> Option<&mut StatefulReader> {
+        self.readers.get_mut(&entity_id)
+    }
+
+    /// Starts tracking the messages of the remote Participant, as soon as it
+    /// is discovered. The messages of the Participants not added are still
+    /// processed, but not tracked.
+    pub fn add_remote_participant(&mut self, guid_prefix: GuidPrefix_t) {
+        if guid_prefix == GuidPrefix_t::GUIDPREFIX_UNKNOWN || guid_prefix == self.guid_prefix {
+            return;
+        }
+        self.remote_participants
+            .entry(guid_prefix)
+            .or_insert(RemoteParticipant {
+                locator: None,
+                last_received: None,
+            });
+    }
+
+    /// Stops tracking the remote Participant, once it is lost.
+    pub fn remove_remote_participant(&mut self, guid_prefix: GuidPrefix_t) {
+        self.remote_participants.remove(&guid_prefix);
+    }
+
+    /// The last time a message was received from the added remote
+    /// Participant.
+    #[cfg(test)]
+    pub fn last_received(&self, guid_prefix: GuidPrefix_t) -> Option<Instant> {
+        self.remote_participants
+            .get(&guid_prefix)
+            .and_then(|remote_participant| remote_participant.last_received)
+    }
+
+    /// Processes the datagram received at now from the src locator.
+    /// Malformed messages are dropped; Submessages decoded before the
+    /// malformed part are processed.
+    #[cfg(test)]
+    pub fn handle_datagram(&mut self, now: Instant, src: Locator_t, datagram: Bytes) {
+        let submessages = MessageReceiver::decode_datagram(src.kind, datagram);
+        self.handle_submessages(now, src, submessages);
+    }
+
+    /// Processes the Submessages of the message received at now from the
+    /// src locator, already decoded by the MessageReceiver, e.g. of the
+    /// transport. The Submessages addressed to other Participants are
+    /// ignored.
+    pub fn handle_submessages(
+        &mut self,
+        now: Instant,
+        src: Locator_t,
+        submessages: Vec<ReceivedSubmessage>,
+    ) {
+        for received in submessages {
+            if received.dest_guid_prefix != GuidPrefix_t::GUIDPREFIX_UNKNOWN
+                && received.dest_guid_prefix != self.guid_prefix
+            {
+                continue;
+            }
+
+            if let Some(remote_participant) = self
+                .remote_participants
+                .get_mut(&received.source_guid_prefix)
+            {
+                remote_participant.locator = Some(src);
+                remote_participant.last_received = Some(now);
+            }
+            let mut submessage = received.submessage;
+            if received.source_vendor_id != self.vendor_id
+                || self.vendor_id == VendorId_t::VENDOR_UNKNOWN
+            {
+                remove_vendor_specific_inline_qos(&mut submessage);
+            }
+            self.handle_submessage(
+                received.source_guid_prefix,
+                received.source_timestamp,
+                submessage,
+            );
+        }
+    }
+
+    /// Processes all timers which expired before or at now.
+    pub fn handle_timeout(&mut self, now: Instant) {
+        let expired: Vec<EntityId_t> = self
+            .heartbeat_deadlines
+            .iter()
+            .filter(|(_, deadline)| **deadline <= now)
+            .map(|(entity_id, _)| *entity_id)
+            .collect();
+
+        for entity_id in expired {
+            let writer = match self.writers.get_mut(&entity_id) {
+                Some(writer) => writer,
+                None => continue,
+            };
+            if let Some(heartbeat_period) = heartbeat_period(writer) {
+                self.heartbeat_deadlines
+                    .insert(entity_id, now + heartbeat_period);
+            }
+            let heartbeats = writer.heartbeat();
+            self.route_writer_submessages(entity_id, heartbeats);
+        }
+
+        for (entity_id, deadline) in self.resend_deadlines.iter_mut() {
+            if *deadline > now {
+                continue;
+            }
+            if let Some(writer) = self.stateless_writers.get_mut(entity_id) {
+                writer.unsent_changes_reset();
+                if let Some(resend_data_period) = resend_data_period(writer) {
+                    *deadline = now + resend_data_period;
+                }
+            }
+        }
+    }
+
+    /// The earliest time at which handle_timeout has to be called.
+    pub fn poll_timeout(&self) -> Option<Instant> {
+        self.heartbeat_deadlines
+            .values()
+            .chain(self.resend_deadlines.values())
+            .min()
+            .cloned()
+    }
+
+    /// Returns the next message which has to be sent, including the changes
+    /// written to the local Writers since the last call.
+    pub fn poll_transmit(&mut self) -> Option<Transmit> {
+        if self.transmits.is_empty() {
+            self.flush();
+        }
+        self.transmits.pop_front()
+    }
+
+    pub fn poll_event(&mut self) -> Option<Event> {
+        self.events.pop_front()
+    }
+
+    fn handle_submessage(
+        &mut self,
+        source_guid_prefix: GuidPrefix_t,
+        source_timestamp: Option<Time_t>,
+        submessage: EntitySubmessage,
+    ) {
+        let remote_guid = |entity_id| GUID_t {
+            guid_prefix: source_guid_prefix,
+            entity_id,
+        };
+
+        match submessage {
+            EntitySubmessage::AckNack(ack_nack, _) => {
+                if let Some(writer) = self.writers.get_mut(&ack_nack.writer_id) {
+                    writer.process_ack_nack(remote_guid(ack_nack.reader_id), &ack_nack);
+                }
+            }
+            EntitySubmessage::NackFrag(nack_frag) => {
+                if let Some(writer) = self.writers.get_mut(&nack_frag.writer_id) {
+                    writer.process_nack_frag(remote_guid(nack_frag.reader_id), &nack_frag);
+                }
+            }
+            EntitySubmessage::Data(data, _) => {
+                let writer_guid = remote_guid(data.writer_id);
+                let events = &mut self.events;
+                for_each_stateless_reader(
+                    &mut self.stateless_readers,
+                    data.reader_id,
+                    writer_guid,
+                    |reader| {
+                        if reader.process_data(writer_guid, &data) {
+                            events.push_back(Event::DataAvailable {
+                                reader_guid: reader.as_entity().guid,
+                            });
+                        }
+                    },
+                );
+                for_each_reader(&mut self.readers, data.reader_id, writer_guid, |reader| {
+                    if reader.process_data(writer_guid, &data, source_timestamp) {
+                        events.push_back(Event::DataAvailable {
+                            reader_guid: reader.as_entity().guid,
+                        });
+                    }
+                    push_sample_lost(events, reader, writer_guid);
+                });
+            }
+            EntitySubmessage::DataFrag(data_frag, _) => {
+                let writer_guid = remote_guid(data_frag.writer_id);
+                let events = &mut self.events;
+                for_each_reader(
+                    &mut self.readers,
+                    data_frag.reader_id,
+                    writer_guid,
+                    |reader| {
+                        if reader.process_data_frag(writer_guid, &data_frag, source_timestamp) {
+                            events.push_back(Event::DataAvailable {
+                                reader_guid: reader.as_entity().guid,
+                            });
+                        }
+                        push_sample_lost(events, reader, writer_guid);
+                    },
+                );
+            }
+            EntitySubmessage::Gap(gap) => {
+                let writer_guid = remote_guid(gap.writer_id);
+                for_each_reader(&mut self.readers, gap.reader_id, writer_guid, |reader| {
+                    reader.process_gap(writer_guid, &gap)
+                });
+            }
+            EntitySubmessage::Heartbeat(heartbeat, flags) => {
+                let writer_guid = remote_guid(heartbeat.writer_id);
+                let events = &mut self.events;
+                let mut liveliness_asserted = false;
+                for_each_reader(
+                    &mut self.readers,
+                    heartbeat.reader_id,
+                    writer_guid,
+                    |reader| {
+                        liveliness_asserted |= reader.matched_writer_lookup(writer_guid).is_some()
+                            && flags.is_flag_set(Heartbeat::LIVELINESS_FLAG);
+                        reader.process_heartbeat(writer_guid, &heartbeat, flags);
+                        push_sample_lost(events, reader, writer_guid);
+                    },
+                );
+                if liveliness_asserted {
+                    self.events
+                        .push_back(Event::LivelinessAsserted { writer_guid });
+                }
+            }
+            EntitySubmessage::HeartbeatFrag(heartbeat_frag) => {
+                let writer_guid = remote_guid(heartbeat_frag.writer_id);
+                for_each_reader(
+                    &mut self.readers,
+                    heartbeat_frag.reader_id,
+                    writer_guid,
+                    |reader| reader.process_heartbeat_frag(writer_guid, &heartbeat_frag),
+                );
+            }
+            // Applied by the MessageReceiver to the following Submessages
+            EntitySubmessage::InfoTimestamp(..) => (),
+        }
+    }
+
+    /// Collects Submessages from all local Endpoints and puts them into
+    /// messages; one message per destination, unless it exceeds the maximum
+    /// message size. Submessages addressed to the local Endpoints are
+    /// delivered until no more responses are created.
+    fn flush(&mut self) {
+        self.collect_submessages();
+        while !self.intra_process.is_empty() {
+            // The Submessages are delivered in order, the InfoTimestamp
+            // applies to the single Submessage following it
+            let mut source_timestamp = None;
+            while let Some(submessage) = self.intra_process.pop_front() {
+                match submessage {
+                    EntitySubmessage::InfoTimestamp(info_timestamp, _) => {
+                        source_timestamp = info_timestamp.timestamp
+                    }
+                    submessage => self.handle_submessage(
+                        self.guid_prefix,
+                        source_timestamp.take(),
+                        submessage,
+                    ),
+                }
+            }
+            self.collect_submessages();
+        }
+        self.encode_submessages();
+    }
+
+    /// Routes Submessages created by the local Endpoints to their
+    /// destinations.
+    fn collect_submessages(&mut self) {
+        // The StatelessWriters reach all Participants behind their locators
+        for writer in self.stateless_writers.values_mut() {
+            for (locator, submessage) in writer.pending_submessages() {
+                self.outgoing
+                    .push((vec![locator], GuidPrefix_t::GUIDPREFIX_UNKNOWN, submessage));
+            }
+        }
+
+        let writer_ids: Vec<EntityId_t> = self.writers.keys().cloned().collect();
+        for writer_id in writer_ids {
+            let submessages = self
+                .writers
+                .get_mut(&writer_id)
+                .unwrap()
+                .pending_submessages();
+            self.route_writer_submessages(writer_id, submessages);
+        }
+
+        let reader_ids: Vec<EntityId_t> = self.readers.keys().cloned().collect();
+        for reader_id in reader_ids {
+            let submessages = self
+                .readers
+                .get_mut(&reader_id)
+                .unwrap()
+                .pending_submessages();
+            self.route_reader_submessages(reader_id, submessages);
+        }
+    }
+
+    /// Puts the routed Submessages into messages.
+    fn encode_submessages(&mut self) {
+        let mut destinations: Vec<((LocatorList_t, GuidPrefix_t), Vec<EntitySubmessage>)> = vec![];
+        for (locators, guid_prefix, submessage) in self.outgoing.drain(..) {
+            match destinations
+                .iter_mut()
+                .find(|((known, prefix), _)| known[0] == locators[0] && *prefix == guid_prefix)
+            {
+                Some((_, submessages)) => submessages.push(submessage),
+                None => destinations.push(((locators, guid_prefix), vec![submessage])),
+            }
+        }
+
+        for ((locators, guid_prefix), submessages) in destinations {
+            self.transmits.extend(
+                self.message_sender
+                    .encode(guid_prefix, submessages)
+                    .into_iter()
+                    .map(|contents| Transmit {
+                        destination: locators[0],
+                        fallbacks: locators[1..].to_vec(),
+                        contents,
+                    }),
+            );
+        }
+    }
+
+    fn route_writer_submessages(
+        &mut self,
+        writer_id: EntityId_t,
+        submessages: Vec<(GUID_t, EntitySubmessage)>,
+    ) {
+        for (reader_guid, submessage) in submessages {
+            let locators = self.writers[&writer_id]
+                .matched_reader_lookup(reader_guid)
+                .next()
+                .map(|proxy| {
+                    reachable_locators(
+                        &self.locator_filter,
+                        &proxy.unicast_locator_list,
+                        &proxy.multicast_locator_list,
+                    )
+                })
+                .unwrap_or_default();
+            self.route(reader_guid, locators, submessage);
+        }
+    }
+
+    fn route_reader_submessages(
+        &mut self,
+        reader_id: EntityId_t,
+        submessages: Vec<(GUID_t, EntitySubmessage)>,
+    ) {
+        for (writer_guid, submessage) in submessages {
+            let locators = self.readers[&reader_id]
+                .matched_writer_lookup(writer_guid)
+                .map(|proxy| {
+                    reachable_locators(
+                        &self.locator_filter,
+                        &proxy.unicast_locator_list,
+                        &proxy.multicast_locator_list,
+                    )
+                })
+                .unwrap_or_default();
+            self.route(writer_guid, locators, submessage);
+        }
+    }
+
+    /// Routes the Submessage to the first of the locators, the others are
+    /// the fallbacks of the Transmit. Without locators, it is routed to the
+    /// locator the remote Participant last sent a message from.
+    fn route(
+        &mut self,
+        remote_guid: GUID_t,
+        mut locators: LocatorList_t,
+        submessage: EntitySubmessage,
+    ) {
+        if remote_guid.guid_prefix == self.guid_prefix {
+            self.intra_process.push_back(submessage);
+            return;
+        }
+
+        if locators.is_empty() {
+            locators.extend(
+                self.remote_participants
+                    .get(&remote_guid.guid_prefix)
+                    .and_then(|remote_participant| remote_participant.locator),
+            );
+        }
+
+        if locators.is_empty() {
+            warn!(
+                "Dropping {:?} submessage, no locator known for {:?}",
+                submessage.submessage_kind(),
+                remote_guid
+            );
+        } else {
+            self.outgoing
+                .push((locators, remote_guid.guid_prefix, submessage));
+        }
+    }
+}
+
+fn heartbeat_period(writer: &StatefulWriter) -> Option<Duration> {
+    let heartbeat_period = Duration::from(writer.as_writer().heartbeat_period);
+    if writer.as_endpoint().reliability_level == ReliabilityKind_t::RELIABLE
+        && heartbeat_period > Duration::from_secs(0)
+    {
+        Some(heartbeat_period)
+    } else {
+        None
+    }
+}
+
+fn resend_data_period(writer: &StatelessWriter) -> Option<Duration> {
+    let resend_data_period = Duration::from(writer.resend_data_period());
+    if resend_data_period > Duration::from_secs(0) {
+        Some(resend_data_period)
+    } else {
+        None
+    }
+}
+
+/// The locators accepted by the filter, unicast locators first.
+fn reachable_locators(
+    locator_filter: &dyn Fn(&Locator_t) -> bool,
+    unicast_locator_list: &LocatorList_t,
+    multicast_locator_list: &LocatorList_t,
+) -> LocatorList_t {
+    unicast_locator_list
+        .iter()
+        .chain(multicast_locator_list)
+        .filter(|locator| locator_filter(locator))
+        .cloned()
+        .collect()
+}
+
+/// Calls f for the Reader identified by reader_id or, when reader_id is
+/// ENTITYID_UNKNOWN, for all Readers matched with the Writer.
+fn for_each_reader(
+    readers: &mut BTreeMap<EntityId_t, StatefulReader>,
+    reader_id: EntityId_t,
+    writer_guid: GUID_t,
+    mut f: impl FnMut(&mut StatefulReader),
+) {
+    if reader_id == EntityId_t::ENTITYID_UNKNOWN {
+        readers
+            .values_mut()
+            .filter(|reader| reader.matched_writer_lookup(writer_guid).is_some())
+            .for_each(f);
+    } else if let Some(reader) = readers.get_mut(&reader_id) {
+        f(reader);
+    }
+}
+
+/// Queues the SampleLost event for the changes of the Writer lost by the
+/// reader.
+fn push_sample_lost(
+    events: &mut VecDeque<Event>,
+    reader: &mut StatefulReader,
+    writer_guid: GUID_t,
+) {
+    let count = reader.take_lost_changes();
+    if count > 0 {
+        events.push_back(Event::SampleLost {
+            reader_guid: reader.as_entity().guid,
+            writer_guid,
+            count,
+        });
+    }
+}
+
+/// Calls f for the StatelessReader identified by reader_id or, when
+/// reader_id is ENTITYID_UNKNOWN, for the built-in StatelessReader
+/// corresponding to the built-in Writer, e.g. the SPDP reader for the SPDP
+/// writer.
+fn for_each_stateless_reader(
+    readers: &mut BTreeMap<EntityId_t, StatelessReader>,
+    reader_id: EntityId_t,
+    writer_guid: GUID_t,
+    mut f: impl FnMut(&mut StatelessReader),
+) {
+    let writer_id = writer_guid.entity_id;
+    if reader_id == EntityId_t::ENTITYID_UNKNOWN {
+        readers
+            .iter_mut()
+            .filter(|(reader_id, _)| {
+                writer_id.is_builtin()
+                    && reader_id.is_builtin()
+                    && reader_id.entity_key() == writer_id.entity_key()
+            })
+            .map(|(_, reader)| reader)
+            .for_each(f);
+    } else if let Some(reader) = readers.get_mut(&reader_id) {
+        f(reader);
+    }
+}
+
+/// Removes the vendor-specific parameters of the inline QoS of the Data and
+/// DataFrag Submessages (9.6.2.2.1).
+fn remove_vendor_specific_inline_qos(submessage: &mut EntitySubmessage) {
+    let inline_qos = match submessage {
+        EntitySubmessage::Data(data, _) => data.inline_qos.as_mut(),
+        EntitySubmessage::DataFrag(data_frag, _) => data_frag.inline_qos.as_mut(),
+        _ => None,
+    };
+    if let Some(inline_qos) = inline_qos {
+        inline_qos.remove_vendor_specific();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::behavior::reader::{Reader, ReaderAttributes};
+    use crate::behavior::reader_proxy::ReaderProxy;
+    use crate::behavior::writer::WriterAttributes;
+    use crate::behavior::writer_proxy::WriterProxy;
+    use crate::structure::change_kind::ChangeKind_t;
+    use crate::structure::data::Data;
+    use crate::structure::duration::Duration_t;
+    use crate::structure::endpoint::EndpointAttributes;
+    use crate::structure::entity::EntityAttributes;
+    use crate::structure::history_cache::HistoryCache;
+    use crate::structure::instance_handle::InstanceHandle_t;
+    use crate::structure::locator::{Ipv4Addr, SocketAddr};
+    use crate::structure::sequence_number::SequenceNumber_t;
+    use crate::structure::topic_kind::TopicKind_t;
+
+    const WRITER_GUID: GUID_t = GUID_t {
+        guid_prefix: GuidPrefix_t {
+            entity_key: [0x01; 12],
+        },
+        entity_id: EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_WRITER,
+    };
+
+    const READER_GUID: GUID_t = GUID_t {
+        guid_prefix: GuidPrefix_t {
+            entity_key: [0x02; 12],
+        },
+        entity_id: EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_READER,
+    };
+
+    fn locator(port: u16) -> Locator_t {
+        Locator_t::from(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), port))
+    }
+
+    fn endpoint() -> EndpointAttributes {
+        EndpointAttributes {
+            topic_kind: TopicKind_t::NO_KEY,
+            reliability_level: ReliabilityKind_t::RELIABLE,
+            unicast_locator_list: vec![],
+            multicast_locator_list: vec![],
+        }
+    }
+
+    /// Participant with a single reliable Writer matched with the Reader of
+    /// the reader_core.
+    fn writer_core(now: Instant) -> ProtocolCore {
+        let mut writer = StatefulWriter::new(
+            EntityAttributes { guid: WRITER_GUID },
+            endpoint(),
+            WriterAttributes {
+                push_mode: true,
+                heartbeat_period: Duration_t::from(Duration::from_secs(1)),
+                nack_response_delay: Duration_t::DURATION_ZERO,
+                nack_suppression_duration: Duration_t::DURATION_ZERO,
+                last_change_sequence_number: SequenceNumber_t::from(0),
+                data_max_size_serialized: 8,
+                fragment_size: 4,
+                writer_cache: HistoryCache::new(),
+            },
+        );
+        writer.matched_reader_add(ReaderProxy::new(
+            READER_GUID,
+            vec![locator(7411)],
+            vec![],
+            false,
+        ));
+
+        let mut core = ProtocolCore::new(WRITER_GUID.guid_prefix, VendorId_t::VENDOR_UNKNOWN);
+        core.add_writer(now, writer);
+        core
+    }
+
+    /// Participant with a single reliable Reader matched with the Writer of
+    /// the writer_core.
+    fn reader_core() -> ProtocolCore {
+        let mut reader = StatefulReader::new(
+            EntityAttributes { guid: READER_GUID },
+            endpoint(),
+            ReaderAttributes {
+                expects_inline_qos: false,
+                heartbeat_response_delay: Duration_t::DURATION_ZERO,
+                heartbeat_suppression_duration: Duration_t::DURATION_ZERO,
+                reader_cache: HistoryCache::new(),
+            },
+        );
+        reader.matched_writer_add(WriterProxy::new(WRITER_GUID, vec![locator(7410)], vec![]));
+
+        let mut core = ProtocolCore::new(READER_GUID.guid_prefix, VendorId_t::VENDOR_UNKNOWN);
+        core.add_reader(reader);
+        core
+    }
+
+    fn write(core: &mut ProtocolCore, value: &'static [u8]) {
+        let writer = core.writer_mut(WRITER_GUID.entity_id).unwrap();
+        let change = writer.new_change(
+            ChangeKind_t::ALIVE,
+            Data::from(Bytes::from_static(value)),
+            InstanceHandle_t::default(),
+        );
+        writer.as_writer_mut().writer_cache.add_change(change);
+    }
+
+    /// Delivers all messages from one core to the other, returns number of
+    /// delivered messages.
+    fn deliver(
+        now: Instant,
+        from: &mut ProtocolCore,
+        src: Locator_t,
+        to: &mut ProtocolCore,
+    ) -> usize {
+        let mut delivered = 0;
+        while let Some(transmit) = from.poll_transmit() {
+            to.handle_datagram(now, src, transmit.contents);
+            delivered += 1;
+        }
+        delivered
+    }
+
+    fn received_values(core: &ProtocolCore) -> Vec<Bytes> {
+        core.reader(READER_GUID.entity_id)
+            .unwrap()
+            .as_reader()
+            .reader_cache
+            .changes()
+            .iter()
+            .map(|change| change.data_value.value.clone())
+            .collect()
+    }
+
+    #[test]
+    fn written_change_is_sent_to_matched_reader_locator() {
+        let now = Instant::now();
+        let mut writer_core = writer_core(now);
+        write(&mut writer_core, b"first");
+
+        let transmit = writer_core.poll_transmit().unwrap();
+
+        assert_eq!(locator(7411), transmit.destination);
+        assert_eq!(None, writer_core.poll_transmit());
+    }
+
+    #[test]
+    fn locators_accepted_by_filter_are_used() {
+        let now = Instant::now();
+        let mut core = writer_core(now);
+        core.writer_mut(WRITER_GUID.entity_id)
+            .unwrap()
+            .matched_reader_add(ReaderProxy::new(
+                GUID_t {
+                    guid_prefix: GuidPrefix_t::from([0x03; 12]),
+                    entity_id: READER_GUID.entity_id,
+                },
+                vec![locator(7412), locator(7413)],
+                vec![locator(7414)],
+                false,
+            ));
+        core.set_locator_filter(|locator| locator.port != 7412);
+        write(&mut core, b"first");
+
+        let mut destinations = vec![];
+        while let Some(transmit) = core.poll_transmit() {
+            destinations.push((transmit.destination, transmit.fallbacks));
+        }
+
+        assert_eq!(
+            vec![
+                (locator(7411), vec![]),
+                (locator(7413), vec![locator(7414)])
+            ],
+            destinations
+        );
+    }
+
+    #[test]
+    fn data_is_delivered_to_reader() {
+        let now = Instant::now();
+        let mut writer_core = writer_core(now);
+        let mut reader_core = reader_core();
+        reader_core.add_remote_participant(WRITER_GUID.guid_prefix);
+        write(&mut writer_core, b"first");
+        write(&mut writer_core, b"0123456789");
+
+        deliver(now, &mut writer_core, locator(7410), &mut reader_core);
+
+        assert_eq!(
+            vec![
+                Bytes::from_static(b"first"),
+                Bytes::from_static(b"0123456789")
+            ],
+            received_values(&reader_core)
+        );
+        assert_eq!(
+            Some(Event::DataAvailable {
+                reader_guid: READER_GUID
+            }),
+            reader_core.poll_event()
+        );
+        assert_eq!(
+            Some(now),
+            reader_core.last_received(WRITER_GUID.guid_prefix)
+        );
+    }
+
+    #[test]
+    fn only_added_remote_participants_are_tracked() {
+        let now = Instant::now();
+        let mut writer_core = writer_core(now);
+        let mut reader_core = reader_core();
+        write(&mut writer_core, b"first");
+        deliver(now, &mut writer_core, locator(7410), &mut reader_core);
+        assert_eq!(None, reader_core.last_received(WRITER_GUID.guid_prefix));
+
+        reader_core.add_remote_participant(WRITER_GUID.guid_prefix);
+        write(&mut writer_core, b"second");
+        deliver(now, &mut writer_core, locator(7410), &mut reader_core);
+        assert_eq!(
+            Some(now),
+            reader_core.last_received(WRITER_GUID.guid_prefix)
+        );
+
+        reader_core.remove_remote_participant(WRITER_GUID.guid_prefix);
+        assert_eq!(None, reader_core.last_received(WRITER_GUID.guid_prefix));
+    }
+
+    #[test]
+    fn lost_data_is_repaired_after_heartbeat() {
+        let now = Instant::now();
+        let mut writer_core = writer_core(now);
+        let mut reader_core = reader_core();
+        write(&mut writer_core, b"first");
+
+        // The Data message is lost
+        assert!(writer_core.poll_transmit().is_some());
+        assert_eq!(
+            Some(now + Duration::from_secs(1)),
+            writer_core.poll_timeout()
+        );
+
+        let now = now + Duration::from_secs(1);
+        writer_core.handle_timeout(now);
+        assert_eq!(
+            Some(now + Duration::from_secs(1)),
+            writer_core.poll_timeout()
+        );
+        assert_eq!(
+            1,
+            deliver(now, &mut writer_core, locator(7410), &mut reader_core)
+        );
+        // AckNack requesting the lost change
+        assert_eq!(
+            1,
+            deliver(now, &mut reader_core, locator(7411), &mut writer_core)
+        );
+        assert_eq!(
+            1,
+            deliver(now, &mut writer_core, locator(7410), &mut reader_core)
+        );
+
+        assert_eq!(
+            vec![Bytes::from_static(b"first")],
+            received_values(&reader_core)
+        );
+    }
+
+    #[test]
+    fn malformed_datagram_is_ignored() {
+        let mut reader_core = reader_core();
+
+        reader_core.handle_datagram(
+            Instant::now(),
+            locator(7410),
+            Bytes::from_static(b"RTPS\x02\x03"),
+        );
+        reader_core.handle_datagram(
+            Instant::now(),
+            locator(7410),
+            Bytes::from_static(&[0xFF; 64]),
+        );
+
+        assert_eq!(None, reader_core.poll_event());
+        assert_eq!(None, reader_core.poll_transmit());
+    }
+
+    #[test]
+    fn data_for_local_reader_is_not_serialized() {
+        let now = Instant::now();
+        let local_reader_guid = GUID_t {
+            guid_prefix: WRITER_GUID.guid_prefix,
+            entity_id: READER_GUID.entity_id,
+        };
+        let mut core = writer_core(now);
+        let writer = core.writer_mut(WRITER_GUID.entity_id).unwrap();
+        writer.matched_reader_add(ReaderProxy::new(local_reader_guid, vec![], vec![], false));
+        let mut reader = StatefulReader::new(
+            EntityAttributes {
+                guid: local_reader_guid,
+            },
+            endpoint(),
+            ReaderAttributes {
+                expects_inline_qos: false,
+                heartbeat_response_delay: Duration_t::DURATION_ZERO,
+                heartbeat_suppression_duration: Duration_t::DURATION_ZERO,
+                reader_cache: HistoryCache::new(),
+            },
+        );
+        reader.matched_writer_add(WriterProxy::new(WRITER_GUID, vec![], vec![]));
+        core.add_reader(reader);
+
+        let value = Bytes::from(b"sample".to_vec());
+        let writer = core.writer_mut(WRITER_GUID.entity_id).unwrap();
+        let change = writer.new_change(
+            ChangeKind_t::ALIVE,
+            Data::from(value.clone()),
+            InstanceHandle_t::default(),
+        );
+        writer.as_writer_mut().writer_cache.add_change(change);
+
+        // Only the remote Reader is reached through the locator
+        assert_eq!(locator(7411), core.poll_transmit().unwrap().destination);
+        assert_eq!(None, core.poll_transmit());
+        assert_eq!(
+            Some(Event::DataAvailable {
+                reader_guid: local_reader_guid
+            }),
+            core.poll_event()
+        );
+        let received = received_values(&core);
+        assert_eq!(vec![value.clone()], received);
+        assert_eq!(value.as_ptr(), received[0].as_ptr());
+
+        // Heartbeat is acknowledged by the local Reader
+        core.handle_timeout(now + Duration::from_secs(1));
+        assert_eq!(locator(7411), core.poll_transmit().unwrap().destination);
+        assert_eq!(None, core.poll_transmit());
+        assert!(core
+            .writer_mut(WRITER_GUID.entity_id)
+            .unwrap()
+            .matched_reader_lookup(local_reader_guid)
+            .next()
+            .unwrap()
+            .is_acked(SequenceNumber_t::from(1)));
+    }
+}
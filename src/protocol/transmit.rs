@@ -0,0 +1,14 @@
+use crate::structure::locator::{LocatorList_t, Locator_t};
+use bytes::Bytes;
+
+/// Serialized RTPS Message which has to be sent to the destination locator.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transmit {
+    pub destination: Locator_t,
+
+    /// The other locators of the destination, tried in order when sending
+    /// to the destination locator fails.
+    pub fallbacks: LocatorList_t,
+
+    pub contents: Bytes,
+}
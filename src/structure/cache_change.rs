@@ -3,6 +3,7 @@ use crate::structure::data::Data;
 use crate::structure::guid::GUID_t;
 use crate::structure::instance_handle::InstanceHandle_t;
 use crate::structure::sequence_number::SequenceNumber_t;
+use crate::structure::time::Time_t;
 
 #[derive(Debug, PartialOrd, PartialEq, Ord, Eq)]
 pub struct CacheChange {
@@ -11,4 +12,8 @@ pub struct CacheChange {
     pub instance_handle: InstanceHandle_t,
     pub sequence_number: SequenceNumber_t,
     pub data_value: Data,
+
+    /// The time of the change set by the DataWriter, sent in the
+    /// InfoTimestamp Submessage preceding the change.
+    pub source_timestamp: Option<Time_t>,
 }
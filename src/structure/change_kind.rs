@@ -1,6 +1,9 @@
-#[derive(Debug, PartialOrd, PartialEq, Ord, Eq)]
+/// The kind of a change to a data-object (8.2.1.2). The changes which are
+/// not ALIVE carry only the key of the instance, see StatusInfo_t.
+#[derive(Clone, Copy, Debug, PartialOrd, PartialEq, Ord, Eq, Hash)]
 pub enum ChangeKind_t {
     ALIVE,
     NOT_ALIVE_DISPOSED,
     NOT_ALIVE_UNREGISTERED,
+    NOT_ALIVE_DISPOSED_UNREGISTERED,
 }
@@ -1,2 +1,13 @@
-#[derive(Debug, PartialOrd, PartialEq, Ord, Eq)]
-pub struct Data {}
+use bytes::Bytes;
+
+/// The serialized value of a data-object carried by a CacheChange.
+#[derive(Clone, Debug, Default, PartialOrd, PartialEq, Ord, Eq)]
+pub struct Data {
+    pub value: Bytes,
+}
+
+impl From<Bytes> for Data {
+    fn from(value: Bytes) -> Self {
+        Data { value }
+    }
+}
@@ -1,8 +1,11 @@
+use crate::common::validity_trait::Validity;
 use speedy::{Readable, Writable};
 use std::convert::From;
 use std::time::Duration;
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Readable, Writable)]
+/// Like Time_t, the duration is expressed in seconds and fraction of seconds
+/// using the formula: duration = seconds + (fraction / 2^(32))
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Readable, Writable)]
 pub struct Duration_t {
     seconds: i32,
     fraction: u32,
@@ -23,18 +26,40 @@ impl Duration_t {
     };
 }
 
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+/// The negative durations, DURATION_INVALID among them, are not valid.
+impl Validity for Duration_t {
+    fn valid(&self) -> bool {
+        self.seconds >= 0
+    }
+}
+
+/// The durations exceeding the range of Duration_t saturate to
+/// DURATION_INFINITE.
 impl From<Duration> for Duration_t {
     fn from(duration: Duration) -> Self {
+        if duration.as_secs() >= Duration_t::DURATION_INFINITE.seconds as u64 {
+            return Duration_t::DURATION_INFINITE;
+        }
+        let fraction =
+            ((u64::from(duration.subsec_nanos()) << 32) + NANOS_PER_SEC / 2) / NANOS_PER_SEC;
         Duration_t {
             seconds: duration.as_secs() as i32,
-            fraction: duration.subsec_nanos() as u32,
+            fraction: fraction as u32,
         }
     }
 }
 
+/// The invalid (negative) durations, which a Duration cannot represent,
+/// convert to zero.
 impl From<Duration_t> for Duration {
     fn from(duration: Duration_t) -> Self {
-        Duration::new(duration.seconds as u64, duration.fraction)
+        if !duration.valid() {
+            return Duration::ZERO;
+        }
+        let nanos = (u64::from(duration.fraction) * NANOS_PER_SEC + (1 << 31)) >> 32;
+        Duration::new(duration.seconds as u64, 0) + Duration::from_nanos(nanos)
     }
 }
 
@@ -74,18 +99,16 @@ mod tests {
         be = [0x5A, 0x8C, 0x6E, 0x78, 0x4F, 0x2A, 0xE0, 0x7E]
     });
 
-    const NANOS_PER_SEC: u64 = 1_000_000_000;
-
     #[test]
     fn convert_from_duration() {
-        let duration = Duration::from_nanos(1_519_152_761 * NANOS_PER_SEC + 328_210_046);
+        let duration = Duration::from_nanos(1_519_152_761 * NANOS_PER_SEC + 250_000_000);
         let duration: Duration_t = duration.into();
 
         assert_eq!(
             duration,
             Duration_t {
                 seconds: 1_519_152_761,
-                fraction: 328_210_046,
+                fraction: 0x4000_0000,
             }
         );
     }
@@ -100,7 +123,36 @@ mod tests {
 
         assert_eq!(
             duration,
-            Duration::from_nanos(1_519_152_760 * NANOS_PER_SEC + 1_328_210_046)
+            Duration::from_nanos(1_519_152_760 * NANOS_PER_SEC + 309_248_000)
+        );
+    }
+
+    #[test]
+    fn conversion_round_trips() {
+        for nanos in &[0, 1, 328_210_046, 999_999_999] {
+            let duration = Duration::new(7, *nanos);
+            assert_eq!(duration, Duration::from(Duration_t::from(duration)));
+        }
+    }
+
+    #[test]
+    fn too_long_duration_saturates_to_infinite() {
+        assert_eq!(
+            Duration_t::DURATION_INFINITE,
+            Duration_t::from(Duration::from_secs(u64::MAX))
         );
     }
+
+    #[test]
+    fn negative_durations_are_invalid_and_convert_to_zero() {
+        let negative = Duration_t {
+            seconds: -5,
+            fraction: 0,
+        };
+        assert!(!negative.valid());
+        assert!(!Duration_t::DURATION_INVALID.valid());
+        assert!(Duration_t::DURATION_INFINITE.valid());
+        assert_eq!(Duration::ZERO, Duration::from(negative));
+        assert_eq!(Duration::ZERO, Duration::from(Duration_t::DURATION_INVALID));
+    }
 }
@@ -15,11 +15,11 @@ pub struct EndpointAttributes {
 
     /// List of unicast locators (transport, address, port combinations) that
     /// can be used to send messages to the Endpoint. The list may be empty.
-    pub unicast_locator_list: locator::Locator_t,
+    pub unicast_locator_list: locator::LocatorList_t,
 
     /// List of multicast locators (transport, address, port combinations) that
     /// can be used to send messages to the Endpoint. The list may be empty.
-    pub multicast_locator_list: locator::Locator_t,
+    pub multicast_locator_list: locator::LocatorList_t,
 }
 
 pub trait Endpoint {
@@ -55,6 +55,42 @@ impl EntityId_t {
         entity_key: [0x00, 0x02, 0x00],
         entity_kind: 0xC7,
     };
+
+    pub const ENTITYKIND_USER_DEFINED_UNKNOWN: u8 = 0x00;
+    pub const ENTITYKIND_USER_DEFINED_WRITER_WITH_KEY: u8 = 0x02;
+    pub const ENTITYKIND_USER_DEFINED_WRITER_NO_KEY: u8 = 0x03;
+    pub const ENTITYKIND_USER_DEFINED_READER_NO_KEY: u8 = 0x04;
+    pub const ENTITYKIND_USER_DEFINED_READER_WITH_KEY: u8 = 0x07;
+    pub const ENTITYKIND_USER_DEFINED_WRITER_GROUP: u8 = 0x08;
+    pub const ENTITYKIND_USER_DEFINED_READER_GROUP: u8 = 0x09;
+    pub const ENTITYKIND_BUILTIN_UNKNOWN: u8 = 0xC0;
+    pub const ENTITYKIND_BUILTIN_PARTICIPANT: u8 = 0xC1;
+    pub const ENTITYKIND_BUILTIN_WRITER_WITH_KEY: u8 = 0xC2;
+    pub const ENTITYKIND_BUILTIN_WRITER_NO_KEY: u8 = 0xC3;
+    pub const ENTITYKIND_BUILTIN_READER_NO_KEY: u8 = 0xC4;
+    pub const ENTITYKIND_BUILTIN_READER_WITH_KEY: u8 = 0xC7;
+
+    pub const fn new(entity_key: [u8; 3], entity_kind: u8) -> Self {
+        EntityId_t {
+            entity_key,
+            entity_kind,
+        }
+    }
+
+    pub fn entity_key(&self) -> [u8; 3] {
+        self.entity_key
+    }
+
+    /// The kind of the Entity, one of the ENTITYKIND constants. The two most
+    /// significant bits distinguish the built-in and the vendor-specific
+    /// Entities from the user-defined ones.
+    pub fn entity_kind(&self) -> u8 {
+        self.entity_kind
+    }
+
+    pub fn is_builtin(&self) -> bool {
+        self.entity_kind & 0xC0 == 0xC0
+    }
 }
 
 impl Default for EntityId_t {
@@ -89,6 +125,16 @@ impl<C: Context> Writable<C> for EntityId_t {
 mod tests {
     use super::*;
 
+    #[test]
+    fn builtin_entities_are_distinguished_by_kind() {
+        assert!(EntityId_t::ENTITYID_SPDP_BUILTIN_PARTICIPANT_WRITER.is_builtin());
+        assert!(!EntityId_t::new(
+            [0x00, 0x00, 0x01],
+            EntityId_t::ENTITYKIND_USER_DEFINED_WRITER_WITH_KEY
+        )
+        .is_builtin());
+    }
+
     serialization_test!( type = EntityId_t,
         {
             entity_unknown,
@@ -167,6 +213,12 @@ mod tests {
             EntityId_t::ENTITYID_P2P_BUILTIN_PARTICIPANT_MESSAGE_READER,
             le = [0x00, 0x02, 0x00, 0xC7],
             be = [0x00, 0x02, 0x00, 0xC7]
+        },
+        {
+            entity_user_defined_reader_no_key,
+            EntityId_t::new([0x12, 0x34, 0x56], EntityId_t::ENTITYKIND_USER_DEFINED_READER_NO_KEY),
+            le = [0x12, 0x34, 0x56, 0x04],
+            be = [0x12, 0x34, 0x56, 0x04]
         }
     );
 }
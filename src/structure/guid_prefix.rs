@@ -1,4 +1,10 @@
+use crate::common::host_id::host_id;
+use crate::messages::vendor_id::VendorId_t;
 use speedy::{Context, Readable, Reader, Writable, Writer};
+use std::sync::atomic::{AtomicU16, Ordering};
+
+/// Distinguishes the prefixes generated within the same process.
+static GENERATED_PREFIXES: AtomicU16 = AtomicU16::new(0);
 
 #[derive(Copy, Clone, Debug, PartialOrd, PartialEq, Ord, Eq)]
 pub struct GuidPrefix_t {
@@ -9,6 +15,28 @@ impl GuidPrefix_t {
     pub const GUIDPREFIX_UNKNOWN: GuidPrefix_t = GuidPrefix_t {
         entity_key: [0x00; 12],
     };
+
+    /// Generates the prefix unique within the domain: the first two bytes
+    /// are the vendor id (8.2.4.2), followed by the host id, the process id
+    /// and the counter of the prefixes generated by the process.
+    pub fn generate(vendor_id: VendorId_t) -> Self {
+        let counter = GENERATED_PREFIXES.fetch_add(1, Ordering::Relaxed);
+        GuidPrefix_t::from_parts(vendor_id, host_id(), std::process::id(), counter)
+    }
+
+    fn from_parts(vendor_id: VendorId_t, host_id: [u8; 16], process_id: u32, counter: u16) -> Self {
+        let mut host = [0x00; 4];
+        for (index, byte) in host_id.iter().enumerate() {
+            host[index % host.len()] ^= byte;
+        }
+
+        let mut entity_key = [0x00; 12];
+        entity_key[0..2].copy_from_slice(&vendor_id.vendor_id);
+        entity_key[2..6].copy_from_slice(&host);
+        entity_key[6..10].copy_from_slice(&process_id.to_be_bytes());
+        entity_key[10..12].copy_from_slice(&counter.to_be_bytes());
+        GuidPrefix_t { entity_key }
+    }
 }
 
 impl Default for GuidPrefix_t {
@@ -56,6 +84,30 @@ mod tests {
     use super::*;
     use speedy::Endianness;
 
+    #[test]
+    fn prefix_consists_of_vendor_host_process_and_counter() {
+        let mut host_id = [0x00; 16];
+        host_id[0] = 0x01;
+        host_id[4] = 0x10;
+        host_id[15] = 0xFF;
+
+        assert_eq!(
+            [0x01, 0x0F, 0x11, 0x00, 0x00, 0xFF, 0x00, 0x00, 0x30, 0x39, 0x00, 0x02],
+            GuidPrefix_t::from_parts(VendorId_t::from([0x01, 0x0F]), host_id, 12345, 2).entity_key
+        );
+    }
+
+    #[test]
+    fn generated_prefixes_are_unique() {
+        let vendor_id = VendorId_t::from([0x01, 0x0F]);
+        let first = GuidPrefix_t::generate(vendor_id);
+        let second = GuidPrefix_t::generate(vendor_id);
+
+        assert_ne!(first, second);
+        assert_eq!(first.entity_key[..10], second.entity_key[..10]);
+        assert_eq!([0x01, 0x0F], first.entity_key[..2]);
+    }
+
     #[test]
     fn minimum_bytes_needed() {
         assert_eq!(
@@ -1,40 +1,60 @@
 use crate::structure::cache_change::CacheChange;
+use crate::structure::guid::GUID_t;
 use crate::structure::sequence_number::SequenceNumber_t;
 
-struct HistoryCache {
+/// Container class used to temporarily store and manage sets of changes to
+/// data-objects.
+#[derive(Debug, Default)]
+pub struct HistoryCache {
     changes: Vec<CacheChange>,
 }
 
 impl HistoryCache {
-    fn new() -> HistoryCache {
+    pub fn new() -> HistoryCache {
         HistoryCache {
             changes: Vec::new(),
         }
     }
 
-    fn add_change(&mut self, change: CacheChange) {
+    pub fn add_change(&mut self, change: CacheChange) {
         self.changes.push(change)
     }
 
-    fn get_change(&self, sequence_number: SequenceNumber_t) -> Option<&CacheChange> {
+    pub fn get_change(&self, sequence_number: SequenceNumber_t) -> Option<&CacheChange> {
         self.changes
             .iter()
             .find(|x| x.sequence_number == sequence_number)
     }
 
-    fn remove_change(&mut self, sequence_number: SequenceNumber_t) {
+    pub fn remove_change(&mut self, sequence_number: SequenceNumber_t) {
         self.changes
             .retain(|x| x.sequence_number != sequence_number)
     }
 
-    fn get_seq_num_min(&self) -> Option<&SequenceNumber_t> {
+    /// Removes the change of the Writer identified by writer_guid; the
+    /// changes of several Writers kept by a Reader may share the sequence
+    /// number.
+    pub fn remove_writer_change(&mut self, writer_guid: GUID_t, sequence_number: SequenceNumber_t) {
+        self.changes
+            .retain(|x| x.writer_guid != writer_guid || x.sequence_number != sequence_number)
+    }
+
+    pub fn changes(&self) -> &[CacheChange] {
+        &self.changes
+    }
+
+    pub fn into_changes(self) -> Vec<CacheChange> {
+        self.changes
+    }
+
+    pub fn get_seq_num_min(&self) -> Option<&SequenceNumber_t> {
         self.changes
             .iter()
             .map(|x| &x.sequence_number)
             .min_by(|x, y| x.cmp(&y))
     }
 
-    fn get_seq_num_max(&self) -> Option<&SequenceNumber_t> {
+    pub fn get_seq_num_max(&self) -> Option<&SequenceNumber_t> {
         self.changes
             .iter()
             .map(|x| &x.sequence_number)
@@ -60,7 +80,8 @@ mod tests {
             writer_guid: GUID_t::GUID_UNKNOWN,
             instance_handle: InstanceHandle_t::default(),
             sequence_number: SequenceNumber_t::SEQUENCENUMBER_UNKNOWN,
-            data_value: Data {},
+            data_value: Data::default(),
+            source_timestamp: None,
         };
 
         assert_eq!(0, history_cache.changes.len());
@@ -80,7 +101,8 @@ mod tests {
             writer_guid: GUID_t::GUID_UNKNOWN,
             instance_handle: InstanceHandle_t::default(),
             sequence_number: SequenceNumber_t::from(10),
-            data_value: Data {},
+            data_value: Data::default(),
+            source_timestamp: None,
         };
         history_cache.add_change(cache_change);
         assert_eq!(1, history_cache.changes.len());
@@ -90,7 +112,8 @@ mod tests {
             writer_guid: GUID_t::GUID_UNKNOWN,
             instance_handle: InstanceHandle_t::default(),
             sequence_number: SequenceNumber_t::from(7),
-            data_value: Data {},
+            data_value: Data::default(),
+            source_timestamp: None,
         };
         history_cache.add_change(cache_change);
         assert_eq!(2, history_cache.changes.len());
@@ -108,7 +131,8 @@ mod tests {
             writer_guid: GUID_t::GUID_UNKNOWN,
             instance_handle: InstanceHandle_t::default(),
             sequence_number: SequenceNumber_t::from(1),
-            data_value: Data {},
+            data_value: Data::default(),
+            source_timestamp: None,
         };
         history_cache.add_change(small_cache_change);
 
@@ -117,7 +141,8 @@ mod tests {
             writer_guid: GUID_t::GUID_UNKNOWN,
             instance_handle: InstanceHandle_t::default(),
             sequence_number: SequenceNumber_t::from(7),
-            data_value: Data {},
+            data_value: Data::default(),
+            source_timestamp: None,
         };
         history_cache.add_change(big_cache_change);
 
@@ -136,7 +161,8 @@ mod tests {
             writer_guid: GUID_t::GUID_UNKNOWN,
             instance_handle: InstanceHandle_t::default(),
             sequence_number: SequenceNumber_t::from(1),
-            data_value: Data {},
+            data_value: Data::default(),
+            source_timestamp: None,
         };
         history_cache.add_change(small_cache_change);
 
@@ -150,7 +176,8 @@ mod tests {
             },
             instance_handle: InstanceHandle_t::default(),
             sequence_number: SequenceNumber_t::from(7),
-            data_value: Data {},
+            data_value: Data::default(),
+            source_timestamp: None,
         };
         history_cache.add_change(big_cache_change);
 
@@ -2,11 +2,18 @@ use speedy::{Context, Readable, Reader, Writable, Writer};
 
 /// Type used to represent the identity of a data-object whose changes in value
 /// are communicated by the RTPS protocol.
-#[derive(Debug, PartialOrd, PartialEq, Ord, Eq)]
+#[derive(Clone, Copy, Debug, PartialOrd, PartialEq, Ord, Eq)]
 pub struct InstanceHandle_t {
     pub entity_key: [u8; 16],
 }
 
+impl InstanceHandle_t {
+    /// The handle not identifying any instance or entity.
+    pub const HANDLE_NIL: InstanceHandle_t = InstanceHandle_t {
+        entity_key: [0x00; 16],
+    };
+}
+
 impl Default for InstanceHandle_t {
     fn default() -> InstanceHandle_t {
         InstanceHandle_t {
@@ -5,7 +5,7 @@ use std::{convert::From, net::SocketAddrV4};
 
 pub use crate::structure::locator_kind::LocatorKind_t;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
 pub struct Locator_t {
     pub kind: LocatorKind_t,
     pub port: u32,
@@ -53,15 +53,17 @@ impl From<SocketAddr> for Locator_t {
 impl From<Locator_t> for SocketAddr {
     fn from(locator: Locator_t) -> Self {
         match locator.kind {
-            LocatorKind_t::LOCATOR_KIND_UDPv4 => SocketAddr::new(
-                IpAddr::V4(Ipv4Addr::new(
-                    locator.address[12],
-                    locator.address[13],
-                    locator.address[14],
-                    locator.address[15],
-                )),
-                locator.port as u16,
-            ),
+            LocatorKind_t::LOCATOR_KIND_UDPv4 | LocatorKind_t::LOCATOR_KIND_TCPv4 => {
+                SocketAddr::new(
+                    IpAddr::V4(Ipv4Addr::new(
+                        locator.address[12],
+                        locator.address[13],
+                        locator.address[14],
+                        locator.address[15],
+                    )),
+                    locator.port as u16,
+                )
+            }
             LocatorKind_t::LOCATOR_KIND_UDPv6 => SocketAddr::new(
                 IpAddr::V6(Ipv6Addr::from(locator.address)),
                 locator.port as u16,
@@ -144,6 +146,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn locator_tcp_v4_to_socket_address_uses_physical_port() {
+        assert_eq!(
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 7410),
+            SocketAddr::from(Locator_t {
+                kind: LocatorKind_t::LOCATOR_KIND_TCPv4,
+                address: [
+                    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x7F,
+                    0x00, 0x00, 0x01
+                ],
+                port: 7411 << 16 | 7410
+            })
+        );
+    }
+
     macro_rules! conversion_test {
         ($({ $name:ident, $left:expr, $right:expr }),+) => {
             $(mod $name {
@@ -1,12 +1,16 @@
 use speedy::{Readable, Writable};
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Readable, Writable)]
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Readable, Writable)]
 #[speedy(tag_type = u32)]
 pub enum LocatorKind_t {
     LOCATOR_KIND_INVALID = 0xFFFFFFFF,
     LOCATOR_KIND_RESERVED = 0,
     LOCATOR_KIND_UDPv4 = 1,
     LOCATOR_KIND_UDPv6 = 2,
+    LOCATOR_KIND_TCPv4 = 4,
+    /// Vendor specific kind (the most significant bit set) of the shared
+    /// memory locators of this implementation.
+    LOCATOR_KIND_SHM = 0x8000_0001,
 }
 
 #[cfg(test)]
@@ -37,6 +41,18 @@ mod tests {
             LocatorKind_t::LOCATOR_KIND_UDPv6,
             le = [0x02, 0x00, 0x00, 0x00],
             be = [0x00, 0x00, 0x00, 0x02]
+        },
+        {
+            locator_kind_tcpv4,
+            LocatorKind_t::LOCATOR_KIND_TCPv4,
+            le = [0x04, 0x00, 0x00, 0x00],
+            be = [0x00, 0x00, 0x00, 0x04]
+        },
+        {
+            locator_kind_shm,
+            LocatorKind_t::LOCATOR_KIND_SHM,
+            le = [0x01, 0x00, 0x00, 0x80],
+            be = [0x80, 0x00, 0x00, 0x01]
         }
     );
 }
@@ -1,6 +1,6 @@
 use speedy::{Readable, Writable};
 
-#[derive(Debug, PartialEq, Readable, Writable)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Readable, Writable)]
 pub struct ParameterId {
     value: u16,
 }
@@ -48,22 +48,38 @@ impl ParameterId {
     pub const PID_PARTICIPANT_MANUAL_LIVELINESS_COUNT: ParameterId = ParameterId { value: 0x0034 };
     pub const PID_PARTICIPANT_BUILTIN_ENDPOINTS: ParameterId = ParameterId { value: 0x0044 };
     pub const PID_PARTICIPANT_LEASE_DURATION: ParameterId = ParameterId { value: 0x0002 };
+    pub const PID_DOMAIN_ID: ParameterId = ParameterId { value: 0x000f };
+    pub const PID_DOMAIN_TAG: ParameterId = ParameterId { value: 0x4014 };
     pub const PID_CONTENT_FILTER_PROPERTY: ParameterId = ParameterId { value: 0x0035 };
     pub const PID_PARTICIPANT_GUID: ParameterId = ParameterId { value: 0x0050 };
     pub const PID_GROUP_GUID: ParameterId = ParameterId { value: 0x0052 };
     pub const PID_GROUP_ENTITYID: ParameterId = ParameterId { value: 0x0053 };
     pub const PID_BUILTIN_ENDPOINT_SET: ParameterId = ParameterId { value: 0x0058 };
+    pub const PID_ENDPOINT_GUID: ParameterId = ParameterId { value: 0x005a };
     pub const PID_PROPERTY_LIST: ParameterId = ParameterId { value: 0x0059 };
     pub const PID_TYPE_MAX_SIZE_SERIALIZED: ParameterId = ParameterId { value: 0x0060 };
     pub const PID_ENTITY_NAME: ParameterId = ParameterId { value: 0x0062 };
     pub const PID_KEY_HASH: ParameterId = ParameterId { value: 0x0070 };
     pub const PID_STATUS_INFO: ParameterId = ParameterId { value: 0x0071 };
+
+    /// The parameters with the most significant bit set are specific to the
+    /// vendor which sent them and have to be interpreted only when the
+    /// vendor id of the message is known (9.6.2.2.1).
+    pub fn is_vendor_specific(&self) -> bool {
+        self.value & 0x8000 != 0
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn vendor_specific_parameters_have_most_significant_bit_set() {
+        assert!(ParameterId { value: 0x8001 }.is_vendor_specific());
+        assert!(!ParameterId::PID_STATUS_INFO.is_vendor_specific());
+    }
+
     serialization_test!( type = ParameterId,
     {
         pid_pad,
@@ -376,5 +392,23 @@ mod tests {
         ParameterId::PID_STATUS_INFO,
         le = [0x71, 0x00],
         be = [0x00, 0x71]
+    },
+    {
+        pid_domain_id,
+        ParameterId::PID_DOMAIN_ID,
+        le = [0x0f, 0x00],
+        be = [0x00, 0x0f]
+    },
+    {
+        pid_domain_tag,
+        ParameterId::PID_DOMAIN_TAG,
+        le = [0x14, 0x40],
+        be = [0x40, 0x14]
+    },
+    {
+        pid_endpoint_guid,
+        ParameterId::PID_ENDPOINT_GUID,
+        le = [0x5a, 0x00],
+        be = [0x00, 0x5a]
     });
 }
@@ -1,27 +1,1535 @@
-use crate::messages::protocol_version;
-use crate::messages::vendor_id;
-use crate::structure::locator;
+use crate::behavior::reader::ReaderAttributes;
+use crate::behavior::stateful_reader::StatefulReader;
+use crate::behavior::stateful_writer::StatefulWriter;
+use crate::behavior::writer::WriterAttributes;
+use crate::discovery::discovered_reader_data::DiscoveredReaderData;
+use crate::discovery::discovered_writer_data::DiscoveredWriterData;
+use crate::discovery::discovery_config::DiscoveryConfig;
+use crate::discovery::endpoint_matcher::EndpointMatcher;
+use crate::discovery::sedp::Sedp;
+use crate::discovery::spdp::Spdp;
+use crate::discovery::spdp_discovered_participant_data::SpdpDiscoveredParticipantData;
+use crate::discovery::static_discovery_config::StaticDiscoveryConfig;
+use crate::discovery::wlp::Wlp;
+use crate::messages::protocol_version::ProtocolVersion_t;
+use crate::messages::received_submessage::ReceivedSubmessage;
+use crate::messages::receiver::MessageReceiver;
+use crate::messages::vendor_id::VendorId_t;
+use crate::protocol::event::Event;
+use crate::protocol::protocol_core::ProtocolCore;
+use crate::protocol::transmit::Transmit;
+use crate::structure::count::Count_t;
+use crate::structure::duration::Duration_t;
+use crate::structure::endpoint::EndpointAttributes;
+use crate::structure::entity::EntityAttributes;
+use crate::structure::entity_id::EntityId_t;
+use crate::structure::guid::GUID_t;
+use crate::structure::guid_prefix::GuidPrefix_t;
+use crate::structure::locator::{LocatorList_t, Locator_t};
+use crate::structure::reliability_kind::ReliabilityKind_t;
+use crate::structure::topic_kind::TopicKind_t;
+use crate::transport::transport::Transport;
+use crate::transport::transport_registry::TransportRegistry;
+use bytes::Bytes;
+use std::collections::VecDeque;
+use std::io;
+use std::sync::Arc;
+use std::time::Instant;
 
 /// Container of all RTPS entities that share common properties and are located
 /// in a single address space.
-struct Participant {
+///
+/// The Participant owns its Endpoints through the ProtocolCore exchanging
+/// their messages and allocates the EntityIds of the user-defined ones.
+pub struct RtpsParticipant {
+    guid: GUID_t,
+
     /// Identifies the version of the RTPS protocol that the Participant uses to
     /// communicate.
-    protocol_version: protocol_version::ProtocolVersion_t,
+    protocol_version: ProtocolVersion_t,
 
     /// Identifies the vendor of the RTPS middleware that contains the
     /// Participant.
-    vendor_id: vendor_id::VendorId_t,
+    vendor_id: VendorId_t,
 
     /// Default list of unicast locators (transport, address, port combinations)
     /// that can be used to send messages to the Endpoints contained in the
-    /// Participant.These are the unicast locators that will be used in case the
-    /// Endpoint does not specify its own set of Locators.
-    default_unicast_locator_list: locator::Locator_t,
+    /// Participant. These are the unicast locators that will be used in case
+    /// the Endpoint does not specify its own set of Locators.
+    default_unicast_locator_list: LocatorList_t,
 
     /// Default list of multicast locators (transport, address, port
     /// combinations) that can be used to send messages to the Endpoints
-    /// contained in the Participant.These are the multicast locators that will
-    /// be used in case the Endpoint does not specify its own set of Locators.
-    default_multicast_locator_list: locator::Locator_t,
+    /// contained in the Participant. These are the multicast locators that
+    /// will be used in case the Endpoint does not specify its own set of
+    /// Locators.
+    default_multicast_locator_list: LocatorList_t,
+
+    /// List of unicast locators that can be used to send messages to the
+    /// built-in Endpoints contained in the Participant.
+    metatraffic_unicast_locator_list: LocatorList_t,
+
+    /// List of multicast locators that can be used to send messages to the
+    /// built-in Endpoints contained in the Participant.
+    metatraffic_multicast_locator_list: LocatorList_t,
+
+    /// The DDS domain of the Participant, announced by the participant
+    /// discovery; the announcements of other domains are ignored.
+    domain_id: u32,
+
+    /// The domain tag of the Participant, announced and matched like the
+    /// domain_id.
+    domain_tag: String,
+
+    /// The entityKey of the last user-defined Endpoint.
+    last_entity_key: u32,
+
+    protocol: ProtocolCore,
+
+    /// The participant discovery, when enabled.
+    spdp: Option<Spdp>,
+
+    /// The endpoint discovery, enabled together with the participant
+    /// discovery.
+    sedp: Option<Sedp>,
+
+    /// The writer liveliness protocol, enabled together with the
+    /// participant discovery.
+    wlp: Option<Wlp>,
+
+    /// Matches the local Endpoints with the local and discovered ones.
+    matcher: EndpointMatcher,
+
+    /// The transports the messages of the ProtocolCore are exchanged with,
+    /// by the caller driving the Participant.
+    transports: TransportRegistry,
+
+    /// Events of the ProtocolCore not consumed by the discovery and the
+    /// discovery events.
+    events: VecDeque<Event>,
+}
+
+impl RtpsParticipant {
+    /// Creates the Participant with the generated GuidPrefix_t.
+    pub fn new(vendor_id: VendorId_t) -> Self {
+        RtpsParticipant::with_guid_prefix(GuidPrefix_t::generate(vendor_id), vendor_id)
+    }
+
+    pub fn with_guid_prefix(guid_prefix: GuidPrefix_t, vendor_id: VendorId_t) -> Self {
+        RtpsParticipant {
+            guid: GUID_t {
+                guid_prefix,
+                entity_id: EntityId_t::ENTITYID_PARTICIPANT,
+            },
+            protocol_version: ProtocolVersion_t::PROTOCOLVERSION,
+            vendor_id,
+            default_unicast_locator_list: vec![],
+            default_multicast_locator_list: vec![],
+            metatraffic_unicast_locator_list: vec![],
+            metatraffic_multicast_locator_list: vec![],
+            domain_id: 0,
+            domain_tag: String::new(),
+            last_entity_key: 0,
+            protocol: ProtocolCore::new(guid_prefix, vendor_id),
+            spdp: None,
+            sedp: None,
+            wlp: None,
+            matcher: EndpointMatcher::new(),
+            transports: TransportRegistry::new(),
+            events: VecDeque::new(),
+        }
+    }
+
+    pub fn guid(&self) -> GUID_t {
+        self.guid
+    }
+
+    pub fn protocol_version(&self) -> ProtocolVersion_t {
+        self.protocol_version
+    }
+
+    pub fn vendor_id(&self) -> VendorId_t {
+        self.vendor_id
+    }
+
+    pub fn domain_id(&self) -> u32 {
+        self.domain_id
+    }
+
+    pub fn domain_tag(&self) -> &str {
+        &self.domain_tag
+    }
+
+    /// Sets the domain, used by the participant discovery enabled
+    /// afterwards.
+    pub fn set_domain(&mut self, domain_id: u32, domain_tag: String) {
+        self.domain_id = domain_id;
+        self.domain_tag = domain_tag;
+    }
+
+    pub fn default_unicast_locator_list(&self) -> &LocatorList_t {
+        &self.default_unicast_locator_list
+    }
+
+    pub fn default_multicast_locator_list(&self) -> &LocatorList_t {
+        &self.default_multicast_locator_list
+    }
+
+    pub fn metatraffic_unicast_locator_list(&self) -> &LocatorList_t {
+        &self.metatraffic_unicast_locator_list
+    }
+
+    pub fn metatraffic_multicast_locator_list(&self) -> &LocatorList_t {
+        &self.metatraffic_multicast_locator_list
+    }
+
+    /// Sets the locators of the user traffic, used by the Endpoints created
+    /// afterwards.
+    pub fn set_default_locators(
+        &mut self,
+        unicast_locator_list: LocatorList_t,
+        multicast_locator_list: LocatorList_t,
+    ) {
+        self.default_unicast_locator_list = unicast_locator_list;
+        self.default_multicast_locator_list = multicast_locator_list;
+    }
+
+    /// Sets the locators of the metatraffic (discovery).
+    pub fn set_metatraffic_locators(
+        &mut self,
+        unicast_locator_list: LocatorList_t,
+        multicast_locator_list: LocatorList_t,
+    ) {
+        self.metatraffic_unicast_locator_list = unicast_locator_list;
+        self.metatraffic_multicast_locator_list = multicast_locator_list;
+    }
+
+    /// Registers the transport; the messages are limited to the size all
+    /// the transports are able to send and the remote Endpoints are reached
+    /// through the locators of the registered transports. The locators of
+    /// the Participant are set separately.
+    pub fn add_transport(&mut self, transport: Box<dyn Transport>) {
+        self.transports.register(transport);
+        self.transports.configure(&mut self.protocol);
+    }
+
+    /// Unregisters the transport, e.g. the one failing to receive; the
+    /// remote Endpoints are reached through the remaining transports.
+    pub fn remove_transport(&mut self, transport: &Arc<dyn Transport>) {
+        self.transports.unregister(transport);
+        self.transports.configure(&mut self.protocol);
+    }
+
+    pub fn transports(&self) -> &TransportRegistry {
+        &self.transports
+    }
+
+    /// The size of the messages the registered transports are able to send.
+    pub fn max_message_size(&self) -> usize {
+        self.protocol.max_message_size()
+    }
+
+    /// Allocates the EntityId_t of the user-defined Entity of the kind, with
+    /// the entityKey unique within the Participant.
+    pub fn next_entity_id(&mut self, entity_kind: u8) -> EntityId_t {
+        self.last_entity_key += 1;
+        assert!(
+            self.last_entity_key < 1 << 24,
+            "All entity keys are allocated"
+        );
+        let [_, entity_key @ ..] = self.last_entity_key.to_be_bytes();
+        EntityId_t::new(entity_key, entity_kind)
+    }
+
+    /// Creates the user-defined Writer reachable through the default
+    /// locators. The reliable Writer sends Heartbeats starting from now.
+    /// Fails when the fragments of the Writer do not fit into the messages
+    /// of the registered transports.
+    pub fn create_writer(
+        &mut self,
+        now: Instant,
+        topic_kind: TopicKind_t,
+        reliability_level: ReliabilityKind_t,
+        writer: WriterAttributes,
+    ) -> io::Result<GUID_t> {
+        let entity_kind = match topic_kind {
+            TopicKind_t::NO_KEY => EntityId_t::ENTITYKIND_USER_DEFINED_WRITER_NO_KEY,
+            TopicKind_t::WITH_KEY => EntityId_t::ENTITYKIND_USER_DEFINED_WRITER_WITH_KEY,
+        };
+        let entity_id = self.next_entity_id(entity_kind);
+        self.add_writer(now, entity_id, topic_kind, reliability_level, writer)
+    }
+
+    /// Creates the user-defined Writer with the EntityId_t allocated by
+    /// next_entity_id, see create_writer.
+    pub fn add_writer(
+        &mut self,
+        now: Instant,
+        entity_id: EntityId_t,
+        topic_kind: TopicKind_t,
+        reliability_level: ReliabilityKind_t,
+        writer: WriterAttributes,
+    ) -> io::Result<GUID_t> {
+        if !writer.fits(self.max_message_size()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "The fragment_size does not fit into the messages of the transports",
+            ));
+        }
+        let entity = self.entity(entity_id);
+        let guid = entity.guid;
+        let endpoint = self.endpoint(topic_kind, reliability_level);
+
+        self.protocol
+            .add_writer(now, StatefulWriter::new(entity, endpoint, writer));
+        Ok(guid)
+    }
+
+    /// Creates the user-defined Reader reachable through the default
+    /// locators.
+    pub fn create_reader(
+        &mut self,
+        topic_kind: TopicKind_t,
+        reliability_level: ReliabilityKind_t,
+        reader: ReaderAttributes,
+    ) -> GUID_t {
+        let entity_kind = match topic_kind {
+            TopicKind_t::NO_KEY => EntityId_t::ENTITYKIND_USER_DEFINED_READER_NO_KEY,
+            TopicKind_t::WITH_KEY => EntityId_t::ENTITYKIND_USER_DEFINED_READER_WITH_KEY,
+        };
+        let entity_id = self.next_entity_id(entity_kind);
+        self.add_reader(entity_id, topic_kind, reliability_level, reader)
+    }
+
+    /// Creates the user-defined Reader with the EntityId_t allocated by
+    /// next_entity_id, see create_reader.
+    pub fn add_reader(
+        &mut self,
+        entity_id: EntityId_t,
+        topic_kind: TopicKind_t,
+        reliability_level: ReliabilityKind_t,
+        reader: ReaderAttributes,
+    ) -> GUID_t {
+        let entity = self.entity(entity_id);
+        let guid = entity.guid;
+        let endpoint = self.endpoint(topic_kind, reliability_level);
+
+        self.protocol
+            .add_reader(StatefulReader::new(entity, endpoint, reader));
+        guid
+    }
+
+    /// Unmatches the local Writer, withdraws its announcement and removes
+    /// it from the core.
+    pub fn delete_writer(&mut self, now: Instant, writer_guid: GUID_t) {
+        self.matcher.remove_writer(&mut self.protocol, writer_guid);
+        if let Some(sedp) = &mut self.sedp {
+            sedp.remove_local_writer(&mut self.protocol, writer_guid);
+        }
+        if let Some(wlp) = &mut self.wlp {
+            wlp.remove_local_writer(writer_guid);
+        }
+        self.protocol.remove_writer(writer_guid.entity_id);
+        self.handle_discovery_events(now);
+    }
+
+    /// Unmatches the local Reader, withdraws its announcement and removes it
+    /// from the core.
+    pub fn delete_reader(&mut self, now: Instant, reader_guid: GUID_t) {
+        self.matcher.remove_reader(&mut self.protocol, reader_guid);
+        if let Some(sedp) = &mut self.sedp {
+            sedp.remove_local_reader(&mut self.protocol, reader_guid);
+        }
+        self.protocol.remove_reader(reader_guid.entity_id);
+        self.handle_discovery_events(now);
+    }
+
+    /// Starts the Simple Participant Discovery Protocol, the Simple
+    /// Endpoint Discovery Protocol and the Writer Liveliness Protocol. The
+    /// Participant is announced with its current locators to the
+    /// announcement locators every resend_period; the remote Participants
+    /// consider it alive for lease_duration after every announcement. The
+    /// Endpoints are announced to the discovered Participants with
+    /// announce_writer and announce_reader.
+    pub fn enable_discovery(
+        &mut self,
+        now: Instant,
+        announcement_locators: LocatorList_t,
+        config: DiscoveryConfig,
+    ) {
+        let participant_data = SpdpDiscoveredParticipantData {
+            domain_id: Some(self.domain_id),
+            domain_tag: self.domain_tag.clone(),
+            protocol_version: self.protocol_version,
+            guid_prefix: self.guid.guid_prefix,
+            vendor_id: self.vendor_id,
+            expects_inline_qos: false,
+            available_builtin_endpoints: Spdp::BUILTIN_ENDPOINTS
+                | Sedp::BUILTIN_ENDPOINTS
+                | Wlp::BUILTIN_ENDPOINTS,
+            metatraffic_unicast_locator_list: self.metatraffic_unicast_locator_list.clone(),
+            metatraffic_multicast_locator_list: self.metatraffic_multicast_locator_list.clone(),
+            default_unicast_locator_list: self.default_unicast_locator_list.clone(),
+            default_multicast_locator_list: self.default_multicast_locator_list.clone(),
+            manual_liveliness_count: Count_t::from(0),
+            lease_duration: Duration_t::from(config.lease_duration),
+            user_data: vec![],
+        };
+        self.sedp = Some(Sedp::new(
+            now,
+            &mut self.protocol,
+            self.guid.guid_prefix,
+            config.heartbeat_period,
+        ));
+        self.wlp = Some(Wlp::new(
+            now,
+            &mut self.protocol,
+            self.guid.guid_prefix,
+            config.heartbeat_period,
+        ));
+        self.spdp = Some(Spdp::new(
+            now,
+            &mut self.protocol,
+            participant_data,
+            announcement_locators,
+            config.resend_period,
+        ));
+    }
+
+    /// Adds the remote Participants and Endpoints of the configuration
+    /// without the discovery protocols. The discovery events are queued as
+    /// if they were discovered and the Endpoints are matched with the local
+    /// ones. The Endpoints without locators use the default locators of
+    /// their Participant.
+    pub fn enable_static_discovery(&mut self, now: Instant, config: &StaticDiscoveryConfig) {
+        for participant_data in &config.participants {
+            self.protocol
+                .add_remote_participant(participant_data.guid_prefix);
+            self.events.push_back(Event::ParticipantDiscovered {
+                participant_data: participant_data.clone(),
+            });
+        }
+        for writer_data in &config.writers {
+            let mut writer_data = writer_data.clone();
+            use_default_locators(
+                &mut writer_data.unicast_locator_list,
+                &mut writer_data.multicast_locator_list,
+                config.participant(writer_data.remote_writer_guid.guid_prefix),
+            );
+            self.events.push_back(Event::PublicationDiscovered {
+                writer_data: writer_data.clone(),
+            });
+            self.matcher
+                .add_remote_writer(&mut self.protocol, writer_data);
+        }
+        for reader_data in &config.readers {
+            let mut reader_data = reader_data.clone();
+            use_default_locators(
+                &mut reader_data.unicast_locator_list,
+                &mut reader_data.multicast_locator_list,
+                config.participant(reader_data.remote_reader_guid.guid_prefix),
+            );
+            self.events.push_back(Event::SubscriptionDiscovered {
+                reader_data: reader_data.clone(),
+            });
+            self.matcher
+                .add_remote_reader(&mut self.protocol, reader_data);
+        }
+        self.handle_discovery_events(now);
+    }
+
+    /// Announces the deletion of the Participant with the SPDP, see
+    /// Spdp::dispose; the remote Participants lose it right away.
+    pub fn dispose(&mut self) {
+        if let Some(spdp) = &mut self.spdp {
+            spdp.dispose(&mut self.protocol);
+        }
+    }
+
+    pub fn spdp(&self) -> Option<&Spdp> {
+        self.spdp.as_ref()
+    }
+
+    pub fn sedp(&self) -> Option<&Sedp> {
+        self.sedp.as_ref()
+    }
+
+    pub fn wlp(&self) -> Option<&Wlp> {
+        self.wlp.as_ref()
+    }
+
+    pub fn wlp_mut(&mut self) -> Option<&mut Wlp> {
+        self.wlp.as_mut()
+    }
+
+    pub fn matcher(&self) -> &EndpointMatcher {
+        &self.matcher
+    }
+
+    pub fn matcher_mut(&mut self) -> &mut EndpointMatcher {
+        &mut self.matcher
+    }
+
+    /// Matches the local Writer with the Readers of its Topic and announces
+    /// it through the endpoint discovery, if enabled. Announcing the Writer
+    /// again updates its QoS. The lease of the Writer with a MANUAL
+    /// liveliness starts at now.
+    pub fn announce_writer(&mut self, now: Instant, writer_data: &DiscoveredWriterData) {
+        self.matcher
+            .add_local_writer(&mut self.protocol, writer_data.clone());
+        if let Some(sedp) = &mut self.sedp {
+            sedp.announce_writer(&mut self.protocol, writer_data);
+        }
+        if let Some(wlp) = &mut self.wlp {
+            wlp.add_local_writer(now, writer_data.remote_writer_guid, writer_data.liveliness);
+        }
+        self.handle_discovery_events(now);
+    }
+
+    /// Matches the local Reader with the Writers of its Topic and announces
+    /// it through the endpoint discovery, if enabled. Announcing the Reader
+    /// again updates its QoS.
+    pub fn announce_reader(&mut self, now: Instant, reader_data: &DiscoveredReaderData) {
+        self.matcher
+            .add_local_reader(&mut self.protocol, reader_data.clone());
+        if let Some(sedp) = &mut self.sedp {
+            sedp.announce_reader(&mut self.protocol, reader_data);
+        }
+        self.handle_discovery_events(now);
+    }
+
+    /// Asserts the liveliness of the Participant, i.e. of its Writers with
+    /// the MANUAL_BY_PARTICIPANT liveliness.
+    pub fn assert_liveliness(&mut self, now: Instant) {
+        if let Some(wlp) = &mut self.wlp {
+            wlp.assert_participant(now, &mut self.protocol);
+        }
+        self.handle_discovery_events(now);
+    }
+
+    /// Asserts the liveliness of the local Writer, to be called for every
+    /// sample written as well.
+    pub fn assert_writer_liveliness(&mut self, now: Instant, writer_guid: GUID_t) {
+        if let Some(wlp) = &mut self.wlp {
+            wlp.assert_writer(now, &mut self.protocol, writer_guid);
+        }
+        self.handle_discovery_events(now);
+    }
+
+    /// Processes the datagram received at now from the src locator, see
+    /// ProtocolCore::handle_datagram. The received announcements are
+    /// processed by the discovery.
+    pub fn handle_datagram(&mut self, now: Instant, src: Locator_t, datagram: Bytes) {
+        let submessages = MessageReceiver::decode_datagram(src.kind, datagram);
+        self.handle_submessages(now, src, submessages);
+    }
+
+    /// Processes the Submessages received by a transport at now from the src
+    /// locator, see ProtocolCore::handle_submessages.
+    pub fn handle_submessages(
+        &mut self,
+        now: Instant,
+        src: Locator_t,
+        submessages: Vec<ReceivedSubmessage>,
+    ) {
+        self.protocol.handle_submessages(now, src, submessages);
+
+        let spdp_reader_guid = GUID_t {
+            guid_prefix: self.guid.guid_prefix,
+            entity_id: Spdp::READER_ID,
+        };
+        while let Some(event) = self.protocol.poll_event() {
+            match (&mut self.spdp, &mut self.sedp, event) {
+                (Some(spdp), _, Event::DataAvailable { reader_guid })
+                    if reader_guid == spdp_reader_guid =>
+                {
+                    spdp.handle_data_available(now, &mut self.protocol)
+                }
+                (_, Some(sedp), Event::DataAvailable { reader_guid })
+                    if sedp.is_builtin_reader(reader_guid) =>
+                {
+                    sedp.handle_data_available(&mut self.protocol)
+                }
+                (_, _, event) => match (&mut self.wlp, event) {
+                    (Some(wlp), Event::DataAvailable { reader_guid })
+                        if wlp.is_builtin_reader(reader_guid) =>
+                    {
+                        wlp.handle_data_available(now, &mut self.protocol)
+                    }
+                    (Some(wlp), Event::LivelinessAsserted { writer_guid }) => {
+                        wlp.handle_liveliness_asserted(now, writer_guid)
+                    }
+                    (_, event) => self.events.push_back(event),
+                },
+            }
+        }
+        self.handle_discovery_events(now);
+    }
+
+    /// Processes all timers which expired before or at now, including the
+    /// leases of the discovered Participants and of the Writers.
+    pub fn handle_timeout(&mut self, now: Instant) {
+        self.protocol.handle_timeout(now);
+        if let Some(spdp) = &mut self.spdp {
+            spdp.handle_timeout(now);
+        }
+        if let Some(wlp) = &mut self.wlp {
+            wlp.handle_timeout(now, &mut self.protocol);
+        }
+        self.handle_discovery_events(now);
+    }
+
+    /// The earliest time at which handle_timeout has to be called.
+    pub fn poll_timeout(&self) -> Option<Instant> {
+        self.protocol
+            .poll_timeout()
+            .into_iter()
+            .chain(self.spdp.as_ref().and_then(Spdp::poll_timeout))
+            .chain(self.wlp.as_ref().and_then(Wlp::poll_timeout))
+            .min()
+    }
+
+    pub fn poll_transmit(&mut self) -> Option<Transmit> {
+        self.protocol.poll_transmit()
+    }
+
+    pub fn poll_event(&mut self) -> Option<Event> {
+        self.events
+            .pop_front()
+            .or_else(|| self.protocol.poll_event())
+    }
+
+    pub fn protocol(&self) -> &ProtocolCore {
+        &self.protocol
+    }
+
+    pub fn protocol_mut(&mut self) -> &mut ProtocolCore {
+        &mut self.protocol
+    }
+
+    /// Matches the built-in Endpoints of the SEDP and the WLP with the
+    /// discovered Participants, the local Endpoints with the discovered ones
+    /// and queues the events of the discovery.
+    fn handle_discovery_events(&mut self, now: Instant) {
+        if let Some(spdp) = &mut self.spdp {
+            while let Some(event) = spdp.poll_event() {
+                match &event {
+                    Event::ParticipantDiscovered { participant_data } => {
+                        self.protocol
+                            .add_remote_participant(participant_data.guid_prefix);
+                        if let Some(sedp) = &mut self.sedp {
+                            sedp.add_participant(&mut self.protocol, participant_data);
+                        }
+                        if let Some(wlp) = &mut self.wlp {
+                            wlp.add_participant(&mut self.protocol, participant_data);
+                        }
+                    }
+                    Event::ParticipantLost { guid_prefix } => {
+                        self.protocol.remove_remote_participant(*guid_prefix);
+                        if let Some(sedp) = &mut self.sedp {
+                            sedp.remove_participant(&mut self.protocol, *guid_prefix);
+                        }
+                        if let Some(wlp) = &mut self.wlp {
+                            wlp.remove_participant(&mut self.protocol, *guid_prefix);
+                        }
+                    }
+                    _ => (),
+                }
+                self.events.push_back(event);
+            }
+        }
+        if let Some(sedp) = &mut self.sedp {
+            while let Some(event) = sedp.poll_event() {
+                let spdp = self.spdp.as_ref();
+                let participant_data = |guid: GUID_t| {
+                    spdp.and_then(|spdp| spdp.discovered_participant(guid.guid_prefix))
+                };
+                match &event {
+                    Event::PublicationDiscovered { writer_data } => {
+                        let mut writer_data = writer_data.clone();
+                        use_default_locators(
+                            &mut writer_data.unicast_locator_list,
+                            &mut writer_data.multicast_locator_list,
+                            participant_data(writer_data.remote_writer_guid),
+                        );
+                        self.matcher
+                            .add_remote_writer(&mut self.protocol, writer_data)
+                    }
+                    Event::SubscriptionDiscovered { reader_data } => {
+                        let mut reader_data = reader_data.clone();
+                        use_default_locators(
+                            &mut reader_data.unicast_locator_list,
+                            &mut reader_data.multicast_locator_list,
+                            participant_data(reader_data.remote_reader_guid),
+                        );
+                        self.matcher
+                            .add_remote_reader(&mut self.protocol, reader_data)
+                    }
+                    Event::PublicationLost { writer_guid } => {
+                        self.matcher.remove_writer(&mut self.protocol, *writer_guid)
+                    }
+                    Event::SubscriptionLost { reader_guid } => {
+                        self.matcher.remove_reader(&mut self.protocol, *reader_guid)
+                    }
+                    _ => (),
+                }
+                self.events.push_back(event);
+            }
+        }
+        while let Some(event) = self.matcher.poll_event() {
+            match (&mut self.wlp, &event) {
+                (
+                    Some(wlp),
+                    Event::SubscriptionMatched {
+                        reader_guid,
+                        writer_guid,
+                    },
+                ) => {
+                    if let Some(writer_data) = self.matcher.writer_data(*writer_guid) {
+                        wlp.writer_matched(now, *reader_guid, *writer_guid, writer_data.liveliness);
+                    }
+                }
+                (
+                    Some(wlp),
+                    Event::SubscriptionUnmatched {
+                        reader_guid,
+                        writer_guid,
+                    },
+                ) => wlp.writer_unmatched(*reader_guid, *writer_guid),
+                _ => (),
+            }
+            self.events.push_back(event);
+        }
+        if let Some(wlp) = &mut self.wlp {
+            while let Some(event) = wlp.poll_event() {
+                self.events.push_back(event);
+            }
+        }
+    }
+
+    fn entity(&self, entity_id: EntityId_t) -> EntityAttributes {
+        EntityAttributes {
+            guid: GUID_t {
+                guid_prefix: self.guid.guid_prefix,
+                entity_id,
+            },
+        }
+    }
+
+    fn endpoint(
+        &self,
+        topic_kind: TopicKind_t,
+        reliability_level: ReliabilityKind_t,
+    ) -> EndpointAttributes {
+        EndpointAttributes {
+            topic_kind,
+            reliability_level,
+            unicast_locator_list: self.default_unicast_locator_list.clone(),
+            multicast_locator_list: self.default_multicast_locator_list.clone(),
+        }
+    }
+}
+
+/// Replaces the empty locators of a remote Endpoint by the default locators
+/// of its Participant.
+fn use_default_locators(
+    unicast_locator_list: &mut LocatorList_t,
+    multicast_locator_list: &mut LocatorList_t,
+    participant_data: Option<&SpdpDiscoveredParticipantData>,
+) {
+    if let (true, Some(participant_data)) = (
+        unicast_locator_list.is_empty() && multicast_locator_list.is_empty(),
+        participant_data,
+    ) {
+        *unicast_locator_list = participant_data.default_unicast_locator_list.clone();
+        *multicast_locator_list = participant_data.default_multicast_locator_list.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::behavior::writer::Writer;
+    use crate::dds::qos::liveliness_qos_policy::LivelinessQosPolicyKind;
+    use crate::dds::qos::qos_policy_id::QosPolicyId_t;
+    use crate::dds::qos::reliability_qos_policy::{ReliabilityQosPolicy, ReliabilityQosPolicyKind};
+    use crate::structure::change_kind::ChangeKind_t;
+    use crate::structure::data::Data;
+    use crate::structure::duration::Duration_t;
+    use crate::structure::endpoint::Endpoint;
+    use crate::structure::entity::Entity;
+    use crate::structure::history_cache::HistoryCache;
+    use crate::structure::instance_handle::InstanceHandle_t;
+    use crate::structure::locator::{Ipv4Addr, Locator_t, SocketAddr};
+    use crate::structure::sequence_number::SequenceNumber_t;
+    use crate::transport::simulated_network::{SimulatedNetwork, SimulatedTransport};
+    use std::time::Duration;
+
+    const VENDOR_ID: VendorId_t = VendorId_t {
+        vendor_id: [0x01, 0x0F],
+    };
+
+    fn writer_attributes() -> WriterAttributes {
+        WriterAttributes {
+            push_mode: true,
+            heartbeat_period: Duration_t::from(std::time::Duration::from_secs(1)),
+            nack_response_delay: Duration_t::DURATION_ZERO,
+            nack_suppression_duration: Duration_t::DURATION_ZERO,
+            last_change_sequence_number: SequenceNumber_t::from(0),
+            data_max_size_serialized: 1024,
+            fragment_size: 1024,
+            writer_cache: HistoryCache::new(),
+        }
+    }
+
+    fn reader_attributes() -> ReaderAttributes {
+        ReaderAttributes {
+            expects_inline_qos: false,
+            heartbeat_response_delay: Duration_t::DURATION_ZERO,
+            heartbeat_suppression_duration: Duration_t::DURATION_ZERO,
+            reader_cache: HistoryCache::new(),
+        }
+    }
+
+    #[test]
+    fn participant_has_generated_guid_prefix() {
+        let first = RtpsParticipant::new(VENDOR_ID);
+        let second = RtpsParticipant::new(VENDOR_ID);
+
+        assert_ne!(first.guid(), second.guid());
+        assert_eq!(EntityId_t::ENTITYID_PARTICIPANT, first.guid().entity_id);
+        assert_eq!([0x01, 0x0F], first.guid().guid_prefix.entity_key[..2]);
+        assert_eq!(first.guid().guid_prefix, first.protocol().guid_prefix());
+        assert_eq!(ProtocolVersion_t::PROTOCOLVERSION, first.protocol_version());
+        assert_eq!(VENDOR_ID, first.vendor_id());
+    }
+
+    #[test]
+    fn endpoints_have_unique_entity_ids_of_their_kind() {
+        let mut participant = RtpsParticipant::new(VENDOR_ID);
+
+        let writer_with_key = participant
+            .create_writer(
+                Instant::now(),
+                TopicKind_t::WITH_KEY,
+                ReliabilityKind_t::RELIABLE,
+                writer_attributes(),
+            )
+            .unwrap();
+        let writer_no_key = participant
+            .create_writer(
+                Instant::now(),
+                TopicKind_t::NO_KEY,
+                ReliabilityKind_t::BEST_EFFORT,
+                writer_attributes(),
+            )
+            .unwrap();
+        let reader_no_key = participant.create_reader(
+            TopicKind_t::NO_KEY,
+            ReliabilityKind_t::RELIABLE,
+            reader_attributes(),
+        );
+        let reader_with_key = participant.create_reader(
+            TopicKind_t::WITH_KEY,
+            ReliabilityKind_t::RELIABLE,
+            reader_attributes(),
+        );
+
+        assert_eq!(
+            EntityId_t::new(
+                [0x00, 0x00, 0x01],
+                EntityId_t::ENTITYKIND_USER_DEFINED_WRITER_WITH_KEY
+            ),
+            writer_with_key.entity_id
+        );
+        assert_eq!(
+            EntityId_t::new(
+                [0x00, 0x00, 0x02],
+                EntityId_t::ENTITYKIND_USER_DEFINED_WRITER_NO_KEY
+            ),
+            writer_no_key.entity_id
+        );
+        assert_eq!(
+            EntityId_t::new(
+                [0x00, 0x00, 0x03],
+                EntityId_t::ENTITYKIND_USER_DEFINED_READER_NO_KEY
+            ),
+            reader_no_key.entity_id
+        );
+        assert_eq!(
+            EntityId_t::new(
+                [0x00, 0x00, 0x04],
+                EntityId_t::ENTITYKIND_USER_DEFINED_READER_WITH_KEY
+            ),
+            reader_with_key.entity_id
+        );
+        assert_eq!(participant.guid().guid_prefix, reader_with_key.guid_prefix);
+
+        let protocol = participant.protocol_mut();
+        assert!(protocol.writer_mut(writer_with_key.entity_id).is_some());
+        assert!(protocol.writer_mut(writer_no_key.entity_id).is_some());
+        assert_eq!(
+            reader_no_key,
+            protocol
+                .reader(reader_no_key.entity_id)
+                .unwrap()
+                .as_entity()
+                .guid
+        );
+        assert!(protocol.reader(reader_with_key.entity_id).is_some());
+    }
+
+    #[test]
+    fn endpoints_use_default_locators() {
+        let unicast_locator = Locator_t::from(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 7411));
+        let multicast_locator =
+            Locator_t::from(SocketAddr::new(Ipv4Addr::new(239, 255, 0, 1).into(), 7401));
+        let metatraffic_locator =
+            Locator_t::from(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 7410));
+        let mut participant = RtpsParticipant::new(VENDOR_ID);
+        participant.set_default_locators(vec![unicast_locator], vec![multicast_locator]);
+        participant.set_metatraffic_locators(vec![metatraffic_locator], vec![]);
+
+        let reader = participant.create_reader(
+            TopicKind_t::NO_KEY,
+            ReliabilityKind_t::RELIABLE,
+            reader_attributes(),
+        );
+
+        let endpoint = participant
+            .protocol()
+            .reader(reader.entity_id)
+            .unwrap()
+            .as_endpoint();
+        assert_eq!(vec![unicast_locator], endpoint.unicast_locator_list);
+        assert_eq!(vec![multicast_locator], endpoint.multicast_locator_list);
+        assert_eq!(
+            &vec![metatraffic_locator],
+            participant.metatraffic_unicast_locator_list()
+        );
+    }
+
+    #[test]
+    fn writers_with_fragments_not_fitting_into_messages_are_not_created() {
+        let mut participant = RtpsParticipant::new(VENDOR_ID);
+        let max_fragment_size = WriterAttributes::max_fragment_size(participant.max_message_size());
+
+        for fragment_size in [0, max_fragment_size + 1] {
+            let error = participant
+                .create_writer(
+                    Instant::now(),
+                    TopicKind_t::NO_KEY,
+                    ReliabilityKind_t::RELIABLE,
+                    WriterAttributes {
+                        fragment_size,
+                        ..writer_attributes()
+                    },
+                )
+                .unwrap_err();
+            assert_eq!(io::ErrorKind::InvalidInput, error.kind());
+        }
+        assert!(participant
+            .create_writer(
+                Instant::now(),
+                TopicKind_t::NO_KEY,
+                ReliabilityKind_t::RELIABLE,
+                WriterAttributes {
+                    fragment_size: max_fragment_size,
+                    ..writer_attributes()
+                },
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn deleted_endpoints_are_unmatched_and_removed() {
+        let now = Instant::now();
+        let mut participant = RtpsParticipant::new(VENDOR_ID);
+        let writer_guid = participant
+            .create_writer(
+                now,
+                TopicKind_t::NO_KEY,
+                ReliabilityKind_t::RELIABLE,
+                writer_attributes(),
+            )
+            .unwrap();
+        participant.announce_writer(
+            now,
+            &DiscoveredWriterData::new(writer_guid, "topic", "type"),
+        );
+        let reader_guid = participant.create_reader(
+            TopicKind_t::NO_KEY,
+            ReliabilityKind_t::BEST_EFFORT,
+            reader_attributes(),
+        );
+        participant.announce_reader(
+            now,
+            &DiscoveredReaderData::new(reader_guid, "topic", "type"),
+        );
+        assert!(participant.matcher().is_matched(writer_guid, reader_guid));
+        events(&mut participant);
+
+        participant.delete_writer(now, writer_guid);
+
+        assert!(!participant.matcher().is_matched(writer_guid, reader_guid));
+        assert!(
+            events(&mut participant).contains(&Event::SubscriptionUnmatched {
+                reader_guid,
+                writer_guid
+            })
+        );
+        assert!(participant
+            .protocol_mut()
+            .writer_mut(writer_guid.entity_id)
+            .is_none());
+
+        participant.delete_reader(now, reader_guid);
+        assert!(participant
+            .protocol()
+            .reader(reader_guid.entity_id)
+            .is_none());
+    }
+
+    /// Runs the Participants attached to the network until the time.
+    fn run(
+        network: &SimulatedNetwork,
+        participants: &mut [(RtpsParticipant, SimulatedTransport)],
+        until: Instant,
+    ) {
+        loop {
+            let now = network.now();
+            for (participant, transport) in participants.iter_mut() {
+                if participant
+                    .poll_timeout()
+                    .is_some_and(|timeout| timeout <= now)
+                {
+                    participant.handle_timeout(now);
+                }
+                while let Some(transmit) = participant.poll_transmit() {
+                    transport
+                        .send_datagram(transmit.destination, transmit.contents)
+                        .unwrap();
+                }
+            }
+            network.advance_to(now);
+
+            let mut received = false;
+            for (participant, transport) in participants.iter_mut() {
+                while let Some((src, datagram)) = transport.try_recv() {
+                    participant.handle_datagram(now, src, datagram);
+                    received = true;
+                }
+            }
+            if received {
+                continue;
+            }
+
+            let next = participants
+                .iter()
+                .filter_map(|(participant, _)| participant.poll_timeout())
+                .chain(network.next_delivery())
+                .min();
+            match next {
+                Some(next) if next <= until => network.advance_to(next),
+                _ => break,
+            }
+        }
+        network.advance_to(until);
+    }
+
+    fn events(participant: &mut RtpsParticipant) -> Vec<Event> {
+        std::iter::from_fn(|| participant.poll_event()).collect()
+    }
+
+    #[test]
+    fn participants_discover_and_lose_each_other() {
+        let start = Instant::now();
+        let network = SimulatedNetwork::new(7, start);
+        let multicast_locator =
+            Locator_t::from(SocketAddr::new(Ipv4Addr::new(239, 255, 0, 1).into(), 7400));
+        let mut participants: Vec<(RtpsParticipant, SimulatedTransport)> = (0..2)
+            .map(|index| {
+                let unicast_locator =
+                    Locator_t::from(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 7410 + index));
+                let mut participant = RtpsParticipant::new(VENDOR_ID);
+                participant
+                    .set_metatraffic_locators(vec![unicast_locator], vec![multicast_locator]);
+                participant.enable_discovery(
+                    start,
+                    vec![multicast_locator],
+                    DiscoveryConfig {
+                        resend_period: Duration::from_secs(1),
+                        lease_duration: Duration::from_secs(3),
+                        heartbeat_period: Duration::from_secs(1),
+                    },
+                );
+                let transport = network.transport(unicast_locator, &[multicast_locator]);
+                (participant, transport)
+            })
+            .collect();
+        let guid_prefixes: Vec<GuidPrefix_t> = participants
+            .iter()
+            .map(|(participant, _)| participant.guid().guid_prefix)
+            .collect();
+
+        run(&network, &mut participants, start + Duration::from_secs(10));
+
+        for (index, (participant, _)) in participants.iter_mut().enumerate() {
+            let remote_guid_prefix = guid_prefixes[1 - index];
+            match &events(participant)[..] {
+                [Event::ParticipantDiscovered { participant_data }] => {
+                    assert_eq!(remote_guid_prefix, participant_data.guid_prefix);
+                    assert_eq!(VENDOR_ID, participant_data.vendor_id);
+                    assert_eq!(Some(0), participant_data.domain_id);
+                    assert_eq!(0xC3F, participant_data.available_builtin_endpoints.bits());
+                }
+                other => panic!("Unexpected events {:?}", other),
+            }
+        }
+
+        network.partition(&[participants[0].1.locator()]);
+        run(&network, &mut participants, start + Duration::from_secs(20));
+
+        for (index, (participant, _)) in participants.iter_mut().enumerate() {
+            assert_eq!(
+                vec![Event::ParticipantLost {
+                    guid_prefix: guid_prefixes[1 - index]
+                }],
+                events(participant)
+            );
+            assert_eq!(
+                0,
+                participant
+                    .spdp()
+                    .unwrap()
+                    .discovered_participants()
+                    .count()
+            );
+        }
+    }
+
+    /// Participants with the discovery enabled, their default locators are
+    /// the metatraffic unicast locators.
+    fn discovering_participants(
+        network: &SimulatedNetwork,
+        start: Instant,
+    ) -> Vec<(RtpsParticipant, SimulatedTransport)> {
+        let multicast_locator =
+            Locator_t::from(SocketAddr::new(Ipv4Addr::new(239, 255, 0, 1).into(), 7400));
+        (0..2)
+            .map(|index| {
+                let unicast_locator =
+                    Locator_t::from(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 7410 + index));
+                let mut participant = RtpsParticipant::new(VENDOR_ID);
+                participant
+                    .set_metatraffic_locators(vec![unicast_locator], vec![multicast_locator]);
+                participant.set_default_locators(vec![unicast_locator], vec![]);
+                participant.enable_discovery(
+                    start,
+                    vec![multicast_locator],
+                    DiscoveryConfig {
+                        resend_period: Duration::from_secs(1),
+                        lease_duration: Duration::from_secs(3),
+                        heartbeat_period: Duration::from_secs(1),
+                    },
+                );
+                let transport = network.transport(unicast_locator, &[multicast_locator]);
+                (participant, transport)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn endpoints_are_discovered_and_lost_with_their_participant() {
+        let start = Instant::now();
+        let network = SimulatedNetwork::new(11, start);
+        let mut participants = discovering_participants(&network, start);
+
+        let writer_guid = participants[0]
+            .0
+            .create_writer(
+                start,
+                TopicKind_t::WITH_KEY,
+                ReliabilityKind_t::RELIABLE,
+                writer_attributes(),
+            )
+            .unwrap();
+        let writer_data = DiscoveredWriterData::new(writer_guid, "topic", "type");
+        participants[0].0.announce_writer(start, &writer_data);
+        let reader_guid = participants[1].0.create_reader(
+            TopicKind_t::WITH_KEY,
+            ReliabilityKind_t::RELIABLE,
+            reader_attributes(),
+        );
+        let reader_data = DiscoveredReaderData::new(reader_guid, "topic", "type");
+        participants[1].0.announce_reader(start, &reader_data);
+
+        run(&network, &mut participants, start + Duration::from_secs(10));
+
+        let first_events = events(&mut participants[0].0);
+        assert!(first_events.contains(&Event::SubscriptionDiscovered {
+            reader_data: reader_data.clone()
+        }));
+        let second_events = events(&mut participants[1].0);
+        assert!(second_events.contains(&Event::PublicationDiscovered {
+            writer_data: writer_data.clone()
+        }));
+        assert_eq!(
+            vec![&writer_data],
+            participants[1]
+                .0
+                .sedp()
+                .unwrap()
+                .discovered_writers()
+                .collect::<Vec<_>>()
+        );
+
+        network.partition(&[participants[0].1.locator()]);
+        run(&network, &mut participants, start + Duration::from_secs(20));
+
+        assert!(events(&mut participants[0].0).contains(&Event::SubscriptionLost { reader_guid }));
+        assert!(events(&mut participants[1].0).contains(&Event::PublicationLost { writer_guid }));
+    }
+
+    #[test]
+    fn deleted_reader_is_unmatched_by_remote_writer() {
+        let start = Instant::now();
+        let network = SimulatedNetwork::new(29, start);
+        let mut participants = discovering_participants(&network, start);
+
+        let writer_guid = participants[0]
+            .0
+            .create_writer(
+                start,
+                TopicKind_t::WITH_KEY,
+                ReliabilityKind_t::RELIABLE,
+                writer_attributes(),
+            )
+            .unwrap();
+        participants[0].0.announce_writer(
+            start,
+            &DiscoveredWriterData::new(writer_guid, "topic", "type"),
+        );
+        let reader_guid = participants[1].0.create_reader(
+            TopicKind_t::WITH_KEY,
+            ReliabilityKind_t::RELIABLE,
+            reader_attributes(),
+        );
+        let mut reader_data = DiscoveredReaderData::new(reader_guid, "topic", "type");
+        reader_data.reliability.kind = ReliabilityQosPolicyKind::RELIABLE;
+        participants[1].0.announce_reader(start, &reader_data);
+        run(&network, &mut participants, start + Duration::from_secs(5));
+        assert!(
+            events(&mut participants[0].0).contains(&Event::PublicationMatched {
+                writer_guid,
+                reader_guid
+            })
+        );
+
+        participants[1]
+            .0
+            .delete_reader(start + Duration::from_secs(5), reader_guid);
+        run(&network, &mut participants, start + Duration::from_secs(6));
+
+        let first_events = events(&mut participants[0].0);
+        assert!(first_events.contains(&Event::SubscriptionLost { reader_guid }));
+        assert!(first_events.contains(&Event::PublicationUnmatched {
+            writer_guid,
+            reader_guid
+        }));
+        let writer = participants[0]
+            .0
+            .protocol_mut()
+            .writer_mut(writer_guid.entity_id)
+            .unwrap();
+        assert!(writer.matched_reader_lookup(reader_guid).next().is_none());
+    }
+
+    #[test]
+    fn discovered_endpoints_are_matched_and_exchange_data() {
+        let start = Instant::now();
+        let network = SimulatedNetwork::new(13, start);
+        let mut participants = discovering_participants(&network, start);
+
+        let writer_guid = participants[0]
+            .0
+            .create_writer(
+                start,
+                TopicKind_t::WITH_KEY,
+                ReliabilityKind_t::RELIABLE,
+                writer_attributes(),
+            )
+            .unwrap();
+        participants[0].0.announce_writer(
+            start,
+            &DiscoveredWriterData::new(writer_guid, "topic", "type"),
+        );
+        let reader_guid = participants[1].0.create_reader(
+            TopicKind_t::WITH_KEY,
+            ReliabilityKind_t::RELIABLE,
+            reader_attributes(),
+        );
+        let mut reader_data = DiscoveredReaderData::new(reader_guid, "topic", "type");
+        reader_data.reliability.kind = ReliabilityQosPolicyKind::RELIABLE;
+        participants[1].0.announce_reader(start, &reader_data);
+        let best_effort_writer_guid = participants[1]
+            .0
+            .create_writer(
+                start,
+                TopicKind_t::WITH_KEY,
+                ReliabilityKind_t::BEST_EFFORT,
+                writer_attributes(),
+            )
+            .unwrap();
+        let mut best_effort_writer_data =
+            DiscoveredWriterData::new(best_effort_writer_guid, "topic", "type");
+        best_effort_writer_data.reliability = ReliabilityQosPolicy::default();
+        participants[1]
+            .0
+            .announce_writer(start, &best_effort_writer_data);
+
+        run(&network, &mut participants, start + Duration::from_secs(5));
+
+        assert!(
+            events(&mut participants[0].0).contains(&Event::PublicationMatched {
+                writer_guid,
+                reader_guid
+            })
+        );
+        let second_events = events(&mut participants[1].0);
+        assert!(second_events.contains(&Event::SubscriptionMatched {
+            reader_guid,
+            writer_guid
+        }));
+        // The local best-effort Writer does not satisfy the reliable Reader
+        assert!(second_events.contains(&Event::RequestedIncompatibleQos {
+            reader_guid,
+            writer_guid: best_effort_writer_guid,
+            policy_id: QosPolicyId_t::RELIABILITY_QOS_POLICY_ID,
+        }));
+
+        let writer = participants[0]
+            .0
+            .protocol_mut()
+            .writer_mut(writer_guid.entity_id)
+            .unwrap();
+        let change = writer.new_change(
+            ChangeKind_t::ALIVE,
+            Data::from(Bytes::from_static(b"sample")),
+            InstanceHandle_t::default(),
+        );
+        writer.as_writer_mut().writer_cache.add_change(change);
+        run(&network, &mut participants, start + Duration::from_secs(6));
+
+        assert_eq!(
+            vec![Event::DataAvailable { reader_guid }],
+            events(&mut participants[1].0)
+        );
+    }
+
+    #[test]
+    fn statically_configured_endpoints_are_matched_and_exchange_data() {
+        let start = Instant::now();
+        let network = SimulatedNetwork::new(17, start);
+        let mut participants: Vec<(RtpsParticipant, SimulatedTransport)> = (0..2)
+            .map(|index| {
+                let unicast_locator =
+                    Locator_t::from(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 7410 + index));
+                let guid_prefix =
+                    GuidPrefix_t::from([0x01, 0x0F, 0, 0, 0, 0, 0, 0, 0, 0, 0, index as u8]);
+                let mut participant = RtpsParticipant::with_guid_prefix(guid_prefix, VENDOR_ID);
+                participant.set_default_locators(vec![unicast_locator], vec![]);
+                let transport = network.transport(unicast_locator, &[]);
+                (participant, transport)
+            })
+            .collect();
+
+        let writer_guid = participants[0]
+            .0
+            .create_writer(
+                start,
+                TopicKind_t::WITH_KEY,
+                ReliabilityKind_t::RELIABLE,
+                writer_attributes(),
+            )
+            .unwrap();
+        participants[0].0.announce_writer(
+            start,
+            &DiscoveredWriterData::new(writer_guid, "topic", "type"),
+        );
+        let reader_guid = participants[1].0.create_reader(
+            TopicKind_t::WITH_KEY,
+            ReliabilityKind_t::RELIABLE,
+            reader_attributes(),
+        );
+        participants[1].0.announce_reader(
+            start,
+            &DiscoveredReaderData::new(reader_guid, "topic", "type"),
+        );
+
+        participants[0].0.enable_static_discovery(
+            start,
+            &StaticDiscoveryConfig::from_toml(
+                r#"
+                [[participant]]
+                guid_prefix = "010f00000000000000000001"
+                default_unicast_locator = ["127.0.0.1:7411"]
+
+                [[participant.reader]]
+                entity_id = "00000107"
+                topic = "topic"
+                type = "type"
+                "#,
+            )
+            .unwrap(),
+        );
+        participants[1].0.enable_static_discovery(
+            start,
+            &StaticDiscoveryConfig::from_toml(
+                r#"
+                [[participant]]
+                guid_prefix = "010f00000000000000000000"
+                default_unicast_locator = ["127.0.0.1:7410"]
+
+                [[participant.writer]]
+                entity_id = "00000102"
+                topic = "topic"
+                type = "type"
+                reliability = "RELIABLE"
+                "#,
+            )
+            .unwrap(),
+        );
+
+        match &events(&mut participants[0].0)[..] {
+            [Event::ParticipantDiscovered { participant_data }, Event::SubscriptionDiscovered { reader_data }, Event::PublicationMatched {
+                writer_guid: matched_writer_guid,
+                reader_guid: matched_reader_guid,
+            }] => {
+                assert_eq!(reader_guid.guid_prefix, participant_data.guid_prefix);
+                assert_eq!(
+                    participant_data.default_unicast_locator_list,
+                    reader_data.unicast_locator_list
+                );
+                assert_eq!(
+                    (writer_guid, reader_guid),
+                    (*matched_writer_guid, *matched_reader_guid)
+                );
+            }
+            other => panic!("Unexpected events {:?}", other),
+        }
+        assert!(
+            events(&mut participants[1].0).contains(&Event::SubscriptionMatched {
+                reader_guid,
+                writer_guid
+            })
+        );
+
+        let writer = participants[0]
+            .0
+            .protocol_mut()
+            .writer_mut(writer_guid.entity_id)
+            .unwrap();
+        let change = writer.new_change(
+            ChangeKind_t::ALIVE,
+            Data::from(Bytes::from_static(b"sample")),
+            InstanceHandle_t::default(),
+        );
+        writer.as_writer_mut().writer_cache.add_change(change);
+        run(&network, &mut participants, start + Duration::from_secs(1));
+
+        assert_eq!(
+            vec![Event::DataAvailable { reader_guid }],
+            events(&mut participants[1].0)
+        );
+    }
+
+    #[test]
+    fn liveliness_of_matched_writers_is_tracked() {
+        let start = Instant::now();
+        let network = SimulatedNetwork::new(19, start);
+        let mut participants = discovering_participants(&network, start);
+
+        let mut writer_data = |kind, lease_duration| {
+            let writer_guid = participants[0]
+                .0
+                .create_writer(
+                    start,
+                    TopicKind_t::WITH_KEY,
+                    ReliabilityKind_t::RELIABLE,
+                    writer_attributes(),
+                )
+                .unwrap();
+            let mut writer_data = DiscoveredWriterData::new(writer_guid, "topic", "type");
+            writer_data.liveliness.kind = kind;
+            writer_data.liveliness.lease_duration =
+                Duration_t::from(Duration::from_secs(lease_duration));
+            writer_data
+        };
+        let automatic_writer_data = writer_data(LivelinessQosPolicyKind::AUTOMATIC, 1);
+        let manual_writer_data = writer_data(LivelinessQosPolicyKind::MANUAL_BY_TOPIC, 2);
+        let automatic_writer_guid = automatic_writer_data.remote_writer_guid;
+        let manual_writer_guid = manual_writer_data.remote_writer_guid;
+        participants[0]
+            .0
+            .announce_writer(start, &automatic_writer_data);
+        participants[0]
+            .0
+            .announce_writer(start, &manual_writer_data);
+        let reader_guid = participants[1].0.create_reader(
+            TopicKind_t::WITH_KEY,
+            ReliabilityKind_t::RELIABLE,
+            reader_attributes(),
+        );
+        participants[1].0.announce_reader(
+            start,
+            &DiscoveredReaderData::new(reader_guid, "topic", "type"),
+        );
+        let changed = |writer_guid, alive| Event::LivelinessChanged {
+            reader_guid,
+            writer_guid,
+            alive,
+        };
+
+        run(&network, &mut participants, start + Duration::from_secs(6));
+
+        // Only the Writer with the MANUAL_BY_TOPIC liveliness is not asserted
+        let first_events = events(&mut participants[0].0);
+        assert!(first_events.contains(&Event::LivelinessLost {
+            writer_guid: manual_writer_guid
+        }));
+        assert!(!first_events.contains(&Event::LivelinessLost {
+            writer_guid: automatic_writer_guid
+        }));
+        let second_events: Vec<Event> = events(&mut participants[1].0)
+            .into_iter()
+            .filter(|event| matches!(event, Event::LivelinessChanged { .. }))
+            .collect();
+        assert_eq!(
+            vec![
+                changed(automatic_writer_guid, true),
+                changed(manual_writer_guid, true),
+                changed(manual_writer_guid, false),
+            ],
+            second_events
+        );
+
+        let now = network.now();
+        participants[0]
+            .0
+            .assert_writer_liveliness(now, manual_writer_guid);
+        run(&network, &mut participants, start + Duration::from_secs(7));
+
+        assert_eq!(
+            vec![changed(manual_writer_guid, true)],
+            events(&mut participants[1].0)
+        );
+        let status = participants[1]
+            .0
+            .wlp_mut()
+            .unwrap()
+            .liveliness_changed_status(reader_guid)
+            .unwrap();
+        assert_eq!((2, 0), (status.alive_count, status.not_alive_count));
+    }
 }
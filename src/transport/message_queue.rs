@@ -0,0 +1,119 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use tokio::sync::Notify;
+
+/// Queue of at most capacity items passed between the tasks of a transport.
+/// When the queue is full, the oldest item is dropped to make room for the
+/// new one, as a datagram lost by UDP; the reliable protocol repairs the
+/// loss.
+pub struct MessageQueue<T> {
+    capacity: usize,
+    /// None once the queue is closed.
+    items: Mutex<Option<VecDeque<T>>>,
+    notify: Notify,
+}
+
+impl<T> MessageQueue<T> {
+    /// The capacity must not be zero.
+    pub fn new(capacity: usize) -> Self {
+        MessageQueue {
+            capacity,
+            items: Mutex::new(Some(VecDeque::with_capacity(capacity))),
+            notify: Notify::new(),
+        }
+    }
+
+    /// Appends the item; returns the oldest item dropped to make room for
+    /// it when the queue is full. Fails with the item once the queue is
+    /// closed.
+    pub fn push(&self, item: T) -> Result<Option<T>, T> {
+        let mut items = self.items.lock().unwrap();
+        let items = match items.as_mut() {
+            Some(items) => items,
+            None => return Err(item),
+        };
+        let dropped = if items.len() >= self.capacity {
+            items.pop_front()
+        } else {
+            None
+        };
+        items.push_back(item);
+        self.notify.notify_one();
+        Ok(dropped)
+    }
+
+    /// Waits for the oldest item. Returns None once the queue is closed.
+    pub async fn pop(&self) -> Option<T> {
+        loop {
+            match self.items.lock().unwrap().as_mut() {
+                Some(items) => {
+                    if let Some(item) = items.pop_front() {
+                        return Some(item);
+                    }
+                }
+                None => return None,
+            }
+            self.notify.notified().await;
+        }
+    }
+
+    /// Drops the queued items; the items pushed afterwards are rejected.
+    pub fn close(&self) {
+        *self.items.lock().unwrap() = None;
+        self.notify.notify_waiters();
+        self.notify.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn oldest_items_are_dropped_when_full() {
+        let queue = MessageQueue::new(2);
+        assert_eq!(Ok(None), queue.push(1));
+        assert_eq!(Ok(None), queue.push(2));
+        assert_eq!(Ok(Some(1)), queue.push(3));
+
+        assert_eq!(Some(2), queue.pop().await);
+        assert_eq!(Some(3), queue.pop().await);
+        assert_eq!(Ok(None), queue.push(4));
+        assert_eq!(Some(4), queue.pop().await);
+    }
+
+    #[tokio::test]
+    async fn items_are_awaited() {
+        let queue = Arc::new(MessageQueue::new(2));
+        let consumer = tokio::spawn({
+            let queue = queue.clone();
+            async move { queue.pop().await }
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        queue.push(1).unwrap();
+
+        assert_eq!(Some(1), consumer.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn closed_queue_rejects_items() {
+        let queue = Arc::new(MessageQueue::new(2));
+        queue.push(1).unwrap();
+        let consumer = tokio::spawn({
+            let queue = queue.clone();
+            async move {
+                queue.pop().await;
+                queue.pop().await
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        queue.close();
+
+        assert_eq!(None, consumer.await.unwrap());
+        assert_eq!(Err(2), queue.push(2));
+    }
+}
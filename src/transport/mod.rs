@@ -0,0 +1,16 @@
+mod message_queue;
+pub mod network_conditions;
+#[cfg(target_os = "linux")]
+mod shm_segment;
+#[cfg(target_os = "linux")]
+pub mod shm_transport;
+pub mod simulated_network;
+mod tcp_frame;
+mod tcp_frame_codec;
+pub mod tcp_v4_transport;
+#[allow(clippy::module_inception)]
+pub mod transport;
+pub(crate) mod transport_registry;
+mod udp_sockets;
+pub mod udp_v4_transport;
+pub mod udp_v6_transport;
@@ -0,0 +1,22 @@
+use std::time::Duration;
+
+/// Impairments applied by the SimulatedNetwork to each datagram. The
+/// default conditions describe a perfect network.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NetworkConditions {
+    /// Probability that the datagram is lost.
+    pub loss: f64,
+
+    /// Probability that the datagram is delivered twice.
+    pub duplication: f64,
+
+    /// Probability that the datagram is held back by reordering_delay, so
+    /// datagrams sent after it may overtake it.
+    pub reordering: f64,
+    pub reordering_delay: Duration,
+
+    /// Time after which the datagram is delivered, extended by a random
+    /// duration up to jitter.
+    pub latency: Duration,
+    pub jitter: Duration,
+}
@@ -0,0 +1,429 @@
+use bytes::Bytes;
+use std::ffi::CString;
+use std::io;
+use std::mem::size_of;
+use std::ptr;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Marks the segment as initialized by its creator ("RTPSSHM1").
+const SEGMENT_MAGIC: u64 = 0x5254_5053_5348_4d31;
+
+#[repr(C)]
+struct SegmentHeader {
+    magic: AtomicU64,
+    capacity: AtomicU32,
+    buffer_size: AtomicU32,
+    enqueue_position: AtomicU64,
+    dequeue_position: AtomicU64,
+    /// Futex word incremented after each enqueued message.
+    notification: AtomicU32,
+}
+
+/// Describes the message stored in the buffer with the same index. The
+/// sequence tells whether the slot is free for the position being enqueued
+/// or holds the message for the position being dequeued.
+#[repr(C)]
+struct Descriptor {
+    sequence: AtomicU64,
+    source_port: AtomicU32,
+    length: AtomicU32,
+}
+
+/// POSIX shared memory segment holding a bounded lock-free ring of message
+/// descriptors, each with its own message buffer.
+///
+/// The segment is created by the receiving Participant; any process on the
+/// same host may open it and push messages. Pushing never blocks: when the
+/// ring is full the message is dropped, as a UDP datagram would be.
+pub struct ShmSegment {
+    name: CString,
+    address: *mut u8,
+    size: usize,
+    owner: bool,
+}
+
+// The shared state is only accessed through atomics or through buffers owned
+// by the ring protocol.
+unsafe impl Send for ShmSegment {}
+unsafe impl Sync for ShmSegment {}
+
+impl ShmSegment {
+    /// Number of descriptors in the ring.
+    pub const CAPACITY: u32 = 64;
+
+    /// Size of the buffer of each descriptor; the maximum size of a message.
+    pub const BUFFER_SIZE: u32 = 65_536;
+
+    /// Creates the segment; fails if a segment with the same name exists.
+    /// The segment is removed when dropped.
+    pub fn create(name: &str) -> io::Result<Self> {
+        let segment = ShmSegment::map(name, true)?;
+
+        let header = segment.header();
+        header
+            .capacity
+            .store(ShmSegment::CAPACITY, Ordering::Relaxed);
+        header
+            .buffer_size
+            .store(ShmSegment::BUFFER_SIZE, Ordering::Relaxed);
+        header.enqueue_position.store(0, Ordering::Relaxed);
+        header.dequeue_position.store(0, Ordering::Relaxed);
+        header.notification.store(0, Ordering::Relaxed);
+        for position in 0..u64::from(ShmSegment::CAPACITY) {
+            segment
+                .descriptor(position)
+                .sequence
+                .store(position, Ordering::Relaxed);
+        }
+        header.magic.store(SEGMENT_MAGIC, Ordering::Release);
+
+        Ok(segment)
+    }
+
+    /// Opens the segment created by another Participant.
+    pub fn open(name: &str) -> io::Result<Self> {
+        let segment = ShmSegment::map(name, false)?;
+
+        let header = segment.header();
+        if header.magic.load(Ordering::Acquire) != SEGMENT_MAGIC
+            || header.capacity.load(Ordering::Relaxed) != ShmSegment::CAPACITY
+            || header.buffer_size.load(Ordering::Relaxed) != ShmSegment::BUFFER_SIZE
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Incompatible shared memory segment {:?}", name),
+            ));
+        }
+        Ok(segment)
+    }
+
+    /// Stores the message in the ring. Fails with WouldBlock when the ring
+    /// is full.
+    pub fn push(&self, source_port: u32, message: &[u8]) -> io::Result<()> {
+        if message.len() > ShmSegment::BUFFER_SIZE as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Message exceeds the shared memory buffer",
+            ));
+        }
+
+        let header = self.header();
+        let mut position = header.enqueue_position.load(Ordering::Relaxed);
+        loop {
+            let descriptor = self.descriptor(position);
+            let sequence = descriptor.sequence.load(Ordering::Acquire);
+            match sequence.cmp(&position) {
+                std::cmp::Ordering::Equal => {
+                    match header.enqueue_position.compare_exchange_weak(
+                        position,
+                        position + 1,
+                        Ordering::Relaxed,
+                        Ordering::Relaxed,
+                    ) {
+                        Ok(_) => {
+                            unsafe {
+                                ptr::copy_nonoverlapping(
+                                    message.as_ptr(),
+                                    self.buffer(position),
+                                    message.len(),
+                                )
+                            };
+                            descriptor.source_port.store(source_port, Ordering::Relaxed);
+                            descriptor
+                                .length
+                                .store(message.len() as u32, Ordering::Relaxed);
+                            descriptor.sequence.store(position + 1, Ordering::Release);
+                            break;
+                        }
+                        Err(current) => position = current,
+                    }
+                }
+                std::cmp::Ordering::Less => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::WouldBlock,
+                        "Shared memory ring is full",
+                    ))
+                }
+                std::cmp::Ordering::Greater => {
+                    position = header.enqueue_position.load(Ordering::Relaxed)
+                }
+            }
+        }
+
+        self.wake();
+        Ok(())
+    }
+
+    /// Takes the oldest message out of the ring together with the port of
+    /// its sender.
+    pub fn pop(&self) -> Option<(u32, Bytes)> {
+        let header = self.header();
+        let mut position = header.dequeue_position.load(Ordering::Relaxed);
+        loop {
+            let descriptor = self.descriptor(position);
+            let sequence = descriptor.sequence.load(Ordering::Acquire);
+            match sequence.cmp(&(position + 1)) {
+                std::cmp::Ordering::Equal => {
+                    match header.dequeue_position.compare_exchange_weak(
+                        position,
+                        position + 1,
+                        Ordering::Relaxed,
+                        Ordering::Relaxed,
+                    ) {
+                        Ok(_) => {
+                            let source_port = descriptor.source_port.load(Ordering::Relaxed);
+                            let length = (descriptor.length.load(Ordering::Relaxed) as usize)
+                                .min(ShmSegment::BUFFER_SIZE as usize);
+                            let message = Bytes::copy_from_slice(unsafe {
+                                std::slice::from_raw_parts(self.buffer(position), length)
+                            });
+                            descriptor.sequence.store(
+                                position + u64::from(ShmSegment::CAPACITY),
+                                Ordering::Release,
+                            );
+                            return Some((source_port, message));
+                        }
+                        Err(current) => position = current,
+                    }
+                }
+                std::cmp::Ordering::Less => return None,
+                std::cmp::Ordering::Greater => {
+                    position = header.dequeue_position.load(Ordering::Relaxed)
+                }
+            }
+        }
+    }
+
+    /// Current value of the notification counter; pass it to wait.
+    pub fn notification(&self) -> u32 {
+        self.header().notification.load(Ordering::Acquire)
+    }
+
+    /// Blocks until a message is pushed after the notification was read,
+    /// wake is called or the timeout elapses.
+    pub fn wait(&self, notification: u32, timeout: Duration) {
+        let timeout = libc::timespec {
+            tv_sec: timeout.as_secs() as libc::time_t,
+            tv_nsec: timeout.subsec_nanos() as libc::c_long,
+        };
+        unsafe {
+            libc::syscall(
+                libc::SYS_futex,
+                &self.header().notification as *const AtomicU32,
+                libc::FUTEX_WAIT,
+                notification,
+                &timeout as *const libc::timespec,
+                ptr::null::<u32>(),
+                0,
+            )
+        };
+    }
+
+    /// Wakes the process waiting for messages.
+    pub fn wake(&self) {
+        let notification = &self.header().notification;
+        notification.fetch_add(1, Ordering::Release);
+        unsafe {
+            libc::syscall(
+                libc::SYS_futex,
+                notification as *const AtomicU32,
+                libc::FUTEX_WAKE,
+                i32::MAX,
+                ptr::null::<libc::timespec>(),
+                ptr::null::<u32>(),
+                0,
+            )
+        };
+    }
+
+    fn map(name: &str, create: bool) -> io::Result<Self> {
+        let name = CString::new(name)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+        let flags = if create {
+            libc::O_CREAT | libc::O_EXCL | libc::O_RDWR
+        } else {
+            libc::O_RDWR
+        };
+
+        let fd = unsafe { libc::shm_open(name.as_ptr(), flags, 0o600) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let mapped = map_fd(fd, create);
+        unsafe { libc::close(fd) };
+
+        match mapped {
+            Ok((address, size)) => Ok(ShmSegment {
+                name,
+                address,
+                size,
+                owner: create,
+            }),
+            Err(error) => {
+                if create {
+                    unsafe { libc::shm_unlink(name.as_ptr()) };
+                }
+                Err(error)
+            }
+        }
+    }
+
+    fn header(&self) -> &SegmentHeader {
+        unsafe { &*(self.address as *const SegmentHeader) }
+    }
+
+    fn descriptor(&self, position: u64) -> &Descriptor {
+        let index = (position % u64::from(ShmSegment::CAPACITY)) as usize;
+        unsafe {
+            &*(self
+                .address
+                .add(DESCRIPTORS_OFFSET + index * size_of::<Descriptor>())
+                as *const Descriptor)
+        }
+    }
+
+    fn buffer(&self, position: u64) -> *mut u8 {
+        let index = (position % u64::from(ShmSegment::CAPACITY)) as usize;
+        unsafe {
+            self.address
+                .add(BUFFERS_OFFSET + index * ShmSegment::BUFFER_SIZE as usize)
+        }
+    }
+}
+
+impl Drop for ShmSegment {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.address as *mut libc::c_void, self.size);
+            if self.owner {
+                libc::shm_unlink(self.name.as_ptr());
+            }
+        }
+    }
+}
+
+const DESCRIPTORS_OFFSET: usize = 64;
+
+const BUFFERS_OFFSET: usize = DESCRIPTORS_OFFSET
+    + (ShmSegment::CAPACITY as usize * size_of::<Descriptor>()).div_ceil(64) * 64;
+
+const SEGMENT_SIZE: usize =
+    BUFFERS_OFFSET + ShmSegment::CAPACITY as usize * ShmSegment::BUFFER_SIZE as usize;
+
+/// Sizes the created segment and maps it into memory.
+fn map_fd(fd: libc::c_int, create: bool) -> io::Result<(*mut u8, usize)> {
+    if create {
+        if unsafe { libc::ftruncate(fd, SEGMENT_SIZE as libc::off_t) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+    } else {
+        let mut stat: libc::stat = unsafe { std::mem::zeroed() };
+        if unsafe { libc::fstat(fd, &mut stat) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        if (stat.st_size as usize) < SEGMENT_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Shared memory segment is too small",
+            ));
+        }
+    }
+
+    let address = unsafe {
+        libc::mmap(
+            ptr::null_mut(),
+            SEGMENT_SIZE,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_SHARED,
+            fd,
+            0,
+        )
+    };
+    if address == libc::MAP_FAILED {
+        return Err(io::Error::last_os_error());
+    }
+    Ok((address as *mut u8, SEGMENT_SIZE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    fn segment_name() -> String {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        format!(
+            "/rtps-test-segment-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        )
+    }
+
+    #[test]
+    fn header_fits_before_descriptors() {
+        assert!(size_of::<SegmentHeader>() <= DESCRIPTORS_OFFSET);
+    }
+
+    #[test]
+    fn pushed_messages_are_popped_in_order() {
+        let name = segment_name();
+        let receiver = ShmSegment::create(&name).unwrap();
+        let sender = ShmSegment::open(&name).unwrap();
+
+        sender.push(7400, b"first").unwrap();
+        sender.push(7401, b"second").unwrap();
+
+        assert_eq!(Some((7400, Bytes::from_static(b"first"))), receiver.pop());
+        assert_eq!(Some((7401, Bytes::from_static(b"second"))), receiver.pop());
+        assert_eq!(None, receiver.pop());
+    }
+
+    #[test]
+    fn push_to_full_ring_fails() {
+        let segment = ShmSegment::create(&segment_name()).unwrap();
+        for _ in 0..ShmSegment::CAPACITY {
+            segment.push(7400, b"RTPS").unwrap();
+        }
+
+        assert_eq!(
+            io::ErrorKind::WouldBlock,
+            segment.push(7400, b"RTPS").unwrap_err().kind()
+        );
+
+        assert!(segment.pop().is_some());
+        segment.push(7400, b"RTPS").unwrap();
+    }
+
+    #[test]
+    fn message_bigger_than_buffer_is_rejected() {
+        let segment = ShmSegment::create(&segment_name()).unwrap();
+
+        let message = vec![0x00; ShmSegment::BUFFER_SIZE as usize + 1];
+
+        assert_eq!(
+            io::ErrorKind::InvalidInput,
+            segment.push(7400, &message).unwrap_err().kind()
+        );
+    }
+
+    #[test]
+    fn segment_is_removed_by_its_creator() {
+        let name = segment_name();
+
+        assert_eq!(
+            io::ErrorKind::NotFound,
+            ShmSegment::open(&name).err().unwrap().kind()
+        );
+        let segment = ShmSegment::create(&name).unwrap();
+        assert_eq!(
+            io::ErrorKind::AlreadyExists,
+            ShmSegment::create(&name).err().unwrap().kind()
+        );
+        drop(segment);
+        assert_eq!(
+            io::ErrorKind::NotFound,
+            ShmSegment::open(&name).err().unwrap().kind()
+        );
+    }
+}
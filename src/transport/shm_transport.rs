@@ -0,0 +1,280 @@
+use crate::common::host_id::host_id;
+use crate::structure::locator::{LocatorKind_t, LocatorList_t, Locator_t};
+use crate::transport::shm_segment::ShmSegment;
+use crate::transport::transport::{unsupported_locator, Transport};
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use std::collections::HashMap;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Transport exchanging RTPS Messages between processes on the same host
+/// through POSIX shared memory.
+///
+/// Each transport owns a segment named after its port and receives the
+/// messages pushed into it by other processes. The address of the locator
+/// identifies the host, so remote Participants on other hosts, or not
+/// supporting shared memory at all, are reached through their other
+/// locators, e.g. UDP.
+pub struct ShmTransport {
+    port: u32,
+    host_id: [u8; 16],
+    segment: Arc<ShmSegment>,
+    /// Segments of the remote Participants, opened on the first message.
+    peers: Mutex<HashMap<u32, Arc<ShmSegment>>>,
+    received: tokio::sync::Mutex<mpsc::UnboundedReceiver<(Locator_t, Bytes)>>,
+    stopped: Arc<AtomicBool>,
+    receiving_thread: Option<JoinHandle<()>>,
+}
+
+impl ShmTransport {
+    /// The maximum size of a message, the size of a single buffer of the
+    /// segment.
+    pub const MAX_MESSAGE_SIZE: usize = ShmSegment::BUFFER_SIZE as usize;
+
+    /// The first port tried when any free port is requested.
+    pub const FIRST_EPHEMERAL_PORT: u32 = 49_152;
+
+    /// Creates the segment for the port; port 0 selects the first free port
+    /// starting from FIRST_EPHEMERAL_PORT. Has to be called within the Tokio
+    /// runtime.
+    pub fn new(port: u32) -> io::Result<Self> {
+        let (port, segment) = create_segment(port)?;
+        let segment = Arc::new(segment);
+        let host_id = host_id();
+        let stopped = Arc::new(AtomicBool::new(false));
+        let (sender, received) = mpsc::unbounded_channel();
+
+        let receiving_thread = {
+            let segment = segment.clone();
+            let stopped = stopped.clone();
+            std::thread::Builder::new()
+                .name(format!("rtps-shm-{}", port))
+                .spawn(move || loop {
+                    let notification = segment.notification();
+                    while let Some((source_port, message)) = segment.pop() {
+                        if sender
+                            .send((locator(host_id, source_port), message))
+                            .is_err()
+                        {
+                            return;
+                        }
+                    }
+                    if stopped.load(Ordering::Acquire) {
+                        return;
+                    }
+                    segment.wait(notification, Duration::from_millis(100));
+                })?
+        };
+
+        Ok(ShmTransport {
+            port,
+            host_id,
+            segment,
+            peers: Mutex::new(HashMap::new()),
+            received: tokio::sync::Mutex::new(received),
+            stopped,
+            receiving_thread: Some(receiving_thread),
+        })
+    }
+
+    pub fn locator(&self) -> Locator_t {
+        locator(self.host_id, self.port)
+    }
+
+    fn peer(&self, port: u32) -> io::Result<Arc<ShmSegment>> {
+        let mut peers = self.peers.lock().unwrap();
+        if let Some(segment) = peers.get(&port) {
+            return Ok(segment.clone());
+        }
+        let segment = Arc::new(ShmSegment::open(&segment_name(port))?);
+        peers.insert(port, segment.clone());
+        Ok(segment)
+    }
+}
+
+impl Transport for ShmTransport {
+    fn supported_kinds(&self) -> &[LocatorKind_t] {
+        &[LocatorKind_t::LOCATOR_KIND_SHM]
+    }
+
+    fn is_reachable(&self, locator: &Locator_t) -> bool {
+        locator.kind == LocatorKind_t::LOCATOR_KIND_SHM && locator.address == self.host_id
+    }
+
+    fn max_message_size(&self) -> usize {
+        ShmTransport::MAX_MESSAGE_SIZE
+    }
+
+    fn local_locators(&self) -> LocatorList_t {
+        vec![self.locator()]
+    }
+
+    fn send(&self, destination: Locator_t, message: Bytes) -> BoxFuture<'_, io::Result<()>> {
+        Box::pin(async move {
+            if !self.is_reachable(&destination) {
+                return Err(unsupported_locator(destination));
+            }
+            let result = self
+                .peer(destination.port)
+                .and_then(|segment| segment.push(self.port, &message));
+            if result.is_err() {
+                // The segment might be gone together with its Participant
+                self.peers.lock().unwrap().remove(&destination.port);
+            }
+            result
+        })
+    }
+
+    fn recv(&self) -> BoxFuture<'_, io::Result<(Locator_t, Bytes)>> {
+        Box::pin(async move {
+            self.received.lock().await.recv().await.ok_or_else(|| {
+                io::Error::new(io::ErrorKind::BrokenPipe, "Receiving thread stopped")
+            })
+        })
+    }
+}
+
+impl Drop for ShmTransport {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Release);
+        self.segment.wake();
+        if let Some(receiving_thread) = self.receiving_thread.take() {
+            let _ = receiving_thread.join();
+        }
+    }
+}
+
+/// Creates the segment for the port or for the first free port.
+fn create_segment(port: u32) -> io::Result<(u32, ShmSegment)> {
+    if port != 0 {
+        return ShmSegment::create(&segment_name(port)).map(|segment| (port, segment));
+    }
+
+    for port in ShmTransport::FIRST_EPHEMERAL_PORT..=u32::from(u16::MAX) {
+        match ShmSegment::create(&segment_name(port)) {
+            Ok(segment) => return Ok((port, segment)),
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(error),
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::AddrInUse,
+        "No free shared memory port",
+    ))
+}
+
+fn segment_name(port: u32) -> String {
+    format!("/rtps-shm-{}", port)
+}
+
+fn locator(host_id: [u8; 16], port: u32) -> Locator_t {
+    Locator_t {
+        kind: LocatorKind_t::LOCATOR_KIND_SHM,
+        port,
+        address: host_id,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Set for the child process started by message_is_exchanged_between_processes.
+    const PEER_PORT_VARIABLE: &str = "RTPS_SHM_TEST_PEER_PORT";
+
+    async fn recv(transport: &ShmTransport) -> (Locator_t, Bytes) {
+        tokio::time::timeout(Duration::from_secs(5), transport.recv())
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn message_is_received_with_source_locator() {
+        let sender = ShmTransport::new(0).unwrap();
+        let receiver = ShmTransport::new(0).unwrap();
+
+        sender
+            .send(receiver.locator(), Bytes::from_static(b"RTPS"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            (sender.locator(), Bytes::from_static(b"RTPS")),
+            recv(&receiver).await
+        );
+    }
+
+    #[tokio::test]
+    async fn locators_of_other_hosts_are_not_reachable() {
+        let transport = ShmTransport::new(0).unwrap();
+        let mut other_host = transport.locator();
+        other_host.address[0] ^= 0xFF;
+
+        assert!(transport.is_reachable(&transport.locator()));
+        assert!(!transport.is_reachable(&other_host));
+        assert_eq!(
+            io::ErrorKind::InvalidInput,
+            transport
+                .send(other_host, Bytes::from_static(b"RTPS"))
+                .await
+                .unwrap_err()
+                .kind()
+        );
+    }
+
+    #[tokio::test]
+    async fn sending_to_closed_transport_fails() {
+        let sender = ShmTransport::new(0).unwrap();
+        let receiver = ShmTransport::new(0).unwrap();
+        let destination = receiver.locator();
+        drop(receiver);
+
+        assert_eq!(
+            io::ErrorKind::NotFound,
+            sender
+                .send(destination, Bytes::from_static(b"RTPS"))
+                .await
+                .unwrap_err()
+                .kind()
+        );
+    }
+
+    #[tokio::test]
+    async fn message_is_exchanged_between_processes() {
+        if let Ok(peer_port) = std::env::var(PEER_PORT_VARIABLE) {
+            let transport = ShmTransport::new(0).unwrap();
+            let mut destination = transport.locator();
+            destination.port = peer_port.parse().unwrap();
+            transport
+                .send(destination, Bytes::from_static(b"RTPS"))
+                .await
+                .unwrap();
+            return;
+        }
+
+        let transport = ShmTransport::new(0).unwrap();
+        let status = std::process::Command::new(std::env::current_exe().unwrap())
+            .args([
+                "--exact",
+                "transport::shm_transport::tests::message_is_exchanged_between_processes",
+            ])
+            .env(PEER_PORT_VARIABLE, transport.locator().port.to_string())
+            .stdout(std::process::Stdio::null())
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let (source, message) = recv(&transport).await;
+        assert_eq!(LocatorKind_t::LOCATOR_KIND_SHM, source.kind);
+        assert_eq!(transport.locator().address, source.address);
+        assert_ne!(transport.locator().port, source.port);
+        assert_eq!(Bytes::from_static(b"RTPS"), message);
+    }
+}
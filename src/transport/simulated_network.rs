@@ -0,0 +1,632 @@
+use crate::structure::locator::{LocatorKind_t, LocatorList_t, Locator_t};
+use crate::transport::network_conditions::NetworkConditions;
+use crate::transport::transport::{unsupported_locator, Transport};
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use std::collections::{BTreeMap, VecDeque};
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+/// In-memory network connecting SimulatedTransports, driven by a mock
+/// clock.
+///
+/// Datagrams sent through the network are subject to the NetworkConditions
+/// and to partitions; they reach the inboxes of the transports only when
+/// the clock is advanced past their delivery time. All random decisions are
+/// made by a generator seeded at construction, so the same sequence of
+/// operations always gives the same outcome.
+#[derive(Clone)]
+pub struct SimulatedNetwork {
+    state: Arc<Mutex<NetworkState>>,
+}
+
+struct NetworkState {
+    random: SplitMix64,
+    conditions: NetworkConditions,
+    now: Instant,
+    /// Datagrams ordered by the time of delivery and the order of sending.
+    in_flight: BTreeMap<(Instant, u64), Datagram>,
+    sent: u64,
+    endpoints: Vec<SimulatedEndpoint>,
+}
+
+struct Datagram {
+    source: Locator_t,
+    destination: Locator_t,
+    contents: Bytes,
+}
+
+struct SimulatedEndpoint {
+    unicast_locator: Locator_t,
+    multicast_locators: LocatorList_t,
+    /// Endpoints in different partitions can not reach each other.
+    partition: usize,
+    inbox: VecDeque<(Locator_t, Bytes)>,
+    notify: Arc<Notify>,
+}
+
+impl SimulatedNetwork {
+    /// The maximum size of a datagram, as for UDP/IPv4.
+    pub const MAX_MESSAGE_SIZE: usize = 65_507;
+
+    /// Creates a perfect network with the clock set to start.
+    pub fn new(seed: u64, start: Instant) -> Self {
+        SimulatedNetwork {
+            state: Arc::new(Mutex::new(NetworkState {
+                random: SplitMix64(seed),
+                conditions: NetworkConditions::default(),
+                now: start,
+                in_flight: BTreeMap::new(),
+                sent: 0,
+                endpoints: vec![],
+            })),
+        }
+    }
+
+    /// Applies to the datagrams sent from now on.
+    pub fn set_conditions(&self, conditions: NetworkConditions) {
+        self.state.lock().unwrap().conditions = conditions;
+    }
+
+    /// Attaches a transport receiving datagrams sent to the unicast locator
+    /// or to any of the multicast locators.
+    pub fn transport(
+        &self,
+        unicast_locator: Locator_t,
+        multicast_locators: &[Locator_t],
+    ) -> SimulatedTransport {
+        let mut state = self.state.lock().unwrap();
+        state.endpoints.push(SimulatedEndpoint {
+            unicast_locator,
+            multicast_locators: multicast_locators.to_vec(),
+            partition: 0,
+            inbox: VecDeque::new(),
+            notify: Arc::new(Notify::new()),
+        });
+
+        SimulatedTransport {
+            network: self.clone(),
+            index: state.endpoints.len() - 1,
+            unicast_locator,
+        }
+    }
+
+    /// Separates the transports with the unicast locators from all others,
+    /// datagrams between them and the rest of the network are lost.
+    pub fn partition(&self, unicast_locators: &[Locator_t]) {
+        let mut state = self.state.lock().unwrap();
+        let partition = state
+            .endpoints
+            .iter()
+            .map(|endpoint| endpoint.partition)
+            .max()
+            .unwrap_or_default()
+            + 1;
+        for endpoint in &mut state.endpoints {
+            if unicast_locators.contains(&endpoint.unicast_locator) {
+                endpoint.partition = partition;
+            }
+        }
+    }
+
+    /// Removes all partitions.
+    pub fn heal(&self) {
+        for endpoint in &mut self.state.lock().unwrap().endpoints {
+            endpoint.partition = 0;
+        }
+    }
+
+    /// The current time of the mock clock.
+    pub fn now(&self) -> Instant {
+        self.state.lock().unwrap().now
+    }
+
+    /// The time at which the next datagram reaches its destination.
+    pub fn next_delivery(&self) -> Option<Instant> {
+        self.state
+            .lock()
+            .unwrap()
+            .in_flight
+            .keys()
+            .next()
+            .map(|(deliver_at, _)| *deliver_at)
+    }
+
+    /// Moves the clock forward to now, delivering all datagrams due before
+    /// or at now. The clock never moves backwards.
+    pub fn advance_to(&self, now: Instant) {
+        let mut state = self.state.lock().unwrap();
+        if now > state.now {
+            state.now = now;
+        }
+
+        let now = state.now;
+        while let Some(entry) = state.in_flight.first_entry() {
+            if entry.key().0 > now {
+                break;
+            }
+            let datagram = entry.remove();
+            state.deliver(datagram);
+        }
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        let now = self.now() + duration;
+        self.advance_to(now)
+    }
+
+    fn send(&self, source: Locator_t, destination: Locator_t, contents: Bytes) {
+        let mut state = self.state.lock().unwrap();
+        let conditions = state.conditions.clone();
+
+        if state.random.chance(conditions.loss) {
+            return;
+        }
+        let copies = if state.random.chance(conditions.duplication) {
+            2
+        } else {
+            1
+        };
+        for _ in 0..copies {
+            let mut delay = conditions.latency + state.random.duration(conditions.jitter);
+            if state.random.chance(conditions.reordering) {
+                delay += conditions.reordering_delay;
+            }
+            let key = (state.now + delay, state.sent);
+            state.sent += 1;
+            state.in_flight.insert(
+                key,
+                Datagram {
+                    source,
+                    destination,
+                    contents: contents.clone(),
+                },
+            );
+        }
+    }
+
+    fn try_recv(&self, index: usize) -> (Option<(Locator_t, Bytes)>, Arc<Notify>) {
+        let mut state = self.state.lock().unwrap();
+        let endpoint = &mut state.endpoints[index];
+        (endpoint.inbox.pop_front(), endpoint.notify.clone())
+    }
+}
+
+impl NetworkState {
+    fn deliver(&mut self, datagram: Datagram) {
+        let source_partition = self
+            .endpoints
+            .iter()
+            .find(|endpoint| endpoint.unicast_locator == datagram.source)
+            .map(|endpoint| endpoint.partition)
+            .unwrap_or_default();
+
+        for endpoint in &mut self.endpoints {
+            let addressed = endpoint.unicast_locator == datagram.destination
+                || endpoint.multicast_locators.contains(&datagram.destination);
+            if addressed && endpoint.partition == source_partition {
+                endpoint
+                    .inbox
+                    .push_back((datagram.source, datagram.contents.clone()));
+                endpoint.notify.notify_one();
+            }
+        }
+    }
+}
+
+/// Transport attached to the SimulatedNetwork, used in place of the
+/// UdpV4Transport.
+pub struct SimulatedTransport {
+    network: SimulatedNetwork,
+    index: usize,
+    unicast_locator: Locator_t,
+}
+
+impl SimulatedTransport {
+    pub fn locator(&self) -> Locator_t {
+        self.unicast_locator
+    }
+
+    /// Sends the datagram at the current time of the network clock.
+    pub fn send_datagram(&self, destination: Locator_t, message: Bytes) -> io::Result<()> {
+        if !self.is_reachable(&destination) {
+            return Err(unsupported_locator(destination));
+        }
+        if message.len() > SimulatedNetwork::MAX_MESSAGE_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Message exceeds the maximum datagram size",
+            ));
+        }
+        self.network
+            .send(self.unicast_locator, destination, message);
+        Ok(())
+    }
+
+    /// Returns the next datagram delivered to the transport, if any.
+    pub fn try_recv(&self) -> Option<(Locator_t, Bytes)> {
+        self.network.try_recv(self.index).0
+    }
+}
+
+impl Transport for SimulatedTransport {
+    fn supported_kinds(&self) -> &[LocatorKind_t] {
+        &[LocatorKind_t::LOCATOR_KIND_UDPv4]
+    }
+
+    fn max_message_size(&self) -> usize {
+        SimulatedNetwork::MAX_MESSAGE_SIZE
+    }
+
+    fn local_locators(&self) -> LocatorList_t {
+        let state = self.network.state.lock().unwrap();
+        let endpoint = &state.endpoints[self.index];
+        let mut locators = vec![endpoint.unicast_locator];
+        locators.extend(&endpoint.multicast_locators);
+        locators
+    }
+
+    fn send(&self, destination: Locator_t, message: Bytes) -> BoxFuture<'_, io::Result<()>> {
+        Box::pin(async move { self.send_datagram(destination, message) })
+    }
+
+    /// Waits until a datagram is delivered to the transport; the network
+    /// clock has to be advanced by someone else.
+    fn recv(&self) -> BoxFuture<'_, io::Result<(Locator_t, Bytes)>> {
+        Box::pin(async move {
+            loop {
+                match self.network.try_recv(self.index) {
+                    (Some(received), _) => return Ok(received),
+                    (None, notify) => notify.notified().await,
+                }
+            }
+        })
+    }
+}
+
+/// Small, portable pseudo random generator; the same seed gives the same
+/// sequence on every platform.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniformly distributed value from [0, 1).
+    fn uniform(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn chance(&mut self, probability: f64) -> bool {
+        probability > 0.0 && self.uniform() < probability
+    }
+
+    /// Uniformly distributed duration from [0, max].
+    fn duration(&mut self, max: Duration) -> Duration {
+        if max == Duration::from_secs(0) {
+            return max;
+        }
+        Duration::from_nanos(self.next() % (max.as_nanos() as u64 + 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::behavior::reader::{Reader, ReaderAttributes};
+    use crate::behavior::reader_proxy::ReaderProxy;
+    use crate::behavior::stateful_reader::StatefulReader;
+    use crate::behavior::stateful_writer::StatefulWriter;
+    use crate::behavior::writer::{Writer, WriterAttributes};
+    use crate::behavior::writer_proxy::WriterProxy;
+    use crate::messages::vendor_id::VendorId_t;
+    use crate::protocol::protocol_core::ProtocolCore;
+    use crate::structure::change_kind::ChangeKind_t;
+    use crate::structure::data::Data;
+    use crate::structure::duration::Duration_t;
+    use crate::structure::endpoint::EndpointAttributes;
+    use crate::structure::entity::EntityAttributes;
+    use crate::structure::entity_id::EntityId_t;
+    use crate::structure::guid::GUID_t;
+    use crate::structure::guid_prefix::GuidPrefix_t;
+    use crate::structure::history_cache::HistoryCache;
+    use crate::structure::instance_handle::InstanceHandle_t;
+    use crate::structure::locator::{Ipv4Addr, SocketAddr};
+    use crate::structure::reliability_kind::ReliabilityKind_t;
+    use crate::structure::sequence_number::SequenceNumber_t;
+    use crate::structure::topic_kind::TopicKind_t;
+
+    fn locator(port: u16) -> Locator_t {
+        Locator_t::from(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), port))
+    }
+
+    fn lossy_conditions() -> NetworkConditions {
+        NetworkConditions {
+            loss: 0.2,
+            duplication: 0.1,
+            reordering: 0.2,
+            reordering_delay: Duration::from_millis(20),
+            latency: Duration::from_millis(5),
+            jitter: Duration::from_millis(5),
+        }
+    }
+
+    #[test]
+    fn datagram_is_delivered_after_latency() {
+        let network = SimulatedNetwork::new(0, Instant::now());
+        network.set_conditions(NetworkConditions {
+            latency: Duration::from_millis(10),
+            ..NetworkConditions::default()
+        });
+        let sender = network.transport(locator(7400), &[]);
+        let receiver = network.transport(locator(7401), &[]);
+
+        sender
+            .send_datagram(receiver.locator(), Bytes::from_static(b"RTPS"))
+            .unwrap();
+
+        network.advance(Duration::from_millis(9));
+        assert_eq!(None, receiver.try_recv());
+        assert_eq!(
+            Some(network.now() + Duration::from_millis(1)),
+            network.next_delivery()
+        );
+        network.advance(Duration::from_millis(1));
+        assert_eq!(
+            Some((sender.locator(), Bytes::from_static(b"RTPS"))),
+            receiver.try_recv()
+        );
+        assert_eq!(None, network.next_delivery());
+    }
+
+    #[test]
+    fn multicast_datagram_reaches_all_members() {
+        let network = SimulatedNetwork::new(0, Instant::now());
+        let multicast_locator =
+            Locator_t::from(SocketAddr::new(Ipv4Addr::new(239, 255, 0, 1).into(), 7400));
+        let sender = network.transport(locator(7410), &[]);
+        let members = [
+            network.transport(locator(7411), &[multicast_locator]),
+            network.transport(locator(7412), &[multicast_locator]),
+        ];
+
+        sender
+            .send_datagram(multicast_locator, Bytes::from_static(b"RTPS"))
+            .unwrap();
+        network.advance(Duration::from_secs(0));
+
+        for member in &members {
+            assert_eq!(
+                Some((sender.locator(), Bytes::from_static(b"RTPS"))),
+                member.try_recv()
+            );
+        }
+        assert_eq!(None, sender.try_recv());
+    }
+
+    #[test]
+    fn partitioned_transports_do_not_reach_each_other() {
+        let network = SimulatedNetwork::new(0, Instant::now());
+        let first = network.transport(locator(7400), &[]);
+        let second = network.transport(locator(7401), &[]);
+
+        network.partition(&[first.locator()]);
+        first
+            .send_datagram(second.locator(), Bytes::from_static(b"lost"))
+            .unwrap();
+        network.advance(Duration::from_secs(1));
+        assert_eq!(None, second.try_recv());
+
+        network.heal();
+        first
+            .send_datagram(second.locator(), Bytes::from_static(b"RTPS"))
+            .unwrap();
+        network.advance(Duration::from_secs(1));
+        assert_eq!(
+            Some((first.locator(), Bytes::from_static(b"RTPS"))),
+            second.try_recv()
+        );
+    }
+
+    /// Sends numbered datagrams over the lossy network, returns the numbers
+    /// in the order of reception.
+    fn received_numbers(seed: u64) -> Vec<u8> {
+        let network = SimulatedNetwork::new(seed, Instant::now());
+        network.set_conditions(lossy_conditions());
+        let sender = network.transport(locator(7400), &[]);
+        let receiver = network.transport(locator(7401), &[]);
+
+        for number in 0..100u8 {
+            sender
+                .send_datagram(receiver.locator(), Bytes::from(vec![number]))
+                .unwrap();
+            network.advance(Duration::from_millis(1));
+        }
+        network.advance(Duration::from_secs(1));
+
+        std::iter::from_fn(|| receiver.try_recv())
+            .map(|(_, datagram)| datagram[0])
+            .collect()
+    }
+
+    #[test]
+    fn impairments_are_reproducible_with_seed() {
+        let received = received_numbers(7);
+
+        assert_eq!(received, received_numbers(7));
+        assert_ne!(received, received_numbers(8));
+
+        let mut sorted = received.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        // Some datagrams are lost, duplicated and reordered
+        assert!(sorted.len() < 100);
+        assert!(received.len() > sorted.len());
+        assert!(received.windows(2).any(|pair| pair[0] > pair[1]));
+    }
+
+    #[tokio::test]
+    async fn transport_receives_datagrams_delivered_by_network() {
+        let network = SimulatedNetwork::new(0, Instant::now());
+        let sender = network.transport(locator(7400), &[]);
+        let receiver = network.transport(locator(7401), &[]);
+
+        sender
+            .send(receiver.locator(), Bytes::from_static(b"RTPS"))
+            .await
+            .unwrap();
+        network.advance(Duration::from_secs(0));
+
+        assert_eq!(
+            (sender.locator(), Bytes::from_static(b"RTPS")),
+            receiver.recv().await.unwrap()
+        );
+    }
+
+    const WRITER_GUID: GUID_t = GUID_t {
+        guid_prefix: GuidPrefix_t {
+            entity_key: [0x01; 12],
+        },
+        entity_id: EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_WRITER,
+    };
+
+    const READER_GUID: GUID_t = GUID_t {
+        guid_prefix: GuidPrefix_t {
+            entity_key: [0x02; 12],
+        },
+        entity_id: EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_READER,
+    };
+
+    fn endpoint() -> EndpointAttributes {
+        EndpointAttributes {
+            topic_kind: TopicKind_t::NO_KEY,
+            reliability_level: ReliabilityKind_t::RELIABLE,
+            unicast_locator_list: vec![],
+            multicast_locator_list: vec![],
+        }
+    }
+
+    /// Runs the Participants until nothing happens before until: delivers
+    /// the datagrams, processes the timers and sends the messages.
+    fn run(
+        network: &SimulatedNetwork,
+        participants: &mut [(SimulatedTransport, ProtocolCore)],
+        until: Instant,
+    ) {
+        loop {
+            let now = network.now();
+            for (transport, core) in participants.iter_mut() {
+                while let Some((source, datagram)) = transport.try_recv() {
+                    core.handle_datagram(now, source, datagram);
+                }
+                if matches!(core.poll_timeout(), Some(deadline) if deadline <= now) {
+                    core.handle_timeout(now);
+                }
+                while let Some(transmit) = core.poll_transmit() {
+                    transport
+                        .send_datagram(transmit.destination, transmit.contents)
+                        .unwrap();
+                }
+            }
+
+            let next = participants
+                .iter()
+                .filter_map(|(_, core)| core.poll_timeout())
+                .chain(network.next_delivery())
+                .min();
+            match next {
+                Some(next) if next <= until => network.advance_to(next),
+                _ => break,
+            }
+        }
+    }
+
+    #[test]
+    fn reliable_writer_delivers_fragmented_changes_over_lossy_network() {
+        let start = Instant::now();
+        let network = SimulatedNetwork::new(42, start);
+        network.set_conditions(lossy_conditions());
+        let writer_transport = network.transport(locator(7410), &[]);
+        let reader_transport = network.transport(locator(7411), &[]);
+
+        let mut writer = StatefulWriter::new(
+            EntityAttributes { guid: WRITER_GUID },
+            endpoint(),
+            WriterAttributes {
+                push_mode: true,
+                heartbeat_period: Duration_t::from(Duration::from_millis(100)),
+                nack_response_delay: Duration_t::DURATION_ZERO,
+                nack_suppression_duration: Duration_t::DURATION_ZERO,
+                last_change_sequence_number: SequenceNumber_t::from(0),
+                data_max_size_serialized: 8,
+                fragment_size: 4,
+                writer_cache: HistoryCache::new(),
+            },
+        );
+        writer.matched_reader_add(ReaderProxy::new(
+            READER_GUID,
+            vec![reader_transport.locator()],
+            vec![],
+            false,
+        ));
+        let values: Vec<Bytes> = (0..10u8)
+            .map(|number| Bytes::from(vec![number; usize::from(number) * 3 + 1]))
+            .collect();
+        for value in &values {
+            let change = writer.new_change(
+                ChangeKind_t::ALIVE,
+                Data::from(value.clone()),
+                InstanceHandle_t::default(),
+            );
+            writer.as_writer_mut().writer_cache.add_change(change);
+        }
+        let mut writer_core =
+            ProtocolCore::new(WRITER_GUID.guid_prefix, VendorId_t::VENDOR_UNKNOWN);
+        writer_core.add_writer(start, writer);
+
+        let mut reader = StatefulReader::new(
+            EntityAttributes { guid: READER_GUID },
+            endpoint(),
+            ReaderAttributes {
+                expects_inline_qos: false,
+                heartbeat_response_delay: Duration_t::DURATION_ZERO,
+                heartbeat_suppression_duration: Duration_t::DURATION_ZERO,
+                reader_cache: HistoryCache::new(),
+            },
+        );
+        reader.matched_writer_add(WriterProxy::new(
+            WRITER_GUID,
+            vec![writer_transport.locator()],
+            vec![],
+        ));
+        let mut reader_core =
+            ProtocolCore::new(READER_GUID.guid_prefix, VendorId_t::VENDOR_UNKNOWN);
+        reader_core.add_reader(reader);
+
+        let mut participants = [
+            (writer_transport, writer_core),
+            (reader_transport, reader_core),
+        ];
+        run(&network, &mut participants, start + Duration::from_secs(10));
+
+        let received: Vec<Bytes> = participants[1]
+            .1
+            .reader(READER_GUID.entity_id)
+            .unwrap()
+            .as_reader()
+            .reader_cache
+            .changes()
+            .iter()
+            .map(|change| change.data_value.value.clone())
+            .collect();
+        assert_eq!(values, received);
+    }
+}
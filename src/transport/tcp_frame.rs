@@ -0,0 +1,13 @@
+use bytes::Bytes;
+
+/// Unit of data exchanged over the TCP connection.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TcpFrame {
+    /// The first frame sent by the connecting transport; announces the port
+    /// it accepts connections on, so the connection can be reused to reach
+    /// the connecting transport.
+    Bind { port: u16 },
+
+    /// RTPS Message addressed to the logical port of the receiving transport.
+    Message { logical_port: u16, message: Bytes },
+}
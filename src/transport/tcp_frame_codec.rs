@@ -0,0 +1,191 @@
+use crate::transport::tcp_frame::TcpFrame;
+
+use bytes::{Buf, BufMut, BytesMut};
+use std::io;
+use tokio_util::codec::{Decoder, Encoder};
+
+/// Frames exchanged over the TCP stream. Each frame is prefixed with the
+/// header:
+///
+/// ```text
+/// 0...2...........8...............16.............24..............32
+/// +---------------+---------------+---------------+---------------+
+/// |                        length (big endian)                    |
+/// +---------------+---------------+---------------+---------------+
+/// |          frame kind           |          logical port         |
+/// +---------------+---------------+---------------+---------------+
+/// ```
+///
+/// where the length counts the bytes following the header. The body of the
+/// Bind frame is the announced port, the body of the Message frame is the
+/// RTPS Message.
+pub struct TcpFrameCodec {
+    max_frame_size: usize,
+}
+
+impl TcpFrameCodec {
+    pub const HEADER_SIZE: usize = 8;
+
+    const BIND: u16 = 0;
+    const MESSAGE: u16 = 1;
+
+    /// Frames with longer body are rejected as malformed.
+    pub fn new(max_frame_size: usize) -> Self {
+        TcpFrameCodec { max_frame_size }
+    }
+}
+
+impl Decoder for TcpFrameCodec {
+    type Item = TcpFrame;
+    type Error = io::Error;
+
+    fn decode(&mut self, bytes: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if bytes.len() < TcpFrameCodec::HEADER_SIZE {
+            return Ok(None);
+        }
+
+        let length = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize;
+        if length > self.max_frame_size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Frame of {} bytes exceeds the maximum size", length),
+            ));
+        }
+        if bytes.len() < TcpFrameCodec::HEADER_SIZE + length {
+            bytes.reserve(TcpFrameCodec::HEADER_SIZE + length - bytes.len());
+            return Ok(None);
+        }
+
+        bytes.advance(4);
+        let kind = bytes.get_u16();
+        let logical_port = bytes.get_u16();
+        let mut body = bytes.split_to(length);
+
+        match kind {
+            TcpFrameCodec::BIND if length == 2 => Ok(Some(TcpFrame::Bind {
+                port: body.get_u16(),
+            })),
+            TcpFrameCodec::MESSAGE => Ok(Some(TcpFrame::Message {
+                logical_port,
+                message: body.freeze(),
+            })),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Malformed frame of kind {}", kind),
+            )),
+        }
+    }
+}
+
+impl Encoder<TcpFrame> for TcpFrameCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, frame: TcpFrame, bytes: &mut BytesMut) -> Result<(), Self::Error> {
+        match frame {
+            TcpFrame::Bind { port } => {
+                bytes.reserve(TcpFrameCodec::HEADER_SIZE + 2);
+                bytes.put_u32(2);
+                bytes.put_u16(TcpFrameCodec::BIND);
+                bytes.put_u16(0);
+                bytes.put_u16(port);
+            }
+            TcpFrame::Message {
+                logical_port,
+                message,
+            } => {
+                if message.len() > self.max_frame_size {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!(
+                            "Message of {} bytes exceeds the maximum size",
+                            message.len()
+                        ),
+                    ));
+                }
+                bytes.reserve(TcpFrameCodec::HEADER_SIZE + message.len());
+                bytes.put_u32(message.len() as u32);
+                bytes.put_u16(TcpFrameCodec::MESSAGE);
+                bytes.put_u16(logical_port);
+                bytes.put_slice(&message);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+
+    fn encode(frame: TcpFrame) -> BytesMut {
+        let mut bytes = BytesMut::new();
+        TcpFrameCodec::new(16).encode(frame, &mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn frames_are_encoded_with_header() {
+        assert_eq!(
+            &[0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x1C, 0xF2][..],
+            &encode(TcpFrame::Bind { port: 7410 })[..]
+        );
+        assert_eq!(
+            &[0x00, 0x00, 0x00, 0x04, 0x00, 0x01, 0x1C, 0xF3, b'R', b'T', b'P', b'S'][..],
+            &encode(TcpFrame::Message {
+                logical_port: 7411,
+                message: Bytes::from_static(b"RTPS"),
+            })[..]
+        );
+    }
+
+    #[test]
+    fn frames_are_decoded_from_stream() {
+        let mut bytes = encode(TcpFrame::Bind { port: 7410 });
+        bytes.extend(encode(TcpFrame::Message {
+            logical_port: 7411,
+            message: Bytes::from_static(b"RTPS"),
+        }));
+        let mut codec = TcpFrameCodec::new(16);
+
+        // The second frame is not received completely yet
+        let mut partial = bytes.split_to(bytes.len() - 1);
+        assert_eq!(
+            Some(TcpFrame::Bind { port: 7410 }),
+            codec.decode(&mut partial).unwrap()
+        );
+        assert_eq!(None, codec.decode(&mut partial).unwrap());
+
+        partial.unsplit(bytes);
+        assert_eq!(
+            Some(TcpFrame::Message {
+                logical_port: 7411,
+                message: Bytes::from_static(b"RTPS"),
+            }),
+            codec.decode(&mut partial).unwrap()
+        );
+        assert!(partial.is_empty());
+    }
+
+    #[test]
+    fn oversized_and_unknown_frames_are_rejected() {
+        let mut codec = TcpFrameCodec::new(16);
+
+        let mut oversized = BytesMut::from(&[0x00, 0x00, 0x00, 0x11, 0x00, 0x01, 0x00, 0x00][..]);
+        assert!(codec.decode(&mut oversized).is_err());
+
+        let mut unknown = BytesMut::from(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x00, 0x00][..]);
+        assert!(codec.decode(&mut unknown).is_err());
+
+        let mut bytes = BytesMut::new();
+        assert!(codec
+            .encode(
+                TcpFrame::Message {
+                    logical_port: 7411,
+                    message: Bytes::from_static(&[0x00; 17]),
+                },
+                &mut bytes
+            )
+            .is_err());
+    }
+}
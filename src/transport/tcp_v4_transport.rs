@@ -0,0 +1,445 @@
+use crate::structure::locator::{Ipv4Addr, LocatorKind_t, LocatorList_t, Locator_t, SocketAddr};
+use crate::transport::message_queue::MessageQueue;
+use crate::transport::tcp_frame::TcpFrame;
+use crate::transport::tcp_frame_codec::TcpFrameCodec;
+use crate::transport::transport::{unsupported_locator, Transport};
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use futures::{SinkExt, StreamExt};
+use log::debug;
+use std::collections::HashMap;
+use std::future::Future;
+use std::io;
+use std::net::SocketAddrV4;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::watch;
+use tokio_util::codec::Framed;
+
+/// Transport exchanging RTPS Messages over TCP/IPv4.
+///
+/// Each message is sent as a length prefixed frame (see TcpFrameCodec) over
+/// a single connection per remote transport; the receiving side passes the
+/// framed messages to the ProtocolCore, which decodes them with the
+/// MessageReceiver as any datagram. Connections are opened on the first
+/// message or to the initial peers and reopened when lost. The transport
+/// accepts connections on a single physical port; the Endpoints are reached
+/// through the logical ports bound on it, which are carried by the locators
+/// in their upper 16 bits.
+pub struct TcpV4Transport {
+    shared: Arc<Shared>,
+    listening_address: SocketAddrV4,
+    /// Stops all tasks of the transport when dropped.
+    _shutdown: watch::Sender<()>,
+}
+
+/// State shared by the transport and its tasks.
+struct Shared {
+    listening_port: u16,
+    logical_ports: Vec<u16>,
+    /// Queues of the frames to be sent over the connection to the remote
+    /// transport identified by its listening address.
+    connections: Mutex<HashMap<SocketAddrV4, Arc<MessageQueue<TcpFrame>>>>,
+    received: MessageQueue<(Locator_t, Bytes)>,
+    shutdown: watch::Receiver<()>,
+}
+
+impl TcpV4Transport {
+    /// The maximum size of a message accepted by the transport.
+    pub const MAX_MESSAGE_SIZE: usize = 1 << 20;
+
+    const RECONNECT_DELAY_MIN: Duration = Duration::from_millis(100);
+    const RECONNECT_DELAY_MAX: Duration = Duration::from_secs(5);
+
+    /// The number of messages queued for each connection and of the
+    /// received messages not yet passed to the ProtocolCore; the oldest
+    /// messages are dropped when more are queued.
+    const MAX_QUEUED_MESSAGES: usize = 256;
+
+    /// Accepts connections on the address and binds the logical ports;
+    /// messages addressed to other logical ports are dropped. Connections to
+    /// the initial peers are opened immediately. Port 0 selects any free
+    /// port. Has to be called within the Tokio runtime.
+    pub fn new(
+        address: SocketAddrV4,
+        logical_ports: &[u16],
+        initial_peers: &[Locator_t],
+    ) -> io::Result<Self> {
+        let listener = std::net::TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        let listener = TcpListener::from_std(listener)?;
+        let listening_address = match listener.local_addr()? {
+            SocketAddr::V4(listening_address) => listening_address,
+            SocketAddr::V6(_) => unreachable!("Listener is bound to IPv4 address"),
+        };
+
+        let (shutdown_sender, shutdown) = watch::channel(());
+        let shared = Arc::new(Shared {
+            listening_port: listening_address.port(),
+            logical_ports: logical_ports.to_vec(),
+            connections: Mutex::new(HashMap::new()),
+            received: MessageQueue::new(TcpV4Transport::MAX_QUEUED_MESSAGES),
+            shutdown,
+        });
+
+        spawn(&shared, accept(shared.clone(), listener));
+        for peer in initial_peers {
+            if peer.kind != LocatorKind_t::LOCATOR_KIND_TCPv4 {
+                return Err(unsupported_locator(*peer));
+            }
+            connection(&shared, physical_address(peer));
+        }
+
+        Ok(TcpV4Transport {
+            shared,
+            listening_address,
+            _shutdown: shutdown_sender,
+        })
+    }
+
+    /// The locator of the logical port.
+    pub fn locator(&self, logical_port: u16) -> Locator_t {
+        locator(self.listening_address, logical_port)
+    }
+}
+
+impl Transport for TcpV4Transport {
+    fn supported_kinds(&self) -> &[LocatorKind_t] {
+        &[LocatorKind_t::LOCATOR_KIND_TCPv4]
+    }
+
+    fn max_message_size(&self) -> usize {
+        TcpV4Transport::MAX_MESSAGE_SIZE
+    }
+
+    fn local_locators(&self) -> LocatorList_t {
+        self.shared
+            .logical_ports
+            .iter()
+            .map(|logical_port| self.locator(*logical_port))
+            .collect()
+    }
+
+    fn send(&self, destination: Locator_t, message: Bytes) -> BoxFuture<'_, io::Result<()>> {
+        Box::pin(async move {
+            if destination.kind != LocatorKind_t::LOCATOR_KIND_TCPv4 {
+                return Err(unsupported_locator(destination));
+            }
+            let peer = physical_address(&destination);
+            let frame = TcpFrame::Message {
+                logical_port: logical_port(&destination),
+                message,
+            };
+            match connection(&self.shared, peer).push(frame) {
+                Ok(Some(_)) => debug!("Dropping the oldest message queued for {}", peer),
+                Ok(None) => (),
+                Err(_) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::BrokenPipe,
+                        "Connection closed",
+                    ))
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn recv(&self) -> BoxFuture<'_, io::Result<(Locator_t, Bytes)>> {
+        Box::pin(async move {
+            self.shared
+                .received
+                .pop()
+                .await
+                .ok_or_else(|| io::Error::new(io::ErrorKind::BrokenPipe, "Transport stopped"))
+        })
+    }
+}
+
+fn locator(address: SocketAddrV4, logical_port: u16) -> Locator_t {
+    Locator_t {
+        kind: LocatorKind_t::LOCATOR_KIND_TCPv4,
+        port: u32::from(logical_port) << 16 | u32::from(address.port()),
+        address: address.ip().to_ipv6_compatible().octets(),
+    }
+}
+
+fn physical_address(locator: &Locator_t) -> SocketAddrV4 {
+    match SocketAddr::from(*locator) {
+        SocketAddr::V4(address) => address,
+        SocketAddr::V6(_) => SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0),
+    }
+}
+
+fn logical_port(locator: &Locator_t) -> u16 {
+    (locator.port >> 16) as u16
+}
+
+/// Runs the task until the transport is dropped.
+fn spawn(shared: &Shared, task: impl Future<Output = ()> + Send + 'static) {
+    let mut shutdown = shared.shutdown.clone();
+    tokio::spawn(async move {
+        tokio::select! {
+            _ = task => (),
+            _ = shutdown.changed() => (),
+        }
+    });
+}
+
+/// The queue of the connection to the remote transport; the connection is
+/// opened if it does not exist yet.
+fn connection(shared: &Arc<Shared>, peer: SocketAddrV4) -> Arc<MessageQueue<TcpFrame>> {
+    let mut connections = shared.connections.lock().unwrap();
+    if let Some(outgoing) = connections.get(&peer) {
+        return outgoing.clone();
+    }
+
+    let outgoing = Arc::new(MessageQueue::new(TcpV4Transport::MAX_QUEUED_MESSAGES));
+    connections.insert(peer, outgoing.clone());
+    spawn(shared, connect(shared.clone(), peer, outgoing.clone()));
+    outgoing
+}
+
+/// Keeps the connection to the remote transport open; frames queued while
+/// not connected are sent after connecting.
+async fn connect(shared: Arc<Shared>, peer: SocketAddrV4, frames: Arc<MessageQueue<TcpFrame>>) {
+    let mut reconnect_delay = TcpV4Transport::RECONNECT_DELAY_MIN;
+    loop {
+        match TcpStream::connect(peer).await {
+            Ok(stream) => {
+                reconnect_delay = TcpV4Transport::RECONNECT_DELAY_MIN;
+                let mut framed =
+                    Framed::new(stream, TcpFrameCodec::new(TcpV4Transport::MAX_MESSAGE_SIZE));
+                let bind = TcpFrame::Bind {
+                    port: shared.listening_port,
+                };
+                if framed.send(bind).await.is_ok() && !serve(&shared, peer, framed, &frames).await {
+                    return;
+                }
+                debug!("Connection to {} lost", peer);
+            }
+            Err(error) => debug!("Connecting to {} failed: {}", peer, error),
+        }
+
+        tokio::time::sleep(reconnect_delay).await;
+        reconnect_delay = (reconnect_delay * 2).min(TcpV4Transport::RECONNECT_DELAY_MAX);
+    }
+}
+
+async fn accept(shared: Arc<Shared>, listener: TcpListener) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => spawn(&shared, accepted(shared.clone(), stream)),
+            Err(error) => debug!("Accepting connection failed: {}", error),
+        }
+    }
+}
+
+/// Serves the connection opened by the remote transport. The connection is
+/// also used to send messages to the remote transport, unless it is already
+/// connected to.
+async fn accepted(shared: Arc<Shared>, stream: TcpStream) {
+    let remote_address = match stream.peer_addr() {
+        Ok(SocketAddr::V4(remote_address)) => remote_address,
+        _ => return,
+    };
+    let mut framed = Framed::new(stream, TcpFrameCodec::new(TcpV4Transport::MAX_MESSAGE_SIZE));
+    let peer = match framed.next().await {
+        Some(Ok(TcpFrame::Bind { port })) => SocketAddrV4::new(*remote_address.ip(), port),
+        _ => {
+            debug!("Connection from {} not bound", remote_address);
+            return;
+        }
+    };
+
+    let outgoing = Arc::new(MessageQueue::new(TcpV4Transport::MAX_QUEUED_MESSAGES));
+    shared
+        .connections
+        .lock()
+        .unwrap()
+        .entry(peer)
+        .or_insert_with(|| outgoing.clone());
+    serve(&shared, peer, framed, &outgoing).await;
+
+    let mut connections = shared.connections.lock().unwrap();
+    if matches!(connections.get(&peer), Some(known) if Arc::ptr_eq(known, &outgoing)) {
+        connections.remove(&peer);
+    }
+    outgoing.close();
+}
+
+/// Passes received messages to the transport and sends the queued frames.
+/// Returns false when the queue of the frames was closed, true when the
+/// connection was lost.
+async fn serve(
+    shared: &Shared,
+    peer: SocketAddrV4,
+    mut framed: Framed<TcpStream, TcpFrameCodec>,
+    frames: &MessageQueue<TcpFrame>,
+) -> bool {
+    loop {
+        tokio::select! {
+            received = framed.next() => match received {
+                Some(Ok(TcpFrame::Message { logical_port, message })) => {
+                    if !shared.logical_ports.contains(&logical_port) {
+                        debug!("Dropping message for not bound logical port {}", logical_port);
+                        continue;
+                    }
+                    match shared.received.push((locator(peer, logical_port), message)) {
+                        Ok(Some(_)) => debug!("Dropping the oldest received message"),
+                        Ok(None) => (),
+                        Err(_) => return false,
+                    }
+                }
+                Some(Ok(TcpFrame::Bind { .. })) => (),
+                Some(Err(error)) => {
+                    debug!("Dropping connection to {}: {}", peer, error);
+                    return true;
+                }
+                None => return true,
+            },
+            frame = frames.pop() => match frame {
+                Some(frame) => {
+                    if let Err(error) = framed.send(frame).await {
+                        debug!("Sending to {} failed: {}", peer, error);
+                        return true;
+                    }
+                }
+                None => return false,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const METATRAFFIC_PORT: u16 = 7410;
+    const USER_PORT: u16 = 7411;
+
+    fn transport(initial_peers: &[Locator_t]) -> TcpV4Transport {
+        TcpV4Transport::new(
+            SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0),
+            &[METATRAFFIC_PORT, USER_PORT],
+            initial_peers,
+        )
+        .unwrap()
+    }
+
+    async fn recv(transport: &TcpV4Transport) -> (Locator_t, Bytes) {
+        tokio::time::timeout(Duration::from_secs(5), transport.recv())
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn locators_carry_physical_and_logical_port() {
+        let transport = transport(&[]);
+
+        let locators = transport.local_locators();
+
+        assert_eq!(2, locators.len());
+        assert_eq!(LocatorKind_t::LOCATOR_KIND_TCPv4, locators[0].kind);
+        assert_eq!(METATRAFFIC_PORT, logical_port(&locators[0]));
+        assert_eq!(USER_PORT, logical_port(&locators[1]));
+        assert_eq!(transport.listening_address, physical_address(&locators[1]));
+    }
+
+    #[tokio::test]
+    async fn messages_are_exchanged_over_single_connection() {
+        let receiver = transport(&[]);
+        let sender = transport(&[receiver.locator(METATRAFFIC_PORT)]);
+
+        sender
+            .send(receiver.locator(USER_PORT), Bytes::from_static(b"RTPS"))
+            .await
+            .unwrap();
+        let (source, message) = recv(&receiver).await;
+        assert_eq!(sender.locator(USER_PORT), source);
+        assert_eq!(Bytes::from_static(b"RTPS"), message);
+
+        // The reply goes over the accepted connection
+        receiver
+            .send(source, Bytes::from_static(b"reply"))
+            .await
+            .unwrap();
+        assert_eq!(
+            (receiver.locator(USER_PORT), Bytes::from_static(b"reply")),
+            recv(&sender).await
+        );
+        assert_eq!(1, sender.shared.connections.lock().unwrap().len());
+        assert_eq!(1, receiver.shared.connections.lock().unwrap().len());
+    }
+
+    #[tokio::test]
+    async fn messages_for_not_bound_logical_port_are_dropped() {
+        let receiver = transport(&[]);
+        let sender = transport(&[]);
+        let mut not_bound = receiver.locator(USER_PORT);
+        not_bound.port = u32::from(7499u16) << 16 | u32::from(receiver.listening_address.port());
+
+        sender
+            .send(not_bound, Bytes::from_static(b"dropped"))
+            .await
+            .unwrap();
+        sender
+            .send(
+                receiver.locator(METATRAFFIC_PORT),
+                Bytes::from_static(b"RTPS"),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(Bytes::from_static(b"RTPS"), recv(&receiver).await.1);
+    }
+
+    #[tokio::test]
+    async fn connection_is_reopened_after_restart_of_remote_transport() {
+        let receiver = transport(&[]);
+        let address = receiver.listening_address;
+        let sender = transport(&[]);
+
+        sender
+            .send(receiver.locator(USER_PORT), Bytes::from_static(b"first"))
+            .await
+            .unwrap();
+        assert_eq!(Bytes::from_static(b"first"), recv(&receiver).await.1);
+        drop(receiver);
+        // The tasks of the dropped transport, including the listener, are
+        // stopped asynchronously
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let receiver = TcpV4Transport::new(address, &[USER_PORT], &[]).unwrap();
+        let message = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                sender
+                    .send(receiver.locator(USER_PORT), Bytes::from_static(b"second"))
+                    .await
+                    .unwrap();
+                tokio::select! {
+                    received = receiver.recv() => return received.unwrap().1,
+                    _ = tokio::time::sleep(Duration::from_millis(50)) => (),
+                }
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(Bytes::from_static(b"second"), message);
+    }
+
+    #[tokio::test]
+    async fn sending_to_not_tcp_v4_locator_fails() {
+        let transport = transport(&[]);
+        let destination = Locator_t::from(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 7400));
+
+        let result = transport
+            .send(destination, Bytes::from_static(b"RTPS"))
+            .await;
+
+        assert_eq!(io::ErrorKind::InvalidInput, result.unwrap_err().kind());
+    }
+}
@@ -0,0 +1,59 @@
+use crate::messages::received_submessage::ReceivedSubmessage;
+use crate::messages::receiver::MessageReceiver;
+use crate::structure::locator::{LocatorKind_t, LocatorList_t, Locator_t};
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use std::io;
+
+/// Moves RTPS Messages between the local Participant and the remote
+/// Participants reachable through locators of the supported kinds.
+///
+/// The transport knows nothing about the protocol; it is registered in the
+/// TransportRegistry which routes each outgoing message to the transport
+/// supporting the kind of its destination locator.
+pub trait Transport: Send + Sync {
+    /// Kinds of the locators the transport is able to send messages to.
+    fn supported_kinds(&self) -> &[LocatorKind_t];
+
+    /// Whether the transport is able to send messages to the locator.
+    fn is_reachable(&self, locator: &Locator_t) -> bool {
+        self.supported_kinds().contains(&locator.kind)
+    }
+
+    /// The maximum size of a single message sent by the transport.
+    fn max_message_size(&self) -> usize;
+
+    /// Locators the transport receives messages on, announced to the remote
+    /// Participants.
+    fn local_locators(&self) -> LocatorList_t;
+
+    /// Sends the message to the destination locator.
+    fn send(&self, destination: Locator_t, message: Bytes) -> BoxFuture<'_, io::Result<()>>;
+
+    /// Waits for the next received message. Returns the message together
+    /// with the locator of its source.
+    fn recv(&self) -> BoxFuture<'_, io::Result<(Locator_t, Bytes)>>;
+
+    /// Waits for the next received message and feeds it to the
+    /// MessageReceiver. Returns the decoded Submessages together with the
+    /// locator of the source of the message.
+    fn recv_submessages(&self) -> BoxFuture<'_, io::Result<(Locator_t, Vec<ReceivedSubmessage>)>> {
+        Box::pin(async move {
+            let (source, message) = self.recv().await?;
+            Ok((
+                source,
+                MessageReceiver::decode_datagram(source.kind, message),
+            ))
+        })
+    }
+}
+
+/// Error returned by the transports asked to send a message to a locator of
+/// not supported kind.
+pub fn unsupported_locator(locator: Locator_t) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("Unsupported locator {:?}", locator),
+    )
+}
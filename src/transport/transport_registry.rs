@@ -0,0 +1,506 @@
+use crate::messages::received_submessage::ReceivedSubmessage;
+use crate::protocol::protocol_core::ProtocolCore;
+use crate::protocol::transmit::Transmit;
+#[cfg(test)]
+use crate::structure::locator::LocatorList_t;
+use crate::structure::locator::Locator_t;
+use crate::transport::transport::{unsupported_locator, Transport};
+
+use bytes::Bytes;
+use log::warn;
+use std::io;
+use std::sync::Arc;
+#[cfg(test)]
+use std::time::Instant;
+
+/// The transports used by the Participant. Each outgoing message is routed
+/// to the first registered transport able to reach its destination locator;
+/// messages received by all transports are passed to the ProtocolCore.
+/// Transports registered first are preferred.
+#[derive(Clone, Default)]
+pub struct TransportRegistry {
+    transports: Vec<Arc<dyn Transport>>,
+}
+
+impl TransportRegistry {
+    pub fn new() -> Self {
+        TransportRegistry::default()
+    }
+
+    pub fn register(&mut self, transport: Box<dyn Transport>) {
+        self.transports.push(Arc::from(transport))
+    }
+
+    /// Unregisters the transport, e.g. the one failing to receive.
+    pub fn unregister(&mut self, transport: &Arc<dyn Transport>) {
+        self.transports
+            .retain(|registered| !Arc::ptr_eq(registered, transport))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transports.is_empty()
+    }
+
+    /// The transport used to send messages to the locator.
+    pub fn transport(&self, locator: &Locator_t) -> Option<&dyn Transport> {
+        self.transports
+            .iter()
+            .find(|transport| transport.is_reachable(locator))
+            .map(|transport| transport.as_ref())
+    }
+
+    /// The size of messages which can be sent by every registered transport.
+    pub fn max_message_size(&self) -> Option<usize> {
+        self.transports
+            .iter()
+            .map(|transport| transport.max_message_size())
+            .min()
+    }
+
+    /// Locators of all registered transports.
+    #[cfg(test)]
+    pub fn local_locators(&self) -> LocatorList_t {
+        self.transports
+            .iter()
+            .flat_map(|transport| transport.local_locators())
+            .collect()
+    }
+
+    /// Sends the message with the transport supporting its destination.
+    pub async fn send(&self, destination: Locator_t, message: Bytes) -> io::Result<()> {
+        match self.transport(&destination) {
+            Some(transport) => transport.send(destination, message).await,
+            None => Err(unsupported_locator(destination)),
+        }
+    }
+
+    /// Sends the message of the Transmit to its destination or, when that
+    /// fails, to the first of its fallbacks it can be sent to; e.g. to the
+    /// UDP locator of a Participant whose shared memory segment is gone.
+    pub async fn send_transmit(&self, transmit: Transmit) {
+        let Transmit {
+            destination,
+            fallbacks,
+            contents,
+        } = transmit;
+        for locator in std::iter::once(destination).chain(fallbacks) {
+            match self.send(locator, contents.clone()).await {
+                Ok(()) => return,
+                Err(error) => warn!("Sending message to {:?} failed: {}", locator, error),
+            }
+        }
+    }
+
+    /// Waits for a message received by any of the transports. Never
+    /// completes when no transport is registered.
+    #[cfg(test)]
+    pub async fn recv(&self) -> io::Result<(Locator_t, Bytes)> {
+        if self.transports.is_empty() {
+            return std::future::pending().await;
+        }
+        let (received, _, _) =
+            futures::future::select_all(self.transports.iter().map(|transport| transport.recv()))
+                .await;
+        received
+    }
+
+    /// Waits for a message received by any of the transports, decoded by the
+    /// MessageReceiver. Fails with the transport failing to receive, to be
+    /// unregistered. Never completes when no transport is registered.
+    pub async fn recv_submessages(
+        &self,
+    ) -> Result<(Locator_t, Vec<ReceivedSubmessage>), (Arc<dyn Transport>, io::Error)> {
+        if self.transports.is_empty() {
+            return std::future::pending().await;
+        }
+        let (received, index, _) = futures::future::select_all(
+            self.transports
+                .iter()
+                .map(|transport| transport.recv_submessages()),
+        )
+        .await;
+        received.map_err(|error| (self.transports[index].clone(), error))
+    }
+
+    /// Limits the messages of the core to the ones the registered transports
+    /// are able to send. Remote Endpoints are reached through the first of
+    /// their locators reachable by any transport, e.g. Endpoints not
+    /// supporting shared memory through UDP.
+    pub fn configure(&self, core: &mut ProtocolCore) {
+        if let Some(max_message_size) = self.max_message_size() {
+            core.set_max_message_size(max_message_size);
+        }
+        let transports = self.transports.clone();
+        core.set_locator_filter(move |locator| {
+            transports
+                .iter()
+                .any(|transport| transport.is_reachable(locator))
+        });
+    }
+
+    /// Sends all messages of the core, then waits until a message is
+    /// received or the timer of the core expires and passes the event to the
+    /// core. The core has to be configured with this registry first; the
+    /// transport failing to receive is unregistered and the core configured
+    /// again. Fails once no transport remains.
+    #[cfg(test)]
+    pub async fn drive(&mut self, core: &mut ProtocolCore) -> io::Result<()> {
+        while let Some(transmit) = core.poll_transmit() {
+            self.send_transmit(transmit).await;
+        }
+
+        let deadline = core.poll_timeout();
+        let timeout = async {
+            match deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline.into()).await,
+                None => std::future::pending().await,
+            }
+        };
+
+        tokio::select! {
+            received = self.recv_submessages() => match received {
+                Ok((source, submessages)) => {
+                    core.handle_submessages(Instant::now(), source, submessages)
+                }
+                Err((transport, error)) => {
+                    warn!("Receiving failed, the transport is unregistered: {}", error);
+                    self.unregister(&transport);
+                    self.configure(core);
+                    if self.is_empty() {
+                        return Err(error);
+                    }
+                }
+            },
+            _ = timeout => core.handle_timeout(Instant::now()),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::messages::vendor_id::VendorId_t;
+    use crate::structure::guid_prefix::GuidPrefix_t;
+    use crate::structure::locator::{Ipv4Addr, Ipv6Addr, LocatorKind_t, SocketAddr};
+    use crate::transport::udp_v4_transport::UdpV4Transport;
+    use crate::transport::udp_v6_transport::UdpV6Transport;
+    use futures::future::BoxFuture;
+
+    /// The transport whose socket is gone: sending and receiving fail.
+    pub(crate) struct FailingTransport;
+
+    impl Transport for FailingTransport {
+        fn supported_kinds(&self) -> &[LocatorKind_t] {
+            &[LocatorKind_t::LOCATOR_KIND_UDPv4]
+        }
+
+        fn max_message_size(&self) -> usize {
+            UdpV4Transport::MAX_MESSAGE_SIZE
+        }
+
+        fn local_locators(&self) -> LocatorList_t {
+            vec![]
+        }
+
+        fn send(&self, _: Locator_t, _: Bytes) -> BoxFuture<'_, io::Result<()>> {
+            Box::pin(async { Err(io::ErrorKind::NotConnected.into()) })
+        }
+
+        fn recv(&self) -> BoxFuture<'_, io::Result<(Locator_t, Bytes)>> {
+            Box::pin(async { Err(io::ErrorKind::NotConnected.into()) })
+        }
+    }
+
+    fn udp_v4_transport() -> UdpV4Transport {
+        UdpV4Transport::new(
+            Ipv4Addr::LOCALHOST,
+            UdpV4Transport::DEFAULT_MULTICAST_GROUP,
+            0,
+            0,
+            0,
+            0,
+        )
+        .unwrap()
+    }
+
+    fn udp_v6_transport() -> UdpV6Transport {
+        UdpV6Transport::new(
+            Ipv6Addr::LOCALHOST,
+            1,
+            UdpV6Transport::MULTICAST_GROUP_LINK_LOCAL,
+            0,
+            0,
+            0,
+            0,
+        )
+        .unwrap()
+    }
+
+    fn registry() -> TransportRegistry {
+        let mut registry = TransportRegistry::new();
+        registry.register(Box::new(udp_v4_transport()));
+        registry.register(Box::new(udp_v6_transport()));
+        registry
+    }
+
+    #[tokio::test]
+    async fn registry_describes_registered_transports() {
+        let registry = registry();
+
+        assert_eq!(
+            Some(UdpV4Transport::MAX_MESSAGE_SIZE),
+            registry.max_message_size()
+        );
+        let local_locators = registry.local_locators();
+        assert_eq!(8, local_locators.len());
+        assert_eq!(
+            4,
+            local_locators
+                .iter()
+                .filter(|locator| locator.kind == LocatorKind_t::LOCATOR_KIND_UDPv6)
+                .count()
+        );
+        assert!(registry.transport(&Locator_t::LOCATOR_INVALID).is_none());
+    }
+
+    #[tokio::test]
+    async fn messages_are_routed_by_locator_kind() {
+        let sender = registry();
+        let receiver = registry();
+
+        let unicast_locators = receiver
+            .local_locators()
+            .into_iter()
+            .filter(|locator| !SocketAddr::from(*locator).ip().is_multicast());
+        for destination in unicast_locators {
+            sender
+                .send(destination, Bytes::from_static(b"RTPS"))
+                .await
+                .unwrap();
+
+            let (source, message) = receiver.recv().await.unwrap();
+            assert_eq!(destination.kind, source.kind);
+            assert_eq!(Bytes::from_static(b"RTPS"), message);
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn shared_memory_is_preferred_on_the_same_host() {
+        use crate::transport::shm_transport::ShmTransport;
+
+        let shm_transport = ShmTransport::new(0).unwrap();
+        let local_shm_locator = shm_transport.locator();
+        let mut remote_shm_locator = local_shm_locator;
+        remote_shm_locator.address[0] ^= 0xFF;
+        let mut registry = TransportRegistry::new();
+        registry.register(Box::new(shm_transport));
+        registry.register(Box::new(udp_v4_transport()));
+
+        assert_eq!(
+            Some(ShmTransport::MAX_MESSAGE_SIZE),
+            registry
+                .transport(&local_shm_locator)
+                .map(|transport| transport.max_message_size())
+        );
+        assert!(registry.transport(&remote_shm_locator).is_none());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn messages_are_sent_to_the_fallbacks_when_sending_fails() {
+        use crate::transport::shm_transport::ShmTransport;
+
+        let shm_transport = ShmTransport::new(0).unwrap();
+        // The segment of the Participant is gone
+        let mut gone_shm_locator = shm_transport.locator();
+        gone_shm_locator.port = u32::MAX;
+        let mut sender = TransportRegistry::new();
+        sender.register(Box::new(shm_transport));
+        sender.register(Box::new(udp_v4_transport()));
+        let receiver = udp_v4_transport();
+
+        sender
+            .send_transmit(Transmit {
+                destination: gone_shm_locator,
+                fallbacks: vec![receiver.user_unicast_locator()],
+                contents: Bytes::from_static(b"RTPS"),
+            })
+            .await;
+
+        let (_, message) = receiver.recv().await.unwrap();
+        assert_eq!(Bytes::from_static(b"RTPS"), message);
+    }
+
+    #[tokio::test]
+    async fn sending_to_not_supported_locator_fails() {
+        let mut registry = TransportRegistry::new();
+        registry.register(Box::new(udp_v4_transport()));
+
+        let result = registry
+            .send(
+                udp_v6_transport().metatraffic_unicast_locator(),
+                Bytes::from_static(b"RTPS"),
+            )
+            .await;
+
+        assert_eq!(io::ErrorKind::InvalidInput, result.unwrap_err().kind());
+    }
+
+    #[tokio::test]
+    async fn protocol_cores_exchange_data() {
+        use crate::behavior::reader::{Reader, ReaderAttributes};
+        use crate::behavior::reader_proxy::ReaderProxy;
+        use crate::behavior::stateful_reader::StatefulReader;
+        use crate::behavior::stateful_writer::StatefulWriter;
+        use crate::behavior::writer::{Writer, WriterAttributes};
+        use crate::behavior::writer_proxy::WriterProxy;
+        use crate::protocol::event::Event;
+        use crate::structure::change_kind::ChangeKind_t;
+        use crate::structure::data::Data;
+        use crate::structure::duration::Duration_t;
+        use crate::structure::endpoint::EndpointAttributes;
+        use crate::structure::entity::EntityAttributes;
+        use crate::structure::entity_id::EntityId_t;
+        use crate::structure::guid::GUID_t;
+        use crate::structure::history_cache::HistoryCache;
+        use crate::structure::instance_handle::InstanceHandle_t;
+        use crate::structure::reliability_kind::ReliabilityKind_t;
+        use crate::structure::sequence_number::SequenceNumber_t;
+        use crate::structure::topic_kind::TopicKind_t;
+        use std::time::Duration;
+
+        let writer_guid = GUID_t {
+            guid_prefix: GuidPrefix_t::from([0x01; 12]),
+            entity_id: EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_WRITER,
+        };
+        let reader_guid = GUID_t {
+            guid_prefix: GuidPrefix_t::from([0x02; 12]),
+            entity_id: EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_READER,
+        };
+        let endpoint = || EndpointAttributes {
+            topic_kind: TopicKind_t::NO_KEY,
+            reliability_level: ReliabilityKind_t::RELIABLE,
+            unicast_locator_list: vec![],
+            multicast_locator_list: vec![],
+        };
+        let reader_transport = udp_v6_transport();
+        let reader_locator = reader_transport.user_unicast_locator();
+        let mut reader_transports = TransportRegistry::new();
+        reader_transports.register(Box::new(reader_transport));
+        let writer_transport = udp_v6_transport();
+        let writer_locator = writer_transport.metatraffic_unicast_locator();
+        let mut writer_transports = TransportRegistry::new();
+        writer_transports.register(Box::new(udp_v4_transport()));
+        writer_transports.register(Box::new(writer_transport));
+
+        let mut writer = StatefulWriter::new(
+            EntityAttributes { guid: writer_guid },
+            endpoint(),
+            WriterAttributes {
+                push_mode: true,
+                heartbeat_period: Duration_t::from(Duration::from_millis(100)),
+                nack_response_delay: Duration_t::DURATION_ZERO,
+                nack_suppression_duration: Duration_t::DURATION_ZERO,
+                last_change_sequence_number: SequenceNumber_t::from(0),
+                data_max_size_serialized: 1024,
+                fragment_size: 1024,
+                writer_cache: HistoryCache::new(),
+            },
+        );
+        writer.matched_reader_add(ReaderProxy::new(
+            reader_guid,
+            vec![reader_locator],
+            vec![],
+            false,
+        ));
+        let change = writer.new_change(
+            ChangeKind_t::ALIVE,
+            Data::from(Bytes::from_static(b"sample")),
+            InstanceHandle_t::default(),
+        );
+        writer.as_writer_mut().writer_cache.add_change(change);
+        let mut writer_core =
+            ProtocolCore::new(writer_guid.guid_prefix, VendorId_t::VENDOR_UNKNOWN);
+        writer_core.add_writer(Instant::now(), writer);
+
+        let mut reader = StatefulReader::new(
+            EntityAttributes { guid: reader_guid },
+            endpoint(),
+            ReaderAttributes {
+                expects_inline_qos: false,
+                heartbeat_response_delay: Duration_t::DURATION_ZERO,
+                heartbeat_suppression_duration: Duration_t::DURATION_ZERO,
+                reader_cache: HistoryCache::new(),
+            },
+        );
+        reader.matched_writer_add(WriterProxy::new(writer_guid, vec![writer_locator], vec![]));
+        let mut reader_core =
+            ProtocolCore::new(reader_guid.guid_prefix, VendorId_t::VENDOR_UNKNOWN);
+        reader_core.add_reader(reader);
+        writer_transports.configure(&mut writer_core);
+        reader_transports.configure(&mut reader_core);
+
+        let writer_task = async {
+            loop {
+                writer_transports.drive(&mut writer_core).await.unwrap();
+            }
+        };
+        let reader_task = async {
+            loop {
+                reader_transports.drive(&mut reader_core).await.unwrap();
+                if let Some(event) = reader_core.poll_event() {
+                    return event;
+                }
+            }
+        };
+        let event = tokio::time::timeout(Duration::from_secs(5), async {
+            tokio::select! {
+                _ = writer_task => unreachable!(),
+                event = reader_task => event,
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(Event::DataAvailable { reader_guid }, event);
+        assert_eq!(
+            Bytes::from_static(b"sample"),
+            reader_core
+                .reader(reader_guid.entity_id)
+                .unwrap()
+                .as_reader()
+                .reader_cache
+                .changes()[0]
+                .data_value
+                .value
+        );
+    }
+
+    #[tokio::test]
+    async fn transports_failing_to_receive_are_unregistered() {
+        let mut core =
+            ProtocolCore::new(GuidPrefix_t::from([0x01; 12]), VendorId_t::VENDOR_UNKNOWN);
+        let mut registry = TransportRegistry::new();
+        registry.register(Box::new(FailingTransport));
+        registry.register(Box::new(udp_v4_transport()));
+        registry.configure(&mut core);
+
+        registry.drive(&mut core).await.unwrap();
+        assert_eq!(1, registry.transports.len());
+        assert!(registry
+            .transport(&Locator_t::from(SocketAddr::from((
+                Ipv4Addr::LOCALHOST,
+                7400
+            ))))
+            .is_some());
+
+        let mut registry = TransportRegistry::new();
+        registry.register(Box::new(FailingTransport));
+        registry.configure(&mut core);
+        let error = registry.drive(&mut core).await.unwrap_err();
+        assert_eq!(io::ErrorKind::NotConnected, error.kind());
+        assert!(registry.is_empty());
+    }
+}
@@ -0,0 +1,67 @@
+use crate::structure::locator::{Locator_t, SocketAddr};
+
+use bytes::{Bytes, BytesMut};
+use std::io;
+use std::sync::Mutex;
+use tokio::net::UdpSocket;
+
+/// The sockets opened by a UDP transport: unicast and multicast sockets for
+/// the metatraffic (discovery) and for the user traffic.
+pub struct UdpSockets {
+    pub metatraffic_unicast: UdpSocket,
+    pub metatraffic_multicast: UdpSocket,
+    pub user_unicast: UdpSocket,
+    pub user_multicast: UdpSocket,
+
+    /// The datagrams are received into the spare capacity of the buffer and
+    /// split off of it. The memory is reused once the previously received
+    /// datagrams are dropped.
+    receive_buffer: Mutex<BytesMut>,
+}
+
+impl UdpSockets {
+    pub fn new(
+        metatraffic_unicast: UdpSocket,
+        metatraffic_multicast: UdpSocket,
+        user_unicast: UdpSocket,
+        user_multicast: UdpSocket,
+    ) -> Self {
+        UdpSockets {
+            metatraffic_unicast,
+            metatraffic_multicast,
+            user_unicast,
+            user_multicast,
+            receive_buffer: Mutex::new(BytesMut::new()),
+        }
+    }
+
+    /// Sends the datagram from the metatraffic unicast socket; the remote
+    /// Participants see the same source locator for all messages.
+    pub async fn send_to(&self, datagram: &[u8], destination: SocketAddr) -> io::Result<()> {
+        self.metatraffic_unicast
+            .send_to(datagram, destination)
+            .await
+            .map(|_| ())
+    }
+
+    /// Waits for a datagram received by any of the sockets. Returns the
+    /// datagram together with the locator of its source.
+    pub async fn recv(&self, max_message_size: usize) -> io::Result<(Locator_t, Bytes)> {
+        loop {
+            let socket = tokio::select! {
+                readable = self.metatraffic_unicast.readable() => readable.map(|_| &self.metatraffic_unicast),
+                readable = self.metatraffic_multicast.readable() => readable.map(|_| &self.metatraffic_multicast),
+                readable = self.user_unicast.readable() => readable.map(|_| &self.user_unicast),
+                readable = self.user_multicast.readable() => readable.map(|_| &self.user_multicast),
+            }?;
+
+            let mut buffer = self.receive_buffer.lock().unwrap();
+            buffer.reserve(max_message_size);
+            match socket.try_recv_buf_from(&mut *buffer) {
+                Ok((_, source)) => return Ok((Locator_t::from(source), buffer.split().freeze())),
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => continue,
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
@@ -0,0 +1,368 @@
+use crate::discovery::port_mapping::PortMapping;
+use crate::structure::locator::{
+    IpAddr, Ipv4Addr, LocatorKind_t, LocatorList_t, Locator_t, SocketAddr,
+};
+use crate::transport::transport::{unsupported_locator, Transport};
+use crate::transport::udp_sockets::UdpSockets;
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use socket2::{Domain, Protocol, Socket, Type};
+use std::io;
+use tokio::net::UdpSocket;
+
+/// Transport exchanging RTPS Messages over UDP/IPv4.
+///
+/// Opens unicast sockets for the metatraffic (discovery) and the user traffic
+/// and joins the multicast group with both multicast ports. All messages
+/// are sent from the metatraffic unicast socket.
+pub struct UdpV4Transport {
+    sockets: UdpSockets,
+    metatraffic_unicast_locator: Locator_t,
+    metatraffic_multicast_locator: Locator_t,
+    user_unicast_locator: Locator_t,
+    user_multicast_locator: Locator_t,
+}
+
+impl UdpV4Transport {
+    /// The default multicast group used by the RTPS implementations.
+    pub const DEFAULT_MULTICAST_GROUP: Ipv4Addr = Ipv4Addr::new(239, 255, 0, 1);
+
+    /// The maximum size of the UDP/IPv4 payload.
+    pub const MAX_MESSAGE_SIZE: usize = 65_507;
+
+    /// Opens the sockets of the transport; the unicast sockets are bound to
+    /// unicast_address. Port 0 selects any free port. Has to be called
+    /// within the Tokio runtime.
+    pub fn new(
+        unicast_address: Ipv4Addr,
+        multicast_group: Ipv4Addr,
+        metatraffic_unicast_port: u16,
+        metatraffic_multicast_port: u16,
+        user_unicast_port: u16,
+        user_multicast_port: u16,
+    ) -> io::Result<Self> {
+        let sockets = UdpSockets::new(
+            unicast_socket(unicast_address, metatraffic_unicast_port)?,
+            multicast_socket(multicast_group, unicast_address, metatraffic_multicast_port)?,
+            unicast_socket(unicast_address, user_unicast_port)?,
+            multicast_socket(multicast_group, unicast_address, user_multicast_port)?,
+        );
+        let multicast_locator = |socket: &UdpSocket| {
+            socket.local_addr().map(|address| {
+                Locator_t::from(SocketAddr::new(multicast_group.into(), address.port()))
+            })
+        };
+
+        Ok(UdpV4Transport {
+            metatraffic_unicast_locator: Locator_t::from(sockets.metatraffic_unicast.local_addr()?),
+            metatraffic_multicast_locator: multicast_locator(&sockets.metatraffic_multicast)?,
+            user_unicast_locator: Locator_t::from(sockets.user_unicast.local_addr()?),
+            user_multicast_locator: multicast_locator(&sockets.user_multicast)?,
+            sockets,
+        })
+    }
+
+    /// Opens the sockets on the well-known ports of the domain for the first
+    /// participant id whose unicast ports are free on the host. Returns the
+    /// participant id together with the transport.
+    pub fn for_domain(
+        unicast_address: Ipv4Addr,
+        multicast_group: Ipv4Addr,
+        port_mapping: &PortMapping,
+        domain_id: u16,
+    ) -> io::Result<(u16, Self)> {
+        let not_mapped = || io::Error::new(io::ErrorKind::InvalidInput, "Port out of range");
+        let metatraffic_multicast_port = port_mapping
+            .metatraffic_multicast_port(domain_id)
+            .ok_or_else(not_mapped)?;
+        let user_multicast_port = port_mapping
+            .user_multicast_port(domain_id)
+            .ok_or_else(not_mapped)?;
+
+        port_mapping.first_free_participant_id(domain_id, |participant_id| {
+            UdpV4Transport::new(
+                unicast_address,
+                multicast_group,
+                port_mapping
+                    .metatraffic_unicast_port(domain_id, participant_id)
+                    .ok_or_else(not_mapped)?,
+                metatraffic_multicast_port,
+                port_mapping
+                    .user_unicast_port(domain_id, participant_id)
+                    .ok_or_else(not_mapped)?,
+                user_multicast_port,
+            )
+        })
+    }
+
+    /// The address of the interface routing the multicast group, through
+    /// which the other hosts are reached; the loopback address without such
+    /// a route.
+    pub fn default_unicast_address() -> Ipv4Addr {
+        let route = std::net::UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).and_then(|socket| {
+            // Connecting only selects the route, nothing is sent
+            socket.connect((UdpV4Transport::DEFAULT_MULTICAST_GROUP, 7400))?;
+            socket.local_addr()
+        });
+        match route.map(|address| address.ip()) {
+            Ok(IpAddr::V4(address)) if !address.is_unspecified() => address,
+            _ => Ipv4Addr::LOCALHOST,
+        }
+    }
+
+    pub fn metatraffic_unicast_locator(&self) -> Locator_t {
+        self.metatraffic_unicast_locator
+    }
+
+    pub fn metatraffic_multicast_locator(&self) -> Locator_t {
+        self.metatraffic_multicast_locator
+    }
+
+    pub fn user_unicast_locator(&self) -> Locator_t {
+        self.user_unicast_locator
+    }
+
+    pub fn user_multicast_locator(&self) -> Locator_t {
+        self.user_multicast_locator
+    }
+}
+
+impl Transport for UdpV4Transport {
+    fn supported_kinds(&self) -> &[LocatorKind_t] {
+        &[LocatorKind_t::LOCATOR_KIND_UDPv4]
+    }
+
+    fn max_message_size(&self) -> usize {
+        UdpV4Transport::MAX_MESSAGE_SIZE
+    }
+
+    fn local_locators(&self) -> LocatorList_t {
+        vec![
+            self.metatraffic_unicast_locator,
+            self.metatraffic_multicast_locator,
+            self.user_unicast_locator,
+            self.user_multicast_locator,
+        ]
+    }
+
+    fn send(&self, destination: Locator_t, message: Bytes) -> BoxFuture<'_, io::Result<()>> {
+        Box::pin(async move {
+            if destination.kind != LocatorKind_t::LOCATOR_KIND_UDPv4 {
+                return Err(unsupported_locator(destination));
+            }
+            self.sockets
+                .send_to(&message, SocketAddr::from(destination))
+                .await
+        })
+    }
+
+    fn recv(&self) -> BoxFuture<'_, io::Result<(Locator_t, Bytes)>> {
+        Box::pin(self.sockets.recv(UdpV4Transport::MAX_MESSAGE_SIZE))
+    }
+}
+
+fn unicast_socket(address: Ipv4Addr, port: u16) -> io::Result<UdpSocket> {
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&SocketAddr::new(address.into(), port).into())?;
+    if !address.is_unspecified() {
+        // Multicast messages are sent through the same interface
+        socket.set_multicast_if_v4(&address)?;
+    }
+    socket.set_multicast_loop_v4(true)?;
+    UdpSocket::from_std(socket.into())
+}
+
+/// Socket receiving messages sent to the multicast group. Several
+/// Participants on the same host share the multicast port, so the address
+/// has to be reusable.
+fn multicast_socket(
+    multicast_group: Ipv4Addr,
+    interface: Ipv4Addr,
+    port: u16,
+) -> io::Result<UdpSocket> {
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_reuse_address(true)?;
+    #[cfg(unix)]
+    socket.set_reuse_port(true)?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), port).into())?;
+    socket.join_multicast_v4(&multicast_group, &interface)?;
+    socket.set_multicast_loop_v4(true)?;
+    UdpSocket::from_std(socket.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transport() -> UdpV4Transport {
+        UdpV4Transport::new(
+            Ipv4Addr::LOCALHOST,
+            UdpV4Transport::DEFAULT_MULTICAST_GROUP,
+            0,
+            0,
+            0,
+            0,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn locators_describe_bound_sockets() {
+        let transport = transport();
+
+        let unicast_locator = transport.metatraffic_unicast_locator();
+        assert_eq!(LocatorKind_t::LOCATOR_KIND_UDPv4, unicast_locator.kind);
+        assert_eq!(
+            SocketAddr::from(unicast_locator),
+            transport.sockets.metatraffic_unicast.local_addr().unwrap()
+        );
+
+        let multicast_locator = transport.user_multicast_locator();
+        assert_eq!(
+            SocketAddr::new(
+                UdpV4Transport::DEFAULT_MULTICAST_GROUP.into(),
+                transport
+                    .sockets
+                    .user_multicast
+                    .local_addr()
+                    .unwrap()
+                    .port()
+            ),
+            SocketAddr::from(multicast_locator)
+        );
+    }
+
+    #[tokio::test]
+    async fn unicast_datagram_is_received_with_source_locator() {
+        let sender = transport();
+        let receiver = transport();
+
+        for destination in [
+            receiver.metatraffic_unicast_locator(),
+            receiver.user_unicast_locator(),
+        ] {
+            sender
+                .send(destination, Bytes::from_static(b"RTPS"))
+                .await
+                .unwrap();
+
+            let (source, datagram) = receiver.recv().await.unwrap();
+            assert_eq!(sender.metatraffic_unicast_locator(), source);
+            assert_eq!(Bytes::from_static(b"RTPS"), datagram);
+        }
+    }
+
+    #[tokio::test]
+    async fn received_messages_are_fed_to_the_message_receiver() {
+        use crate::messages::heartbeat::Heartbeat;
+        use crate::messages::sender::MessageSender;
+        use crate::messages::submessage::EntitySubmessage;
+        use crate::messages::submessage_flag::SubmessageFlag;
+        use crate::messages::vendor_id::VendorId_t;
+        use crate::structure::count::Count_t;
+        use crate::structure::entity_id::EntityId_t;
+        use crate::structure::guid_prefix::GuidPrefix_t;
+        use crate::structure::sequence_number::SequenceNumber_t;
+
+        let sender = transport();
+        let receiver = transport();
+        let source_guid_prefix = GuidPrefix_t::from([0x01; 12]);
+        let heartbeat = |count| {
+            EntitySubmessage::Heartbeat(
+                Heartbeat {
+                    reader_id: EntityId_t::ENTITYID_UNKNOWN,
+                    writer_id: EntityId_t::ENTITYID_SEDP_BUILTIN_PUBLICATIONS_WRITER,
+                    first_sn: SequenceNumber_t::from(1),
+                    last_sn: SequenceNumber_t::from(2),
+                    count: Count_t::from(count),
+                },
+                SubmessageFlag { flags: 0x01 },
+            )
+        };
+        let message_sender = MessageSender::new(source_guid_prefix, VendorId_t::VENDOR_UNKNOWN);
+
+        for count in 1..=2 {
+            for message in
+                message_sender.encode(GuidPrefix_t::GUIDPREFIX_UNKNOWN, vec![heartbeat(count)])
+            {
+                sender
+                    .send(receiver.user_unicast_locator(), message)
+                    .await
+                    .unwrap();
+            }
+        }
+
+        // The datagrams received into the same buffer stay intact
+        let (_, first) = receiver.recv_submessages().await.unwrap();
+        let (source, second) = receiver.recv_submessages().await.unwrap();
+        assert_eq!(sender.metatraffic_unicast_locator(), source);
+        for (count, submessages) in [(1, first), (2, second)] {
+            assert_eq!(1, submessages.len());
+            assert_eq!(source_guid_prefix, submessages[0].source_guid_prefix);
+            assert_eq!(heartbeat(count), submessages[0].submessage);
+        }
+    }
+
+    #[tokio::test]
+    async fn multicast_datagram_is_received_by_group_members() {
+        let sender = transport();
+        let receiver = transport();
+
+        sender
+            .send(
+                receiver.metatraffic_multicast_locator(),
+                Bytes::from_static(b"RTPS"),
+            )
+            .await
+            .unwrap();
+
+        let (source, datagram) = receiver.recv().await.unwrap();
+        assert_eq!(sender.metatraffic_unicast_locator(), source);
+        assert_eq!(Bytes::from_static(b"RTPS"), datagram);
+    }
+
+    #[tokio::test]
+    async fn participants_of_domain_use_successive_participant_ids() {
+        let port_mapping = PortMapping {
+            port_base: 27_400,
+            ..PortMapping::default()
+        };
+        let open = || {
+            UdpV4Transport::for_domain(
+                Ipv4Addr::LOCALHOST,
+                UdpV4Transport::DEFAULT_MULTICAST_GROUP,
+                &port_mapping,
+                0,
+            )
+            .unwrap()
+        };
+
+        let (first_participant_id, first) = open();
+        let (second_participant_id, second) = open();
+
+        assert_eq!(0, first_participant_id);
+        assert_eq!(1, second_participant_id);
+        assert_eq!(27_410, first.metatraffic_unicast_locator().port);
+        assert_eq!(27_413, second.user_unicast_locator().port);
+        assert_eq!(27_400, second.metatraffic_multicast_locator().port);
+        assert_eq!(27_401, second.user_multicast_locator().port);
+    }
+
+    #[tokio::test]
+    async fn sending_to_not_udp_v4_locator_fails() {
+        let transport = transport();
+        let destination = Locator_t::from(SocketAddr::new(
+            crate::structure::locator::Ipv6Addr::LOCALHOST.into(),
+            7400,
+        ));
+
+        let result = transport
+            .send(destination, Bytes::from_static(b"RTPS"))
+            .await;
+
+        assert_eq!(io::ErrorKind::InvalidInput, result.unwrap_err().kind());
+    }
+}
@@ -0,0 +1,281 @@
+use crate::structure::locator::{Ipv6Addr, LocatorKind_t, LocatorList_t, Locator_t, SocketAddr};
+use crate::transport::transport::{unsupported_locator, Transport};
+use crate::transport::udp_sockets::UdpSockets;
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use socket2::{Domain, Protocol, Socket, Type};
+use std::io;
+use std::net::SocketAddrV6;
+use tokio::net::UdpSocket;
+
+/// Transport exchanging RTPS Messages over UDP/IPv6.
+///
+/// Works like the UdpV4Transport. Link-local addresses and multicast groups
+/// are only meaningful together with a network interface, so the transport
+/// is bound to a single interface selected by its index.
+pub struct UdpV6Transport {
+    sockets: UdpSockets,
+    interface: u32,
+    metatraffic_unicast_locator: Locator_t,
+    metatraffic_multicast_locator: Locator_t,
+    user_unicast_locator: Locator_t,
+    user_multicast_locator: Locator_t,
+}
+
+impl UdpV6Transport {
+    /// Multicast group reaching the Participants on the same link (ff02
+    /// scope).
+    pub const MULTICAST_GROUP_LINK_LOCAL: Ipv6Addr =
+        Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0xffff, 0xefff, 0x0001);
+
+    /// Multicast group reaching the Participants on the same site (ff05
+    /// scope).
+    pub const MULTICAST_GROUP_SITE_LOCAL: Ipv6Addr =
+        Ipv6Addr::new(0xff05, 0, 0, 0, 0, 0xffff, 0xefff, 0x0001);
+
+    /// The maximum size of the UDP/IPv6 payload without jumbograms.
+    pub const MAX_MESSAGE_SIZE: usize = 65_527;
+
+    /// Opens the sockets of the transport; the unicast sockets are bound to
+    /// unicast_address and the multicast group is joined on the interface
+    /// with the given index. Index 0 selects the interface chosen by the
+    /// system. Port 0 selects any free port. Has to be called within the
+    /// Tokio runtime.
+    pub fn new(
+        unicast_address: Ipv6Addr,
+        interface: u32,
+        multicast_group: Ipv6Addr,
+        metatraffic_unicast_port: u16,
+        metatraffic_multicast_port: u16,
+        user_unicast_port: u16,
+        user_multicast_port: u16,
+    ) -> io::Result<Self> {
+        let sockets = UdpSockets::new(
+            unicast_socket(unicast_address, interface, metatraffic_unicast_port)?,
+            multicast_socket(multicast_group, interface, metatraffic_multicast_port)?,
+            unicast_socket(unicast_address, interface, user_unicast_port)?,
+            multicast_socket(multicast_group, interface, user_multicast_port)?,
+        );
+        let multicast_locator = |socket: &UdpSocket| {
+            socket.local_addr().map(|address| {
+                Locator_t::from(SocketAddr::new(multicast_group.into(), address.port()))
+            })
+        };
+
+        Ok(UdpV6Transport {
+            interface,
+            metatraffic_unicast_locator: Locator_t::from(sockets.metatraffic_unicast.local_addr()?),
+            metatraffic_multicast_locator: multicast_locator(&sockets.metatraffic_multicast)?,
+            user_unicast_locator: Locator_t::from(sockets.user_unicast.local_addr()?),
+            user_multicast_locator: multicast_locator(&sockets.user_multicast)?,
+            sockets,
+        })
+    }
+
+    pub fn metatraffic_unicast_locator(&self) -> Locator_t {
+        self.metatraffic_unicast_locator
+    }
+
+    pub fn metatraffic_multicast_locator(&self) -> Locator_t {
+        self.metatraffic_multicast_locator
+    }
+
+    pub fn user_unicast_locator(&self) -> Locator_t {
+        self.user_unicast_locator
+    }
+
+    pub fn user_multicast_locator(&self) -> Locator_t {
+        self.user_multicast_locator
+    }
+
+    /// Locators do not carry the scope of the address; link scoped
+    /// destinations are reached through the interface of the transport.
+    fn socket_address(&self, locator: Locator_t) -> SocketAddrV6 {
+        let address = Ipv6Addr::from(locator.address);
+        let scope_id = if is_link_scoped(&address) {
+            self.interface
+        } else {
+            0
+        };
+        SocketAddrV6::new(address, locator.port as u16, 0, scope_id)
+    }
+}
+
+impl Transport for UdpV6Transport {
+    fn supported_kinds(&self) -> &[LocatorKind_t] {
+        &[LocatorKind_t::LOCATOR_KIND_UDPv6]
+    }
+
+    fn max_message_size(&self) -> usize {
+        UdpV6Transport::MAX_MESSAGE_SIZE
+    }
+
+    fn local_locators(&self) -> LocatorList_t {
+        vec![
+            self.metatraffic_unicast_locator,
+            self.metatraffic_multicast_locator,
+            self.user_unicast_locator,
+            self.user_multicast_locator,
+        ]
+    }
+
+    fn send(&self, destination: Locator_t, message: Bytes) -> BoxFuture<'_, io::Result<()>> {
+        Box::pin(async move {
+            if destination.kind != LocatorKind_t::LOCATOR_KIND_UDPv6 {
+                return Err(unsupported_locator(destination));
+            }
+            self.sockets
+                .send_to(&message, self.socket_address(destination).into())
+                .await
+        })
+    }
+
+    fn recv(&self) -> BoxFuture<'_, io::Result<(Locator_t, Bytes)>> {
+        Box::pin(self.sockets.recv(UdpV6Transport::MAX_MESSAGE_SIZE))
+    }
+}
+
+/// Link-local unicast (fe80::/10) and link-local multicast (ff02) addresses
+/// require the scope identifier.
+fn is_link_scoped(address: &Ipv6Addr) -> bool {
+    let segment = address.segments()[0];
+    (segment & 0xffc0) == 0xfe80 || (address.is_multicast() && (segment & 0x000f) == 0x0002)
+}
+
+fn unicast_socket(address: Ipv6Addr, interface: u32, port: u16) -> io::Result<UdpSocket> {
+    let scope_id = if is_link_scoped(&address) {
+        interface
+    } else {
+        0
+    };
+    let socket = Socket::new(Domain::IPV6, Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_only_v6(true)?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&SocketAddrV6::new(address, port, 0, scope_id).into())?;
+    // Multicast messages are sent through the selected interface
+    socket.set_multicast_if_v6(interface)?;
+    socket.set_multicast_loop_v6(true)?;
+    UdpSocket::from_std(socket.into())
+}
+
+/// Socket receiving messages sent to the multicast group. Several
+/// Participants on the same host share the multicast port, so the address
+/// has to be reusable.
+fn multicast_socket(multicast_group: Ipv6Addr, interface: u32, port: u16) -> io::Result<UdpSocket> {
+    let socket = Socket::new(Domain::IPV6, Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_only_v6(true)?;
+    socket.set_reuse_address(true)?;
+    #[cfg(unix)]
+    socket.set_reuse_port(true)?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&SocketAddrV6::new(Ipv6Addr::UNSPECIFIED, port, 0, 0).into())?;
+    socket.join_multicast_v6(&multicast_group, interface)?;
+    socket.set_multicast_loop_v6(true)?;
+    UdpSocket::from_std(socket.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::locator::{IpAddr, Ipv4Addr};
+
+    /// Index of the loopback interface on Linux.
+    const LOOPBACK_INTERFACE: u32 = 1;
+
+    fn transport(multicast_group: Ipv6Addr) -> UdpV6Transport {
+        UdpV6Transport::new(
+            Ipv6Addr::LOCALHOST,
+            LOOPBACK_INTERFACE,
+            multicast_group,
+            0,
+            0,
+            0,
+            0,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn link_scoped_addresses_are_detected() {
+        assert!(is_link_scoped(&"fe80::1".parse().unwrap()));
+        assert!(is_link_scoped(&UdpV6Transport::MULTICAST_GROUP_LINK_LOCAL));
+        assert!(!is_link_scoped(&UdpV6Transport::MULTICAST_GROUP_SITE_LOCAL));
+        assert!(!is_link_scoped(&Ipv6Addr::LOCALHOST));
+        assert!(!is_link_scoped(&"fd00::2".parse().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn locators_describe_bound_sockets() {
+        let transport = transport(UdpV6Transport::MULTICAST_GROUP_SITE_LOCAL);
+
+        let unicast_locator = transport.metatraffic_unicast_locator();
+        assert_eq!(LocatorKind_t::LOCATOR_KIND_UDPv6, unicast_locator.kind);
+        assert_eq!(
+            SocketAddr::from(unicast_locator),
+            transport.sockets.metatraffic_unicast.local_addr().unwrap()
+        );
+
+        let multicast_locator = transport.user_multicast_locator();
+        assert_eq!(LocatorKind_t::LOCATOR_KIND_UDPv6, multicast_locator.kind);
+        assert_eq!(
+            SocketAddr::new(
+                UdpV6Transport::MULTICAST_GROUP_SITE_LOCAL.into(),
+                transport
+                    .sockets
+                    .user_multicast
+                    .local_addr()
+                    .unwrap()
+                    .port()
+            ),
+            SocketAddr::from(multicast_locator)
+        );
+    }
+
+    #[tokio::test]
+    async fn unicast_datagram_is_received_with_source_locator() {
+        let sender = transport(UdpV6Transport::MULTICAST_GROUP_LINK_LOCAL);
+        let receiver = transport(UdpV6Transport::MULTICAST_GROUP_LINK_LOCAL);
+
+        for destination in [
+            receiver.metatraffic_unicast_locator(),
+            receiver.user_unicast_locator(),
+        ] {
+            sender
+                .send(destination, Bytes::from_static(b"RTPS"))
+                .await
+                .unwrap();
+
+            let (source, datagram) = receiver.recv().await.unwrap();
+            assert_eq!(sender.metatraffic_unicast_locator(), source);
+            assert_eq!(Bytes::from_static(b"RTPS"), datagram);
+        }
+    }
+
+    #[tokio::test]
+    async fn multicast_groups_of_both_scopes_are_joined() {
+        for multicast_group in [
+            UdpV6Transport::MULTICAST_GROUP_LINK_LOCAL,
+            UdpV6Transport::MULTICAST_GROUP_SITE_LOCAL,
+        ] {
+            let transport = transport(multicast_group);
+
+            assert_eq!(
+                IpAddr::V6(multicast_group),
+                SocketAddr::from(transport.metatraffic_multicast_locator()).ip()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn sending_to_not_udp_v6_locator_fails() {
+        let transport = transport(UdpV6Transport::MULTICAST_GROUP_LINK_LOCAL);
+        let destination = Locator_t::from(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 7400));
+
+        let result = transport
+            .send(destination, Bytes::from_static(b"RTPS"))
+            .await;
+
+        assert_eq!(io::ErrorKind::InvalidInput, result.unwrap_err().kind());
+    }
+}